@@ -28,6 +28,10 @@ pub struct Cli {
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub run: RunCmd,
+	/// Index the events deposited by contracts, so that `contracts_getEvents` can find them
+	/// by contract address or topic. The index only keeps the blocks whose state is kept.
+	#[structopt(long)]
+	pub index_contract_events: bool,
 }
 
 /// Possible subcommands of the main binary.
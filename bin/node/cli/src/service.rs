@@ -22,10 +22,10 @@
 
 use std::sync::Arc;
 use sc_consensus_babe;
-use node_primitives::Block;
+use node_primitives::{Block, AccountId, Balance};
 use node_runtime::RuntimeApi;
 use sc_service::{
-	config::{Configuration}, error::{Error as ServiceError},
+	config::{Configuration, PruningMode}, error::{Error as ServiceError},
 	RpcHandlers, TaskManager,
 };
 use sp_inherents::InherentDataProviders;
@@ -114,6 +114,7 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		let shared_epoch_changes = babe_link.epoch_changes().clone();
 
 		let client = client.clone();
+		let backend = backend.clone();
 		let pool = transaction_pool.clone();
 		let select_chain = select_chain.clone();
 		let keystore = keystore_container.sync_keystore();
@@ -122,6 +123,7 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		let rpc_extensions_builder = move |deny_unsafe, subscription_executor| {
 			let deps = node_rpc::FullDeps {
 				client: client.clone(),
+				backend: backend.clone(),
 				pool: pool.clone(),
 				select_chain: select_chain.clone(),
 				chain_spec: chain_spec.cloned_box(),
@@ -331,9 +333,26 @@ pub fn new_full_base(
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration)
+///
+/// If `index_contract_events` is set, the events deposited by contracts are indexed so that
+/// they can be queried efficiently through `contracts_getEvents`. The index is pruned along with
+/// the state of old blocks.
+pub fn new_full(config: Configuration, index_contract_events: bool)
 -> Result<TaskManager, ServiceError> {
-	new_full_base(config, |_, _| ()).map(|NewFullBase { task_manager, .. }| {
+	let keep_blocks = match config.pruning {
+		PruningMode::Constrained(ref constraints) => Some(constraints.max_blocks.unwrap_or(0)),
+		PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => None,
+	};
+	new_full_base(config, |_, _| ()).map(|NewFullBase { task_manager, client, .. }| {
+		if index_contract_events {
+			task_manager.spawn_handle().spawn(
+				"contracts-events-indexer",
+				pallet_contracts_rpc::index_contract_events::<_, Block, AccountId, Balance>(
+					client,
+					keep_blocks,
+				),
+			);
+		}
 		task_manager
 	})
 }
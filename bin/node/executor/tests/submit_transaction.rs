@@ -53,6 +53,7 @@ fn should_submit_unsigned_transaction() {
 			session_index: 1,
 			authority_index: 0,
 			validators_len: 0,
+			stats: Default::default(),
 		};
 
 		let call = pallet_im_online::Call::heartbeat(heartbeat_data, signature);
@@ -253,7 +254,13 @@ fn submitted_transaction_should_be_valid() {
 		let author = extrinsic.signature.clone().unwrap().0;
 		let address = Indices::lookup(author).unwrap();
 		let data = pallet_balances::AccountData { free: 5_000_000_000_000, ..Default::default() };
-		let account = frame_system::AccountInfo { nonce: 0, refcount: 0, data };
+		let account = frame_system::AccountInfo {
+			nonce: 0,
+			consumers: 0,
+			providers: 1,
+			sufficients: 0,
+			data,
+		};
 		<frame_system::Account<Runtime>>::insert(&address, account);
 
 		// check validity
@@ -49,7 +49,7 @@ use sp_consensus::SelectChain;
 use sp_consensus_babe::BabeApi;
 use sc_rpc::SubscriptionTaskExecutor;
 use sp_transaction_pool::TransactionPool;
-use sc_client_api::AuxStore;
+use sc_client_api::{AuxStore, BlockchainEvents};
 
 /// Light client extra dependencies.
 pub struct LightDeps<C, F, P> {
@@ -91,6 +91,8 @@ pub struct GrandpaDeps<B> {
 pub struct FullDeps<C, P, SC, B> {
 	/// The client instance to use.
 	pub client: Arc<C>,
+	/// The backend instance to use.
+	pub backend: Arc<B>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
 	/// The SelectChain Strategy
@@ -112,11 +114,12 @@ pub type IoHandler = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
 pub fn create_full<C, P, SC, B>(
 	deps: FullDeps<C, P, SC, B>,
 ) -> jsonrpc_core::IoHandler<sc_rpc_api::Metadata> where
-	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore +
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore + BlockchainEvents<Block> +
 		HeaderMetadata<Block, Error=BlockChainError> + Sync + Send + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: pallet_session_rpc::SessionMembershipRuntimeApi<Block>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
@@ -125,12 +128,14 @@ pub fn create_full<C, P, SC, B>(
 	B::State: sc_client_api::backend::StateBackend<sp_runtime::traits::HashFor<Block>>,
 {
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
-	use pallet_contracts_rpc::{Contracts, ContractsApi};
+	use pallet_contracts_rpc::{Contracts, ContractsApi, ContractsEvents, ContractsEventsApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
+	use pallet_session_rpc::{Session, SessionApi};
 
 	let mut io = jsonrpc_core::IoHandler::default();
 	let FullDeps {
 		client,
+		backend,
 		pool,
 		select_chain,
 		chain_spec,
@@ -161,9 +166,20 @@ pub fn create_full<C, P, SC, B>(
 	io.extend_with(
 		ContractsApi::to_delegate(Contracts::new(client.clone()))
 	);
+	io.extend_with(
+		ContractsEventsApi::to_delegate(
+			ContractsEvents::<_, _, Balance>::new(client.clone(), subscription_executor.clone())
+		)
+	);
 	io.extend_with(
 		TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone()))
 	);
+	// Membership proofs are generated from historical data in the off-chain database.
+	if let Some(offchain_storage) = backend.offchain_storage() {
+		io.extend_with(
+			SessionApi::to_delegate(Session::new(client.clone(), offchain_storage))
+		);
+	}
 	io.extend_with(
 		sc_consensus_babe_rpc::BabeApi::to_delegate(
 			BabeRpcHandler::new(
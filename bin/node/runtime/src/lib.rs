@@ -88,6 +88,9 @@ use impls::Author;
 /// Constant values used within the runtime.
 pub mod constants;
 use constants::{time::*, currency::*};
+
+/// The bag thresholds of the voter list.
+mod voter_bags;
 use sp_runtime::generic::Era;
 
 // Make the WASM binary available.
@@ -111,10 +114,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 260,
+	spec_version: 288,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 2,
+	transaction_version: 5,
 };
 
 /// Native version.
@@ -207,6 +210,7 @@ impl frame_system::Config for Runtime {
 impl pallet_utility::Config for Runtime {
 	type Event = Event;
 	type Call = Call;
+	type PalletsOrigin = OriginCaller;
 	type WeightInfo = pallet_utility::weights::SubstrateWeight<Runtime>;
 }
 
@@ -233,6 +237,9 @@ parameter_types! {
 	pub const ProxyDepositBase: Balance = deposit(1, 8);
 	// Additional storage item size of 33 bytes.
 	pub const ProxyDepositFactor: Balance = deposit(0, 33);
+	// Additional size of an expiry and a spending allowance: 2 option tags, 3 block numbers and
+	// 2 balances.
+	pub const ProxyLimitDepositFactor: Balance = deposit(0, 46);
 	pub const MaxProxies: u16 = 32;
 	pub const AnnouncementDepositBase: Balance = deposit(1, 8);
 	pub const AnnouncementDepositFactor: Balance = deposit(0, 66);
@@ -288,6 +295,7 @@ impl pallet_proxy::Config for Runtime {
 	type ProxyType = ProxyType;
 	type ProxyDepositBase = ProxyDepositBase;
 	type ProxyDepositFactor = ProxyDepositFactor;
+	type ProxyLimitDepositFactor = ProxyLimitDepositFactor;
 	type MaxProxies = MaxProxies;
 	type WeightInfo = pallet_proxy::weights::SubstrateWeight<Runtime>;
 	type MaxPending = MaxPending;
@@ -300,6 +308,7 @@ parameter_types! {
 	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) *
 		RuntimeBlockWeights::get().max_block;
 	pub const MaxScheduledPerBlock: u32 = 50;
+	pub const NoPreimagePostponement: Option<BlockNumber> = Some(10);
 }
 
 impl pallet_scheduler::Config for Runtime {
@@ -311,6 +320,25 @@ impl pallet_scheduler::Config for Runtime {
 	type ScheduleOrigin = EnsureRoot<AccountId>;
 	type MaxScheduledPerBlock = MaxScheduledPerBlock;
 	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+	type PreimageProvider = Preimage;
+	type NoPreimagePostponement = NoPreimagePostponement;
+}
+
+parameter_types! {
+	pub const PreimageMaxSize: u32 = 4096 * 1024;
+	pub const PreimageBaseDeposit: Balance = 1 * DOLLARS;
+	// One cent: $10,000 / MB
+	pub const PreimageByteDeposit: Balance = 1 * CENTS;
+}
+
+impl pallet_preimage::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<AccountId>;
+	type MaxSize = PreimageMaxSize;
+	type BaseDeposit = PreimageBaseDeposit;
+	type ByteDeposit = PreimageByteDeposit;
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -358,10 +386,14 @@ parameter_types! {
 	// For weight estimation, we assume that the most locks on an individual account will be 50.
 	// This number may need to be adjusted in the future if this assumption no longer holds true.
 	pub const MaxLocks: u32 = 50;
+	pub const MaxReserves: u32 = 50;
 }
 
 impl pallet_balances::Config for Runtime {
 	type MaxLocks = MaxLocks;
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = EnsureRoot<AccountId>;
 	type Balance = Balance;
 	type DustRemoval = ();
 	type Event = Event;
@@ -436,6 +468,7 @@ impl pallet_session::Config for Runtime {
 impl pallet_session::historical::Config for Runtime {
 	type FullIdentification = pallet_staking::Exposure<AccountId, Balance>;
 	type FullIdentificationOf = pallet_staking::ExposureOf<Runtime>;
+	type PruningDepth = ();
 }
 
 pallet_staking_reward_curve::build! {
@@ -463,6 +496,10 @@ parameter_types! {
 		.get(DispatchClass::Normal)
 		.max_extrinsic.expect("Normal extrinsics have a weight limit configured; qed")
 		.saturating_sub(BlockExecutionWeight::get());
+	// The snapshot is taken in `on_initialize`, so reading the voters may take up to a quarter
+	// of the block.
+	pub VoterSnapshotWeightLimit: Weight = RuntimeBlockWeights::get().max_block / 4;
+	pub const CommissionChangeDelay: pallet_staking::EraIndex = 24 * 2; // 2 days.
 }
 
 impl pallet_staking::Config for Runtime {
@@ -494,9 +531,60 @@ impl pallet_staking::Config for Runtime {
 	// The unsigned solution weight targeted by the OCW. We set it to the maximum possible value of
 	// a single extrinsic.
 	type OffchainSolutionWeightLimit = OffchainSolutionWeightLimit;
+	// Slashes of unlocking funds are applied to the unbonding pools.
+	type OnStakerSlash = NominationPools;
+	// The nominators of the snapshot are taken from the bags list, highest stake first.
+	type VoterList = BagsList;
+	type VoterSnapshotWeightLimit = VoterSnapshotWeightLimit;
+	type CommissionChangeDelay = CommissionChangeDelay;
 	type WeightInfo = pallet_staking::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const BagThresholds: &'static [u64] = &voter_bags::THRESHOLDS;
+}
+
+impl pallet_bags_list::Config for Runtime {
+	type Event = Event;
+	type ScoreProvider = Staking;
+	type BagThresholds = BagThresholds;
+	type WeightInfo = pallet_bags_list::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const FastUnstakeDeposit: Balance = 1 * DOLLARS;
+	pub const ErasToCheckPerBlock: u32 = 1;
+}
+
+impl pallet_fast_unstake::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type Staking = Staking;
+	type Deposit = FastUnstakeDeposit;
+	type ErasToCheckPerBlock = ErasToCheckPerBlock;
+	type WeightInfo = pallet_fast_unstake::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const NominationPoolsModuleId: ModuleId = ModuleId(*b"py/nopls");
+	pub const MinJoinBond: Balance = 1 * DOLLARS;
+	pub const MinCreateBond: Balance = 10 * DOLLARS;
+	pub const MaxPoolUnbonding: u32 = 8;
+	pub const PostUnbondingPoolsWindow: u32 = 4;
+}
+
+impl pallet_nomination_pools::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type Staking = Staking;
+	type ModuleId = NominationPoolsModuleId;
+	type MinJoinBond = MinJoinBond;
+	type MinCreateBond = MinCreateBond;
+	type MaxUnbonding = MaxPoolUnbonding;
+	type PostUnbondingPoolsWindow = PostUnbondingPoolsWindow;
+	type WeightInfo = pallet_nomination_pools::weights::SubstrateWeight<Runtime>;
+}
+
 parameter_types! {
 	pub const LaunchPeriod: BlockNumber = 28 * 24 * 60 * MINUTES;
 	pub const VotingPeriod: BlockNumber = 28 * 24 * 60 * MINUTES;
@@ -505,12 +593,24 @@ parameter_types! {
 	pub const MinimumDeposit: Balance = 100 * DOLLARS;
 	pub const EnactmentPeriod: BlockNumber = 30 * 24 * 60 * MINUTES;
 	pub const CooloffPeriod: BlockNumber = 28 * 24 * 60 * MINUTES;
-	// One cent: $10,000 / MB
-	pub const PreimageByteDeposit: Balance = 1 * CENTS;
 	pub const MaxVotes: u32 = 100;
 	pub const MaxProposals: u32 = 100;
 }
 
+/// Delegation classes of democracy referenda: runtime upgrades and treasury spending can be
+/// delegated separately from everything else.
+pub struct DemocracyClassifier;
+impl pallet_democracy::ClassifyProposal<Call, u8> for DemocracyClassifier {
+	fn classify(proposal: &Call) -> u8 {
+		match proposal {
+			Call::System(frame_system::Call::set_code(..)) |
+			Call::System(frame_system::Call::set_code_without_checks(..)) => 1,
+			Call::Treasury(..) => 2,
+			_ => 0,
+		}
+	}
+}
+
 impl pallet_democracy::Config for Runtime {
 	type Proposal = Call;
 	type Event = Event;
@@ -546,16 +646,77 @@ impl pallet_democracy::Config for Runtime {
 	// only do it once and it lasts only for the cooloff period.
 	type VetoOrigin = pallet_collective::EnsureMember<AccountId, TechnicalCollective>;
 	type CooloffPeriod = CooloffPeriod;
-	type PreimageByteDeposit = PreimageByteDeposit;
-	type OperationalPreimageOrigin = pallet_collective::EnsureMember<AccountId, CouncilCollective>;
+	type Preimages = Preimage;
 	type Slash = Treasury;
 	type Scheduler = Scheduler;
 	type PalletsOrigin = OriginCaller;
 	type MaxVotes = MaxVotes;
+	type VoteClass = u8;
+	type Classifier = DemocracyClassifier;
 	type WeightInfo = pallet_democracy::weights::SubstrateWeight<Runtime>;
 	type MaxProposals = MaxProposals;
 }
 
+parameter_types! {
+	pub const SubmissionDeposit: Balance = 100 * DOLLARS;
+	pub const UndecidingTimeout: BlockNumber = 28 * DAYS;
+	pub const VoteLockingPeriod: BlockNumber = 7 * DAYS;
+}
+
+type ReferendaTrackInfo = pallet_referenda::TrackInfo<Balance, BlockNumber>;
+
+/// The referenda tracks: referenda of root origin are decided on a single track.
+pub struct ReferendaTracks;
+impl pallet_referenda::TracksInfo<Balance, BlockNumber> for ReferendaTracks {
+	type Origin = OriginCaller;
+	fn tracks() -> &'static [(pallet_referenda::TrackId, ReferendaTrackInfo)] {
+		static DATA: [(pallet_referenda::TrackId, ReferendaTrackInfo); 1] = [
+			(0, pallet_referenda::TrackInfo {
+				name: "root",
+				max_deciding: 1,
+				decision_deposit: 1_000 * DOLLARS,
+				prepare_period: 3 * HOURS,
+				decision_period: 28 * DAYS,
+				confirm_period: 3 * HOURS,
+				min_enactment_period: 3 * HOURS,
+				min_approval: pallet_referenda::Curve::LinearDecreasing {
+					begin: Perbill::from_percent(100),
+					end: Perbill::from_percent(50),
+				},
+				min_support: pallet_referenda::Curve::LinearDecreasing {
+					begin: Perbill::from_percent(50),
+					end: Perbill::from_percent(0),
+				},
+			}),
+		];
+		&DATA[..]
+	}
+	fn track_for(origin: &OriginCaller) -> Result<pallet_referenda::TrackId, ()> {
+		match origin {
+			OriginCaller::system(frame_system::RawOrigin::Root) => Ok(0),
+			_ => Err(()),
+		}
+	}
+}
+
+impl pallet_referenda::Config for Runtime {
+	type Proposal = Call;
+	type Event = Event;
+	type Currency = Balances;
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
+	type Preimages = Preimage;
+	type Tracks = ReferendaTracks;
+	type CancelOrigin = pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+	type KillOrigin = EnsureRoot<AccountId>;
+	type Slash = Treasury;
+	type SubmissionDeposit = SubmissionDeposit;
+	type UndecidingTimeout = UndecidingTimeout;
+	type VoteLockingPeriod = VoteLockingPeriod;
+	type MaxVotes = MaxVotes;
+	type WeightInfo = pallet_referenda::weights::SubstrateWeight<Runtime>;
+}
+
 parameter_types! {
 	pub const CouncilMotionDuration: BlockNumber = 5 * DAYS;
 	pub const CouncilMaxProposals: u32 = 100;
@@ -571,6 +732,7 @@ impl pallet_collective::Config<CouncilCollective> for Runtime {
 	type MaxProposals = CouncilMaxProposals;
 	type MaxMembers = CouncilMaxMembers;
 	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type Preimages = Preimage;
 	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
 }
 
@@ -621,6 +783,7 @@ impl pallet_collective::Config<TechnicalCollective> for Runtime {
 	type MaxProposals = TechnicalMaxProposals;
 	type MaxMembers = TechnicalMaxMembers;
 	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type Preimages = Preimage;
 	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
 }
 
@@ -701,6 +864,11 @@ parameter_types! {
 	pub const MaxDepth: u32 = 32;
 	pub const StorageSizeOffset: u32 = 8;
 	pub const MaxValueSize: u32 = 16 * 1024;
+	pub const ContractStorageModel: pallet_contracts::StorageModel =
+		pallet_contracts::StorageModel::Rent;
+	pub const ContractDepositPerByte: Balance = 4 * MILLICENTS;
+	pub const ContractDepositPerItem: Balance = 40 * MILLICENTS;
+	pub const ContractCodeDepositPerByte: Balance = 1 * MILLICENTS;
 }
 
 impl pallet_contracts::Config for Runtime {
@@ -717,8 +885,13 @@ impl pallet_contracts::Config for Runtime {
 	type SurchargeReward = SurchargeReward;
 	type MaxDepth = MaxDepth;
 	type MaxValueSize = MaxValueSize;
+	type StorageModel = ContractStorageModel;
+	type DepositPerByte = ContractDepositPerByte;
+	type DepositPerItem = ContractDepositPerItem;
+	type CodeDepositPerByte = ContractCodeDepositPerByte;
 	type WeightPrice = pallet_transaction_payment::Module<Self>;
 	type WeightInfo = pallet_contracts::weights::SubstrateWeight<Self>;
+	type ChainExtension = ();
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -729,6 +902,7 @@ impl pallet_sudo::Config for Runtime {
 parameter_types! {
 	pub const SessionDuration: BlockNumber = EPOCH_DURATION_IN_SLOTS as _;
 	pub const ImOnlineUnsignedPriority: TransactionPriority = TransactionPriority::max_value();
+	pub const ImOnlineStatsHistoryDepth: sp_staking::SessionIndex = 24;
 	/// We prioritize im-online heartbeats over election solution submission.
 	pub const StakingUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
 }
@@ -797,6 +971,7 @@ impl pallet_im_online::Config for Runtime {
 	type SessionDuration = SessionDuration;
 	type ReportUnresponsiveness = Offences;
 	type UnsignedPriority = ImOnlineUnsignedPriority;
+	type StatsHistoryDepth = ImOnlineStatsHistoryDepth;
 	type WeightInfo = pallet_im_online::weights::SubstrateWeight<Runtime>;
 }
 
@@ -841,6 +1016,9 @@ parameter_types! {
 	pub const MaxSubAccounts: u32 = 100;
 	pub const MaxAdditionalFields: u32 = 100;
 	pub const MaxRegistrars: u32 = 20;
+	pub const PendingUsernameExpiration: BlockNumber = 7 * DAYS;
+	pub const MaxSuffixLength: u32 = 7;
+	pub const MaxUsernameLength: u32 = 32;
 }
 
 impl pallet_identity::Config for Runtime {
@@ -855,6 +1033,10 @@ impl pallet_identity::Config for Runtime {
 	type Slashed = Treasury;
 	type ForceOrigin = EnsureRootOrHalfCouncil;
 	type RegistrarOrigin = EnsureRootOrHalfCouncil;
+	type UsernameAuthorityOrigin = EnsureRootOrHalfCouncil;
+	type PendingUsernameExpiration = PendingUsernameExpiration;
+	type MaxSuffixLength = MaxSuffixLength;
+	type MaxUsernameLength = MaxUsernameLength;
 	type WeightInfo = pallet_identity::weights::SubstrateWeight<Runtime>;
 }
 
@@ -863,6 +1045,7 @@ parameter_types! {
 	pub const FriendDepositFactor: Balance = 50 * CENTS;
 	pub const MaxFriends: u16 = 9;
 	pub const RecoveryDeposit: Balance = 5 * DOLLARS;
+	pub const RecoveryChallengePeriod: BlockNumber = 1 * DAYS;
 }
 
 impl pallet_recovery::Config for Runtime {
@@ -873,6 +1056,9 @@ impl pallet_recovery::Config for Runtime {
 	type FriendDepositFactor = FriendDepositFactor;
 	type MaxFriends = MaxFriends;
 	type RecoveryDeposit = RecoveryDeposit;
+	type ChallengePeriod = RecoveryChallengePeriod;
+	// `Balances` goes last, to move whatever the other pallets leave behind.
+	type MigrateAccount = (Proxy, Multisig, Vesting, Staking, Balances);
 }
 
 parameter_types! {
@@ -955,6 +1141,9 @@ construct_runtime!(
 		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
 		TransactionPayment: pallet_transaction_payment::{Module, Storage},
 		Staking: pallet_staking::{Module, Call, Config<T>, Storage, Event<T>, ValidateUnsigned},
+		NominationPools: pallet_nomination_pools::{Module, Call, Storage, Event<T>},
+		BagsList: pallet_bags_list::{Module, Call, Storage, Event<T>},
+		FastUnstake: pallet_fast_unstake::{Module, Call, Storage, Event<T>},
 		Session: pallet_session::{Module, Call, Storage, Event, Config<T>},
 		Democracy: pallet_democracy::{Module, Call, Storage, Config, Event<T>},
 		Council: pallet_collective::<Instance1>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
@@ -979,6 +1168,8 @@ construct_runtime!(
 		Multisig: pallet_multisig::{Module, Call, Storage, Event<T>},
 		Assets: pallet_assets::{Module, Call, Storage, Event<T>},
 		Mmr: pallet_mmr::{Module, Storage},
+		Preimage: pallet_preimage::{Module, Call, Storage, Event<T>},
+		Referenda: pallet_referenda::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -1171,10 +1362,21 @@ impl_runtime_apis! {
 			value: Balance,
 			gas_limit: u64,
 			input_data: Vec<u8>,
-		) -> pallet_contracts_primitives::ContractExecResult {
+		) -> pallet_contracts_primitives::ContractExecResult<AccountId, Balance, Hash> {
 			Contracts::bare_call(origin, dest, value, gas_limit, input_data)
 		}
 
+		fn instantiate(
+			origin: AccountId,
+			endowment: Balance,
+			gas_limit: u64,
+			code: pallet_contracts_primitives::Code<Hash>,
+			data: Vec<u8>,
+			salt: Vec<u8>,
+		) -> pallet_contracts_primitives::ContractInstantiateResult<AccountId, Balance, Hash> {
+			Contracts::bare_instantiate(origin, endowment, gas_limit, code, data, salt)
+		}
+
 		fn get_storage(
 			address: AccountId,
 			key: [u8; 32],
@@ -1187,6 +1389,27 @@ impl_runtime_apis! {
 		) -> pallet_contracts_primitives::RentProjectionResult<BlockNumber> {
 			Contracts::rent_projection(address)
 		}
+
+		fn code_info(
+			code_hash: Hash,
+		) -> Option<pallet_contracts_primitives::CodeInfo<AccountId, Balance>> {
+			Contracts::code_info(code_hash)
+		}
+
+		fn contract_events(
+		) -> Vec<pallet_contracts_primitives::ContractEvent<AccountId, Hash>> {
+			Contracts::contract_events()
+		}
+	}
+
+	impl pallet_identity_rpc_runtime_api::IdentityApi<Block, AccountId> for Runtime {
+		fn account_of_username(username: Vec<u8>) -> Option<AccountId> {
+			Identity::account_of_username(username)
+		}
+
+		fn primary_username_of(who: AccountId) -> Option<Vec<u8>> {
+			Identity::primary_username_of(who)
+		}
 	}
 
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<
@@ -1210,6 +1433,27 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_im_online_rpc_runtime_api::ImOnlineApi<Block, BlockNumber> for Runtime {
+		fn heartbeat_stats(
+			session: sp_staking::SessionIndex,
+		) -> Vec<(u32, pallet_im_online::HeartbeatStats<BlockNumber>)> {
+			ImOnline::session_heartbeat_stats(session)
+		}
+	}
+
+	impl sp_session::SessionMembershipApi<Block> for Runtime {
+		fn prove_session_membership(
+			session: sp_staking::SessionIndex,
+			key_type: KeyTypeId,
+			key: Vec<u8>,
+		) -> Option<sp_session::MembershipProof> {
+			pallet_session::historical::offchain::prove_session_membership::<Runtime, _>(
+				session,
+				(key_type, key),
+			)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -1247,19 +1491,24 @@ impl_runtime_apis! {
 
 			add_benchmark!(params, batches, pallet_assets, Assets);
 			add_benchmark!(params, batches, pallet_babe, Babe);
+			add_benchmark!(params, batches, pallet_bags_list, BagsList);
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_collective, Council);
 			add_benchmark!(params, batches, pallet_contracts, Contracts);
 			add_benchmark!(params, batches, pallet_democracy, Democracy);
 			add_benchmark!(params, batches, pallet_elections_phragmen, Elections);
+			add_benchmark!(params, batches, pallet_fast_unstake, FastUnstake);
 			add_benchmark!(params, batches, pallet_grandpa, Grandpa);
 			add_benchmark!(params, batches, pallet_identity, Identity);
 			add_benchmark!(params, batches, pallet_im_online, ImOnline);
 			add_benchmark!(params, batches, pallet_indices, Indices);
 			add_benchmark!(params, batches, pallet_mmr, Mmr);
 			add_benchmark!(params, batches, pallet_multisig, Multisig);
+			add_benchmark!(params, batches, pallet_nomination_pools, NominationPools);
 			add_benchmark!(params, batches, pallet_offences, OffencesBench::<Runtime>);
+			add_benchmark!(params, batches, pallet_preimage, Preimage);
 			add_benchmark!(params, batches, pallet_proxy, Proxy);
+			add_benchmark!(params, batches, pallet_referenda, Referenda);
 			add_benchmark!(params, batches, pallet_scheduler, Scheduler);
 			add_benchmark!(params, batches, pallet_session, SessionBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_staking, Staking);
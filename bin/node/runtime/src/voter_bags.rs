@@ -0,0 +1,62 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The bag thresholds of the voter list.
+//!
+//! There are 200 bags, whose thresholds grow by a constant ratio of `2^(64/200)`, about
+//! 1.248, from 1 to `VoteWeight::max_value()`. The spread of the vote weights within each bag
+//! is then proportional to the vote weights themselves. The thresholds were generated as the
+//! rounded powers of the ratio, bumped where needed to keep them strictly increasing.
+
+/// The upper thresholds of the bags of the voter list.
+pub const THRESHOLDS: [u64; 200] = [
+	1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 14, 18, 22, 28, 35, 43, 54, 68, 84, 105, 132, 164, 205, 256,
+	320, 399, 498, 622, 776, 969, 1_209, 1_510, 1_885, 2_353, 2_937, 3_666, 4_576, 5_713, 7_132,
+	8_903, 11_113, 13_873, 17_318, 21_619, 26_987, 33_689, 42_055, 52_499, 65_536, 81_811, 102_127,
+	127_488, 159_147, 198_668, 248_003, 309_590, 386_471, 482_443, 602_249, 751_806, 938_502,
+	1_171_560, 1_462_495, 1_825_677, 2_279_048, 2_845_005, 3_551_507, 4_433_455, 5_534_417,
+	6_908_782, 8_624_444, 10_766_157, 13_439_722, 16_777_216, 20_943_511, 26_144_425, 32_636_884,
+	40_741_620, 50_859_008, 63_488_854, 79_255_076, 98_936_532, 123_505_496, 154_175_683,
+	192_462_215, 240_256_463, 299_919_482, 374_398_652, 467_373_275, 583_436_337, 728_321_402,
+	909_185_856, 1_134_964_479, 1_416_810_831, 1_768_648_242, 2_207_857_631, 2_756_136_128,
+	3_440_568_926, 4_294_967_296, 5_361_538_882, 6_692_972_776, 8_355_042_379, 10_429_854_639,
+	13_019_906_166, 16_253_146_611, 20_289_299_431, 25_327_752_295, 31_617_406_925, 39_468_974_941,
+	49_270_327_153, 61_505_654_540, 76_779_387_492, 95_846_054_932, 119_647_558_364,
+	149_359_702_207, 186_450_279_040, 232_751_579_177, 290_550_906_592, 362_703_572_709,
+	452_773_950_009, 565_211_553_543, 705_570_848_880, 880_785_645_071, 1_099_511_627_776,
+	1_372_553_953_825, 1_713_401_030_576, 2_138_890_848_987, 2_670_042_787_557, 3_333_095_978_582,
+	4_160_805_532_485, 5_194_060_654_240, 6_483_904_587_535, 8_094_056_172_783, 10_104_057_585_011,
+	12_613_203_751_231, 15_745_447_562_181, 19_655_523_198_000, 24_536_590_062_707,
+	30_629_774_941_153, 38_236_083_765_022, 47_731_271_434_238, 59_584_404_269_275,
+	74_381_032_087_439, 92_852_114_613_581, 115_910_131_202_247, 144_694_157_706_959,
+	180_626_137_313_185, 225_481_125_138_146, 281_474_976_710_655, 351_373_812_179_280,
+	438_630_663_827_412, 547_556_057_340_752, 683_530_953_614_583, 853_272_570_516_935,
+	1_065_166_216_316_095, 1_329_679_527_485_324, 1_659_879_574_408_990, 2_072_078_380_232_548,
+	2_586_638_741_762_860, 3_228_980_160_315_006, 4_030_834_575_918_446, 5_031_813_938_687_869,
+	6_281_367_056_053_010, 7_841_222_384_935_156, 9_788_437_443_845_708, 12_219_205_487_165_004,
+	15_253_607_492_934_278, 19_041_544_214_384_388, 23_770_141_341_076_772, 29_672_993_587_775_264,
+	37_041_704_372_981_504, 46_240_291_152_175_240, 57_723_168_035_365_264, 72_057_594_037_927_504,
+	89_951_695_917_895_552, 112_289_449_939_817_440, 140_174_350_679_232_368,
+	174_983_924_125_333_184, 218_437_778_052_335_168, 272_682_551_376_920_032,
+	340_397_959_036_242_560, 424_929_171_048_701_056, 530_452_065_339_531_968,
+	662_179_517_891_291_648, 826_618_921_040_640_768, 1_031_893_651_435_121_280,
+	1_288_144_368_304_093_440, 1_608_029_966_349_569_024, 2_007_352_930_543_398_144,
+	2_505_839_985_624_499_200, 3_128_116_604_714_238_464, 3_904_923_518_191_171_584,
+	4_874_635_318_882_399_232, 6_085_156_183_315_648_512, 7_596_286_358_470_461_440,
+	9_482_676_319_483_256_832, 11_837_514_534_956_851_200, 14_777_131_017_053_495_296,
+	18_446_744_073_709_551_615,
+];
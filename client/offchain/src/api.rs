@@ -315,6 +315,10 @@ mod tests {
 		fn set_authorized_only(&self, _reserved_only: bool) {
 			unimplemented!()
 		}
+
+		fn num_connected_peers(&self) -> usize {
+			0
+		}
 	}
 
 	impl NetworkStateInfo for TestNetwork {
@@ -46,12 +46,14 @@ use log::{debug, warn};
 use sc_network::{ExHashT, NetworkService, NetworkStateInfo, PeerId};
 use sp_core::{offchain::{self, OffchainStorage}, ExecutionContext, traits::SpawnNamed};
 use sp_runtime::{generic::BlockId, traits::{self, Header}};
+use sp_blockchain::HeaderBackend;
+use codec::Encode;
 use futures::{prelude::*, future::ready};
 
 mod api;
 use api::SharedClient;
 
-pub use sp_offchain::{OffchainWorkerApi, STORAGE_PREFIX};
+pub use sp_offchain::{OffchainWorkerApi, NodeHealth, NODE_HEALTH_KEY, STORAGE_PREFIX};
 
 /// NetworkProvider provides [`OffchainWorkers`] with all necessary hooks into the
 /// underlying Substrate networking.
@@ -61,6 +63,9 @@ pub trait NetworkProvider: NetworkStateInfo {
 	
 	/// Set the authorized only flag.
 	fn set_authorized_only(&self, reserved_only: bool);
+
+	/// Returns the number of peers we're connected to.
+	fn num_connected_peers(&self) -> usize;
 }
 
 impl<B, H> NetworkProvider for NetworkService<B, H>
@@ -75,6 +80,10 @@ where
 	fn set_authorized_only(&self, reserved_only: bool) {
 		self.set_authorized_only(reserved_only)
 	}
+
+	fn num_connected_peers(&self) -> usize {
+		self.num_connected()
+	}
 }
 
 /// An offchain workers manager.
@@ -116,7 +125,7 @@ impl<Client, Storage, Block> OffchainWorkers<
 	Block,
 > where
 	Block: traits::Block,
-	Client: ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	Client: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
 	Client::Api: OffchainWorkerApi<Block>,
 	Storage: OffchainStorage + 'static,
 {
@@ -147,6 +156,7 @@ impl<Client, Storage, Block> OffchainWorkers<
 		};
 		debug!("Checking offchain workers at {:?}: version:{}", at, version);
 		if version > 0 {
+			self.store_node_health(&*network_provider);
 			let (api, runner) = api::AsyncApi::new(
 				self.db.clone(),
 				network_provider,
@@ -181,6 +191,15 @@ impl<Client, Storage, Block> OffchainWorkers<
 		}
 	}
 
+	/// Stores a snapshot of the node's health for the offchain workers to read.
+	fn store_node_health(&self, network_provider: &dyn NetworkProvider) {
+		let health = NodeHealth {
+			finalized_number: self.client.info().finalized_number,
+			peers: network_provider.num_connected_peers() as u32,
+		};
+		self.db.clone().set(STORAGE_PREFIX, NODE_HEALTH_KEY, &health.encode());
+	}
+
 	/// Spawns a new offchain worker.
 	///
 	/// We spawn offchain workers for each block in a separate thread,
@@ -204,7 +223,8 @@ pub async fn notification_future<Client, Storage, Block, Spawner>(
 )
 	where
 		Block: traits::Block,
-		Client: ProvideRuntimeApi<Block> + sc_client_api::BlockchainEvents<Block> + Send + Sync + 'static,
+		Client: ProvideRuntimeApi<Block> + HeaderBackend<Block> +
+			sc_client_api::BlockchainEvents<Block> + Send + Sync + 'static,
 		Client::Api: OffchainWorkerApi<Block>,
 		Storage: OffchainStorage + 'static,
 		Spawner: SpawnNamed
@@ -239,6 +259,7 @@ mod tests {
 	use substrate_test_runtime_client::{TestClient, runtime::Block};
 	use sc_transaction_pool::{BasicPool, FullChainApi};
 	use sp_transaction_pool::{TransactionPool, InPoolTransaction};
+	use codec::Decode;
 
 	struct TestNetwork();
 
@@ -260,6 +281,10 @@ mod tests {
 		fn set_authorized_only(&self, _reserved_only: bool) {
 			unimplemented!()
 		}
+
+		fn num_connected_peers(&self) -> usize {
+			3
+		}
 	}
 
 	struct TestPool(
@@ -305,4 +330,25 @@ mod tests {
 		assert_eq!(pool.0.status().ready, 1);
 		assert_eq!(pool.0.ready().next().unwrap().is_propagable(), false);
 	}
+
+	#[test]
+	fn should_store_node_health_for_offchain_workers() {
+		sp_tracing::try_init_simple();
+
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let db = sc_client_db::offchain::LocalStorage::new_test();
+		let network = Arc::new(TestNetwork());
+		let header = client.header(&BlockId::number(0)).unwrap().unwrap();
+
+		// when
+		let offchain = OffchainWorkers::new(client, db.clone());
+		futures::executor::block_on(
+			offchain.on_block_imported(&header, network, false)
+		);
+
+		// then
+		let health = db.get(STORAGE_PREFIX, NODE_HEALTH_KEY)
+			.and_then(|health| NodeHealth::<u64>::decode(&mut &health[..]).ok());
+		assert_eq!(health, Some(NodeHealth { finalized_number: 0, peers: 3 }));
+	}
 }
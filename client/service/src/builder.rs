@@ -496,7 +496,8 @@ pub fn build_offchain_workers<TBl, TBackend, TCl>(
 	where
 		TBl: BlockT, TBackend: sc_client_api::Backend<TBl>,
 		<TBackend as sc_client_api::Backend<TBl>>::OffchainStorage: 'static,
-		TCl: Send + Sync + ProvideRuntimeApi<TBl> + BlockchainEvents<TBl> + 'static,
+		TCl: Send + Sync + ProvideRuntimeApi<TBl> + HeaderBackend<TBl> + BlockchainEvents<TBl> +
+			'static,
 		<TCl as ProvideRuntimeApi<TBl>>::Api: sc_offchain::OffchainWorkerApi<TBl>,
 {
 	let offchain_workers = match backend.offchain_storage() {
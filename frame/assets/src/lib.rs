@@ -54,7 +54,9 @@
 //! * **Owner**: An account ID uniquely privileged to be able to destroy a particular asset class,
 //!   or to set the Issuer, Freezer or Admin of that asset class.
 //! * **Zombie**: An account which has a balance of some assets in this pallet, but no other
-//!   footprint on-chain, in particular no account managed in the `frame_system` pallet.
+//!   footprint on-chain, in particular nothing providing for its existence in the `frame_system`
+//!   pallet (such as a native balance). A zombie is kept alive by a *sufficient* reference held
+//!   by this pallet.
 //!
 //! ### Goals
 //!
@@ -119,6 +121,7 @@ use sp_runtime::{RuntimeDebug, traits::{
 }};
 use codec::{Encode, Decode, HasCompact};
 use frame_support::{Parameter, decl_module, decl_event, decl_storage, decl_error, ensure,
+	IterableStorageDoubleMap,
 	traits::{Currency, ReservableCurrency, EnsureOrigin, Get, BalanceStatus::Reserved},
 	dispatch::{DispatchResult, DispatchError},
 };
@@ -194,7 +197,8 @@ pub struct AssetBalance<
 	balance: Balance,
 	/// Whether the account is frozen.
 	is_frozen: bool,
-	/// Whether the account is a zombie. If not, then it has a reference.
+	/// Whether the account is a zombie. If so, then it holds a sufficient reference on the
+	/// account; if not, then it holds a consumer reference.
 	is_zombie: bool,
 }
 
@@ -406,14 +410,15 @@ decl_module! {
 			let origin = ensure_signed(origin)?;
 
 			Asset::<T>::try_mutate_exists(id, |maybe_details| {
-				let details = maybe_details.take().ok_or(Error::<T>::Unknown)?;
+				let mut details = maybe_details.take().ok_or(Error::<T>::Unknown)?;
 				ensure!(details.owner == origin, Error::<T>::NoPermission);
 				ensure!(details.accounts == details.zombies, Error::<T>::RefsLeft);
 				ensure!(details.zombies <= zombies_witness, Error::<T>::BadWitness);
 				T::Currency::unreserve(&details.owner, details.deposit);
 
-				*maybe_details = None;
-				Account::<T>::remove_prefix(&id);
+				for (who, v) in Account::<T>::drain_prefix(&id) {
+					Self::dead_account(&who, &mut details, v.is_zombie);
+				}
 				Self::deposit_event(RawEvent::Destroyed(id));
 				Ok(())
 			})
@@ -437,13 +442,14 @@ decl_module! {
 			T::ForceOrigin::ensure_origin(origin)?;
 
 			Asset::<T>::try_mutate_exists(id, |maybe_details| {
-				let details = maybe_details.take().ok_or(Error::<T>::Unknown)?;
+				let mut details = maybe_details.take().ok_or(Error::<T>::Unknown)?;
 				ensure!(details.accounts == details.zombies, Error::<T>::RefsLeft);
 				ensure!(details.zombies <= zombies_witness, Error::<T>::BadWitness);
 				T::Currency::unreserve(&details.owner, details.deposit);
 
-				*maybe_details = None;
-				Account::<T>::remove_prefix(&id);
+				for (who, v) in Account::<T>::drain_prefix(&id) {
+					Self::dead_account(&who, &mut details, v.is_zombie);
+				}
 				Self::deposit_event(RawEvent::Destroyed(id));
 				Ok(())
 			})
@@ -862,26 +868,27 @@ impl<T: Config> Module<T> {
 		d: &mut AssetDetails<T::Balance, T::AccountId, BalanceOf<T>>,
 	) -> Result<bool, DispatchError> {
 		let accounts = d.accounts.checked_add(1).ok_or(Error::<T>::Overflow)?;
-		let r = Ok(if frame_system::Module::<T>::account_exists(who) {
-			frame_system::Module::<T>::inc_ref(who);
+		let is_zombie = if frame_system::Module::<T>::providers(who) > 0 {
+			frame_system::Module::<T>::inc_consumers(who)?;
 			false
 		} else {
 			ensure!(d.zombies < d.max_zombies, Error::<T>::TooManyZombies);
 			d.zombies += 1;
+			frame_system::Module::<T>::inc_sufficients(who);
 			true
-		});
+		};
 		d.accounts = accounts;
-		r
+		Ok(is_zombie)
 	}
 
-	/// If `who`` exists in system and it's a zombie, dezombify it.
+	/// If something else now provides for `who` in system and it's a zombie, dezombify it.
 	fn dezombify(
 		who: &T::AccountId,
 		d: &mut AssetDetails<T::Balance, T::AccountId, BalanceOf<T>>,
 		is_zombie: &mut bool,
 	) {
-		if *is_zombie && frame_system::Module::<T>::account_exists(who) {
-			frame_system::Module::<T>::inc_ref(who);
+		if *is_zombie && frame_system::Module::<T>::inc_consumers(who).is_ok() {
+			frame_system::Module::<T>::dec_sufficients(who);
 			*is_zombie = false;
 			d.zombies = d.zombies.saturating_sub(1);
 		}
@@ -894,8 +901,9 @@ impl<T: Config> Module<T> {
 	) {
 		if is_zombie {
 			d.zombies = d.zombies.saturating_sub(1);
+			frame_system::Module::<T>::dec_sufficients(who);
 		} else {
-			frame_system::Module::<T>::dec_ref(who);
+			frame_system::Module::<T>::dec_consumers(who);
 		}
 		d.accounts = d.accounts.saturating_sub(1);
 	}
@@ -960,6 +968,9 @@ mod tests {
 
 	impl pallet_balances::Config for Test {
 		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 		type Balance = u64;
 		type DustRemoval = ();
 		type Event = Event;
@@ -1106,6 +1117,28 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn zombies_are_kept_alive_by_sufficient_references() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 1));
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 100));
+			assert!(System::account_exists(&2));
+			assert_eq!(System::providers(&2), 0);
+			assert_eq!(System::sufficients(&2), 1);
+
+			Balances::make_free_balance_be(&1, 100);
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+			assert_eq!(System::sufficients(&1), 0);
+			assert_eq!(System::consumers(&1), 1);
+
+			assert_ok!(Assets::burn(Origin::signed(1), 0, 1, 100));
+			assert_eq!(System::consumers(&1), 0);
+
+			assert_ok!(Assets::force_destroy(Origin::root(), 0, 1));
+			assert!(!System::account_exists(&2));
+		});
+	}
+
 	#[test]
 	fn min_balance_should_work() {
 		new_test_ext().execute_with(|| {
@@ -48,6 +48,9 @@ parameter_types! {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type Balance = u64;
 	type DustRemoval = ();
 	type Event = ();
@@ -129,6 +129,7 @@ mod tests {
 	impl pallet_session::historical::Config for Test {
 		type FullIdentification = ();
 		type FullIdentificationOf = ();
+		type PruningDepth = ();
 	}
 
 	pub type BlockNumber = u64;
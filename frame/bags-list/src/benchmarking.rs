@@ -0,0 +1,76 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bags list pallet benchmarking.
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, account, whitelisted_caller};
+use frame_system::RawOrigin;
+
+use crate::Module as BagsList;
+
+const SEED: u32 = 0;
+
+/// Insert a new account with the given name and score into the list.
+fn insert_node<T: Config>(
+	name: &'static str,
+	score: VoteWeight,
+) -> Result<T::AccountId, &'static str> {
+	let who: T::AccountId = account(name, 0, SEED);
+	List::<T>::insert(who.clone(), score).map_err(|_| "account already in the list")?;
+	Ok(who)
+}
+
+benchmarks! {
+	_ { }
+
+	// The worst case is an account with neighbours on both sides in its bag, moving to the tail
+	// of a non-empty bag.
+	rebag {
+		List::<T>::clear();
+		let score = T::ScoreProvider::score(&account("dislocated", 0, SEED));
+		let to = notional_bag_for::<T>(score);
+		// Put the account into a bag other than the one of its score.
+		let wrong_score = if to == VoteWeight::max_value() { 0 } else { VoteWeight::max_value() };
+
+		insert_node::<T>("origin_head", wrong_score)?;
+		let dislocated = insert_node::<T>("dislocated", wrong_score)?;
+		insert_node::<T>("origin_tail", wrong_score)?;
+		insert_node::<T>("destination_tail", score)?;
+
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), dislocated.clone())
+	verify {
+		assert_eq!(BagsList::<T>::list_node(&dislocated).map(|node| node.bag_upper), Some(to));
+		assert!(List::<T>::sanity_check().is_ok());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_rebag::<Test>());
+		});
+	}
+}
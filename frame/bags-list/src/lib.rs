@@ -0,0 +1,208 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Bags List Pallet
+//!
+//! - [`bags_list::Config`](./trait.Config.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! A semi-sorted list of accounts, which can be iterated from the highest to the lowest score
+//! without reading more than the accounts which are taken.
+//!
+//! The accounts are kept in _bags_, each of which covers a range of scores. The upper thresholds
+//! of the bags are given by `BagThresholds`, and an account belongs to the bag with the smallest
+//! threshold which is at least equal to its score. Within a bag, accounts are kept in a
+//! doubly-linked list in insertion order, so the list is sorted by bag but not within a bag.
+//! Iterating the list goes through the bags from the highest threshold to the lowest, and through
+//! each bag from its head to its tail.
+//!
+//! The list implements [`SortedListProvider`], which the staking pallet uses to keep track of
+//! its nominators, so that the election snapshot only takes the nominators with the highest
+//! stake.
+//!
+//! ### Rebagging
+//!
+//! The owner of the list is expected to update the position of an account whenever its score
+//! changes, through [`SortedListProvider::on_update`]. When the score of an account changes
+//! without the list being notified, e.g. because the issuance changed, the account can end up in
+//! the wrong bag. Anyone can then call `rebag` to move it to the bag of its current score, as
+//! given by `ScoreProvider`.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! * `rebag` - Move an account to the bag of its current score.
+//!
+//! ### Choosing the Thresholds
+//!
+//! Thresholds growing by a constant ratio keep the spread of the scores within each bag
+//! proportional to the scores themselves. The thresholds must be strictly increasing, and
+//! changing them requires the list to be regenerated, or all the accounts to be rebagged.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::{Encode, Decode};
+use sp_runtime::RuntimeDebug;
+use frame_support::{
+	decl_module, decl_event, decl_storage, decl_error, ensure, traits::Get,
+};
+use frame_system::ensure_signed;
+use sp_npos_elections::VoteWeight;
+use sp_staking::{ScoreProvider, SortedListProvider};
+
+mod list;
+pub mod weights;
+pub use list::{notional_bag_for, Bag, List, ListError, Node};
+pub use weights::WeightInfo;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+/// Configuration trait.
+pub trait Config: frame_system::Config {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+	/// Something which provides the current score of the accounts, used by `rebag`.
+	type ScoreProvider: ScoreProvider<Self::AccountId, Score = VoteWeight>;
+
+	/// The upper thresholds of the bags, in strictly increasing order.
+	///
+	/// A bag with the threshold `VoteWeight::max_value()` is implied if it is not the last one.
+	/// The list must be regenerated whenever the thresholds change.
+	type BagThresholds: Get<&'static [VoteWeight]>;
+
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as BagsList {
+		/// The number of nodes in the list.
+		pub CounterForListNodes get(fn counter_for_list_nodes): u32;
+
+		/// The nodes of the list, keyed by their account.
+		pub ListNodes get(fn list_node):
+			map hasher(twox_64_concat) T::AccountId => Option<Node<T::AccountId>>;
+
+		/// The non-empty bags of the list, keyed by their upper threshold.
+		pub ListBags get(fn list_bag):
+			map hasher(twox_64_concat) VoteWeight => Option<Bag<T::AccountId>>;
+	}
+}
+
+decl_event! {
+	pub enum Event<T> where AccountId = <T as frame_system::Config>::AccountId {
+		/// An account was moved from one bag to another. \[who, from, to\]
+		Rebagged(AccountId, VoteWeight, VoteWeight),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// The account is not in the list.
+		NotInList,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		/// The upper thresholds of the bags.
+		const BagThresholds: &'static [VoteWeight] = T::BagThresholds::get();
+
+		fn deposit_event() = default;
+
+		/// Move `dislocated` to the bag of its current score, if it is not in it already.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Parameters:
+		/// - `dislocated`: The account to rebag.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Up to four nodes and two bags are written.
+		/// # </weight>
+		#[weight = T::WeightInfo::rebag()]
+		fn rebag(origin, dislocated: T::AccountId) {
+			ensure_signed(origin)?;
+			let node = Self::list_node(&dislocated).ok_or(Error::<T>::NotInList)?;
+			let score = T::ScoreProvider::score(&dislocated);
+			if let Some((from, to)) = List::<T>::update_position_for(node, score) {
+				Self::deposit_event(RawEvent::Rebagged(dislocated, from, to));
+			}
+		}
+	}
+}
+
+impl<T: Config> SortedListProvider<T::AccountId> for Module<T> {
+	type Score = VoteWeight;
+	type Error = ListError;
+
+	fn iter() -> Box<dyn Iterator<Item = T::AccountId>> {
+		Box::new(List::<T>::iter().map(|node| node.id))
+	}
+
+	fn count() -> u32 {
+		List::<T>::count()
+	}
+
+	fn contains(id: &T::AccountId) -> bool {
+		ListNodes::<T>::contains_key(id)
+	}
+
+	fn on_insert(id: T::AccountId, score: VoteWeight) -> Result<(), ListError> {
+		List::<T>::insert(id, score)
+	}
+
+	fn on_update(id: &T::AccountId, score: VoteWeight) {
+		if let Some(node) = Self::list_node(id) {
+			List::<T>::update_position_for(node, score);
+		}
+	}
+
+	fn on_remove(id: &T::AccountId) {
+		List::<T>::remove(id)
+	}
+
+	fn regenerate(
+		all: impl IntoIterator<Item = T::AccountId>,
+		score_of: Box<dyn Fn(&T::AccountId) -> VoteWeight>,
+	) -> u32 {
+		List::<T>::regenerate(all, score_of)
+	}
+
+	fn clear() {
+		List::<T>::clear()
+	}
+
+	fn sanity_check() -> Result<(), &'static str> {
+		List::<T>::sanity_check()
+	}
+}
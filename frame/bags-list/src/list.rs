@@ -0,0 +1,264 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The bags of the list and the doubly-linked lists of nodes they contain.
+//!
+//! Each node is stored under the account it represents, and each bag under its upper threshold.
+//! The nodes of a bag are linked from its head to its tail, and new nodes are appended at the
+//! tail.
+
+use super::*;
+use frame_support::{StorageMap, StorageValue, IterableStorageMap, StoragePrefixedMap};
+use sp_std::{collections::btree_set::BTreeSet, iter, marker::PhantomData};
+
+/// The errors of list operations.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum ListError {
+	/// The account is already in the list.
+	Duplicate,
+}
+
+/// The upper threshold of the bag an account with the given score belongs to.
+///
+/// This is the smallest threshold which is at least equal to the score, or
+/// `VoteWeight::max_value()` if the score is above all the thresholds.
+pub fn notional_bag_for<T: Config>(score: VoteWeight) -> VoteWeight {
+	let thresholds = T::BagThresholds::get();
+	let index = match thresholds.binary_search(&score) {
+		Ok(index) | Err(index) => index,
+	};
+	thresholds.get(index).copied().unwrap_or_else(VoteWeight::max_value)
+}
+
+/// A node of the list, representing one account.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct Node<AccountId> {
+	/// The account of the node.
+	pub id: AccountId,
+	/// The previous node in the bag, towards its head.
+	pub prev: Option<AccountId>,
+	/// The next node in the bag, towards its tail.
+	pub next: Option<AccountId>,
+	/// The upper threshold of the bag the node is in.
+	pub bag_upper: VoteWeight,
+}
+
+impl<AccountId> Node<AccountId> {
+	/// Whether the node is not in the bag for `score`.
+	pub fn is_misplaced<T: Config>(&self, score: VoteWeight) -> bool {
+		notional_bag_for::<T>(score) != self.bag_upper
+	}
+}
+
+/// A bag of the list, which contains the nodes whose score is between the threshold of the
+/// previous bag (exclusive) and its own (inclusive).
+#[derive(Clone, Default, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct Bag<AccountId> {
+	/// The first node of the bag.
+	pub head: Option<AccountId>,
+	/// The last node of the bag.
+	pub tail: Option<AccountId>,
+	/// The upper threshold of the bag, which is its storage key.
+	#[codec(skip)]
+	pub bag_upper: VoteWeight,
+}
+
+/// The operations on the list, on top of the storage of the pallet.
+pub struct List<T>(PhantomData<T>);
+
+impl<T: Config> List<T> {
+	/// Get the bag with the given upper threshold.
+	pub fn get_bag(bag_upper: VoteWeight) -> Option<Bag<T::AccountId>> {
+		ListBags::<T>::get(bag_upper).map(|mut bag| {
+			bag.bag_upper = bag_upper;
+			bag
+		})
+	}
+
+	/// Get the bag with the given upper threshold, or an empty one if it does not exist.
+	fn get_bag_or_default(bag_upper: VoteWeight) -> Bag<T::AccountId> {
+		Self::get_bag(bag_upper).unwrap_or_else(|| Bag { bag_upper, ..Default::default() })
+	}
+
+	/// Store `bag`, or remove it if it is empty.
+	fn put_bag(bag: Bag<T::AccountId>) {
+		if bag.head.is_none() {
+			ListBags::<T>::remove(bag.bag_upper);
+		} else {
+			ListBags::<T>::insert(bag.bag_upper, bag);
+		}
+	}
+
+	/// An iterator over the nodes of `bag`, from its head to its tail.
+	pub fn bag_iter(bag: Bag<T::AccountId>) -> impl Iterator<Item = Node<T::AccountId>> {
+		iter::successors(
+			bag.head.and_then(ListNodes::<T>::get),
+			|node| node.next.as_ref().and_then(ListNodes::<T>::get),
+		)
+	}
+
+	/// An iterator over the nodes of the list, from the bag with the highest threshold to the
+	/// bag with the lowest one.
+	///
+	/// The nodes of a bag are not sorted by score, so the order is only approximate.
+	pub fn iter() -> impl Iterator<Item = Node<T::AccountId>> {
+		let mut thresholds = T::BagThresholds::get().to_vec();
+		if thresholds.last() != Some(&VoteWeight::max_value()) {
+			thresholds.push(VoteWeight::max_value());
+		}
+		thresholds.into_iter()
+			.rev()
+			.filter_map(Self::get_bag)
+			.flat_map(Self::bag_iter)
+	}
+
+	/// Append `node` to the tail of `bag`, and store the node.
+	fn insert_into_bag(bag: &mut Bag<T::AccountId>, mut node: Node<T::AccountId>) {
+		node.bag_upper = bag.bag_upper;
+		node.prev = bag.tail.clone();
+		node.next = None;
+		if let Some(tail) = &bag.tail {
+			ListNodes::<T>::mutate(tail, |tail| if let Some(tail) = tail {
+				tail.next = Some(node.id.clone());
+			});
+		}
+		if bag.head.is_none() {
+			bag.head = Some(node.id.clone());
+		}
+		bag.tail = Some(node.id.clone());
+		ListNodes::<T>::insert(node.id.clone(), node);
+	}
+
+	/// Unlink `node` from its neighbours in `bag`, without removing the node from storage.
+	fn remove_from_bag(bag: &mut Bag<T::AccountId>, node: &Node<T::AccountId>) {
+		if let Some(prev) = &node.prev {
+			ListNodes::<T>::mutate(prev, |prev| if let Some(prev) = prev {
+				prev.next = node.next.clone();
+			});
+		}
+		if let Some(next) = &node.next {
+			ListNodes::<T>::mutate(next, |next| if let Some(next) = next {
+				next.prev = node.prev.clone();
+			});
+		}
+		if bag.head.as_ref() == Some(&node.id) {
+			bag.head = node.next.clone();
+		}
+		if bag.tail.as_ref() == Some(&node.id) {
+			bag.tail = node.prev.clone();
+		}
+	}
+
+	/// Insert `id` with the given score into the tail of its bag.
+	pub fn insert(id: T::AccountId, score: VoteWeight) -> Result<(), ListError> {
+		if ListNodes::<T>::contains_key(&id) {
+			return Err(ListError::Duplicate)
+		}
+		let mut bag = Self::get_bag_or_default(notional_bag_for::<T>(score));
+		let node = Node { id, prev: None, next: None, bag_upper: bag.bag_upper };
+		Self::insert_into_bag(&mut bag, node);
+		Self::put_bag(bag);
+		CounterForListNodes::mutate(|count| *count = count.saturating_add(1));
+		Ok(())
+	}
+
+	/// Remove `id` from the list, if it is in it.
+	pub fn remove(id: &T::AccountId) {
+		if let Some(node) = ListNodes::<T>::take(id) {
+			let mut bag = Self::get_bag_or_default(node.bag_upper);
+			Self::remove_from_bag(&mut bag, &node);
+			Self::put_bag(bag);
+			CounterForListNodes::mutate(|count| *count = count.saturating_sub(1));
+		}
+	}
+
+	/// Move `node` to the tail of the bag for `score`, if it is not in it already.
+	///
+	/// Returns the upper thresholds of the bags the node was moved from and to, if it moved.
+	pub fn update_position_for(
+		node: Node<T::AccountId>,
+		score: VoteWeight,
+	) -> Option<(VoteWeight, VoteWeight)> {
+		if !node.is_misplaced::<T>(score) {
+			return None
+		}
+		let from = node.bag_upper;
+		let mut old_bag = Self::get_bag_or_default(from);
+		Self::remove_from_bag(&mut old_bag, &node);
+		Self::put_bag(old_bag);
+
+		let mut new_bag = Self::get_bag_or_default(notional_bag_for::<T>(score));
+		let to = new_bag.bag_upper;
+		Self::insert_into_bag(&mut new_bag, node);
+		Self::put_bag(new_bag);
+		Some((from, to))
+	}
+
+	/// Remove all the nodes and bags of the list.
+	pub fn clear() {
+		ListNodes::<T>::remove_all();
+		ListBags::<T>::remove_all();
+		CounterForListNodes::kill();
+	}
+
+	/// Clear the list and insert all of `all` into it, ignoring duplicates.
+	///
+	/// Returns the number of accounts inserted.
+	pub fn regenerate(
+		all: impl IntoIterator<Item = T::AccountId>,
+		score_of: Box<dyn Fn(&T::AccountId) -> VoteWeight>,
+	) -> u32 {
+		Self::clear();
+		let mut count = 0;
+		for id in all {
+			let score = score_of(&id);
+			if Self::insert(id, score).is_ok() {
+				count += 1;
+			}
+		}
+		count
+	}
+
+	/// Check that the bags are consistently linked, that every node is in exactly one bag and
+	/// that the counter matches the number of nodes.
+	pub fn sanity_check() -> Result<(), &'static str> {
+		let mut seen = BTreeSet::new();
+		for (bag_upper, bag) in ListBags::<T>::iter() {
+			let bag = Bag { bag_upper, ..bag };
+			let head = bag.head.as_ref().and_then(ListNodes::<T>::get).ok_or("bag without head")?;
+			ensure!(head.prev.is_none(), "head of a bag has a previous node");
+			let mut last = None;
+			for node in Self::bag_iter(bag.clone()) {
+				ensure!(node.bag_upper == bag_upper, "node in the wrong bag");
+				ensure!(node.prev == last, "node not linked to its previous node");
+				ensure!(seen.insert(node.id.clone()), "node in the list twice");
+				last = Some(node.id);
+			}
+			ensure!(last == bag.tail, "tail of a bag is not its last node");
+		}
+		ensure!(
+			seen.len() as u32 == Self::count() && ListNodes::<T>::iter().count() == seen.len(),
+			"counter does not match the number of nodes"
+		);
+		Ok(())
+	}
+
+	/// The number of nodes in the list.
+	pub fn count() -> u32 {
+		CounterForListNodes::get()
+	}
+}
@@ -0,0 +1,143 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test utilities
+
+use super::*;
+
+use frame_support::{impl_outer_origin, impl_outer_event, parameter_types};
+use std::{cell::RefCell, collections::HashMap};
+use sp_core::H256;
+use sp_runtime::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+use crate as bags_list;
+
+pub type AccountId = u64;
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		system<T>,
+		bags_list<T>,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+
+thread_local! {
+	static SCORES: RefCell<HashMap<AccountId, VoteWeight>> = RefCell::new(Default::default());
+}
+
+/// Provides the scores set by the tests, defaulting to zero.
+pub struct StakingMock;
+impl StakingMock {
+	pub fn set_score_of(who: AccountId, score: VoteWeight) {
+		SCORES.with(|s| s.borrow_mut().insert(who, score));
+	}
+}
+
+impl ScoreProvider<AccountId> for StakingMock {
+	type Score = VoteWeight;
+
+	fn score(who: &AccountId) -> VoteWeight {
+		SCORES.with(|s| s.borrow().get(who).copied().unwrap_or_default())
+	}
+}
+
+pub const THRESHOLDS: [VoteWeight; 9] = [10, 20, 30, 40, 50, 60, 1_000, 2_000, 10_000];
+
+parameter_types! {
+	pub const BagThresholds: &'static [VoteWeight] = &THRESHOLDS;
+}
+
+impl Config for Test {
+	type Event = TestEvent;
+	type ScoreProvider = StakingMock;
+	type BagThresholds = BagThresholds;
+	type WeightInfo = ();
+}
+
+pub type System = frame_system::Module<Test>;
+pub type BagsList = Module<Test>;
+
+/// The accounts in the list at genesis, with their scores.
+pub const GENESIS_IDS: [(AccountId, VoteWeight); 4] =
+	[(1, 10), (2, 1_000), (3, 1_000), (4, 1_000)];
+
+/// Build the externalities with the accounts of `GENESIS_IDS` in the list.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	SCORES.with(|s| s.borrow_mut().clear());
+	let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		for (id, score) in GENESIS_IDS.iter() {
+			StakingMock::set_score_of(*id, *score);
+			assert!(List::<Test>::insert(*id, *score).is_ok());
+		}
+	});
+	ext
+}
+
+/// The accounts of each non-empty bag, from the lowest threshold to the highest.
+pub fn get_bags() -> Vec<(VoteWeight, Vec<AccountId>)> {
+	let mut thresholds = THRESHOLDS.to_vec();
+	thresholds.push(VoteWeight::max_value());
+	thresholds.into_iter().filter_map(|bag_upper| {
+		List::<Test>::get_bag(bag_upper)
+			.map(|bag| (bag_upper, List::<Test>::bag_iter(bag).map(|node| node.id).collect()))
+	}).collect()
+}
+
+/// The events of this pallet deposited so far.
+pub fn bags_list_events() -> Vec<RawEvent<AccountId>> {
+	System::events().into_iter().filter_map(|r| match r.event {
+		TestEvent::bags_list(e) => Some(e),
+		_ => None,
+	}).collect()
+}
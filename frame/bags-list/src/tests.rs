@@ -0,0 +1,175 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the module.
+
+use super::*;
+use mock::{
+	AccountId, BagsList, Origin, StakingMock, Test, get_bags, bags_list_events, new_test_ext,
+};
+use frame_support::{assert_noop, assert_ok, StorageMap, StorageValue};
+
+fn ids() -> Vec<AccountId> {
+	<BagsList as SortedListProvider<AccountId>>::iter().collect()
+}
+
+#[test]
+fn notional_bag_for_works() {
+	assert_eq!(notional_bag_for::<Test>(0), 10);
+	assert_eq!(notional_bag_for::<Test>(10), 10);
+	assert_eq!(notional_bag_for::<Test>(11), 20);
+	assert_eq!(notional_bag_for::<Test>(60), 60);
+	assert_eq!(notional_bag_for::<Test>(61), 1_000);
+	assert_eq!(notional_bag_for::<Test>(10_000), 10_000);
+	assert_eq!(notional_bag_for::<Test>(10_001), VoteWeight::max_value());
+	assert_eq!(notional_bag_for::<Test>(VoteWeight::max_value()), VoteWeight::max_value());
+}
+
+#[test]
+fn genesis_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![2, 3, 4])]);
+		assert_eq!(List::<Test>::count(), 4);
+		assert_ok!(List::<Test>::sanity_check());
+	});
+}
+
+#[test]
+fn iteration_goes_from_the_highest_bag_to_the_lowest() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(BagsList::on_insert(5, 15));
+		assert_ok!(BagsList::on_insert(6, 20_000));
+		assert_ok!(BagsList::on_insert(7, 5));
+
+		// Within a bag, the accounts keep their insertion order.
+		assert_eq!(ids(), vec![6, 2, 3, 4, 5, 1, 7]);
+		assert_eq!(ids().into_iter().take(2).collect::<Vec<_>>(), vec![6, 2]);
+		assert_eq!(
+			get_bags(),
+			vec![
+				(10, vec![1, 7]),
+				(20, vec![5]),
+				(1_000, vec![2, 3, 4]),
+				(VoteWeight::max_value(), vec![6]),
+			],
+		);
+		assert_ok!(List::<Test>::sanity_check());
+	});
+}
+
+#[test]
+fn insert_and_remove_work() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(BagsList::on_insert(2, 20), Err(ListError::Duplicate));
+		assert_eq!(BagsList::count(), 4);
+
+		// Removing the middle, the head and the tail of a bag relinks its other nodes.
+		BagsList::on_remove(&3);
+		assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![2, 4])]);
+		assert_ok!(List::<Test>::sanity_check());
+		BagsList::on_remove(&2);
+		assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![4])]);
+		assert_ok!(List::<Test>::sanity_check());
+
+		// Removing the last node of a bag removes the bag.
+		BagsList::on_remove(&4);
+		assert_eq!(get_bags(), vec![(10, vec![1])]);
+		assert!(ListBags::<Test>::get(1_000).is_none());
+		assert!(!BagsList::contains(&4));
+		assert_eq!(BagsList::count(), 1);
+
+		// Removing an account which is not in the list is a no-op.
+		BagsList::on_remove(&42);
+		assert_eq!(BagsList::count(), 1);
+
+		assert_ok!(BagsList::on_insert(3, 1_000));
+		assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![3])]);
+		assert_ok!(List::<Test>::sanity_check());
+	});
+}
+
+#[test]
+fn on_update_moves_to_the_tail_of_the_new_bag() {
+	new_test_ext().execute_with(|| {
+		BagsList::on_update(&2, 15);
+		assert_eq!(get_bags(), vec![(10, vec![1]), (20, vec![2]), (1_000, vec![3, 4])]);
+
+		BagsList::on_update(&1, 20);
+		assert_eq!(get_bags(), vec![(20, vec![2, 1]), (1_000, vec![3, 4])]);
+
+		// A new score within the same bag does not move the account.
+		BagsList::on_update(&3, 999);
+		assert_eq!(get_bags(), vec![(20, vec![2, 1]), (1_000, vec![3, 4])]);
+
+		// Accounts which are not in the list are ignored.
+		BagsList::on_update(&42, 10);
+		assert!(!BagsList::contains(&42));
+
+		assert_eq!(BagsList::count(), 4);
+		assert_ok!(List::<Test>::sanity_check());
+		// Updates from the owner of the list deposit no events.
+		assert!(bags_list_events().is_empty());
+	});
+}
+
+#[test]
+fn rebag_works() {
+	new_test_ext().execute_with(|| {
+		// The score of 4 changed without the list being notified.
+		StakingMock::set_score_of(4, 25);
+		assert_ok!(BagsList::rebag(Origin::signed(42), 4));
+		assert_eq!(get_bags(), vec![(10, vec![1]), (30, vec![4]), (1_000, vec![2, 3])]);
+		assert_eq!(bags_list_events(), vec![RawEvent::Rebagged(4, 1_000, 30)]);
+
+		// Rebagging an account in the right bag is a no-op.
+		assert_ok!(BagsList::rebag(Origin::signed(42), 4));
+		assert_eq!(bags_list_events().len(), 1);
+
+		assert_noop!(BagsList::rebag(Origin::signed(42), 5), Error::<Test>::NotInList);
+		assert_ok!(List::<Test>::sanity_check());
+	});
+}
+
+#[test]
+fn regenerate_and_clear_work() {
+	new_test_ext().execute_with(|| {
+		let inserted = BagsList::regenerate(vec![5, 6, 5, 7], Box::new(|id: &AccountId| *id * 10));
+		assert_eq!(inserted, 3);
+		assert_eq!(get_bags(), vec![(50, vec![5]), (60, vec![6]), (1_000, vec![7])]);
+		assert_eq!(BagsList::count(), 3);
+		assert_ok!(List::<Test>::sanity_check());
+
+		BagsList::clear();
+		assert!(get_bags().is_empty());
+		assert_eq!(BagsList::count(), 0);
+		assert!(!BagsList::contains(&5));
+		assert_ok!(List::<Test>::sanity_check());
+	});
+}
+
+#[test]
+fn sanity_check_detects_corruption() {
+	new_test_ext().execute_with(|| {
+		ListNodes::<Test>::mutate(3, |node| node.as_mut().unwrap().prev = None);
+		assert!(List::<Test>::sanity_check().is_err());
+	});
+
+	new_test_ext().execute_with(|| {
+		CounterForListNodes::put(5);
+		assert!(List::<Test>::sanity_check().is_err());
+	});
+}
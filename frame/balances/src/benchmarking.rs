@@ -22,6 +22,7 @@
 use super::*;
 
 use frame_system::RawOrigin;
+use frame_support::traits::UnfilteredDispatchable;
 use frame_benchmarking::{benchmarks, account, whitelisted_caller};
 use sp_runtime::traits::Bounded;
 
@@ -144,6 +145,45 @@ benchmarks! {
 		assert_eq!(Balances::<T>::free_balance(&source), Zero::zero());
 		assert_eq!(Balances::<T>::free_balance(&recipient), transfer_amount);
 	}
+
+	freeze_account {
+		let origin = T::FreezeOrigin::successful_origin();
+		let who: T::AccountId = account("who", 0, SEED);
+		let who_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(who.clone());
+		let call = Call::<T>::freeze_account(who_lookup);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(Balances::<T>::is_frozen(&who));
+	}
+
+	thaw_account {
+		let origin = T::FreezeOrigin::successful_origin();
+		let who: T::AccountId = account("who", 0, SEED);
+		let who_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(who.clone());
+		FrozenAccounts::<T>::insert(&who, true);
+		let call = Call::<T>::thaw_account(who_lookup);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(!Balances::<T>::is_frozen(&who));
+	}
+
+	// Benchmark `force_burn` with the worst possible condition:
+	// * Both the free and the reserved balance are burnt.
+	force_burn {
+		let origin = T::FreezeOrigin::successful_origin();
+		let existential_deposit = T::ExistentialDeposit::get();
+		let who: T::AccountId = account("who", 0, SEED);
+		let who_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(who.clone());
+		let balance = existential_deposit.saturating_mul(ED_MULTIPLIER.into());
+		let _ = <Balances<T> as Currency<_>>::make_free_balance_be(&who, balance);
+		<Balances<T> as ReservableCurrency<_>>::reserve(&who, existential_deposit)?;
+		let amount = balance;
+		let call = Call::<T>::force_burn(who_lookup, amount, *b"burnbnch", true);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Balances::<T>::reserved_balance(&who), Zero::zero());
+		assert_eq!(Balances::<T>::free_balance(&who), Zero::zero());
+	}
 }
 
 #[cfg(test)]
@@ -193,4 +233,25 @@ mod tests {
 			assert_ok!(test_benchmark_force_transfer::<Test>());
 		});
 	}
+
+	#[test]
+	fn freeze_account() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_freeze_account::<Test>());
+		});
+	}
+
+	#[test]
+	fn thaw_account() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_thaw_account::<Test>());
+		});
+	}
+
+	#[test]
+	fn force_burn() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_force_burn::<Test>());
+		});
+	}
 }
@@ -62,6 +62,9 @@
 //! return an object of the `Imbalance` trait that can be managed within your runtime logic. (If an imbalance is
 //! simply dropped, it should automatically maintain any book-keeping such as total issuance.)
 //!
+//! - **Named Reserve:** A part of the reserved balance which is set aside under an identifier, so that
+//!   it can only be unreserved or slashed under the same identifier.
+//!
 //! - **Lock:** A freeze on a specified amount of an account's free balance until a specified block number. Multiple
 //! locks always operate over the same funds, so they "overlay" rather than "stack".
 //!
@@ -74,6 +77,8 @@
 //! fungible assets system.
 //! - [`ReservableCurrency`](../frame_support/traits/trait.ReservableCurrency.html):
 //! Functions for dealing with assets that can be reserved from an account.
+//! - [`NamedReservableCurrency`](../frame_support/traits/trait.NamedReservableCurrency.html):
+//! Functions for dealing with assets that can be reserved from an account under an identifier.
 //! - [`LockableCurrency`](../frame_support/traits/trait.LockableCurrency.html): Functions for
 //! dealing with accounts that allow liquidity restrictions.
 //! - [`Imbalance`](../frame_support/traits/trait.Imbalance.html): Functions for handling
@@ -88,6 +93,11 @@
 //!
 //! - `transfer` - Transfer some liquid free balance to another account.
 //! - `set_balance` - Set the balances of a given account. The origin of this call must be root.
+//! - `freeze_account` - Freeze an account, so that no funds can be moved out of it. The origin
+//!   of this call must be `FreezeOrigin`.
+//! - `thaw_account` - Thaw a frozen account. The origin of this call must be `FreezeOrigin`.
+//! - `force_burn` - Burn some balance of an account, with a reason code. The origin of this call must
+//!   be `FreezeOrigin`.
 //!
 //! ## Usage
 //!
@@ -157,7 +167,7 @@ mod benchmarking;
 pub mod weights;
 
 use sp_std::prelude::*;
-use sp_std::{cmp, result, mem, fmt::Debug, ops::BitOr, convert::Infallible};
+use sp_std::{cmp, result, mem, fmt::Debug, ops::BitOr};
 use codec::{Codec, Encode, Decode};
 use frame_support::{
 	StorageValue, Parameter, decl_event, decl_storage, decl_module, decl_error, ensure,
@@ -166,7 +176,9 @@ use frame_support::{
 		WithdrawReasons, LockIdentifier, LockableCurrency, ExistenceRequirement,
 		Imbalance, SignedImbalance, ReservableCurrency, Get, ExistenceRequirement::KeepAlive,
 		ExistenceRequirement::AllowDeath, IsDeadAccount, BalanceStatus as Status,
-	}
+		NamedReservableCurrency, EnsureOrigin, MigrateAccount,
+	},
+	weights::Weight,
 };
 use sp_runtime::{
 	RuntimeDebug, DispatchResult, DispatchError,
@@ -221,6 +233,15 @@ pub trait Config<I: Instance = DefaultInstance>: frame_system::Config {
 	/// The maximum number of locks that should exist on an account.
 	/// Not strictly enforced, but used for weight estimation.
 	type MaxLocks: Get<u32>;
+
+	/// The maximum number of named reserves that can exist on an account.
+	type MaxReserves: Get<u32>;
+
+	/// The id type for named reserves.
+	type ReserveIdentifier: Parameter + Member + Ord + Copy;
+
+	/// The origin which may freeze and thaw accounts, and burn their funds.
+	type FreezeOrigin: EnsureOrigin<Self::Origin>;
 }
 
 impl<T: Config<I>, I: Instance> Subtrait<I> for T {
@@ -255,6 +276,12 @@ decl_event!(
 		/// Final argument indicates the destination balance type.
 		/// \[from, to, balance, destination_status\]
 		ReserveRepatriated(AccountId, AccountId, Balance, Status),
+		/// An account was frozen. \[who\]
+		Frozen(AccountId),
+		/// An account was thawed. \[who\]
+		Thawed(AccountId),
+		/// Some balance of an account was burnt, for the given reason. \[who, amount, reason\]
+		Burned(AccountId, Balance, BurnReason),
 	}
 );
 
@@ -276,6 +303,12 @@ decl_error! {
 		ExistingVestingSchedule,
 		/// Beneficiary account must pre-exist
 		DeadAccount,
+		/// Number of named reserves exceed MaxReserves
+		TooManyReserves,
+		/// The account is frozen
+		AccountFrozen,
+		/// The account is not frozen
+		NotFrozen,
 	}
 }
 
@@ -322,6 +355,18 @@ pub struct BalanceLock<Balance> {
 	pub reasons: Reasons,
 }
 
+/// A code for the reason of a burn, recorded in the `Burned` event.
+pub type BurnReason = [u8; 8];
+
+/// A named reserve of a balance.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ReserveData<ReserveIdentifier, Balance> {
+	/// The identifier of this reserve. Only one reserve may be in existence for each identifier.
+	pub id: ReserveIdentifier,
+	/// The amount of the reserved balance which is named by `id`.
+	pub amount: Balance,
+}
+
 /// All balance information for an account.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
 pub struct AccountData<Balance> {
@@ -397,6 +442,17 @@ decl_storage! {
 		/// NOTE: Should only be accessed when setting, changing and freeing a lock.
 		pub Locks get(fn locks): map hasher(blake2_128_concat) T::AccountId => Vec<BalanceLock<T::Balance>>;
 
+		/// Named reserves on some account balances, sorted by identifier.
+		///
+		/// Their amounts are part of the reserved balance of the account.
+		pub Reserves get(fn reserves):
+			map hasher(blake2_128_concat) T::AccountId
+			=> Vec<ReserveData<T::ReserveIdentifier, T::Balance>>;
+
+		/// The accounts which are frozen: no funds can be transferred, withdrawn or repatriated out
+		/// of them.
+		pub FrozenAccounts get(fn is_frozen): map hasher(blake2_128_concat) T::AccountId => bool;
+
 		/// Storage version of the pallet.
 		///
 		/// This is set to v2.0.0 for new networks.
@@ -413,7 +469,8 @@ decl_storage! {
 				)
 			}
 			for &(ref who, free) in config.balances.iter() {
-				T::AccountStore::insert(who, AccountData { free, .. Default::default() });
+				let account = AccountData { free, .. Default::default() };
+				assert!(T::AccountStore::insert(who, account).is_ok());
 			}
 		});
 	}
@@ -501,25 +558,26 @@ decl_module! {
 			let new_free = if wipeout { Zero::zero() } else { new_free };
 			let new_reserved = if wipeout { Zero::zero() } else { new_reserved };
 
-			let (free, reserved) = Self::mutate_account(&who, |account| {
-				if new_free > account.free {
-					mem::drop(PositiveImbalance::<T, I>::new(new_free - account.free));
-				} else if new_free < account.free {
-					mem::drop(NegativeImbalance::<T, I>::new(account.free - new_free));
-				}
-
-				if new_reserved > account.reserved {
-					mem::drop(PositiveImbalance::<T, I>::new(new_reserved - account.reserved));
-				} else if new_reserved < account.reserved {
-					mem::drop(NegativeImbalance::<T, I>::new(account.reserved - new_reserved));
-				}
-
+			let (old_free, old_reserved) = Self::mutate_account(&who, |account| {
+				let old = (account.free, account.reserved);
 				account.free = new_free;
 				account.reserved = new_reserved;
+				old
+			})?;
 
-				(account.free, account.reserved)
-			});
-			Self::deposit_event(RawEvent::BalanceSet(who, free, reserved));
+			if new_free > old_free {
+				mem::drop(PositiveImbalance::<T, I>::new(new_free - old_free));
+			} else if new_free < old_free {
+				mem::drop(NegativeImbalance::<T, I>::new(old_free - new_free));
+			}
+
+			if new_reserved > old_reserved {
+				mem::drop(PositiveImbalance::<T, I>::new(new_reserved - old_reserved));
+			} else if new_reserved < old_reserved {
+				mem::drop(NegativeImbalance::<T, I>::new(old_reserved - new_reserved));
+			}
+
+			Self::deposit_event(RawEvent::BalanceSet(who, new_free, new_reserved));
 		}
 
 		/// Exactly as `transfer`, except the origin must be root and the source account may be
@@ -562,6 +620,87 @@ decl_module! {
 			let dest = T::Lookup::lookup(dest)?;
 			<Self as Currency<_>>::transfer(&transactor, &dest, value, KeepAlive)?;
 		}
+
+		/// Freeze an account.
+		///
+		/// No funds can be transferred, deposited, withdrawn or repatriated into or out of a frozen
+		/// account, and none of its free balance can be reserved. Its reserved balance can still be
+		/// unreserved, so that deposits held by other pallets can be returned to it. Its funds can
+		/// still be slashed, or burnt with `force_burn`.
+		///
+		/// The dispatch origin for this call must be `FreezeOrigin`.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - DB Weight: 1 Read and 1 Write to `FrozenAccounts`
+		/// # </weight>
+		#[weight = T::WeightInfo::freeze_account()]
+		fn freeze_account(origin, who: <T::Lookup as StaticLookup>::Source) {
+			T::FreezeOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(!Self::is_frozen(&who), Error::<T, I>::AccountFrozen);
+
+			FrozenAccounts::<T, I>::insert(&who, true);
+			Self::deposit_event(RawEvent::Frozen(who));
+		}
+
+		/// Thaw a frozen account.
+		///
+		/// The dispatch origin for this call must be `FreezeOrigin`.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - DB Weight: 1 Read and 1 Write to `FrozenAccounts`
+		/// # </weight>
+		#[weight = T::WeightInfo::thaw_account()]
+		fn thaw_account(origin, who: <T::Lookup as StaticLookup>::Source) {
+			T::FreezeOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(Self::is_frozen(&who), Error::<T, I>::NotFrozen);
+
+			FrozenAccounts::<T, I>::remove(&who);
+			Self::deposit_event(RawEvent::Thawed(who));
+		}
+
+		/// Burn up to `amount` of the balance of an account, for the given `reason`.
+		///
+		/// The free balance is burnt first. The reserved balance which is not held in a named
+		/// reserve is only burnt if `burn_reserved` is set. The total issuance is reduced by the
+		/// burnt amount, which is recorded in the `Burned` event. The account may be frozen.
+		///
+		/// The dispatch origin for this call must be `FreezeOrigin`.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - DB Weight: 1 Read and 1 Write to `who`, 1 Write to `TotalIssuance`
+		/// # </weight>
+		#[weight = T::WeightInfo::force_burn()]
+		fn force_burn(
+			origin,
+			who: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+			reason: BurnReason,
+			burn_reserved: bool,
+		) {
+			T::FreezeOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			let free_burn = Self::mutate_account(&who, |account| {
+				let free_burn = cmp::min(account.free, amount);
+				account.free -= free_burn;
+				free_burn
+			})?;
+			let mut imbalance = NegativeImbalance::new(free_burn);
+			let remaining = amount - free_burn;
+			if burn_reserved && !remaining.is_zero() {
+				let (reserved_imbalance, _) = Self::slash_reserved(&who, remaining);
+				imbalance.subsume(reserved_imbalance);
+			}
+			let burnt = imbalance.peek();
+			// Dropping the imbalance reduces the total issuance.
+			mem::drop(imbalance);
+			Self::deposit_event(RawEvent::Burned(who, burnt, reason));
+		}
 	}
 }
 
@@ -595,28 +734,28 @@ impl<T: Config<I>, I: Instance> Module<T, I> {
 		T::AccountStore::get(&who)
 	}
 
-	/// Places the `free` and `reserved` parts of `new` into `account`. Also does any steps needed
-	/// after mutating an account. This includes DustRemoval unbalancing, in the case than the `new`
-	/// account's total balance is non-zero but below ED.
+	/// Returns what should be stored for an account after it has been mutated to `new`, along
+	/// with any dust that it leaves behind, in the case that the `new` account's total balance is
+	/// non-zero but below ED.
 	///
-	/// Returns the final free balance, iff the account was previously of total balance zero, known
-	/// as its "endowment".
+	/// The dust must be handled with `handle_dust` once the mutation is known to have succeeded.
 	fn post_mutation(
-		who: &T::AccountId,
 		new: AccountData<T::Balance>,
-	) -> Option<AccountData<T::Balance>> {
+	) -> (Option<AccountData<T::Balance>>, Option<T::Balance>) {
 		let total = new.total();
 		if total < T::ExistentialDeposit::get() {
-			if !total.is_zero() {
-				T::DustRemoval::on_unbalanced(NegativeImbalance::new(total));
-				Self::deposit_event(RawEvent::DustLost(who.clone(), total));
-			}
-			None
+			(None, Some(total).filter(|dust| !dust.is_zero()))
 		} else {
-			Some(new)
+			(Some(new), None)
 		}
 	}
 
+	/// Hand the `dust` left behind by the account `who` over to `DustRemoval`.
+	fn handle_dust(who: &T::AccountId, dust: T::Balance) {
+		T::DustRemoval::on_unbalanced(NegativeImbalance::new(dust));
+		Self::deposit_event(RawEvent::DustLost(who.clone(), dust));
+	}
+
 	/// Mutate an account to some new value, or delete it entirely with `None`. Will enforce
 	/// `ExistentialDeposit` law, annulling the account as needed.
 	///
@@ -625,12 +764,14 @@ impl<T: Config<I>, I: Instance> Module<T, I> {
 	///
 	/// NOTE: LOW-LEVEL: This will not attempt to maintain total issuance. It is expected that
 	/// the caller will do this.
+	///
+	/// Fails with `ConsumerRemaining`, leaving the account untouched, if this would annul an
+	/// account on which other modules still depend.
 	pub fn mutate_account<R>(
 		who: &T::AccountId,
 		f: impl FnOnce(&mut AccountData<T::Balance>) -> R
-	) -> R {
-		Self::try_mutate_account(who, |a, _| -> Result<R, Infallible> { Ok(f(a)) })
-			.expect("Error is infallible; qed")
+	) -> Result<R, DispatchError> {
+		Self::try_mutate_account(who, |a, _| -> Result<R, DispatchError> { Ok(f(a)) })
 	}
 
 	/// Mutate an account to some new value, or delete it entirely with `None`. Will enforce
@@ -642,7 +783,10 @@ impl<T: Config<I>, I: Instance> Module<T, I> {
 	///
 	/// NOTE: LOW-LEVEL: This will not attempt to maintain total issuance. It is expected that
 	/// the caller will do this.
-	fn try_mutate_account<R, E>(
+	///
+	/// Fails with `ConsumerRemaining`, leaving the account untouched, if this would annul an
+	/// account on which other modules still depend.
+	fn try_mutate_account<R, E: From<DispatchError>>(
 		who: &T::AccountId,
 		f: impl FnOnce(&mut AccountData<T::Balance>, bool) -> Result<R, E>
 	) -> Result<R, E> {
@@ -651,17 +795,27 @@ impl<T: Config<I>, I: Instance> Module<T, I> {
 			let mut account = maybe_account.take().unwrap_or_default();
 			f(&mut account, is_new).map(move |result| {
 				let maybe_endowed = if is_new { Some(account.free) } else { None };
-				*maybe_account = Self::post_mutation(who, account);
-				(maybe_endowed, result)
+				let (new_account, maybe_dust) = Self::post_mutation(account);
+				*maybe_account = new_account;
+				(maybe_endowed, maybe_dust, result)
 			})
-		}).map(|(maybe_endowed, result)| {
+		}).map(|(maybe_endowed, maybe_dust, result)| {
 			if let Some(endowed) = maybe_endowed {
 				Self::deposit_event(RawEvent::Endowed(who.clone(), endowed));
 			}
+			if let Some(dust) = maybe_dust {
+				Self::handle_dust(who, dust);
+			}
 			result
 		})
 	}
 
+	/// Ensure that funds can be moved in or out of `who`.
+	fn ensure_not_frozen(who: &T::AccountId) -> DispatchResult {
+		ensure!(!Self::is_frozen(who), Error::<T, I>::AccountFrozen);
+		Ok(())
+	}
+
 	/// Update the account entry for `who`, given the locks.
 	fn update_locks(who: &T::AccountId, locks: &[BalanceLock<T::Balance>]) {
 		if locks.len() as u32 > T::MaxLocks::get() {
@@ -670,7 +824,7 @@ impl<T: Config<I>, I: Instance> Module<T, I> {
 				A runtime configuration adjustment may be needed."
 			);
 		}
-		Self::mutate_account(who, |b| {
+		let res = Self::mutate_account(who, |b| {
 			b.misc_frozen = Zero::zero();
 			b.fee_frozen = Zero::zero();
 			for l in locks.iter() {
@@ -682,6 +836,8 @@ impl<T: Config<I>, I: Instance> Module<T, I> {
 				}
 			}
 		});
+		// Locks don't change the total balance, so the account is never annulled here.
+		debug_assert!(res.is_ok());
 
 		let existed = Locks::<T, I>::contains_key(who);
 		if locks.is_empty() {
@@ -689,15 +845,144 @@ impl<T: Config<I>, I: Instance> Module<T, I> {
 			if existed {
 				// TODO: use Locks::<T, I>::hashed_key
 				// https://github.com/paritytech/substrate/issues/4969
-				system::Module::<T>::dec_ref(who);
+				system::Module::<T>::dec_consumers(who);
 			}
 		} else {
 			Locks::<T, I>::insert(who, locks);
 			if !existed {
-				system::Module::<T>::inc_ref(who);
+				if system::Module::<T>::inc_consumers(who).is_err() {
+					// No providers for the locks. This is impossible under normal circumstances
+					// since the funds that are under the lock will themselves be stored in the
+					// account and therefore will need a reference.
+					frame_support::debug::warn!(
+						"Warning: Attempt to introduce lock consumer reference, yet no providers. \
+						This is unexpected but should be safe."
+					);
+				}
+			}
+		}
+	}
+
+	/// Update the named reserves of `who`.
+	///
+	/// NOTE: LOW-LEVEL: This will not update the reserved balance of the account. It is expected
+	/// that the caller will do this.
+	fn update_reserves(
+		who: &T::AccountId,
+		reserves: &[ReserveData<T::ReserveIdentifier, T::Balance>],
+	) {
+		let existed = Reserves::<T, I>::contains_key(who);
+		if reserves.is_empty() {
+			Reserves::<T, I>::remove(who);
+			if existed {
+				system::Module::<T>::dec_consumers(who);
+			}
+		} else {
+			Reserves::<T, I>::insert(who, reserves);
+			if !existed {
+				if system::Module::<T>::inc_consumers(who).is_err() {
+					// As with locks, the reserved funds are held by the account itself, so it
+					// must already have a provider reference.
+					frame_support::debug::warn!(
+						"Warning: Attempt to introduce reserve consumer reference, yet no \
+						providers. This is unexpected but should be safe."
+					);
+				}
 			}
 		}
 	}
+
+	/// Reduce the named reserve at `index` of the `reserves` of `who` by `amount`, removing it
+	/// once it is empty.
+	fn reduce_reserve(
+		who: &T::AccountId,
+		reserves: &mut Vec<ReserveData<T::ReserveIdentifier, T::Balance>>,
+		index: usize,
+		amount: T::Balance,
+	) {
+		reserves[index].amount = reserves[index].amount.saturating_sub(amount);
+		if reserves[index].amount.is_zero() {
+			reserves.remove(index);
+		}
+		Self::update_reserves(who, reserves);
+	}
+
+	/// The reserved balance of `who` which is not held in any named reserve.
+	fn unnamed_reserved(who: &T::AccountId) -> T::Balance {
+		let named = Self::reserves(who).iter()
+			.fold(Zero::zero(), |acc: T::Balance, r| acc.saturating_add(r.amount));
+		Self::account(who).reserved.saturating_sub(named)
+	}
+
+	/// Unreserve up to `value` of the reserved balance of `who`, named or not, returning any
+	/// amount that was unable to be unreserved.
+	fn do_unreserve(who: &T::AccountId, value: T::Balance) -> T::Balance {
+		if value.is_zero() { return Zero::zero() }
+
+		let actual = match Self::mutate_account(who, |account| {
+			let actual = cmp::min(account.reserved, value);
+			account.reserved -= actual;
+			// defensive only: this can never fail since total issuance which is at least free+reserved
+			// fits into the same data type.
+			account.free = account.free.saturating_add(actual);
+			actual
+		}) {
+			Ok(actual) => actual,
+			// defensive only: unreserving never changes the total balance.
+			Err(_) => return value,
+		};
+
+		Self::deposit_event(RawEvent::Unreserved(who.clone(), actual.clone()));
+		value - actual
+	}
+
+	/// Slash up to `value` of the reserved balance of `who`, named or not, returning the negative
+	/// imbalance created and any amount that was unable to be slashed.
+	fn do_slash_reserved(
+		who: &T::AccountId,
+		value: T::Balance,
+	) -> (NegativeImbalance<T, I>, T::Balance) {
+		if value.is_zero() { return (NegativeImbalance::zero(), Zero::zero()) }
+
+		match Self::mutate_account(who, |account| {
+			// underflow should never happen, but it if does, there's nothing to be done here.
+			let actual = cmp::min(account.reserved, value);
+			account.reserved -= actual;
+			actual
+		}) {
+			Ok(actual) => (NegativeImbalance::new(actual), value - actual),
+			Err(_) => (NegativeImbalance::zero(), value),
+		}
+	}
+
+	/// Move up to `value` of the reserved balance of `slashed`, named or not, into the balance of
+	/// `beneficiary`, according to `status`. `slashed` and `beneficiary` must differ.
+	fn do_repatriate_reserved(
+		slashed: &T::AccountId,
+		beneficiary: &T::AccountId,
+		value: T::Balance,
+		status: Status,
+	) -> Result<T::Balance, DispatchError> {
+		Self::ensure_not_frozen(slashed)?;
+		Self::ensure_not_frozen(beneficiary)?;
+		if value.is_zero() { return Ok(Zero::zero()) }
+
+		let actual = Self::try_mutate_account(beneficiary, |to_account, is_new|-> Result<Self::Balance, DispatchError> {
+			ensure!(!is_new, Error::<T, I>::DeadAccount);
+			Self::try_mutate_account(slashed, |from_account, _| -> Result<Self::Balance, DispatchError> {
+				let actual = cmp::min(from_account.reserved, value);
+				match status {
+					Status::Free => to_account.free = to_account.free.checked_add(&actual).ok_or(Error::<T, I>::Overflow)?,
+					Status::Reserved => to_account.reserved = to_account.reserved.checked_add(&actual).ok_or(Error::<T, I>::Overflow)?,
+				}
+				from_account.reserved -= actual;
+				Ok(actual)
+			})
+		})?;
+
+		Self::deposit_event(RawEvent::ReserveRepatriated(slashed.clone(), beneficiary.clone(), actual, status));
+		Ok(value - actual)
+	}
 }
 
 // wrapping these imbalances in a private module is necessary to ensure absolute privacy
@@ -939,6 +1224,8 @@ impl<T: Config<I>, I: Instance> Currency<T::AccountId> for Module<T, I> where
 		existence_requirement: ExistenceRequirement,
 	) -> DispatchResult {
 		if value.is_zero() || transactor == dest { return Ok(()) }
+		Self::ensure_not_frozen(transactor)?;
+		Self::ensure_not_frozen(dest)?;
 
 		Self::try_mutate_account(dest, |to_account, _| -> DispatchResult {
 			Self::try_mutate_account(transactor, |from_account, _| -> DispatchResult {
@@ -960,7 +1247,7 @@ impl<T: Config<I>, I: Instance> Currency<T::AccountId> for Module<T, I> where
 				)?;
 
 				let allow_death = existence_requirement == ExistenceRequirement::AllowDeath;
-				let allow_death = allow_death && system::Module::<T>::allow_death(transactor);
+				let allow_death = allow_death && system::Module::<T>::can_dec_provider(transactor);
 				ensure!(allow_death || from_account.free >= ed, Error::<T, I>::KeepAlive);
 
 				Ok(())
@@ -981,26 +1268,30 @@ impl<T: Config<I>, I: Instance> Currency<T::AccountId> for Module<T, I> where
 	/// NOTE: `slash()` prefers free balance, but assumes that reserve balance can be drawn
 	/// from in extreme circumstances. `can_slash()` should be used prior to `slash()` to avoid having
 	/// to draw from reserved funds, however we err on the side of punishment if things are inconsistent
-	/// or `can_slash` wasn't used appropriately.
+	/// or `can_slash` wasn't used appropriately. Named reserves are never drawn from.
 	fn slash(
 		who: &T::AccountId,
 		value: Self::Balance
 	) -> (Self::NegativeImbalance, Self::Balance) {
 		if value.is_zero() { return (NegativeImbalance::zero(), Zero::zero()) }
 
-		Self::mutate_account(who, |account| {
+		let unnamed_reserved = Self::unnamed_reserved(who);
+		match Self::mutate_account(who, |account| {
 			let free_slash = cmp::min(account.free, value);
 			account.free -= free_slash;
 
 			let remaining_slash = value - free_slash;
 			if !remaining_slash.is_zero() {
-				let reserved_slash = cmp::min(account.reserved, remaining_slash);
+				let reserved_slash = cmp::min(unnamed_reserved, remaining_slash);
 				account.reserved -= reserved_slash;
-				(NegativeImbalance::new(free_slash + reserved_slash), remaining_slash - reserved_slash)
+				free_slash + reserved_slash
 			} else {
-				(NegativeImbalance::new(value), Zero::zero())
+				value
 			}
-		})
+		}) {
+			Ok(actual) => (NegativeImbalance::new(actual), value - actual),
+			Err(_) => (NegativeImbalance::zero(), value),
+		}
 	}
 
 	/// Deposit some `value` into the free balance of an existing target account `who`.
@@ -1011,6 +1302,7 @@ impl<T: Config<I>, I: Instance> Currency<T::AccountId> for Module<T, I> where
 		value: Self::Balance
 	) -> Result<Self::PositiveImbalance, DispatchError> {
 		if value.is_zero() { return Ok(PositiveImbalance::zero()) }
+		Self::ensure_not_frozen(who)?;
 
 		Self::try_mutate_account(who, |account, is_new| -> Result<Self::PositiveImbalance, DispatchError> {
 			ensure!(!is_new, Error::<T, I>::DeadAccount);
@@ -1024,24 +1316,25 @@ impl<T: Config<I>, I: Instance> Currency<T::AccountId> for Module<T, I> where
 	/// This function is a no-op if:
 	/// - the `value` to be deposited is zero; or
 	/// - if the `value` to be deposited is less than the ED and the account does not yet exist; or
-	/// - `value` is so large it would cause the balance of `who` to overflow.
+	/// - `value` is so large it would cause the balance of `who` to overflow; or
+	/// - `who` is frozen.
 	fn deposit_creating(
 		who: &T::AccountId,
 		value: Self::Balance,
 	) -> Self::PositiveImbalance {
-		if value.is_zero() { return Self::PositiveImbalance::zero() }
+		if value.is_zero() || Self::is_frozen(who) { return Self::PositiveImbalance::zero() }
 
-		Self::try_mutate_account(who, |account, is_new| -> Result<Self::PositiveImbalance, Self::PositiveImbalance> {
+		Self::try_mutate_account(who, |account, is_new| -> DispatchResult {
 			// bail if not yet created and this operation wouldn't be enough to create it.
 			let ed = T::ExistentialDeposit::get();
-			ensure!(value >= ed || !is_new, Self::PositiveImbalance::zero());
+			ensure!(value >= ed || !is_new, Error::<T, I>::ExistentialDeposit);
 
 			// defensive only: overflow should never happen, however in case it does, then this
 			// operation is a no-op.
-			account.free = account.free.checked_add(&value).ok_or_else(|| Self::PositiveImbalance::zero())?;
+			account.free = account.free.checked_add(&value).ok_or(Error::<T, I>::Overflow)?;
 
-			Ok(PositiveImbalance::new(value))
-		}).unwrap_or_else(|x| x)
+			Ok(())
+		}).map_or_else(|_| Self::PositiveImbalance::zero(), |_| PositiveImbalance::new(value))
 	}
 
 	/// Withdraw some free balance from an account, respecting existence requirements.
@@ -1054,10 +1347,9 @@ impl<T: Config<I>, I: Instance> Currency<T::AccountId> for Module<T, I> where
 		liveness: ExistenceRequirement,
 	) -> result::Result<Self::NegativeImbalance, DispatchError> {
 		if value.is_zero() { return Ok(NegativeImbalance::zero()); }
+		Self::ensure_not_frozen(who)?;
 
-		Self::try_mutate_account(who, |account, _|
-			-> Result<Self::NegativeImbalance, DispatchError>
-		{
+		Self::try_mutate_account(who, |account, _| -> DispatchResult {
 			let new_free_account = account.free.checked_sub(&value)
 				.ok_or(Error::<T, I>::InsufficientBalance)?;
 
@@ -1071,17 +1363,15 @@ impl<T: Config<I>, I: Instance> Currency<T::AccountId> for Module<T, I> where
 
 			account.free = new_free_account;
 
-			Ok(NegativeImbalance::new(value))
-		})
+			Ok(())
+		}).map(|_| NegativeImbalance::new(value))
 	}
 
 	/// Force the new free balance of a target account `who` to some new value `balance`.
 	fn make_free_balance_be(who: &T::AccountId, value: Self::Balance)
 		-> SignedImbalance<Self::Balance, Self::PositiveImbalance>
 	{
-		Self::try_mutate_account(who, |account, is_new|
-			-> Result<SignedImbalance<Self::Balance, Self::PositiveImbalance>, ()>
-		{
+		Self::try_mutate_account(who, |account, is_new| -> Result<Self::Balance, DispatchError> {
 			let ed = T::ExistentialDeposit::get();
 			// If we're attempting to set an existing account to less than ED, then
 			// bypass the entire operation. It's a no-op if you follow it through, but
@@ -1090,15 +1380,18 @@ impl<T: Config<I>, I: Instance> Currency<T::AccountId> for Module<T, I> where
 			// equal and opposite cause (returned as an Imbalance), then in the
 			// instance that there's no other accounts on the system at all, we might
 			// underflow the issuance and our arithmetic will be off.
-			ensure!(value.saturating_add(account.reserved) >= ed || !is_new, ());
+			ensure!(
+				value.saturating_add(account.reserved) >= ed || !is_new,
+				Error::<T, I>::ExistentialDeposit,
+			);
 
-			let imbalance = if account.free <= value {
-				SignedImbalance::Positive(PositiveImbalance::new(value - account.free))
-			} else {
-				SignedImbalance::Negative(NegativeImbalance::new(account.free - value))
-			};
+			let old_free = account.free;
 			account.free = value;
-			Ok(imbalance)
+			Ok(old_free)
+		}).map(|old_free| if old_free <= value {
+			SignedImbalance::Positive(PositiveImbalance::new(value - old_free))
+		} else {
+			SignedImbalance::Negative(NegativeImbalance::new(old_free - value))
 		}).unwrap_or_else(|_| SignedImbalance::Positive(Self::PositiveImbalance::zero()))
 	}
 }
@@ -1111,6 +1404,7 @@ impl<T: Config<I>, I: Instance> ReservableCurrency<T::AccountId> for Module<T, I
 	/// Always `true` if value to be reserved is zero.
 	fn can_reserve(who: &T::AccountId, value: Self::Balance) -> bool {
 		if value.is_zero() { return true }
+		if Self::is_frozen(who) { return false }
 		Self::account(who).free
 			.checked_sub(&value)
 			.map_or(false, |new_balance|
@@ -1127,6 +1421,7 @@ impl<T: Config<I>, I: Instance> ReservableCurrency<T::AccountId> for Module<T, I
 	/// Is a no-op if value to be reserved is zero.
 	fn reserve(who: &T::AccountId, value: Self::Balance) -> DispatchResult {
 		if value.is_zero() { return Ok(()) }
+		Self::ensure_not_frozen(who)?;
 
 		Self::try_mutate_account(who, |account, _| -> DispatchResult {
 			account.free = account.free.checked_sub(&value).ok_or(Error::<T, I>::InsufficientBalance)?;
@@ -1140,43 +1435,33 @@ impl<T: Config<I>, I: Instance> ReservableCurrency<T::AccountId> for Module<T, I
 
 	/// Unreserve some funds, returning any amount that was unable to be unreserved.
 	///
+	/// Funds held in a named reserve are not unreserved.
+	///
 	/// Is a no-op if the value to be unreserved is zero.
 	fn unreserve(who: &T::AccountId, value: Self::Balance) -> Self::Balance {
-		if value.is_zero() { return Zero::zero() }
-
-		let actual = Self::mutate_account(who, |account| {
-			let actual = cmp::min(account.reserved, value);
-			account.reserved -= actual;
-			// defensive only: this can never fail since total issuance which is at least free+reserved
-			// fits into the same data type.
-			account.free = account.free.saturating_add(actual);
-			actual
-		});
-
-		Self::deposit_event(RawEvent::Unreserved(who.clone(), actual.clone()));
-		value - actual
+		let unnamed = cmp::min(value, Self::unnamed_reserved(who));
+		value - unnamed + Self::do_unreserve(who, unnamed)
 	}
 
 	/// Slash from reserved balance, returning the negative imbalance created,
 	/// and any amount that was unable to be slashed.
 	///
+	/// Funds held in a named reserve are not slashed.
+	///
 	/// Is a no-op if the value to be slashed is zero.
 	fn slash_reserved(
 		who: &T::AccountId,
 		value: Self::Balance
 	) -> (Self::NegativeImbalance, Self::Balance) {
-		if value.is_zero() { return (NegativeImbalance::zero(), Zero::zero()) }
-
-		Self::mutate_account(who, |account| {
-			// underflow should never happen, but it if does, there's nothing to be done here.
-			let actual = cmp::min(account.reserved, value);
-			account.reserved -= actual;
-			(NegativeImbalance::new(actual), value - actual)
-		})
+		let unnamed = cmp::min(value, Self::unnamed_reserved(who));
+		let (imbalance, remaining) = Self::do_slash_reserved(who, unnamed);
+		(imbalance, value - unnamed + remaining)
 	}
 
 	/// Move the reserved balance of one account into the balance of another, according to `status`.
 	///
+	/// Funds held in a named reserve are not moved.
+	///
 	/// Is a no-op if:
 	/// - the value to be moved is zero; or
 	/// - the `slashed` id equal to `beneficiary` and the `status` is `Reserved`.
@@ -1191,24 +1476,197 @@ impl<T: Config<I>, I: Instance> ReservableCurrency<T::AccountId> for Module<T, I
 		if slashed == beneficiary {
 			return match status {
 				Status::Free => Ok(Self::unreserve(slashed, value)),
-				Status::Reserved => Ok(value.saturating_sub(Self::reserved_balance(slashed))),
+				Status::Reserved => Ok(value.saturating_sub(Self::unnamed_reserved(slashed))),
 			};
 		}
+		let unnamed = cmp::min(value, Self::unnamed_reserved(slashed));
+		let remaining = Self::do_repatriate_reserved(slashed, beneficiary, unnamed, status)?;
+		Ok(value - unnamed + remaining)
+	}
+}
 
-		let actual = Self::try_mutate_account(beneficiary, |to_account, is_new|-> Result<Self::Balance, DispatchError> {
-			ensure!(!is_new, Error::<T, I>::DeadAccount);
-			Self::try_mutate_account(slashed, |from_account, _| -> Result<Self::Balance, DispatchError> {
-				let actual = cmp::min(from_account.reserved, value);
-				match status {
-					Status::Free => to_account.free = to_account.free.checked_add(&actual).ok_or(Error::<T, I>::Overflow)?,
-					Status::Reserved => to_account.reserved = to_account.reserved.checked_add(&actual).ok_or(Error::<T, I>::Overflow)?,
+impl<T: Config<I>, I: Instance> NamedReservableCurrency<T::AccountId> for Module<T, I>  where
+	T::Balance: MaybeSerializeDeserialize + Debug
+{
+	type ReserveIdentifier = T::ReserveIdentifier;
+
+	fn reserved_balance_named(id: &Self::ReserveIdentifier, who: &T::AccountId) -> Self::Balance {
+		let reserves = Self::reserves(who);
+		reserves.binary_search_by_key(id, |data| data.id)
+			.map(|index| reserves[index].amount)
+			.unwrap_or_default()
+	}
+
+	/// Move `value` from the free balance from `who` to the reserve named `id`.
+	///
+	/// Is a no-op if value to be reserved is zero.
+	fn reserve_named(
+		id: &Self::ReserveIdentifier,
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> DispatchResult {
+		if value.is_zero() { return Ok(()) }
+
+		let mut reserves = Self::reserves(who);
+		match reserves.binary_search_by_key(id, |data| data.id) {
+			Ok(index) => {
+				// this add can't overflow but just to be defensive.
+				reserves[index].amount = reserves[index].amount.saturating_add(value);
+			},
+			Err(index) => {
+				ensure!(
+					(reserves.len() as u32) < T::MaxReserves::get(),
+					Error::<T, I>::TooManyReserves,
+				);
+				reserves.insert(index, ReserveData { id: *id, amount: value });
+			},
+		}
+		<Self as ReservableCurrency<_>>::reserve(who, value)?;
+		Self::update_reserves(who, &reserves);
+		Ok(())
+	}
+
+	/// Unreserve some funds of the reserve named `id`, returning any amount that was unable to be
+	/// unreserved.
+	///
+	/// Is a no-op if the value to be unreserved is zero.
+	fn unreserve_named(
+		id: &Self::ReserveIdentifier,
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> Self::Balance {
+		if value.is_zero() { return Zero::zero() }
+
+		let mut reserves = Self::reserves(who);
+		match reserves.binary_search_by_key(id, |data| data.id) {
+			Ok(index) => {
+				let to_change = cmp::min(reserves[index].amount, value);
+				let remain = Self::do_unreserve(who, to_change);
+				// remain should always be zero but just to be defensive here.
+				let actual = to_change.saturating_sub(remain);
+				Self::reduce_reserve(who, &mut reserves, index, actual);
+				value - actual
+			},
+			Err(_) => value,
+		}
+	}
+
+	/// Slash from the reserve named `id`, returning the negative imbalance created,
+	/// and any amount that was unable to be slashed.
+	///
+	/// Is a no-op if the value to be slashed is zero.
+	fn slash_reserved_named(
+		id: &Self::ReserveIdentifier,
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> (Self::NegativeImbalance, Self::Balance) {
+		if value.is_zero() { return (NegativeImbalance::zero(), Zero::zero()) }
+
+		let mut reserves = Self::reserves(who);
+		match reserves.binary_search_by_key(id, |data| data.id) {
+			Ok(index) => {
+				let to_change = cmp::min(reserves[index].amount, value);
+				let (imbalance, remain) = Self::do_slash_reserved(who, to_change);
+				// remain should always be zero but just to be defensive here.
+				let actual = to_change.saturating_sub(remain);
+				Self::reduce_reserve(who, &mut reserves, index, actual);
+				(imbalance, value - actual)
+			},
+			Err(_) => (NegativeImbalance::zero(), value),
+		}
+	}
+
+	/// Move the reserve named `id` of one account into the balance of another, according to
+	/// `status`. If `status` is `Reserved`, the funds are reserved under the same `id`.
+	///
+	/// Is a no-op if:
+	/// - the value to be moved is zero; or
+	/// - the `slashed` id equal to `beneficiary` and the `status` is `Reserved`.
+	fn repatriate_reserved_named(
+		id: &Self::ReserveIdentifier,
+		slashed: &T::AccountId,
+		beneficiary: &T::AccountId,
+		value: Self::Balance,
+		status: Status,
+	) -> Result<Self::Balance, DispatchError> {
+		if value.is_zero() { return Ok(Zero::zero()) }
+
+		if slashed == beneficiary {
+			return match status {
+				Status::Free => Ok(Self::unreserve_named(id, slashed, value)),
+				Status::Reserved =>
+					Ok(value.saturating_sub(Self::reserved_balance_named(id, slashed))),
+			};
+		}
+
+		let mut reserves = Self::reserves(slashed);
+		let index = match reserves.binary_search_by_key(id, |data| data.id) {
+			Ok(index) => index,
+			Err(_) => return Ok(value),
+		};
+		let to_change = cmp::min(reserves[index].amount, value);
+
+		let actual = match status {
+			Status::Free => {
+				let remain = Self::do_repatriate_reserved(slashed, beneficiary, to_change, status)?;
+				to_change.saturating_sub(remain)
+			},
+			Status::Reserved => {
+				let mut beneficiary_reserves = Self::reserves(beneficiary);
+				let b_index = beneficiary_reserves.binary_search_by_key(id, |data| data.id);
+				if let Err(b_index) = b_index {
+					ensure!(
+						(beneficiary_reserves.len() as u32) < T::MaxReserves::get(),
+						Error::<T, I>::TooManyReserves,
+					);
+					let reserve = ReserveData { id: *id, amount: Zero::zero() };
+					beneficiary_reserves.insert(b_index, reserve);
 				}
-				from_account.reserved -= actual;
-				Ok(actual)
-			})
-		})?;
+				let b_index = b_index.unwrap_or_else(|b_index| b_index);
+
+				let remain = Self::do_repatriate_reserved(slashed, beneficiary, to_change, status)?;
+				let actual = to_change.saturating_sub(remain);
+				// this add can't overflow but just to be defensive.
+				beneficiary_reserves[b_index].amount =
+					beneficiary_reserves[b_index].amount.saturating_add(actual);
+				if !beneficiary_reserves[b_index].amount.is_zero() {
+					Self::update_reserves(beneficiary, &beneficiary_reserves);
+				}
+				actual
+			},
+		};
 
-		Self::deposit_event(RawEvent::ReserveRepatriated(slashed.clone(), beneficiary.clone(), actual, status));
+		Self::reduce_reserve(slashed, &mut reserves, index, actual);
+		Ok(value - actual)
+	}
+
+	/// Move up to `value` of the reserved balance of `who` held outside of any named reserve into
+	/// the reserve named `id`, returning any amount that was unable to be moved.
+	///
+	/// Is a no-op if the value to be moved is zero.
+	fn name_reserved(
+		id: &Self::ReserveIdentifier,
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> Result<Self::Balance, DispatchError> {
+		let actual = cmp::min(Self::unnamed_reserved(who), value);
+		if actual.is_zero() { return Ok(value) }
+
+		let mut reserves = Self::reserves(who);
+		match reserves.binary_search_by_key(id, |data| data.id) {
+			Ok(index) => {
+				// this add can't overflow but just to be defensive.
+				reserves[index].amount = reserves[index].amount.saturating_add(actual);
+			},
+			Err(index) => {
+				ensure!(
+					(reserves.len() as u32) < T::MaxReserves::get(),
+					Error::<T, I>::TooManyReserves,
+				);
+				reserves.insert(index, ReserveData { id: *id, amount: actual });
+			},
+		}
+		Self::update_reserves(who, &reserves);
 		Ok(value - actual)
 	}
 }
@@ -1296,6 +1754,50 @@ where
 	}
 }
 
+/// Moves the locks still held on an account, together with the funds behind them, onto another
+/// account. Locks keep their identifier and are merged with any lock of the same identifier on the
+/// destination.
+///
+/// Reserved funds are not moved: they back deposits recorded by other pallets, which move them
+/// along with their records when they migrate those. Any other reserve stays on the account.
+///
+/// This should come after every other pallet in a `MigrateAccount` tuple, so that those pallets
+/// move the funds they account for themselves and only what is left over is moved here.
+impl<T: Config<I>, I: Instance> MigrateAccount<T::AccountId> for Module<T, I> where
+	T::Balance: MaybeSerializeDeserialize + Debug
+{
+	fn migration_weight(_from: &T::AccountId) -> Weight {
+		T::DbWeight::get().reads_writes(5, 4)
+	}
+
+	fn migrate_account(from: &T::AccountId, to: &T::AccountId) -> Weight {
+		let weight = Self::migration_weight(from);
+		if from == to { return weight }
+
+		let locks = Self::locks(from);
+		if let Some(locked) = locks.iter().map(|l| l.amount).max() {
+			let value = locked.min(Self::free_balance(from));
+			Self::update_locks(from, &[]);
+			if <Self as Currency<_>>::transfer(from, to, value, AllowDeath).is_err() {
+				Self::update_locks(from, &locks[..]);
+			} else {
+				let mut to_locks = Self::locks(to);
+				for lock in locks {
+					match to_locks.iter_mut().find(|l| l.id == lock.id) {
+						Some(l) => {
+							l.amount = l.amount.max(lock.amount);
+							l.reasons = l.reasons | lock.reasons;
+						},
+						None => to_locks.push(lock),
+					}
+				}
+				Self::update_locks(to, &to_locks[..]);
+			}
+		}
+		weight
+	}
+}
+
 impl<T: Config<I>, I: Instance> IsDeadAccount<T::AccountId> for Module<T, I> where
 	T::Balance: MaybeSerializeDeserialize + Debug
 {
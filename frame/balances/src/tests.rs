@@ -43,7 +43,8 @@ macro_rules! decl_tests {
 			assert_noop, assert_ok, assert_err,
 			traits::{
 				LockableCurrency, LockIdentifier, WithdrawReasons,
-				Currency, ReservableCurrency, ExistenceRequirement::AllowDeath, StoredMap
+				Currency, ReservableCurrency, NamedReservableCurrency,
+				ExistenceRequirement::AllowDeath, StoredMap, MigrateAccount,
 			}
 		};
 		use pallet_transaction_payment::{ChargeTransactionPayment, Multiplier};
@@ -417,7 +418,7 @@ macro_rules! decl_tests {
 		fn refunding_balance_should_work() {
 			<$ext_builder>::default().build().execute_with(|| {
 				let _ = Balances::deposit_creating(&1, 42);
-				Balances::mutate_account(&1, |a| a.reserved = 69);
+				assert_ok!(Balances::mutate_account(&1, |a| a.reserved = 69));
 				Balances::unreserve(&1, 69);
 				assert_eq!(Balances::free_balance(1), 111);
 				assert_eq!(Balances::reserved_balance(1), 0);
@@ -748,44 +749,386 @@ macro_rules! decl_tests {
 					assert_eq!(
 						events(),
 						[
+							Event::system(system::RawEvent::KilledAccount(1)),
 							Event::balances(RawEvent::DustLost(1, 99)),
-							Event::system(system::RawEvent::KilledAccount(1))
 						]
 					);
 				});
 		}
 
 		#[test]
-		fn emit_events_with_no_existential_deposit_suicide() {
-			<$ext_builder>::default()
-				.existential_deposit(0)
-				.build()
-				.execute_with(|| {
-					assert_ok!(Balances::set_balance(RawOrigin::Root.into(), 1, 100, 0));
+		fn account_with_consumers_cannot_be_reaped_by_transfer() {
+			<$ext_builder>::default().existential_deposit(1).monied(true).build().execute_with(|| {
+				assert_ok!(System::inc_consumers(&1));
+				assert_noop!(
+					<Balances as Currency<_>>::transfer(&1, &2, 10, AllowDeath),
+					Error::<$test, _>::KeepAlive
+				);
 
-					assert_eq!(
-						events(),
-						[
-							Event::system(system::RawEvent::NewAccount(1)),
-							Event::balances(RawEvent::Endowed(1, 100)),
-							Event::balances(RawEvent::BalanceSet(1, 100, 0)),
-						]
-					);
+				System::dec_consumers(&1);
+				assert_ok!(<Balances as Currency<_>>::transfer(&1, &2, 10, AllowDeath));
+				assert!(!System::account_exists(&1));
+			});
+		}
+
+		#[test]
+		fn account_with_consumers_cannot_be_reaped_by_slash() {
+			<$ext_builder>::default().existential_deposit(1).monied(true).build().execute_with(|| {
+				assert_ok!(System::inc_consumers(&1));
+				let issuance = Balances::total_issuance();
 
-					let _ = Balances::slash(&1, 100);
+				let (imbalance, remaining) = Balances::slash(&1, 10);
+				assert_eq!((imbalance.peek(), remaining), (0, 10));
+				assert_eq!(Balances::free_balance(1), 10);
+				assert_eq!(Balances::total_issuance(), issuance);
+				assert_noop!(
+					Balances::set_balance(RawOrigin::Root.into(), 1, 0, 0),
+					system::Error::<$test>::ConsumerRemaining,
+				);
 
-					// no events
-					assert_eq!(events(), []);
+				System::dec_consumers(&1);
+				let (imbalance, _) = Balances::slash(&1, 10);
+				assert_eq!(imbalance.peek(), 10);
+				assert!(!System::account_exists(&1));
+			});
+		}
 
-					assert_ok!(System::suicide(Origin::signed(1)));
+		#[test]
+		fn named_reserve_should_work() {
+			<$ext_builder>::default().build().execute_with(|| {
+				let _ = Balances::deposit_creating(&1, 100);
+				let id_1 = [1u8; 8];
+				let id_2 = [2u8; 8];
+
+				assert_ok!(Balances::reserve_named(&id_1, &1, 20));
+				assert_ok!(Balances::reserve_named(&id_2, &1, 30));
+				assert_ok!(Balances::reserve_named(&id_1, &1, 5));
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 25);
+				assert_eq!(Balances::reserved_balance_named(&id_2, &1), 30);
+				assert_eq!(Balances::reserved_balance(&1), 55);
+				assert_eq!(Balances::free_balance(&1), 45);
+				assert_noop!(
+					Balances::reserve_named(&id_1, &1, 46),
+					Error::<$test, _>::InsufficientBalance,
+				);
 
-					assert_eq!(
-						events(),
-						[
-							Event::system(system::RawEvent::KilledAccount(1))
-						]
-					);
-				});
+				// the named reserves can not unreserve each other's funds.
+				assert_eq!(Balances::unreserve_named(&id_1, &1, 40), 15);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 0);
+				assert_eq!(Balances::reserved_balance_named(&id_2, &1), 30);
+				assert_eq!(Balances::reserved_balance(&1), 30);
+				assert_eq!(Balances::reserves(&1).len(), 1);
+
+				// an anonymous reserve is not part of any named reserve.
+				assert_ok!(Balances::reserve(&1, 10));
+				assert_eq!(Balances::unreserve_all_named(&id_2, &1), 30);
+				assert_eq!(Balances::reserved_balance(&1), 10);
+				assert!(!Reserves::<$test, _>::contains_key(&1));
+			});
+		}
+
+		#[test]
+		fn anonymous_reserve_functions_leave_named_reserves_alone() {
+			<$ext_builder>::default().build().execute_with(|| {
+				let _ = Balances::deposit_creating(&1, 100);
+				let _ = Balances::deposit_creating(&2, 100);
+				let id = [1u8; 8];
+				assert_ok!(Balances::reserve_named(&id, &1, 20));
+				assert_ok!(Balances::reserve(&1, 10));
+
+				assert_eq!(Balances::unreserve(&1, 5), 0);
+				assert_eq!(Balances::reserved_balance(&1), 25);
+
+				let (imbalance, remaining) = Balances::slash_reserved(&1, 10);
+				assert_eq!(imbalance.peek(), 5);
+				assert_eq!(remaining, 5);
+				drop(imbalance);
+
+				assert_eq!(Balances::repatriate_reserved(&1, &2, 10, Status::Free), Ok(10));
+				assert_eq!(Balances::unreserve(&1, 10), 10);
+				assert_eq!(Balances::reserved_balance(&1), 20);
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 20);
+				assert_eq!(Balances::free_balance(&2), 100);
+
+				// a slash takes the free balance, then the unnamed reserve only.
+				assert_ok!(Balances::reserve(&1, 10));
+				let (imbalance, remaining) = Balances::slash(&1, 100);
+				assert_eq!(imbalance.peek(), 75);
+				assert_eq!(remaining, 25);
+				assert_eq!(Balances::reserved_balance(&1), 20);
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 20);
+			});
+		}
+
+		#[test]
+		fn name_reserved_should_work() {
+			<$ext_builder>::default().build().execute_with(|| {
+				let _ = Balances::deposit_creating(&1, 100);
+				let id = [1u8; 8];
+				assert_ok!(Balances::reserve(&1, 30));
+				assert_ok!(Balances::reserve_named(&id, &1, 10));
+
+				assert_eq!(Balances::name_reserved(&id, &1, 20), Ok(0));
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 30);
+				// only the unnamed reserve can be named.
+				assert_eq!(Balances::name_reserved(&id, &1, 20), Ok(10));
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 40);
+				assert_eq!(Balances::reserved_balance(&1), 40);
+				assert_eq!(Balances::free_balance(&1), 60);
+			});
+		}
+
+		#[test]
+		fn reserve_named_respects_max_reserves() {
+			<$ext_builder>::default().build().execute_with(|| {
+				let _ = Balances::deposit_creating(&1, 100);
+				let max_reserves = MaxReserves::get() as u8;
+				for i in 0..max_reserves {
+					assert_ok!(Balances::reserve_named(&[i; 8], &1, 1));
+				}
+				assert_noop!(
+					Balances::reserve_named(&[max_reserves; 8], &1, 1),
+					Error::<$test, _>::TooManyReserves,
+				);
+				// existing reserves can still grow.
+				assert_ok!(Balances::reserve_named(&[0; 8], &1, 1));
+				assert_eq!(Balances::reserved_balance_named(&[0; 8], &1), 2);
+			});
+		}
+
+		#[test]
+		fn slash_reserved_named_should_work() {
+			<$ext_builder>::default().build().execute_with(|| {
+				let _ = Balances::deposit_creating(&1, 100);
+				let id_1 = [1u8; 8];
+				let id_2 = [2u8; 8];
+				assert_ok!(Balances::reserve_named(&id_1, &1, 20));
+				assert_ok!(Balances::reserve_named(&id_2, &1, 30));
+
+				let (imbalance, remaining) = Balances::slash_reserved_named(&id_1, &1, 25);
+				assert_eq!(imbalance.peek(), 20);
+				assert_eq!(remaining, 5);
+				drop(imbalance);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 0);
+				assert_eq!(Balances::reserved_balance(&1), 30);
+
+				assert_eq!(Balances::slash_all_reserved_named(&id_2, &1).peek(), 30);
+				assert_eq!(Balances::reserved_balance(&1), 0);
+				assert_eq!(Balances::total_balance(&1), 50);
+			});
+		}
+
+		#[test]
+		fn repatriate_reserved_named_should_work() {
+			<$ext_builder>::default().build().execute_with(|| {
+				let _ = Balances::deposit_creating(&1, 100);
+				let _ = Balances::deposit_creating(&2, 100);
+				let id = [1u8; 8];
+				assert_ok!(Balances::reserve_named(&id, &1, 50));
+
+				assert_ok!(Balances::repatriate_reserved_named(&id, &1, &2, 10, Status::Free), 0);
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 40);
+				assert_eq!(Balances::free_balance(&2), 110);
+
+				// reserved funds keep their name.
+				assert_ok!(
+					Balances::repatriate_reserved_named(&id, &1, &2, 50, Status::Reserved),
+					10,
+				);
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 0);
+				assert_eq!(Balances::reserved_balance_named(&id, &2), 40);
+				assert_eq!(Balances::reserved_balance(&2), 40);
+
+				assert_noop!(
+					Balances::repatriate_reserved_named(&id, &2, &3, 10, Status::Free),
+					Error::<$test, _>::DeadAccount,
+				);
+				// nothing is moved from another reserve.
+				assert_ok!(Balances::reserve(&2, 10));
+				assert_ok!(
+					Balances::repatriate_reserved_named(&[2; 8], &2, &1, 10, Status::Free),
+					10,
+				);
+				assert_eq!(Balances::reserved_balance(&2), 50);
+			});
+		}
+
+		#[test]
+		fn ensure_reserved_named_should_work() {
+			<$ext_builder>::default().build().execute_with(|| {
+				let _ = Balances::deposit_creating(&1, 100);
+				let id = [1u8; 8];
+
+				assert_ok!(Balances::ensure_reserved_named(&id, &1, 15));
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 15);
+				assert_ok!(Balances::ensure_reserved_named(&id, &1, 10));
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 10);
+				assert_ok!(Balances::ensure_reserved_named(&id, &1, 20));
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 20);
+				assert_eq!(Balances::free_balance(&1), 80);
+			});
+		}
+
+		#[test]
+		fn frozen_account_cannot_move_funds_in_or_out() {
+			<$ext_builder>::default().monied(true).build().execute_with(|| {
+				assert_noop!(Balances::freeze_account(RawOrigin::Signed(1).into(), 1), BadOrigin);
+				assert_ok!(Balances::freeze_account(RawOrigin::Root.into(), 1));
+				assert!(Balances::is_frozen(&1));
+				assert_eq!(last_event(), Event::balances(RawEvent::Frozen(1)));
+				assert_noop!(
+					Balances::freeze_account(RawOrigin::Root.into(), 1),
+					Error::<$test, _>::AccountFrozen,
+				);
+
+				// no funds move out of the account.
+				assert_noop!(
+					Balances::transfer(RawOrigin::Signed(1).into(), 2, 5),
+					Error::<$test, _>::AccountFrozen,
+				);
+				assert_noop!(
+					Balances::withdraw(&1, 5, WithdrawReasons::TRANSFER, AllowDeath).map(|_| ()),
+					Error::<$test, _>::AccountFrozen,
+				);
+				assert_eq!(Balances::free_balance(1), 10);
+
+				// nor into it.
+				assert_noop!(
+					Balances::transfer(RawOrigin::Signed(2).into(), 1, 5),
+					Error::<$test, _>::AccountFrozen,
+				);
+				assert_noop!(
+					Balances::deposit_into_existing(&1, 5).map(|_| ()),
+					Error::<$test, _>::AccountFrozen,
+				);
+				assert_eq!(Balances::deposit_creating(&1, 5).peek(), 0);
+				assert_eq!(Balances::free_balance(1), 10);
+				assert_eq!(Balances::free_balance(2), 20);
+
+				assert_noop!(Balances::thaw_account(RawOrigin::Signed(1).into(), 1), BadOrigin);
+				assert_ok!(Balances::thaw_account(RawOrigin::Root.into(), 1));
+				assert!(!Balances::is_frozen(&1));
+				assert_eq!(last_event(), Event::balances(RawEvent::Thawed(1)));
+				assert_noop!(
+					Balances::thaw_account(RawOrigin::Root.into(), 1),
+					Error::<$test, _>::NotFrozen,
+				);
+			});
+		}
+
+		#[test]
+		fn frozen_account_reserved_funds_cannot_be_repatriated_in_or_out() {
+			<$ext_builder>::default().monied(true).build().execute_with(|| {
+				assert_ok!(Balances::reserve(&1, 5));
+				assert_ok!(Balances::reserve(&2, 5));
+				assert_ok!(Balances::freeze_account(RawOrigin::Root.into(), 1));
+
+				assert_noop!(
+					Balances::repatriate_reserved(&1, &2, 5, Status::Free),
+					Error::<$test, _>::AccountFrozen,
+				);
+				assert_eq!(Balances::reserved_balance(1), 5);
+
+				assert_noop!(
+					Balances::repatriate_reserved(&2, &1, 5, Status::Reserved),
+					Error::<$test, _>::AccountFrozen,
+				);
+				assert_eq!(Balances::reserved_balance(2), 5);
+
+				// the reserved funds of a frozen account may still be returned to it.
+				assert_eq!(Balances::repatriate_reserved(&1, &1, 5, Status::Free), Ok(0));
+				assert_eq!(Balances::reserved_balance(1), 0);
+				assert_eq!(Balances::free_balance(1), 10);
+			});
+		}
+
+		#[test]
+		fn frozen_account_can_unreserve_but_not_reserve() {
+			<$ext_builder>::default().monied(true).build().execute_with(|| {
+				assert_ok!(Balances::reserve(&1, 5));
+				assert_ok!(Balances::freeze_account(RawOrigin::Root.into(), 1));
+
+				assert!(!Balances::can_reserve(&1, 5));
+				assert_noop!(Balances::reserve(&1, 5), Error::<$test, _>::AccountFrozen);
+				assert_noop!(
+					Balances::reserve_named(&[1u8; 8], &1, 5),
+					Error::<$test, _>::AccountFrozen,
+				);
+				assert_eq!(Balances::reserved_balance(1), 5);
+
+				// reserved funds can be returned, e.g. a deposit held by another pallet.
+				assert_eq!(Balances::unreserve(&1, 5), 0);
+				assert_eq!(Balances::reserved_balance(1), 0);
+				assert_eq!(Balances::free_balance(1), 10);
+				assert!(Balances::is_frozen(&1));
+			});
+		}
+
+		#[test]
+		fn force_burn_should_work() {
+			<$ext_builder>::default().monied(true).build().execute_with(|| {
+				let reason = *b"sanction";
+				assert_ok!(Balances::reserve(&1, 4));
+				let issuance = Balances::total_issuance();
+				assert_noop!(
+					Balances::force_burn(RawOrigin::Signed(1).into(), 1, 5, reason, false),
+					BadOrigin,
+				);
+
+				// the free balance is burnt first, even from a frozen account.
+				assert_ok!(Balances::freeze_account(RawOrigin::Root.into(), 1));
+				assert_ok!(Balances::force_burn(RawOrigin::Root.into(), 1, 5, reason, true));
+				assert_eq!(Balances::reserved_balance(1), 4);
+				assert_eq!(Balances::free_balance(1), 1);
+				assert_eq!(Balances::total_issuance(), issuance - 5);
+				assert_eq!(last_event(), Event::balances(RawEvent::Burned(1, 5, reason)));
+
+				// the reserved balance is left alone unless explicitly requested.
+				assert_ok!(Balances::force_burn(RawOrigin::Root.into(), 1, 5, reason, false));
+				assert_eq!(Balances::reserved_balance(1), 4);
+				assert_eq!(Balances::free_balance(1), 0);
+				assert_eq!(Balances::total_issuance(), issuance - 6);
+				assert_eq!(last_event(), Event::balances(RawEvent::Burned(1, 1, reason)));
+
+				// no more than the balance of the account is burnt.
+				assert_ok!(Balances::force_burn(RawOrigin::Root.into(), 1, 10, reason, true));
+				assert_eq!(Balances::total_balance(&1), 0);
+				assert_eq!(Balances::total_issuance(), issuance - 10);
+				assert_eq!(last_event(), Event::balances(RawEvent::Burned(1, 4, reason)));
+			});
+		}
+
+		#[test]
+		fn migrate_account_moves_locks_but_not_reserves() {
+			<$ext_builder>::default().build().execute_with(|| {
+				let _ = Balances::deposit_creating(&1, 100);
+				let _ = Balances::deposit_creating(&2, 100);
+				let id = [1u8; 8];
+				assert_ok!(Balances::reserve_named(&id, &1, 20));
+				assert_ok!(Balances::reserve(&1, 10));
+				Balances::set_lock(ID_1, &1, 30, WithdrawReasons::all());
+				Balances::set_lock(ID_2, &1, 20, WithdrawReasons::all());
+				Balances::set_lock(ID_1, &2, 40, WithdrawReasons::TRANSACTION_PAYMENT);
+
+				<Balances as MigrateAccount<_>>::migrate_account(&1, &2);
+
+				assert!(Balances::locks(&1).is_empty());
+				assert_eq!(Balances::free_balance(&1), 40);
+
+				// locked funds are moved along with the locks, which are merged by identifier.
+				assert_eq!(Balances::free_balance(&2), 130);
+				assert_eq!(
+					Balances::locks(&2),
+					vec![
+						BalanceLock { id: ID_1, amount: 40, reasons: Reasons::All },
+						BalanceLock { id: ID_2, amount: 20, reasons: Reasons::All },
+					],
+				);
+				// reserved funds stay with the records of the pallets which reserved them.
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 20);
+				assert_eq!(Balances::reserved_balance(&1), 30);
+				assert_eq!(Balances::reserved_balance(&2), 0);
+			});
 		}
 	}
 }
@@ -54,6 +54,7 @@ parameter_types! {
 	pub BlockWeights: frame_system::limits::BlockWeights =
 		frame_system::limits::BlockWeights::simple_max(1024);
 	pub static ExistentialDeposit: u64 = 0;
+	pub const MaxReserves: u32 = 2;
 }
 impl frame_system::Config for Test {
 	type BaseCallFilter = ();
@@ -95,6 +96,9 @@ impl Config for Test {
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = system::Module<Test>;
 	type MaxLocks = ();
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type WeightInfo = ();
 }
 
@@ -146,3 +150,34 @@ impl ExtBuilder {
 }
 
 decl_tests!{ Test, ExtBuilder, EXISTENTIAL_DEPOSIT }
+
+#[test]
+fn emit_events_with_no_existential_deposit_reaps_empty_account() {
+	<ExtBuilder>::default()
+		.existential_deposit(0)
+		.build()
+		.execute_with(|| {
+			assert_ok!(Balances::set_balance(RawOrigin::Root.into(), 1, 100, 0));
+
+			assert_eq!(
+				events(),
+				[
+					Event::system(system::RawEvent::NewAccount(1)),
+					Event::balances(RawEvent::Endowed(1, 100)),
+					Event::balances(RawEvent::BalanceSet(1, 100, 0)),
+				]
+			);
+			assert_eq!(System::providers(&1), 1);
+
+			let _ = Balances::slash(&1, 100);
+
+			// The balance was the only provider of the account, so it is reaped right away.
+			assert_eq!(
+				events(),
+				[
+					Event::system(system::RawEvent::KilledAccount(1))
+				]
+			);
+			assert!(!System::account_exists(&1));
+		});
+}
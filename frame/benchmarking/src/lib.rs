@@ -66,7 +66,7 @@ pub use sp_storage::TrackedStorageKey;
 /// simple syntax, the associated dispatchable function maps 1:1 with the benchmark and the name of
 /// the benchmark is the same as that of the associated function. However, extended syntax allows
 /// for arbitrary expresions to be evaluated in a benchmark (including for example,
-/// `on_initialize`).
+/// `on_initialize` or `on_idle`).
 ///
 /// The macro allows for common parameters whose ranges and instancing expressions may be drawn upon
 /// (or not) by each arm. Syntax is available to allow for only the range to be drawn upon if
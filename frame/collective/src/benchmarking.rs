@@ -633,6 +633,27 @@ benchmarks_instance! {
 		assert_eq!(Collective::<T, _>::proposals().len(), (p - 1) as usize);
 		assert_last_event::<T, I>(RawEvent::Disapproved(last_hash).into());
 	}
+
+	set_member_weight {
+		let m in 1 .. T::MaxMembers::get();
+
+		let mut members = vec![];
+		for i in 0 .. m {
+			let member = account("member", i, SEED);
+			members.push(member);
+		}
+		Collective::<T, _>::set_members(SystemOrigin::Root.into(), members.clone(), None, T::MaxMembers::get())?;
+
+		// Give every member a weight so that the weights are as large as possible.
+		for member in members.iter() {
+			Collective::<T, _>::set_member_weight(SystemOrigin::Root.into(), member.clone(), Some(2))?;
+		}
+		let who = members[0].clone();
+	}: _(SystemOrigin::Root, who.clone(), Some(3))
+	verify {
+		assert_eq!(Collective::<T, _>::weight_of(&who), 3);
+		assert_last_event::<T, I>(RawEvent::MemberWeightSet(who, 3).into());
+	}
 }
 
 #[cfg(test)]
@@ -710,4 +731,11 @@ mod tests {
 			assert_ok!(test_benchmark_disapprove_proposal::<Test>());
 		});
 	}
+
+	#[test]
+	fn set_member_weight() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_member_weight::<Test>());
+		});
+	}
 }
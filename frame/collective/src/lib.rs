@@ -23,28 +23,37 @@
 //! The pallet assumes that the amount of members stays at or below `MaxMembers` for its weight
 //! calculations, but enforces this neither in `set_members` nor in `change_members_sorted`.
 //!
+//! Members may be given a vote weight with the Root-dispatchable function `set_member_weight`;
+//! members without one have a weight of 1. All tallies, thresholds and the `Members` origin count
+//! votes by weight, so `EnsureMembers` and the proportion origins take the weights into account.
+//!
 //! A "prime" member may be set to help determine the default vote behavior based on chain
 //! config. If `PreimDefaultVote` is used, the prime vote acts as the default vote in case of any
 //! abstentions after the voting period. If `MoreThanMajorityThenPrimeDefaultVote` is used, then
 //! abstentations will first follow the majority of the collective voting, and then the prime
-//! member.
+//! member. `AyeDefaultVote`, `NayDefaultVote` and `AbstainDefaultVote` count abstentions as ayes,
+//! as nays, or not at all; a runtime may also implement `DefaultVote` itself to pick a strategy
+//! for each proposal.
 //!
 //! Voting happens through motions comprising a proposal (i.e. a curried dispatchable) plus a
 //! number of approvals required for it to pass and be called. Motions are open for members to
 //! vote on for a minimum period given by `MotionDuration`. As soon as the needed number of
 //! approvals is given, the motion is closed and executed. If the number of approvals is not reached
 //! during the voting period, then `close` may be called by any account in order to force the end
-//! the motion explicitly. If a prime member is defined then their vote is used in place of any
-//! abstentions and the proposal is executed if there are enough approvals counting the new votes.
+//! the motion explicitly. Abstentions are then counted according to the default vote strategy
+//! and the proposal is executed if there are enough approvals counting the new votes.
+//!
+//! If there are not, then the motion is dropped without being executed.
 //!
-//! If there are not, or if no prime is set, then the motion is dropped without being executed.
+//! The proposals of open motions are not stored by this pallet. They are noted with, and requested
+//! from, the pallet configured as `Config::Preimages`, and the request is dropped again once the
+//! motion is closed.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![recursion_limit="128"]
 
 use sp_std::{prelude::*, result};
 use sp_core::u32_trait::Value as U32;
-use sp_io::storage;
 use sp_runtime::{RuntimeDebug, traits::Hash};
 
 use frame_support::{
@@ -54,8 +63,11 @@ use frame_support::{
 		DispatchError, DispatchResult, DispatchResultWithPostInfo, Dispatchable, Parameter,
 		PostDispatchInfo,
 	},
-	ensure,
-	traits::{ChangeMembers, EnsureOrigin, Get, InitializeMembers},
+	ensure, Identity,
+	storage::migration::StorageKeyIterator,
+	traits::{
+		ChangeMembers, EnsureOrigin, Get, InitializeMembers, PreimageProvider, PreimageRecipient,
+	},
 	weights::{DispatchClass, GetDispatchInfo, Weight},
 };
 use frame_system::{self as system, ensure_signed, ensure_root};
@@ -71,37 +83,58 @@ pub type ProposalIndex = u32;
 
 /// A number of members.
 ///
-/// This also serves as a number of voting members, and since for motions, each member may
-/// vote exactly once, therefore also the number of votes for any given motion.
+/// This also serves as a number of votes, where each member votes with their weight (1 unless
+/// set otherwise through `set_member_weight`), and therefore also as the number of votes for any
+/// given motion.
 pub type MemberCount = u32;
 
+/// How the votes of the members who did not vote on a motion are counted when it is closed after
+/// its voting period.
+#[derive(PartialEq, Eq, Clone, Copy, RuntimeDebug)]
+pub enum DefaultVoteChoice {
+	/// Abstentions count as approvals.
+	Aye,
+	/// Abstentions count as rejections.
+	Nay,
+	/// Abstentions are ignored, and the threshold applies in proportion to the votes cast.
+	Abstain,
+}
+
+impl From<bool> for DefaultVoteChoice {
+	fn from(aye: bool) -> Self {
+		if aye { DefaultVoteChoice::Aye } else { DefaultVoteChoice::Nay }
+	}
+}
+
 /// Default voting strategy when a member is inactive.
-pub trait DefaultVote {
-	/// Get the default voting strategy, given:
+pub trait DefaultVote<Proposal> {
+	/// Get the default vote for a motion on `proposal`, given:
 	///
 	/// - Whether the prime member voted Aye.
-	/// - Raw number of yes votes.
-	/// - Raw number of no votes.
-	/// - Total number of member count.
+	/// - Weighted number of yes votes.
+	/// - Weighted number of no votes.
+	/// - Total weight of the members.
 	fn default_vote(
+		proposal: &Proposal,
 		prime_vote: Option<bool>,
 		yes_votes: MemberCount,
 		no_votes: MemberCount,
 		len: MemberCount,
-	) -> bool;
+	) -> DefaultVoteChoice;
 }
 
 /// Set the prime member's vote as the default vote.
 pub struct PrimeDefaultVote;
 
-impl DefaultVote for PrimeDefaultVote {
+impl<Proposal> DefaultVote<Proposal> for PrimeDefaultVote {
 	fn default_vote(
+		_proposal: &Proposal,
 		prime_vote: Option<bool>,
 		_yes_votes: MemberCount,
 		_no_votes: MemberCount,
 		_len: MemberCount,
-	) -> bool {
-		prime_vote.unwrap_or(false)
+	) -> DefaultVoteChoice {
+		prime_vote.unwrap_or(false).into()
 	}
 }
 
@@ -109,15 +142,62 @@ impl DefaultVote for PrimeDefaultVote {
 /// as yes. Otherwise, use the prime meber's vote as the default vote.
 pub struct MoreThanMajorityThenPrimeDefaultVote;
 
-impl DefaultVote for MoreThanMajorityThenPrimeDefaultVote {
+impl<Proposal> DefaultVote<Proposal> for MoreThanMajorityThenPrimeDefaultVote {
 	fn default_vote(
+		_proposal: &Proposal,
 		prime_vote: Option<bool>,
 		yes_votes: MemberCount,
 		_no_votes: MemberCount,
 		len: MemberCount,
-	) -> bool {
-		let more_than_majority = yes_votes * 2 > len;
-		more_than_majority || prime_vote.unwrap_or(false)
+	) -> DefaultVoteChoice {
+		let more_than_majority = yes_votes as u64 * 2 > len as u64;
+		(more_than_majority || prime_vote.unwrap_or(false)).into()
+	}
+}
+
+/// Count abstentions as approvals.
+pub struct AyeDefaultVote;
+
+impl<Proposal> DefaultVote<Proposal> for AyeDefaultVote {
+	fn default_vote(
+		_proposal: &Proposal,
+		_prime_vote: Option<bool>,
+		_yes_votes: MemberCount,
+		_no_votes: MemberCount,
+		_len: MemberCount,
+	) -> DefaultVoteChoice {
+		DefaultVoteChoice::Aye
+	}
+}
+
+/// Count abstentions as rejections.
+pub struct NayDefaultVote;
+
+impl<Proposal> DefaultVote<Proposal> for NayDefaultVote {
+	fn default_vote(
+		_proposal: &Proposal,
+		_prime_vote: Option<bool>,
+		_yes_votes: MemberCount,
+		_no_votes: MemberCount,
+		_len: MemberCount,
+	) -> DefaultVoteChoice {
+		DefaultVoteChoice::Nay
+	}
+}
+
+/// Ignore abstentions: the motion is approved if the share of approvals in the votes cast is at
+/// least the share of the threshold in the whole collective.
+pub struct AbstainDefaultVote;
+
+impl<Proposal> DefaultVote<Proposal> for AbstainDefaultVote {
+	fn default_vote(
+		_proposal: &Proposal,
+		_prime_vote: Option<bool>,
+		_yes_votes: MemberCount,
+		_no_votes: MemberCount,
+		_len: MemberCount,
+	) -> DefaultVoteChoice {
+		DefaultVoteChoice::Abstain
 	}
 }
 
@@ -148,7 +228,10 @@ pub trait Config<I: Instance=DefaultInstance>: frame_system::Config {
 	type MaxMembers: Get<MemberCount>;
 
 	/// Default vote strategy of this collective.
-	type DefaultVote: DefaultVote;
+	type DefaultVote: DefaultVote<<Self as Config<I>>::Proposal>;
+
+	/// The preimage provider used to store the proposals of open motions.
+	type Preimages: PreimageRecipient<Self::Hash>;
 
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
@@ -157,7 +240,8 @@ pub trait Config<I: Instance=DefaultInstance>: frame_system::Config {
 /// Origin for the collective module.
 #[derive(PartialEq, Eq, Clone, RuntimeDebug, Encode, Decode)]
 pub enum RawOrigin<AccountId, I> {
-	/// It has been condoned by a given number of members of the collective from a given total.
+	/// It has been condoned by a given number of votes of the collective from a given total,
+	/// where each member votes with their weight.
 	Members(MemberCount, MemberCount),
 	/// It has been condoned by a single member of the collective.
 	Member(AccountId),
@@ -173,7 +257,7 @@ pub type Origin<T, I=DefaultInstance> = RawOrigin<<T as frame_system::Config>::A
 pub struct Votes<AccountId, BlockNumber> {
 	/// The proposal's unique index.
 	index: ProposalIndex,
-	/// The number of approval votes that are needed to pass the motion.
+	/// The number of (weighted) approval votes that are needed to pass the motion.
 	threshold: MemberCount,
 	/// The current set of voters that approved it.
 	ayes: Vec<AccountId>,
@@ -183,13 +267,19 @@ pub struct Votes<AccountId, BlockNumber> {
 	end: BlockNumber,
 }
 
+// A value placed in storage that represents the current version of the Collective storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+enum Releases {
+	V1,
+	V2,
+}
+
 decl_storage! {
 	trait Store for Module<T: Config<I>, I: Instance=DefaultInstance> as Collective {
 		/// The hashes of the active proposals.
 		pub Proposals get(fn proposals): Vec<T::Hash>;
-		/// Actual proposal for a given hash, if it's current.
-		pub ProposalOf get(fn proposal_of):
-			map hasher(identity) T::Hash => Option<<T as Config<I>>::Proposal>;
 		/// Votes on a given proposal, if it is ongoing.
 		pub Voting get(fn voting):
 			map hasher(identity) T::Hash => Option<Votes<T::AccountId, T::BlockNumber>>;
@@ -199,6 +289,14 @@ decl_storage! {
 		pub Members get(fn members): Vec<T::AccountId>;
 		/// The prime member that helps determine the default vote behavior in case of absentations.
 		pub Prime get(fn prime): Option<T::AccountId>;
+		/// The vote weights of the members which have one, sorted by account. Members without one
+		/// have a weight of 1.
+		pub MemberWeights get(fn member_weights): Vec<(T::AccountId, MemberCount)>;
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with last version.
+		StorageVersion build(|_| Some(Releases::V2)): Option<Releases>;
 	}
 	add_extra_genesis {
 		config(phantom): sp_std::marker::PhantomData<I>;
@@ -217,7 +315,7 @@ decl_event! {
 		/// \[account, proposal_index, proposal_hash, threshold\]
 		Proposed(AccountId, ProposalIndex, Hash, MemberCount),
 		/// A motion (given hash) has been voted on by given account, leaving
-		/// a tally (yes votes and no votes given respectively as `MemberCount`).
+		/// a tally (weighted yes votes and no votes given respectively as `MemberCount`).
 		/// \[account, proposal_hash, voted, yes, no\]
 		Voted(AccountId, Hash, bool, MemberCount, MemberCount),
 		/// A motion was approved by the required threshold.
@@ -235,6 +333,9 @@ decl_event! {
 		/// A proposal was closed because its threshold was reached or after its duration was up.
 		/// \[proposal_hash, yes, no\]
 		Closed(Hash, MemberCount, MemberCount),
+		/// The vote weight of a member was set.
+		/// \[account, weight\]
+		MemberWeightSet(AccountId, MemberCount),
 	}
 }
 
@@ -260,6 +361,10 @@ decl_error! {
 		WrongProposalWeight,
 		/// The given length bound for the proposal was too low.
 		WrongProposalLength,
+		/// The proposal is larger than the maximum size of a preimage.
+		ProposalTooLarge,
+		/// A member may not have a vote weight of zero.
+		ZeroWeight,
 	}
 }
 
@@ -281,6 +386,10 @@ decl_module! {
 
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			Self::migrate_proposals_to_preimages()
+		}
+
 		/// Set the collective's membership.
 		///
 		/// - `new_members`: The new member list. Be nice to the chain and provide it sorted.
@@ -405,13 +514,13 @@ decl_module! {
 		///     - `P2` is proposals-count (code-bounded) (`threshold >= 2`)
 		/// - DB:
 		///   - 1 storage read `is_member` (codec `O(M)`)
-		///   - 1 storage read `ProposalOf::contains_key` (codec `O(1)`)
+		///   - 1 storage read `Voting::contains_key` (codec `O(1)`)
 		///   - DB accesses influenced by `threshold`:
 		///     - EITHER storage accesses done by `proposal` (`threshold < 2`)
 		///     - OR proposal insertion (`threshold <= 2`)
 		///       - 1 storage mutation `Proposals` (codec `O(P2)`)
 		///       - 1 storage mutation `ProposalCount` (codec `O(1)`)
-		///       - 1 storage write `ProposalOf` (codec `O(B)`)
+		///       - 1 preimage request and 1 preimage write (codec `O(B)`)
 		///       - 1 storage write `Voting` (codec `O(M)`)
 		///   - 1 event
 		/// # </weight>
@@ -442,11 +551,13 @@ decl_module! {
 			let proposal_len = proposal.using_encoded(|x| x.len());
 			ensure!(proposal_len <= length_bound as usize, Error::<T, I>::WrongProposalLength);
 			let proposal_hash = T::Hashing::hash_of(&proposal);
-			ensure!(!<ProposalOf<T, I>>::contains_key(proposal_hash), Error::<T, I>::DuplicateProposal);
+			ensure!(!<Voting<T, I>>::contains_key(proposal_hash), Error::<T, I>::DuplicateProposal);
 
 			if threshold < 2 {
-				let seats = Self::members().len() as MemberCount;
-				let result = proposal.dispatch(RawOrigin::Members(1, seats).into());
+				let weights = Self::member_weights();
+				let seats = Self::tally(&weights, &members);
+				let votes = Self::weight_in(&weights, &who);
+				let result = proposal.dispatch(RawOrigin::Members(votes, seats).into());
 				Self::deposit_event(
 					RawEvent::Executed(proposal_hash, result.map(|_| ()).map_err(|e| e.error))
 				);
@@ -458,6 +569,10 @@ decl_module! {
 					).saturating_add(w) // P1
 				}).into())
 			} else {
+				ensure!(
+					proposal_len <= <T::Preimages as PreimageRecipient<T::Hash>>::MaxSize::get() as usize,
+					Error::<T, I>::ProposalTooLarge
+				);
 				let active_proposals =
 					<Proposals<T, I>>::try_mutate(|proposals| -> Result<usize, DispatchError> {
 						proposals.push(proposal_hash);
@@ -469,7 +584,8 @@ decl_module! {
 					})?;
 				let index = Self::proposal_count();
 				<ProposalCount<I>>::mutate(|i| *i += 1);
-				<ProposalOf<T, I>>::insert(proposal_hash, *proposal);
+				T::Preimages::request_preimage(&proposal_hash);
+				T::Preimages::note_preimage(proposal.encode());
 				let end = system::Module::<T>::block_number() + T::MotionDuration::get();
 				let votes = Votes { index, threshold, ayes: vec![who.clone()], nays: vec![], end };
 				<Voting<T, I>>::insert(proposal_hash, votes);
@@ -535,8 +651,9 @@ decl_module! {
 				}
 			}
 
-			let yes_votes = voting.ayes.len() as MemberCount;
-			let no_votes = voting.nays.len() as MemberCount;
+			let weights = Self::member_weights();
+			let yes_votes = Self::tally(&weights, &voting.ayes);
+			let no_votes = Self::tally(&weights, &voting.nays);
 			Self::deposit_event(RawEvent::Voted(who, proposal, approve, yes_votes, no_votes));
 
 			Voting::<T, I>::insert(&proposal, voting);
@@ -551,12 +668,14 @@ decl_module! {
 		/// If called before the end of the voting period it will only close the vote if it is
 		/// has enough votes to be approved or disapproved.
 		///
-		/// If called after the end of the voting period abstentions are counted as rejections
-		/// unless there is a prime member set and the prime member cast an approval.
+		/// If called after the end of the voting period abstentions are counted according to
+		/// `Config::DefaultVote`: as approvals, as rejections, or not at all, in which case the
+		/// threshold applies in proportion to the weight of the votes cast. The proposal is then
+		/// dispatched from a `Members` origin of the weighted ayes only, so that it does not pass
+		/// origin checks requiring a larger proportion of the whole collective.
 		///
 		/// + `proposal_weight_bound`: The maximum amount of weight consumed by executing the closed proposal.
-		/// + `length_bound`: The upper bound for the length of the proposal in storage. Checked via
-		///                   `storage::read` so it is `size_of::<u32>() == 4` larger than the pure length.
+		/// + `length_bound`: The upper bound for the length of the proposal preimage.
 		///
 		/// # <weight>
 		/// ## Weight
@@ -566,8 +685,9 @@ decl_module! {
 		///   - `P1` is the complexity of `proposal` preimage.
 		///   - `P2` is proposal-count (code-bounded)
 		/// - DB:
-		///  - 2 storage reads (`Members`: codec `O(M)`, `Prime`: codec `O(1)`)
-		///  - 3 mutations (`Voting`: codec `O(M)`, `ProposalOf`: codec `O(B)`, `Proposals`: codec `O(P2)`)
+		///  - 3 storage reads (`Members`: codec `O(M)`, `MemberWeights`: codec `O(M)`, `Prime`: codec `O(1)`)
+		///  - 1 preimage read (codec `O(B)`)
+		///  - 3 mutations (`Voting`: codec `O(M)`, preimage request: codec `O(1)`, `Proposals`: codec `O(P2)`)
 		///  - any mutations done while executing `proposal` (`P1`)
 		/// - up to 3 events
 		/// # </weight>
@@ -596,9 +716,12 @@ decl_module! {
 			let voting = Self::voting(&proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
 			ensure!(voting.index == index, Error::<T, I>::WrongIndex);
 
-			let mut no_votes = voting.nays.len() as MemberCount;
-			let mut yes_votes = voting.ayes.len() as MemberCount;
-			let seats = Self::members().len() as MemberCount;
+			let members = Self::members();
+			let weights = Self::member_weights();
+			let mut no_votes = Self::tally(&weights, &voting.nays);
+			let mut yes_votes = Self::tally(&weights, &voting.ayes);
+			let seats = Self::tally(&weights, &members);
+			let member_count = members.len() as MemberCount;
 			let approved = yes_votes >= voting.threshold;
 			let disapproved = seats.saturating_sub(no_votes) < voting.threshold;
 			// Allow (dis-)approving the proposal as soon as there are enough votes.
@@ -610,16 +733,16 @@ decl_module! {
 				)?;
 				Self::deposit_event(RawEvent::Closed(proposal_hash, yes_votes, no_votes));
 				let (proposal_weight, proposal_count) =
-					Self::do_approve_proposal(seats, voting, proposal_hash, proposal);
+					Self::do_approve_proposal(seats, yes_votes, voting, proposal_hash, proposal);
 				return Ok(Some(
-					T::WeightInfo::close_early_approved(len as u32, seats, proposal_count)
+					T::WeightInfo::close_early_approved(len as u32, member_count, proposal_count)
 						.saturating_add(proposal_weight)
 				).into());
 			} else if disapproved {
 				Self::deposit_event(RawEvent::Closed(proposal_hash, yes_votes, no_votes));
 				let proposal_count = Self::do_disapprove_proposal(proposal_hash);
 				return Ok(Some(
-					T::WeightInfo::close_early_disapproved(seats, proposal_count)
+					T::WeightInfo::close_early_disapproved(member_count, proposal_count)
 				).into());
 			}
 
@@ -628,15 +751,34 @@ decl_module! {
 
 			let prime_vote = Self::prime().map(|who| voting.ayes.iter().any(|a| a == &who));
 
-			// default voting strategy.
-			let default = T::DefaultVote::default_vote(prime_vote, yes_votes, no_votes, seats);
-
-			let abstentions = seats - (yes_votes + no_votes);
-			match default {
-				true => yes_votes += abstentions,
-				false => no_votes += abstentions,
-			}
-			let approved = yes_votes >= voting.threshold;
+			// default voting strategy; a motion whose proposal is gone can only be rejected.
+			let default = match Self::proposal_of(&proposal_hash) {
+				Some(proposal) => T::DefaultVote::default_vote(
+					&proposal,
+					prime_vote,
+					yes_votes,
+					no_votes,
+					seats,
+				),
+				None => DefaultVoteChoice::Nay,
+			};
+
+			let abstentions = seats.saturating_sub(yes_votes.saturating_add(no_votes));
+			let approved = match default {
+				DefaultVoteChoice::Aye => {
+					yes_votes = yes_votes.saturating_add(abstentions);
+					yes_votes >= voting.threshold
+				},
+				DefaultVoteChoice::Nay => {
+					no_votes = no_votes.saturating_add(abstentions);
+					yes_votes >= voting.threshold
+				},
+				DefaultVoteChoice::Abstain => {
+					let cast = yes_votes as u64 + no_votes as u64;
+					yes_votes > 0 &&
+						yes_votes as u64 * seats as u64 >= voting.threshold as u64 * cast
+				},
+			};
 
 			if approved {
 				let (proposal, len) = Self::validate_and_get_proposal(
@@ -646,16 +788,16 @@ decl_module! {
 				)?;
 				Self::deposit_event(RawEvent::Closed(proposal_hash, yes_votes, no_votes));
 				let (proposal_weight, proposal_count) =
-					Self::do_approve_proposal(seats, voting, proposal_hash, proposal);
+					Self::do_approve_proposal(seats, yes_votes, voting, proposal_hash, proposal);
 				return Ok(Some(
-					T::WeightInfo::close_approved(len as u32, seats, proposal_count)
+					T::WeightInfo::close_approved(len as u32, member_count, proposal_count)
 						.saturating_add(proposal_weight)
 				).into());
 			} else {
 				Self::deposit_event(RawEvent::Closed(proposal_hash, yes_votes, no_votes));
 				let proposal_count = Self::do_disapprove_proposal(proposal_hash);
 				return Ok(Some(
-					T::WeightInfo::close_disapproved(seats, proposal_count)
+					T::WeightInfo::close_disapproved(member_count, proposal_count)
 				).into());
 			}
 		}
@@ -671,7 +813,7 @@ decl_module! {
 		/// Complexity: O(P) where P is the number of max proposals
 		/// DB Weight:
 		/// * Reads: Proposals
-		/// * Writes: Voting, Proposals, ProposalOf
+		/// * Writes: Voting, Proposals, preimage request
 		/// # </weight>
 		#[weight = T::WeightInfo::disapprove_proposal(T::MaxProposals::get())]
 		fn disapprove_proposal(origin, proposal_hash: T::Hash) -> DispatchResultWithPostInfo {
@@ -679,6 +821,55 @@ decl_module! {
 			let proposal_count = Self::do_disapprove_proposal(proposal_hash);
 			Ok(Some(T::WeightInfo::disapprove_proposal(proposal_count)).into())
 		}
+
+		/// Set the vote weight of a member of the collective.
+		///
+		/// Must be called by the Root origin.
+		///
+		/// Parameters:
+		/// * `who`: The member whose weight should be set.
+		/// * `weight`: The new vote weight, or `None` to reset it to the default weight of 1.
+		///
+		/// The weights of members are read when votes are tallied, so a new weight also applies to
+		/// the votes already cast on open motions.
+		///
+		/// # <weight>
+		/// ## Weight
+		/// - `O(M)` where `M` is members-count (code- and governance-bounded)
+		/// - DB:
+		///   - 1 storage read `Members` (codec `O(M)`)
+		///   - 1 storage mutation `MemberWeights` (codec `O(M)`)
+		/// - 1 event
+		/// # </weight>
+		#[weight = (
+			T::WeightInfo::set_member_weight(T::MaxMembers::get()),
+			DispatchClass::Operational
+		)]
+		fn set_member_weight(origin,
+			who: T::AccountId,
+			weight: Option<MemberCount>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let members = Self::members();
+			ensure!(members.contains(&who), Error::<T, I>::NotMember);
+			ensure!(weight != Some(0), Error::<T, I>::ZeroWeight);
+
+			let mut weights = Self::member_weights();
+			match (weights.binary_search_by(|(a, _)| a.cmp(&who)), weight) {
+				(Ok(i), Some(w)) => weights[i].1 = w,
+				(Ok(i), None) => { weights.remove(i); },
+				(Err(i), Some(w)) => weights.insert(i, (who.clone(), w)),
+				(Err(_), None) => (),
+			}
+			if weights.is_empty() {
+				MemberWeights::<T, I>::kill();
+			} else {
+				MemberWeights::<T, I>::put(weights);
+			}
+			Self::deposit_event(RawEvent::MemberWeightSet(who, weight.unwrap_or(1)));
+
+			Ok(Some(T::WeightInfo::set_member_weight(members.len() as u32)).into())
+		}
 	}
 }
 
@@ -690,24 +881,54 @@ impl<T: Config<I>, I: Instance> Module<T, I> {
 		Self::members().contains(who)
 	}
 
-	/// Ensure that the right proposal bounds were passed and get the proposal from storage.
-	///
-	/// Checks the length in storage via `storage::read` which adds an extra `size_of::<u32>() == 4`
-	/// to the length.
+	/// The vote weight of `who`, whether or not they are a member.
+	pub fn weight_of(who: &T::AccountId) -> MemberCount {
+		Self::weight_in(&Self::member_weights(), who)
+	}
+
+	/// The total vote weight of the members of the collective.
+	pub fn total_weight() -> MemberCount {
+		Self::tally(&Self::member_weights(), &Self::members())
+	}
+
+	/// The vote weight of `who` according to `weights`, which must be sorted by account.
+	fn weight_in(weights: &[(T::AccountId, MemberCount)], who: &T::AccountId) -> MemberCount {
+		weights.binary_search_by(|(a, _)| a.cmp(who))
+			.map(|i| weights[i].1)
+			.unwrap_or(1)
+	}
+
+	/// The total vote weight of `voters` according to `weights`.
+	fn tally(weights: &[(T::AccountId, MemberCount)], voters: &[T::AccountId]) -> MemberCount {
+		voters.iter().fold(0, |total: MemberCount, who| {
+			total.saturating_add(Self::weight_in(weights, who))
+		})
+	}
+
+	/// The proposal of an open motion, if it's current.
+	pub fn proposal_of(hash: &T::Hash) -> Option<<T as Config<I>>::Proposal> {
+		if !<Voting<T, I>>::contains_key(hash) {
+			return None;
+		}
+		T::Preimages::get_preimage(hash)
+			.and_then(|data| <T as Config<I>>::Proposal::decode(&mut &data[..]).ok())
+	}
+
+	/// Ensure that the right proposal bounds were passed and get the proposal from the preimage
+	/// provider.
 	fn validate_and_get_proposal(
 		hash: &T::Hash,
 		length_bound: u32,
 		weight_bound: Weight
 	) -> Result<(<T as Config<I>>::Proposal, usize), DispatchError> {
-		let key = ProposalOf::<T, I>::hashed_key_for(hash);
-		// read the length of the proposal storage entry directly
-		let proposal_len = storage::read(&key, &mut [0; 0], 0)
-			.ok_or(Error::<T, I>::ProposalMissing)?;
-		ensure!(proposal_len <= length_bound, Error::<T, I>::WrongProposalLength);
-		let proposal = ProposalOf::<T, I>::get(hash).ok_or(Error::<T, I>::ProposalMissing)?;
+		let data = T::Preimages::get_preimage(hash).ok_or(Error::<T, I>::ProposalMissing)?;
+		let proposal_len = data.len();
+		ensure!(proposal_len <= length_bound as usize, Error::<T, I>::WrongProposalLength);
+		let proposal = <T as Config<I>>::Proposal::decode(&mut &data[..])
+			.map_err(|_| Error::<T, I>::ProposalMissing)?;
 		let proposal_weight = proposal.get_dispatch_info().weight;
 		ensure!(proposal_weight <= weight_bound, Error::<T, I>::WrongProposalWeight);
-		Ok((proposal, proposal_len as usize))
+		Ok((proposal, proposal_len))
 	}
 
 	/// Weight:
@@ -724,8 +945,13 @@ impl<T: Config<I>, I: Instance> Module<T, I> {
 	/// Two removals, one mutation.
 	/// Computation and i/o `O(P)` where:
 	/// - `P` is number of active proposals
+	///
+	/// The proposal is dispatched from a `Members` origin of the threshold out of `seats`, or of
+	/// `yes_votes` if they are fewer, i.e. if the motion passed with abstentions being ignored.
+	/// Ignored abstentions thus never count toward the proportion of the origin.
 	fn do_approve_proposal(
 		seats: MemberCount,
+		yes_votes: MemberCount,
 		voting: Votes<T::AccountId, T::BlockNumber>,
 		proposal_hash: T::Hash,
 		proposal: <T as Config<I>>::Proposal,
@@ -733,7 +959,7 @@ impl<T: Config<I>, I: Instance> Module<T, I> {
 		Self::deposit_event(RawEvent::Approved(proposal_hash));
 
 		let dispatch_weight = proposal.get_dispatch_info().weight;
-		let origin = RawOrigin::Members(voting.threshold, seats).into();
+		let origin = RawOrigin::Members(voting.threshold.min(yes_votes), seats).into();
 		let result = proposal.dispatch(origin);
 		Self::deposit_event(
 			RawEvent::Executed(proposal_hash, result.map(|_| ()).map_err(|e| e.error))
@@ -753,8 +979,8 @@ impl<T: Config<I>, I: Instance> Module<T, I> {
 
 	// Removes a proposal from the pallet, cleaning up votes and the vector of proposals.
 	fn remove_proposal(proposal_hash: T::Hash) -> u32 {
-		// remove proposal and vote
-		ProposalOf::<T, I>::remove(&proposal_hash);
+		// drop the proposal preimage and remove the vote
+		T::Preimages::unrequest_preimage(&proposal_hash);
 		Voting::<T, I>::remove(&proposal_hash);
 		let num_proposals = Proposals::<T, I>::mutate(|proposals| {
 			proposals.retain(|h| h != &proposal_hash);
@@ -762,10 +988,40 @@ impl<T: Config<I>, I: Instance> Module<T, I> {
 		});
 		num_proposals as u32
 	}
+
+	/// Move the proposals of open motions out of the legacy `ProposalOf` storage and into
+	/// `Config::Preimages`.
+	///
+	/// Proposals without a vote are dropped. Does nothing if the storage is already at
+	/// `Releases::V2`.
+	fn migrate_proposals_to_preimages() -> Weight {
+		if <StorageVersion<I>>::get() >= Some(Releases::V2) {
+			return T::DbWeight::get().reads(1);
+		}
+		let mut reads: Weight = 1;
+		let mut writes: Weight = 0;
+		let legacy = StorageKeyIterator::<T::Hash, <T as Config<I>>::Proposal, Identity>::new(
+			I::PREFIX.as_bytes(),
+			b"ProposalOf",
+		).drain();
+		for (hash, proposal) in legacy {
+			reads += 2;
+			writes += 1;
+			if <Voting<T, I>>::contains_key(&hash) {
+				T::Preimages::request_preimage(&hash);
+				T::Preimages::note_preimage(proposal.encode());
+				reads += 1;
+				writes += 2;
+			}
+		}
+		<StorageVersion<I>>::put(Releases::V2);
+		T::DbWeight::get().reads_writes(reads + 1, writes + 1)
+	}
 }
 
 impl<T: Config<I>, I: Instance> ChangeMembers<T::AccountId> for Module<T, I> {
-	/// Update the members of the collective. Votes are updated and the prime is reset.
+	/// Update the members of the collective. Votes and weights of outgoing members are removed
+	/// and the prime is reset.
 	///
 	/// NOTE: Does not enforce the expected `MaxMembers` limit on the amount of members, but
 	///       the weight estimations rely on it to estimate dispatchable weight.
@@ -780,6 +1036,7 @@ impl<T: Config<I>, I: Instance> ChangeMembers<T::AccountId> for Module<T, I> {
 	///   - 1 storage read (codec `O(P)`) for reading the proposals
 	///   - `P` storage mutations for updating the votes (codec `O(M)`)
 	///   - 1 storage write (codec `O(N)`) for storing the new members
+	///   - 1 storage mutation (codec `O(M)`) for removing the weights of outgoing members
 	///   - 1 storage write (codec `O(1)`) for deleting the old prime
 	/// # </weight>
 	fn change_members_sorted(
@@ -810,6 +1067,17 @@ impl<T: Config<I>, I: Instance> ChangeMembers<T::AccountId> for Module<T, I> {
 				}
 			);
 		}
+		let weights = Self::member_weights();
+		if !weights.is_empty() {
+			let remaining: Vec<_> = weights.into_iter()
+				.filter(|(who, _)| outgoing.binary_search(who).is_err())
+				.collect();
+			if remaining.is_empty() {
+				MemberWeights::<T, I>::kill();
+			} else {
+				MemberWeights::<T, I>::put(remaining);
+			}
+		}
 		Members::<T, I>::put(new);
 		Prime::<T, I>::kill();
 	}
@@ -828,8 +1096,8 @@ impl<T: Config<I>, I: Instance> InitializeMembers<T::AccountId> for Module<T, I>
 	}
 }
 
-/// Ensure that the origin `o` represents at least `n` members. Returns `Ok` or an `Err`
-/// otherwise.
+/// Ensure that the origin `o` represents at least `n` (weighted) votes of members. Returns `Ok`
+/// or an `Err` otherwise.
 pub fn ensure_members<OuterOrigin, AccountId, I>(o: OuterOrigin, n: MemberCount)
 	-> result::Result<MemberCount, &'static str>
 where
@@ -861,6 +1129,7 @@ impl<
 	}
 }
 
+/// Ensure that the origin was condoned by at least `N` votes, counted by member weight.
 pub struct EnsureMembers<N: U32, AccountId, I=DefaultInstance>(sp_std::marker::PhantomData<(N, AccountId, I)>);
 impl<
 	O: Into<Result<RawOrigin<AccountId, I>, O>> + From<RawOrigin<AccountId, I>>,
@@ -882,6 +1151,7 @@ impl<
 	}
 }
 
+/// Ensure that the origin was condoned by more than `N / D` of the total member weight.
 pub struct EnsureProportionMoreThan<N: U32, D: U32, AccountId, I=DefaultInstance>(
 	sp_std::marker::PhantomData<(N, D, AccountId, I)>
 );
@@ -895,7 +1165,8 @@ impl<
 	type Success = ();
 	fn try_origin(o: O) -> Result<Self::Success, O> {
 		o.into().and_then(|o| match o {
-			RawOrigin::Members(n, m) if n * D::VALUE > N::VALUE * m => Ok(()),
+			RawOrigin::Members(n, m) if n as u64 * D::VALUE as u64 > N::VALUE as u64 * m as u64 =>
+				Ok(()),
 			r => Err(O::from(r)),
 		})
 	}
@@ -906,6 +1177,7 @@ impl<
 	}
 }
 
+/// Ensure that the origin was condoned by at least `N / D` of the total member weight.
 pub struct EnsureProportionAtLeast<N: U32, D: U32, AccountId, I=DefaultInstance>(
 	sp_std::marker::PhantomData<(N, D, AccountId, I)>
 );
@@ -919,7 +1191,8 @@ impl<
 	type Success = ();
 	fn try_origin(o: O) -> Result<Self::Success, O> {
 		o.into().and_then(|o| match o {
-			RawOrigin::Members(n, m) if n * D::VALUE >= N::VALUE * m => Ok(()),
+			RawOrigin::Members(n, m) if n as u64 * D::VALUE as u64 >= N::VALUE as u64 * m as u64 =>
+				Ok(()),
 			r => Err(O::from(r)),
 		})
 	}
@@ -933,10 +1206,13 @@ impl<
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use frame_support::{Hashable, assert_ok, assert_noop, parameter_types};
+	use frame_support::{
+		Hashable, assert_ok, assert_noop, parameter_types, storage::migration, traits::OnRuntimeUpgrade,
+	};
 	use frame_system::{self as system, EventRecord, Phase};
 	use hex_literal::hex;
-	use sp_core::H256;
+	use sp_core::{H256, u32_trait::{_1, _2, _3, _5}};
+	use std::{cell::RefCell, collections::BTreeMap};
 	use sp_runtime::{
 		traits::{BlakeTwo256, IdentityLookup, Block as BlockT}, testing::Header,
 		BuildStorage,
@@ -948,6 +1224,7 @@ mod tests {
 		pub const MotionDuration: u64 = 3;
 		pub const MaxProposals: u32 = 100;
 		pub const MaxMembers: u32 = 100;
+		pub const MaxPreimageSize: u32 = 4096;
 		pub BlockWeights: frame_system::limits::BlockWeights =
 			frame_system::limits::BlockWeights::simple_max(1024);
 	}
@@ -974,6 +1251,60 @@ mod tests {
 		type OnKilledAccount = ();
 		type SystemWeightInfo = ();
 	}
+
+	thread_local! {
+		/// The request count and noted preimage of every hash known to `TestPreimages`.
+		static PREIMAGES: RefCell<BTreeMap<H256, (u32, Option<Vec<u8>>)>> = Default::default();
+	}
+
+	/// A preimage store which keeps preimages for as long as they are requested.
+	pub struct TestPreimages;
+	impl PreimageProvider<H256> for TestPreimages {
+		fn have_preimage(hash: &H256) -> bool {
+			Self::get_preimage(hash).is_some()
+		}
+		fn get_preimage(hash: &H256) -> Option<Vec<u8>> {
+			PREIMAGES.with(|p| p.borrow().get(hash).and_then(|(_, data)| data.clone()))
+		}
+		fn preimage_requested(hash: &H256) -> bool {
+			PREIMAGES.with(|p| p.borrow().get(hash).map_or(false, |(count, _)| *count > 0))
+		}
+		fn request_preimage(hash: &H256) {
+			PREIMAGES.with(|p| p.borrow_mut().entry(*hash).or_default().0 += 1);
+		}
+		fn unrequest_preimage(hash: &H256) {
+			PREIMAGES.with(|p| {
+				let mut p = p.borrow_mut();
+				let count = {
+					let entry = p.get_mut(hash).expect("unrequested preimage was requested");
+					entry.0 -= 1;
+					entry.0
+				};
+				if count == 0 {
+					p.remove(hash);
+				}
+			});
+		}
+	}
+	impl PreimageRecipient<H256> for TestPreimages {
+		type MaxSize = MaxPreimageSize;
+		fn note_preimage(bytes: Vec<u8>) {
+			if bytes.len() > MaxPreimageSize::get() as usize {
+				return;
+			}
+			let hash = BlakeTwo256::hash(&bytes);
+			PREIMAGES.with(|p| p.borrow_mut().entry(hash).or_default().1 = Some(bytes));
+		}
+		fn unnote_preimage(hash: &H256) {
+			PREIMAGES.with(|p| {
+				let mut p = p.borrow_mut();
+				if p.get(hash).map_or(false, |(count, _)| *count == 0) {
+					p.remove(hash);
+				}
+			});
+		}
+	}
+
 	impl Config<Instance1> for Test {
 		type Origin = Origin;
 		type Proposal = Call;
@@ -982,6 +1313,7 @@ mod tests {
 		type MaxProposals = MaxProposals;
 		type MaxMembers = MaxMembers;
 		type DefaultVote = PrimeDefaultVote;
+		type Preimages = TestPreimages;
 		type WeightInfo = ();
 	}
 	impl Config<Instance2> for Test {
@@ -992,6 +1324,7 @@ mod tests {
 		type MaxProposals = MaxProposals;
 		type MaxMembers = MaxMembers;
 		type DefaultVote = MoreThanMajorityThenPrimeDefaultVote;
+		type Preimages = TestPreimages;
 		type WeightInfo = ();
 	}
 	impl Config for Test {
@@ -1001,10 +1334,32 @@ mod tests {
 		type MotionDuration = MotionDuration;
 		type MaxProposals = MaxProposals;
 		type MaxMembers = MaxMembers;
-		type DefaultVote = PrimeDefaultVote;
+		type DefaultVote = TestDefaultVote;
+		type Preimages = TestPreimages;
 		type WeightInfo = ();
 	}
 
+	/// Count abstentions on a remark of 1 as approvals, on a remark of 2 as rejections and ignore
+	/// them on any other proposal.
+	pub struct TestDefaultVote;
+	impl DefaultVote<Call> for TestDefaultVote {
+		fn default_vote(
+			proposal: &Call,
+			_prime_vote: Option<bool>,
+			_yes_votes: MemberCount,
+			_no_votes: MemberCount,
+			_len: MemberCount,
+		) -> DefaultVoteChoice {
+			if *proposal == make_proposal(1) {
+				DefaultVoteChoice::Aye
+			} else if *proposal == make_proposal(2) {
+				DefaultVoteChoice::Nay
+			} else {
+				DefaultVoteChoice::Abstain
+			}
+		}
+	}
+
 	pub type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
 	pub type UncheckedExtrinsic = sp_runtime::generic::UncheckedExtrinsic<u32, u64, Call, ()>;
 
@@ -1314,6 +1669,74 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn proposal_preimage_is_kept_while_motion_is_open() {
+		new_test_ext().execute_with(|| {
+			let proposal = make_proposal(42);
+			let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+			let proposal_weight = proposal.get_dispatch_info().weight;
+			let hash = BlakeTwo256::hash_of(&proposal);
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone()), proposal_len));
+			assert!(TestPreimages::preimage_requested(&hash));
+			assert_eq!(TestPreimages::get_preimage(&hash), Some(proposal.encode()));
+
+			assert_ok!(Collective::vote(Origin::signed(2), hash.clone(), 0, false));
+			assert_ok!(Collective::close(Origin::signed(2), hash.clone(), 0, proposal_weight, proposal_len));
+			assert!(!TestPreimages::preimage_requested(&hash));
+			assert!(!TestPreimages::have_preimage(&hash));
+			assert_eq!(Collective::proposal_of(&hash), None);
+		});
+	}
+
+	#[test]
+	fn propose_fails_for_proposal_larger_than_preimage_max_size() {
+		new_test_ext().execute_with(|| {
+			let proposal = Call::System(frame_system::Call::remark(vec![0; MaxPreimageSize::get() as usize]));
+			let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+			assert_noop!(
+				Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone()), proposal_len),
+				Error::<Test, Instance1>::ProposalTooLarge
+			);
+			// Proposals which are executed right away are not stored, so any size is fine.
+			assert_ok!(Collective::propose(Origin::signed(1), 1, Box::new(proposal.clone()), proposal_len));
+		})
+	}
+
+	#[test]
+	fn migration_to_preimages_works() {
+		new_test_ext().execute_with(|| {
+			let open = make_proposal(42);
+			let open_hash = BlakeTwo256::hash_of(&open);
+			let stale = make_proposal(69);
+			let stale_hash = BlakeTwo256::hash_of(&stale);
+			let prefix = <Instance1 as frame_support::traits::Instance>::PREFIX.as_bytes();
+			migration::put_storage_value(prefix, b"ProposalOf", &open_hash.encode(), open.clone());
+			migration::put_storage_value(prefix, b"ProposalOf", &stale_hash.encode(), stale);
+			Proposals::<Test, Instance1>::put(vec![open_hash]);
+			Voting::<Test, Instance1>::insert(
+				open_hash,
+				Votes { index: 0, threshold: 3, ayes: vec![1], nays: vec![], end: 4 },
+			);
+			<StorageVersion<Instance1>>::kill();
+
+			Collective::on_runtime_upgrade();
+			assert_eq!(<StorageVersion<Instance1>>::get(), Some(Releases::V2));
+
+			for h in &[open_hash, stale_hash] {
+				assert!(!migration::have_storage_value(prefix, b"ProposalOf", &h.encode()));
+			}
+			assert!(TestPreimages::preimage_requested(&open_hash));
+			assert_eq!(Collective::proposal_of(&open_hash), Some(open));
+			assert!(!TestPreimages::have_preimage(&stale_hash));
+
+			// the migration is not run again.
+			let stale = make_proposal(69);
+			migration::put_storage_value(prefix, b"ProposalOf", &stale_hash.encode(), stale);
+			Collective::on_runtime_upgrade();
+			assert!(migration::have_storage_value(prefix, b"ProposalOf", &stale_hash.encode()));
+		})
+	}
+
 	#[test]
 	fn correct_validate_and_get_proposal() {
 		new_test_ext().execute_with(|| {
@@ -1611,4 +2034,179 @@ mod tests {
 			]);
 		})
 	}
+
+	#[test]
+	fn set_member_weight_works() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Collective::set_member_weight(Origin::signed(1), 1, Some(2)),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				Collective::set_member_weight(Origin::root(), 4, Some(2)),
+				Error::<Test, Instance1>::NotMember,
+			);
+			assert_noop!(
+				Collective::set_member_weight(Origin::root(), 1, Some(0)),
+				Error::<Test, Instance1>::ZeroWeight,
+			);
+
+			assert_ok!(Collective::set_member_weight(Origin::root(), 3, Some(2)));
+			assert_ok!(Collective::set_member_weight(Origin::root(), 1, Some(5)));
+			assert_eq!(Collective::member_weights(), vec![(1, 5), (3, 2)]);
+			assert_eq!(Collective::weight_of(&2), 1);
+			assert_eq!(Collective::total_weight(), 8);
+
+			assert_ok!(Collective::set_member_weight(Origin::root(), 1, None));
+			assert_eq!(Collective::member_weights(), vec![(3, 2)]);
+			assert_eq!(Collective::total_weight(), 4);
+
+			let record = |event| EventRecord { phase: Phase::Initialization, event, topics: vec![] };
+			assert_eq!(System::events(), vec![
+				record(Event::collective_Instance1(RawEvent::MemberWeightSet(3, 2))),
+				record(Event::collective_Instance1(RawEvent::MemberWeightSet(1, 5))),
+				record(Event::collective_Instance1(RawEvent::MemberWeightSet(1, 1))),
+			]);
+		});
+	}
+
+	#[test]
+	fn outgoing_members_lose_their_weight() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Collective::set_member_weight(Origin::root(), 1, Some(3)));
+			assert_ok!(Collective::set_member_weight(Origin::root(), 3, Some(2)));
+			assert_ok!(Collective::set_members(Origin::root(), vec![2, 3, 4], None, MaxMembers::get()));
+			assert_eq!(Collective::member_weights(), vec![(3, 2)]);
+			assert_eq!(Collective::total_weight(), 4);
+
+			assert_ok!(Collective::set_members(Origin::root(), vec![2, 4], None, MaxMembers::get()));
+			assert!(!MemberWeights::<Test, Instance1>::exists());
+		});
+	}
+
+	#[test]
+	fn votes_are_tallied_by_weight() {
+		new_test_ext().execute_with(|| {
+			let proposal = make_proposal(42);
+			let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+			let proposal_weight = proposal.get_dispatch_info().weight;
+			let hash = BlakeTwo256::hash_of(&proposal);
+
+			// 1 and 2 hold 4 of the 5 votes.
+			assert_ok!(Collective::set_member_weight(Origin::root(), 1, Some(3)));
+			assert_ok!(Collective::propose(Origin::signed(1), 4, Box::new(proposal.clone()), proposal_len));
+			assert_noop!(
+				Collective::close(Origin::signed(4), hash.clone(), 0, proposal_weight, proposal_len),
+				Error::<Test, Instance1>::TooEarly
+			);
+			assert_ok!(Collective::vote(Origin::signed(2), hash.clone(), 0, true));
+			assert_ok!(Collective::close(Origin::signed(4), hash.clone(), 0, proposal_weight, proposal_len));
+
+			let record = |event| EventRecord { phase: Phase::Initialization, event, topics: vec![] };
+			assert_eq!(System::events(), vec![
+				record(Event::collective_Instance1(RawEvent::MemberWeightSet(1, 3))),
+				record(Event::collective_Instance1(RawEvent::Proposed(1, 0, hash.clone(), 4))),
+				record(Event::collective_Instance1(RawEvent::Voted(2, hash.clone(), true, 4, 0))),
+				record(Event::collective_Instance1(RawEvent::Closed(hash.clone(), 4, 0))),
+				record(Event::collective_Instance1(RawEvent::Approved(hash.clone()))),
+				record(Event::collective_Instance1(RawEvent::Executed(hash.clone(), Err(DispatchError::BadOrigin))))
+			]);
+		});
+	}
+
+	#[test]
+	fn heavy_nay_disapproves_early() {
+		new_test_ext().execute_with(|| {
+			let proposal = make_proposal(42);
+			let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+			let proposal_weight = proposal.get_dispatch_info().weight;
+			let hash = BlakeTwo256::hash_of(&proposal);
+
+			assert_ok!(Collective::set_member_weight(Origin::root(), 3, Some(3)));
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone()), proposal_len));
+			assert_ok!(Collective::vote(Origin::signed(3), hash.clone(), 0, false));
+			assert_ok!(Collective::close(Origin::signed(4), hash.clone(), 0, proposal_weight, proposal_len));
+
+			let record = |event| EventRecord { phase: Phase::Initialization, event, topics: vec![] };
+			assert_eq!(System::events(), vec![
+				record(Event::collective_Instance1(RawEvent::MemberWeightSet(3, 3))),
+				record(Event::collective_Instance1(RawEvent::Proposed(1, 0, hash.clone(), 3))),
+				record(Event::collective_Instance1(RawEvent::Voted(3, hash.clone(), false, 1, 3))),
+				record(Event::collective_Instance1(RawEvent::Closed(hash.clone(), 1, 3))),
+				record(Event::collective_Instance1(RawEvent::Disapproved(hash.clone()))),
+			]);
+		});
+	}
+
+	#[test]
+	fn proportion_origins_count_weighted_votes() {
+		type AtLeastThreeFifths = EnsureProportionAtLeast<_3, _5, u64, Instance1>;
+		type MoreThanHalf = EnsureProportionMoreThan<_1, _2, u64, Instance1>;
+		type AtLeastThree = EnsureMembers<_3, u64, Instance1>;
+		let members = |n, m| Origin::from(RawOrigin::<u64, Instance1>::Members(n, m));
+
+		assert!(AtLeastThreeFifths::try_origin(members(6, 10)).is_ok());
+		assert!(AtLeastThreeFifths::try_origin(members(5, 10)).is_err());
+		assert!(MoreThanHalf::try_origin(members(6, 10)).is_ok());
+		assert!(MoreThanHalf::try_origin(members(5, 10)).is_err());
+		assert!(AtLeastThree::try_origin(members(3, 10)).is_ok());
+		assert!(AtLeastThree::try_origin(members(2, 3)).is_err());
+		// Large weights do not overflow the proportion.
+		assert!(AtLeastThreeFifths::try_origin(members(u32::max_value(), u32::max_value())).is_ok());
+	}
+
+	#[test]
+	fn default_vote_is_chosen_per_proposal() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(DefaultCollective::set_members(Origin::root(), vec![1, 2, 3, 4], None, MaxMembers::get()));
+			let close = |value: u64, yes: MemberCount, no: MemberCount, approved: bool| {
+				let proposal = make_proposal(value);
+				let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+				let proposal_weight = proposal.get_dispatch_info().weight;
+				let hash = BlakeTwo256::hash_of(&proposal);
+				let index = DefaultCollective::proposal_count() - 1;
+				assert_ok!(DefaultCollective::close(Origin::signed(4), hash, index, proposal_weight, proposal_len));
+				let closed: Event = collective::Event::<Test>::Closed(hash, yes, no).into();
+				let outcome: Event = if approved {
+					collective::Event::<Test>::Approved(hash).into()
+				} else {
+					collective::Event::<Test>::Disapproved(hash).into()
+				};
+				let events: Vec<_> = System::events().into_iter().map(|r| r.event).collect();
+				assert!(events.contains(&closed));
+				assert!(events.contains(&outcome));
+			};
+			let propose = |value: u64, threshold: MemberCount| {
+				let proposal = make_proposal(value);
+				let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+				assert_ok!(DefaultCollective::propose(Origin::signed(1), threshold, Box::new(proposal), proposal_len));
+				BlakeTwo256::hash_of(&make_proposal(value))
+			};
+
+			// Abstentions count as ayes on a remark of 1.
+			let hash = propose(1, 3);
+			assert_ok!(DefaultCollective::vote(Origin::signed(2), hash, 0, false));
+			System::set_block_number(4);
+			close(1, 3, 1, true);
+
+			// Abstentions count as nays on a remark of 2.
+			let hash = propose(2, 3);
+			assert_ok!(DefaultCollective::vote(Origin::signed(2), hash, 1, true));
+			System::set_block_number(7);
+			close(2, 2, 2, false);
+
+			// Abstentions are ignored otherwise, so 2 of 2 votes pass a threshold of 3 out of 4.
+			let hash = propose(3, 3);
+			assert_ok!(DefaultCollective::vote(Origin::signed(2), hash, 2, true));
+			System::set_block_number(10);
+			close(3, 2, 0, true);
+			// But 2 of 3 votes do not.
+			let hash = propose(4, 3);
+			assert_ok!(DefaultCollective::vote(Origin::signed(2), hash, 3, true));
+			assert_ok!(DefaultCollective::vote(Origin::signed(3), hash, 3, false));
+			System::set_block_number(13);
+			close(4, 2, 1, false);
+			assert!(DefaultCollective::proposals().is_empty());
+		});
+	}
 }
@@ -53,6 +53,7 @@ pub trait WeightInfo {
 	fn close_disapproved(_m: u32, _p: u32, ) -> Weight;
 	fn close_approved(_b: u32, _m: u32, _p: u32, ) -> Weight;
 	fn disapprove_proposal(_p: u32, ) -> Weight;
+	fn set_member_weight(_m: u32, ) -> Weight;
 
 }
 
@@ -140,6 +141,12 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 
 	}
+	fn set_member_weight(m: u32, ) -> Weight {
+		(25_403_000 as Weight)
+			.saturating_add((182_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
 
 }
 
@@ -226,5 +233,11 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 
 	}
+	fn set_member_weight(m: u32, ) -> Weight {
+		(25_403_000 as Weight)
+			.saturating_add((182_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
 
 }
@@ -20,18 +20,124 @@
 
 use bitflags::bitflags;
 use codec::{Decode, Encode};
-use sp_runtime::{DispatchError, RuntimeDebug};
+use sp_runtime::{DispatchError, RuntimeDebug, traits::{Saturating, Zero}};
 use sp_std::prelude::*;
 
+/// Result type of a `bare_call` or `bare_instantiate` call.
+///
+/// It contains the execution result together with all the information collected while
+/// executing: consumed gas, storage deposit, debug output, emitted events and storage changes.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct ContractResult<R, AccountId, Balance, Hash> {
+	/// The execution result of the contract call or instantiation.
+	pub result: R,
+	/// How much gas was consumed. In case of an error this is the amount that was used
+	/// up until the error occurred.
+	pub gas_consumed: u64,
+	/// The net storage deposit that was charged from or refunded to the origin.
+	///
+	/// This is always zero when the contracts module is configured to charge rent.
+	pub storage_deposit: StorageDeposit<Balance>,
+	/// The output written by the contracts through `seal_println`.
+	///
+	/// This also contains the output of nested calls that were reverted.
+	pub debug_message: Vec<u8>,
+	/// The events deposited by the contracts during the execution.
+	pub events: Vec<ContractEvent<AccountId, Hash>>,
+	/// The storage changes of every contract that was written to during the execution.
+	pub storage_diffs: Vec<ContractStorageDiff<AccountId>>,
+}
+
 /// Result type of a `bare_call` call.
+pub type ContractExecResult<AccountId, Balance, Hash> =
+	ContractResult<ExecResult, AccountId, Balance, Hash>;
+
+/// Result type of a contract call as returned by versions of the contracts runtime API before
+/// version 3.
 ///
 /// The result of a contract execution along with a gas consumed.
 #[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
-pub struct ContractExecResult {
+pub struct LegacyContractExecResult {
 	pub exec_result: ExecResult,
 	pub gas_consumed: u64,
 }
 
+/// Result type of a `bare_instantiate` call.
+pub type ContractInstantiateResult<AccountId, Balance, Hash> =
+	ContractResult<Result<InstantiateReturnValue<AccountId, Hash>, ExecError>, AccountId, Balance, Hash>;
+
+/// Output of a successful contract instantiation.
+#[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct InstantiateReturnValue<AccountId, Hash> {
+	/// The output of the constructor.
+	pub result: ExecReturnValue,
+	/// The address of the new contract.
+	pub account_id: AccountId,
+	/// The hash of the code the contract was instantiated from.
+	pub code_hash: Hash,
+}
+
+/// Reference to an existing code hash or a new wasm module.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum Code<Hash> {
+	/// A wasm module that is uploaded as part of the instantiation.
+	Upload(Vec<u8>),
+	/// The hash of code that is already stored on chain.
+	Existing(Hash),
+}
+
+/// A single change to a storage entry of a contract.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct StorageChange {
+	/// The key of the changed entry.
+	pub key: [u8; 32],
+	/// The value before the execution or `None` if the entry didn't exist.
+	pub old_value: Option<Vec<u8>>,
+	/// The value after the execution or `None` if the entry was removed.
+	pub new_value: Option<Vec<u8>>,
+}
+
+/// All storage changes made to a single contract.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct ContractStorageDiff<AccountId> {
+	/// The contract whose storage was changed.
+	pub contract: AccountId,
+	/// The changed entries in the order they were first written.
+	pub changes: Vec<StorageChange>,
+}
+
+/// The amount of balance that was either charged or refunded in order to pay for storage.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum StorageDeposit<Balance> {
+	/// The storage usage was reduced and the origin was refunded this amount.
+	Refund(Balance),
+	/// The storage usage was increased and the origin was charged this amount.
+	Charge(Balance),
+}
+
+impl<Balance: Zero> Default for StorageDeposit<Balance> {
+	fn default() -> Self {
+		Self::Charge(Zero::zero())
+	}
+}
+
+impl<Balance: Saturating + Ord + Copy> StorageDeposit<Balance> {
+	/// Combine two deposits into their net effect.
+	pub fn saturating_add(&self, rhs: &Self) -> Self {
+		use StorageDeposit::*;
+		match (self, rhs) {
+			(Charge(lhs), Charge(rhs)) => Charge(lhs.saturating_add(*rhs)),
+			(Refund(lhs), Refund(rhs)) => Refund(lhs.saturating_add(*rhs)),
+			(Charge(charge), Refund(refund)) | (Refund(refund), Charge(charge)) =>
+				if charge >= refund {
+					Charge(charge.saturating_sub(*refund))
+				} else {
+					Refund(refund.saturating_sub(*charge))
+				},
+		}
+	}
+}
+
 /// Result type of a `get_storage` call.
 pub type GetStorageResult = Result<Option<Vec<u8>>, ContractAccessError>;
 
@@ -48,6 +154,30 @@ pub enum ContractAccessError {
 	IsTombstone,
 }
 
+/// Ownership and usage information of uploaded code.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct CodeInfo<AccountId, Balance> {
+	/// The account that uploaded the code and can remove it.
+	pub owner: AccountId,
+	/// The deposit that is reserved from the owner for as long as the code is stored.
+	pub deposit: Balance,
+	/// The number of alive contracts that use the code.
+	pub refcount: u64,
+}
+
+/// An event deposited by a contract through `seal_deposit_event`.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct ContractEvent<AccountId, Hash> {
+	/// The index of the event within the events of the block it was deposited in.
+	pub event_index: u32,
+	/// The contract that deposited the event.
+	pub contract: AccountId,
+	/// The topics the event was deposited with.
+	pub topics: Vec<Hash>,
+	/// The data attached to the event.
+	pub data: Vec<u8>,
+}
+
 #[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
 pub enum RentProjection<BlockNumber> {
 	/// Eviction is projected to happen at the specified block number.
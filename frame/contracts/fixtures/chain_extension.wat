@@ -0,0 +1,48 @@
+;; Call the chain extension by passing through input and output of this contract.
+(module
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32)))
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok (get_local 0))
+			(unreachable)
+		)
+	)
+
+	;; [0, 4) length of the input buffer
+	(data (i32.const 0) "\08")
+
+	;; [4, 12) buffer where input is copied
+
+	;; [12, 16) length of the output buffer
+	(data (i32.const 12) "\10")
+
+	;; [16, 32) buffer where the output of the chain extension is copied
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 4) (i32.const 0))
+
+		;; The first byte of the input selects the function of the chain extension.
+		;; Our test extension returns the function id as return value.
+		(call $assert
+			(i32.eq
+				(call $seal_call_chain_extension
+					(i32.load8_u (i32.const 4))	;; func_id
+					(i32.const 4)				;; input_ptr
+					(i32.load (i32.const 0))	;; input_len
+					(i32.const 16)				;; output_ptr
+					(i32.const 12)				;; output_len_ptr
+				)
+				(i32.load8_u (i32.const 4))
+			)
+		)
+
+		(call $seal_return (i32.const 0) (i32.const 16) (i32.load (i32.const 12)))
+	)
+)
@@ -25,15 +25,32 @@
 
 use codec::Codec;
 use sp_std::vec::Vec;
-use pallet_contracts_primitives::{ContractExecResult, GetStorageResult, RentProjectionResult};
+use sp_runtime::traits::Block as BlockT;
+use pallet_contracts_primitives::{
+	Code, CodeInfo, ContractEvent, ContractExecResult, ContractInstantiateResult, GetStorageResult,
+	LegacyContractExecResult, RentProjectionResult,
+};
 
 sp_api::decl_runtime_apis! {
 	/// The API to interact with contracts without using executive.
+	#[api_version(3)]
 	pub trait ContractsApi<AccountId, Balance, BlockNumber> where
 		AccountId: Codec,
 		Balance: Codec,
 		BlockNumber: Codec,
 	{
+		/// Perform a call from a specified account to a given contract.
+		///
+		/// Only returns the execution result and the consumed gas.
+		#[changed_in(3)]
+		fn call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: u64,
+			input_data: Vec<u8>,
+		) -> LegacyContractExecResult;
+
 		/// Perform a call from a specified account to a given contract.
 		///
 		/// See the contracts' `call` dispatchable function for more details.
@@ -43,7 +60,22 @@ sp_api::decl_runtime_apis! {
 			value: Balance,
 			gas_limit: u64,
 			input_data: Vec<u8>,
-		) -> ContractExecResult;
+		) -> ContractExecResult<AccountId, Balance, <Block as BlockT>::Hash>;
+
+		/// Instantiate a new contract from a specified account.
+		///
+		/// The code can either be uploaded as part of the instantiation or refer to code that is
+		/// already stored on chain.
+		///
+		/// See the contracts' `instantiate` dispatchable function for more details.
+		fn instantiate(
+			origin: AccountId,
+			endowment: Balance,
+			gas_limit: u64,
+			code: Code<<Block as BlockT>::Hash>,
+			data: Vec<u8>,
+			salt: Vec<u8>,
+		) -> ContractInstantiateResult<AccountId, Balance, <Block as BlockT>::Hash>;
 
 		/// Query a given storage key in a given contract.
 		///
@@ -63,5 +95,18 @@ sp_api::decl_runtime_apis! {
 		///
 		/// Returns `Err` if the contract is in a tombstone state or doesn't exist.
 		fn rent_projection(address: AccountId) -> RentProjectionResult<BlockNumber>;
+
+		/// Returns the owner, deposit and number of users of the code with the given hash.
+		///
+		/// Returns `None` if the code doesn't exist or was stored without an owner.
+		fn code_info(code_hash: <Block as BlockT>::Hash) -> Option<CodeInfo<AccountId, Balance>>;
+
+		/// Returns the events deposited by contracts in the current block.
+		///
+		/// Available since version 2 of this API.
+		///
+		/// Every event is returned together with the topics it was deposited with and its index
+		/// in the events of the block.
+		fn contract_events() -> Vec<ContractEvent<AccountId, <Block as BlockT>::Hash>>;
 	}
 }
@@ -0,0 +1,807 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Querying and subscribing to the events deposited by contracts.
+//!
+//! Contract events are only kept in `System::Events` for the block they were deposited in.
+//! In order to find them without downloading every block, a node can run
+//! [`index_contract_events`] which keeps an index from contract addresses and event topics
+//! to the blocks containing matching events in the auxiliary storage of the client.
+//!
+//! The index is optional and covers a contiguous range of blocks, starting with the best block
+//! at the time the indexer was first started. The events of a block can only be fetched while its
+//! state is kept, so the indexer can be given the number of blocks whose state is kept, and
+//! prunes the entries of older blocks along with it. Blocks outside of the indexed range are
+//! scanned on demand, which is only allowed for a limited number of blocks per query. Queries
+//! that would need to scan more blocks are rejected rather than answered incompletely.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::{Codec, Decode, Encode};
+use futures::{StreamExt, TryStreamExt, future, stream};
+use jsonrpc_core::{
+	Error, ErrorCode, Result,
+	futures::{
+		future::{Executor as Executor01, Future as Future01},
+		sink::Sink as Sink01,
+		stream::Stream as Stream01,
+	},
+};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{manager::SubscriptionManager, typed::Subscriber, SubscriptionId};
+use log::warn;
+use pallet_contracts_primitives::ContractEvent;
+use sc_client_api::{AuxStore, BlockchainEvents};
+use serde::{Deserialize, Serialize};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{
+		Block as BlockT, Header as HeaderT, NumberFor, One, Saturating, UniqueSaturatedInto, Zero,
+	},
+};
+
+use crate::{runtime_error_into_rpc_err, ContractsRuntimeApi};
+
+const INVALID_BLOCK_RANGE: i64 = 10;
+const CLIENT_ERROR: i64 = 11;
+const UNSUPPORTED_RUNTIME: i64 = 12;
+
+/// The maximum number of blocks that are scanned for events by a single query when they
+/// are not covered by the index.
+const MAX_SCANNED_BLOCKS: u64 = 1024;
+
+/// The maximum number of indexed blocks that are looked up by a single query.
+const MAX_INDEXED_BLOCKS: u64 = 65_536;
+
+/// Prefix of all keys that are used by the index in the auxiliary storage.
+const INDEX_PREFIX: &[u8] = b"contracts_events";
+/// Key under which the first and the last indexed block number are stored.
+const INDEX_RANGE_KEY: &[u8] = b"contracts_events_range";
+/// Prefix of the keys under which the index keys written for each block number are stored, so
+/// that they can be removed when the block is pruned.
+const INDEX_KEYS_PREFIX: &[u8] = b"contracts_events_keys";
+
+/// The version of `ContractsApi` that introduced `contract_events`.
+const CONTRACT_EVENTS_API_VERSION: u32 = 2;
+
+/// A location of an indexed event within the block it is stored for: the block hash and the
+/// index of the event within the block.
+///
+/// Entries are stored separately for every block number, so blocks of different forks at the
+/// same height share a key.
+type IndexEntry<Hash> = (Hash, u32);
+
+/// Selects which contract events are returned by a query or a subscription.
+///
+/// An event matches if it was deposited by `address` (if specified) and was deposited
+/// with all of the `topics`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct EventFilter<AccountId, Hash> {
+	#[serde(default)]
+	address: Option<AccountId>,
+	#[serde(default)]
+	topics: Vec<Hash>,
+}
+
+impl<AccountId: PartialEq, Hash: PartialEq> EventFilter<AccountId, Hash> {
+	fn matches(&self, event: &ContractEvent<AccountId, Hash>) -> bool {
+		self.address.as_ref().map_or(true, |address| *address == event.contract) &&
+			self.topics.iter().all(|topic| event.topics.contains(topic))
+	}
+}
+
+/// A contract event together with the block it was deposited in.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct RpcContractEvent<AccountId, Hash, BlockNumber> {
+	/// Number of the block that contains the event.
+	block_number: BlockNumber,
+	/// Hash of the block that contains the event.
+	block_hash: Hash,
+	/// Index of the event within the events of the block.
+	event_index: u32,
+	/// The contract that deposited the event.
+	contract: AccountId,
+	/// The topics the event was deposited with.
+	topics: Vec<Hash>,
+	/// The data attached to the event.
+	data: Bytes,
+}
+
+impl<AccountId, Hash, BlockNumber> RpcContractEvent<AccountId, Hash, BlockNumber> {
+	fn new(
+		block_number: BlockNumber,
+		block_hash: Hash,
+		event: ContractEvent<AccountId, Hash>,
+	) -> Self {
+		RpcContractEvent {
+			block_number,
+			block_hash,
+			event_index: event.event_index,
+			contract: event.contract,
+			topics: event.topics,
+			data: event.data.into(),
+		}
+	}
+}
+
+/// Contract events RPC methods.
+#[rpc]
+pub trait ContractsEventsApi<BlockHash, BlockNumber, AccountId> {
+	/// RPC Metadata
+	type Metadata;
+
+	/// Returns all contract events matching `filter` deposited in the blocks `from..=to`.
+	///
+	/// `from` defaults to the genesis block and `to` defaults to the best block.
+	#[rpc(name = "contracts_getEvents")]
+	fn get_events(
+		&self,
+		filter: EventFilter<AccountId, BlockHash>,
+		from: Option<BlockNumber>,
+		to: Option<BlockNumber>,
+	) -> Result<Vec<RpcContractEvent<AccountId, BlockHash, BlockNumber>>>;
+
+	/// Subscribe to the contract events matching `filter` of newly imported blocks.
+	#[pubsub(subscription = "contracts_events", subscribe, name = "contracts_subscribeEvents")]
+	fn subscribe_events(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<RpcContractEvent<AccountId, BlockHash, BlockNumber>>,
+		filter: EventFilter<AccountId, BlockHash>,
+	);
+
+	/// Unsubscribe from contract events.
+	#[pubsub(
+		subscription = "contracts_events",
+		unsubscribe,
+		name = "contracts_unsubscribeEvents"
+	)]
+	fn unsubscribe_events(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> Result<bool>;
+}
+
+/// An implementation of the contract events RPC methods.
+pub struct ContractsEvents<C, B, Balance> {
+	client: Arc<C>,
+	manager: SubscriptionManager,
+	_marker: PhantomData<(B, Balance)>,
+}
+
+impl<C, B, Balance> ContractsEvents<C, B, Balance> {
+	/// Create new `ContractsEvents` with the given reference to the client.
+	///
+	/// The `executor` is used to drive the subscriptions.
+	pub fn new<E>(client: Arc<C>, executor: E) -> Self
+	where
+		E: Executor01<Box<dyn Future01<Item = (), Error = ()> + Send>> + Send + Sync + 'static,
+	{
+		ContractsEvents {
+			client,
+			manager: SubscriptionManager::new(Arc::new(executor)),
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block, AccountId, Balance> ContractsEvents<C, Block, Balance>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: ContractsRuntimeApi<
+		Block,
+		AccountId,
+		Balance,
+		<<Block as BlockT>::Header as HeaderT>::Number,
+	>,
+	AccountId: Codec + PartialEq,
+	Balance: Codec,
+{
+	/// Fetch the events of the canonical blocks `from..=to` and collect those matching `filter`.
+	fn scan_blocks(
+		&self,
+		filter: &EventFilter<AccountId, Block::Hash>,
+		from: NumberFor<Block>,
+		to: NumberFor<Block>,
+		events: &mut Vec<RpcContractEvent<AccountId, Block::Hash, NumberFor<Block>>>,
+	) -> Result<()> {
+		let mut number = from;
+		while number <= to {
+			if let Some(hash) = self.client.hash(number).map_err(client_error_into_rpc_err)? {
+				events.extend(
+					block_events::<_, Block, AccountId, Balance>(&*self.client, hash)?
+						.into_iter()
+						.filter(|event| filter.matches(event))
+						.map(|event| RpcContractEvent::new(number, hash, event))
+				);
+			}
+			number += One::one();
+		}
+		Ok(())
+	}
+}
+
+impl<C, Block, AccountId, Balance>
+	ContractsEventsApi<
+		<Block as BlockT>::Hash,
+		<<Block as BlockT>::Header as HeaderT>::Number,
+		AccountId,
+	> for ContractsEvents<C, Block, Balance>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+	C: BlockchainEvents<Block>,
+	C::Api: ContractsRuntimeApi<
+		Block,
+		AccountId,
+		Balance,
+		<<Block as BlockT>::Header as HeaderT>::Number,
+	>,
+	AccountId: Codec + Clone + PartialEq + Send + Sync + 'static,
+	Balance: Codec + Send + Sync + 'static,
+{
+	type Metadata = sc_rpc_api::Metadata;
+
+	fn get_events(
+		&self,
+		filter: EventFilter<AccountId, Block::Hash>,
+		from: Option<NumberFor<Block>>,
+		to: Option<NumberFor<Block>>,
+	) -> Result<Vec<RpcContractEvent<AccountId, Block::Hash, NumberFor<Block>>>> {
+		let from = from.unwrap_or_else(Zero::zero);
+		let to = to.unwrap_or_else(|| self.client.info().best_number);
+		if from > to {
+			return Err(Error {
+				code: ErrorCode::ServerError(INVALID_BLOCK_RANGE),
+				message: format!("Invalid block range: {:?} > {:?}", from, to),
+				data: None,
+			});
+		}
+
+		let index_key = filter.topics.first().map(|topic| topic_key(topic))
+			.or_else(|| filter.address.as_ref().map(|address| address_key(address)));
+		let index_range = match index_key {
+			Some(_) => read_aux::<_, (NumberFor<Block>, NumberFor<Block>)>(
+				&*self.client,
+				INDEX_RANGE_KEY,
+			)?,
+			None => None,
+		};
+
+		// Split the queried blocks into the part covered by the index and the parts before and
+		// after it, which need to be scanned.
+		let (before, indexed, after) = match index_range {
+			Some((start, end)) => (
+				if from < start { Some((from, to.min(start - One::one()))) } else { None },
+				Some((from.max(start), to.min(end))).filter(|(lo, hi)| lo <= hi),
+				if to > end { Some((from.max(end + One::one()), to)) } else { None },
+			),
+			None => (Some((from, to)), None, None),
+		};
+
+		let scanned = before.into_iter().chain(after).map(block_count).sum::<u64>();
+		if scanned > MAX_SCANNED_BLOCKS {
+			return Err(Error {
+				code: ErrorCode::ServerError(INVALID_BLOCK_RANGE),
+				message: format!(
+					"{} blocks of {:?}..={:?} are not indexed, which exceeds the maximum of {} \
+					blocks that can be scanned by one query",
+					scanned, from, to, MAX_SCANNED_BLOCKS,
+				),
+				data: None,
+			});
+		}
+		if indexed.map_or(0, block_count) > MAX_INDEXED_BLOCKS {
+			return Err(Error {
+				code: ErrorCode::ServerError(INVALID_BLOCK_RANGE),
+				message: format!(
+					"Blocks {:?}..={:?} exceed the maximum of {} blocks that can be queried by \
+					one query",
+					from, to, MAX_INDEXED_BLOCKS,
+				),
+				data: None,
+			});
+		}
+
+		let mut events = Vec::new();
+		if let Some((lo, hi)) = before {
+			self.scan_blocks(&filter, lo, hi, &mut events)?;
+		}
+		if let (Some((lo, hi)), Some(key)) = (indexed, index_key) {
+			let mut number = lo;
+			while number <= hi {
+				let entries = read_aux::<_, Vec<IndexEntry<Block::Hash>>>(
+					&*self.client,
+					&block_key(&key, number),
+				)?.unwrap_or_default();
+				let canonical = if entries.is_empty() {
+					None
+				} else {
+					self.client.hash(number).map_err(client_error_into_rpc_err)?
+				};
+				// Skip entries of blocks that are not part of the canonical chain.
+				let canonical = canonical.filter(|hash| entries.iter().any(|(h, _)| h == hash));
+				if let Some(hash) = canonical {
+					let block_events =
+						block_events::<_, Block, AccountId, Balance>(&*self.client, hash)?;
+					for (_, event_index) in entries.into_iter().filter(|(h, _)| *h == hash) {
+						let event = block_events.iter()
+							.find(|event| event.event_index == event_index);
+						if let Some(event) = event.filter(|event| filter.matches(event)) {
+							events.push(RpcContractEvent::new(number, hash, event.clone()));
+						}
+					}
+				}
+				number += One::one();
+			}
+		}
+		if let Some((lo, hi)) = after {
+			self.scan_blocks(&filter, lo, hi, &mut events)?;
+		}
+
+		Ok(events)
+	}
+
+	fn subscribe_events(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<RpcContractEvent<AccountId, Block::Hash, NumberFor<Block>>>,
+		filter: EventFilter<AccountId, Block::Hash>,
+	) {
+		let client = self.client.clone();
+		let stream = self.client.import_notification_stream()
+			.map(move |notification| {
+				let number = *notification.header.number();
+				let events = block_events::<_, Block, AccountId, Balance>(
+					&*client,
+					notification.hash,
+				).unwrap_or_else(|e| {
+					warn!("Failed to fetch contract events of {:?}: {:?}", notification.hash, e);
+					Vec::new()
+				});
+				let matching = events.into_iter()
+					.filter(|event| filter.matches(event))
+					.map(|event| RpcContractEvent::new(number, notification.hash, event))
+					.collect::<Vec<_>>();
+				stream::iter(matching)
+			})
+			.flatten()
+			.map(|event| Ok::<_, ()>(event))
+			.compat();
+
+		self.manager.add(subscriber, |sink| {
+			let stream = stream.map(|res| Ok(res));
+			sink.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(stream)
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_events(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> Result<bool> {
+		Ok(self.manager.cancel(id))
+	}
+}
+
+/// Index the events deposited by contracts.
+///
+/// The index is kept in the auxiliary storage of the client and is used by
+/// `contracts_getEvents` to find events by contract address or topic without scanning
+/// every block. It starts with the best block at the time the indexer is first started and is
+/// extended along the canonical chain, including the blocks that were imported while the
+/// indexer was not running or without an import notification, e.g. during the initial sync.
+///
+/// If `keep_blocks` is given, only the entries of that many of the latest indexed blocks are
+/// kept and those of older blocks are removed. It should match the number of blocks whose state
+/// is kept by the client, as the events of the other blocks cannot be fetched anyway.
+///
+/// The returned future never completes and should be spawned as a task.
+pub async fn index_contract_events<C, Block, AccountId, Balance>(
+	client: Arc<C>,
+	keep_blocks: Option<u32>,
+)
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore + BlockchainEvents<Block>,
+	C::Api: ContractsRuntimeApi<
+		Block,
+		AccountId,
+		Balance,
+		<<Block as BlockT>::Header as HeaderT>::Number,
+	>,
+	AccountId: Codec,
+	Balance: Codec,
+{
+	let extend = |client: &C| {
+		if let Err(e) = extend_index::<_, Block, AccountId, Balance>(client, keep_blocks) {
+			warn!("Failed to index contract events: {:?}", e);
+		}
+	};
+
+	extend(&*client);
+	client.import_notification_stream()
+		.for_each(|notification| {
+			let number = *notification.header.number();
+			let range = read_aux::<_, (NumberFor<Block>, NumberFor<Block>)>(
+				&*client,
+				INDEX_RANGE_KEY,
+			).ok().flatten();
+			// Blocks of other forks within the indexed range are indexed as well, so that their
+			// events are found if they become canonical.
+			if range.map_or(false, |(start, end)| start <= number && number <= end) {
+				if let Err(e) = index_block::<_, Block, AccountId, Balance>(
+					&*client,
+					number,
+					notification.hash,
+					None,
+				) {
+					warn!("Failed to index contract events of {:?}: {:?}", notification.hash, e);
+				}
+			}
+			extend(&*client);
+			future::ready(())
+		})
+		.await
+}
+
+/// Extend the indexed range up to the best block, and prune the blocks which are no longer among
+/// the latest `keep_blocks` indexed blocks.
+///
+/// Starts the index with the best block if nothing is indexed yet and the runtime supports
+/// querying contract events.
+fn extend_index<C, Block, AccountId, Balance>(client: &C, keep_blocks: Option<u32>) -> Result<()>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+	C::Api: ContractsRuntimeApi<
+		Block,
+		AccountId,
+		Balance,
+		<<Block as BlockT>::Header as HeaderT>::Number,
+	>,
+	AccountId: Codec,
+	Balance: Codec,
+{
+	let best = client.info().best_number;
+	let range = read_aux::<_, (NumberFor<Block>, NumberFor<Block>)>(client, INDEX_RANGE_KEY)?;
+	let (start, mut end) = match range {
+		Some(range) => range,
+		None => {
+			let hash = match client.hash(best).map_err(client_error_into_rpc_err)? {
+				Some(hash) => hash,
+				None => return Ok(()),
+			};
+			if !supports_contract_events::<_, Block, AccountId, Balance>(client, hash)? {
+				return Ok(());
+			}
+			index_block::<_, Block, AccountId, Balance>(client, best, hash, Some((best, best)))?;
+			(best, best)
+		},
+	};
+
+	while end < best {
+		let number = end + One::one();
+		let hash = match client.hash(number).map_err(client_error_into_rpc_err)? {
+			Some(hash) => hash,
+			None => break,
+		};
+		index_block::<_, Block, AccountId, Balance>(client, number, hash, Some((start, number)))?;
+		end = number;
+	}
+
+	if let Some(keep_blocks) = keep_blocks {
+		let keep_blocks: NumberFor<Block> = keep_blocks.max(1).into();
+		let mut start = start;
+		while end.saturating_sub(start) >= keep_blocks {
+			prune_block::<_, Block>(client, start, (start + One::one(), end))?;
+			start += One::one();
+		}
+	}
+	Ok(())
+}
+
+/// Add the contract events of a single block to the index.
+///
+/// If `range` is given it is stored as the new indexed range together with the entries.
+fn index_block<C, Block, AccountId, Balance>(
+	client: &C,
+	number: NumberFor<Block>,
+	hash: Block::Hash,
+	range: Option<(NumberFor<Block>, NumberFor<Block>)>,
+) -> Result<()>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + AuxStore,
+	C::Api: ContractsRuntimeApi<
+		Block,
+		AccountId,
+		Balance,
+		<<Block as BlockT>::Header as HeaderT>::Number,
+	>,
+	AccountId: Codec,
+	Balance: Codec,
+{
+	let events = block_events::<_, Block, AccountId, Balance>(client, hash)?;
+
+	let mut updates: Vec<(Vec<u8>, Vec<IndexEntry<Block::Hash>>)> = Vec::new();
+	let mut add = |key: Vec<u8>, entry| {
+		match updates.iter_mut().find(|(k, _)| *k == key) {
+			Some((_, entries)) => entries.push(entry),
+			None => updates.push((key, vec![entry])),
+		}
+	};
+	for event in events {
+		let entry = (hash, event.event_index);
+		add(block_key(&address_key(&event.contract), number), entry);
+		for topic in &event.topics {
+			add(block_key(&topic_key(topic), number), entry);
+		}
+	}
+
+	let mut insert = Vec::with_capacity(updates.len() + 2);
+	if let Some(range) = range {
+		insert.push((INDEX_RANGE_KEY.to_vec(), range.encode()));
+	}
+	if !updates.is_empty() {
+		let keys_key = block_key(INDEX_KEYS_PREFIX, number);
+		let mut keys = read_aux::<_, Vec<Vec<u8>>>(client, &keys_key)?.unwrap_or_default();
+		for (key, _) in &updates {
+			if !keys.contains(key) {
+				keys.push(key.clone());
+			}
+		}
+		insert.push((keys_key, keys.encode()));
+	}
+	for (key, mut entries) in updates {
+		// The existing entries only belong to blocks of other forks at the same height.
+		let mut existing = read_aux::<_, Vec<IndexEntry<Block::Hash>>>(client, &key)?
+			.unwrap_or_default();
+		existing.retain(|(h, _)| *h != hash);
+		existing.append(&mut entries);
+		insert.push((key, existing.encode()));
+	}
+
+	let insert = insert.iter().map(|(k, v)| (&k[..], &v[..])).collect::<Vec<_>>();
+	client.insert_aux(&insert, &[]).map_err(client_error_into_rpc_err)
+}
+
+/// Remove the entries of all blocks with the given `number` from the index, and store `range` as
+/// the new indexed range.
+fn prune_block<C, Block>(
+	client: &C,
+	number: NumberFor<Block>,
+	range: (NumberFor<Block>, NumberFor<Block>),
+) -> Result<()>
+where
+	Block: BlockT,
+	C: AuxStore,
+{
+	let keys_key = block_key(INDEX_KEYS_PREFIX, number);
+	let keys = read_aux::<_, Vec<Vec<u8>>>(client, &keys_key)?.unwrap_or_default();
+	let delete = keys.iter()
+		.map(|key| &key[..])
+		.chain(std::iter::once(&keys_key[..]))
+		.collect::<Vec<_>>();
+	client.insert_aux(&[(INDEX_RANGE_KEY, &range.encode()[..])], &delete)
+		.map_err(client_error_into_rpc_err)
+}
+
+/// Whether the runtime of the block with the given `hash` can return its contract events.
+fn supports_contract_events<C, Block, AccountId, Balance>(
+	client: &C,
+	hash: Block::Hash,
+) -> Result<bool>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: ContractsRuntimeApi<
+		Block,
+		AccountId,
+		Balance,
+		<<Block as BlockT>::Header as HeaderT>::Number,
+	>,
+	AccountId: Codec,
+	Balance: Codec,
+{
+	client.runtime_api()
+		.has_api_with::<dyn ContractsRuntimeApi<
+			Block,
+			AccountId,
+			Balance,
+			NumberFor<Block>,
+			Error = (),
+		>, _>(&BlockId::hash(hash), |v| v >= CONTRACT_EVENTS_API_VERSION)
+		.map_err(runtime_error_into_rpc_err)
+}
+
+/// Fetch the contract events deposited in the block with the given `hash`.
+fn block_events<C, Block, AccountId, Balance>(
+	client: &C,
+	hash: Block::Hash,
+) -> Result<Vec<ContractEvent<AccountId, Block::Hash>>>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: ContractsRuntimeApi<
+		Block,
+		AccountId,
+		Balance,
+		<<Block as BlockT>::Header as HeaderT>::Number,
+	>,
+	AccountId: Codec,
+	Balance: Codec,
+{
+	if !supports_contract_events::<_, Block, AccountId, Balance>(client, hash)? {
+		return Err(Error {
+			code: ErrorCode::ServerError(UNSUPPORTED_RUNTIME),
+			message: format!("The runtime of block {:?} does not support contract events", hash),
+			data: None,
+		});
+	}
+	client.runtime_api()
+		.contract_events(&BlockId::hash(hash))
+		.map_err(runtime_error_into_rpc_err)
+}
+
+fn read_aux<C: AuxStore, T: Decode>(client: &C, key: &[u8]) -> Result<Option<T>> {
+	match client.get_aux(key).map_err(client_error_into_rpc_err)? {
+		Some(raw) => T::decode(&mut &raw[..]).map(Some).map_err(|e| Error {
+			code: ErrorCode::ServerError(CLIENT_ERROR),
+			message: "Contract events index is corrupted".into(),
+			data: Some(format!("{:?}", e).into()),
+		}),
+		None => Ok(None),
+	}
+}
+
+fn address_key<AccountId: Encode>(address: &AccountId) -> Vec<u8> {
+	(INDEX_PREFIX, &b"address"[..], address).encode()
+}
+
+fn topic_key<Hash: Encode>(topic: &Hash) -> Vec<u8> {
+	(INDEX_PREFIX, &b"topic"[..], topic).encode()
+}
+
+/// The key under which the entries of `key` in the blocks with the given `number` are stored.
+fn block_key<Number: Encode>(key: &[u8], number: Number) -> Vec<u8> {
+	let mut key = key.to_vec();
+	number.encode_to(&mut key);
+	key
+}
+
+/// The number of blocks in the range `from..=to`.
+fn block_count<Number>((from, to): (Number, Number)) -> u64
+where
+	Number: Saturating + UniqueSaturatedInto<u64>,
+{
+	let count: u64 = to.saturating_sub(from).unique_saturated_into();
+	count.saturating_add(1)
+}
+
+fn client_error_into_rpc_err(err: sp_blockchain::Error) -> Error {
+	Error {
+		code: ErrorCode::ServerError(CLIENT_ERROR),
+		message: "Client error".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn filter_should_deserialize_with_defaults() {
+		let filter: EventFilter<String, String> = serde_json::from_str("{}").unwrap();
+		assert!(filter.address.is_none());
+		assert!(filter.topics.is_empty());
+
+		let filter: EventFilter<String, String> =
+			serde_json::from_str(r#"{"address":"bob","topics":["a"]}"#).unwrap();
+		assert_eq!(filter.address, Some("bob".to_string()));
+		assert_eq!(filter.topics, vec!["a".to_string()]);
+	}
+
+	#[test]
+	fn filter_matches_address_and_all_topics() {
+		let event = ContractEvent {
+			event_index: 0,
+			contract: 1u32,
+			topics: vec![10u32, 20],
+			data: vec![],
+		};
+		let filter = |address, topics| EventFilter { address, topics };
+
+		assert!(filter(None, vec![]).matches(&event));
+		assert!(filter(Some(1), vec![]).matches(&event));
+		assert!(filter(Some(1), vec![20, 10]).matches(&event));
+		assert!(!filter(Some(2), vec![]).matches(&event));
+		assert!(!filter(None, vec![10, 30]).matches(&event));
+	}
+
+	#[test]
+	fn index_keys_are_distinct_per_block() {
+		assert_ne!(block_key(&topic_key(&1u32), 1u32), block_key(&topic_key(&1u32), 2u32));
+		assert_ne!(block_key(&topic_key(&1u32), 1u32), block_key(&address_key(&1u32), 1u32));
+		assert_eq!(block_count((3u32, 3)), 1);
+		assert_eq!(block_count((0u32, 9)), 10);
+	}
+
+	#[derive(Default)]
+	struct MemoryAux(std::sync::Mutex<std::collections::HashMap<Vec<u8>, Vec<u8>>>);
+
+	impl AuxStore for MemoryAux {
+		fn insert_aux<
+			'a,
+			'b: 'a,
+			'c: 'a,
+			I: IntoIterator<Item=&'a(&'c [u8], &'c [u8])>,
+			D: IntoIterator<Item=&'a &'b [u8]>,
+		>(&self, insert: I, delete: D) -> sp_blockchain::Result<()> {
+			let mut aux = self.0.lock().unwrap();
+			for (k, v) in insert {
+				aux.insert(k.to_vec(), v.to_vec());
+			}
+			for k in delete {
+				aux.remove(*k);
+			}
+			Ok(())
+		}
+
+		fn get_aux(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+			Ok(self.0.lock().unwrap().get(key).cloned())
+		}
+	}
+
+	#[test]
+	fn pruned_blocks_are_removed_from_the_index() {
+		type Block = sp_runtime::testing::Block<sp_runtime::testing::ExtrinsicWrapper<u64>>;
+		let aux = MemoryAux::default();
+		let entry = |number: u64| {
+			let key = block_key(&topic_key(&1u32), number);
+			let entries: Vec<IndexEntry<sp_core::H256>> = vec![(Default::default(), 0)];
+			let keys = vec![key.clone()];
+			aux.insert_aux(&[
+				(&key[..], &entries.encode()[..]),
+				(&block_key(INDEX_KEYS_PREFIX, number)[..], &keys.encode()[..]),
+			], &[]).unwrap();
+			key
+		};
+		let first = entry(1);
+		let second = entry(2);
+
+		prune_block::<_, Block>(&aux, 1, (2, 2)).unwrap();
+
+		assert!(aux.get_aux(&first).unwrap().is_none());
+		assert!(aux.get_aux(&block_key(INDEX_KEYS_PREFIX, 1u64)).unwrap().is_none());
+		assert!(aux.get_aux(&second).unwrap().is_some());
+		assert!(aux.get_aux(&block_key(INDEX_KEYS_PREFIX, 2u64)).unwrap().is_some());
+		assert_eq!(read_aux::<_, (u64, u64)>(&aux, INDEX_RANGE_KEY).unwrap(), Some((2, 2)));
+	}
+}
@@ -24,24 +24,35 @@ use jsonrpc_core::{Error, ErrorCode, Result};
 use jsonrpc_derive::rpc;
 use pallet_contracts_primitives::RentProjection;
 use serde::{Deserialize, Serialize};
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_core::{Bytes, H256};
 use sp_rpc::number;
 use sp_runtime::{
 	generic::BlockId,
-	traits::{Block as BlockT, Header as HeaderT},
+	traits::{Block as BlockT, Header as HeaderT, Zero},
 	DispatchError,
 };
 use std::convert::TryInto;
-use pallet_contracts_primitives::ContractExecResult;
+use pallet_contracts_primitives::{
+	Code, CodeInfo, ContractEvent, ContractResult, ContractStorageDiff, ExecError, ExecReturnValue,
+	InstantiateReturnValue, LegacyContractExecResult, StorageDeposit,
+};
 
 pub use pallet_contracts_rpc_runtime_api::ContractsApi as ContractsRuntimeApi;
+pub use events::{
+	index_contract_events, ContractsEvents, ContractsEventsApi, EventFilter, RpcContractEvent,
+};
+
+mod events;
 
 const RUNTIME_ERROR: i64 = 1;
 const CONTRACT_DOESNT_EXIST: i64 = 2;
 const CONTRACT_IS_A_TOMBSTONE: i64 = 3;
 
+/// The version of `ContractsApi` from which `call` returns a [`ContractResult`].
+const CONTRACT_RESULT_API_VERSION: u32 = 3;
+
 /// A rough estimate of how much gas a decent hardware consumes per second,
 /// using native execution.
 /// This value is used to set the upper bound for maximal contract calls to
@@ -84,47 +95,252 @@ pub struct CallRequest<AccountId, Balance> {
 	input_data: Bytes,
 }
 
+/// A struct that encodes RPC parameters required to instantiate a new smart-contract.
 #[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
+pub struct InstantiateRequest<AccountId, Balance, Hash> {
+	origin: AccountId,
+	endowment: Balance,
+	gas_limit: number::NumberOrHex,
+	code: RpcCode<Hash>,
+	data: Bytes,
+	salt: Bytes,
+}
+
+/// Reference to an existing code hash or a new wasm module.
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct RpcContractExecSuccess {
+#[serde(deny_unknown_fields)]
+pub enum RpcCode<Hash> {
+	/// A wasm module that is uploaded as part of the instantiation.
+	Upload(Bytes),
+	/// The hash of code that is already stored on chain.
+	Existing(Hash),
+}
+
+impl<Hash> From<RpcCode<Hash>> for Code<Hash> {
+	fn from(code: RpcCode<Hash>) -> Self {
+		match code {
+			RpcCode::Upload(wasm) => Code::Upload(wasm.to_vec()),
+			RpcCode::Existing(hash) => Code::Existing(hash),
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContractExecSuccess {
 	/// The return flags. See `pallet_contracts_primitives::ReturnFlags`.
 	flags: u32,
 	/// Data as returned by the contract.
 	data: Bytes,
 }
 
-/// An RPC serializable result of contract execution
+impl From<ExecReturnValue> for RpcContractExecSuccess {
+	fn from(val: ExecReturnValue) -> Self {
+		RpcContractExecSuccess {
+			flags: val.flags.bits(),
+			data: val.data.into(),
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContractInstantiateSuccess<AccountId, Hash> {
+	/// The return flags of the constructor. See `pallet_contracts_primitives::ReturnFlags`.
+	flags: u32,
+	/// Data as returned by the constructor.
+	data: Bytes,
+	/// The address of the new contract.
+	account_id: AccountId,
+	/// The hash of the code the contract was instantiated from.
+	code_hash: Hash,
+}
+
+impl<AccountId, Hash> From<InstantiateReturnValue<AccountId, Hash>>
+	for RpcContractInstantiateSuccess<AccountId, Hash>
+{
+	fn from(val: InstantiateReturnValue<AccountId, Hash>) -> Self {
+		RpcContractInstantiateSuccess {
+			flags: val.result.flags.bits(),
+			data: val.result.data.into(),
+			account_id: val.account_id,
+			code_hash: val.code_hash,
+		}
+	}
+}
+
+/// The storage deposit charged or refunded by a contract execution.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcStorageDeposit<Balance> {
+	/// The amount that was refunded to the origin.
+	Refund(Balance),
+	/// The amount that was charged from the origin.
+	Charge(Balance),
+}
+
+impl<Balance> From<StorageDeposit<Balance>> for RpcStorageDeposit<Balance> {
+	fn from(deposit: StorageDeposit<Balance>) -> Self {
+		match deposit {
+			StorageDeposit::Refund(amount) => RpcStorageDeposit::Refund(amount),
+			StorageDeposit::Charge(amount) => RpcStorageDeposit::Charge(amount),
+		}
+	}
+}
+
+/// An event deposited by a contract during a dry run.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcEmittedEvent<AccountId, Hash> {
+	/// The contract that deposited the event.
+	contract: AccountId,
+	/// The topics the event was deposited with.
+	topics: Vec<Hash>,
+	/// The data attached to the event.
+	data: Bytes,
+}
+
+impl<AccountId, Hash> From<ContractEvent<AccountId, Hash>> for RpcEmittedEvent<AccountId, Hash> {
+	fn from(event: ContractEvent<AccountId, Hash>) -> Self {
+		RpcEmittedEvent {
+			contract: event.contract,
+			topics: event.topics,
+			data: event.data.into(),
+		}
+	}
+}
+
+/// A change to a single storage entry of a contract.
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "camelCase")]
-pub struct RpcContractExecResult {
+pub struct RpcStorageChange {
+	/// The key of the changed entry.
+	key: H256,
+	/// The value before the execution or `null` if the entry didn't exist.
+	old_value: Option<Bytes>,
+	/// The value after the execution or `null` if the entry was removed.
+	new_value: Option<Bytes>,
+}
+
+/// All storage changes made to a single contract.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContractStorageDiff<AccountId> {
+	/// The contract whose storage was changed.
+	contract: AccountId,
+	/// The changed entries.
+	changes: Vec<RpcStorageChange>,
+}
+
+impl<AccountId> From<ContractStorageDiff<AccountId>> for RpcContractStorageDiff<AccountId> {
+	fn from(diff: ContractStorageDiff<AccountId>) -> Self {
+		RpcContractStorageDiff {
+			contract: diff.contract,
+			changes: diff.changes
+				.into_iter()
+				.map(|change| RpcStorageChange {
+					key: change.key.into(),
+					old_value: change.old_value.map(Into::into),
+					new_value: change.new_value.map(Into::into),
+				})
+				.collect(),
+		}
+	}
+}
+
+/// Ownership and usage information of uploaded code.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcCodeInfo<AccountId, Balance> {
+	/// The account that uploaded the code and can remove it.
+	owner: AccountId,
+	/// The deposit that is reserved from the owner for as long as the code is stored.
+	deposit: Balance,
+	/// The number of alive contracts that use the code.
+	refcount: u64,
+}
+
+impl<AccountId, Balance> From<CodeInfo<AccountId, Balance>> for RpcCodeInfo<AccountId, Balance> {
+	fn from(info: CodeInfo<AccountId, Balance>) -> Self {
+		RpcCodeInfo {
+			owner: info.owner,
+			deposit: info.deposit,
+			refcount: info.refcount,
+		}
+	}
+}
+
+/// An RPC serializable result of a contract dry run.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContractResult<R, AccountId, Balance, Hash> {
 	/// How much gas was consumed by the call. In case of an error this is the amount
 	/// that was used up until the error occurred.
 	gas_consumed: u64,
-	/// Additional dynamic human readable error information for debugging. An empty string
+	/// The storage deposit that was charged or refunded by the call. This is always
+	/// zero when the runtime uses storage rent.
+	storage_deposit: RpcStorageDeposit<Balance>,
+	/// The output written by the contracts through `seal_println`. An empty string
 	/// indicates that no additional information is available.
 	debug_message: String,
+	/// The events deposited by the contracts.
+	events: Vec<RpcEmittedEvent<AccountId, Hash>>,
+	/// The storage changes of every contract that was written to.
+	storage_diffs: Vec<RpcContractStorageDiff<AccountId>>,
 	/// Indicates whether the contract execution was successful or not.
-	result: std::result::Result<RpcContractExecSuccess, DispatchError>,
+	result: std::result::Result<R, DispatchError>,
 }
 
-impl From<ContractExecResult> for RpcContractExecResult {
-	fn from(r: ContractExecResult) -> Self {
-		match r.exec_result {
-			Ok(val) => RpcContractExecResult {
-				gas_consumed: r.gas_consumed,
-				debug_message: String::new(),
-				result: Ok(RpcContractExecSuccess {
-					flags: val.flags.bits(),
-					data: val.data.into(),
-				}),
-			},
-			Err(err) => RpcContractExecResult {
-				gas_consumed: r.gas_consumed,
-				debug_message: String::new(),
-				result: Err(err.error),
-			},
+/// An RPC serializable result of contract execution.
+pub type RpcContractExecResult<AccountId, Balance, Hash> =
+	RpcContractResult<RpcContractExecSuccess, AccountId, Balance, Hash>;
+
+/// An RPC serializable result of contract instantiation.
+pub type RpcContractInstantiateResult<AccountId, Balance, Hash> =
+	RpcContractResult<RpcContractInstantiateSuccess<AccountId, Hash>, AccountId, Balance, Hash>;
+
+impl<R, S, AccountId, Balance, Hash>
+	From<ContractResult<std::result::Result<S, ExecError>, AccountId, Balance, Hash>>
+	for RpcContractResult<R, AccountId, Balance, Hash>
+where
+	S: Into<R>,
+{
+	fn from(r: ContractResult<std::result::Result<S, ExecError>, AccountId, Balance, Hash>) -> Self {
+		RpcContractResult {
+			gas_consumed: r.gas_consumed,
+			storage_deposit: r.storage_deposit.into(),
+			debug_message: String::from_utf8_lossy(&r.debug_message).into_owned(),
+			events: r.events.into_iter().map(Into::into).collect(),
+			storage_diffs: r.storage_diffs.into_iter().map(Into::into).collect(),
+			result: r.result.map(Into::into).map_err(|err| err.error),
+		}
+	}
+}
+
+/// Results of runtimes which only return the execution result and the consumed gas leave the
+/// other fields empty.
+impl<AccountId, Balance: Zero, Hash> From<LegacyContractExecResult>
+	for RpcContractExecResult<AccountId, Balance, Hash>
+{
+	fn from(r: LegacyContractExecResult) -> Self {
+		RpcContractResult {
+			gas_consumed: r.gas_consumed,
+			storage_deposit: RpcStorageDeposit::Charge(Zero::zero()),
+			debug_message: String::new(),
+			events: Vec::new(),
+			storage_diffs: Vec::new(),
+			result: r.exec_result.map(Into::into).map_err(|err| err.error),
 		}
 	}
 }
@@ -143,7 +359,22 @@ pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance> {
 		&self,
 		call_request: CallRequest<AccountId, Balance>,
 		at: Option<BlockHash>,
-	) -> Result<RpcContractExecResult>;
+	) -> Result<RpcContractExecResult<AccountId, Balance, BlockHash>>;
+
+	/// Instantiate a new contract.
+	///
+	/// This instantiation is performed locally without submitting any transactions. Thus the
+	/// contract is not actually created. The result contains the address and code hash the
+	/// contract would have, together with the events and storage changes of the constructor.
+	///
+	/// This method is useful for checking whether an instantiation would succeed before
+	/// submitting it.
+	#[rpc(name = "contracts_instantiate")]
+	fn instantiate(
+		&self,
+		instantiate_request: InstantiateRequest<AccountId, Balance, BlockHash>,
+		at: Option<BlockHash>,
+	) -> Result<RpcContractInstantiateResult<AccountId, Balance, BlockHash>>;
 
 	/// Returns the value under a specified storage `key` in a contract given by `address` param,
 	/// or `None` if it is not set.
@@ -155,6 +386,17 @@ pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance> {
 		at: Option<BlockHash>,
 	) -> Result<Option<Bytes>>;
 
+	/// Returns the owner, the deposit and the number of contracts using the code
+	/// with the given hash.
+	///
+	/// Returns `None` if the code doesn't exist or was stored without an owner.
+	#[rpc(name = "contracts_codeInfo")]
+	fn code_info(
+		&self,
+		code_hash: BlockHash,
+		at: Option<BlockHash>,
+	) -> Result<Option<RpcCodeInfo<AccountId, Balance>>>;
+
 	/// Returns the projected time a given contract will be able to sustain paying its rent.
 	///
 	/// The returned projection is relevant for the given block, i.e. it is as if the contract was
@@ -201,13 +443,13 @@ where
 		<<Block as BlockT>::Header as HeaderT>::Number,
 	>,
 	AccountId: Codec,
-	Balance: Codec,
+	Balance: Codec + Zero,
 {
 	fn call(
 		&self,
 		call_request: CallRequest<AccountId, Balance>,
 		at: Option<<Block as BlockT>::Hash>,
-	) -> Result<RpcContractExecResult> {
+	) -> Result<RpcContractExecResult<AccountId, Balance, <Block as BlockT>::Hash>> {
 		let api = self.client.runtime_api();
 		let at = BlockId::hash(at.unwrap_or_else(||
 			// If the block hash is not supplied assume the best block.
@@ -221,23 +463,23 @@ where
 			input_data,
 		} = call_request;
 
-		// Make sure that gas_limit fits into 64 bits.
-		let gas_limit: u64 = gas_limit.try_into().map_err(|_| Error {
-			code: ErrorCode::InvalidParams,
-			message: format!("{:?} doesn't fit in 64 bit unsigned value", gas_limit),
-			data: None,
-		})?;
-
-		let max_gas_limit = 5 * GAS_PER_SECOND;
-		if gas_limit > max_gas_limit {
-			return Err(Error {
-				code: ErrorCode::InvalidParams,
-				message: format!(
-					"Requested gas limit is greater than maximum allowed: {} > {}",
-					gas_limit, max_gas_limit
-				),
-				data: None,
-			});
+		let gas_limit = limit_gas(gas_limit)?;
+
+		let has_contract_result = api
+			.has_api_with::<dyn ContractsRuntimeApi<
+				Block,
+				AccountId,
+				Balance,
+				<<Block as BlockT>::Header as HeaderT>::Number,
+				Error = (),
+			>, _>(&at, |v| v >= CONTRACT_RESULT_API_VERSION)
+			.map_err(runtime_error_into_rpc_err)?;
+		if !has_contract_result {
+			#[allow(deprecated)] // old `call`
+			let exec_result = api
+				.call_before_version_3(&at, origin, dest, value, gas_limit, input_data.to_vec())
+				.map_err(runtime_error_into_rpc_err)?;
+			return Ok(exec_result.into())
 		}
 
 		let exec_result = api
@@ -247,6 +489,34 @@ where
 		Ok(exec_result.into())
 	}
 
+	fn instantiate(
+		&self,
+		instantiate_request: InstantiateRequest<AccountId, Balance, <Block as BlockT>::Hash>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RpcContractInstantiateResult<AccountId, Balance, <Block as BlockT>::Hash>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let InstantiateRequest {
+			origin,
+			endowment,
+			gas_limit,
+			code,
+			data,
+			salt,
+		} = instantiate_request;
+
+		let gas_limit = limit_gas(gas_limit)?;
+
+		let instantiate_result = api
+			.instantiate(&at, origin, endowment, gas_limit, code.into(), data.to_vec(), salt.to_vec())
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(instantiate_result.into())
+	}
+
 	fn get_storage(
 		&self,
 		address: AccountId,
@@ -267,6 +537,23 @@ where
 		Ok(result)
 	}
 
+	fn code_info(
+		&self,
+		code_hash: <Block as BlockT>::Hash,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<RpcCodeInfo<AccountId, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let result = api
+			.code_info(&at, code_hash)
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(result.map(Into::into))
+	}
+
 	fn rent_projection(
 		&self,
 		address: AccountId,
@@ -289,6 +576,29 @@ where
 	}
 }
 
+/// Make sure that `gas_limit` fits into 64 bits and doesn't exceed the maximum allowed.
+fn limit_gas(gas_limit: number::NumberOrHex) -> Result<u64> {
+	let gas_limit: u64 = gas_limit.try_into().map_err(|_| Error {
+		code: ErrorCode::InvalidParams,
+		message: format!("{:?} doesn't fit in 64 bit unsigned value", gas_limit),
+		data: None,
+	})?;
+
+	let max_gas_limit = 5 * GAS_PER_SECOND;
+	if gas_limit > max_gas_limit {
+		return Err(Error {
+			code: ErrorCode::InvalidParams,
+			message: format!(
+				"Requested gas limit is greater than maximum allowed: {} > {}",
+				gas_limit, max_gas_limit
+			),
+			data: None,
+		});
+	}
+
+	Ok(gas_limit)
+}
+
 /// Converts a runtime trap into an RPC error.
 fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
 	Error {
@@ -318,14 +628,45 @@ mod tests {
 		assert_eq!(req.gas_limit.into_u256(), U256::from(0xe8d4a51000u64));
 	}
 
+	#[test]
+	fn instantiate_request_should_serialize_deserialize_properly() {
+		type Req = InstantiateRequest<String, u128, H256>;
+		let req: Req = serde_json::from_str(r#"
+		{
+			"origin": "5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL",
+			"endowment": 0,
+			"gasLimit": 1000000000000,
+			"code": { "existing": "0x3333333333333333333333333333333333333333333333333333333333333333" },
+			"data": "0x8c97db39",
+			"salt": "0x"
+		}
+		"#).unwrap();
+		assert_eq!(req.gas_limit.into_u256(), U256::from(0xe8d4a51000u64));
+		match req.code {
+			RpcCode::Existing(hash) => assert_eq!(hash, H256::repeat_byte(0x33)),
+			RpcCode::Upload(_) => panic!("code was given as a hash"),
+		}
+	}
+
 	#[test]
 	fn result_should_serialize_deserialize_properly() {
 		fn test(expected: &str) {
-			let res: RpcContractExecResult = serde_json::from_str(expected).unwrap();
+			let res: RpcContractExecResult<String, u32, H256> = serde_json::from_str(expected).unwrap();
+			let actual = serde_json::to_string(&res).unwrap();
+			assert_eq!(actual, expected);
+		}
+		test(r#"{"gasConsumed":5000,"storageDeposit":{"charge":20},"debugMessage":"helpOk","events":[{"contract":"alice","topics":["0x3333333333333333333333333333333333333333333333333333333333333333"],"data":"0x01"}],"storageDiffs":[{"contract":"alice","changes":[{"key":"0x0101010101010101010101010101010101010101010101010101010101010101","oldValue":null,"newValue":"0x2a"}]}],"result":{"Ok":{"flags":5,"data":"0x1234"}}}"#);
+		test(r#"{"gasConsumed":3400,"storageDeposit":{"refund":0},"debugMessage":"helpErr","events":[],"storageDiffs":[],"result":{"Err":"BadOrigin"}}"#);
+	}
+
+	#[test]
+	fn instantiate_result_should_serialize_deserialize_properly() {
+		fn test(expected: &str) {
+			let res: RpcContractInstantiateResult<String, u32, H256> =
+				serde_json::from_str(expected).unwrap();
 			let actual = serde_json::to_string(&res).unwrap();
 			assert_eq!(actual, expected);
 		}
-		test(r#"{"gasConsumed":5000,"debugMessage":"helpOk","result":{"Ok":{"flags":5,"data":"0x1234"}}}"#);
-		test(r#"{"gasConsumed":3400,"debugMessage":"helpErr","result":{"Err":"BadOrigin"}}"#);
+		test(r#"{"gasConsumed":5000,"storageDeposit":{"charge":0},"debugMessage":"","events":[],"storageDiffs":[],"result":{"Ok":{"flags":0,"data":"0x","accountId":"bob","codeHash":"0x3333333333333333333333333333333333333333333333333333333333333333"}}}"#);
 	}
 }
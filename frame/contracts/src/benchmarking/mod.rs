@@ -24,6 +24,8 @@ mod sandbox;
 
 use crate::{
 	*, Module as Contracts,
+	chain_extension::ChainExtension,
+	deposit::Deposit,
 	exec::StorageKey,
 	rent::Rent,
 	schedule::{API_BENCHMARK_BATCH_SIZE, INSTR_BENCHMARK_BATCH_SIZE},
@@ -281,13 +283,32 @@ benchmarks! {
 	// This constructs a contract that is maximal expensive to instrument.
 	// It creates a maximum number of metering blocks per byte.
 	// `n`: Size of the code in kilobytes.
-	put_code {
+	upload_code {
 		let n in 0 .. Contracts::<T>::current_schedule().limits.code_size / 1024;
 		let caller = whitelisted_caller();
 		T::Currency::make_free_balance_be(&caller, caller_funding::<T>());
 		let module = WasmModule::<T>::sized(n * 1024);
+		let hash = module.hash.clone();
 		let origin = RawOrigin::Signed(caller);
 	}: _(origin, module.code)
+	verify {
+		assert!(<crate::OwnerInfoOf<T>>::contains_key(&hash));
+	}
+
+	// Removing code does not depend on the size of the code because all storage items
+	// are removed without reading them.
+	remove_code {
+		let caller = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, caller_funding::<T>());
+		let WasmModule { code, hash, .. } = WasmModule::<T>::dummy();
+		let origin = RawOrigin::Signed(caller.clone());
+		Contracts::<T>::upload_code(origin.clone().into(), code)?;
+		assert!(<crate::OwnerInfoOf<T>>::contains_key(&hash));
+	}: _(origin, hash)
+	verify {
+		assert!(!<crate::OwnerInfoOf<T>>::contains_key(&hash));
+		assert_eq!(T::Currency::reserved_balance(&caller), 0u32.into());
+	}
 
 	// Instantiate uses a dummy contract constructor to measure the overhead of the instantiate.
 	// The size of the input data influences the runtime because it is hashed in order to determine
@@ -378,6 +399,20 @@ benchmarks! {
 		);
 	}
 
+	// We benchmark settling the storage deposit of a contract whose storage grew by one
+	// maximum sized item. This is the more expensive direction as the deposit is transferred
+	// from the origin and reserved by the contract. Only used when storage deposits are enabled.
+	settle_storage_deposit {
+		let instance = Contract::<T>::new(WasmModule::dummy(), vec![], Endow::Max)?;
+		instance.store(&create_storage::<T>(1, T::MaxValueSize::get())?)?;
+		let before = instance.alive_info()?.storage_deposit;
+	}: {
+		Deposit::<T>::settle_enabled(&instance.caller, &instance.account_id)?;
+	}
+	verify {
+		assert!(instance.alive_info()?.storage_deposit > before);
+	}
+
 	seal_caller {
 		let r in 0 .. API_BENCHMARK_BATCHES;
 		let instance = Contract::<T>::new(WasmModule::getter(
@@ -1611,6 +1646,44 @@ benchmarks! {
 		let origin = RawOrigin::Signed(instance.caller.clone());
 	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
 
+	// Only the overhead of entering the chain extension: function 0 of the chain extension
+	// should not do any work. Runtimes without a chain extension trap before it is charged,
+	// so the contract does not call it at all in that case.
+	seal_call_chain_extension {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let repeat = if <T::ChainExtension as ChainExtension<T>>::enabled() {
+			r * API_BENCHMARK_BATCH_SIZE
+		} else {
+			0
+		};
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				name: "seal_call_chain_extension",
+				params: vec![ValueType::I32; 5],
+				return_type: Some(ValueType::I32),
+			}],
+			data_segments: vec![
+				DataSegment {
+					offset: 0,
+					value: 0u32.to_le_bytes().to_vec(),
+				},
+			],
+			call_body: Some(body::repeated(repeat, &[
+				Instruction::I32Const(0), // func_id
+				Instruction::I32Const(4), // input_ptr
+				Instruction::I32Const(0), // input_len
+				Instruction::I32Const(4), // output_ptr
+				Instruction::I32Const(0), // output_len_ptr
+				Instruction::Call(0),
+				Instruction::Drop,
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
 	// We make the assumption that pushing a constant and dropping a value takes roughly
 	// the same amount of time. We follow that `t.load` and `drop` both have the weight
 	// of this benchmark / 2. We need to make this assumption because there is no way
@@ -2395,10 +2468,12 @@ mod tests {
 	}
 
 	create_test!(update_schedule);
-	create_test!(put_code);
+	create_test!(upload_code);
+	create_test!(remove_code);
 	create_test!(instantiate);
 	create_test!(call);
 	create_test!(claim_surcharge);
+	create_test!(settle_storage_deposit);
 
 	create_test!(seal_caller);
 	create_test!(seal_address);
@@ -2441,6 +2516,7 @@ mod tests {
 	create_test!(seal_hash_blake2_256_per_kb);
 	create_test!(seal_hash_blake2_128);
 	create_test!(seal_hash_blake2_128_per_kb);
+	create_test!(seal_call_chain_extension);
 
 	create_test!(instr_i64const);
 	create_test!(instr_i64load);
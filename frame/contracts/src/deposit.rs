@@ -0,0 +1,124 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A module responsible for charging and refunding storage deposits.
+//!
+//! This is only active when the runtime selected [`StorageModel::Deposit`].
+
+use crate::{
+	AliveContractInfo, BalanceOf, ContractInfo, ContractInfoOf, Config, Error, StorageModel,
+};
+use sp_std::marker::PhantomData;
+use sp_core::crypto::UncheckedFrom;
+use sp_runtime::{DispatchError, traits::{Saturating, Zero}};
+use frame_support::{
+	StorageMap,
+	traits::{Currency, ExistenceRequirement, Get, ReservableCurrency},
+};
+use pallet_contracts_primitives::StorageDeposit;
+
+pub struct Deposit<T>(PhantomData<T>);
+
+impl<T> Deposit<T>
+where
+	T: Config,
+	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>
+{
+	/// Returns whether the runtime uses storage deposits instead of rent.
+	pub fn is_enabled() -> bool {
+		T::StorageModel::get() == StorageModel::Deposit
+	}
+
+	/// The deposit required for the storage that the given contract currently occupies.
+	pub fn required(contract: &AliveContractInfo<T>) -> BalanceOf<T> {
+		T::DepositPerByte::get()
+			.saturating_mul(contract.storage_size.into())
+			.saturating_add(T::DepositPerItem::get().saturating_mul(contract.total_pair_count.into()))
+	}
+
+	/// Bring the deposit held by the contract at `account` in line with its storage usage.
+	///
+	/// Missing deposit is transferred from `origin` to the contract and reserved there. Excess
+	/// deposit is unreserved and transferred to `origin`, which freed the storage.
+	///
+	/// Does nothing if storage deposits are not enabled.
+	pub fn settle(
+		origin: &T::AccountId,
+		account: &T::AccountId,
+	) -> Result<StorageDeposit<BalanceOf<T>>, DispatchError> {
+		if !Self::is_enabled() {
+			return Ok(StorageDeposit::default());
+		}
+		Self::settle_enabled(origin, account)
+	}
+
+	/// Like [`Self::settle`] but assumes that storage deposits are enabled.
+	pub(crate) fn settle_enabled(
+		origin: &T::AccountId,
+		account: &T::AccountId,
+	) -> Result<StorageDeposit<BalanceOf<T>>, DispatchError> {
+		let mut contract = match <ContractInfoOf<T>>::get(account) {
+			Some(ContractInfo::Alive(contract)) => contract,
+			None | Some(ContractInfo::Tombstone(_)) => return Ok(StorageDeposit::default()),
+		};
+
+		let required = Self::required(&contract);
+		let deposit = if required > contract.storage_deposit {
+			let charge = required.saturating_sub(contract.storage_deposit);
+			T::Currency::transfer(origin, account, charge, ExistenceRequirement::KeepAlive)
+				.and_then(|_| T::Currency::reserve(account, charge))
+				.map_err(|_| Error::<T>::StorageDepositNotEnoughFunds)?;
+			StorageDeposit::Charge(charge)
+		} else if required < contract.storage_deposit {
+			let refund = contract.storage_deposit.saturating_sub(required);
+			Self::refund(origin, account, refund)?;
+			StorageDeposit::Refund(refund)
+		} else {
+			return Ok(StorageDeposit::default());
+		};
+
+		contract.storage_deposit = required;
+		<ContractInfoOf<T>>::insert(account, ContractInfo::Alive(contract));
+		Ok(deposit)
+	}
+
+	/// Refund the whole deposit held by the contract at `account` to `origin`.
+	///
+	/// This is used when a contract is removed together with all of its storage.
+	pub fn refund_all(
+		origin: &T::AccountId,
+		account: &T::AccountId,
+		contract: &AliveContractInfo<T>,
+	) -> Result<StorageDeposit<BalanceOf<T>>, DispatchError> {
+		if contract.storage_deposit.is_zero() {
+			return Ok(StorageDeposit::default());
+		}
+		Self::refund(origin, account, contract.storage_deposit)?;
+		Ok(StorageDeposit::Refund(contract.storage_deposit))
+	}
+
+	/// Unreserve `amount` of the deposit held by the contract at `account` and transfer it to
+	/// `origin`. The contract is kept alive.
+	fn refund(
+		origin: &T::AccountId,
+		account: &T::AccountId,
+		amount: BalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		T::Currency::unreserve(account, amount);
+		T::Currency::transfer(account, origin, amount, ExistenceRequirement::KeepAlive)
+	}
+}
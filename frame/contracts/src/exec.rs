@@ -17,7 +17,7 @@
 use crate::{
 	CodeHash, ConfigCache, Event, RawEvent, Config, Module as Contracts,
 	TrieId, BalanceOf, ContractInfo, gas::GasMeter, rent::Rent, storage::{self, Storage},
-	Error, ContractInfoOf
+	Error, ContractInfoOf, deposit::Deposit,
 };
 use sp_core::crypto::UncheckedFrom;
 use sp_std::prelude::*;
@@ -28,7 +28,10 @@ use frame_support::{
 	weights::Weight,
 	ensure, StorageMap,
 };
-use pallet_contracts_primitives::{ErrorOrigin, ExecError, ExecReturnValue, ExecResult, ReturnFlags};
+use pallet_contracts_primitives::{
+	ContractStorageDiff, ErrorOrigin, ExecError, ExecReturnValue, ExecResult, ReturnFlags,
+	StorageChange, StorageDeposit,
+};
 
 pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 pub type MomentOf<T> = <<T as Config>::Time as Time>::Moment;
@@ -64,7 +67,9 @@ pub trait Ext {
 
 	/// Sets the storage entry by the given key to the specified value. If `value` is `None` then
 	/// the storage entry is deleted.
-	fn set_storage(&mut self, key: StorageKey, value: Option<Vec<u8>>);
+	///
+	/// Returns `Err` if the storage deposit required for the new value could not be paid.
+	fn set_storage(&mut self, key: StorageKey, value: Option<Vec<u8>>) -> Result<(), DispatchError>;
 
 	/// Instantiate a contract from the given code.
 	///
@@ -167,6 +172,11 @@ pub trait Ext {
 
 	/// Returns the price for the specified amount of weight.
 	fn get_weight_price(&self, weight: Weight) -> BalanceOf<Self::T>;
+
+	/// Append a string to the debug buffer.
+	///
+	/// Returns `false` if the execution is not recorded and the message was discarded.
+	fn append_debug_buffer(&mut self, msg: &str) -> bool;
 }
 
 /// Loader is a companion of the `Vm` trait. It loads an appropriate abstract
@@ -202,6 +212,62 @@ pub trait Vm<T: Config> {
 	) -> ExecResult;
 }
 
+/// Information that is collected while executing a dry run.
+pub struct ExecutionRecord<T: Config> {
+	/// The output of `seal_println` in the order it was emitted.
+	pub debug_message: Vec<u8>,
+	/// All storage writes in the order they were made.
+	pub storage_changes: Vec<(T::AccountId, StorageChange)>,
+}
+
+impl<T: Config> Default for ExecutionRecord<T> {
+	fn default() -> Self {
+		ExecutionRecord {
+			debug_message: Vec::new(),
+			storage_changes: Vec::new(),
+		}
+	}
+}
+
+impl<T: Config> ExecutionRecord<T> {
+	/// Merge the record of a nested context into this one.
+	///
+	/// The storage changes of a reverted context are dropped because they were rolled back.
+	/// Its debug output is kept as it is most useful exactly in that case.
+	fn merge(&mut self, nested: Self, committed: bool) {
+		self.debug_message.extend(nested.debug_message);
+		if committed {
+			self.storage_changes.extend(nested.storage_changes);
+		}
+	}
+
+	/// Collapse the recorded storage writes into one diff per contract.
+	///
+	/// Repeated writes to the same key are combined and entries that end up with their
+	/// original value are omitted.
+	pub fn storage_diffs(self) -> Vec<ContractStorageDiff<T::AccountId>> {
+		let mut diffs: Vec<ContractStorageDiff<T::AccountId>> = Vec::new();
+		for (contract, change) in self.storage_changes {
+			let diff = match diffs.iter().position(|diff| diff.contract == contract) {
+				Some(index) => &mut diffs[index],
+				None => {
+					diffs.push(ContractStorageDiff { contract, changes: Vec::new() });
+					diffs.last_mut().expect("an element was just pushed; qed")
+				}
+			};
+			match diff.changes.iter_mut().find(|existing| existing.key == change.key) {
+				Some(existing) => existing.new_value = change.new_value,
+				None => diff.changes.push(change),
+			}
+		}
+		for diff in diffs.iter_mut() {
+			diff.changes.retain(|change| change.old_value != change.new_value);
+		}
+		diffs.retain(|diff| !diff.changes.is_empty());
+		diffs
+	}
+}
+
 pub struct ExecutionContext<'a, T: Config + 'a, V, L> {
 	pub caller: Option<&'a ExecutionContext<'a, T, V, L>>,
 	pub self_account: T::AccountId,
@@ -212,6 +278,13 @@ pub struct ExecutionContext<'a, T: Config + 'a, V, L> {
 	pub loader: &'a L,
 	pub timestamp: MomentOf<T>,
 	pub block_number: T::BlockNumber,
+	/// The net storage deposit charged or refunded by this context and its committed
+	/// nested contexts.
+	pub storage_deposit: StorageDeposit<BalanceOf<T>>,
+	/// Collects debug output and storage changes when set.
+	///
+	/// This is only enabled for dry runs.
+	pub record: Option<ExecutionRecord<T>>,
 }
 
 impl<'a, T, E, V, L> ExecutionContext<'a, T, V, L>
@@ -236,6 +309,8 @@ where
 			loader: &loader,
 			timestamp: T::Time::now(),
 			block_number: <frame_system::Module<T>>::block_number(),
+			storage_deposit: Default::default(),
+			record: None,
 		}
 	}
 
@@ -252,6 +327,8 @@ where
 			loader: self.loader,
 			timestamp: self.timestamp.clone(),
 			block_number: self.block_number.clone(),
+			storage_deposit: Default::default(),
+			record: self.record.as_ref().map(|_| Default::default()),
 		}
 	}
 
@@ -396,13 +473,27 @@ where
 	{
 		use frame_support::storage::TransactionOutcome::*;
 		let mut nested = self.nested(dest, trie_id);
-		frame_support::storage::with_transaction(|| {
+		let output = frame_support::storage::with_transaction(|| {
 			let output = func(&mut nested);
 			match output {
 				Ok(ref rv) if !rv.flags.contains(ReturnFlags::REVERT) => Commit(output),
 				_ => Rollback(output),
 			}
-		})
+		});
+		// Deposits of rolled back contexts were reverted together with their storage.
+		let committed = matches!(output, Ok(ref rv) if !rv.flags.contains(ReturnFlags::REVERT));
+		if committed {
+			self.storage_deposit = self.storage_deposit.saturating_add(&nested.storage_deposit);
+		}
+		if let (Some(record), Some(nested_record)) = (self.record.as_mut(), nested.record) {
+			record.merge(nested_record, committed);
+		}
+		output
+	}
+
+	/// Returns the account that initiated the top level call.
+	pub fn origin(&self) -> &T::AccountId {
+		self.caller.map_or(&self.self_account, |caller| caller.origin())
 	}
 
 	/// Returns whether a contract, identified by address, is currently live in the execution
@@ -510,13 +601,18 @@ where
 		Storage::<T>::read(trie_id, key)
 	}
 
-	fn set_storage(&mut self, key: StorageKey, value: Option<Vec<u8>>) {
+	fn set_storage(&mut self, key: StorageKey, value: Option<Vec<u8>>) -> Result<(), DispatchError> {
 		let trie_id = self.ctx.self_trie_id.as_ref().expect(
 			"`ctx.self_trie_id` points to an alive contract within the `CallContext`;\
 				it cannot be `None`;\
 				expect can't fail;\
 				qed",
 		);
+		let change = self.ctx.record.as_ref().map(|_| StorageChange {
+			key,
+			old_value: Storage::<T>::read(trie_id, &key),
+			new_value: value.clone(),
+		});
 		if let Err(storage::ContractAbsentError) =
 			Storage::<T>::write(&self.ctx.self_account, trie_id, &key, value)
 		{
@@ -527,6 +623,13 @@ where
 				qed"
 			);
 		}
+		if let (Some(record), Some(change)) = (self.ctx.record.as_mut(), change) {
+			record.storage_changes.push((self.ctx.self_account.clone(), change));
+		}
+		let origin = self.ctx.origin().clone();
+		let deposit = Deposit::<T>::settle(&origin, &self.ctx.self_account)?;
+		self.ctx.storage_deposit = self.ctx.storage_deposit.saturating_add(&deposit);
+		Ok(())
 	}
 
 	fn instantiate(
@@ -560,12 +663,19 @@ where
 		beneficiary: &AccountIdOf<Self::T>,
 	) -> Result<(), DispatchError> {
 		let self_id = self.ctx.self_account.clone();
-		let value = T::Currency::free_balance(&self_id);
 		if let Some(caller_ctx) = self.ctx.caller {
 			if caller_ctx.is_live(&self_id) {
 				return Err(Error::<T>::ReentranceDenied.into());
 			}
 		}
+		if Deposit::<T>::is_enabled() {
+			if let Some(ContractInfo::Alive(contract)) = <ContractInfoOf<T>>::get(&self_id) {
+				let origin = self.ctx.origin().clone();
+				let refund = Deposit::<T>::refund_all(&origin, &self_id, &contract)?;
+				self.ctx.storage_deposit = self.ctx.storage_deposit.saturating_add(&refund);
+			}
+		}
+		let value = T::Currency::free_balance(&self_id);
 		transfer(
 			TransferCause::Terminate,
 			TransactorKind::Contract,
@@ -691,6 +801,15 @@ where
 	fn get_weight_price(&self, weight: Weight) -> BalanceOf<Self::T> {
 		T::WeightPrice::convert(weight)
 	}
+
+	fn append_debug_buffer(&mut self, msg: &str) -> bool {
+		if let Some(record) = self.ctx.record.as_mut() {
+			record.debug_message.extend(msg.as_bytes());
+			true
+		} else {
+			false
+		}
+	}
 }
 
 fn deposit_event<T: Config>(
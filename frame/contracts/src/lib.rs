@@ -38,6 +38,16 @@
 //! Finally, when an account is reaped, its associated code and storage of the smart-contract account
 //! will also be deleted.
 //!
+//! ### Storage
+//!
+//! Contracts pay for the storage they occupy according to the [`StorageModel`] selected by the
+//! runtime. With [`StorageModel::Rent`] contracts pay rent for their storage every block and are
+//! evicted to tombstones when they can no longer afford it. With [`StorageModel::Deposit`] the
+//! origin of a transaction pays a refundable deposit for every byte and every item that it adds
+//! to the storage of a contract. The deposit is held by the contract and tracked in its
+//! [`RawAliveContractInfo::storage_deposit`]. When storage is removed, the deposit is refunded to
+//! the origin of the transaction that removed it.
+//!
 //! ### Gas
 //!
 //! Senders must specify a gas limit with every call, as all instructions invoked by the smart-contract require gas.
@@ -58,7 +68,10 @@
 //!
 //! ### Dispatchable functions
 //!
-//! * `put_code` - Stores the given binary Wasm code into the chain's storage and returns its `code_hash`.
+//! * `upload_code` - Stores the given binary Wasm code into the chain's storage and returns its `code_hash`.
+//! The uploader becomes the owner of the code and reserves a deposit for it.
+//! * `remove_code` - Removes code that is no longer used by any contract and refunds the deposit
+//! to its owner.
 //! * `instantiate` - Deploys a new contract from the given `code_hash`, optionally transferring some balance.
 //! This instantiates a new smart contract account and calls its contract deploy handler to
 //! initialize the contract.
@@ -86,9 +99,12 @@ mod storage;
 mod exec;
 mod wasm;
 mod rent;
+mod deposit;
 mod benchmarking;
 mod schedule;
+mod migration;
 pub mod weights;
+pub mod chain_extension;
 
 #[cfg(test)]
 mod tests;
@@ -103,10 +119,11 @@ use crate::{
 	exec::ExecutionContext,
 	wasm::{WasmLoader, WasmVm},
 	rent::Rent,
+	deposit::Deposit,
 	storage::Storage,
 };
 use sp_core::crypto::UncheckedFrom;
-use sp_std::{prelude::*, marker::PhantomData, fmt::Debug};
+use sp_std::{prelude::*, marker::PhantomData, fmt::Debug, convert::TryInto};
 use codec::{Codec, Encode, Decode};
 use sp_runtime::{
 	traits::{
@@ -118,11 +135,13 @@ use frame_support::{
 	decl_module, decl_event, decl_storage, decl_error, ensure,
 	storage::child::ChildInfo,
 	dispatch::{DispatchResult, DispatchResultWithPostInfo},
-	traits::{OnUnbalanced, Currency, Get, Time, Randomness},
+	traits::{OnUnbalanced, Currency, ReservableCurrency, Get, Time, Randomness},
 };
 use frame_system::{ensure_signed, ensure_root};
 use pallet_contracts_primitives::{
 	RentProjectionResult, GetStorageResult, ContractAccessError, ContractExecResult, ExecResult,
+	ContractEvent, ContractResult, ContractInstantiateResult, Code, ExecError,
+	InstantiateReturnValue, CodeInfo,
 };
 use frame_support::weights::Weight;
 
@@ -215,6 +234,10 @@ pub struct RawAliveContractInfo<CodeHash, Balance, BlockNumber> {
 	pub deduct_block: BlockNumber,
 	/// Last block child storage has been written.
 	pub last_write: Option<BlockNumber>,
+	/// The deposit currently held by this contract in order to pay for its storage.
+	///
+	/// This is always zero when the rent storage model is used.
+	pub storage_deposit: Balance,
 }
 
 impl<CodeHash, Balance, BlockNumber> RawAliveContractInfo<CodeHash, Balance, BlockNumber> {
@@ -250,6 +273,19 @@ where
 	}
 }
 
+pub type OwnerInfo<T> = RawOwnerInfo<<T as frame_system::Config>::AccountId, BalanceOf<T>>;
+
+/// Information about the owner of uploaded code.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct RawOwnerInfo<AccountId, Balance> {
+	/// The account that uploaded the code and can remove it.
+	pub owner: AccountId,
+	/// The deposit that is reserved from the owner for as long as the code is stored.
+	pub deposit: Balance,
+	/// The number of alive contracts and tombstones that use this code.
+	pub refcount: u64,
+}
+
 impl<T: Config> From<AliveContractInfo<T>> for ContractInfo<T> {
 	fn from(alive_info: AliveContractInfo<T>) -> Self {
 		Self::Alive(alive_info)
@@ -261,12 +297,29 @@ pub type BalanceOf<T> =
 pub type NegativeImbalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
 
+/// The economic model that is used to pay for the storage occupied by contracts.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+pub enum StorageModel {
+	/// Contracts pay rent for their storage and get evicted when they can't afford it.
+	Rent,
+	/// The origin of a transaction pays a refundable deposit for the storage it adds.
+	///
+	/// Contracts are never evicted in this model.
+	Deposit,
+}
+
+impl Default for StorageModel {
+	fn default() -> Self {
+		Self::Rent
+	}
+}
+
 pub trait Config: frame_system::Config {
 	type Time: Time;
 	type Randomness: Randomness<Self::Hash>;
 
 	/// The currency in which fees are paid and contract balances are held.
-	type Currency: Currency<Self::AccountId>;
+	type Currency: ReservableCurrency<Self::AccountId>;
 
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
@@ -306,6 +359,18 @@ pub trait Config: frame_system::Config {
 	/// to removal of a contract.
 	type SurchargeReward: Get<BalanceOf<Self>>;
 
+	/// The economic model that is used to pay for the storage of contracts.
+	type StorageModel: Get<StorageModel>;
+
+	/// The deposit that is charged per byte of contract storage in the deposit storage model.
+	type DepositPerByte: Get<BalanceOf<Self>>;
+
+	/// The deposit that is charged per storage item in the deposit storage model.
+	type DepositPerItem: Get<BalanceOf<Self>>;
+
+	/// The deposit that is reserved from the uploader per byte of uploaded code.
+	type CodeDepositPerByte: Get<BalanceOf<Self>>;
+
 	/// The maximum nesting level of a call/instantiate stack.
 	type MaxDepth: Get<u32>;
 
@@ -319,6 +384,9 @@ pub trait Config: frame_system::Config {
 	/// Describes the weights of the dispatchables of this module and is also used to
 	/// construct a default cost schedule.
 	type WeightInfo: WeightInfo;
+
+	/// Type that allows the runtime authors to add new host functions for a contract to call.
+	type ChainExtension: chain_extension::ChainExtension<Self>;
 }
 
 decl_error! {
@@ -362,8 +430,16 @@ decl_error! {
 		/// The contract that was called is either no contract at all (a plain account)
 		/// or is a tombstone.
 		NotCallable,
-		/// The code supplied to `put_code` exceeds the limit specified in the current schedule.
+		/// The code supplied to `upload_code` exceeds the limit specified in the current schedule.
 		CodeTooLarge,
+		/// The code supplied to `upload_code` is already stored.
+		CodeExists,
+		/// The uploader doesn't have enough free balance to reserve the code deposit.
+		CodeDepositNotEnoughFunds,
+		/// Only the owner of the code is allowed to remove it.
+		NotCodeOwner,
+		/// The code can't be removed because there are still contracts using it.
+		CodeInUse,
 		/// No code could be found at the supplied code hash.
 		CodeNotFound,
 		/// A buffer outside of sandbox memory was passed to a contract API function.
@@ -378,6 +454,12 @@ decl_error! {
 		/// on the call stack. Those actions are contract self destruction and restoration
 		/// of a tombstone.
 		ReentranceDenied,
+		/// The origin doesn't have enough balance to pay the storage deposit required
+		/// by the storage it added.
+		StorageDepositNotEnoughFunds,
+		/// The contract called `seal_call_chain_extension` but the chain does not
+		/// provide a chain extension.
+		NoChainExtension,
 	}
 }
 
@@ -424,6 +506,18 @@ decl_module! {
 		/// to removal of a contract.
 		const SurchargeReward: BalanceOf<T> = T::SurchargeReward::get();
 
+		/// The economic model that is used to pay for the storage of contracts.
+		const StorageModel: StorageModel = T::StorageModel::get();
+
+		/// The deposit that is charged per byte of contract storage in the deposit storage model.
+		const DepositPerByte: BalanceOf<T> = T::DepositPerByte::get();
+
+		/// The deposit that is charged per storage item in the deposit storage model.
+		const DepositPerItem: BalanceOf<T> = T::DepositPerItem::get();
+
+		/// The deposit that is reserved from the uploader per byte of uploaded code.
+		const CodeDepositPerByte: BalanceOf<T> = T::CodeDepositPerByte::get();
+
 		/// The maximum nesting level of a call/instantiate stack. A reasonable default
 		/// value is 100.
 		const MaxDepth: u32 = T::MaxDepth::get();
@@ -433,6 +527,10 @@ decl_module! {
 
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			migration::migrate::<T>()
+		}
+
 		/// Updates the schedule for metering contracts.
 		///
 		/// The schedule must have a greater version than the stored schedule.
@@ -451,19 +549,19 @@ decl_module! {
 
 		/// Stores the given binary Wasm code into the chain's storage and returns its `codehash`.
 		/// You can instantiate contracts only with stored code.
-		#[weight = T::WeightInfo::put_code(code.len() as u32 / 1024)]
-		pub fn put_code(
+		///
+		/// The sender becomes the owner of the code. A deposit of `CodeDepositPerByte` for
+		/// every byte of the code is reserved from the sender until the code is removed.
+		#[weight = T::WeightInfo::upload_code(code.len() as u32 / 1024)]
+		pub fn upload_code(
 			origin,
 			code: Vec<u8>
 		) -> DispatchResult {
-			ensure_signed(origin)?;
+			let origin = ensure_signed(origin)?;
 			let schedule = <Module<T>>::current_schedule();
 			ensure!(code.len() as u32 <= schedule.limits.code_size, Error::<T>::CodeTooLarge);
-			let result = wasm::save_code::<T>(code, &schedule);
-			if let Ok(code_hash) = result {
-				Self::deposit_event(RawEvent::CodeStored(code_hash));
-			}
-			result.map(|_| ()).map_err(Into::into)
+			wasm::save_code::<T>(code, &schedule, &origin)?;
+			Ok(())
 		}
 
 		/// Makes a call to an account, optionally transferring some balance.
@@ -491,7 +589,7 @@ decl_module! {
 			gas_meter.into_dispatch_result(result)
 		}
 
-		/// Instantiates a new contract from the `code_hash` generated by `put_code`,
+		/// Instantiates a new contract from the `code_hash` generated by `upload_code`,
 		/// optionally transferring some balance.
 		///
 		/// The supplied `salt` is used for contract address deriviation. See `fn contract_address`.
@@ -560,6 +658,16 @@ decl_module! {
 				T::Currency::deposit_into_existing(&rewarded, T::SurchargeReward::get())?;
 			}
 		}
+
+		/// Removes the code stored under `code_hash` and refunds the deposit to its owner.
+		///
+		/// Only the owner of the code can remove it and only if no contract uses it anymore.
+		/// Code that was stored without an owner can't be removed.
+		#[weight = T::WeightInfo::remove_code()]
+		pub fn remove_code(origin, code_hash: CodeHash<T>) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			wasm::remove_code::<T>(&origin, code_hash)
+		}
 	}
 }
 
@@ -573,23 +681,66 @@ where
 	/// This function is similar to `Self::call`, but doesn't perform any address lookups and better
 	/// suitable for calling directly from Rust.
 	///
-	/// It returns the exection result and the amount of used weight.
+	/// Besides the execution result it returns everything that is useful to know about a dry run:
+	/// the amount of used weight, the storage deposit that was charged or refunded, the output of
+	/// `seal_println`, the deposited contract events and the storage changes of every contract.
 	pub fn bare_call(
 		origin: T::AccountId,
 		dest: T::AccountId,
 		value: BalanceOf<T>,
 		gas_limit: Gas,
 		input_data: Vec<u8>,
-	) -> ContractExecResult {
+	) -> ContractExecResult<T::AccountId, BalanceOf<T>, T::Hash>
+	where
+		<T as frame_system::Config>::Event: TryInto<Event<T>>,
+	{
 		let mut gas_meter = GasMeter::new(gas_limit);
-		let exec_result = Self::execute_wasm(origin, &mut gas_meter, |ctx, gas_meter| {
+		Self::execute_recorded(origin, &mut gas_meter, |ctx, gas_meter| {
 			ctx.call(dest, value, gas_meter, input_data)
-		});
-		let gas_consumed = gas_meter.gas_spent();
-		ContractExecResult {
-			exec_result,
-			gas_consumed,
-		}
+		})
+	}
+
+	/// Instantiate a new contract.
+	///
+	/// This function is similar to `Self::instantiate`, but the code can either be passed as
+	/// an already stored code hash or be uploaded as part of the instantiation. It returns
+	/// the address of the new contract and the hash of its code together with the same
+	/// information as `Self::bare_call`.
+	pub fn bare_instantiate(
+		origin: T::AccountId,
+		endowment: BalanceOf<T>,
+		gas_limit: Gas,
+		code: Code<CodeHash<T>>,
+		data: Vec<u8>,
+		salt: Vec<u8>,
+	) -> ContractInstantiateResult<T::AccountId, BalanceOf<T>, T::Hash>
+	where
+		<T as frame_system::Config>::Event: TryInto<Event<T>>,
+	{
+		let mut gas_meter = GasMeter::new(gas_limit);
+		Self::execute_recorded(origin, &mut gas_meter, |ctx, gas_meter| {
+			let code_hash = match code {
+				Code::Existing(code_hash) => code_hash,
+				Code::Upload(code) => {
+					let schedule = <Module<T>>::current_schedule();
+					ensure!(
+						code.len() as u32 <= schedule.limits.code_size,
+						Error::<T>::CodeTooLarge,
+					);
+					let code_hash = T::Hashing::hash(&code);
+					if !<PristineCode<T>>::contains_key(&code_hash) {
+						wasm::save_code::<T>(code, &schedule, ctx.origin())?;
+					}
+					code_hash
+				}
+			};
+			ctx.instantiate(endowment, gas_meter, &code_hash, data, &salt)
+				.map(|(account_id, result)| InstantiateReturnValue {
+					result,
+					account_id,
+					code_hash,
+				})
+		})
 	}
 
 	/// Query storage of a specified contract under a specified key.
@@ -607,6 +758,38 @@ where
 		Rent::<T>::compute_projection(&address)
 	}
 
+	/// Query the owner, deposit and number of users of the specified code.
+	pub fn code_info(code_hash: CodeHash<T>) -> Option<CodeInfo<T::AccountId, BalanceOf<T>>> {
+		<OwnerInfoOf<T>>::get(code_hash).map(|info| CodeInfo {
+			owner: info.owner,
+			deposit: info.deposit,
+			refcount: info.refcount,
+		})
+	}
+
+	/// Returns all events deposited by contracts in the current block.
+	///
+	/// The events are returned in the order they were deposited together with their topics
+	/// and their index into `frame_system::Events`.
+	pub fn contract_events() -> Vec<ContractEvent<T::AccountId, T::Hash>>
+	where
+		<T as frame_system::Config>::Event: TryInto<Event<T>>,
+	{
+		<frame_system::Module<T>>::events()
+			.into_iter()
+			.enumerate()
+			.filter_map(|(index, record)| match record.event.try_into() {
+				Ok(RawEvent::ContractExecution(contract, data)) => Some(ContractEvent {
+					event_index: index as u32,
+					contract,
+					topics: record.topics,
+					data,
+				}),
+				_ => None,
+			})
+			.collect()
+	}
+
 	/// Put code for benchmarks which does not check or instrument the code.
 	#[cfg(feature = "runtime-benchmarks")]
 	pub fn put_code_raw(code: Vec<u8>) -> DispatchResult {
@@ -642,17 +825,49 @@ impl<T: Config> Module<T>
 where
 	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
 {
-	fn execute_wasm(
+	fn execute_wasm<R>(
 		origin: T::AccountId,
 		gas_meter: &mut GasMeter<T>,
-		func: impl FnOnce(&mut ExecutionContext<T, WasmVm<T>, WasmLoader<T>>, &mut GasMeter<T>) -> ExecResult,
-	) -> ExecResult {
+		func: impl FnOnce(&mut ExecutionContext<T, WasmVm<T>, WasmLoader<T>>, &mut GasMeter<T>) -> R,
+	) -> R {
 		let cfg = ConfigCache::preload();
 		let vm = WasmVm::new(&cfg.schedule);
 		let loader = WasmLoader::new(&cfg.schedule);
 		let mut ctx = ExecutionContext::top_level(origin, &cfg, &vm, &loader);
 		func(&mut ctx, gas_meter)
 	}
+
+	/// Execute `func` while recording everything that is reported back by dry runs.
+	fn execute_recorded<R>(
+		origin: T::AccountId,
+		gas_meter: &mut GasMeter<T>,
+		func: impl FnOnce(
+			&mut ExecutionContext<T, WasmVm<T>, WasmLoader<T>>,
+			&mut GasMeter<T>,
+		) -> Result<R, ExecError>,
+	) -> ContractResult<Result<R, ExecError>, T::AccountId, BalanceOf<T>, T::Hash>
+	where
+		<T as frame_system::Config>::Event: TryInto<Event<T>>,
+	{
+		let first_event = <frame_system::Module<T>>::event_count();
+		let (result, storage_deposit, mut record) = Self::execute_wasm(origin, gas_meter, |ctx, gas_meter| {
+			ctx.record = Some(Default::default());
+			let result = func(ctx, gas_meter);
+			(result, ctx.storage_deposit.clone(), ctx.record.take().unwrap_or_default())
+		});
+		let events = Self::contract_events()
+			.into_iter()
+			.filter(|event| event.event_index >= first_event)
+			.collect();
+		ContractResult {
+			result,
+			gas_consumed: gas_meter.gas_spent(),
+			storage_deposit,
+			debug_message: sp_std::mem::take(&mut record.debug_message),
+			events,
+			storage_diffs: record.storage_diffs(),
+		}
+	}
 }
 
 decl_event! {
@@ -689,6 +904,10 @@ decl_event! {
 		/// \[code_hash\]
 		CodeStored(Hash),
 
+		/// Code with the specified hash has been removed and its deposit was refunded.
+		/// \[code_hash\]
+		CodeRemoved(Hash),
+
 		/// Triggered when the current \[schedule\] is updated.
 		ScheduleUpdated(u32),
 
@@ -709,12 +928,20 @@ decl_storage! {
 		pub PristineCode: map hasher(identity) CodeHash<T> => Option<Vec<u8>>;
 		/// A mapping between an original code hash and instrumented wasm code, ready for execution.
 		pub CodeStorage: map hasher(identity) CodeHash<T> => Option<wasm::PrefabWasmModule>;
+		/// The owner, deposit and number of users of code that was uploaded with `upload_code`.
+		///
+		/// Code that was stored before code ownership was introduced has no entry.
+		pub OwnerInfoOf: map hasher(identity) CodeHash<T> => Option<OwnerInfo<T>>;
 		/// The subtrie counter.
 		pub AccountCounter: u64 = 0;
 		/// The code associated with a given account.
 		///
 		/// TWOX-NOTE: SAFE since `AccountId` is a secure hash.
 		pub ContractInfoOf: map hasher(twox_64_concat) T::AccountId => Option<ContractInfo<T>>;
+		/// Storage version of the pallet.
+		///
+		/// New networks start with last version.
+		StorageVersion build(|_| migration::Releases::V2): migration::Releases;
 	}
 }
 
@@ -0,0 +1,100 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations for the contracts module.
+
+use crate::{
+	AliveContractInfo, BalanceOf, CodeHash, Config, ContractInfo, ContractInfoOf, StorageVersion,
+	TombstoneContractInfo, TrieId,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	IterableStorageMap, StorageValue,
+	traits::Get,
+	weights::Weight,
+};
+use sp_core::crypto::UncheckedFrom;
+use sp_runtime::{RuntimeDebug, traits::Zero};
+use sp_std::cell::Cell;
+
+/// A value placed in storage that represents the current version of the contracts storage.
+/// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+/// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+	/// Contracts don't track a storage deposit.
+	V1,
+	/// `RawAliveContractInfo::storage_deposit` was added.
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
+#[derive(Decode)]
+struct AliveContractInfoV1<T: Config> {
+	trie_id: TrieId,
+	storage_size: u32,
+	empty_pair_count: u32,
+	total_pair_count: u32,
+	code_hash: CodeHash<T>,
+	rent_allowance: BalanceOf<T>,
+	deduct_block: T::BlockNumber,
+	last_write: Option<T::BlockNumber>,
+}
+
+#[derive(Decode)]
+enum ContractInfoV1<T: Config> {
+	Alive(AliveContractInfoV1<T>),
+	Tombstone(TombstoneContractInfo<T>),
+}
+
+/// Run all pending migrations and return the consumed weight.
+pub fn migrate<T: Config>() -> Weight
+where
+	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
+{
+	if StorageVersion::get() != Releases::V1 {
+		return 0;
+	}
+
+	let translated = Cell::new(0 as Weight);
+	<ContractInfoOf<T>>::translate::<ContractInfoV1<T>, _>(|_, old| {
+		translated.set(translated.get() + 1);
+		Some(match old {
+			ContractInfoV1::Alive(old) => ContractInfo::Alive(AliveContractInfo::<T> {
+				trie_id: old.trie_id,
+				storage_size: old.storage_size,
+				empty_pair_count: old.empty_pair_count,
+				total_pair_count: old.total_pair_count,
+				code_hash: old.code_hash,
+				rent_allowance: old.rent_allowance,
+				deduct_block: old.deduct_block,
+				last_write: old.last_write,
+				storage_deposit: Zero::zero(),
+			}),
+			ContractInfoV1::Tombstone(tombstone) => ContractInfo::Tombstone(tombstone),
+		})
+	});
+	StorageVersion::put(Releases::V2);
+
+	let translated = translated.get();
+	T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+}
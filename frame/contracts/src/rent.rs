@@ -18,7 +18,7 @@
 
 use crate::{
 	AliveContractInfo, BalanceOf, ContractInfo, ContractInfoOf, Module, RawEvent,
-	TombstoneContractInfo, Config, CodeHash, ConfigCache, Error,
+	TombstoneContractInfo, Config, CodeHash, ConfigCache, Error, deposit::Deposit, wasm,
 };
 use sp_std::prelude::*;
 use sp_io::hashing::blake2_256;
@@ -238,6 +238,7 @@ where
 			Verdict::Exempt => return Some(ContractInfo::Alive(alive_contract_info)),
 			Verdict::Kill => {
 				<ContractInfoOf<T>>::remove(account);
+				wasm::decrement_refcount::<T>(&alive_contract_info.code_hash);
 				child::kill_storage(
 					&alive_contract_info.child_trie_info(),
 					None,
@@ -261,6 +262,7 @@ where
 				);
 				let tombstone_info = ContractInfo::Tombstone(tombstone);
 				<ContractInfoOf<T>>::insert(account, &tombstone_info);
+				// The tombstone keeps its reference to the code so that it can be restored.
 
 				child::kill_storage(
 					&alive_contract_info.child_trie_info(),
@@ -286,12 +288,16 @@ where
 
 	/// Make account paying the rent for the current block number
 	///
+	/// No rent is collected when storage deposits are used instead of rent.
+	///
 	/// NOTE this function performs eviction eagerly. All changes are read and written directly to
 	/// storage.
 	pub fn collect(account: &T::AccountId) -> Option<ContractInfo<T>> {
 		let contract_info = <ContractInfoOf<T>>::get(account);
 		let alive_contract_info = match contract_info {
 			None | Some(ContractInfo::Tombstone(_)) => return contract_info,
+			Some(ContractInfo::Alive(contract)) if Deposit::<T>::is_enabled() =>
+				return Some(ContractInfo::Alive(contract)),
 			Some(ContractInfo::Alive(contract)) => contract,
 		};
 
@@ -321,6 +327,9 @@ where
 		account: &T::AccountId,
 		handicap: T::BlockNumber,
 	) -> bool {
+		if Deposit::<T>::is_enabled() {
+			return false;
+		}
 		let contract_info = <ContractInfoOf<T>>::get(account);
 		let alive_contract_info = match contract_info {
 			None | Some(ContractInfo::Tombstone(_)) => return false,
@@ -361,6 +370,8 @@ where
 		let contract_info = <ContractInfoOf<T>>::get(account);
 		let alive_contract_info = match contract_info {
 			None | Some(ContractInfo::Tombstone(_)) => return Err(ContractAccessError::IsTombstone),
+			Some(ContractInfo::Alive(_)) if Deposit::<T>::is_enabled() =>
+				return Ok(RentProjection::NoEviction),
 			Some(ContractInfo::Alive(contract)) => contract,
 		};
 		let current_block_number = <frame_system::Module<T>>::block_number();
@@ -479,6 +490,8 @@ where
 			.sum::<u32>();
 
 		<ContractInfoOf<T>>::remove(&origin);
+		// The restored contract takes over the reference to `code_hash` held by the tombstone.
+		wasm::decrement_refcount::<T>(&origin_contract.code_hash);
 		<ContractInfoOf<T>>::insert(&dest, ContractInfo::Alive(AliveContractInfo::<T> {
 			trie_id: origin_contract.trie_id,
 			storage_size: origin_contract.storage_size,
@@ -488,6 +501,7 @@ where
 			rent_allowance,
 			deduct_block: current_block,
 			last_write,
+			storage_deposit: origin_contract.storage_deposit,
 		}));
 
 		let origin_free_balance = T::Currency::free_balance(&origin);
@@ -105,7 +105,7 @@ pub struct Limits {
 	pub subject_len: u32,
 
 	/// The maximum length of a contract code in bytes. This limit applies to the uninstrumented
-	/// and pristine form of the code as supplied to `put_code`.
+	/// and pristine form of the code as supplied to `upload_code`.
 	pub code_size: u32,
 }
 
@@ -329,6 +329,17 @@ pub struct HostFnWeights<T: Config> {
 	/// Weight per byte hashed by `seal_hash_blake2_128`.
 	pub hash_blake2_128_per_byte: Weight,
 
+	/// Weight of calling `seal_call_chain_extension`.
+	///
+	/// This is only the overhead of entering the chain extension. The chain extension charges
+	/// for the work it performs itself.
+	pub call_chain_extension: Weight,
+
+	/// Weight of settling the storage deposit of a contract after its storage was changed.
+	///
+	/// Only charged when storage deposits are enabled.
+	pub settle_storage_deposit: Weight,
+
 	/// The type parameter is used in the default implementation.
 	pub _phantom: PhantomData<T>
 }
@@ -549,6 +560,8 @@ impl<T: Config> Default for HostFnWeights<T> {
 			hash_blake2_256_per_byte: cost_byte_batched!(seal_hash_blake2_256_per_kb),
 			hash_blake2_128: cost_batched!(seal_hash_blake2_128),
 			hash_blake2_128_per_byte: cost_byte_batched!(seal_hash_blake2_128_per_kb),
+			call_chain_extension: cost_batched!(seal_call_chain_extension),
+			settle_storage_deposit: T::WeightInfo::settle_storage_deposit(),
 			_phantom: PhantomData,
 		}
 	}
@@ -19,12 +19,12 @@
 use crate::{
 	exec::{AccountIdOf, StorageKey},
 	AliveContractInfo, BalanceOf, CodeHash, ContractInfo, ContractInfoOf, Config, TrieId,
-	AccountCounter,
+	AccountCounter, wasm,
 };
 use sp_std::prelude::*;
 use sp_std::marker::PhantomData;
 use sp_io::hashing::blake2_256;
-use sp_runtime::traits::Bounded;
+use sp_runtime::traits::{Bounded, Zero};
 use sp_core::crypto::UncheckedFrom;
 use frame_support::{storage::child, StorageMap};
 
@@ -172,6 +172,7 @@ where
 				return Err("Alive contract or tombstone already exists");
 			}
 
+			wasm::increment_refcount::<T>(&ch);
 			*maybe_contract_info = Some(
 				AliveContractInfo::<T> {
 					code_hash: ch,
@@ -182,6 +183,7 @@ where
 					empty_pair_count: 0,
 					total_pair_count: 0,
 					last_write: None,
+					storage_deposit: Zero::zero(),
 				}
 				.into(),
 			);
@@ -194,7 +196,9 @@ where
 	///
 	/// This function doesn't affect the account.
 	pub fn destroy_contract(address: &AccountIdOf<T>, trie_id: &TrieId) {
-		<ContractInfoOf<T>>::remove(address);
+		if let Some(ContractInfo::Alive(contract)) = <ContractInfoOf<T>>::take(address) {
+			wasm::decrement_refcount::<T>(&contract.code_hash);
+		}
 		child::kill_storage(&crate::child_trie_info(&trie_id), None);
 	}
 
@@ -15,26 +15,34 @@
 // along with Substrate. If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
-	BalanceOf, ContractInfo, ContractInfoOf, GenesisConfig, Module,
+	BalanceOf, ContractInfo, ContractInfoOf, GenesisConfig, Module, OwnerInfoOf, RawOwnerInfo,
 	RawAliveContractInfo, RawEvent, Config, Schedule, gas::Gas,
 	Error, ConfigCache, RuntimeReturnCode, storage::Storage,
-	exec::AccountIdOf,
+	exec::AccountIdOf, StorageModel,
+	chain_extension::{
+		ChainExtension, Environment, Ext, SysConfig, RetVal, UncheckedFrom,
+		Result as ExtensionResult,
+	},
 };
 use assert_matches::assert_matches;
 use codec::Encode;
+use pallet_contracts_primitives::{
+	Code, ContractEvent, ContractStorageDiff, ReturnFlags, StorageChange, StorageDeposit,
+};
 use sp_runtime::{
 	traits::{BlakeTwo256, Hash, IdentityLookup, Convert},
 	testing::{Header, H256},
-	AccountId32,
+	AccountId32, DispatchError,
 };
 use frame_support::{
-	assert_ok, assert_err_ignore_postinfo, impl_outer_dispatch, impl_outer_event,
+	assert_ok, assert_noop, assert_err_ignore_postinfo, impl_outer_dispatch, impl_outer_event,
 	impl_outer_origin, parameter_types, StorageMap,
 	traits::{Currency, ReservableCurrency},
 	weights::{Weight, PostDispatchInfo},
 	dispatch::DispatchErrorWithPostInfo,
 };
 use frame_system::{self as system, EventRecord, Phase};
+use std::cell::RefCell;
 
 mod contracts {
 	// Re-export contents of the root. This basically
@@ -133,6 +141,9 @@ impl frame_system::Config for Test {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type Balance = u64;
 	type Event = MetaEvent;
 	type DustRemoval = ();
@@ -158,6 +169,10 @@ parameter_types! {
 	pub const SurchargeReward: u64 = 150;
 	pub const MaxDepth: u32 = 100;
 	pub const MaxValueSize: u32 = 16_384;
+	pub const DepositPerByte: u64 = 2;
+	pub const DepositPerItem: u64 = 10;
+	pub static StorageModelUsed: StorageModel = StorageModel::Rent;
+	pub static CodeDepositPerByte: u64 = 0;
 }
 
 parameter_types! {
@@ -182,10 +197,77 @@ impl Config for Test {
 	type RentByteFee = RentByteFee;
 	type RentDepositOffset = RentDepositOffset;
 	type SurchargeReward = SurchargeReward;
+	type StorageModel = StorageModelUsed;
+	type DepositPerByte = DepositPerByte;
+	type DepositPerItem = DepositPerItem;
+	type CodeDepositPerByte = CodeDepositPerByte;
 	type MaxDepth = MaxDepth;
 	type MaxValueSize = MaxValueSize;
 	type WeightPrice = Self;
 	type WeightInfo = ();
+	type ChainExtension = TestExtension;
+}
+
+thread_local! {
+	static TEST_EXTENSION: RefCell<TestExtension> = Default::default();
+}
+
+/// Chain extension used by the tests. It is enabled unless `TestExtension::disable` is called.
+pub struct TestExtension {
+	enabled: bool,
+	last_seen_buffer: Vec<u8>,
+}
+
+impl TestExtension {
+	fn disable() {
+		TEST_EXTENSION.with(|e| e.borrow_mut().enabled = false)
+	}
+
+	fn last_seen_buffer() -> Vec<u8> {
+		TEST_EXTENSION.with(|e| e.borrow().last_seen_buffer.clone())
+	}
+}
+
+impl Default for TestExtension {
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			last_seen_buffer: vec![],
+		}
+	}
+}
+
+impl ChainExtension<Test> for TestExtension {
+	fn call<E>(func_id: u32, mut env: Environment<E>) -> ExtensionResult<RetVal>
+	where
+		E: Ext<T = Test>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		match func_id {
+			// Passes the input through to the output.
+			0 => {
+				let input = env.read(2)?;
+				env.write(&input, false, None)?;
+				TEST_EXTENSION.with(|e| e.borrow_mut().last_seen_buffer = input);
+				Ok(RetVal::Converging(func_id))
+			},
+			// Charges the weight given by the second byte of the input.
+			1 => {
+				let input: [u8; 2] = env.read_as()?;
+				env.charge_weight(input[1].into())?;
+				Ok(RetVal::Converging(func_id))
+			},
+			// Returns to the caller of the contract without resuming it.
+			2 => {
+				Ok(RetVal::Diverging{flags: ReturnFlags::REVERT, data: vec![42, 99]})
+			},
+			_ => Err(Error::<Test>::DecodingFailed.into()),
+		}
+	}
+
+	fn enabled() -> bool {
+		TEST_EXTENSION.with(|e| e.borrow().enabled)
+	}
 }
 
 type Balances = pallet_balances::Module<Test>;
@@ -203,11 +285,15 @@ const GAS_LIMIT: Gas = 10_000_000_000;
 
 pub struct ExtBuilder {
 	existential_deposit: u64,
+	storage_model: StorageModel,
+	code_deposit_per_byte: u64,
 }
 impl Default for ExtBuilder {
 	fn default() -> Self {
 		Self {
 			existential_deposit: 1,
+			storage_model: StorageModel::Rent,
+			code_deposit_per_byte: 0,
 		}
 	}
 }
@@ -216,8 +302,18 @@ impl ExtBuilder {
 		self.existential_deposit = existential_deposit;
 		self
 	}
+	pub fn storage_model(mut self, storage_model: StorageModel) -> Self {
+		self.storage_model = storage_model;
+		self
+	}
+	pub fn code_deposit_per_byte(mut self, code_deposit_per_byte: u64) -> Self {
+		self.code_deposit_per_byte = code_deposit_per_byte;
+		self
+	}
 	pub fn set_associated_consts(&self) {
 		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.existential_deposit);
+		STORAGE_MODEL_USED.with(|v| *v.borrow_mut() = self.storage_model);
+		CODE_DEPOSIT_PER_BYTE.with(|v| *v.borrow_mut() = self.code_deposit_per_byte);
 	}
 	pub fn build(self) -> sp_io::TestExternalities {
 		self.set_associated_consts();
@@ -298,6 +394,7 @@ fn account_removal_does_not_remove_storage() {
 				code_hash: H256::repeat_byte(1),
 				rent_allowance: 40,
 				last_write: None,
+				storage_deposit: 0,
 			});
 			let _ = Balances::deposit_creating(&ALICE, 110);
 			ContractInfoOf::<Test>::insert(ALICE, &alice_contract_info);
@@ -313,6 +410,7 @@ fn account_removal_does_not_remove_storage() {
 				code_hash: H256::repeat_byte(2),
 				rent_allowance: 40,
 				last_write: None,
+				storage_deposit: 0,
 			});
 			let _ = Balances::deposit_creating(&BOB, 110);
 			ContractInfoOf::<Test>::insert(BOB, &bob_contract_info);
@@ -363,7 +461,7 @@ fn instantiate_and_call_and_deposit_event() {
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
 			let subsistence = super::ConfigCache::<Test>::subsistence_threshold_uncached();
 
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 
 			// Check at the end to get hash on error easily
 			let creation = Contracts::instantiate(
@@ -432,6 +530,52 @@ fn instantiate_and_call_and_deposit_event() {
 		});
 }
 
+#[test]
+fn contract_events_are_reported_with_topics() {
+	let (wasm, code_hash) = compile_module::<Test>("return_from_start_fn").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(100)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			let subsistence = super::ConfigCache::<Test>::subsistence_threshold_uncached();
+
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				subsistence,
+				GAS_LIMIT,
+				code_hash.into(),
+				vec![],
+				vec![],
+			));
+			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+
+			// Deposit another contract event with topics directly.
+			let topics = vec![H256::repeat_byte(1), H256::repeat_byte(2)];
+			System::deposit_event_indexed(
+				&topics,
+				MetaEvent::contracts(RawEvent::ContractExecution(addr.clone(), vec![5, 6])),
+			);
+
+			assert_eq!(Contracts::contract_events(), vec![
+				ContractEvent {
+					event_index: 6,
+					contract: addr.clone(),
+					topics: vec![],
+					data: vec![1, 2, 3, 4],
+				},
+				ContractEvent {
+					event_index: 8,
+					contract: addr,
+					topics,
+					data: vec![5, 6],
+				},
+			]);
+		});
+}
+
 #[test]
 fn deposit_event_max_value_limit() {
 	let (wasm, code_hash) = compile_module::<Test>("event_size").unwrap();
@@ -442,7 +586,7 @@ fn deposit_event_max_value_limit() {
 		.execute_with(|| {
 			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 			assert_ok!(Contracts::instantiate(
 				Origin::signed(ALICE),
 				30_000,
@@ -493,7 +637,7 @@ fn run_out_of_gas() {
 		.execute_with(|| {
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
 
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 
 			assert_ok!(Contracts::instantiate(
 				Origin::signed(ALICE),
@@ -542,7 +686,7 @@ fn test_set_rent_code_and_hash() {
 		.build()
 		.execute_with(|| {
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 
 			// If you ever need to update the wasm source this test will fail
 			// and will show you the actual hash.
@@ -568,6 +712,135 @@ fn test_set_rent_code_and_hash() {
 		});
 }
 
+#[test]
+fn storage_deposit_is_charged_and_refunded() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_model(StorageModel::Deposit)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				30_000,
+				GAS_LIMIT,
+				code_hash.into(),
+				<Test as pallet_balances::Config>::Balance::from(1_000u32).encode(), // rent allowance
+				vec![],
+			));
+			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+
+			// The constructor stored one item of 4 bytes: 4 * 2 + 10.
+			let deposit = |addr| ContractInfoOf::<Test>::get(addr)
+				.unwrap()
+				.get_alive()
+				.unwrap()
+				.storage_deposit;
+			assert_eq!(deposit(&addr), 18);
+			assert_eq!(Balances::reserved_balance(&addr), 18);
+			assert_eq!(Balances::free_balance(&ALICE), 1_000_000 - 30_000 - 18);
+
+			// The charged deposit is reported back to the caller.
+			let result = Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				call::set_storage_4_byte(),
+			);
+			assert_ok!(result.result);
+			assert_eq!(result.storage_deposit, StorageDeposit::Charge(18));
+			assert_eq!(deposit(&addr), 36);
+			assert_eq!(Balances::reserved_balance(&addr), 36);
+			assert_eq!(Balances::free_balance(&ALICE), 1_000_000 - 30_000 - 36);
+
+			let result = Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				call::remove_storage_4_byte(),
+			);
+			assert_ok!(result.result);
+			assert_eq!(result.storage_deposit, StorageDeposit::Refund(18));
+			assert_eq!(deposit(&addr), 18);
+			assert_eq!(Balances::reserved_balance(&addr), 18);
+			assert_eq!(Balances::free_balance(&ALICE), 1_000_000 - 30_000 - 18);
+
+			// Contracts are never evicted when deposits are used.
+			initialize_block(1_000_000);
+			assert_ok!(Contracts::claim_surcharge(Origin::none(), addr.clone(), Some(ALICE)));
+			assert!(ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().is_some());
+		});
+}
+
+#[test]
+fn storage_deposit_is_refunded_to_the_caller_removing_storage() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_model(StorageModel::Deposit)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			let _ = Balances::deposit_creating(&BOB, 1_000_000);
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				30_000,
+				GAS_LIMIT,
+				code_hash.into(),
+				<Test as pallet_balances::Config>::Balance::from(1_000u32).encode(), // rent allowance
+				vec![],
+			));
+			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+			assert_eq!(Balances::free_balance(&ALICE), 1_000_000 - 30_000 - 18);
+
+			// Storage removed by another caller refunds that caller.
+			let result = Contracts::bare_call(
+				BOB,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				call::remove_storage_4_byte(),
+			);
+			assert_ok!(result.result);
+			assert_eq!(result.storage_deposit, StorageDeposit::Refund(18));
+			assert_eq!(Balances::free_balance(&ALICE), 1_000_000 - 30_000 - 18);
+			assert_eq!(Balances::free_balance(&BOB), 1_000_000 + 18);
+		});
+}
+
+#[test]
+fn storage_deposit_fails_without_funds() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.storage_model(StorageModel::Deposit)
+		.build()
+		.execute_with(|| {
+			// Enough for the endowment but not for the storage deposit of the constructor.
+			let _ = Balances::deposit_creating(&ALICE, 30_060);
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
+			assert_err_ignore_postinfo!(
+				Contracts::instantiate(
+					Origin::signed(ALICE),
+					30_000,
+					GAS_LIMIT,
+					code_hash.into(),
+					<Test as pallet_balances::Config>::Balance::from(1_000u32).encode(),
+					vec![],
+				),
+				Error::<Test>::StorageDepositNotEnoughFunds,
+			);
+		});
+}
+
 #[test]
 fn storage_size() {
 	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
@@ -579,7 +852,7 @@ fn storage_size() {
 		.execute_with(|| {
 			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 			assert_ok!(Contracts::instantiate(
 				Origin::signed(ALICE),
 				30_000,
@@ -664,7 +937,7 @@ fn empty_kv_pairs() {
 		.build()
 		.execute_with(|| {
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 			assert_ok!(Contracts::instantiate(
 				Origin::signed(ALICE),
 				30_000,
@@ -714,7 +987,7 @@ fn deduct_blocks() {
 		.execute_with(|| {
 			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 			assert_ok!(Contracts::instantiate(
 				Origin::signed(ALICE),
 				30_000,
@@ -819,7 +1092,7 @@ fn claim_surcharge(blocks: u64, trigger_call: impl Fn(AccountIdOf<Test>) -> bool
 		.execute_with(|| {
 			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 			assert_ok!(Contracts::instantiate(
 				Origin::signed(ALICE),
 				100,
@@ -858,7 +1131,7 @@ fn removals(trigger_call: impl Fn(AccountIdOf<Test>) -> bool) {
 		.execute_with(|| {
 			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm.clone()));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm.clone()));
 			assert_ok!(Contracts::instantiate(
 				Origin::signed(ALICE),
 				100,
@@ -899,7 +1172,7 @@ fn removals(trigger_call: impl Fn(AccountIdOf<Test>) -> bool) {
 		.execute_with(|| {
 			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm.clone()));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm.clone()));
 			assert_ok!(Contracts::instantiate(
 				Origin::signed(ALICE),
 				1_000,
@@ -955,7 +1228,7 @@ fn removals(trigger_call: impl Fn(AccountIdOf<Test>) -> bool) {
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
 			let subsistence_threshold =
 				Balances::minimum_balance() + <Test as Config>::TombstoneDeposit::get();
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm.clone()));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm.clone()));
 			assert_ok!(Contracts::instantiate(
 				Origin::signed(ALICE),
 				50 + subsistence_threshold,
@@ -1028,7 +1301,7 @@ fn call_removed_contract() {
 		.execute_with(|| {
 			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm.clone()));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm.clone()));
 			assert_ok!(Contracts::instantiate(
 				Origin::signed(ALICE),
 				100,
@@ -1078,7 +1351,7 @@ fn default_rent_allowance_on_instantiate() {
 		.execute_with(|| {
 			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 			assert_ok!(Contracts::instantiate(
 				Origin::signed(ALICE),
 				30_000,
@@ -1136,8 +1409,8 @@ fn restoration(test_different_storage: bool, test_restore_to_with_dirty_storage:
 		.build()
 		.execute_with(|| {
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), restoration_wasm));
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), set_rent_wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), restoration_wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), set_rent_wasm));
 
 			// If you ever need to update the wasm source this test will fail
 			// and will show you the actual hash.
@@ -1357,7 +1630,7 @@ fn storage_max_value_limit() {
 		.execute_with(|| {
 			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 			assert_ok!(Contracts::instantiate(
 				Origin::signed(ALICE),
 				30_000,
@@ -1406,8 +1679,8 @@ fn deploy_and_call_other_contract() {
 		.execute_with(|| {
 			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), callee_wasm));
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), caller_wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), callee_wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), caller_wasm));
 
 			assert_ok!(Contracts::instantiate(
 				Origin::signed(ALICE),
@@ -1439,7 +1712,7 @@ fn cannot_self_destruct_through_draning() {
 		.build()
 		.execute_with(|| {
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 
 			// Instantiate the BOB contract.
 			assert_ok!(Contracts::instantiate(
@@ -1480,7 +1753,7 @@ fn cannot_self_destruct_while_live() {
 		.build()
 		.execute_with(|| {
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 
 			// Instantiate the BOB contract.
 			assert_ok!(Contracts::instantiate(
@@ -1528,7 +1801,7 @@ fn self_destruct_works() {
 		.build()
 		.execute_with(|| {
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 
 			// Instantiate the BOB contract.
 			assert_ok!(Contracts::instantiate(
@@ -1580,8 +1853,8 @@ fn destroy_contract_and_transfer_funds() {
 		.execute_with(|| {
 			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), callee_wasm));
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), caller_wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), callee_wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), caller_wasm));
 
 			// This deploys the BOB contract, which in turn deploys the CHARLIE contract during
 			// construction.
@@ -1626,7 +1899,7 @@ fn cannot_self_destruct_in_constructor() {
 		.build()
 		.execute_with(|| {
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 
 			// Fail to instantiate the BOB because the contructor calls seal_terminate.
 			assert_err_ignore_postinfo!(
@@ -1652,7 +1925,7 @@ fn crypto_hashes() {
 		.build()
 		.execute_with(|| {
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 
 			// Instantiate the CRYPTO_HASHES contract.
 			assert_ok!(Contracts::instantiate(
@@ -1691,7 +1964,7 @@ fn crypto_hashes() {
 					0,
 					GAS_LIMIT,
 					params,
-				).exec_result.unwrap();
+				).result.unwrap();
 				assert!(result.is_success());
 				let expected = hash_fn(input.as_ref());
 				assert_eq!(&result.data[..*expected_size], &*expected);
@@ -1705,7 +1978,7 @@ fn transfer_return_code() {
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
 		let subsistence = ConfigCache::<Test>::subsistence_threshold_uncached();
 		let _ = Balances::deposit_creating(&ALICE, 10 * subsistence);
-		assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+		assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
 
 		assert_ok!(
 			Contracts::instantiate(
@@ -1726,7 +1999,7 @@ fn transfer_return_code() {
 			0,
 			GAS_LIMIT,
 			vec![],
-		).exec_result.unwrap();
+		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::BelowSubsistenceThreshold);
 
 		// Contract has enough total balance in order to not go below the subsistence
@@ -1740,7 +2013,7 @@ fn transfer_return_code() {
 			0,
 			GAS_LIMIT,
 			vec![],
-		).exec_result.unwrap();
+		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 	});
 }
@@ -1753,8 +2026,8 @@ fn call_return_code() {
 		let subsistence = ConfigCache::<Test>::subsistence_threshold_uncached();
 		let _ = Balances::deposit_creating(&ALICE, 10 * subsistence);
 		let _ = Balances::deposit_creating(&CHARLIE, 10 * subsistence);
-		assert_ok!(Contracts::put_code(Origin::signed(ALICE), caller_code));
-		assert_ok!(Contracts::put_code(Origin::signed(ALICE), callee_code));
+		assert_ok!(Contracts::upload_code(Origin::signed(ALICE), caller_code));
+		assert_ok!(Contracts::upload_code(Origin::signed(ALICE), callee_code));
 
 		assert_ok!(
 			Contracts::instantiate(
@@ -1775,7 +2048,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&DJANGO).to_vec(),
-		).exec_result.unwrap();
+		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::NotCallable);
 
 		assert_ok!(
@@ -1797,7 +2070,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&0u32.to_le_bytes()).cloned().collect(),
-		).exec_result.unwrap();
+		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::BelowSubsistenceThreshold);
 
 		// Contract has enough total balance in order to not go below the subsistence
@@ -1811,7 +2084,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&0u32.to_le_bytes()).cloned().collect(),
-		).exec_result.unwrap();
+		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 
 		// Contract has enough balance but callee reverts because "1" is passed.
@@ -1822,7 +2095,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&1u32.to_le_bytes()).cloned().collect(),
-		).exec_result.unwrap();
+		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeReverted);
 
 		// Contract has enough balance but callee traps because "2" is passed.
@@ -1832,7 +2105,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&2u32.to_le_bytes()).cloned().collect(),
-		).exec_result.unwrap();
+		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeTrapped);
 
 	});
@@ -1846,8 +2119,8 @@ fn instantiate_return_code() {
 		let subsistence = ConfigCache::<Test>::subsistence_threshold_uncached();
 		let _ = Balances::deposit_creating(&ALICE, 10 * subsistence);
 		let _ = Balances::deposit_creating(&CHARLIE, 10 * subsistence);
-		assert_ok!(Contracts::put_code(Origin::signed(ALICE), caller_code));
-		assert_ok!(Contracts::put_code(Origin::signed(ALICE), callee_code));
+		assert_ok!(Contracts::upload_code(Origin::signed(ALICE), caller_code));
+		assert_ok!(Contracts::upload_code(Origin::signed(ALICE), callee_code));
 		let callee_hash = callee_hash.as_ref().to_vec();
 
 		assert_ok!(
@@ -1869,7 +2142,7 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			vec![0; 33],
-		).exec_result.unwrap();
+		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::BelowSubsistenceThreshold);
 
 		// Contract has enough total balance in order to not go below the subsistence
@@ -1883,7 +2156,7 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			vec![0; 33],
-		).exec_result.unwrap();
+		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 
 		// Contract has enough balance but the passed code hash is invalid
@@ -1894,7 +2167,7 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			vec![0; 33],
-		).exec_result.unwrap();
+		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CodeNotFound);
 
 		// Contract has enough balance but callee reverts because "1" is passed.
@@ -1904,7 +2177,7 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			callee_hash.iter().chain(&1u32.to_le_bytes()).cloned().collect(),
-		).exec_result.unwrap();
+		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeReverted);
 
 		// Contract has enough balance but callee traps because "2" is passed.
@@ -1914,8 +2187,306 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			callee_hash.iter().chain(&2u32.to_le_bytes()).cloned().collect(),
-		).exec_result.unwrap();
+		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeTrapped);
 
 	});
 }
+
+#[test]
+fn dry_run_reports_debug_message() {
+	let (wasm, code_hash) = compile_module::<Test>("debug_message").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let subsistence = ConfigCache::<Test>::subsistence_threshold_uncached();
+		let _ = Balances::deposit_creating(&ALICE, 10 * subsistence);
+		assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
+		assert_ok!(Contracts::instantiate(
+			Origin::signed(ALICE),
+			subsistence,
+			GAS_LIMIT,
+			code_hash.into(),
+			vec![],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+
+		let result = Contracts::bare_call(ALICE, addr, 0, GAS_LIMIT, vec![]);
+		assert_ok!(result.result);
+		assert_eq!(result.debug_message, b"Hello World!".to_vec());
+		assert!(result.events.is_empty());
+		assert!(result.storage_diffs.is_empty());
+	});
+}
+
+#[test]
+fn dry_run_reports_storage_diffs() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
+		let result = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			Code::Existing(code_hash),
+			<Test as pallet_balances::Config>::Balance::from(1_000u32).encode(),
+			vec![],
+		);
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+		assert_eq!(result.result.unwrap().account_id, addr);
+
+		// The constructor writes its 4 byte value under a key starting with the value itself.
+		let mut key = [0u8; 32];
+		key[0] = 0x28;
+		assert_eq!(result.storage_diffs, vec![ContractStorageDiff {
+			contract: addr.clone(),
+			changes: vec![StorageChange {
+				key,
+				old_value: None,
+				new_value: Some(vec![0x28, 0, 0, 0]),
+			}],
+		}]);
+
+		// Every call reports the value it replaced.
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			call::set_storage_4_byte(),
+		);
+		assert_ok!(result.result);
+		assert_eq!(result.storage_diffs, vec![ContractStorageDiff {
+			contract: addr.clone(),
+			changes: vec![StorageChange {
+				key: [0u8; 32],
+				old_value: None,
+				new_value: Some(vec![0x28, 0, 0, 0]),
+			}],
+		}]);
+
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			call::remove_storage_4_byte(),
+		);
+		assert_ok!(result.result);
+		assert_eq!(result.storage_diffs, vec![ContractStorageDiff {
+			contract: addr,
+			changes: vec![StorageChange {
+				key: [0u8; 32],
+				old_value: Some(vec![0x28, 0, 0, 0]),
+				new_value: None,
+			}],
+		}]);
+	});
+}
+
+#[test]
+fn dry_run_instantiate_with_uploaded_code() {
+	let (wasm, code_hash) = compile_module::<Test>("return_from_start_fn").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let subsistence = ConfigCache::<Test>::subsistence_threshold_uncached();
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		let result = Contracts::bare_instantiate(
+			ALICE,
+			subsistence,
+			GAS_LIMIT,
+			Code::Upload(wasm),
+			vec![],
+			vec![42],
+		);
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[42]);
+
+		let output = result.result.unwrap();
+		assert_eq!(output.account_id, addr);
+		assert_eq!(output.code_hash, code_hash);
+		assert_eq!(output.result.data, vec![1, 2, 3, 4]);
+		assert!(result.gas_consumed > 0);
+		assert_eq!(
+			result.events.into_iter().map(|event| (event.contract, event.data)).collect::<Vec<_>>(),
+			vec![(addr, vec![1, 2, 3, 4])],
+		);
+	});
+}
+
+#[test]
+fn upload_code_reserves_deposit_and_remove_code_refunds_it() {
+	let (wasm, code_hash) = compile_module::<Test>("self_destruct").unwrap();
+	let deposit = 2 * wasm.len() as u64;
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.code_deposit_per_byte(2)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			let _ = Balances::deposit_creating(&BOB, 1_000_000);
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm.clone()));
+			assert_eq!(Balances::reserved_balance(&ALICE), deposit);
+			assert_eq!(OwnerInfoOf::<Test>::get(&code_hash), Some(RawOwnerInfo {
+				owner: ALICE,
+				deposit,
+				refcount: 0,
+			}));
+
+			// Nobody can take over the ownership by uploading the same code again.
+			assert_noop!(
+				Contracts::upload_code(Origin::signed(BOB), wasm),
+				Error::<Test>::CodeExists,
+			);
+
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(BOB),
+				100_000,
+				GAS_LIMIT,
+				code_hash.into(),
+				vec![],
+				vec![],
+			));
+			let addr = Contracts::contract_address(&BOB, &code_hash, &[]);
+			assert_eq!(OwnerInfoOf::<Test>::get(&code_hash).unwrap().refcount, 1);
+
+			assert_noop!(
+				Contracts::remove_code(Origin::signed(BOB), code_hash),
+				Error::<Test>::NotCodeOwner,
+			);
+			assert_noop!(
+				Contracts::remove_code(Origin::signed(ALICE), code_hash),
+				Error::<Test>::CodeInUse,
+			);
+
+			// Calling without input data terminates the contract.
+			assert_ok!(Contracts::call(Origin::signed(BOB), addr, 0, GAS_LIMIT, vec![]));
+			assert_eq!(OwnerInfoOf::<Test>::get(&code_hash).unwrap().refcount, 0);
+
+			assert_ok!(Contracts::remove_code(Origin::signed(ALICE), code_hash));
+			assert_eq!(Balances::reserved_balance(&ALICE), 0);
+			assert!(OwnerInfoOf::<Test>::get(&code_hash).is_none());
+			assert!(crate::PristineCode::<Test>::get(&code_hash).is_none());
+			assert!(crate::CodeStorage::<Test>::get(&code_hash).is_none());
+			assert_eq!(
+				System::events().last().map(|record| record.event.clone()),
+				Some(MetaEvent::contracts(RawEvent::CodeRemoved(code_hash))),
+			);
+		});
+}
+
+#[test]
+fn upload_code_fails_without_deposit() {
+	let (wasm, _code_hash) = compile_module::<Test>("self_destruct").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.code_deposit_per_byte(2)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, wasm.len() as u64);
+			assert_noop!(
+				Contracts::upload_code(Origin::signed(ALICE), wasm),
+				Error::<Test>::CodeDepositNotEnoughFunds,
+			);
+		});
+}
+
+#[test]
+fn tombstones_keep_their_code_in_use() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				100,
+				GAS_LIMIT,
+				code_hash.into(),
+				<Test as pallet_balances::Config>::Balance::from(1_000u32).encode(),
+				vec![],
+			));
+			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+			assert_eq!(OwnerInfoOf::<Test>::get(&code_hash).unwrap().refcount, 1);
+
+			// Not enough balance to pay rent for a long time: the contract becomes a tombstone.
+			initialize_block(20);
+			assert_ok!(Contracts::claim_surcharge(Origin::none(), addr.clone(), Some(ALICE)));
+			assert!(ContractInfoOf::<Test>::get(&addr).unwrap().get_tombstone().is_some());
+
+			// The tombstone can still be restored and therefore keeps the code in use.
+			assert_eq!(OwnerInfoOf::<Test>::get(&code_hash).unwrap().refcount, 1);
+			assert_noop!(
+				Contracts::remove_code(Origin::signed(ALICE), code_hash),
+				Error::<Test>::CodeInUse,
+			);
+		});
+}
+
+#[test]
+fn disabled_chain_extension_wont_deploy() {
+	let (wasm, _code_hash) = compile_module::<Test>("chain_extension").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		TestExtension::disable();
+		assert_noop!(
+			Contracts::upload_code(Origin::signed(ALICE), wasm),
+			"module uses chain extensions but chain extensions are disabled",
+		);
+	});
+}
+
+#[test]
+fn chain_extension_works() {
+	let (wasm, code_hash) = compile_module::<Test>("chain_extension").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let subsistence = ConfigCache::<Test>::subsistence_threshold_uncached();
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm));
+		assert_ok!(Contracts::instantiate(
+			Origin::signed(ALICE),
+			subsistence,
+			GAS_LIMIT,
+			code_hash.into(),
+			vec![],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+
+		// The contract passes the input through to the extension which writes it back
+		// into the output buffer.
+		let result = Contracts::bare_call(ALICE, addr.clone(), 0, GAS_LIMIT, vec![0, 99]);
+		assert_eq!(TestExtension::last_seen_buffer(), vec![0, 99]);
+		assert_eq!(result.result.unwrap().data, vec![0, 99]);
+
+		// The extension charges the weight passed as second byte.
+		let gas_consumed = Contracts::bare_call(ALICE, addr.clone(), 0, GAS_LIMIT, vec![1, 0])
+			.gas_consumed;
+		let result = Contracts::bare_call(ALICE, addr.clone(), 0, GAS_LIMIT, vec![1, 42]);
+		assert_ok!(result.result);
+		assert_eq!(result.gas_consumed, gas_consumed + 42);
+
+		// Diverging returns the supplied data without resuming the contract.
+		let result = Contracts::bare_call(ALICE, addr.clone(), 0, GAS_LIMIT, vec![2, 0])
+			.result
+			.unwrap();
+		assert_eq!(result.flags, ReturnFlags::REVERT);
+		assert_eq!(result.data, vec![42, 99]);
+
+		// An unknown function id traps the contract with the error of the extension.
+		let result = Contracts::bare_call(ALICE, addr, 0, GAS_LIMIT, vec![3, 0]);
+		assert_eq!(
+			result.result.unwrap_err().error,
+			DispatchError::from(Error::<Test>::DecodingFailed),
+		);
+	});
+}
@@ -25,31 +25,92 @@
 //! - When we update the schedule we want it to have strictly greater version than the current saved one:
 //! this guarantees that every instrumented contract code in cache cannot have the version equal to the current one.
 //! Thus, before executing a contract it should be reinstrument with new schedule.
+//! - Code uploaded through `upload_code` is owned by the uploader who reserves a deposit for it.
+//! The owner can remove the code and get the deposit back once no contract uses it anymore.
 
 use crate::wasm::{prepare, runtime::Env, PrefabWasmModule};
-use crate::{CodeHash, CodeStorage, PristineCode, Schedule, Config};
+use crate::{
+	BalanceOf, CodeHash, CodeStorage, Config, Error, Module, OwnerInfo, OwnerInfoOf, PristineCode,
+	RawEvent, Schedule,
+};
 use sp_std::prelude::*;
-use sp_runtime::traits::Hash;
+use sp_runtime::{DispatchError, DispatchResult, traits::{Hash, Saturating}};
 use sp_core::crypto::UncheckedFrom;
-use frame_support::StorageMap;
+use frame_support::{StorageMap, ensure, traits::{Get, ReservableCurrency}};
 
 /// Put code in the storage. The hash of code is used as a key and is returned
 /// as a result of this function.
 ///
-/// This function instruments the given code and caches it in the storage.
+/// This function instruments the given code and caches it in the storage. The `owner`
+/// reserves a deposit proportional to the size of the code.
 pub fn save<T: Config>(
 	original_code: Vec<u8>,
 	schedule: &Schedule<T>,
-) -> Result<CodeHash<T>, &'static str> where T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]> {
+	owner: &T::AccountId,
+) -> Result<CodeHash<T>, DispatchError> where T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]> {
 	let prefab_module = prepare::prepare_contract::<Env, T>(&original_code, schedule)?;
 	let code_hash = T::Hashing::hash(&original_code);
+	ensure!(!<PristineCode<T>>::contains_key(&code_hash), Error::<T>::CodeExists);
+
+	let deposit = T::CodeDepositPerByte::get()
+		.saturating_mul(BalanceOf::<T>::from(original_code.len() as u32));
+	T::Currency::reserve(owner, deposit)
+		.map_err(|_| Error::<T>::CodeDepositNotEnoughFunds)?;
 
 	<CodeStorage<T>>::insert(code_hash, prefab_module);
 	<PristineCode<T>>::insert(code_hash, original_code);
+	<OwnerInfoOf<T>>::insert(code_hash, OwnerInfo::<T> {
+		owner: owner.clone(),
+		deposit,
+		refcount: 0,
+	});
+	<Module<T>>::deposit_event(RawEvent::CodeStored(code_hash));
 
 	Ok(code_hash)
 }
 
+/// Remove the code with the given hash and refund the deposit to its owner.
+///
+/// This fails if `origin` isn't the owner of the code or if the code is still in use.
+pub fn remove<T: Config>(
+	origin: &T::AccountId,
+	code_hash: CodeHash<T>,
+) -> DispatchResult where T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]> {
+	let owner_info = <OwnerInfoOf<T>>::get(&code_hash).ok_or(Error::<T>::CodeNotFound)?;
+	ensure!(&owner_info.owner == origin, Error::<T>::NotCodeOwner);
+	ensure!(owner_info.refcount == 0, Error::<T>::CodeInUse);
+
+	T::Currency::unreserve(&owner_info.owner, owner_info.deposit);
+	<OwnerInfoOf<T>>::remove(&code_hash);
+	<CodeStorage<T>>::remove(&code_hash);
+	<PristineCode<T>>::remove(&code_hash);
+	<Module<T>>::deposit_event(RawEvent::CodeRemoved(code_hash));
+
+	Ok(())
+}
+
+/// Increment the number of contracts that use the given code.
+///
+/// Code that was stored without an owner isn't tracked.
+pub fn increment_refcount<T: Config>(code_hash: &CodeHash<T>) {
+	<OwnerInfoOf<T>>::mutate(code_hash, |owner_info| {
+		if let Some(owner_info) = owner_info {
+			owner_info.refcount = owner_info.refcount.saturating_add(1);
+		}
+	});
+}
+
+/// Decrement the number of contracts that use the given code.
+///
+/// Code that was stored without an owner isn't tracked.
+pub fn decrement_refcount<T: Config>(code_hash: &CodeHash<T>) {
+	<OwnerInfoOf<T>>::mutate(code_hash, |owner_info| {
+		if let Some(owner_info) = owner_info {
+			owner_info.refcount = owner_info.refcount.saturating_sub(1);
+		}
+	});
+}
+
 /// Version of `save` to be used in runtime benchmarks.
 //
 /// This version neither checks nor instruments the passed in code. This is useful
@@ -33,14 +33,15 @@ mod code_cache;
 mod prepare;
 mod runtime;
 
-use self::runtime::Runtime;
 use self::code_cache::load as load_code;
 use pallet_contracts_primitives::ExecResult;
 
-pub use self::code_cache::save as save_code;
+pub use self::code_cache::{
+	save as save_code, remove as remove_code, increment_refcount, decrement_refcount,
+};
 #[cfg(feature = "runtime-benchmarks")]
 pub use self::code_cache::save_raw as save_code_raw;
-pub use self::runtime::ReturnCode;
+pub use self::runtime::{ReturnCode, Runtime, RuntimeToken};
 
 /// A prepared wasm module ready for execution.
 #[derive(Clone, Encode, Decode)]
@@ -220,6 +221,7 @@ mod tests {
 		restores: Vec<RestoreEntry>,
 		// (topics, data)
 		events: Vec<(Vec<H256>, Vec<u8>)>,
+		debug_buffer: Vec<u8>,
 	}
 
 	impl Ext for MockExt {
@@ -228,8 +230,13 @@ mod tests {
 		fn get_storage(&self, key: &StorageKey) -> Option<Vec<u8>> {
 			self.storage.get(key).cloned()
 		}
-		fn set_storage(&mut self, key: StorageKey, value: Option<Vec<u8>>) {
+		fn set_storage(
+			&mut self,
+			key: StorageKey,
+			value: Option<Vec<u8>>,
+		) -> Result<(), DispatchError> {
 			*self.storage.entry(key).or_insert(Vec::new()) = value.unwrap_or(Vec::new());
+			Ok(())
 		}
 		fn instantiate(
 			&mut self,
@@ -354,6 +361,10 @@ mod tests {
 		fn get_weight_price(&self, weight: Weight) -> BalanceOf<Self::T> {
 			BalanceOf::<Self::T>::from(1312_u32).saturating_mul(weight.into())
 		}
+		fn append_debug_buffer(&mut self, msg: &str) -> bool {
+			self.debug_buffer.extend(msg.as_bytes());
+			true
+		}
 	}
 
 	impl Ext for &mut MockExt {
@@ -362,7 +373,11 @@ mod tests {
 		fn get_storage(&self, key: &[u8; 32]) -> Option<Vec<u8>> {
 			(**self).get_storage(key)
 		}
-		fn set_storage(&mut self, key: [u8; 32], value: Option<Vec<u8>>) {
+		fn set_storage(
+			&mut self,
+			key: [u8; 32],
+			value: Option<Vec<u8>>,
+		) -> Result<(), DispatchError> {
 			(**self).set_storage(key, value)
 		}
 		fn instantiate(
@@ -453,6 +468,9 @@ mod tests {
 		fn get_weight_price(&self, weight: Weight) -> BalanceOf<Self::T> {
 			(**self).get_weight_price(weight)
 		}
+		fn append_debug_buffer(&mut self, msg: &str) -> bool {
+			(**self).append_debug_buffer(msg)
+		}
 	}
 
 	fn execute<E: Ext>(
@@ -468,7 +486,10 @@ mod tests {
 		use crate::exec::Vm;
 
 		let wasm = wat::parse_str(wat).unwrap();
-		let schedule = crate::Schedule::default();
+		let schedule = crate::Schedule {
+			enable_println: true,
+			..Default::default()
+		};
 		let prefab_module =
 			prepare_contract::<super::runtime::Env, E::T>(&wasm, &schedule).unwrap();
 
@@ -1497,6 +1518,36 @@ mod tests {
 		assert!(gas_meter.gas_left() > 0);
 	}
 
+	const CODE_PRINTLN: &str = r#"
+(module
+	(import "seal0" "seal_println" (func $seal_println (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "call")
+		(call $seal_println
+			(i32.const 0) ;; Pointer to the text buffer
+			(i32.const 12) ;; The size of the buffer
+		)
+	)
+	(func (export "deploy"))
+
+	(data (i32.const 0) "Hello World!")
+)
+"#;
+
+	#[test]
+	fn println_appends_to_debug_buffer() {
+		let mut mock_ext = MockExt::default();
+		let _ = execute(
+			CODE_PRINTLN,
+			vec![],
+			&mut mock_ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		).unwrap();
+
+		assert_eq!(mock_ext.debug_buffer, b"Hello World!".to_vec());
+	}
+
 	const CODE_DEPOSIT_EVENT_MAX_TOPICS: &str = r#"
 (module
 	(import "seal0" "seal_deposit_event" (func $seal_deposit_event (param i32 i32 i32 i32)))
@@ -18,6 +18,7 @@
 
 use crate::{
 	HostFnWeights, Schedule, Config, CodeHash, BalanceOf, Error,
+	deposit::Deposit,
 	exec::{Ext, StorageKey, TopicOf},
 	gas::{Gas, GasMeter, Token, GasMeterResult},
 	wasm::env_def::ConvertibleToWasm,
@@ -191,6 +192,12 @@ pub enum RuntimeToken {
 	HashBlake256(u32),
 	/// Weight of calling `seal_hash_blake2_128` for the given input size.
 	HashBlake128(u32),
+	/// Weight of calling `seal_call_chain_extension`.
+	CallChainExtension,
+	/// Weight charged by a chain extension through its `Environment`.
+	ChainExtension(Gas),
+	/// Weight of settling the storage deposit after the contract storage was changed.
+	SettleStorageDeposit,
 }
 
 impl<T: Config> Token<T> for RuntimeToken
@@ -249,6 +256,9 @@ where
 				.saturating_add(s.hash_blake2_256_per_byte.saturating_mul(len.into())),
 			HashBlake128(len) => s.hash_blake2_128
 				.saturating_add(s.hash_blake2_128_per_byte.saturating_mul(len.into())),
+			CallChainExtension => s.call_chain_extension,
+			ChainExtension(amount) => amount,
+			SettleStorageDeposit => s.settle_storage_deposit,
 		}
 	}
 }
@@ -369,10 +379,29 @@ where
 		}
 	}
 
+	/// Get a mutable reference to the inner `Ext`.
+	///
+	/// This is mainly for the chain extension to have access to the environment the
+	/// contract is executing in.
+	pub(crate) fn ext(&mut self) -> &mut E {
+		self.ext
+	}
+
+	/// Takes the error that was stored by a failed host function helper.
+	///
+	/// Used by the chain extension to turn a `HostError` returned by one of the helpers
+	/// back into the `DispatchError` that caused it.
+	pub(crate) fn take_error(&mut self) -> DispatchError {
+		match self.trap_reason.take() {
+			Some(TrapReason::SupervisorError(err)) => err,
+			_ => Error::<E::T>::ContractTrapped.into(),
+		}
+	}
+
 	/// Charge the gas meter with the specified token.
 	///
 	/// Returns `Err(HostError)` if there is not enough gas.
-	fn charge_gas<Tok>(&mut self, token: Tok) -> Result<(), sp_sandbox::HostError>
+	pub(crate) fn charge_gas<Tok>(&mut self, token: Tok) -> Result<(), sp_sandbox::HostError>
 	where
 		Tok: Token<E::T, Metadata=HostFnWeights<E::T>>,
 	{
@@ -392,7 +421,7 @@ where
 	/// Returns `Err` if one of the following conditions occurs:
 	///
 	/// - requested buffer is not within the bounds of the sandbox memory.
-	fn read_sandbox_memory(&mut self, ptr: u32, len: u32)
+	pub(crate) fn read_sandbox_memory(&mut self, ptr: u32, len: u32)
 	-> Result<Vec<u8>, sp_sandbox::HostError>
 	{
 		let mut buf = vec![0u8; len as usize];
@@ -453,7 +482,7 @@ where
 	///
 	/// In addition to the error conditions of `write_sandbox_memory` this functions returns
 	/// `Err` if the size of the buffer located at `out_ptr` is too small to fit `buf`.
-	fn write_sandbox_output(
+	pub(crate) fn write_sandbox_output(
 		&mut self,
 		out_ptr: u32,
 		out_len_ptr: u32,
@@ -635,15 +664,19 @@ define_env!(Env, <E: Ext>,
 	//
 	// - If value length exceeds the configured maximum value length of a storage entry.
 	// - Upon trying to set an empty storage entry (value length is 0).
+	// - If the origin can't pay the storage deposit required for the new value.
 	seal_set_storage(ctx, key_ptr: u32, value_ptr: u32, value_len: u32) => {
 		ctx.charge_gas(RuntimeToken::SetStorage(value_len))?;
+		if Deposit::<E::T>::is_enabled() {
+			ctx.charge_gas(RuntimeToken::SettleStorageDeposit)?;
+		}
 		if value_len > ctx.ext.max_value_size() {
 			Err(ctx.store_err(Error::<E::T>::ValueTooLarge))?;
 		}
 		let mut key: StorageKey = [0; 32];
 		ctx.read_sandbox_memory_into_buf(key_ptr, &mut key)?;
 		let value = Some(ctx.read_sandbox_memory(value_ptr, value_len)?);
-		ctx.ext.set_storage(key, value);
+		ctx.ext.set_storage(key, value).map_err(|err| ctx.store_err(err))?;
 		Ok(())
 	},
 
@@ -654,9 +687,12 @@ define_env!(Env, <E: Ext>,
 	// - `key_ptr`: pointer into the linear memory where the location to clear the value is placed.
 	seal_clear_storage(ctx, key_ptr: u32) => {
 		ctx.charge_gas(RuntimeToken::ClearStorage)?;
+		if Deposit::<E::T>::is_enabled() {
+			ctx.charge_gas(RuntimeToken::SettleStorageDeposit)?;
+		}
 		let mut key: StorageKey = [0; 32];
 		ctx.read_sandbox_memory_into_buf(key_ptr, &mut key)?;
-		ctx.ext.set_storage(key, None);
+		ctx.ext.set_storage(key, None).map_err(|err| ctx.store_err(err))?;
 		Ok(())
 	},
 
@@ -922,6 +958,9 @@ define_env!(Env, <E: Ext>,
 		beneficiary_len: u32
 	) => {
 		ctx.charge_gas(RuntimeToken::Terminate)?;
+		if Deposit::<E::T>::is_enabled() {
+			ctx.charge_gas(RuntimeToken::SettleStorageDeposit)?;
+		}
 		let beneficiary: <<E as Ext>::T as frame_system::Config>::AccountId =
 			ctx.read_sandbox_memory_as(beneficiary_ptr, beneficiary_len)?;
 
@@ -1280,9 +1319,12 @@ define_env!(Env, <E: Ext>,
 	// Prints utf8 encoded string from the data buffer.
 	// Only available on `--dev` chains.
 	// This function may be removed at any time, superseded by a more general contract debugging feature.
+	//
+	// The string is also appended to the debug buffer which is returned by dry runs.
 	seal_println(ctx, str_ptr: u32, str_len: u32) => {
 		let data = ctx.read_sandbox_memory(str_ptr, str_len)?;
 		if let Ok(utf8) = core::str::from_utf8(&data) {
+			ctx.ext.append_debug_buffer(utf8);
 			sp_runtime::print(utf8);
 		}
 		Ok(())
@@ -1400,4 +1442,51 @@ define_env!(Env, <E: Ext>,
 		ctx.charge_gas(RuntimeToken::HashBlake128(input_len))?;
 		ctx.compute_hash_on_intermediate_buffer(blake2_128, input_ptr, input_len, output_ptr)
 	},
+
+	// Call into the chain extension provided by the chain if any.
+	//
+	// Handling of the input values is up to the specific chain extension and so is the
+	// return value. The extension can decide to use the inputs as primitive inputs or as
+	// in/out arguments by interpreting them as pointers. Any caller of this function
+	// must therefore coordinate with the chain that it targets.
+	//
+	// # Parameters
+	//
+	// - func_id: selects the function of the chain extension that is called.
+	// - input_ptr: a pointer to the input buffer handed to the chain extension.
+	// - input_len: length of the input buffer.
+	// - output_ptr: a pointer where the chain extension can write its output buffer to.
+	// - output_len_ptr: in-out pointer to where the length of the buffer is read from
+	//   and the actual length is written to.
+	//
+	// # Note
+	//
+	// If no chain extension exists the contract will trap with the `NoChainExtension`
+	// module error.
+	seal_call_chain_extension(
+		ctx,
+		func_id: u32,
+		input_ptr: u32,
+		input_len: u32,
+		output_ptr: u32,
+		output_len_ptr: u32
+	) -> u32 => {
+		use crate::chain_extension::{ChainExtension, Environment, RetVal};
+		if !<<E::T as Config>::ChainExtension as ChainExtension<E::T>>::enabled() {
+			Err(ctx.store_err(Error::<E::T>::NoChainExtension))?;
+		}
+		ctx.charge_gas(RuntimeToken::CallChainExtension)?;
+		let env = Environment::new(ctx, input_ptr, input_len, output_ptr, output_len_ptr);
+		match <<E::T as Config>::ChainExtension as ChainExtension<E::T>>::call(func_id, env) {
+			Ok(RetVal::Converging(val)) => Ok(val),
+			Ok(RetVal::Diverging{flags, data}) => {
+				ctx.trap_reason = Some(TrapReason::Return(ReturnData {
+					flags: flags.bits(),
+					data,
+				}));
+				Err(sp_sandbox::HostError)
+			},
+			Err(err) => Err(ctx.store_err(err)),
+		}
+	},
 );
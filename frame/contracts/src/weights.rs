@@ -44,10 +44,12 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for pallet_contracts.
 pub trait WeightInfo {
 	fn update_schedule() -> Weight;
-	fn put_code(n: u32, ) -> Weight;
+	fn upload_code(n: u32, ) -> Weight;
+	fn remove_code() -> Weight;
 	fn instantiate(n: u32, s: u32, ) -> Weight;
 	fn call() -> Weight;
 	fn claim_surcharge() -> Weight;
+	fn settle_storage_deposit() -> Weight;
 	fn seal_caller(r: u32, ) -> Weight;
 	fn seal_address(r: u32, ) -> Weight;
 	fn seal_gas_left(r: u32, ) -> Weight;
@@ -89,6 +91,7 @@ pub trait WeightInfo {
 	fn seal_hash_blake2_256_per_kb(n: u32, ) -> Weight;
 	fn seal_hash_blake2_128(r: u32, ) -> Weight;
 	fn seal_hash_blake2_128_per_kb(n: u32, ) -> Weight;
+	fn seal_call_chain_extension(r: u32, ) -> Weight;
 	fn instr_i64const(r: u32, ) -> Weight;
 	fn instr_i64load(r: u32, ) -> Weight;
 	fn instr_i64store(r: u32, ) -> Weight;
@@ -150,11 +153,16 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
-	fn put_code(n: u32, ) -> Weight {
+	fn upload_code(n: u32, ) -> Weight {
 		(0 as Weight)
 			.saturating_add((109_242_000 as Weight).saturating_mul(n as Weight))
-			.saturating_add(T::DbWeight::get().reads(1 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn remove_code() -> Weight {
+		(48_507_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
 	}
 	fn instantiate(n: u32, s: u32, ) -> Weight {
 		(195_276_000 as Weight)
@@ -173,6 +181,11 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 	}
+	fn settle_storage_deposit() -> Weight {
+		(98_417_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
 	fn seal_caller(r: u32, ) -> Weight {
 		(136_550_000 as Weight)
 			.saturating_add((373_182_000 as Weight).saturating_mul(r as Weight))
@@ -411,6 +424,11 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add((153_146_000 as Weight).saturating_mul(n as Weight))
 			.saturating_add(T::DbWeight::get().reads(4 as Weight))
 	}
+	fn seal_call_chain_extension(r: u32, ) -> Weight {
+		(142_378_000 as Weight)
+			.saturating_add((255_913_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+	}
 	fn instr_i64const(r: u32, ) -> Weight {
 		(26_679_000 as Weight)
 			.saturating_add((3_155_000 as Weight).saturating_mul(r as Weight))
@@ -624,11 +642,16 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
-	fn put_code(n: u32, ) -> Weight {
+	fn upload_code(n: u32, ) -> Weight {
 		(0 as Weight)
 			.saturating_add((109_242_000 as Weight).saturating_mul(n as Weight))
-			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn remove_code() -> Weight {
+		(48_507_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
 	}
 	fn instantiate(n: u32, s: u32, ) -> Weight {
 		(195_276_000 as Weight)
@@ -647,6 +670,11 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 	}
+	fn settle_storage_deposit() -> Weight {
+		(98_417_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
 	fn seal_caller(r: u32, ) -> Weight {
 		(136_550_000 as Weight)
 			.saturating_add((373_182_000 as Weight).saturating_mul(r as Weight))
@@ -885,6 +913,11 @@ impl WeightInfo for () {
 			.saturating_add((153_146_000 as Weight).saturating_mul(n as Weight))
 			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
 	}
+	fn seal_call_chain_extension(r: u32, ) -> Weight {
+		(142_378_000 as Weight)
+			.saturating_add((255_913_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+	}
 	fn instr_i64const(r: u32, ) -> Weight {
 		(26_679_000 as Weight)
 			.saturating_add((3_155_000 as Weight).saturating_mul(r as Weight))
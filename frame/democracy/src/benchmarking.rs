@@ -25,14 +25,13 @@ use frame_support::{
 	traits::{Currency, Get, EnsureOrigin, OnInitialize, UnfilteredDispatchable, schedule::DispatchTime},
 };
 use frame_system::{RawOrigin, Module as System, self, EventRecord};
-use sp_runtime::traits::{Bounded, One};
+use sp_runtime::traits::{Bounded, Hash};
 
 use crate::Module as Democracy;
 
 const SEED: u32 = 0;
 const MAX_REFERENDUMS: u32 = 99;
 const MAX_SECONDERS: u32 = 100;
-const MAX_BYTES: u32 = 16_384;
 
 fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
 	let events = System::<T>::events();
@@ -147,7 +146,7 @@ benchmarks! {
 			let ref_idx = add_referendum::<T>(i)?;
 			Democracy::<T>::vote(RawOrigin::Signed(caller.clone()).into(), ref_idx, account_vote.clone())?;
 		}
-		let votes = match VotingOf::<T>::get(&caller) {
+		let votes = match VotingOf::<T>::get(&caller, T::VoteClass::default()) {
 			Voting::Direct { votes, .. } => votes,
 			_ => return Err("Votes are not direct"),
 		};
@@ -157,7 +156,7 @@ benchmarks! {
 		whitelist_account!(caller);
 	}: vote(RawOrigin::Signed(caller.clone()), referendum_index, account_vote)
 	verify {
-		let votes = match VotingOf::<T>::get(&caller) {
+		let votes = match VotingOf::<T>::get(&caller, T::VoteClass::default()) {
 			Voting::Direct { votes, .. } => votes,
 			_ => return Err("Votes are not direct"),
 		};
@@ -175,7 +174,7 @@ benchmarks! {
 			let ref_idx = add_referendum::<T>(i)?;
 			Democracy::<T>::vote(RawOrigin::Signed(caller.clone()).into(), ref_idx, account_vote.clone())?;
 		}
-		let votes = match VotingOf::<T>::get(&caller) {
+		let votes = match VotingOf::<T>::get(&caller, T::VoteClass::default()) {
 			Voting::Direct { votes, .. } => votes,
 			_ => return Err("Votes are not direct"),
 		};
@@ -190,7 +189,7 @@ benchmarks! {
 		whitelist_account!(caller);
 	}: vote(RawOrigin::Signed(caller.clone()), referendum_index, new_vote)
 	verify {
-		let votes = match VotingOf::<T>::get(&caller) {
+		let votes = match VotingOf::<T>::get(&caller, T::VoteClass::default()) {
 			Voting::Direct { votes, .. } => votes,
 			_ => return Err("Votes are not direct"),
 		};
@@ -461,11 +460,12 @@ benchmarks! {
 		let old_delegate: T::AccountId = funded_account::<T>("old_delegate", r);
 		Democracy::<T>::delegate(
 			RawOrigin::Signed(caller.clone()).into(),
+			T::VoteClass::default(),
 			old_delegate.clone(),
 			Conviction::Locked1x,
 			delegated_balance,
 		)?;
-		let (target, balance) = match VotingOf::<T>::get(&caller) {
+		let (target, balance) = match VotingOf::<T>::get(&caller, T::VoteClass::default()) {
 			Voting::Delegating { target, balance, .. } => (target, balance),
 			_ => return Err("Votes are not direct"),
 		};
@@ -479,21 +479,27 @@ benchmarks! {
 			let ref_idx = add_referendum::<T>(i)?;
 			Democracy::<T>::vote(RawOrigin::Signed(new_delegate.clone()).into(), ref_idx, account_vote.clone())?;
 		}
-		let votes = match VotingOf::<T>::get(&new_delegate) {
+		let votes = match VotingOf::<T>::get(&new_delegate, T::VoteClass::default()) {
 			Voting::Direct { votes, .. } => votes,
 			_ => return Err("Votes are not direct"),
 		};
 		assert_eq!(votes.len(), r as usize, "Votes were not recorded.");
 		whitelist_account!(caller);
-	}: _(RawOrigin::Signed(caller.clone()), new_delegate.clone(), Conviction::Locked1x, delegated_balance)
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		T::VoteClass::default(),
+		new_delegate.clone(),
+		Conviction::Locked1x,
+		delegated_balance
+	)
 	verify {
-		let (target, balance) = match VotingOf::<T>::get(&caller) {
+		let (target, balance) = match VotingOf::<T>::get(&caller, T::VoteClass::default()) {
 			Voting::Delegating { target, balance, .. } => (target, balance),
 			_ => return Err("Votes are not direct"),
 		};
 		assert_eq!(target, new_delegate, "delegation target didn't work");
 		assert_eq!(balance, delegated_balance, "delegation balance didn't work");
-		let delegations = match VotingOf::<T>::get(&new_delegate) {
+		let delegations = match VotingOf::<T>::get(&new_delegate, T::VoteClass::default()) {
 			Voting::Direct { delegations, .. } => delegations,
 			_ => return Err("Votes are not direct"),
 		};
@@ -511,11 +517,12 @@ benchmarks! {
 		let the_delegate: T::AccountId = funded_account::<T>("delegate", r);
 		Democracy::<T>::delegate(
 			RawOrigin::Signed(caller.clone()).into(),
+			T::VoteClass::default(),
 			the_delegate.clone(),
 			Conviction::Locked1x,
 			delegated_balance,
 		)?;
-		let (target, balance) = match VotingOf::<T>::get(&caller) {
+		let (target, balance) = match VotingOf::<T>::get(&caller, T::VoteClass::default()) {
 			Voting::Delegating { target, balance, .. } => (target, balance),
 			_ => return Err("Votes are not direct"),
 		};
@@ -531,16 +538,16 @@ benchmarks! {
 				account_vote.clone()
 			)?;
 		}
-		let votes = match VotingOf::<T>::get(&the_delegate) {
+		let votes = match VotingOf::<T>::get(&the_delegate, T::VoteClass::default()) {
 			Voting::Direct { votes, .. } => votes,
 			_ => return Err("Votes are not direct"),
 		};
 		assert_eq!(votes.len(), r as usize, "Votes were not recorded.");
 		whitelist_account!(caller);
-	}: _(RawOrigin::Signed(caller.clone()))
+	}: _(RawOrigin::Signed(caller.clone()), T::VoteClass::default())
 	verify {
 		// Voting should now be direct
-		match VotingOf::<T>::get(&caller) {
+		match VotingOf::<T>::get(&caller, T::VoteClass::default()) {
 			Voting::Direct { .. } => (),
 			_ => return Err("undelegation failed"),
 		}
@@ -551,68 +558,6 @@ benchmarks! {
 
 	}: _(RawOrigin::Root)
 
-	note_preimage {
-		// Num of bytes in encoded proposal
-		let b in 0 .. MAX_BYTES;
-
-		let caller = funded_account::<T>("caller", 0);
-		let encoded_proposal = vec![1; b as usize];
-		whitelist_account!(caller);
-	}: _(RawOrigin::Signed(caller), encoded_proposal.clone())
-	verify {
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		match Preimages::<T>::get(proposal_hash) {
-			Some(PreimageStatus::Available { .. }) => (),
-			_ => return Err("preimage not available")
-		}
-	}
-
-	note_imminent_preimage {
-		// Num of bytes in encoded proposal
-		let b in 0 .. MAX_BYTES;
-
-		// d + 1 to include the one we are testing
-		let encoded_proposal = vec![1; b as usize];
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		let block_number = T::BlockNumber::one();
-		Preimages::<T>::insert(&proposal_hash, PreimageStatus::Missing(block_number));
-
-		let caller = funded_account::<T>("caller", 0);
-		let encoded_proposal = vec![1; b as usize];
-		whitelist_account!(caller);
-	}: _(RawOrigin::Signed(caller), encoded_proposal.clone())
-	verify {
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		match Preimages::<T>::get(proposal_hash) {
-			Some(PreimageStatus::Available { .. }) => (),
-			_ => return Err("preimage not available")
-		}
-	}
-
-	reap_preimage {
-		// Num of bytes in encoded proposal
-		let b in 0 .. MAX_BYTES;
-
-		let encoded_proposal = vec![1; b as usize];
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-
-		let submitter = funded_account::<T>("submitter", b);
-		Democracy::<T>::note_preimage(RawOrigin::Signed(submitter.clone()).into(), encoded_proposal.clone())?;
-
-		// We need to set this otherwise we get `Early` error.
-		let block_number = T::VotingPeriod::get() + T::EnactmentPeriod::get() + T::BlockNumber::one();
-		System::<T>::set_block_number(block_number.into());
-
-		assert!(Preimages::<T>::contains_key(proposal_hash));
-
-		let caller = funded_account::<T>("caller", 0);
-		whitelist_account!(caller);
-	}: _(RawOrigin::Signed(caller), proposal_hash.clone(), u32::max_value())
-	verify {
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		assert!(!Preimages::<T>::contains_key(proposal_hash));
-	}
-
 	// Test when unlock will remove locks
 	unlock_remove {
 		let r in 1 .. MAX_REFERENDUMS;
@@ -630,10 +575,10 @@ benchmarks! {
 
 		let caller = funded_account::<T>("caller", 0);
 		whitelist_account!(caller);
-	}: unlock(RawOrigin::Signed(caller), locker.clone())
+	}: unlock(RawOrigin::Signed(caller), T::VoteClass::default(), locker.clone())
 	verify {
 		// Note that we may want to add a `get_lock` api to actually verify
-		let voting = VotingOf::<T>::get(&locker);
+		let voting = VotingOf::<T>::get(&locker, T::VoteClass::default());
 		assert_eq!(voting.locked_balance(), BalanceOf::<T>::zero());
 	}
 
@@ -655,28 +600,28 @@ benchmarks! {
 		let referendum_index = add_referendum::<T>(r)?;
 		Democracy::<T>::vote(RawOrigin::Signed(locker.clone()).into(), referendum_index, big_vote)?;
 
-		let votes = match VotingOf::<T>::get(&locker) {
+		let votes = match VotingOf::<T>::get(&locker, T::VoteClass::default()) {
 			Voting::Direct { votes, .. } => votes,
 			_ => return Err("Votes are not direct"),
 		};
 		assert_eq!(votes.len(), (r + 1) as usize, "Votes were not recorded.");
 
-		let voting = VotingOf::<T>::get(&locker);
+		let voting = VotingOf::<T>::get(&locker, T::VoteClass::default());
 		assert_eq!(voting.locked_balance(), base_balance * 10u32.into());
 
 		Democracy::<T>::remove_vote(RawOrigin::Signed(locker.clone()).into(), referendum_index)?;
 
 		let caller = funded_account::<T>("caller", 0);
 		whitelist_account!(caller);
-	}: unlock(RawOrigin::Signed(caller), locker.clone())
+	}: unlock(RawOrigin::Signed(caller), T::VoteClass::default(), locker.clone())
 	verify {
-		let votes = match VotingOf::<T>::get(&locker) {
+		let votes = match VotingOf::<T>::get(&locker, T::VoteClass::default()) {
 			Voting::Direct { votes, .. } => votes,
 			_ => return Err("Votes are not direct"),
 		};
 		assert_eq!(votes.len(), r as usize, "Vote was not removed");
 
-		let voting = VotingOf::<T>::get(&locker);
+		let voting = VotingOf::<T>::get(&locker, T::VoteClass::default());
 		// Note that we may want to add a `get_lock` api to actually verify
 		assert_eq!(voting.locked_balance(), base_balance);
 	}
@@ -692,7 +637,7 @@ benchmarks! {
 			Democracy::<T>::vote(RawOrigin::Signed(caller.clone()).into(), ref_idx, account_vote.clone())?;
 		}
 
-		let votes = match VotingOf::<T>::get(&caller) {
+		let votes = match VotingOf::<T>::get(&caller, T::VoteClass::default()) {
 			Voting::Direct { votes, .. } => votes,
 			_ => return Err("Votes are not direct"),
 		};
@@ -702,7 +647,7 @@ benchmarks! {
 		whitelist_account!(caller);
 	}: _(RawOrigin::Signed(caller.clone()), referendum_index)
 	verify {
-		let votes = match VotingOf::<T>::get(&caller) {
+		let votes = match VotingOf::<T>::get(&caller, T::VoteClass::default()) {
 			Voting::Direct { votes, .. } => votes,
 			_ => return Err("Votes are not direct"),
 		};
@@ -721,7 +666,7 @@ benchmarks! {
 			Democracy::<T>::vote(RawOrigin::Signed(caller.clone()).into(), ref_idx, account_vote.clone())?;
 		}
 
-		let votes = match VotingOf::<T>::get(&caller) {
+		let votes = match VotingOf::<T>::get(&caller, T::VoteClass::default()) {
 			Voting::Direct { votes, .. } => votes,
 			_ => return Err("Votes are not direct"),
 		};
@@ -731,7 +676,7 @@ benchmarks! {
 		whitelist_account!(caller);
 	}: _(RawOrigin::Signed(caller.clone()), caller.clone(), referendum_index)
 	verify {
-		let votes = match VotingOf::<T>::get(&caller) {
+		let votes = match VotingOf::<T>::get(&caller, T::VoteClass::default()) {
 			Voting::Direct { votes, .. } => votes,
 			_ => return Err("Votes are not direct"),
 		};
@@ -740,19 +685,15 @@ benchmarks! {
 
 	#[extra]
 	enact_proposal_execute {
-		// Num of bytes in encoded proposal
-		let b in 0 .. MAX_BYTES;
-
-		let proposer = funded_account::<T>("proposer", 0);
-		let raw_call = Call::note_preimage(vec![1; b as usize]);
+		let raw_call = Call::remove_vote(0);
 		let generic_call: T::Proposal = raw_call.into();
 		let encoded_proposal = generic_call.encode();
 		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		Democracy::<T>::note_preimage(RawOrigin::Signed(proposer).into(), encoded_proposal)?;
+		T::Preimages::request_preimage(&proposal_hash);
+		T::Preimages::note_preimage(encoded_proposal);
 
-		match Preimages::<T>::get(proposal_hash) {
-			Some(PreimageStatus::Available { .. }) => (),
-			_ => return Err("preimage not available")
+		if !T::Preimages::have_preimage(&proposal_hash) {
+			return Err("preimage not available")
 		}
 	}: enact_proposal(RawOrigin::Root, proposal_hash, 0)
 	verify {
@@ -761,19 +702,18 @@ benchmarks! {
 	}
 
 	#[extra]
-	enact_proposal_slash {
+	enact_proposal_invalid {
 		// Num of bytes in encoded proposal
-		let b in 0 .. MAX_BYTES;
+		let b in 0 .. <T::Preimages as PreimageRecipient<T::Hash>>::MaxSize::get();
 
-		let proposer = funded_account::<T>("proposer", 0);
 		// Random invalid bytes
 		let encoded_proposal = vec![200; b as usize];
 		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		Democracy::<T>::note_preimage(RawOrigin::Signed(proposer).into(), encoded_proposal)?;
+		T::Preimages::request_preimage(&proposal_hash);
+		T::Preimages::note_preimage(encoded_proposal);
 
-		match Preimages::<T>::get(proposal_hash) {
-			Some(PreimageStatus::Available { .. }) => (),
-			_ => return Err("preimage not available")
+		if !T::Preimages::have_preimage(&proposal_hash) {
+			return Err("preimage not available")
 		}
 	}: {
 		assert_eq!(
@@ -810,15 +750,12 @@ mod tests {
 			assert_ok!(test_benchmark_delegate::<Test>());
 			assert_ok!(test_benchmark_undelegate::<Test>());
 			assert_ok!(test_benchmark_clear_public_proposals::<Test>());
-			assert_ok!(test_benchmark_note_preimage::<Test>());
-			assert_ok!(test_benchmark_note_imminent_preimage::<Test>());
-			assert_ok!(test_benchmark_reap_preimage::<Test>());
 			assert_ok!(test_benchmark_unlock_remove::<Test>());
 			assert_ok!(test_benchmark_unlock_set::<Test>());
 			assert_ok!(test_benchmark_remove_vote::<Test>());
 			assert_ok!(test_benchmark_remove_other_vote::<Test>());
 			assert_ok!(test_benchmark_enact_proposal_execute::<Test>());
-			assert_ok!(test_benchmark_enact_proposal_slash::<Test>());
+			assert_ok!(test_benchmark_enact_proposal_invalid::<Test>());
 			assert_ok!(test_benchmark_blacklist::<Test>());
 			assert_ok!(test_benchmark_cancel_proposal::<Test>());
 		});
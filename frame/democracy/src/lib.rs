@@ -54,6 +54,9 @@
 //!   either acceptance or rejection as a change to the system.
 //! - **Delegation:** The act of granting your voting power to the decisions of another account for
 //!   up to a certain conviction.
+//! - **Vote Class:** The kind of a referendum, determined from its proposal by
+//!   `Config::Classifier` once its preimage is available. Delegations and the locks of voters
+//!   are kept separately for each class.
 //!
 //! ### Adaptive Quorum Biasing
 //!
@@ -84,23 +87,21 @@
 //! - `vote` - Votes in a referendum, either the vote is "Aye" to enact the proposal or "Nay" to
 //!   keep the status quo.
 //! - `unvote` - Cancel a previous vote, this must be done by the voter before the vote ends.
-//! - `delegate` - Delegates the voting power (tokens * conviction) to another account.
-//! - `undelegate` - Stops the delegation of voting power to another account.
+//! - `delegate` - Delegates the voting power (tokens * conviction) on one class of referenda to
+//!   another account.
+//! - `undelegate` - Stops the delegation of voting power on one class of referenda to another
+//!   account.
 //!
 //! Administration actions that can be done to any account:
 //! - `reap_vote` - Remove some account's expired votes.
 //! - `unlock` - Redetermine the account's balance lock, potentially making tokens available.
+//! - `reclassify_vote` - Move a vote cast before the class of its referendum was known into that
+//!   class.
 //!
-//! Preimage actions:
-//! - `note_preimage` - Registers the preimage for an upcoming proposal, requires
-//!   a deposit that is returned once the proposal is enacted.
-//! - `note_preimage_operational` - same but provided by `T::OperationalPreimageOrigin`.
-//! - `note_imminent_preimage` - Registers the preimage for an upcoming proposal.
-//!   Does not require a deposit, but the proposal must be in the dispatch queue.
-//! - `note_imminent_preimage_operational` - same but provided by `T::OperationalPreimageOrigin`.
-//! - `reap_preimage` - Removes the preimage for an expired proposal. Will only
-//!   work under the condition that it's the same account that noted it and
-//!   after the voting period, OR it's a different account after the enactment period.
+//! Preimages of proposals are not stored by this pallet. They are noted with the pallet
+//! configured as `Config::Preimages` (usually the preimage pallet). A preimage is requested
+//! once a referendum on its hash starts, which makes noting it free of deposit, and the request
+//! is dropped again once the referendum is cancelled, rejected or enacted.
 //!
 //! #### Cancellation Origin
 //!
@@ -155,15 +156,16 @@
 use sp_std::prelude::*;
 use sp_runtime::{
 	DispatchResult, DispatchError, RuntimeDebug,
-	traits::{Zero, Hash, Dispatchable, Saturating, Bounded},
+	traits::{Zero, Dispatchable, Saturating, Bounded},
 };
-use codec::{Encode, Decode, Input};
+use codec::{Encode, Decode};
 use frame_support::{
 	decl_module, decl_storage, decl_event, decl_error, ensure, Parameter,
-	weights::{Weight, DispatchClass, Pays},
+	weights::{Weight, DispatchClass},
 	traits::{
-		Currency, ReservableCurrency, LockableCurrency, WithdrawReasons, LockIdentifier, Get,
-		OnUnbalanced, BalanceStatus, schedule::{Named as ScheduleNamed, DispatchTime}, EnsureOrigin
+		Currency, NamedReservableCurrency, ReserveIdentifier, LockableCurrency, WithdrawReasons,
+		LockIdentifier, Get, OnUnbalanced, schedule::{Named as ScheduleNamed, DispatchTime},
+		EnsureOrigin, PreimageProvider, PreimageRecipient,
 	},
 	dispatch::DispatchResultWithPostInfo,
 };
@@ -173,12 +175,15 @@ mod vote_threshold;
 mod vote;
 mod conviction;
 mod types;
+pub mod migrations;
 pub mod weights;
 pub use weights::WeightInfo;
 pub use vote_threshold::{Approved, VoteThreshold};
-pub use vote::{Vote, AccountVote, Voting};
+pub use vote::{Vote, AccountVote, Voting, PriorLock};
 pub use conviction::Conviction;
-pub use types::{ReferendumInfo, ReferendumStatus, Tally, UnvoteScope, Delegations};
+pub use types::{
+	ReferendumInfo, ReferendumStatus, Tally, UnvoteScope, Delegations, ClassifyProposal,
+};
 
 #[cfg(test)]
 mod tests;
@@ -188,6 +193,9 @@ pub mod benchmarking;
 
 const DEMOCRACY_ID: LockIdentifier = *b"democrac";
 
+/// The identifier of the reserve holding the deposits of public proposals.
+pub const PROPOSAL_DEPOSIT_ID: ReserveIdentifier = *b"democrac";
+
 /// The maximum number of vetoers on a single proposal used to compute Weight.
 ///
 /// NOTE: This is not enforced by any logic.
@@ -208,7 +216,7 @@ pub trait Config: frame_system::Config + Sized {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
 
 	/// Currency type for this module.
-	type Currency: ReservableCurrency<Self::AccountId>
+	type Currency: NamedReservableCurrency<Self::AccountId, ReserveIdentifier = ReserveIdentifier>
 		+ LockableCurrency<Self::AccountId, Moment=Self::BlockNumber>;
 
 	/// The minimum period of locking and the period between a proposal being approved and enacted.
@@ -277,13 +285,10 @@ pub trait Config: frame_system::Config + Sized {
 	/// Period in blocks where an external proposal may not be re-submitted after being vetoed.
 	type CooloffPeriod: Get<Self::BlockNumber>;
 
-	/// The amount of balance that must be deposited per byte of preimage stored.
-	type PreimageByteDeposit: Get<BalanceOf<Self>>;
-
-	/// An origin that can provide a preimage using operational extrinsics.
-	type OperationalPreimageOrigin: EnsureOrigin<Self::Origin, Success=Self::AccountId>;
+	/// The preimage provider used to look up the preimages of proposals.
+	type Preimages: PreimageRecipient<Self::Hash>;
 
-	/// Handler for the unbalanced reduction when slashing a preimage deposit.
+	/// Handler for the unbalanced reduction when slashing a proposal deposit.
 	type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
 
 	/// The Scheduler.
@@ -292,12 +297,22 @@ pub trait Config: frame_system::Config + Sized {
 	/// Overarching type of all pallets origins.
 	type PalletsOrigin: From<system::RawOrigin<Self::AccountId>>;
 
-	/// The maximum number of votes for an account.
+	/// The maximum number of votes for an account in a single class.
 	///
 	/// Also used to compute weight, an overly big value can
 	/// lead to extrinsic with very big weight: see `delegate` for instance.
 	type MaxVotes: Get<u32>;
 
+	/// The class of a referendum. Voting power is delegated separately for each class.
+	type VoteClass: Parameter + Ord + Copy + Default;
+
+	/// Determines the class of a referendum from its proposal.
+	///
+	/// The class is resolved when the referendum starts or, if the preimage is not available yet,
+	/// by the first vote after it is noted. Votes cast before that are of the default class until
+	/// they are changed or moved with `reclassify_vote`.
+	type Classifier: ClassifyProposal<Self::Proposal, Self::VoteClass>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 
@@ -305,36 +320,15 @@ pub trait Config: frame_system::Config + Sized {
 	type MaxProposals: Get<u32>;
 }
 
-#[derive(Clone, Encode, Decode, RuntimeDebug)]
-pub enum PreimageStatus<AccountId, Balance, BlockNumber> {
-	/// The preimage is imminently needed at the argument.
-	Missing(BlockNumber),
-	/// The preimage is available.
-	Available {
-		data: Vec<u8>,
-		provider: AccountId,
-		deposit: Balance,
-		since: BlockNumber,
-		/// None if it's not imminent.
-		expiry: Option<BlockNumber>,
-	},
-}
-
-impl<AccountId, Balance, BlockNumber> PreimageStatus<AccountId, Balance, BlockNumber> {
-	fn to_missing_expiry(self) -> Option<BlockNumber> {
-		match self {
-			PreimageStatus::Missing(expiry) => Some(expiry),
-			_ => None,
-		}
-	}
-}
-
 // A value placed in storage that represents the current version of the Democracy storage.
 // This value is used by the `on_runtime_upgrade` logic to determine whether we run
 // storage migration logic.
-#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
 enum Releases {
 	V1,
+	V2,
+	V3,
+	V4,
 }
 
 decl_storage! {
@@ -351,14 +345,6 @@ decl_storage! {
 		pub DepositOf get(fn deposit_of):
 			map hasher(twox_64_concat) PropIndex => Option<(Vec<T::AccountId>, BalanceOf<T>)>;
 
-		/// Map of hashes to the proposal preimage, along with who registered it and their deposit.
-		/// The block number is the block at which it was deposited.
-		// TODO: Refactor Preimages into its own pallet.
-		// https://github.com/paritytech/substrate/issues/5322
-		pub Preimages:
-			map hasher(identity) T::Hash
-			=> Option<PreimageStatus<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
-
 		/// The next free referendum index, aka the number of referenda started so far.
 		pub ReferendumCount get(fn referendum_count) build(|_| 0 as ReferendumIndex): ReferendumIndex;
 		/// The lowest referendum index representing an unbaked referendum. Equal to
@@ -372,11 +358,36 @@ decl_storage! {
 			map hasher(twox_64_concat) ReferendumIndex
 			=> Option<ReferendumInfo<T::BlockNumber, T::Hash, BalanceOf<T>>>;
 
-		/// All votes for a particular voter. We store the balance for the number of votes that we
-		/// have recorded. The second item is the total amount of delegations, that will be added.
+		/// The class of each ongoing referendum. It is resolved from the preimage once that is
+		/// available; until then votes are of the default class.
+		///
+		/// TWOX-NOTE: SAFE as indexes are not under an attacker’s control.
+		pub ReferendumClass get(fn referendum_class):
+			map hasher(twox_64_concat) ReferendumIndex => Option<T::VoteClass>;
+
+		/// All votes for a particular voter in a class of referenda. We store the balance for the
+		/// number of votes that we have recorded. The second item is the total amount of
+		/// delegations, that will be added.
+		///
+		/// TWOX-NOTE: SAFE as `AccountId`s are crypto hashes anyway.
+		pub VotingOf: double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) T::VoteClass
+			=> Voting<BalanceOf<T>, T::AccountId, T::BlockNumber>;
+
+		/// The class in which the vote of an account on a referendum is recorded in `VotingOf`.
+		/// Votes without an entry here are of the default class.
 		///
 		/// TWOX-NOTE: SAFE as `AccountId`s are crypto hashes anyway.
-		pub VotingOf: map hasher(twox_64_concat) T::AccountId => Voting<BalanceOf<T>, T::AccountId, T::BlockNumber>;
+		pub VoteClassOf get(fn vote_class_of): double_map
+			hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) ReferendumIndex
+			=> Option<T::VoteClass>;
+
+		/// The voting classes which have a non-zero lock requirement for an account, and the lock
+		/// amounts which they require. The actual amount locked on behalf of this pallet is the
+		/// maximum of these.
+		///
+		/// TWOX-NOTE: OK ― `AccountId` is a secure hash.
+		pub ClassLocksFor get(fn class_locks_for):
+			map hasher(twox_64_concat) T::AccountId => Vec<(T::VoteClass, BalanceOf<T>)>;
 
 		/// Accounts for which there are locks in action which may be removed at some point in the
 		/// future. The value is the block number at which the lock expires and may be removed.
@@ -406,7 +417,7 @@ decl_storage! {
 		/// Storage version of the pallet.
 		///
 		/// New networks start with last version.
-		StorageVersion build(|_| Some(Releases::V1)): Option<Releases>;
+		StorageVersion build(|_| Some(Releases::V4)): Option<Releases>;
 	}
 }
 
@@ -416,6 +427,7 @@ decl_event! {
 		<T as frame_system::Config>::AccountId,
 		<T as frame_system::Config>::Hash,
 		<T as frame_system::Config>::BlockNumber,
+		<T as Config>::VoteClass,
 	{
 		/// A motion has been proposed by a public account. \[proposal_index, deposit\]
 		Proposed(PropIndex, Balance),
@@ -433,26 +445,19 @@ decl_event! {
 		Cancelled(ReferendumIndex),
 		/// A proposal has been enacted. \[ref_index, is_ok\]
 		Executed(ReferendumIndex, bool),
-		/// An account has delegated their vote to another account. \[who, target\]
-		Delegated(AccountId, AccountId),
-		/// An \[account\] has cancelled a previous delegation operation.
-		Undelegated(AccountId),
+		/// An account has delegated their vote in a class to another account.
+		/// \[who, target, class\]
+		Delegated(AccountId, AccountId, VoteClass),
+		/// An account has cancelled a previous delegation operation in a class. \[who, class\]
+		Undelegated(AccountId, VoteClass),
 		/// An external proposal has been vetoed. \[who, proposal_hash, until\]
 		Vetoed(AccountId, Hash, BlockNumber),
-		/// A proposal's preimage was noted, and the deposit taken. \[proposal_hash, who, deposit\]
-		PreimageNoted(Hash, AccountId, Balance),
-		/// A proposal preimage was removed and used (the deposit was returned).
-		/// \[proposal_hash, provider, deposit\]
-		PreimageUsed(Hash, AccountId, Balance),
 		/// A proposal could not be executed because its preimage was invalid.
 		/// \[proposal_hash, ref_index\]
 		PreimageInvalid(Hash, ReferendumIndex),
 		/// A proposal could not be executed because its preimage was missing.
 		/// \[proposal_hash, ref_index\]
 		PreimageMissing(Hash, ReferendumIndex),
-		/// A registered preimage was removed and the deposit collected by the reaper.
-		/// \[proposal_hash, provider, deposit, reaper\]
-		PreimageReaped(Hash, AccountId, Balance, AccountId),
 		/// An \[account\] has been unlocked successfully.
 		Unlocked(AccountId),
 		/// A proposal \[hash\] has been blacklisted permanently.
@@ -484,14 +489,6 @@ decl_error! {
 		AlreadyVetoed,
 		/// Not delegated
 		NotDelegated,
-		/// Preimage already noted
-		DuplicatePreimage,
-		/// Not imminent
-		NotImminent,
-		/// Too early
-		TooEarly,
-		/// Imminent
-		Imminent,
 		/// Preimage not found
 		PreimageMissing,
 		/// Vote given for invalid referendum
@@ -533,6 +530,8 @@ decl_error! {
 		InvalidWitness,
 		/// Maximum number of proposals reached.
 		TooManyProposals,
+		/// The vote is already recorded in the class of the referendum.
+		AlreadyClassified,
 	}
 }
 
@@ -562,14 +561,17 @@ decl_module! {
 		/// Period in blocks where an external proposal may not be re-submitted after being vetoed.
 		const CooloffPeriod: T::BlockNumber = T::CooloffPeriod::get();
 
-		/// The amount of balance that must be deposited per byte of preimage stored.
-		const PreimageByteDeposit: BalanceOf<T> = T::PreimageByteDeposit::get();
-
 		/// The maximum number of votes for an account.
 		const MaxVotes: u32 = T::MaxVotes::get();
 
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			migrations::migrate_to_v2::<T>()
+				.saturating_add(migrations::migrate_to_v3::<T>())
+				.saturating_add(migrations::migrate_to_v4::<T>())
+		}
+
 		/// Propose a sensitive action to be taken.
 		///
 		/// The dispatch origin of this call must be _Signed_ and the sender must
@@ -601,7 +603,7 @@ decl_module! {
 				);
 			}
 
-			T::Currency::reserve(&who, value)?;
+			T::Currency::reserve_named(&PROPOSAL_DEPOSIT_ID, &who, value)?;
 			PublicPropCount::put(index + 1);
 			<DepositOf<T>>::insert(index, (&[&who][..], value));
 
@@ -629,7 +631,7 @@ decl_module! {
 			ensure!(seconds <= seconds_upper_bound, Error::<T>::WrongUpperBound);
 			let mut deposit = Self::deposit_of(proposal)
 				.ok_or(Error::<T>::ProposalMissing)?;
-			T::Currency::reserve(&who, deposit.1)?;
+			T::Currency::reserve_named(&PROPOSAL_DEPOSIT_ID, &who, deposit.1)?;
 			deposit.0.push(who);
 			<DepositOf<T>>::insert(proposal, deposit);
 		}
@@ -642,9 +644,13 @@ decl_module! {
 		/// - `ref_index`: The index of the referendum to vote for.
 		/// - `vote`: The vote configuration.
 		///
+		/// If the vote was cast before the class of the referendum was known, it is moved into that
+		/// class.
+		///
 		/// Weight: `O(R)` where R is the number of referendums the voter has voted on.
 		#[weight = T::WeightInfo::vote_new(T::MaxVotes::get())
-			.max(T::WeightInfo::vote_existing(T::MaxVotes::get()))]
+			.max(T::WeightInfo::vote_existing(T::MaxVotes::get()))
+			.saturating_add(T::DbWeight::get().reads_writes(3, 2))]
 		fn vote(origin,
 			#[compact] ref_index: ReferendumIndex,
 			vote: AccountVote<BalanceOf<T>>,
@@ -848,16 +854,18 @@ decl_module! {
 			})
 		}
 
-		/// Delegate the voting power (with some given conviction) of the sending account.
+		/// Delegate the voting power (with some given conviction) of the sending account for a
+		/// particular class of referenda.
 		///
 		/// The balance delegated is locked for as long as it's delegated, and thereafter for the
 		/// time appropriate for the conviction's lock period.
 		///
 		/// The dispatch origin of this call must be _Signed_, and the signing account must either:
-		///   - be delegating already; or
-		///   - have no voting activity (if there is, then it will need to be removed/consolidated
-		///     through `reap_vote` or `unvote`).
+		///   - be delegating already in this class; or
+		///   - have no voting activity in this class (if there is, then it will need to be
+		///     removed/consolidated through `reap_vote` or `unvote`).
 		///
+		/// - `class`: The class of referenda to which the delegation applies.
 		/// - `to`: The account whose voting the `target` account's voting power will follow.
 		/// - `conviction`: The conviction that will be attached to the delegated votes. When the
 		///   account is undelegated, the funds will be locked for the corresponding period.
@@ -873,23 +881,26 @@ decl_module! {
 		#[weight = T::WeightInfo::delegate(T::MaxVotes::get())]
 		pub fn delegate(
 			origin,
+			class: T::VoteClass,
 			to: T::AccountId,
 			conviction: Conviction,
 			balance: BalanceOf<T>
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
-			let votes = Self::try_delegate(who, to, conviction, balance)?;
+			let votes = Self::try_delegate(who, class, to, conviction, balance)?;
 
 			Ok(Some(T::WeightInfo::delegate(votes)).into())
 		}
 
-		/// Undelegate the voting power of the sending account.
+		/// Undelegate the voting power of the sending account for a particular class of referenda.
 		///
 		/// Tokens may be unlocked following once an amount of time consistent with the lock period
 		/// of the conviction with which the delegation was issued.
 		///
 		/// The dispatch origin of this call must be _Signed_ and the signing account must be
-		/// currently delegating.
+		/// currently delegating in this class.
+		///
+		/// - `class`: The class of referenda for which the delegation is removed.
 		///
 		/// Emits `Undelegated`.
 		///
@@ -898,9 +909,9 @@ decl_module! {
 		// NOTE: weight must cover an incorrect voting of origin with max votes, this is ensure
 		// because a valid delegation cover decoding a direct voting with max votes.
 		#[weight = T::WeightInfo::undelegate(T::MaxVotes::get().into())]
-		fn undelegate(origin) -> DispatchResultWithPostInfo {
+		fn undelegate(origin, class: T::VoteClass) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
-			let votes = Self::try_undelegate(who)?;
+			let votes = Self::try_undelegate(who, class)?;
 			Ok(Some(T::WeightInfo::undelegate(votes)).into())
 		}
 
@@ -915,118 +926,22 @@ decl_module! {
 			<PublicProps<T>>::kill();
 		}
 
-		/// Register the preimage for an upcoming proposal. This doesn't require the proposal to be
-		/// in the dispatch queue but does require a deposit, returned once enacted.
-		///
-		/// The dispatch origin of this call must be _Signed_.
-		///
-		/// - `encoded_proposal`: The preimage of a proposal.
-		///
-		/// Emits `PreimageNoted`.
-		///
-		/// Weight: `O(E)` with E size of `encoded_proposal` (protected by a required deposit).
-		#[weight = T::WeightInfo::note_preimage(encoded_proposal.len() as u32)]
-		fn note_preimage(origin, encoded_proposal: Vec<u8>) {
-			Self::note_preimage_inner(ensure_signed(origin)?, encoded_proposal)?;
-		}
-
-		/// Same as `note_preimage` but origin is `OperationalPreimageOrigin`.
-		#[weight = (
-			T::WeightInfo::note_preimage(encoded_proposal.len() as u32),
-			DispatchClass::Operational,
-		)]
-		fn note_preimage_operational(origin, encoded_proposal: Vec<u8>) {
-			let who = T::OperationalPreimageOrigin::ensure_origin(origin)?;
-			Self::note_preimage_inner(who, encoded_proposal)?;
-		}
-
-		/// Register the preimage for an upcoming proposal. This requires the proposal to be
-		/// in the dispatch queue. No deposit is needed. When this call is successful, i.e.
-		/// the preimage has not been uploaded before and matches some imminent proposal,
-		/// no fee is paid.
-		///
-		/// The dispatch origin of this call must be _Signed_.
-		///
-		/// - `encoded_proposal`: The preimage of a proposal.
-		///
-		/// Emits `PreimageNoted`.
-		///
-		/// Weight: `O(E)` with E size of `encoded_proposal` (protected by a required deposit).
-		#[weight = T::WeightInfo::note_imminent_preimage(encoded_proposal.len() as u32)]
-		fn note_imminent_preimage(origin, encoded_proposal: Vec<u8>) -> DispatchResultWithPostInfo {
-			Self::note_imminent_preimage_inner(ensure_signed(origin)?, encoded_proposal)?;
-			// We check that this preimage was not uploaded before in `note_imminent_preimage_inner`,
-			// thus this call can only be successful once. If successful, user does not pay a fee.
-			Ok(Pays::No.into())
-		}
-
-		/// Same as `note_imminent_preimage` but origin is `OperationalPreimageOrigin`.
-		#[weight = (
-			T::WeightInfo::note_imminent_preimage(encoded_proposal.len() as u32),
-			DispatchClass::Operational,
-		)]
-		fn note_imminent_preimage_operational(origin, encoded_proposal: Vec<u8>) -> DispatchResultWithPostInfo {
-			let who = T::OperationalPreimageOrigin::ensure_origin(origin)?;
-			Self::note_imminent_preimage_inner(who, encoded_proposal)?;
-			// We check that this preimage was not uploaded before in `note_imminent_preimage_inner`,
-			// thus this call can only be successful once. If successful, user does not pay a fee.
-			Ok(Pays::No.into())
-		}
-
-		/// Remove an expired proposal preimage and collect the deposit.
-		///
-		/// The dispatch origin of this call must be _Signed_.
-		///
-		/// - `proposal_hash`: The preimage hash of a proposal.
-		/// - `proposal_length_upper_bound`: an upper bound on length of the proposal.
-		///   Extrinsic is weighted according to this value with no refund.
-		///
-		/// This will only work after `VotingPeriod` blocks from the time that the preimage was
-		/// noted, if it's the same account doing it. If it's a different account, then it'll only
-		/// work an additional `EnactmentPeriod` later.
-		///
-		/// Emits `PreimageReaped`.
-		///
-		/// Weight: `O(D)` where D is length of proposal.
-		#[weight = T::WeightInfo::reap_preimage(*proposal_len_upper_bound)]
-		fn reap_preimage(origin, proposal_hash: T::Hash, #[compact] proposal_len_upper_bound: u32) {
-			let who = ensure_signed(origin)?;
-
-			ensure!(
-				Self::pre_image_data_len(proposal_hash)? <= proposal_len_upper_bound,
-				Error::<T>::WrongUpperBound,
-			);
-
-			let (provider, deposit, since, expiry) = <Preimages<T>>::get(&proposal_hash)
-				.and_then(|m| match m {
-					PreimageStatus::Available { provider, deposit, since, expiry, .. }
-						=> Some((provider, deposit, since, expiry)),
-					_ => None,
-				}).ok_or(Error::<T>::PreimageMissing)?;
-
-			let now = <frame_system::Module<T>>::block_number();
-			let (voting, enactment) = (T::VotingPeriod::get(), T::EnactmentPeriod::get());
-			let additional = if who == provider { Zero::zero() } else { enactment };
-			ensure!(now >= since + voting + additional, Error::<T>::TooEarly);
-			ensure!(expiry.map_or(true, |e| now > e), Error::<T>::Imminent);
-
-			let _ = T::Currency::repatriate_reserved(&provider, &who, deposit, BalanceStatus::Free);
-			<Preimages<T>>::remove(&proposal_hash);
-			Self::deposit_event(RawEvent::PreimageReaped(proposal_hash, provider, deposit, who));
-		}
-
 		/// Unlock tokens that have an expired lock.
 		///
+		/// The lock required by the votes and delegations of `target` in `class` is redetermined.
+		/// The account stays locked for the maximum of the locks required by all of its classes.
+		///
 		/// The dispatch origin of this call must be _Signed_.
 		///
+		/// - `class`: The class of referenda whose lock is redetermined.
 		/// - `target`: The account to remove the lock on.
 		///
 		/// Weight: `O(R)` with R number of vote of target.
 		#[weight = T::WeightInfo::unlock_set(T::MaxVotes::get())
 			.max(T::WeightInfo::unlock_remove(T::MaxVotes::get()))]
-		fn unlock(origin, target: T::AccountId) {
+		fn unlock(origin, class: T::VoteClass, target: T::AccountId) {
 			ensure_signed(origin)?;
-			Self::update_lock(&target);
+			Self::update_lock(&target, class);
 		}
 
 		/// Remove a vote for a referendum.
@@ -1056,7 +971,8 @@ decl_module! {
 		///
 		/// Weight: `O(R + log R)` where R is the number of referenda that `target` has voted on.
 		///   Weight is calculated for the maximum number of vote.
-		#[weight = T::WeightInfo::remove_vote(T::MaxVotes::get())]
+		#[weight = T::WeightInfo::remove_vote(T::MaxVotes::get())
+			.saturating_add(T::DbWeight::get().reads_writes(1, 1))]
 		fn remove_vote(origin, index: ReferendumIndex) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			Self::try_remove_vote(&who, index, UnvoteScope::Any)
@@ -1077,7 +993,8 @@ decl_module! {
 		///
 		/// Weight: `O(R + log R)` where R is the number of referenda that `target` has voted on.
 		///   Weight is calculated for the maximum number of vote.
-		#[weight = T::WeightInfo::remove_other_vote(T::MaxVotes::get())]
+		#[weight = T::WeightInfo::remove_other_vote(T::MaxVotes::get())
+			.saturating_add(T::DbWeight::get().reads_writes(1, 1))]
 		fn remove_other_vote(origin, target: T::AccountId, index: ReferendumIndex) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			let scope = if target == who { UnvoteScope::Any } else { UnvoteScope::OnlyExpired };
@@ -1123,9 +1040,7 @@ decl_module! {
 				if let Some(index) = props.iter().position(|p| p.1 == proposal_hash) {
 					let (prop_index, ..) = props.remove(index);
 					if let Some((whos, amount)) = DepositOf::<T>::take(prop_index) {
-						for who in whos.into_iter() {
-							T::Slash::on_unbalanced(T::Currency::slash_reserved(&who, amount).0);
-						}
+						Self::slash_proposal_deposits(whos, amount);
 					}
 				}
 			});
@@ -1160,11 +1075,31 @@ decl_module! {
 
 			PublicProps::<T>::mutate(|props| props.retain(|p| p.0 != prop_index));
 			if let Some((whos, amount)) = DepositOf::<T>::take(prop_index) {
-				for who in whos.into_iter() {
-					T::Slash::on_unbalanced(T::Currency::slash_reserved(&who, amount).0);
-				}
+				Self::slash_proposal_deposits(whos, amount);
 			}
 		}
+
+		/// Move the vote of `target` on an ongoing referendum into the class of the referendum.
+		///
+		/// A vote cast before the preimage of the proposal is available is recorded in the default
+		/// class, so it does not carry the voting power delegated to `target` in the class of the
+		/// referendum. Once that class is known, anyone may move the vote so that these
+		/// delegations are counted.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `target`: The account whose vote is moved.
+		/// - `index`: The index of the referendum on which `target` voted.
+		///
+		/// Weight: `O(R)` where R is the number of referenda that `target` has voted on.
+		///   Weight is calculated for the maximum number of vote.
+		#[weight = T::WeightInfo::remove_vote(T::MaxVotes::get())
+			.saturating_add(T::WeightInfo::vote_new(T::MaxVotes::get()))
+			.saturating_add(T::DbWeight::get().reads_writes(3, 2))]
+		fn reclassify_vote(origin, target: T::AccountId, #[compact] index: ReferendumIndex) {
+			ensure_signed(origin)?;
+			Self::try_reclassify_vote(&target, index)?;
+		}
 	}
 }
 
@@ -1219,7 +1154,10 @@ impl<T: Config> Module<T> {
 	/// Remove a referendum.
 	pub fn internal_cancel_referendum(ref_index: ReferendumIndex) {
 		Self::deposit_event(RawEvent::Cancelled(ref_index));
-		ReferendumInfoOf::<T>::remove(ref_index);
+		if let Some(ReferendumInfo::Ongoing(status)) = ReferendumInfoOf::<T>::take(ref_index) {
+			T::Preimages::unrequest_preimage(&status.proposal_hash);
+		}
+		ReferendumClass::<T>::remove(ref_index);
 	}
 
 	// private.
@@ -1246,9 +1184,19 @@ impl<T: Config> Module<T> {
 	fn try_vote(who: &T::AccountId, ref_index: ReferendumIndex, vote: AccountVote<BalanceOf<T>>) -> DispatchResult {
 		let mut status = Self::referendum_status(ref_index)?;
 		ensure!(vote.balance() <= T::Currency::free_balance(who), Error::<T>::InsufficientFunds);
-		VotingOf::<T>::try_mutate(who, |voting| -> DispatchResult {
+		let class = Self::resolve_class(ref_index, &status.proposal_hash);
+		let old_class = Self::vote_class_of(who, ref_index).unwrap_or_default();
+		VotingOf::<T>::try_mutate(who, class, |voting| -> DispatchResult {
 			if let Voting::Direct { ref mut votes, delegations, .. } = voting {
-				match votes.binary_search_by_key(&ref_index, |i| i.0) {
+				let position = votes.binary_search_by_key(&ref_index, |i| i.0);
+				if position.is_err() {
+					ensure!(votes.len() as u32 <= T::MaxVotes::get(), Error::<T>::MaxVotesReached);
+				}
+				if old_class != class {
+					// The vote was cast before the class of the referendum was known.
+					Self::unrecord_vote(who, old_class, ref_index, &mut status)?;
+				}
+				match position {
 					Ok(i) => {
 						// Shouldn't be possible to fail, but we handle it gracefully.
 						status.tally.remove(votes[i].1).ok_or(Error::<T>::Underflow)?;
@@ -1257,10 +1205,7 @@ impl<T: Config> Module<T> {
 						}
 						votes[i].1 = vote;
 					}
-					Err(i) => {
-						ensure!(votes.len() as u32 <= T::MaxVotes::get(), Error::<T>::MaxVotesReached);
-						votes.insert(i, (ref_index, vote));
-					}
+					Err(i) => votes.insert(i, (ref_index, vote)),
 				}
 				// Shouldn't be possible to fail, but we handle it gracefully.
 				status.tally.add(vote).ok_or(Error::<T>::Overflow)?;
@@ -1272,18 +1217,56 @@ impl<T: Config> Module<T> {
 				Err(Error::<T>::AlreadyDelegating.into())
 			}
 		})?;
+		if class == Default::default() {
+			VoteClassOf::<T>::remove(who, ref_index);
+		} else {
+			VoteClassOf::<T>::insert(who, ref_index, class);
+		}
 		// Extend the lock to `balance` (rather than setting it) since we don't know what other
 		// votes are in place.
-		T::Currency::extend_lock(
-			DEMOCRACY_ID,
-			who,
-			vote.balance(),
-			WithdrawReasons::TRANSFER
-		);
+		Self::extend_lock(who, class, vote.balance());
 		ReferendumInfoOf::<T>::insert(ref_index, ReferendumInfo::Ongoing(status));
 		Ok(())
 	}
 
+	/// Remove the vote of `who` on the ongoing referendum `ref_index` from `class`, along with
+	/// its share of the tally in `status`. Does nothing if there is no such vote.
+	fn unrecord_vote(
+		who: &T::AccountId,
+		class: T::VoteClass,
+		ref_index: ReferendumIndex,
+		status: &mut ReferendumStatus<T::BlockNumber, T::Hash, BalanceOf<T>>,
+	) -> DispatchResult {
+		if let Voting::Direct { mut votes, delegations, prior } = VotingOf::<T>::get(who, class) {
+			if let Ok(i) = votes.binary_search_by_key(&ref_index, |i| i.0) {
+				// Shouldn't be possible to fail, but we handle it gracefully.
+				status.tally.remove(votes[i].1).ok_or(Error::<T>::Underflow)?;
+				if let Some(approve) = votes[i].1.as_standard() {
+					status.tally.reduce(approve, delegations);
+				}
+				votes.remove(i);
+				VotingOf::<T>::insert(who, class, Voting::Direct { votes, delegations, prior });
+			}
+		}
+		Ok(())
+	}
+
+	/// Move the vote of `who` on the ongoing referendum `ref_index` into the class of the
+	/// referendum.
+	fn try_reclassify_vote(who: &T::AccountId, ref_index: ReferendumIndex) -> DispatchResult {
+		let status = Self::referendum_status(ref_index)?;
+		let old_class = Self::vote_class_of(who, ref_index).unwrap_or_default();
+		let class = Self::resolve_class(ref_index, &status.proposal_hash);
+		ensure!(old_class != class, Error::<T>::AlreadyClassified);
+		let vote = match VotingOf::<T>::get(who, old_class) {
+			Voting::Direct { votes, .. } => votes.binary_search_by_key(&ref_index, |i| i.0)
+				.ok()
+				.map(|i| votes[i].1),
+			Voting::Delegating { .. } => None,
+		}.ok_or(Error::<T>::NotVoter)?;
+		Self::try_vote(who, ref_index, vote)
+	}
+
 	/// Remove the account's vote for the given referendum if possible. This is possible when:
 	/// - The referendum has not finished.
 	/// - The referendum has finished and the voter lost their direction.
@@ -1292,7 +1275,8 @@ impl<T: Config> Module<T> {
 	/// This will generally be combined with a call to `unlock`.
 	fn try_remove_vote(who: &T::AccountId, ref_index: ReferendumIndex, scope: UnvoteScope) -> DispatchResult {
 		let info = ReferendumInfoOf::<T>::get(ref_index);
-		VotingOf::<T>::try_mutate(who, |voting| -> DispatchResult {
+		let class = Self::vote_class_of(who, ref_index).unwrap_or_default();
+		VotingOf::<T>::try_mutate(who, class, |voting| -> DispatchResult {
 			if let Voting::Direct { ref mut votes, delegations, ref mut prior } = voting {
 				let i = votes.binary_search_by_key(&ref_index, |i| i.0).map_err(|_| Error::<T>::NotVoter)?;
 				match info {
@@ -1320,12 +1304,17 @@ impl<T: Config> Module<T> {
 			}
 			Ok(())
 		})?;
+		VoteClassOf::<T>::remove(who, ref_index);
 		Ok(())
 	}
 
-	/// Return the number of votes for `who`
-	fn increase_upstream_delegation(who: &T::AccountId, amount: Delegations<BalanceOf<T>>) -> u32 {
-		VotingOf::<T>::mutate(who, |voting| match voting {
+	/// Return the number of votes for `who` in `class`
+	fn increase_upstream_delegation(
+		who: &T::AccountId,
+		class: T::VoteClass,
+		amount: Delegations<BalanceOf<T>>,
+	) -> u32 {
+		VotingOf::<T>::mutate(who, class, |voting| match voting {
 			Voting::Delegating { delegations, .. } => {
 				// We don't support second level delegating, so we don't need to do anything more.
 				*delegations = delegations.saturating_add(amount);
@@ -1347,9 +1336,13 @@ impl<T: Config> Module<T> {
 		})
 	}
 
-	/// Return the number of votes for `who`
-	fn reduce_upstream_delegation(who: &T::AccountId, amount: Delegations<BalanceOf<T>>) -> u32 {
-		VotingOf::<T>::mutate(who, |voting| match voting {
+	/// Return the number of votes for `who` in `class`
+	fn reduce_upstream_delegation(
+		who: &T::AccountId,
+		class: T::VoteClass,
+		amount: Delegations<BalanceOf<T>>,
+	) -> u32 {
+		VotingOf::<T>::mutate(who, class, |voting| match voting {
 			Voting::Delegating { delegations, .. } => {
 				// We don't support second level delegating, so we don't need to do anything more.
 				*delegations = delegations.saturating_sub(amount);
@@ -1371,18 +1364,20 @@ impl<T: Config> Module<T> {
 		})
 	}
 
-	/// Attempt to delegate `balance` times `conviction` of voting power from `who` to `target`.
+	/// Attempt to delegate `balance` times `conviction` of voting power in `class` from `who` to
+	/// `target`.
 	///
 	/// Return the upstream number of votes.
 	fn try_delegate(
 		who: T::AccountId,
+		class: T::VoteClass,
 		target: T::AccountId,
 		conviction: Conviction,
 		balance: BalanceOf<T>,
 	) -> Result<u32, DispatchError> {
 		ensure!(who != target, Error::<T>::Nonsense);
 		ensure!(balance <= T::Currency::free_balance(&who), Error::<T>::InsufficientFunds);
-		let votes = VotingOf::<T>::try_mutate(&who, |voting| -> Result<u32, DispatchError> {
+		let votes = VotingOf::<T>::try_mutate(&who, class, |voting| -> Result<u32, DispatchError> {
 			let mut old = Voting::Delegating {
 				balance,
 				target: target.clone(),
@@ -1394,7 +1389,7 @@ impl<T: Config> Module<T> {
 			match old {
 				Voting::Delegating { balance, target, conviction, delegations, prior, .. } => {
 					// remove any delegation votes to our current target.
-					Self::reduce_upstream_delegation(&target, conviction.votes(balance));
+					Self::reduce_upstream_delegation(&target, class, conviction.votes(balance));
 					voting.set_common(delegations, prior);
 				}
 				Voting::Direct { votes, delegations, prior } => {
@@ -1403,26 +1398,21 @@ impl<T: Config> Module<T> {
 					voting.set_common(delegations, prior);
 				}
 			}
-			let votes = Self::increase_upstream_delegation(&target, conviction.votes(balance));
+			let votes = Self::increase_upstream_delegation(&target, class, conviction.votes(balance));
 			// Extend the lock to `balance` (rather than setting it) since we don't know what other
 			// votes are in place.
-			T::Currency::extend_lock(
-				DEMOCRACY_ID,
-				&who,
-				balance,
-				WithdrawReasons::TRANSFER
-			);
+			Self::extend_lock(&who, class, balance);
 			Ok(votes)
 		})?;
-		Self::deposit_event(Event::<T>::Delegated(who, target));
+		Self::deposit_event(Event::<T>::Delegated(who, target, class));
 		Ok(votes)
 	}
 
-	/// Attempt to end the current delegation.
+	/// Attempt to end the current delegation in `class`.
 	///
 	/// Return the number of votes of upstream.
-	fn try_undelegate(who: T::AccountId) -> Result<u32, DispatchError> {
-		let votes = VotingOf::<T>::try_mutate(&who, |voting| -> Result<u32, DispatchError> {
+	fn try_undelegate(who: T::AccountId, class: T::VoteClass) -> Result<u32, DispatchError> {
+		let votes = VotingOf::<T>::try_mutate(&who, class, |voting| -> Result<u32, DispatchError> {
 			let mut old = Voting::default();
 			sp_std::mem::swap(&mut old, voting);
 			match old {
@@ -1434,7 +1424,7 @@ impl<T: Config> Module<T> {
 					mut prior,
 				} => {
 					// remove any delegation votes to our current target.
-					let votes = Self::reduce_upstream_delegation(&target, conviction.votes(balance));
+					let votes = Self::reduce_upstream_delegation(&target, class, conviction.votes(balance));
 					let now = system::Module::<T>::block_number();
 					let lock_periods = conviction.lock_periods().into();
 					prior.accumulate(now + T::EnactmentPeriod::get() * lock_periods, balance);
@@ -1447,17 +1437,41 @@ impl<T: Config> Module<T> {
 				}
 			}
 		})?;
-		Self::deposit_event(Event::<T>::Undelegated(who));
+		Self::deposit_event(Event::<T>::Undelegated(who, class));
 		Ok(votes)
 	}
 
-	/// Rejig the lock on an account. It will never get more stringent (since that would indicate
+	/// Extend the lock required by `class` on an account to at least `amount`, and the lock on
+	/// the account accordingly.
+	fn extend_lock(who: &T::AccountId, class: T::VoteClass, amount: BalanceOf<T>) {
+		ClassLocksFor::<T>::mutate(who, |locks| {
+			match locks.iter().position(|x| x.0 == class) {
+				Some(i) => locks[i].1 = locks[i].1.max(amount),
+				None => locks.push((class, amount)),
+			}
+		});
+		T::Currency::extend_lock(DEMOCRACY_ID, who, amount, WithdrawReasons::TRANSFER);
+	}
+
+	/// Rejig the lock required by `class` on an account, and set the lock on the account to the
+	/// maximum required by any class. It will never get more stringent (since that would indicate
 	/// a security hole) but may be reduced from what they are currently.
-	fn update_lock(who: &T::AccountId) {
-		let lock_needed = VotingOf::<T>::mutate(who, |voting| {
+	fn update_lock(who: &T::AccountId, class: T::VoteClass) {
+		let class_lock_needed = VotingOf::<T>::mutate(who, class, |voting| {
 			voting.rejig(system::Module::<T>::block_number());
 			voting.locked_balance()
 		});
+		let mut locks = ClassLocksFor::<T>::get(who);
+		locks.retain(|x| x.0 != class);
+		if !class_lock_needed.is_zero() {
+			locks.push((class, class_lock_needed));
+		}
+		let lock_needed = locks.iter().map(|x| x.1).max().unwrap_or_else(Zero::zero);
+		if locks.is_empty() {
+			ClassLocksFor::<T>::remove(who);
+		} else {
+			ClassLocksFor::<T>::insert(who, locks);
+		}
 		if lock_needed.is_zero() {
 			T::Currency::remove_lock(DEMOCRACY_ID, who);
 		} else {
@@ -1465,6 +1479,30 @@ impl<T: Config> Module<T> {
 		}
 	}
 
+	/// The class of a referendum on `proposal_hash`, if its preimage is available.
+	fn class_of(proposal_hash: &T::Hash) -> Option<T::VoteClass> {
+		T::Preimages::get_preimage(proposal_hash)
+			.and_then(|data| T::Proposal::decode(&mut &data[..]).ok())
+			.map(|proposal| T::Classifier::classify(&proposal))
+	}
+
+	/// The class of the ongoing referendum `ref_index`.
+	///
+	/// The class is recorded in `ReferendumClass` as soon as the preimage is available. Until
+	/// then the default class is used.
+	fn resolve_class(ref_index: ReferendumIndex, proposal_hash: &T::Hash) -> T::VoteClass {
+		if let Some(class) = ReferendumClass::<T>::get(ref_index) {
+			return class
+		}
+		match Self::class_of(proposal_hash) {
+			Some(class) => {
+				ReferendumClass::<T>::insert(ref_index, class);
+				class
+			},
+			None => T::VoteClass::default(),
+		}
+	}
+
 	/// Start a referendum
 	fn inject_referendum(
 		end: T::BlockNumber,
@@ -1477,6 +1515,10 @@ impl<T: Config> Module<T> {
 		let status = ReferendumStatus { end, proposal_hash, threshold, delay, tally: Default::default() };
 		let item = ReferendumInfo::Ongoing(status);
 		<ReferendumInfoOf<T>>::insert(ref_index, item);
+		Self::resolve_class(ref_index, &proposal_hash);
+		// Ask for the preimage so that it is kept around, and can be noted without a deposit,
+		// until the referendum is over.
+		T::Preimages::request_preimage(&proposal_hash);
 		Self::deposit_event(RawEvent::Started(ref_index, threshold));
 		ref_index
 	}
@@ -1521,7 +1563,7 @@ impl<T: Config> Module<T> {
 			if let Some((depositors, deposit)) = <DepositOf<T>>::take(prop_index) {
 				// refund depositors
 				for d in &depositors {
-					T::Currency::unreserve(d, deposit);
+					T::Currency::unreserve_named(&PROPOSAL_DEPOSIT_ID, d, deposit);
 				}
 				Self::deposit_event(RawEvent::Tabled(prop_index, deposit, depositors));
 				Self::inject_referendum(
@@ -1538,18 +1580,16 @@ impl<T: Config> Module<T> {
 	}
 
 	fn do_enact_proposal(proposal_hash: T::Hash, index: ReferendumIndex) -> DispatchResult {
-		let preimage = <Preimages<T>>::take(&proposal_hash);
-		if let Some(PreimageStatus::Available { data, provider, deposit, .. }) = preimage {
+		let maybe_data = T::Preimages::get_preimage(&proposal_hash);
+		// The referendum is over either way, so we no longer need the preimage.
+		T::Preimages::unrequest_preimage(&proposal_hash);
+		if let Some(data) = maybe_data {
 			if let Ok(proposal) = T::Proposal::decode(&mut &data[..]) {
-				let _ = T::Currency::unreserve(&provider, deposit);
-				Self::deposit_event(RawEvent::PreimageUsed(proposal_hash, provider, deposit));
-
 				let ok = proposal.dispatch(frame_system::RawOrigin::Root.into()).is_ok();
 				Self::deposit_event(RawEvent::Executed(index, ok));
 
 				Ok(())
 			} else {
-				T::Slash::on_unbalanced(T::Currency::slash_reserved(&provider, deposit).0);
 				Self::deposit_event(RawEvent::PreimageInvalid(proposal_hash, index));
 				Err(Error::<T>::PreimageInvalid.into())
 			}
@@ -1566,6 +1606,7 @@ impl<T: Config> Module<T> {
 	) -> Result<bool, DispatchError> {
 		let total_issuance = T::Currency::total_issuance();
 		let approved = status.threshold.approved(status.tally, total_issuance);
+		ReferendumClass::<T>::remove(index);
 
 		if approved {
 			Self::deposit_event(RawEvent::Passed(index));
@@ -1573,12 +1614,6 @@ impl<T: Config> Module<T> {
 				let _ = Self::do_enact_proposal(status.proposal_hash, index);
 			} else {
 				let when = now + status.delay;
-				// Note that we need the preimage now.
-				Preimages::<T>::mutate_exists(&status.proposal_hash, |maybe_pre| match *maybe_pre {
-					Some(PreimageStatus::Available { ref mut expiry, .. }) => *expiry = Some(when),
-					ref mut a => *a = Some(PreimageStatus::Missing(when)),
-				});
-
 				if T::Scheduler::schedule_named(
 					(DEMOCRACY_ID, index).encode(),
 					DispatchTime::At(when),
@@ -1591,6 +1626,7 @@ impl<T: Config> Module<T> {
 				}
 			}
 		} else {
+			T::Preimages::unrequest_preimage(&status.proposal_hash);
 			Self::deposit_event(RawEvent::NotPassed(index));
 		}
 
@@ -1643,115 +1679,13 @@ impl<T: Config> Module<T> {
 		decode_compact_u32_at(&<DepositOf<T>>::hashed_key_for(proposal))
 	}
 
-	/// Check that pre image exists and its value is variant `PreimageStatus::Missing`.
-	///
-	/// This check is done without getting the complete value in the runtime to avoid copying a big
-	/// value in the runtime.
-	fn check_pre_image_is_missing(proposal_hash: T::Hash) -> DispatchResult {
-		// To decode the enum variant we only need the first byte.
-		let mut buf = [0u8; 1];
-		let key = <Preimages<T>>::hashed_key_for(proposal_hash);
-		let bytes = match sp_io::storage::read(&key, &mut buf, 0) {
-			Some(bytes) => bytes,
-			None => return Err(Error::<T>::NotImminent.into()),
-		};
-		// The value may be smaller that 1 byte.
-		let mut input = &buf[0..buf.len().min(bytes as usize)];
-
-		match input.read_byte() {
-			Ok(0) => Ok(()), // PreimageStatus::Missing is variant 0
-			Ok(1) => Err(Error::<T>::DuplicatePreimage.into()),
-			_ => {
-				sp_runtime::print("Failed to decode `PreimageStatus` variant");
-				Err(Error::<T>::NotImminent.into())
-			}
-		}
-	}
-
-	/// Check that pre image exists, its value is variant `PreimageStatus::Available` and decode
-	/// the length of `data: Vec<u8>` fields.
-	///
-	/// This check is done without getting the complete value in the runtime to avoid copying a big
-	/// value in the runtime.
-	///
-	/// If the pre image is missing variant or doesn't exist then the error `PreimageMissing` is
-	/// returned.
-	fn pre_image_data_len(proposal_hash: T::Hash) -> Result<u32, DispatchError> {
-		// To decode the `data` field of Available variant we need:
-		// * one byte for the variant
-		// * at most 5 bytes to decode a `Compact<u32>`
-		let mut buf = [0u8; 6];
-		let key = <Preimages<T>>::hashed_key_for(proposal_hash);
-		let bytes = match sp_io::storage::read(&key, &mut buf, 0) {
-			Some(bytes) => bytes,
-			None => return Err(Error::<T>::PreimageMissing.into()),
-		};
-		// The value may be smaller that 6 bytes.
-		let mut input = &buf[0..buf.len().min(bytes as usize)];
-
-		match input.read_byte() {
-			Ok(1) => (), // Check that input exists and is second variant.
-			Ok(0) => return Err(Error::<T>::PreimageMissing.into()),
-			_ => {
-				sp_runtime::print("Failed to decode `PreimageStatus` variant");
-				return Err(Error::<T>::PreimageMissing.into());
-			}
+	/// Slash the deposit of every proposer and seconder of a public proposal.
+	fn slash_proposal_deposits(depositors: Vec<T::AccountId>, deposit: BalanceOf<T>) {
+		for who in depositors {
+			let (imbalance, _) =
+				T::Currency::slash_reserved_named(&PROPOSAL_DEPOSIT_ID, &who, deposit);
+			T::Slash::on_unbalanced(imbalance);
 		}
-
-		// Decode the length of the vector.
-		let len = codec::Compact::<u32>::decode(&mut input).map_err(|_| {
-			sp_runtime::print("Failed to decode `PreimageStatus` variant");
-			DispatchError::from(Error::<T>::PreimageMissing)
-		})?.0;
-
-		Ok(len)
-	}
-
-	// See `note_preimage`
-	fn note_preimage_inner(who: T::AccountId, encoded_proposal: Vec<u8>) -> DispatchResult {
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		ensure!(!<Preimages<T>>::contains_key(&proposal_hash), Error::<T>::DuplicatePreimage);
-
-		let deposit = <BalanceOf<T>>::from(encoded_proposal.len() as u32)
-			.saturating_mul(T::PreimageByteDeposit::get());
-		T::Currency::reserve(&who, deposit)?;
-
-		let now = <frame_system::Module<T>>::block_number();
-		let a = PreimageStatus::Available {
-			data: encoded_proposal,
-			provider: who.clone(),
-			deposit,
-			since: now,
-			expiry: None,
-		};
-		<Preimages<T>>::insert(proposal_hash, a);
-
-		Self::deposit_event(RawEvent::PreimageNoted(proposal_hash, who, deposit));
-
-		Ok(())
-	}
-
-	// See `note_imminent_preimage`
-	fn note_imminent_preimage_inner(who: T::AccountId, encoded_proposal: Vec<u8>) -> DispatchResult {
-		let proposal_hash = T::Hashing::hash(&encoded_proposal[..]);
-		Self::check_pre_image_is_missing(proposal_hash)?;
-		let status = Preimages::<T>::get(&proposal_hash).ok_or(Error::<T>::NotImminent)?;
-		let expiry = status.to_missing_expiry().ok_or(Error::<T>::DuplicatePreimage)?;
-
-		let now = <frame_system::Module<T>>::block_number();
-		let free = <BalanceOf<T>>::zero();
-		let a = PreimageStatus::Available {
-			data: encoded_proposal,
-			provider: who.clone(),
-			deposit: Zero::zero(),
-			since: now,
-			expiry: Some(expiry),
-		};
-		<Preimages<T>>::insert(proposal_hash, a);
-
-		Self::deposit_event(RawEvent::PreimageNoted(proposal_hash, who, free));
-
-		Ok(())
 	}
 }
 
@@ -0,0 +1,160 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations for the democracy pallet.
+
+use super::*;
+use frame_support::{
+	Identity, Twox64Concat,
+	storage::{IterableStorageMap, migration::StorageKeyIterator},
+	traits::{PreimageProvider, PreimageRecipient, ReservableCurrency},
+};
+
+/// The status of a preimage as it used to be stored by this pallet in `Democracy::Preimages`,
+/// before preimages were moved to `Config::Preimages`.
+#[derive(Clone, Encode, Decode, RuntimeDebug)]
+pub enum PreimageStatus<AccountId, Balance, BlockNumber> {
+	/// The preimage is imminently needed at the argument.
+	Missing(BlockNumber),
+	/// The preimage is available.
+	Available {
+		data: Vec<u8>,
+		provider: AccountId,
+		deposit: Balance,
+		since: BlockNumber,
+		/// None if it's not imminent.
+		expiry: Option<BlockNumber>,
+	},
+}
+
+/// Move all preimages out of the legacy `Democracy::Preimages` storage and into
+/// `Config::Preimages`.
+///
+/// - The deposits of the providers are unreserved and the preimages are noted.
+/// - Preimages which were imminently needed, as well as the preimages of all ongoing referenda,
+///   are requested so that they are kept until they are used.
+///
+/// Preimages which are larger than the maximum size supported by `Config::Preimages` are
+/// dropped. Does nothing if the storage is already at `Releases::V2` or later.
+pub fn migrate_to_v2<T: Config>() -> Weight {
+	if StorageVersion::get() >= Some(Releases::V2) {
+		return T::DbWeight::get().reads(1);
+	}
+
+	let mut reads: Weight = 1;
+	let mut writes: Weight = 1;
+	let legacy = StorageKeyIterator::<
+		T::Hash,
+		PreimageStatus<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+		Identity,
+	>::new(b"Democracy", b"Preimages").drain();
+	for (hash, status) in legacy {
+		reads += 1;
+		writes += 1;
+		match status {
+			PreimageStatus::Available { data, provider, deposit, expiry, .. } => {
+				T::Currency::unreserve(&provider, deposit);
+				if expiry.is_some() {
+					T::Preimages::request_preimage(&hash);
+				}
+				T::Preimages::note_preimage(data);
+				reads += 2;
+				writes += 2;
+			},
+			PreimageStatus::Missing(_) => {
+				T::Preimages::request_preimage(&hash);
+				reads += 1;
+				writes += 1;
+			},
+		}
+	}
+
+	for (_, info) in ReferendumInfoOf::<T>::iter() {
+		reads += 1;
+		if let ReferendumInfo::Ongoing(status) = info {
+			T::Preimages::request_preimage(&status.proposal_hash);
+			reads += 1;
+			writes += 1;
+		}
+	}
+
+	StorageVersion::put(Releases::V2);
+
+	T::DbWeight::get().reads_writes(reads, writes)
+}
+
+/// Move the votes and delegations of all accounts out of the legacy `Democracy::VotingOf` map
+/// and into the default vote class, recording the locks they require in `ClassLocksFor`.
+///
+/// Referenda started before the migration have no entry in `ReferendumClass`. Their class is
+/// resolved by the next vote, while the votes moved here stay in the default class. Does nothing
+/// if the storage is already at `Releases::V3`.
+pub fn migrate_to_v3<T: Config>() -> Weight {
+	if StorageVersion::get() >= Some(Releases::V3) {
+		return T::DbWeight::get().reads(1);
+	}
+
+	// The legacy keys are prefixes of the new ones, so they are all drained before any new entry
+	// is written.
+	let legacy = StorageKeyIterator::<
+		T::AccountId,
+		Voting<BalanceOf<T>, T::AccountId, T::BlockNumber>,
+		Twox64Concat,
+	>::new(b"Democracy", b"VotingOf").drain().collect::<Vec<_>>();
+
+	let mut reads: Weight = 1;
+	let mut writes: Weight = 1;
+	let class = T::VoteClass::default();
+	for (who, voting) in legacy {
+		let lock = voting.locked_balance();
+		if !lock.is_zero() {
+			ClassLocksFor::<T>::insert(&who, vec![(class, lock)]);
+			writes += 1;
+		}
+		VotingOf::<T>::insert(&who, class, voting);
+		reads += 1;
+		writes += 2;
+	}
+
+	StorageVersion::put(Releases::V3);
+
+	T::DbWeight::get().reads_writes(reads, writes)
+}
+
+/// Move the deposits of the proposers and seconders of all public proposals, which were reserved
+/// anonymously, into the reserve named [`PROPOSAL_DEPOSIT_ID`]. Does nothing if the storage is
+/// already at `Releases::V4`.
+pub fn migrate_to_v4<T: Config>() -> Weight {
+	if StorageVersion::get() >= Some(Releases::V4) {
+		return T::DbWeight::get().reads(1);
+	}
+
+	let mut reads: Weight = 1;
+	let mut writes: Weight = 1;
+	for (_, (depositors, deposit)) in DepositOf::<T>::iter() {
+		reads += 1;
+		for who in depositors {
+			let _ = T::Currency::name_reserved(&PROPOSAL_DEPOSIT_ID, &who, deposit);
+			reads += 2;
+			writes += 2;
+		}
+	}
+
+	StorageVersion::put(Releases::V4);
+
+	T::DbWeight::get().reads_writes(reads, writes)
+}
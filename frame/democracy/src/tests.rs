@@ -21,12 +21,13 @@ use super::*;
 use codec::Encode;
 use frame_support::{
 	impl_outer_origin, impl_outer_dispatch, assert_noop, assert_ok, parameter_types,
-	impl_outer_event, ord_parameter_types, traits::{Contains, OnInitialize, Filter},
+	impl_outer_event, ord_parameter_types,
+	traits::{Contains, OnInitialize, Filter, ReservableCurrency},
 	weights::Weight,
 };
 use sp_core::H256;
 use sp_runtime::{
-	traits::{BlakeTwo256, IdentityLookup, BadOrigin},
+	traits::{BlakeTwo256, IdentityLookup, BadOrigin, Hash},
 	testing::Header, Perbill,
 };
 use pallet_balances::{BalanceLock, Error as BalancesError};
@@ -71,6 +72,7 @@ impl_outer_event! {
 		system<T>,
 		pallet_balances<T>,
 		pallet_scheduler<T>,
+		pallet_preimage<T>,
 		democracy<T>,
 	}
 }
@@ -126,12 +128,18 @@ impl pallet_scheduler::Config for Test {
 	type ScheduleOrigin = EnsureRoot<u64>;
 	type MaxScheduledPerBlock = ();
 	type WeightInfo = ();
+	type PreimageProvider = Preimage;
+	type NoPreimagePostponement = ();
 }
 parameter_types! {
 	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxReserves: u32 = 2;
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type Balance = u64;
 	type Event = Event;
 	type DustRemoval = ();
@@ -139,6 +147,20 @@ impl pallet_balances::Config for Test {
 	type AccountStore = System;
 	type WeightInfo = ();
 }
+parameter_types! {
+	pub const PreimageMaxSize: u32 = 4096;
+	pub const PreimageBaseDeposit: u64 = 0;
+	pub static PreimageByteDeposit: u64 = 0;
+}
+impl pallet_preimage::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<u64>;
+	type MaxSize = PreimageMaxSize;
+	type BaseDeposit = PreimageBaseDeposit;
+	type ByteDeposit = PreimageByteDeposit;
+	type WeightInfo = ();
+}
 parameter_types! {
 	pub const LaunchPeriod: u64 = 2;
 	pub const VotingPeriod: u64 = 2;
@@ -148,7 +170,6 @@ parameter_types! {
 	pub const CooloffPeriod: u64 = 2;
 	pub const MaxVotes: u32 = 100;
 	pub const MaxProposals: u32 = MAX_PROPOSALS;
-	pub static PreimageByteDeposit: u64 = 0;
 	pub static InstantAllowed: bool = false;
 }
 ord_parameter_types! {
@@ -168,6 +189,16 @@ impl Contains<u64> for OneToFive {
 	fn add(_m: &u64) {}
 }
 
+pub struct TestClassifier;
+impl ClassifyProposal<Call, u8> for TestClassifier {
+	fn classify(proposal: &Call) -> u8 {
+		match proposal {
+			Call::System(..) => 1,
+			_ => 0,
+		}
+	}
+}
+
 impl super::Config for Test {
 	type Proposal = Call;
 	type Event = Event;
@@ -186,13 +217,14 @@ impl super::Config for Test {
 	type CancelProposalOrigin = EnsureRoot<u64>;
 	type VetoOrigin = EnsureSignedBy<OneToFive, u64>;
 	type CooloffPeriod = CooloffPeriod;
-	type PreimageByteDeposit = PreimageByteDeposit;
+	type Preimages = Preimage;
 	type Slash = ();
 	type InstantOrigin = EnsureSignedBy<Six, u64>;
 	type InstantAllowed = InstantAllowed;
 	type Scheduler = Scheduler;
 	type MaxVotes = MaxVotes;
-	type OperationalPreimageOrigin = EnsureSignedBy<Six, u64>;
+	type VoteClass = u8;
+	type Classifier = TestClassifier;
 	type PalletsOrigin = OriginCaller;
 	type WeightInfo = ();
 	type MaxProposals = MaxProposals;
@@ -218,6 +250,7 @@ pub fn new_test_ext_execute_with_cond(execute: impl FnOnce(bool) -> () + Clone)
 type System = frame_system::Module<Test>;
 type Balances = pallet_balances::Module<Test>;
 type Scheduler = pallet_scheduler::Module<Test>;
+type Preimage = pallet_preimage::Module<Test>;
 type Democracy = Module<Test>;
 
 #[test]
@@ -248,14 +281,21 @@ fn set_balance_proposal_hash(value: u64) -> H256 {
 fn set_balance_proposal_hash_and_note(value: u64) -> H256 {
 	let p = set_balance_proposal(value);
 	let h = BlakeTwo256::hash(&p[..]);
-	match Democracy::note_preimage(Origin::signed(6), p) {
+	match Preimage::note_preimage(Origin::signed(6), p) {
 		Ok(_) => (),
-		Err(x) if x == Error::<Test>::DuplicatePreimage.into() => (),
+		Err(x) if x.error == pallet_preimage::Error::<Test>::AlreadyNoted.into() => (),
 		Err(x) => panic!(x),
 	}
 	h
 }
 
+fn remark_proposal_hash_and_note(value: u8) -> H256 {
+	let p = Call::System(frame_system::Call::remark(vec![value])).encode();
+	let h = BlakeTwo256::hash(&p[..]);
+	assert_ok!(Preimage::note_preimage(Origin::signed(6), p));
+	h
+}
+
 fn propose_set_balance(who: u64, value: u64, delay: u64) -> DispatchResult {
 	Democracy::propose(
 		Origin::signed(who),
@@ -50,32 +50,3 @@ fn len_of_deposit_of() {
 		assert_eq!(Democracy::len_of_deposit_of(2), None);
 	})
 }
-
-#[test]
-fn pre_image() {
-	new_test_ext().execute_with(|| {
-		let key = Default::default();
-		let missing = PreimageStatus::Missing(0);
-		Preimages::<Test>::insert(key, missing);
-		assert!(Democracy::pre_image_data_len(key).is_err());
-		assert_eq!(Democracy::check_pre_image_is_missing(key), Ok(()));
-
-		Preimages::<Test>::remove(key);
-		assert!(Democracy::pre_image_data_len(key).is_err());
-		assert!(Democracy::check_pre_image_is_missing(key).is_err());
-
-		for l in vec![0, 10, 100, 1000u32] {
-			let available = PreimageStatus::Available{
-				data: (0..l).map(|i| i as u8).collect(),
-				provider: 0,
-				deposit: 0,
-				since: 0,
-				expiry: None,
-			};
-
-			Preimages::<Test>::insert(key, available);
-			assert_eq!(Democracy::pre_image_data_len(key), Ok(l));
-			assert!(Democracy::check_pre_image_is_missing(key).is_err());
-		}
-	})
-}
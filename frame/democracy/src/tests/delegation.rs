@@ -18,6 +18,8 @@
 //! The tests for functionality concerning delegation.
 
 use super::*;
+use frame_support::{Twox64Concat, StorageHasher, IterableStorageDoubleMap, storage::migration};
+use crate::migrations;
 
 #[test]
 fn single_proposal_should_work_with_delegation() {
@@ -29,21 +31,21 @@ fn single_proposal_should_work_with_delegation() {
 		fast_forward_to(2);
 
 		// Delegate first vote.
-		assert_ok!(Democracy::delegate(Origin::signed(2), 1, Conviction::None, 20));
+		assert_ok!(Democracy::delegate(Origin::signed(2), 0, 1, Conviction::None, 20));
 		let r = 0;
 		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
 		assert_eq!(tally(r), Tally { ayes: 3, nays: 0, turnout: 30 });
 
 		// Delegate a second vote.
-		assert_ok!(Democracy::delegate(Origin::signed(3), 1, Conviction::None, 30));
+		assert_ok!(Democracy::delegate(Origin::signed(3), 0, 1, Conviction::None, 30));
 		assert_eq!(tally(r), Tally { ayes: 6, nays: 0, turnout: 60 });
 
 		// Reduce first vote.
-		assert_ok!(Democracy::delegate(Origin::signed(2), 1, Conviction::None, 10));
+		assert_ok!(Democracy::delegate(Origin::signed(2), 0, 1, Conviction::None, 10));
 		assert_eq!(tally(r), Tally { ayes: 5, nays: 0, turnout: 50 });
 
 		// Second vote delegates to first; we don't do tiered delegation, so it doesn't get used.
-		assert_ok!(Democracy::delegate(Origin::signed(3), 2, Conviction::None, 30));
+		assert_ok!(Democracy::delegate(Origin::signed(3), 0, 2, Conviction::None, 30));
 		assert_eq!(tally(r), Tally { ayes: 2, nays: 0, turnout: 20 });
 
 		// Main voter cancels their vote
@@ -51,7 +53,7 @@ fn single_proposal_should_work_with_delegation() {
 		assert_eq!(tally(r), Tally { ayes: 0, nays: 0, turnout: 0 });
 
 		// First delegator delegates half funds with conviction; nothing changes yet.
-		assert_ok!(Democracy::delegate(Origin::signed(2), 1, Conviction::Locked1x, 10));
+		assert_ok!(Democracy::delegate(Origin::signed(2), 0, 1, Conviction::Locked1x, 10));
 		assert_eq!(tally(r), Tally { ayes: 0, nays: 0, turnout: 0 });
 
 		// Main voter reinstates their vote
@@ -64,7 +66,7 @@ fn single_proposal_should_work_with_delegation() {
 fn self_delegation_not_allowed() {
 	new_test_ext().execute_with(|| {
 		assert_noop!(
-			Democracy::delegate(Origin::signed(1), 1, Conviction::None, 10),
+			Democracy::delegate(Origin::signed(1), 0, 1, Conviction::None, 10),
 			Error::<Test>::Nonsense,
 		);
 	});
@@ -80,13 +82,13 @@ fn cyclic_delegation_should_unwind() {
 		fast_forward_to(2);
 
 		// Check behavior with cycle.
-		assert_ok!(Democracy::delegate(Origin::signed(2), 1, Conviction::None, 20));
-		assert_ok!(Democracy::delegate(Origin::signed(3), 2, Conviction::None, 30));
-		assert_ok!(Democracy::delegate(Origin::signed(1), 3, Conviction::None, 10));
+		assert_ok!(Democracy::delegate(Origin::signed(2), 0, 1, Conviction::None, 20));
+		assert_ok!(Democracy::delegate(Origin::signed(3), 0, 2, Conviction::None, 30));
+		assert_ok!(Democracy::delegate(Origin::signed(1), 0, 3, Conviction::None, 10));
 		let r = 0;
-		assert_ok!(Democracy::undelegate(Origin::signed(3)));
+		assert_ok!(Democracy::undelegate(Origin::signed(3), 0));
 		assert_ok!(Democracy::vote(Origin::signed(3), r, aye(3)));
-		assert_ok!(Democracy::undelegate(Origin::signed(1)));
+		assert_ok!(Democracy::undelegate(Origin::signed(1), 0));
 		assert_ok!(Democracy::vote(Origin::signed(1), r, nay(1)));
 
 		// Delegated vote is counted.
@@ -111,7 +113,7 @@ fn single_proposal_should_work_with_vote_and_delegation() {
 
 		// Delegate vote.
 		assert_ok!(Democracy::remove_vote(Origin::signed(2), r));
-		assert_ok!(Democracy::delegate(Origin::signed(2), 1, Conviction::None, 20));
+		assert_ok!(Democracy::delegate(Origin::signed(2), 0, 1, Conviction::None, 20));
 		// Delegated vote replaces the explicit vote.
 		assert_eq!(tally(r), Tally { ayes: 3, nays: 0, turnout: 30 });
 	});
@@ -125,8 +127,8 @@ fn single_proposal_should_work_with_undelegation() {
 		assert_ok!(propose_set_balance_and_note(1, 2, 1));
 
 		// Delegate and undelegate vote.
-		assert_ok!(Democracy::delegate(Origin::signed(2), 1, Conviction::None, 20));
-		assert_ok!(Democracy::undelegate(Origin::signed(2)));
+		assert_ok!(Democracy::delegate(Origin::signed(2), 0, 1, Conviction::None, 20));
+		assert_ok!(Democracy::undelegate(Origin::signed(2), 0));
 
 		fast_forward_to(2);
 		let r = 0;
@@ -144,9 +146,9 @@ fn single_proposal_should_work_with_delegation_and_vote() {
 		let r = begin_referendum();
 		// Delegate, undelegate and vote.
 		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
-		assert_ok!(Democracy::delegate(Origin::signed(2), 1, Conviction::None, 20));
+		assert_ok!(Democracy::delegate(Origin::signed(2), 0, 1, Conviction::None, 20));
 		assert_eq!(tally(r), Tally { ayes: 3, nays: 0, turnout: 30 });
-		assert_ok!(Democracy::undelegate(Origin::signed(2)));
+		assert_ok!(Democracy::undelegate(Origin::signed(2), 0));
 		assert_ok!(Democracy::vote(Origin::signed(2), r, aye(2)));
 		// Delegated vote is not counted.
 		assert_eq!(tally(r), Tally { ayes: 3, nays: 0, turnout: 30 });
@@ -159,7 +161,7 @@ fn conviction_should_be_honored_in_delegation() {
 	new_test_ext().execute_with(|| {
 		let r = begin_referendum();
 		// Delegate, undelegate and vote.
-		assert_ok!(Democracy::delegate(Origin::signed(2), 1, Conviction::Locked6x, 20));
+		assert_ok!(Democracy::delegate(Origin::signed(2), 0, 1, Conviction::Locked6x, 20));
 		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
 		// Delegated vote is huge.
 		assert_eq!(tally(r), Tally { ayes: 121, nays: 0, turnout: 30 });
@@ -171,9 +173,201 @@ fn split_vote_delegation_should_be_ignored() {
 	// If transactor voted, delegated vote is overwritten.
 	new_test_ext().execute_with(|| {
 		let r = begin_referendum();
-		assert_ok!(Democracy::delegate(Origin::signed(2), 1, Conviction::Locked6x, 20));
+		assert_ok!(Democracy::delegate(Origin::signed(2), 0, 1, Conviction::Locked6x, 20));
 		assert_ok!(Democracy::vote(Origin::signed(1), r, AccountVote::Split { aye: 10, nay: 0 }));
 		// Delegated vote is huge.
 		assert_eq!(tally(r), Tally { ayes: 1, nays: 0, turnout: 10 });
 	});
 }
+
+#[test]
+fn delegation_is_per_class() {
+	new_test_ext().execute_with(|| {
+		let r0 = Democracy::inject_referendum(
+			2,
+			set_balance_proposal_hash_and_note(2),
+			VoteThreshold::SimpleMajority,
+			0
+		);
+		let r1 = Democracy::inject_referendum(
+			2,
+			remark_proposal_hash_and_note(1),
+			VoteThreshold::SimpleMajority,
+			0
+		);
+		assert_eq!(Democracy::referendum_class(r0), Some(0));
+		assert_eq!(Democracy::referendum_class(r1), Some(1));
+
+		// 2 follows 1 on class 0, and 3 on class 1.
+		assert_ok!(Democracy::delegate(Origin::signed(2), 0, 1, Conviction::None, 20));
+		assert_ok!(Democracy::delegate(Origin::signed(2), 1, 3, Conviction::None, 20));
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::democracy(RawEvent::Delegated(2, 3, 1)),
+		);
+
+		assert_ok!(Democracy::vote(Origin::signed(1), r0, aye(1)));
+		assert_ok!(Democracy::vote(Origin::signed(1), r1, aye(1)));
+		assert_ok!(Democracy::vote(Origin::signed(3), r1, nay(3)));
+		assert_eq!(tally(r0), Tally { ayes: 3, nays: 0, turnout: 30 });
+		assert_eq!(tally(r1), Tally { ayes: 1, nays: 5, turnout: 60 });
+
+		// Undelegating one class leaves the other one alone.
+		assert_ok!(Democracy::undelegate(Origin::signed(2), 1));
+		assert_eq!(tally(r0), Tally { ayes: 3, nays: 0, turnout: 30 });
+		assert_eq!(tally(r1), Tally { ayes: 1, nays: 3, turnout: 40 });
+		assert_noop!(Democracy::undelegate(Origin::signed(2), 1), Error::<Test>::NotDelegating);
+
+		// Voting directly is only prevented in the delegated class.
+		assert_noop!(
+			Democracy::vote(Origin::signed(2), r0, aye(2)),
+			Error::<Test>::AlreadyDelegating,
+		);
+		assert_ok!(Democracy::vote(Origin::signed(2), r1, aye(2)));
+		assert_eq!(tally(r1), Tally { ayes: 3, nays: 3, turnout: 60 });
+	});
+}
+
+#[test]
+fn referendum_without_preimage_is_of_default_class() {
+	new_test_ext().execute_with(|| {
+		let r = Democracy::inject_referendum(
+			2,
+			BlakeTwo256::hash(&Call::System(frame_system::Call::remark(vec![])).encode()[..]),
+			VoteThreshold::SimpleMajority,
+			0
+		);
+		assert_eq!(Democracy::referendum_class(r), None);
+		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
+		assert_eq!(Democracy::referendum_class(r), None);
+		assert!(VotingOf::<Test>::get(1, 0).locked_balance() > 0);
+	});
+}
+
+#[test]
+fn referendum_class_is_resolved_once_preimage_is_noted() {
+	new_test_ext().execute_with(|| {
+		let r = Democracy::inject_referendum(
+			2,
+			BlakeTwo256::hash(&Call::System(frame_system::Call::remark(vec![1])).encode()[..]),
+			VoteThreshold::SimpleMajority,
+			0
+		);
+		// 1 votes before the class is known, so its vote is of the default class.
+		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
+		assert_eq!(Democracy::referendum_class(r), None);
+
+		remark_proposal_hash_and_note(1);
+		assert_ok!(Democracy::vote(Origin::signed(2), r, aye(2)));
+		assert_eq!(Democracy::referendum_class(r), Some(1));
+		assert!(VotingOf::<Test>::get(2, 1).locked_balance() > 0);
+
+		// Changing the earlier vote moves it into the class of the referendum.
+		assert_ok!(Democracy::vote(Origin::signed(1), r, nay(1)));
+		assert_eq!(VotingOf::<Test>::get(1, 0), Default::default());
+		assert_eq!(Democracy::vote_class_of(1, r), Some(1));
+		assert_eq!(tally(r), Tally { ayes: 2, nays: 1, turnout: 30 });
+		assert_ok!(Democracy::remove_vote(Origin::signed(1), r));
+		assert_eq!(VotingOf::<Test>::get(1, 1), Default::default());
+		assert_eq!(Democracy::vote_class_of(1, r), None);
+		assert_eq!(tally(r), Tally { ayes: 2, nays: 0, turnout: 20 });
+	});
+}
+
+#[test]
+fn default_class_vote_carries_class_delegations_once_reclassified() {
+	new_test_ext().execute_with(|| {
+		let r = Democracy::inject_referendum(
+			2,
+			BlakeTwo256::hash(&Call::System(frame_system::Call::remark(vec![2])).encode()[..]),
+			VoteThreshold::SimpleMajority,
+			0
+		);
+		// 2 follows 1 on class 1, but 1 votes before the class of the referendum is known.
+		assert_ok!(Democracy::delegate(Origin::signed(2), 1, 1, Conviction::None, 20));
+		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
+		assert_eq!(Democracy::vote_class_of(1, r), None);
+		assert_eq!(tally(r), Tally { ayes: 1, nays: 0, turnout: 10 });
+		assert_noop!(
+			Democracy::reclassify_vote(Origin::signed(3), 1, r),
+			Error::<Test>::AlreadyClassified,
+		);
+
+		// Once the class is known, anyone can move the vote so that the delegation counts.
+		remark_proposal_hash_and_note(2);
+		assert_noop!(Democracy::reclassify_vote(Origin::signed(3), 3, r), Error::<Test>::NotVoter);
+		assert_ok!(Democracy::reclassify_vote(Origin::signed(3), 1, r));
+		assert_eq!(Democracy::vote_class_of(1, r), Some(1));
+		assert_eq!(VotingOf::<Test>::get(1, 0), Default::default());
+		assert!(VotingOf::<Test>::get(1, 1).locked_balance() > 0);
+		assert_eq!(tally(r), Tally { ayes: 3, nays: 0, turnout: 30 });
+		assert_noop!(
+			Democracy::reclassify_vote(Origin::signed(3), 1, r),
+			Error::<Test>::AlreadyClassified,
+		);
+	});
+}
+
+#[test]
+fn referendum_class_is_removed_when_referendum_ends() {
+	new_test_ext().execute_with(|| {
+		let r0 = Democracy::inject_referendum(
+			2,
+			remark_proposal_hash_and_note(1),
+			VoteThreshold::SimpleMajority,
+			0
+		);
+		let r1 = Democracy::inject_referendum(
+			2,
+			remark_proposal_hash_and_note(2),
+			VoteThreshold::SimpleMajority,
+			0
+		);
+		assert_ok!(Democracy::vote(Origin::signed(1), r0, aye(1)));
+		assert!(VotingOf::<Test>::get(1, 1).locked_balance() > 0);
+		assert_eq!(Democracy::referendum_class(r0), Some(1));
+		assert_eq!(Democracy::referendum_class(r1), Some(1));
+
+		Democracy::internal_cancel_referendum(r1);
+		assert_eq!(Democracy::referendum_class(r1), None);
+
+		fast_forward_to(2);
+		assert_eq!(Democracy::referendum_class(r0), None);
+		// The vote can still be removed from the class it was cast in.
+		assert_ok!(Democracy::remove_vote(Origin::signed(1), r0));
+		assert_eq!(VotingOf::<Test>::get(1, 1), Default::default());
+	});
+}
+
+#[test]
+fn migration_to_v3_should_move_votes_into_default_class() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::put(Releases::V2);
+
+		let voting = Voting::<u64, u64, u64>::Delegating {
+			balance: 20,
+			target: 1,
+			conviction: Conviction::Locked1x,
+			delegations: Default::default(),
+			prior: Default::default(),
+		};
+		let key = Twox64Concat::hash(&2u64.encode());
+		migration::put_storage_value(b"Democracy", b"VotingOf", &key, voting.clone());
+		let idle = Voting::<u64, u64, u64>::default();
+		let key = Twox64Concat::hash(&3u64.encode());
+		migration::put_storage_value(b"Democracy", b"VotingOf", &key, idle.clone());
+
+		migrations::migrate_to_v3::<Test>();
+
+		assert_eq!(StorageVersion::get(), Some(Releases::V3));
+		assert_eq!(VotingOf::<Test>::get(2, 0), voting);
+		assert_eq!(VotingOf::<Test>::get(3, 0), idle);
+		assert_eq!(VotingOf::<Test>::iter().count(), 2);
+		assert_eq!(Democracy::class_locks_for(2), vec![(0, 20)]);
+		assert!(Democracy::class_locks_for(3).is_empty());
+
+		// Running it again does nothing.
+		migrations::migrate_to_v3::<Test>();
+		assert_eq!(VotingOf::<Test>::iter().count(), 2);
+	});
+}
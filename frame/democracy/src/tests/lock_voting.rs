@@ -68,16 +68,16 @@ fn lock_voting_should_work() {
 
 		// Referendum passed; 1 and 5 didn't get their way and can now reap and unlock.
 		assert_ok!(Democracy::remove_vote(Origin::signed(1), r));
-		assert_ok!(Democracy::unlock(Origin::signed(1), 1));
+		assert_ok!(Democracy::unlock(Origin::signed(1), 0, 1));
 		// Anyone can reap and unlock anyone else's in this context.
 		assert_ok!(Democracy::remove_other_vote(Origin::signed(2), 5, r));
-		assert_ok!(Democracy::unlock(Origin::signed(2), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(2), 0, 5));
 
 		// 2, 3, 4 got their way with the vote, so they cannot be reaped by others.
 		assert_noop!(Democracy::remove_other_vote(Origin::signed(1), 2, r), Error::<Test>::NoPermission);
 		// However, they can be unvoted by the owner, though it will make no difference to the lock.
 		assert_ok!(Democracy::remove_vote(Origin::signed(2), r));
-		assert_ok!(Democracy::unlock(Origin::signed(2), 2));
+		assert_ok!(Democracy::unlock(Origin::signed(2), 0, 2));
 
 		assert_eq!(Balances::locks(1), vec![]);
 		assert_eq!(Balances::locks(2), vec![the_lock(20)]);
@@ -90,29 +90,29 @@ fn lock_voting_should_work() {
 		fast_forward_to(5);
 		// No change yet...
 		assert_noop!(Democracy::remove_other_vote(Origin::signed(1), 4, r), Error::<Test>::NoPermission);
-		assert_ok!(Democracy::unlock(Origin::signed(1), 4));
+		assert_ok!(Democracy::unlock(Origin::signed(1), 0, 4));
 		assert_eq!(Balances::locks(4), vec![the_lock(40)]);
 		fast_forward_to(6);
 		// 4 should now be able to reap and unlock
 		assert_ok!(Democracy::remove_other_vote(Origin::signed(1), 4, r));
-		assert_ok!(Democracy::unlock(Origin::signed(1), 4));
+		assert_ok!(Democracy::unlock(Origin::signed(1), 0, 4));
 		assert_eq!(Balances::locks(4), vec![]);
 
 		fast_forward_to(9);
 		assert_noop!(Democracy::remove_other_vote(Origin::signed(1), 3, r), Error::<Test>::NoPermission);
-		assert_ok!(Democracy::unlock(Origin::signed(1), 3));
+		assert_ok!(Democracy::unlock(Origin::signed(1), 0, 3));
 		assert_eq!(Balances::locks(3), vec![the_lock(30)]);
 		fast_forward_to(10);
 		assert_ok!(Democracy::remove_other_vote(Origin::signed(1), 3, r));
-		assert_ok!(Democracy::unlock(Origin::signed(1), 3));
+		assert_ok!(Democracy::unlock(Origin::signed(1), 0, 3));
 		assert_eq!(Balances::locks(3), vec![]);
 
 		// 2 doesn't need to reap_vote here because it was already done before.
 		fast_forward_to(17);
-		assert_ok!(Democracy::unlock(Origin::signed(1), 2));
+		assert_ok!(Democracy::unlock(Origin::signed(1), 0, 2));
 		assert_eq!(Balances::locks(2), vec![the_lock(20)]);
 		fast_forward_to(18);
-		assert_ok!(Democracy::unlock(Origin::signed(1), 2));
+		assert_ok!(Democracy::unlock(Origin::signed(1), 0, 2));
 		assert_eq!(Balances::locks(2), vec![]);
 	});
 }
@@ -133,7 +133,7 @@ fn no_locks_without_conviction_should_work() {
 
 		assert_eq!(Balances::free_balance(42), 2);
 		assert_ok!(Democracy::remove_other_vote(Origin::signed(2), 1, r));
-		assert_ok!(Democracy::unlock(Origin::signed(2), 1));
+		assert_ok!(Democracy::unlock(Origin::signed(2), 0, 1));
 		assert_eq!(Balances::locks(1), vec![]);
 	});
 }
@@ -150,7 +150,7 @@ fn lock_voting_should_work_with_delegation() {
 		assert_ok!(Democracy::vote(Origin::signed(1), r, nay(5, 10)));
 		assert_ok!(Democracy::vote(Origin::signed(2), r, aye(4, 20)));
 		assert_ok!(Democracy::vote(Origin::signed(3), r, aye(3, 30)));
-		assert_ok!(Democracy::delegate(Origin::signed(4), 2, Conviction::Locked2x, 40));
+		assert_ok!(Democracy::delegate(Origin::signed(4), 0, 2, Conviction::Locked2x, 40));
 		assert_ok!(Democracy::vote(Origin::signed(5), r, nay(1, 50)));
 
 		assert_eq!(tally(r), Tally { ayes: 250, nays: 100, turnout: 150 });
@@ -203,27 +203,27 @@ fn prior_lockvotes_should_be_enforced() {
 
 		fast_forward_to(5);
 		assert_noop!(Democracy::remove_other_vote(Origin::signed(1), 5, r.2), Error::<Test>::NoPermission);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![the_lock(50)]);
 		fast_forward_to(6);
 		assert_ok!(Democracy::remove_other_vote(Origin::signed(1), 5, r.2));
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![the_lock(20)]);
 		fast_forward_to(9);
 		assert_noop!(Democracy::remove_other_vote(Origin::signed(1), 5, r.1), Error::<Test>::NoPermission);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![the_lock(20)]);
 		fast_forward_to(10);
 		assert_ok!(Democracy::remove_other_vote(Origin::signed(1), 5, r.1));
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![the_lock(10)]);
 		fast_forward_to(17);
 		assert_noop!(Democracy::remove_other_vote(Origin::signed(1), 5, r.0), Error::<Test>::NoPermission);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![the_lock(10)]);
 		fast_forward_to(18);
 		assert_ok!(Democracy::remove_other_vote(Origin::signed(1), 5, r.0));
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![]);
 	});
 }
@@ -238,26 +238,26 @@ fn single_consolidation_of_lockvotes_should_work_as_before() {
 
 		fast_forward_to(5);
 		assert_ok!(Democracy::remove_vote(Origin::signed(5), r.2));
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![the_lock(50)]);
 		fast_forward_to(6);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![the_lock(20)]);
 
 		fast_forward_to(9);
 		assert_ok!(Democracy::remove_vote(Origin::signed(5), r.1));
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![the_lock(20)]);
 		fast_forward_to(10);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![the_lock(10)]);
 
 		fast_forward_to(17);
 		assert_ok!(Democracy::remove_vote(Origin::signed(5), r.0));
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![the_lock(10)]);
 		fast_forward_to(18);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![]);
 	});
 }
@@ -275,15 +275,15 @@ fn multi_consolidation_of_lockvotes_should_be_conservative() {
 		assert_ok!(Democracy::remove_vote(Origin::signed(5), r.0));
 
 		fast_forward_to(6);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert!(Balances::locks(5)[0].amount >= 20);
 
 		fast_forward_to(10);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert!(Balances::locks(5)[0].amount >= 10);
 
 		fast_forward_to(18);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![]);
 	});
 }
@@ -303,27 +303,27 @@ fn locks_should_persist_from_voting_to_delegation() {
 		assert_ok!(Democracy::remove_vote(Origin::signed(5), r));
 		// locked 10 until #18.
 
-		assert_ok!(Democracy::delegate(Origin::signed(5), 1, Conviction::Locked3x, 20));
+		assert_ok!(Democracy::delegate(Origin::signed(5), 0, 1, Conviction::Locked3x, 20));
 		// locked 20.
 		assert!(Balances::locks(5)[0].amount == 20);
 
-		assert_ok!(Democracy::undelegate(Origin::signed(5)));
+		assert_ok!(Democracy::undelegate(Origin::signed(5), 0));
 		// locked 20 until #10
 
 		fast_forward_to(9);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert!(Balances::locks(5)[0].amount == 20);
 
 		fast_forward_to(10);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert!(Balances::locks(5)[0].amount >= 10);
 
 		fast_forward_to(17);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert!(Balances::locks(5)[0].amount >= 10);
 
 		fast_forward_to(18);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![]);
 	});
 }
@@ -332,8 +332,8 @@ fn locks_should_persist_from_voting_to_delegation() {
 fn locks_should_persist_from_delegation_to_voting() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(0);
-		assert_ok!(Democracy::delegate(Origin::signed(5), 1, Conviction::Locked5x, 5));
-		assert_ok!(Democracy::undelegate(Origin::signed(5)));
+		assert_ok!(Democracy::delegate(Origin::signed(5), 0, 1, Conviction::Locked5x, 5));
+		assert_ok!(Democracy::undelegate(Origin::signed(5), 0));
 		// locked 5 until #32
 
 		let r = setup_three_referenda();
@@ -346,19 +346,45 @@ fn locks_should_persist_from_delegation_to_voting() {
 		assert_ok!(Democracy::remove_vote(Origin::signed(5), r.0));
 
 		fast_forward_to(6);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert!(Balances::locks(5)[0].amount >= 20);
 
 		fast_forward_to(10);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert!(Balances::locks(5)[0].amount >= 10);
 
 		fast_forward_to(18);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert!(Balances::locks(5)[0].amount >= 5);
 
 		fast_forward_to(32);
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![]);
 	});
 }
+
+#[test]
+fn unlock_keeps_the_maximum_lock_across_classes() {
+	new_test_ext().execute_with(|| {
+		// locked 10 until #3 for class 0, and 20 until #5 for class 1.
+		assert_ok!(Democracy::delegate(Origin::signed(5), 0, 1, Conviction::Locked1x, 10));
+		assert_ok!(Democracy::delegate(Origin::signed(5), 1, 1, Conviction::Locked2x, 20));
+		assert_ok!(Democracy::undelegate(Origin::signed(5), 0));
+		assert_ok!(Democracy::undelegate(Origin::signed(5), 1));
+		assert_eq!(Balances::locks(5)[0].amount, 20);
+		assert_eq!(Democracy::class_locks_for(5), vec![(0, 10), (1, 20)]);
+
+		fast_forward_to(3);
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
+		assert_eq!(Balances::locks(5)[0].amount, 20);
+		assert_eq!(Democracy::class_locks_for(5), vec![(1, 20)]);
+
+		assert_ok!(Democracy::unlock(Origin::signed(5), 1, 5));
+		assert_eq!(Balances::locks(5)[0].amount, 20);
+
+		fast_forward_to(5);
+		assert_ok!(Democracy::unlock(Origin::signed(5), 1, 5));
+		assert_eq!(Balances::locks(5), vec![]);
+		assert!(Democracy::class_locks_for(5).is_empty());
+	});
+}
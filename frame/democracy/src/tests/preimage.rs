@@ -18,6 +18,8 @@
 //! The preimage tests.
 
 use super::*;
+use frame_support::{storage::migration, weights::Pays};
+use crate::migrations::{self, PreimageStatus};
 
 #[test]
 fn missing_preimage_should_fail() {
@@ -38,94 +40,38 @@ fn missing_preimage_should_fail() {
 }
 
 #[test]
-fn preimage_deposit_should_be_required_and_returned() {
-	new_test_ext_execute_with_cond(|operational| {
+fn preimage_deposit_should_be_returned_once_requested() {
+	new_test_ext().execute_with(|| {
 		// fee of 100 is too much.
 		PREIMAGE_BYTE_DEPOSIT.with(|v| *v.borrow_mut() = 100);
 		assert_noop!(
-			if operational { Democracy::note_preimage_operational(Origin::signed(6), vec![0; 500]) }
-			else { Democracy::note_preimage(Origin::signed(6), vec![0; 500]) },
+			Preimage::note_preimage(Origin::signed(6), vec![0; 500]),
 			BalancesError::<Test, _>::InsufficientBalance,
 		);
 		// fee of 1 is reasonable.
 		PREIMAGE_BYTE_DEPOSIT.with(|v| *v.borrow_mut() = 1);
-		let r = Democracy::inject_referendum(
-			2,
-			set_balance_proposal_hash_and_note(2),
-			VoteThreshold::SuperMajorityApprove,
-			0
-		);
-		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
-
+		let h = set_balance_proposal_hash_and_note(2);
 		assert_eq!(Balances::reserved_balance(6), 12);
 
-		next_block();
-		next_block();
-
+		// Starting the referendum requests the preimage, which refunds the deposit.
+		let r = Democracy::inject_referendum(2, h, VoteThreshold::SuperMajorityApprove, 0);
 		assert_eq!(Balances::reserved_balance(6), 0);
 		assert_eq!(Balances::free_balance(6), 60);
-		assert_eq!(Balances::free_balance(42), 2);
-	});
-}
-
-#[test]
-fn preimage_deposit_should_be_reapable_earlier_by_owner() {
-	new_test_ext_execute_with_cond(|operational| {
-		PREIMAGE_BYTE_DEPOSIT.with(|v| *v.borrow_mut() = 1);
-		assert_ok!(
-			if operational { Democracy::note_preimage_operational(Origin::signed(6), set_balance_proposal(2)) }
-			else { Democracy::note_preimage(Origin::signed(6), set_balance_proposal(2)) }
-		);
-
-		assert_eq!(Balances::reserved_balance(6), 12);
-
-		next_block();
-		assert_noop!(
-			Democracy::reap_preimage(Origin::signed(6), set_balance_proposal_hash(2), u32::max_value()),
-			Error::<Test>::TooEarly
-		);
-		next_block();
-		assert_ok!(Democracy::reap_preimage(Origin::signed(6), set_balance_proposal_hash(2), u32::max_value()));
-
-		assert_eq!(Balances::free_balance(6), 60);
-		assert_eq!(Balances::reserved_balance(6), 0);
-	});
-}
-
-#[test]
-fn preimage_deposit_should_be_reapable() {
-	new_test_ext_execute_with_cond(|operational| {
-		assert_noop!(
-				Democracy::reap_preimage(Origin::signed(5), set_balance_proposal_hash(2), u32::max_value()),
-				Error::<Test>::PreimageMissing
-			);
-
-		PREIMAGE_BYTE_DEPOSIT.with(|v| *v.borrow_mut() = 1);
-		assert_ok!(
-			if operational { Democracy::note_preimage_operational(Origin::signed(6), set_balance_proposal(2)) }
-			else { Democracy::note_preimage(Origin::signed(6), set_balance_proposal(2)) }
-		);
-		assert_eq!(Balances::reserved_balance(6), 12);
+		assert!(Preimage::preimage_requested(&h));
+		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
 
 		next_block();
 		next_block();
-		next_block();
-		assert_noop!(
-				Democracy::reap_preimage(Origin::signed(5), set_balance_proposal_hash(2), u32::max_value()),
-				Error::<Test>::TooEarly
-			);
 
-		next_block();
-		assert_ok!(Democracy::reap_preimage(Origin::signed(5), set_balance_proposal_hash(2), u32::max_value()));
-		assert_eq!(Balances::reserved_balance(6), 0);
-		assert_eq!(Balances::free_balance(6), 48);
-		assert_eq!(Balances::free_balance(5), 62);
+		assert_eq!(Balances::free_balance(42), 2);
+		assert!(!Preimage::preimage_requested(&h));
+		assert!(!Preimage::have_preimage(&h));
 	});
 }
 
 #[test]
-fn noting_imminent_preimage_for_free_should_work() {
-	new_test_ext_execute_with_cond(|operational| {
+fn noting_requested_preimage_for_free_should_work() {
+	new_test_ext().execute_with(|| {
 		PREIMAGE_BYTE_DEPOSIT.with(|v| *v.borrow_mut() = 1);
 
 		let r = Democracy::inject_referendum(
@@ -136,16 +82,12 @@ fn noting_imminent_preimage_for_free_should_work() {
 		);
 		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
 
-		assert_noop!(
-			if operational { Democracy::note_imminent_preimage_operational(Origin::signed(6), set_balance_proposal(2)) }
-			else { Democracy::note_imminent_preimage(Origin::signed(6), set_balance_proposal(2)) },
-			Error::<Test>::NotImminent
-		);
-
 		next_block();
 
-		// Now we're in the dispatch queue it's all good.
-		assert_ok!(Democracy::note_imminent_preimage(Origin::signed(6), set_balance_proposal(2)));
+		// The referendum passed and is in the dispatch queue; noting is free.
+		let post_info = Preimage::note_preimage(Origin::signed(6), set_balance_proposal(2)).unwrap();
+		assert_eq!(post_info.pays_fee, Pays::No);
+		assert_eq!(Balances::reserved_balance(6), 0);
 
 		next_block();
 
@@ -154,44 +96,94 @@ fn noting_imminent_preimage_for_free_should_work() {
 }
 
 #[test]
-fn reaping_imminent_preimage_should_fail() {
+fn preimage_request_should_be_dropped_when_referendum_fails() {
 	new_test_ext().execute_with(|| {
 		let h = set_balance_proposal_hash_and_note(2);
-		let r = Democracy::inject_referendum(3, h, VoteThreshold::SuperMajorityApprove, 1);
-		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
+		let r = Democracy::inject_referendum(2, h, VoteThreshold::SuperMajorityApprove, 0);
+		assert!(Preimage::preimage_requested(&h));
+		assert_ok!(Democracy::vote(Origin::signed(1), r, nay(1)));
+
 		next_block();
 		next_block();
-		assert_noop!(Democracy::reap_preimage(Origin::signed(6), h, u32::max_value()), Error::<Test>::Imminent);
+
+		assert_eq!(Balances::free_balance(42), 0);
+		assert!(!Preimage::preimage_requested(&h));
+		assert!(!Preimage::have_preimage(&h));
 	});
 }
 
 #[test]
-fn note_imminent_preimage_can_only_be_successful_once() {
+fn preimage_request_should_be_dropped_when_referendum_is_cancelled() {
 	new_test_ext().execute_with(|| {
-		PREIMAGE_BYTE_DEPOSIT.with(|v| *v.borrow_mut() = 1);
+		let h = set_balance_proposal_hash(2);
+		let r = Democracy::inject_referendum(2, h, VoteThreshold::SuperMajorityApprove, 0);
+		assert!(Preimage::preimage_requested(&h));
 
-		let r = Democracy::inject_referendum(
-			2,
-			set_balance_proposal_hash(2),
-			VoteThreshold::SuperMajorityApprove,
-			1
-		);
-		assert_ok!(Democracy::vote(Origin::signed(1), r, aye(1)));
-		next_block();
+		assert_ok!(Democracy::cancel_referendum(Origin::root(), r));
+		assert!(!Preimage::preimage_requested(&h));
+	});
+}
 
-		// First time works
-		assert_ok!(Democracy::note_imminent_preimage(Origin::signed(6), set_balance_proposal(2)));
+#[test]
+fn migration_to_v2_should_move_preimages() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::put(Releases::V1);
+
+		// Noted with a deposit, and the subject of an ongoing referendum.
+		let h1 = set_balance_proposal_hash(2);
+		assert_ok!(Balances::reserve(&6, 12));
+		let available = PreimageStatus::<u64, u64, u64>::Available {
+			data: set_balance_proposal(2),
+			provider: 6,
+			deposit: 12,
+			since: 0,
+			expiry: None,
+		};
+		migration::put_storage_value(b"Democracy", b"Preimages", &h1.encode(), available);
+		let status = ReferendumStatus {
+			end: 2,
+			proposal_hash: h1,
+			threshold: VoteThreshold::SuperMajorityApprove,
+			delay: 0,
+			tally: Default::default(),
+		};
+		ReferendumInfoOf::<Test>::insert(0, ReferendumInfo::Ongoing(status));
+
+		// Imminently needed but missing.
+		let h2 = set_balance_proposal_hash(3);
+		let missing = PreimageStatus::<u64, u64, u64>::Missing(5);
+		migration::put_storage_value(b"Democracy", b"Preimages", &h2.encode(), missing);
+
+		// Imminently needed and available.
+		let h3 = set_balance_proposal_hash(4);
+		let imminent = PreimageStatus::<u64, u64, u64>::Available {
+			data: set_balance_proposal(4),
+			provider: 5,
+			deposit: 0,
+			since: 0,
+			expiry: Some(5),
+		};
+		migration::put_storage_value(b"Democracy", b"Preimages", &h3.encode(), imminent);
+
+		migrations::migrate_to_v2::<Test>();
+
+		assert_eq!(StorageVersion::get(), Some(Releases::V2));
+		assert_eq!(Balances::reserved_balance(6), 0);
+		assert_eq!(Balances::free_balance(6), 60);
 
-		// Second time fails
-		assert_noop!(
-			Democracy::note_imminent_preimage(Origin::signed(6), set_balance_proposal(2)),
-			Error::<Test>::DuplicatePreimage
-		);
+		assert!(Preimage::have_preimage(&h1));
+		assert!(Preimage::preimage_requested(&h1));
+		assert!(!Preimage::have_preimage(&h2));
+		assert!(Preimage::preimage_requested(&h2));
+		assert!(Preimage::have_preimage(&h3));
+		assert!(Preimage::preimage_requested(&h3));
 
-		// Fails from any user
-		assert_noop!(
-			Democracy::note_imminent_preimage(Origin::signed(5), set_balance_proposal(2)),
-			Error::<Test>::DuplicatePreimage
-		);
+		for h in &[h1, h2, h3] {
+			assert!(!migration::have_storage_value(b"Democracy", b"Preimages", &h.encode()));
+		}
+
+		// Running it again does nothing.
+		migrations::migrate_to_v2::<Test>();
+		assert_eq!(StorageVersion::get(), Some(Releases::V2));
 	});
 }
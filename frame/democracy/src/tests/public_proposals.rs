@@ -45,6 +45,29 @@ fn deposit_for_proposals_should_be_taken() {
 	});
 }
 
+#[test]
+fn migrate_to_v4_names_the_deposits_for_proposals() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(propose_set_balance_and_note(1, 2, 5));
+		assert_ok!(Democracy::second(Origin::signed(5), 0, u32::max_value()));
+		assert_ok!(Democracy::second(Origin::signed(5), 0, u32::max_value()));
+		assert_eq!(Balances::reserved_balance_named(&PROPOSAL_DEPOSIT_ID, &5), 10);
+
+		// the deposits as they were held before named reserves were used.
+		for &(who, deposit) in &[(1, 5), (5, 10)] {
+			let _ = Balances::unreserve_named(&PROPOSAL_DEPOSIT_ID, &who, deposit);
+			assert_ok!(Balances::reserve(&who, deposit));
+		}
+		StorageVersion::put(Releases::V3);
+
+		crate::migrations::migrate_to_v4::<Test>();
+		assert_eq!(Balances::reserved_balance_named(&PROPOSAL_DEPOSIT_ID, &1), 5);
+		assert_eq!(Balances::reserved_balance_named(&PROPOSAL_DEPOSIT_ID, &5), 10);
+		assert_eq!(Balances::reserved_balance(5), 10);
+		assert_eq!(StorageVersion::get(), Some(Releases::V4));
+	});
+}
+
 #[test]
 fn deposit_for_proposals_should_be_returned() {
 	new_test_ext().execute_with(|| {
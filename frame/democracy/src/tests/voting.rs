@@ -48,7 +48,7 @@ fn split_vote_cancellation_should_work() {
 		assert_ok!(Democracy::vote(Origin::signed(5), r, v));
 		assert_ok!(Democracy::remove_vote(Origin::signed(5), r));
 		assert_eq!(tally(r), Tally { ayes: 0, nays: 0, turnout: 0 });
-		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 0, 5));
 		assert_eq!(Balances::locks(5), vec![]);
 	});
 }
@@ -37,9 +37,9 @@ pub struct Tally<Balance> {
 #[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
 pub struct Delegations<Balance> {
 	/// The number of votes (this is post-conviction).
-	pub (crate) votes: Balance,
+	pub votes: Balance,
 	/// The amount of raw capital, used for the turnout.
-	pub (crate) capital: Balance,
+	pub capital: Balance,
 }
 
 impl<Balance: Saturating> Saturating for Delegations<Balance> {
@@ -205,3 +205,16 @@ pub enum UnvoteScope {
 	/// Permitted to do only the changes that do not need the owner's permission.
 	OnlyExpired,
 }
+
+/// Determines the class of a referendum from its proposal.
+pub trait ClassifyProposal<Proposal, Class> {
+	/// The class of referenda on `proposal`.
+	fn classify(proposal: &Proposal) -> Class;
+}
+
+/// Puts all referenda in the default class.
+impl<Proposal, Class: Default> ClassifyProposal<Proposal, Class> for () {
+	fn classify(_: &Proposal) -> Class {
+		Default::default()
+	}
+}
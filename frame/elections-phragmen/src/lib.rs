@@ -1108,6 +1108,9 @@ mod tests {
 		type ExistentialDeposit = ExistentialDeposit;
 		type AccountStore = frame_system::Module<Test>;
 		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 		type WeightInfo = ();
 	}
 
@@ -118,7 +118,7 @@
 use sp_std::{prelude::*, marker::PhantomData};
 use frame_support::{
 	StorageValue, StorageMap, weights::{GetDispatchInfo, DispatchInfo, DispatchClass},
-	traits::{OnInitialize, OnFinalize, OnRuntimeUpgrade, OffchainWorker},
+	traits::{OnInitialize, OnIdle, OnFinalize, OnRuntimeUpgrade, OffchainWorker},
 	dispatch::PostDispatchInfo,
 };
 use sp_runtime::{
@@ -165,6 +165,7 @@ impl<
 	AllModules:
 		OnRuntimeUpgrade +
 		OnInitialize<System::BlockNumber> +
+		OnIdle<System::BlockNumber> +
 		OnFinalize<System::BlockNumber> +
 		OffchainWorker<System::BlockNumber>,
 	COnRuntimeUpgrade: OnRuntimeUpgrade,
@@ -192,6 +193,7 @@ impl<
 	AllModules:
 		OnRuntimeUpgrade +
 		OnInitialize<System::BlockNumber> +
+		OnIdle<System::BlockNumber> +
 		OnFinalize<System::BlockNumber> +
 		OffchainWorker<System::BlockNumber>,
 	COnRuntimeUpgrade: OnRuntimeUpgrade,
@@ -327,8 +329,7 @@ where
 
 		// post-extrinsics book-keeping
 		<frame_system::Module<System>>::note_finished_extrinsics();
-		<frame_system::Module<System> as OnFinalize<System::BlockNumber>>::on_finalize(block_number);
-		<AllModules as OnFinalize<System::BlockNumber>>::on_finalize(block_number);
+		Self::idle_and_finalize_hook(block_number);
 	}
 
 	/// Finalize the block - it is up the caller to ensure that all header fields are valid
@@ -338,14 +339,40 @@ where
 		sp_tracing::enter_span!( sp_tracing::Level::TRACE, "finalize_block" );
 		<frame_system::Module<System>>::note_finished_extrinsics();
 		let block_number = <frame_system::Module<System>>::block_number();
-		<frame_system::Module<System> as OnFinalize<System::BlockNumber>>::on_finalize(block_number);
-		<AllModules as OnFinalize<System::BlockNumber>>::on_finalize(block_number);
+		Self::idle_and_finalize_hook(block_number);
 
 		// set up extrinsics
 		<frame_system::Module<System>>::derive_extrinsics();
 		<frame_system::Module<System>>::finalize()
 	}
 
+	/// Run the `on_idle` hooks with the weight left in the block, followed by `on_finalize`.
+	fn idle_and_finalize_hook(block_number: NumberFor<Block>) {
+		let weight = <frame_system::Module<System>>::block_weight();
+		let max_weight = <System::BlockWeights as frame_support::traits::Get<_>>::get().max_block;
+		let remaining_weight = max_weight.saturating_sub(weight.total());
+
+		if remaining_weight > 0 {
+			let mut used_weight =
+				<frame_system::Module<System> as OnIdle<System::BlockNumber>>::on_idle(
+					block_number,
+					remaining_weight,
+				);
+			let remaining_weight = remaining_weight.saturating_sub(used_weight);
+			used_weight = <AllModules as OnIdle<System::BlockNumber>>::on_idle(
+				block_number,
+				remaining_weight,
+			).saturating_add(used_weight);
+			<frame_system::Module::<System>>::register_extra_weight_unchecked(
+				used_weight,
+				DispatchClass::Mandatory,
+			);
+		}
+
+		<frame_system::Module<System> as OnFinalize<System::BlockNumber>>::on_finalize(block_number);
+		<AllModules as OnFinalize<System::BlockNumber>>::on_finalize(block_number);
+	}
+
 	/// Apply extrinsic outside of the block execution function.
 	///
 	/// This doesn't attempt to validate anything regarding the block, but it builds a list of uxt
@@ -513,6 +540,12 @@ mod tests {
 
 		pub trait Config: frame_system::Config {}
 
+		thread_local! {
+			pub static ON_IDLE_WEIGHT: std::cell::RefCell<Weight> = std::cell::RefCell::new(0);
+			pub static ON_IDLE_REMAINING: std::cell::RefCell<Option<Weight>> =
+				std::cell::RefCell::new(None);
+		}
+
 		frame_support::decl_module! {
 			pub struct Module<T: Config> for enum Call where origin: T::Origin {
 				#[weight = 100]
@@ -546,6 +579,12 @@ mod tests {
 					175
 				}
 
+				fn on_idle(n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+					println!("on_idle({}, {})", n, remaining_weight);
+					ON_IDLE_REMAINING.with(|r| *r.borrow_mut() = Some(remaining_weight));
+					ON_IDLE_WEIGHT.with(|w| *w.borrow()).min(remaining_weight)
+				}
+
 				fn on_finalize() {
 					println!("on_finalize(?)");
 				}
@@ -636,6 +675,9 @@ mod tests {
 		type ExistentialDeposit = ExistentialDeposit;
 		type AccountStore = System;
 		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 		type WeightInfo = ();
 	}
 
@@ -997,6 +1039,24 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn on_idle_gets_remaining_block_weight() {
+		new_test_ext(1).execute_with(|| {
+			custom::ON_IDLE_WEIGHT.with(|w| *w.borrow_mut() = 150);
+
+			Executive::initialize_block(&Header::new_from_number(1));
+			let max_block = <Runtime as frame_system::Config>::BlockWeights::get().max_block;
+			let consumed = <frame_system::Module<Runtime>>::block_weight().total();
+
+			Executive::finalize_block();
+			assert_eq!(
+				custom::ON_IDLE_REMAINING.with(|r| *r.borrow()),
+				Some(max_block - consumed),
+			);
+			assert_eq!(<frame_system::Module<Runtime>>::block_weight().total(), consumed + 150);
+		})
+	}
+
 	#[test]
 	fn runtime_upgraded_should_work() {
 		new_test_ext(1).execute_with(|| {
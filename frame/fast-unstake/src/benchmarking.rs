@@ -0,0 +1,134 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fast unstake pallet benchmarking.
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, account};
+use frame_system::RawOrigin;
+use frame_support::{StorageMap, StorageValue, traits::OnIdle};
+
+use crate::Module as FastUnstake;
+
+const SEED: u32 = 0;
+const MAX_VALIDATORS: u32 = 1000;
+
+/// Create a nominator, returning its stash and controller.
+fn create_nominator<T: Config>() -> Result<(T::AccountId, T::AccountId), &'static str> {
+	let stash: T::AccountId = account("stash", 0, SEED);
+	let controller: T::AccountId = account("controller", 0, SEED);
+	let amount = T::Staking::minimum_bond() * 10u32.into();
+	let balance = amount + T::Deposit::get() * 10u32.into();
+	T::Currency::make_free_balance_be(&stash, balance);
+	T::Currency::make_free_balance_be(&controller, balance);
+	T::Staking::bond(stash.clone(), controller.clone(), amount, stash.clone())?;
+	T::Staking::nominate(controller.clone(), vec![account("validator", 0, SEED)])?;
+	Ok((stash, controller))
+}
+
+/// Expose `v` validators in `era`, each with the largest exposure checks are benchmarked against.
+fn setup_exposures<T: Config>(v: u32, era: EraIndex) {
+	let exposure_size = T::Staking::max_exposure_size();
+	for i in 0..v {
+		let validator: T::AccountId = account("validator", i, SEED);
+		let nominators = (0..exposure_size)
+			.map(|j| account("nominator", i * exposure_size + j, SEED))
+			.collect();
+		T::Staking::add_era_stakers(&era, &validator, nominators);
+	}
+}
+
+/// Create a nominator and register it, returning its stash.
+fn register_nominator<T: Config>() -> Result<T::AccountId, &'static str> {
+	let (stash, controller) = create_nominator::<T>()?;
+	FastUnstake::<T>::register_fast_unstake(RawOrigin::Signed(controller).into())?;
+	Ok(stash)
+}
+
+benchmarks! {
+	_ { }
+
+	register_fast_unstake {
+		let (stash, controller) = create_nominator::<T>()?;
+	}: _(RawOrigin::Signed(controller))
+	verify {
+		assert!(FastUnstake::<T>::queue(&stash).is_some());
+	}
+
+	deregister {
+		let (stash, controller) = create_nominator::<T>()?;
+		FastUnstake::<T>::register_fast_unstake(RawOrigin::Signed(controller.clone()).into())?;
+	}: _(RawOrigin::Signed(controller))
+	verify {
+		assert!(FastUnstake::<T>::queue(&stash).is_none());
+	}
+
+	// Check a single era with `v` exposed validators for a new head, taken out of the queue, with
+	// the weight left in the block being just enough for one era.
+	on_initialize_check {
+		let v in 1 .. MAX_VALIDATORS;
+		setup_exposures::<T>(v, T::Staking::current_era());
+		let stash = register_nominator::<T>()?;
+		let remaining_weight = T::WeightInfo::on_initialize_check(T::Staking::validator_count());
+	}: {
+		FastUnstake::<T>::on_idle(Zero::zero(), remaining_weight);
+	}
+	verify {
+		assert_eq!(FastUnstake::<T>::head().map(|head| head.stash), Some(stash));
+	}
+
+	// Unstake the head, checking the current era with `v` exposed validators once more.
+	on_initialize_unstake {
+		let v in 1 .. MAX_VALIDATORS;
+		setup_exposures::<T>(v, T::Staking::current_era());
+		let stash = register_nominator::<T>()?;
+		let (depositor, deposit) = Queue::<T>::take(&stash).ok_or("stash not queued")?;
+		let current_era = T::Staking::current_era();
+		let first_era = current_era.saturating_sub(T::Staking::bonding_duration());
+		Head::<T>::put(UnstakeRequest {
+			stash: stash.clone(),
+			depositor,
+			deposit,
+			checked: (first_era..=current_era).collect(),
+		});
+		let remaining_weight = T::WeightInfo::on_initialize_unstake(T::Staking::validator_count());
+	}: {
+		FastUnstake::<T>::on_idle(Zero::zero(), remaining_weight);
+	}
+	verify {
+		assert!(FastUnstake::<T>::head().is_none());
+		assert!(T::Staking::total_stake(&stash).is_none());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_register_fast_unstake::<Test>());
+			assert_ok!(test_benchmark_deregister::<Test>());
+			assert_ok!(test_benchmark_on_initialize_check::<Test>());
+			assert_ok!(test_benchmark_on_initialize_unstake::<Test>());
+		});
+	}
+}
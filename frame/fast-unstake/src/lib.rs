@@ -0,0 +1,348 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Fast Unstake Pallet
+//!
+//! - [`fast_unstake::Config`](./trait.Config.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! A pallet which allows stakers which were never exposed to unstake right away, without waiting
+//! for the bonding duration of the staking system.
+//!
+//! Funds have to stay bonded for the bonding duration after they are unbonded, so that they can
+//! still be slashed for offences committed while they were backing validators. A stash which was
+//! not exposed in any of the eras of the bonding duration cannot have been backing a validator
+//! committing an offence, so its funds can be unlocked immediately.
+//!
+//! ### Registration
+//!
+//! The controller of a fully bonded stash calls `register_fast_unstake`, which chills the stash,
+//! unbonds all of its funds, reserves `Deposit` from the controller and puts the stash into the
+//! queue. Until the stash is checked, the controller can call `deregister` to leave the queue and
+//! get the deposit back. The funds of the stash keep unbonding in the staking system then.
+//!
+//! ### Checking
+//!
+//! At the end of every block, the eras of the bonding duration are checked for the exposures of
+//! the stash at the head of the queue, as many as the weight left in the block allows and up to
+//! `ErasToCheckPerBlock`. Once all the eras are checked:
+//!
+//! * If the stash was not exposed in any of them, and is still chilled, it is fully unstaked and
+//!   the deposit is returned.
+//! * Otherwise, the funds of the stash keep unbonding in the staking system, and the deposit is
+//!   slashed as a compensation for the wasted checks.
+//!
+//! Nominating again while being checked thus forfeits the deposit, as the stash could be exposed
+//! in an era which was never checked.
+//!
+//! The checks stop at the first era in which the stash was exposed. Setting
+//! `ErasToCheckPerBlock` to zero disables the processing of the queue.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! * `register_fast_unstake` - Register a stash to be unstaked as soon as it is checked.
+//! * `deregister` - Remove a stash from the queue, before it is checked.
+//!
+//! ### Staking Integration
+//!
+//! The pallet checks the exposures and unstakes through [`sp_staking::StakingInterface`].
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::{Encode, Decode};
+use sp_runtime::{DispatchResult, RuntimeDebug, traits::{Saturating, Zero, SaturatedConversion}};
+use frame_support::{
+	decl_module, decl_event, decl_storage, decl_error, ensure, transactional,
+	traits::{Currency, Get, ReservableCurrency},
+	weights::Weight,
+	IterableStorageMap,
+};
+use frame_system::ensure_signed;
+use sp_staking::{EraIndex, StakingInterface};
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Configuration trait.
+pub trait Config: frame_system::Config {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+	/// The currency in which the deposit is reserved.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The staking system in which the stashes are checked and unstaked.
+	type Staking: StakingInterface<Balance = BalanceOf<Self>, AccountId = Self::AccountId>;
+
+	/// The deposit reserved from the controller while its stash is in the queue.
+	///
+	/// It is slashed if the stash turns out to have been exposed.
+	type Deposit: Get<BalanceOf<Self>>;
+
+	/// The maximum number of eras checked for the exposures of the head of the queue per block.
+	type ErasToCheckPerBlock: Get<u32>;
+
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+/// A stash being checked, at the head of the queue.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct UnstakeRequest<AccountId, Balance> {
+	/// The stash to unstake.
+	pub stash: AccountId,
+	/// The account from which the deposit was reserved.
+	pub depositor: AccountId,
+	/// The reserved deposit.
+	pub deposit: Balance,
+	/// The eras checked so far, in which the stash was not exposed.
+	pub checked: Vec<EraIndex>,
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as FastUnstake {
+		/// The stashes waiting to be checked, with the account from which their deposit was
+		/// reserved and the deposit.
+		pub Queue get(fn queue):
+			map hasher(twox_64_concat) T::AccountId => Option<(T::AccountId, BalanceOf<T>)>;
+
+		/// The number of stashes in `Queue`.
+		pub CounterForQueue get(fn counter_for_queue): u32;
+
+		/// The stash being checked, which was taken out of `Queue`.
+		pub Head get(fn head): Option<UnstakeRequest<T::AccountId, BalanceOf<T>>>;
+	}
+}
+
+decl_event! {
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Config>::AccountId,
+		Balance = BalanceOf<T>,
+	{
+		/// A stash was registered to be unstaked. \[stash\]
+		Registered(AccountId),
+		/// A stash was removed from the queue. \[stash\]
+		Deregistered(AccountId),
+		/// A stash was checked and unstaked. \[stash, result\]
+		Unstaked(AccountId, DispatchResult),
+		/// A stash turned out to have been exposed, and its deposit was slashed.
+		/// \[stash, amount\]
+		Slashed(AccountId, Balance),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// The origin is not the controller of a stash.
+		NotController,
+		/// The stash is already in the queue.
+		AlreadyQueued,
+		/// The stash is already being checked.
+		AlreadyHead,
+		/// The stash has funds being unlocked.
+		NotFullyBonded,
+		/// The stash is not in the queue.
+		NotQueued,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		/// The deposit reserved from the controller while its stash is in the queue.
+		const Deposit: BalanceOf<T> = T::Deposit::get();
+
+		/// The maximum number of eras checked per block.
+		const ErasToCheckPerBlock: u32 = T::ErasToCheckPerBlock::get();
+
+		fn deposit_event() = default;
+
+		/// Check the next eras for the exposures of the head of the queue, or unstake it if
+		/// all the eras are checked, with the weight left in the block.
+		fn on_idle(_now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::process_head(T::ErasToCheckPerBlock::get(), remaining_weight)
+		}
+
+		/// Register the stash of the origin to be unstaked as soon as it is checked.
+		///
+		/// The stash is chilled and all of its funds are unbonded right away, and `Deposit` is
+		/// reserved from the origin until the stash is checked.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the controller, and the stash
+		/// must have no funds being unlocked.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - One chill and one unbond in the staking system, and one reserve.
+		/// - Two storage writes.
+		/// # </weight>
+		#[weight = T::WeightInfo::register_fast_unstake()]
+		#[transactional]
+		fn register_fast_unstake(origin) {
+			let controller = ensure_signed(origin)?;
+			let stash = T::Staking::stash_by_ctrl(&controller)
+				.map_err(|_| Error::<T>::NotController)?;
+			ensure!(!Queue::<T>::contains_key(&stash), Error::<T>::AlreadyQueued);
+			ensure!(
+				Self::head().map_or(true, |head| head.stash != stash),
+				Error::<T>::AlreadyHead,
+			);
+			let active = T::Staking::active_stake(&stash).unwrap_or_else(Zero::zero);
+			ensure!(
+				Some(active) == T::Staking::total_stake(&stash),
+				Error::<T>::NotFullyBonded,
+			);
+
+			T::Staking::chill(controller.clone())?;
+			// Unbonded funds cannot back a validator anymore.
+			T::Staking::unbond(controller.clone(), active)?;
+			let deposit = T::Deposit::get();
+			T::Currency::reserve(&controller, deposit)?;
+
+			Queue::<T>::insert(&stash, (controller, deposit));
+			CounterForQueue::mutate(|count| *count = count.saturating_add(1));
+			Self::deposit_event(RawEvent::Registered(stash));
+		}
+
+		/// Remove the stash of the origin from the queue, and return the deposit.
+		///
+		/// Once the stash is being checked, it cannot be removed anymore. The funds of the stash
+		/// keep unbonding in the staking system.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the controller.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - One unreserve.
+		/// - Two storage writes.
+		/// # </weight>
+		#[weight = T::WeightInfo::deregister()]
+		fn deregister(origin) {
+			let controller = ensure_signed(origin)?;
+			let stash = T::Staking::stash_by_ctrl(&controller)
+				.map_err(|_| Error::<T>::NotController)?;
+			let (depositor, deposit) = Queue::<T>::take(&stash).ok_or(Error::<T>::NotQueued)?;
+
+			T::Currency::unreserve(&depositor, deposit);
+			CounterForQueue::mutate(|count| *count = count.saturating_sub(1));
+			Self::deposit_event(RawEvent::Deregistered(stash));
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// Take the next stash out of the queue, to be checked.
+	fn next_request() -> Option<UnstakeRequest<T::AccountId, BalanceOf<T>>> {
+		let (stash, (depositor, deposit)) = Queue::<T>::iter().next()?;
+		Queue::<T>::remove(&stash);
+		CounterForQueue::mutate(|count| *count = count.saturating_sub(1));
+		Some(UnstakeRequest { stash, depositor, deposit, checked: Vec::new() })
+	}
+
+	/// Check up to `eras_to_check` eras of the bonding duration for the exposures of the head of
+	/// the queue, as many as fit in `remaining_weight`, taking a new head out of the queue if there
+	/// is none.
+	///
+	/// Once all the eras are checked, the head is unstaked. If it was exposed in any of them, or
+	/// nominated again in the meantime, its deposit is slashed instead, and its funds keep
+	/// unbonding.
+	fn process_head(eras_to_check: u32, remaining_weight: Weight) -> Weight {
+		if eras_to_check.is_zero() {
+			return 0
+		}
+		// Each checked era iterates over the exposures of all of its validators.
+		let validator_count = T::Staking::validator_count();
+		let check_weight = T::WeightInfo::on_initialize_check(validator_count);
+		let eras_to_check = eras_to_check
+			.min((remaining_weight / check_weight.max(1)).saturated_into::<u32>());
+		if eras_to_check.is_zero() {
+			return T::DbWeight::get().reads(1).min(remaining_weight)
+		}
+
+		let mut request = match Self::head().or_else(Self::next_request) {
+			Some(request) => request,
+			None => return T::DbWeight::get().reads(3),
+		};
+
+		let current_era = T::Staking::current_era();
+		let first_era = current_era.saturating_sub(T::Staking::bonding_duration());
+		// Eras which left the bonding duration since they were checked do not matter anymore.
+		request.checked.retain(|era| *era >= first_era);
+		let unchecked = (first_era..=current_era)
+			.rev()
+			.filter(|era| !request.checked.contains(era))
+			.take(eras_to_check as usize)
+			.collect::<Vec<_>>();
+
+		if unchecked.is_empty() {
+			let unstake_weight = T::WeightInfo::on_initialize_unstake(validator_count);
+			if unstake_weight > remaining_weight {
+				// The head is unstaked in a block with more weight left.
+				return T::DbWeight::get().reads(3)
+			}
+			Head::<T>::kill();
+			// The stash could have nominated again since it was checked, so it is checked once
+			// more right before being unstaked.
+			if T::Staking::is_chilled(&request.stash) &&
+				!T::Staking::is_exposed_in_era(&request.stash, &current_era)
+			{
+				let result = T::Staking::force_unstake(request.stash.clone());
+				T::Currency::unreserve(&request.depositor, request.deposit);
+				Self::deposit_event(RawEvent::Unstaked(request.stash, result));
+			} else {
+				Self::slash_deposit(request);
+			}
+			return unstake_weight
+		}
+
+		let num_checked = unchecked.len() as u32;
+		if unchecked.iter().any(|era| T::Staking::is_exposed_in_era(&request.stash, era)) {
+			Head::<T>::kill();
+			Self::slash_deposit(request);
+		} else {
+			request.checked.extend(unchecked);
+			Head::<T>::put(request);
+		}
+		check_weight.saturating_mul(num_checked as Weight)
+	}
+
+	/// Slash the deposit of a stash which cannot be unstaked.
+	fn slash_deposit(request: UnstakeRequest<T::AccountId, BalanceOf<T>>) {
+		// The slashed deposit is burnt.
+		let (_, remaining) = T::Currency::slash_reserved(&request.depositor, request.deposit);
+		let slashed = request.deposit.saturating_sub(remaining);
+		Self::deposit_event(RawEvent::Slashed(request.stash, slashed));
+	}
+}
@@ -0,0 +1,309 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test utilities
+
+use super::*;
+
+use frame_support::{impl_outer_origin, impl_outer_event, parameter_types, traits::OnIdle};
+use std::{cell::RefCell, collections::BTreeMap};
+use sp_core::H256;
+use sp_runtime::{
+	DispatchError, traits::{BlakeTwo256, IdentityLookup}, testing::Header,
+};
+use crate as fast_unstake;
+
+pub type AccountId = u64;
+pub type Balance = u64;
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		system<T>,
+		pallet_balances<T>,
+		fast_unstake<T>,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
+	type Balance = Balance;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+pub const BONDING_DURATION: EraIndex = 3;
+pub const VALIDATOR_COUNT: u32 = 4;
+
+/// The ledger of a stash of the mock staking system.
+#[derive(Clone)]
+pub struct Ledger {
+	pub stash: AccountId,
+	pub active: Balance,
+	pub total: Balance,
+}
+
+thread_local! {
+	static CURRENT_ERA: RefCell<EraIndex> = RefCell::new(0);
+	/// The ledgers, keyed by controller.
+	static LEDGERS: RefCell<BTreeMap<AccountId, Ledger>> = RefCell::new(Default::default());
+	static NOMINATORS: RefCell<Vec<AccountId>> = RefCell::new(vec![]);
+	static EXPOSURES: RefCell<BTreeMap<EraIndex, Vec<AccountId>>> =
+		RefCell::new(Default::default());
+}
+
+/// A staking system which keeps its ledgers in memory.
+pub struct StakingMock;
+impl StakingMock {
+	pub fn set_current_era(era: EraIndex) {
+		CURRENT_ERA.with(|e| *e.borrow_mut() = era);
+	}
+
+	/// Expose `who` in `era`.
+	pub fn expose(who: AccountId, era: EraIndex) {
+		EXPOSURES.with(|e| e.borrow_mut().entry(era).or_default().push(who));
+	}
+
+	pub fn is_bonded(stash: AccountId) -> bool {
+		LEDGERS.with(|l| l.borrow().values().any(|l| l.stash == stash))
+	}
+
+	pub fn is_nominator(stash: AccountId) -> bool {
+		NOMINATORS.with(|n| n.borrow().contains(&stash))
+	}
+
+}
+
+impl StakingInterface for StakingMock {
+	type Balance = Balance;
+	type AccountId = AccountId;
+
+	fn minimum_bond() -> Balance {
+		10
+	}
+
+	fn bonding_duration() -> EraIndex {
+		BONDING_DURATION
+	}
+
+	fn current_era() -> EraIndex {
+		CURRENT_ERA.with(|e| *e.borrow())
+	}
+
+	fn validator_count() -> u32 {
+		VALIDATOR_COUNT
+	}
+
+	fn active_stake(stash: &AccountId) -> Option<Balance> {
+		LEDGERS.with(|l| l.borrow().values().find(|l| l.stash == *stash).map(|l| l.active))
+	}
+
+	fn total_stake(stash: &AccountId) -> Option<Balance> {
+		LEDGERS.with(|l| l.borrow().values().find(|l| l.stash == *stash).map(|l| l.total))
+	}
+
+	fn bond(
+		stash: AccountId,
+		controller: AccountId,
+		value: Balance,
+		_payee: AccountId,
+	) -> DispatchResult {
+		let ledger = Ledger { stash, active: value, total: value };
+		LEDGERS.with(|l| l.borrow_mut().insert(controller, ledger));
+		Ok(())
+	}
+
+	fn bond_extra(stash: AccountId, extra: Balance) -> DispatchResult {
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			let ledger = ledgers.values_mut()
+				.find(|l| l.stash == stash)
+				.ok_or(DispatchError::Other("not bonded"))?;
+			ledger.active += extra;
+			ledger.total += extra;
+			Ok(())
+		})
+	}
+
+	fn unbond(controller: AccountId, value: Balance) -> DispatchResult {
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			let ledger = ledgers.get_mut(&controller).ok_or(DispatchError::Other("not bonded"))?;
+			ledger.active -= value.min(ledger.active);
+			Ok(())
+		})
+	}
+
+	fn withdraw_unbonded(controller: AccountId) -> Result<bool, DispatchError> {
+		// The unbonding funds are never unlocked in the mock.
+		Self::stash_by_ctrl(&controller).map(|_| false)
+	}
+
+	fn nominate(controller: AccountId, _: Vec<AccountId>) -> DispatchResult {
+		let stash = Self::stash_by_ctrl(&controller)?;
+		NOMINATORS.with(|n| n.borrow_mut().push(stash));
+		Ok(())
+	}
+
+	fn chill(controller: AccountId) -> DispatchResult {
+		let stash = Self::stash_by_ctrl(&controller)?;
+		NOMINATORS.with(|n| n.borrow_mut().retain(|n| *n != stash));
+		Ok(())
+	}
+
+	fn is_chilled(stash: &AccountId) -> bool {
+		!Self::is_nominator(*stash)
+	}
+
+	fn stash_by_ctrl(controller: &AccountId) -> Result<AccountId, DispatchError> {
+		LEDGERS.with(|l| l.borrow().get(controller).map(|l| l.stash))
+			.ok_or(DispatchError::Other("not a controller"))
+	}
+
+	fn is_exposed_in_era(who: &AccountId, era: &EraIndex) -> bool {
+		EXPOSURES.with(|e| e.borrow().get(era).map_or(false, |exposed| exposed.contains(who)))
+	}
+
+	fn force_unstake(stash: AccountId) -> DispatchResult {
+		ensure!(Self::is_bonded(stash), DispatchError::Other("not a stash"));
+		LEDGERS.with(|l| l.borrow_mut().retain(|_, l| l.stash != stash));
+		NOMINATORS.with(|n| n.borrow_mut().retain(|n| *n != stash));
+		Ok(())
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn max_exposure_size() -> u32 {
+		4
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn add_era_stakers(era: &EraIndex, validator: &AccountId, nominators: Vec<AccountId>) {
+		Self::expose(*validator, *era);
+		nominators.into_iter().for_each(|who| Self::expose(who, *era));
+	}
+}
+
+parameter_types! {
+	pub const Deposit: Balance = 10;
+	pub static ErasToCheckPerBlock: u32 = 1;
+}
+
+impl Config for Test {
+	type Event = TestEvent;
+	type Currency = Balances;
+	type Staking = StakingMock;
+	type Deposit = Deposit;
+	type ErasToCheckPerBlock = ErasToCheckPerBlock;
+	type WeightInfo = ();
+}
+
+pub type System = frame_system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type FastUnstake = Module<Test>;
+
+/// The stashes bonded at genesis, with their controllers, all nominating.
+pub const STAKERS: [(AccountId, AccountId); 3] = [(1, 2), (3, 4), (5, 6)];
+
+/// Build the externalities at era 3, with the nominators of `STAKERS` bonding 100 each.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	StakingMock::set_current_era(3);
+	ErasToCheckPerBlock::set(1);
+	LEDGERS.with(|l| l.borrow_mut().clear());
+	NOMINATORS.with(|n| n.borrow_mut().clear());
+	EXPOSURES.with(|e| e.borrow_mut().clear());
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: STAKERS.iter()
+			.flat_map(|(stash, ctrl)| vec![(*stash, 100), (*ctrl, 100)])
+			.collect(),
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		for (stash, ctrl) in STAKERS.iter() {
+			assert!(StakingMock::bond(*stash, *ctrl, 100, *stash).is_ok());
+			assert!(StakingMock::nominate(*ctrl, vec![100]).is_ok());
+		}
+	});
+	ext
+}
+
+/// Run `on_idle` of the pallet for the next `n` blocks, with no limit on the weight.
+pub fn run_blocks(n: u64) {
+	for _ in 0..n {
+		run_block_with_weight(Weight::max_value());
+	}
+}
+
+/// Run `on_idle` of the pallet for the next block, with `remaining_weight` left in it.
+pub fn run_block_with_weight(remaining_weight: Weight) -> Weight {
+	let now = System::block_number() + 1;
+	System::set_block_number(now);
+	FastUnstake::on_idle(now, remaining_weight)
+}
+
+/// The events of this pallet deposited so far.
+pub fn fast_unstake_events() -> Vec<RawEvent<AccountId, Balance>> {
+	System::events().into_iter().filter_map(|r| match r.event {
+		TestEvent::fast_unstake(e) => Some(e),
+		_ => None,
+	}).collect()
+}
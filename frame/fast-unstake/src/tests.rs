@@ -0,0 +1,271 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the module.
+
+use super::*;
+use mock::{
+	Balances, ErasToCheckPerBlock, FastUnstake, Origin, StakingMock, Test, VALIDATOR_COUNT,
+	fast_unstake_events, new_test_ext, run_blocks, run_block_with_weight,
+};
+use frame_support::{assert_noop, assert_ok, StorageValue};
+
+#[test]
+fn register_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(2)));
+
+		assert_eq!(FastUnstake::queue(1), Some((2, 10)));
+		assert_eq!(FastUnstake::counter_for_queue(), 1);
+		assert_eq!(Balances::reserved_balance(2), 10);
+		assert!(!StakingMock::is_nominator(1));
+		assert_eq!(StakingMock::active_stake(&1), Some(0));
+		assert_eq!(fast_unstake_events(), vec![RawEvent::Registered(1)]);
+
+		assert_noop!(
+			FastUnstake::register_fast_unstake(Origin::signed(2)),
+			Error::<Test>::AlreadyQueued
+		);
+		// the stash is not a controller.
+		assert_noop!(
+			FastUnstake::register_fast_unstake(Origin::signed(3)),
+			Error::<Test>::NotController
+		);
+		assert_ok!(StakingMock::unbond(4, 10));
+		assert_noop!(
+			FastUnstake::register_fast_unstake(Origin::signed(4)),
+			Error::<Test>::NotFullyBonded
+		);
+	});
+}
+
+#[test]
+fn deregister_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(FastUnstake::deregister(Origin::signed(2)), Error::<Test>::NotQueued);
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(2)));
+		assert_ok!(FastUnstake::deregister(Origin::signed(2)));
+
+		assert!(FastUnstake::queue(1).is_none());
+		assert_eq!(FastUnstake::counter_for_queue(), 0);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(
+			fast_unstake_events(),
+			vec![RawEvent::Registered(1), RawEvent::Deregistered(1)],
+		);
+
+		// the funds of the stash keep unbonding.
+		assert_eq!(StakingMock::active_stake(&1), Some(0));
+		assert_noop!(
+			FastUnstake::register_fast_unstake(Origin::signed(2)),
+			Error::<Test>::NotFullyBonded
+		);
+
+		// once the stash is being checked, it can neither leave nor register again.
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(4)));
+		run_blocks(1);
+		assert_eq!(FastUnstake::head().map(|head| head.stash), Some(3));
+		assert_noop!(FastUnstake::deregister(Origin::signed(4)), Error::<Test>::NotQueued);
+		assert_noop!(
+			FastUnstake::register_fast_unstake(Origin::signed(4)),
+			Error::<Test>::AlreadyHead
+		);
+	});
+}
+
+#[test]
+fn unexposed_stash_is_unstaked() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(2)));
+
+		// one era of the bonding duration is checked per block, from the current one.
+		run_blocks(1);
+		assert_eq!(FastUnstake::head().unwrap().checked, vec![3]);
+		assert!(FastUnstake::queue(1).is_none());
+		assert_eq!(FastUnstake::counter_for_queue(), 0);
+		run_blocks(3);
+		assert_eq!(FastUnstake::head().unwrap().checked, vec![3, 2, 1, 0]);
+		assert!(StakingMock::is_bonded(1));
+
+		// all the eras were checked.
+		run_blocks(1);
+		assert!(FastUnstake::head().is_none());
+		assert!(!StakingMock::is_bonded(1));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 100);
+		assert_eq!(
+			fast_unstake_events(),
+			vec![RawEvent::Registered(1), RawEvent::Unstaked(1, Ok(()))],
+		);
+
+		// nothing left to do.
+		run_blocks(1);
+		assert!(FastUnstake::head().is_none());
+	});
+}
+
+#[test]
+fn eras_to_check_per_block_is_respected() {
+	new_test_ext().execute_with(|| {
+		ErasToCheckPerBlock::set(3);
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(2)));
+
+		run_blocks(1);
+		assert_eq!(FastUnstake::head().unwrap().checked, vec![3, 2, 1]);
+		run_blocks(1);
+		assert_eq!(FastUnstake::head().unwrap().checked, vec![3, 2, 1, 0]);
+		run_blocks(1);
+		assert!(!StakingMock::is_bonded(1));
+
+		// zero disables the processing of the queue.
+		ErasToCheckPerBlock::set(0);
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(4)));
+		run_blocks(10);
+		assert!(FastUnstake::head().is_none());
+		assert_eq!(FastUnstake::queue(3), Some((4, 10)));
+	});
+}
+
+#[test]
+fn checks_fit_in_the_remaining_weight() {
+	new_test_ext().execute_with(|| {
+		ErasToCheckPerBlock::set(4);
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(2)));
+		let check_weight = <Test as Config>::WeightInfo::on_initialize_check(VALIDATOR_COUNT);
+		let unstake_weight = <Test as Config>::WeightInfo::on_initialize_unstake(VALIDATOR_COUNT);
+
+		// not even a single era can be checked.
+		assert!(run_block_with_weight(check_weight - 1) < check_weight);
+		assert!(FastUnstake::head().is_none());
+		assert_eq!(FastUnstake::queue(1), Some((2, 10)));
+
+		assert_eq!(run_block_with_weight(2 * check_weight + 1), 2 * check_weight);
+		assert_eq!(FastUnstake::head().unwrap().checked, vec![3, 2]);
+		assert_eq!(run_block_with_weight(5 * check_weight), 2 * check_weight);
+		assert_eq!(FastUnstake::head().unwrap().checked, vec![3, 2, 1, 0]);
+
+		// unstaking needs more weight than checking an era.
+		assert!(run_block_with_weight(unstake_weight - 1) < unstake_weight);
+		assert!(StakingMock::is_bonded(1));
+		assert_eq!(run_block_with_weight(unstake_weight), unstake_weight);
+		assert!(!StakingMock::is_bonded(1));
+	});
+}
+
+#[test]
+fn exposed_stash_is_slashed() {
+	new_test_ext().execute_with(|| {
+		StakingMock::expose(1, 1);
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(2)));
+
+		run_blocks(2);
+		assert_eq!(FastUnstake::head().unwrap().checked, vec![3, 2]);
+
+		// the stash was exposed in era 1.
+		run_blocks(1);
+		assert!(FastUnstake::head().is_none());
+		assert!(StakingMock::is_bonded(1));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 90);
+		assert_eq!(
+			fast_unstake_events(),
+			vec![RawEvent::Registered(1), RawEvent::Slashed(1, 10)],
+		);
+	});
+}
+
+#[test]
+fn nominating_again_forfeits_the_deposit() {
+	new_test_ext().execute_with(|| {
+		ErasToCheckPerBlock::set(4);
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(2)));
+		run_blocks(1);
+		assert_eq!(FastUnstake::head().unwrap().checked, vec![3, 2, 1, 0]);
+
+		// the stash nominates again before being unstaked.
+		assert_ok!(StakingMock::nominate(2, vec![100]));
+		run_blocks(1);
+		assert!(FastUnstake::head().is_none());
+		assert!(StakingMock::is_bonded(1));
+		assert_eq!(Balances::free_balance(2), 90);
+		assert_eq!(
+			fast_unstake_events(),
+			vec![RawEvent::Registered(1), RawEvent::Slashed(1, 10)],
+		);
+	});
+}
+
+#[test]
+fn exposure_in_current_era_is_checked_again() {
+	new_test_ext().execute_with(|| {
+		ErasToCheckPerBlock::set(4);
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(2)));
+		run_blocks(1);
+
+		// the stash is exposed in the current era after it was checked.
+		StakingMock::expose(1, 3);
+		run_blocks(1);
+		assert!(StakingMock::is_bonded(1));
+		assert_eq!(
+			fast_unstake_events(),
+			vec![RawEvent::Registered(1), RawEvent::Slashed(1, 10)],
+		);
+	});
+}
+
+#[test]
+fn new_eras_are_checked_too() {
+	new_test_ext().execute_with(|| {
+		ErasToCheckPerBlock::set(2);
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(2)));
+		run_blocks(1);
+		assert_eq!(FastUnstake::head().unwrap().checked, vec![3, 2]);
+
+		// era 2 is still in the bonding duration, but 1 and 0 are not anymore.
+		StakingMock::set_current_era(5);
+		StakingMock::expose(1, 0);
+		run_blocks(1);
+		assert_eq!(FastUnstake::head().unwrap().checked, vec![3, 2, 5, 4]);
+		run_blocks(1);
+		assert!(!StakingMock::is_bonded(1));
+		assert_eq!(Balances::free_balance(2), 100);
+	});
+}
+
+#[test]
+fn queue_is_processed_one_stash_at_a_time() {
+	new_test_ext().execute_with(|| {
+		ErasToCheckPerBlock::set(4);
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(2)));
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(4)));
+		assert_eq!(FastUnstake::counter_for_queue(), 2);
+
+		run_blocks(1);
+		assert_eq!(FastUnstake::counter_for_queue(), 1);
+		let first = FastUnstake::head().unwrap().stash;
+		run_blocks(1);
+		assert!(!StakingMock::is_bonded(first));
+		assert_eq!(FastUnstake::counter_for_queue(), 1);
+
+		run_blocks(2);
+		assert!(!StakingMock::is_bonded(1));
+		assert!(!StakingMock::is_bonded(3));
+		assert_eq!(FastUnstake::counter_for_queue(), 0);
+		assert!(Queue::<Test>::iter().next().is_none());
+		assert!(!Head::<Test>::exists());
+	});
+}
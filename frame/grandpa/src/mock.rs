@@ -133,6 +133,7 @@ impl pallet_session::Config for Test {
 impl pallet_session::historical::Config for Test {
 	type FullIdentification = pallet_staking::Exposure<u64, u128>;
 	type FullIdentificationOf = pallet_staking::ExposureOf<Self>;
+	type PruningDepth = ();
 }
 
 parameter_types! {
@@ -152,6 +153,9 @@ parameter_types! {
 
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type Balance = u128;
 	type DustRemoval = ();
 	type Event = TestEvent;
@@ -189,6 +193,7 @@ parameter_types! {
 	pub const AttestationPeriod: u64 = 100;
 	pub const RewardCurve: &'static PiecewiseLinear<'static> = &REWARD_CURVE;
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const VoterSnapshotWeightLimit: u64 = u64::max_value();
 	pub const ElectionLookahead: u64 = 0;
 	pub const StakingUnsignedPriority: u64 = u64::max_value() / 2;
 }
@@ -215,6 +220,10 @@ impl pallet_staking::Config for Test {
 	type MaxIterations = ();
 	type MinSolutionScoreBump = ();
 	type OffchainSolutionWeightLimit = ();
+	type OnStakerSlash = ();
+	type VoterList = pallet_staking::UseNominatorsMap<Self>;
+	type VoterSnapshotWeightLimit = VoterSnapshotWeightLimit;
+	type CommissionChangeDelay = ();
 	type WeightInfo = ();
 }
 
@@ -0,0 +1,41 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Runtime API definition for resolving the usernames of the identity pallet.
+//!
+//! This API should be imported and implemented by the runtime of a node whose wallets need to
+//! resolve usernames to accounts, and accounts to their primary usernames.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// The API to resolve usernames.
+	pub trait IdentityApi<AccountId> where
+		AccountId: Codec,
+	{
+		/// Returns the account holding `username`, if it has been accepted.
+		///
+		/// `username` is the full username, including the suffix of the authority which granted
+		/// it, e.g. `alice.chain`.
+		fn account_of_username(username: Vec<u8>) -> Option<AccountId>;
+
+		/// Returns the primary username of `who`, if it has any.
+		fn primary_username_of(who: AccountId) -> Option<Vec<u8>>;
+	}
+}
@@ -106,6 +106,26 @@ fn create_identity_info<T: Config>(num_fields: u32) -> IdentityInfo {
 	return info
 }
 
+// Adds a username authority with the longest suffix and has it grant a username made of `c`
+// characters to `who`, such that the full username is as long as possible.
+fn grant_username<T: Config>(who: &T::AccountId, c: u8) -> Result<Username, &'static str> {
+	let authority: T::AccountId = account("authority", 0, SEED);
+	let authority_lookup = T::Lookup::unlookup(authority.clone());
+	let suffix = vec![b'a'; T::MaxSuffixLength::get() as usize];
+	let root = RawOrigin::Root.into();
+	Identity::<T>::add_username_authority(root, authority_lookup, suffix.clone(), 10)?;
+
+	let name_len = T::MaxUsernameLength::get().saturating_sub(suffix.len() as u32 + 1).max(1);
+	let name = vec![c; name_len as usize];
+	let who_lookup = T::Lookup::unlookup(who.clone());
+	Identity::<T>::set_username_for(RawOrigin::Signed(authority).into(), who_lookup, name.clone())?;
+
+	let mut username = name;
+	username.push(b'.');
+	username.extend_from_slice(&suffix);
+	Ok(username)
+}
+
 benchmarks! {
 	// These are the common parameters along with their instancing.
 	_ {
@@ -143,7 +163,8 @@ benchmarks! {
 			// The target user
 			let caller: T::AccountId = whitelisted_caller();
 			let caller_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(caller.clone());
-			let caller_origin: <T as frame_system::Config>::Origin = RawOrigin::Signed(caller.clone()).into();
+			let caller_origin: <T as frame_system::Config>::Origin =
+			RawOrigin::Signed(caller.clone()).into();
 			let _ = T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
 
 			// Add an initial identity
@@ -402,6 +423,95 @@ benchmarks! {
 		ensure!(!SuperOf::<T>::contains_key(&caller), "Sub not removed");
 	}
 
+	add_username_authority {
+		let authority: T::AccountId = account("authority", 0, SEED);
+		let authority_lookup = T::Lookup::unlookup(authority.clone());
+		let suffix = vec![b'a'; T::MaxSuffixLength::get() as usize];
+	}: _(RawOrigin::Root, authority_lookup, suffix, 10)
+	verify {
+		assert_last_event::<T>(RawEvent::UsernameAuthorityAdded(authority).into());
+	}
+
+	remove_username_authority {
+		let authority: T::AccountId = account("authority", 0, SEED);
+		let authority_lookup = T::Lookup::unlookup(authority.clone());
+		let suffix = vec![b'a'; T::MaxSuffixLength::get() as usize];
+		Identity::<T>::add_username_authority(
+			RawOrigin::Root.into(), authority_lookup.clone(), suffix, 10,
+		)?;
+	}: _(RawOrigin::Root, authority_lookup)
+	verify {
+		assert_last_event::<T>(RawEvent::UsernameAuthorityRemoved(authority).into());
+	}
+
+	set_username_for {
+		let authority: T::AccountId = account("authority", 0, SEED);
+		let authority_lookup = T::Lookup::unlookup(authority.clone());
+		let suffix = vec![b'a'; T::MaxSuffixLength::get() as usize];
+		let root = RawOrigin::Root.into();
+		Identity::<T>::add_username_authority(root, authority_lookup, suffix, 10)?;
+		let name_len = T::MaxUsernameLength::get()
+			.saturating_sub(T::MaxSuffixLength::get() + 1)
+			.max(1);
+		let who: T::AccountId = account("target", 0, SEED);
+		let who_lookup = T::Lookup::unlookup(who.clone());
+	}: _(RawOrigin::Signed(authority), who_lookup, vec![b'b'; name_len as usize])
+	verify {
+		ensure!(PendingUsernames::<T>::iter().any(|(_, (a, _))| a == who), "Username not queued");
+	}
+
+	accept_username {
+		let caller: T::AccountId = whitelisted_caller();
+		let username = grant_username::<T>(&caller, b'b')?;
+	}: _(RawOrigin::Signed(caller.clone()), username.clone())
+	verify {
+		ensure!(AccountOfUsername::<T>::get(&username) == Some(caller), "Username not set");
+	}
+
+	reject_username {
+		let caller: T::AccountId = whitelisted_caller();
+		let username = grant_username::<T>(&caller, b'b')?;
+	}: _(RawOrigin::Signed(caller), username.clone())
+	verify {
+		assert_last_event::<T>(RawEvent::PendingUsernameRemoved(username).into());
+	}
+
+	remove_expired_approval {
+		let caller: T::AccountId = whitelisted_caller();
+		let who: T::AccountId = account("target", 0, SEED);
+		let username = grant_username::<T>(&who, b'b')?;
+		let (_, expiration) = PendingUsernames::<T>::get(&username).ok_or("Username not queued")?;
+		frame_system::Module::<T>::set_block_number(expiration + 1u32.into());
+	}: _(RawOrigin::Signed(caller), username.clone())
+	verify {
+		ensure!(!PendingUsernames::<T>::contains_key(&username), "Username not removed");
+	}
+
+	set_primary_username {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin: <T as frame_system::Config>::Origin =
+			RawOrigin::Signed(caller.clone()).into();
+		let first = grant_username::<T>(&caller, b'b')?;
+		Identity::<T>::accept_username(caller_origin.clone(), first)?;
+		let second = grant_username::<T>(&caller, b'c')?;
+		Identity::<T>::accept_username(caller_origin, second.clone())?;
+	}: _(RawOrigin::Signed(caller.clone()), second.clone())
+	verify {
+		ensure!(PrimaryUsernameOf::<T>::get(&caller) == Some(second), "Primary username not set");
+	}
+
+	remove_username {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_origin: <T as frame_system::Config>::Origin =
+			RawOrigin::Signed(caller.clone()).into();
+		let username = grant_username::<T>(&caller, b'b')?;
+		Identity::<T>::accept_username(caller_origin, username.clone())?;
+	}: _(RawOrigin::Signed(caller.clone()), username.clone())
+	verify {
+		ensure!(!AccountOfUsername::<T>::contains_key(&username), "Username not removed");
+		ensure!(!PrimaryUsernameOf::<T>::contains_key(&caller), "Primary username not removed");
+	}
+
 }
 
 #[cfg(test)]
@@ -429,6 +539,14 @@ mod tests {
 			assert_ok!(test_benchmark_rename_sub::<Test>());
 			assert_ok!(test_benchmark_remove_sub::<Test>());
 			assert_ok!(test_benchmark_quit_sub::<Test>());
+			assert_ok!(test_benchmark_add_username_authority::<Test>());
+			assert_ok!(test_benchmark_remove_username_authority::<Test>());
+			assert_ok!(test_benchmark_set_username_for::<Test>());
+			assert_ok!(test_benchmark_accept_username::<Test>());
+			assert_ok!(test_benchmark_reject_username::<Test>());
+			assert_ok!(test_benchmark_remove_expired_approval::<Test>());
+			assert_ok!(test_benchmark_set_primary_username::<Test>());
+			assert_ok!(test_benchmark_remove_username::<Test>());
 		});
 	}
 }
@@ -40,6 +40,12 @@
 //! The number of registrars should be limited, and the deposit made sufficiently large, to ensure
 //! no state-bloat attack is viable.
 //!
+//! Accounts may also hold globally unique usernames, such as `alice.chain`. Usernames are granted
+//! by username authorities, each of which is added from a specified origin together with the
+//! suffix it issues usernames under and the number of usernames it may still grant. A granted
+//! username is pending until the receiving account accepts it, and one of the usernames of an
+//! account is its primary username, to be used as its display name.
+//!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
@@ -63,9 +69,21 @@
 //! * `set_fields` - Set the fields that a registrar cares about in their judgements.
 //! * `provide_judgement` - Provide a judgement to an identity.
 //!
+//! #### For username authorities
+//! * `set_username_for` - Grant a username to an account, pending its acceptance.
+//!
+//! #### For general users with usernames
+//! * `accept_username` - Accept a username granted to the sender.
+//! * `reject_username` - Reject a username granted to the sender.
+//! * `set_primary_username` - Set which of the sender's usernames is its primary username.
+//! * `remove_username` - Give up one of the sender's usernames.
+//! * `remove_expired_approval` - Remove a granted username which was not accepted in time.
+//!
 //! #### For super-users
 //! * `add_registrar` - Add a new registrar to the system.
 //! * `kill_identity` - Forcibly remove the associated identity; the deposit is lost.
+//! * `add_username_authority` - Add or update a username authority.
+//! * `remove_username_authority` - Remove a username authority.
 //!
 //! [`Call`]: ./enum.Call.html
 //! [`Config`]: ./trait.Config.html
@@ -86,7 +104,11 @@ use sp_runtime::traits::{StaticLookup, Zero, AppendZerosInput, Saturating};
 use frame_support::{
 	decl_module, decl_event, decl_storage, ensure, decl_error,
 	dispatch::DispatchResultWithPostInfo,
-	traits::{Currency, ReservableCurrency, OnUnbalanced, Get, BalanceStatus, EnsureOrigin},
+	weights::Weight,
+	traits::{
+		Currency, NamedReservableCurrency, ReserveIdentifier, OnUnbalanced, Get, BalanceStatus,
+		EnsureOrigin,
+	},
 };
 use frame_system::ensure_signed;
 pub use weights::WeightInfo;
@@ -99,7 +121,7 @@ pub trait Config: frame_system::Config {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
 
 	/// The currency trait.
-	type Currency: ReservableCurrency<Self::AccountId>;
+	type Currency: NamedReservableCurrency<Self::AccountId, ReserveIdentifier = ReserveIdentifier>;
 
 	/// The amount held on deposit for a registered identity.
 	type BasicDeposit: Get<BalanceOf<Self>>;
@@ -132,6 +154,19 @@ pub trait Config: frame_system::Config {
 	/// The origin which may add or remove registrars. Root can always do this.
 	type RegistrarOrigin: EnsureOrigin<Self::Origin>;
 
+	/// The origin which may add or remove username authorities. Root can always do this.
+	type UsernameAuthorityOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The number of blocks for which a granted username may be accepted before anyone may
+	/// remove it.
+	type PendingUsernameExpiration: Get<Self::BlockNumber>;
+
+	/// The maximum length of the suffix of a username authority.
+	type MaxSuffixLength: Get<u32>;
+
+	/// The maximum length of a username, including its suffix and the separating `.`.
+	type MaxUsernameLength: Get<u32>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
@@ -398,6 +433,32 @@ pub struct RegistrarInfo<
 	pub fields: IdentityFields,
 }
 
+/// A username, formed as `<name>.<suffix>` of lowercase ASCII letters and digits.
+pub type Username = Vec<u8>;
+
+/// Information concerning a username authority.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct AuthorityProperties {
+	/// The suffix of the usernames granted by the authority.
+	pub suffix: Vec<u8>,
+
+	/// The number of usernames the authority may still grant.
+	pub allocation: u32,
+}
+
+/// The identifier of the reserve holding the deposits for identities, sub-accounts and judgement
+/// fees.
+pub const IDENTITY_ID: ReserveIdentifier = *b"identity";
+
+// A value placed in storage that represents the current version of the Identity storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+enum Releases {
+	V1,
+	V2,
+}
+
 decl_storage! {
 	trait Store for Module<T: Config> as Identity {
 		/// Information that is pertinent to identify the entity behind an account.
@@ -424,11 +485,39 @@ decl_storage! {
 		///
 		/// The index into this can be cast to `RegistrarIndex` to get a valid value.
 		pub Registrars get(fn registrars): Vec<Option<RegistrarInfo<BalanceOf<T>, T::AccountId>>>;
+
+		/// The accounts which may grant usernames, together with their properties.
+		pub UsernameAuthorities get(fn username_authority):
+			map hasher(twox_64_concat) T::AccountId => Option<AuthorityProperties>;
+
+		/// The account holding each accepted username.
+		pub AccountOfUsername get(fn account_of_username):
+			map hasher(blake2_128_concat) Username => Option<T::AccountId>;
+
+		/// The primary username of an account, to be used as its display name.
+		///
+		/// TWOX-NOTE: OK ― `AccountId` is a secure hash.
+		pub PrimaryUsernameOf get(fn primary_username_of):
+			map hasher(twox_64_concat) T::AccountId => Option<Username>;
+
+		/// Usernames which have been granted but not yet accepted, together with the account they
+		/// were granted to and the block after which they may be removed.
+		pub PendingUsernames get(fn pending_username):
+			map hasher(blake2_128_concat) Username => Option<(T::AccountId, T::BlockNumber)>;
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with last version.
+		StorageVersion build(|_| Some(Releases::V2)): Option<Releases>;
 	}
 }
 
 decl_event!(
-	pub enum Event<T> where AccountId = <T as frame_system::Config>::AccountId, Balance = BalanceOf<T> {
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Config>::AccountId,
+		Balance = BalanceOf<T>,
+		BlockNumber = <T as frame_system::Config>::BlockNumber,
+	{
 		/// A name was set or reset (which will remove all judgements). \[who\]
 		IdentitySet(AccountId),
 		/// A name was cleared, and the given balance returned. \[who, deposit\]
@@ -451,6 +540,20 @@ decl_event!(
 		/// A sub-identity was cleared, and the given deposit repatriated from the
 		/// main identity account to the sub-identity account. \[sub, main, deposit\]
 		SubIdentityRevoked(AccountId, AccountId, Balance),
+		/// A username authority was added or updated. \[authority\]
+		UsernameAuthorityAdded(AccountId),
+		/// A username authority was removed. \[authority\]
+		UsernameAuthorityRemoved(AccountId),
+		/// A username was granted and awaits acceptance. \[who, username, expiration\]
+		UsernameQueued(AccountId, Username, BlockNumber),
+		/// A username was accepted. \[who, username\]
+		UsernameSet(AccountId, Username),
+		/// A username was made the primary username of an account. \[who, username\]
+		PrimaryUsernameSet(AccountId, Username),
+		/// A granted username was rejected or expired. \[username\]
+		PendingUsernameRemoved(Username),
+		/// A username was given up by its holder. \[who, username\]
+		UsernameRemoved(AccountId, Username),
 	}
 );
 
@@ -488,7 +591,21 @@ decl_error! {
 		/// Sender is not a sub-account.
 		NotSub,
 		/// Sub-account isn't owned by sender.
-		NotOwned
+		NotOwned,
+		/// The sender is not a username authority.
+		NotUsernameAuthority,
+		/// The username authority has no allocation left.
+		NoAllocation,
+		/// The suffix is empty, too long or contains invalid characters.
+		InvalidSuffix,
+		/// The username is empty, too long or contains invalid characters.
+		InvalidUsername,
+		/// The username is already granted or taken.
+		UsernameTaken,
+		/// The username was not found for the sender.
+		NoUsername,
+		/// The granted username has not expired yet.
+		NotExpired,
 	}
 }
 
@@ -517,6 +634,20 @@ decl_module! {
 		/// of, e.g., updating judgements.
 		const MaxRegistrars: u32 = T::MaxRegistrars::get();
 
+		/// The number of blocks for which a granted username may be accepted before anyone may
+		/// remove it.
+		const PendingUsernameExpiration: T::BlockNumber = T::PendingUsernameExpiration::get();
+
+		/// The maximum length of the suffix of a username authority.
+		const MaxSuffixLength: u32 = T::MaxSuffixLength::get();
+
+		fn on_runtime_upgrade() -> Weight {
+			migration::migrate_to_named_reserves::<T>()
+		}
+
+		/// The maximum length of a username, including its suffix and the separating `.`.
+		const MaxUsernameLength: u32 = T::MaxUsernameLength::get();
+
 		type Error = Error<T>;
 
 		fn deposit_event() = default;
@@ -595,10 +726,11 @@ decl_module! {
 			let old_deposit = id.deposit;
 			id.deposit = T::BasicDeposit::get() + fd;
 			if id.deposit > old_deposit {
-				T::Currency::reserve(&sender, id.deposit - old_deposit)?;
+				T::Currency::reserve_named(&IDENTITY_ID, &sender, id.deposit - old_deposit)?;
 			}
 			if old_deposit > id.deposit {
-				let _ = T::Currency::unreserve(&sender, old_deposit - id.deposit);
+				let excess = old_deposit - id.deposit;
+				let _ = T::Currency::unreserve_named(&IDENTITY_ID, &sender, excess);
 			}
 
 			let judgements = id.judgements.len();
@@ -653,9 +785,10 @@ decl_module! {
 			ensure!(not_other_sub, Error::<T>::AlreadyClaimed);
 
 			if old_deposit < new_deposit {
-				T::Currency::reserve(&sender, new_deposit - old_deposit)?;
+				T::Currency::reserve_named(&IDENTITY_ID, &sender, new_deposit - old_deposit)?;
 			} else if old_deposit > new_deposit {
-				let _ = T::Currency::unreserve(&sender, old_deposit - new_deposit);
+				let excess = old_deposit - new_deposit;
+				let _ = T::Currency::unreserve_named(&IDENTITY_ID, &sender, excess);
 			}
 			// do nothing if they're equal.
 
@@ -713,7 +846,7 @@ decl_module! {
 				<SuperOf<T>>::remove(sub);
 			}
 
-			let _ = T::Currency::unreserve(&sender, deposit.clone());
+			let _ = T::Currency::unreserve_named(&IDENTITY_ID, &sender, deposit.clone());
 
 			Self::deposit_event(RawEvent::IdentityCleared(sender, deposit));
 
@@ -772,7 +905,7 @@ decl_module! {
 				Err(i) => id.judgements.insert(i, item),
 			}
 
-			T::Currency::reserve(&sender, registrar.fee)?;
+			T::Currency::reserve_named(&IDENTITY_ID, &sender, registrar.fee)?;
 
 			let judgements = id.judgements.len();
 			let extra_fields = id.info.additional.len();
@@ -819,7 +952,7 @@ decl_module! {
 				Err(Error::<T>::JudgementGiven)?
 			};
 
-			let _ = T::Currency::unreserve(&sender, fee);
+			let _ = T::Currency::unreserve_named(&IDENTITY_ID, &sender, fee);
 			let judgements = id.judgements.len();
 			let extra_fields = id.info.additional.len();
 			<IdentityOf<T>>::insert(&sender, id);
@@ -966,7 +1099,9 @@ decl_module! {
 			match id.judgements.binary_search_by_key(&reg_index, |x| x.0) {
 				Ok(position) => {
 					if let Judgement::FeePaid(fee) = id.judgements[position].1 {
-						let _ = T::Currency::repatriate_reserved(&target, &sender, fee, BalanceStatus::Free);
+						let _ = T::Currency::repatriate_reserved_named(
+							&IDENTITY_ID, &target, &sender, fee, BalanceStatus::Free,
+						);
 					}
 					id.judgements[position] = item
 				}
@@ -1021,7 +1156,8 @@ decl_module! {
 				<SuperOf<T>>::remove(sub);
 			}
 			// Slash their deposit from them.
-			T::Slashed::on_unbalanced(T::Currency::slash_reserved(&target, deposit).0);
+			let (imbalance, _) = T::Currency::slash_reserved_named(&IDENTITY_ID, &target, deposit);
+			T::Slashed::on_unbalanced(imbalance);
 
 			Self::deposit_event(RawEvent::IdentityKilled(target, deposit));
 
@@ -1052,7 +1188,7 @@ decl_module! {
 				// Ensure there is space and that the deposit is paid.
 				ensure!(sub_ids.len() < T::MaxSubAccounts::get() as usize, Error::<T>::TooManySubAccounts);
 				let deposit = T::SubAccountDeposit::get();
-				T::Currency::reserve(&sender, deposit)?;
+				T::Currency::reserve_named(&IDENTITY_ID, &sender, deposit)?;
 
 				SuperOf::<T>::insert(&sub, (sender.clone(), data));
 				sub_ids.push(sub.clone());
@@ -1095,7 +1231,7 @@ decl_module! {
 				sub_ids.retain(|x| x != &sub);
 				let deposit = T::SubAccountDeposit::get().min(*subs_deposit);
 				*subs_deposit -= deposit;
-				let _ = T::Currency::unreserve(&sender, deposit);
+				let _ = T::Currency::unreserve_named(&IDENTITY_ID, &sender, deposit);
 				Self::deposit_event(RawEvent::SubIdentityRemoved(sub, sender, deposit));
 			});
 		}
@@ -1118,10 +1254,207 @@ decl_module! {
 				sub_ids.retain(|x| x != &sender);
 				let deposit = T::SubAccountDeposit::get().min(*subs_deposit);
 				*subs_deposit -= deposit;
-				let _ = T::Currency::repatriate_reserved(&sup, &sender, deposit, BalanceStatus::Free);
+				let _ = T::Currency::repatriate_reserved_named(
+					&IDENTITY_ID, &sup, &sender, deposit, BalanceStatus::Free,
+				);
 				Self::deposit_event(RawEvent::SubIdentityRevoked(sender, sup.clone(), deposit));
 			});
 		}
+
+		/// Add a username authority, or update the suffix and allocation of an existing one.
+		///
+		/// The dispatch origin for this call must be `T::UsernameAuthorityOrigin`.
+		///
+		/// - `authority`: the account of the username authority.
+		/// - `suffix`: the suffix of the usernames granted by the authority, without the leading
+		///   `.`. Must be lowercase ASCII letters and digits, at most `MaxSuffixLength` long.
+		/// - `allocation`: the number of usernames the authority may grant.
+		///
+		/// Emits `UsernameAuthorityAdded` if successful.
+		#[weight = T::WeightInfo::add_username_authority()]
+		fn add_username_authority(origin,
+			authority: <T::Lookup as StaticLookup>::Source,
+			suffix: Vec<u8>,
+			allocation: u32,
+		) {
+			T::UsernameAuthorityOrigin::ensure_origin(origin)?;
+			let authority = T::Lookup::lookup(authority)?;
+			ensure!(
+				suffix.len() <= T::MaxSuffixLength::get() as usize && Self::is_valid_part(&suffix),
+				Error::<T>::InvalidSuffix,
+			);
+			let properties = AuthorityProperties { suffix, allocation };
+			UsernameAuthorities::<T>::insert(&authority, properties);
+			Self::deposit_event(RawEvent::UsernameAuthorityAdded(authority));
+		}
+
+		/// Remove a username authority. Usernames it has already granted are kept.
+		///
+		/// The dispatch origin for this call must be `T::UsernameAuthorityOrigin`.
+		///
+		/// - `authority`: the account of the username authority.
+		///
+		/// Emits `UsernameAuthorityRemoved` if successful.
+		#[weight = T::WeightInfo::remove_username_authority()]
+		fn remove_username_authority(origin, authority: <T::Lookup as StaticLookup>::Source) {
+			T::UsernameAuthorityOrigin::ensure_origin(origin)?;
+			let authority = T::Lookup::lookup(authority)?;
+			UsernameAuthorities::<T>::take(&authority).ok_or(Error::<T>::NotUsernameAuthority)?;
+			Self::deposit_event(RawEvent::UsernameAuthorityRemoved(authority));
+		}
+
+		/// Grant the username `<username>.<suffix>` to an account, using up one of the
+		/// authority's allocation. The account must accept it with `accept_username` before
+		/// `PendingUsernameExpiration` blocks have passed, after which anyone may remove it.
+		///
+		/// The dispatch origin for this call must be _Signed_ by a username authority.
+		///
+		/// - `who`: the account to grant the username to.
+		/// - `username`: the username, without the authority's suffix. Must be lowercase ASCII
+		///   letters and digits.
+		///
+		/// Emits `UsernameQueued` if successful.
+		#[weight = T::WeightInfo::set_username_for()]
+		fn set_username_for(origin,
+			who: <T::Lookup as StaticLookup>::Source,
+			username: Vec<u8>,
+		) {
+			let sender = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			let mut authority = UsernameAuthorities::<T>::get(&sender)
+				.ok_or(Error::<T>::NotUsernameAuthority)?;
+			ensure!(authority.allocation > 0, Error::<T>::NoAllocation);
+
+			ensure!(Self::is_valid_part(&username), Error::<T>::InvalidUsername);
+			let mut username = username;
+			username.push(b'.');
+			username.extend_from_slice(&authority.suffix);
+			ensure!(
+				username.len() <= T::MaxUsernameLength::get() as usize,
+				Error::<T>::InvalidUsername,
+			);
+			ensure!(
+				!AccountOfUsername::<T>::contains_key(&username)
+					&& !PendingUsernames::<T>::contains_key(&username),
+				Error::<T>::UsernameTaken,
+			);
+
+			authority.allocation -= 1;
+			UsernameAuthorities::<T>::insert(&sender, authority);
+
+			let expiration = frame_system::Module::<T>::block_number()
+				.saturating_add(T::PendingUsernameExpiration::get());
+			PendingUsernames::<T>::insert(&username, (who.clone(), expiration));
+			Self::deposit_event(RawEvent::UsernameQueued(who, username, expiration));
+		}
+
+		/// Accept a username granted to the sender. If the sender has no primary username yet,
+		/// it becomes its primary username.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the account the username was
+		/// granted to.
+		///
+		/// - `username`: the full username, including its suffix.
+		///
+		/// Emits `UsernameSet`, and `PrimaryUsernameSet` if it became the primary username.
+		#[weight = T::WeightInfo::accept_username()]
+		fn accept_username(origin, username: Username) {
+			let sender = ensure_signed(origin)?;
+			let (who, _) = PendingUsernames::<T>::get(&username).ok_or(Error::<T>::NoUsername)?;
+			ensure!(who == sender, Error::<T>::NoUsername);
+
+			PendingUsernames::<T>::remove(&username);
+			AccountOfUsername::<T>::insert(&username, &sender);
+			Self::deposit_event(RawEvent::UsernameSet(sender.clone(), username.clone()));
+
+			if !PrimaryUsernameOf::<T>::contains_key(&sender) {
+				PrimaryUsernameOf::<T>::insert(&sender, &username);
+				Self::deposit_event(RawEvent::PrimaryUsernameSet(sender, username));
+			}
+		}
+
+		/// Reject a username granted to the sender. The allocation used by the authority to grant
+		/// it is not returned.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the account the username was
+		/// granted to.
+		///
+		/// - `username`: the full username, including its suffix.
+		///
+		/// Emits `PendingUsernameRemoved` if successful.
+		#[weight = T::WeightInfo::reject_username()]
+		fn reject_username(origin, username: Username) {
+			let sender = ensure_signed(origin)?;
+			let (who, _) = PendingUsernames::<T>::get(&username).ok_or(Error::<T>::NoUsername)?;
+			ensure!(who == sender, Error::<T>::NoUsername);
+
+			PendingUsernames::<T>::remove(&username);
+			Self::deposit_event(RawEvent::PendingUsernameRemoved(username));
+		}
+
+		/// Remove a granted username which was not accepted before it expired, freeing it to be
+		/// granted again.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `username`: the full username, including its suffix.
+		///
+		/// Emits `PendingUsernameRemoved` if successful.
+		#[weight = T::WeightInfo::remove_expired_approval()]
+		fn remove_expired_approval(origin, username: Username) {
+			ensure_signed(origin)?;
+			let (_, expiration) = PendingUsernames::<T>::get(&username)
+				.ok_or(Error::<T>::NoUsername)?;
+			ensure!(
+				frame_system::Module::<T>::block_number() > expiration,
+				Error::<T>::NotExpired,
+			);
+
+			PendingUsernames::<T>::remove(&username);
+			Self::deposit_event(RawEvent::PendingUsernameRemoved(username));
+		}
+
+		/// Set one of the sender's usernames as its primary username.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the holder of the username.
+		///
+		/// - `username`: the full username, including its suffix.
+		///
+		/// Emits `PrimaryUsernameSet` if successful.
+		#[weight = T::WeightInfo::set_primary_username()]
+		fn set_primary_username(origin, username: Username) {
+			let sender = ensure_signed(origin)?;
+			ensure!(
+				AccountOfUsername::<T>::get(&username).as_ref() == Some(&sender),
+				Error::<T>::NoUsername,
+			);
+
+			PrimaryUsernameOf::<T>::insert(&sender, &username);
+			Self::deposit_event(RawEvent::PrimaryUsernameSet(sender, username));
+		}
+
+		/// Give up one of the sender's usernames, freeing it to be granted again. If it was the
+		/// primary username of the sender, the sender is left without a primary username.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the holder of the username.
+		///
+		/// - `username`: the full username, including its suffix.
+		///
+		/// Emits `UsernameRemoved` if successful.
+		#[weight = T::WeightInfo::remove_username()]
+		fn remove_username(origin, username: Username) {
+			let sender = ensure_signed(origin)?;
+			ensure!(
+				AccountOfUsername::<T>::get(&username).as_ref() == Some(&sender),
+				Error::<T>::NoUsername,
+			);
+
+			AccountOfUsername::<T>::remove(&username);
+			if PrimaryUsernameOf::<T>::get(&sender).as_ref() == Some(&username) {
+				PrimaryUsernameOf::<T>::remove(&sender);
+			}
+			Self::deposit_event(RawEvent::UsernameRemoved(sender, username));
+		}
 	}
 }
 
@@ -1133,5 +1466,39 @@ impl<T: Config> Module<T> {
 			.filter_map(|a| SuperOf::<T>::get(&a).map(|x| (a, x.1)))
 			.collect()
 	}
+
+	/// Check that a part of a username is non-empty and only contains lowercase ASCII letters
+	/// and digits.
+	fn is_valid_part(part: &[u8]) -> bool {
+		!part.is_empty() && part.iter().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+	}
 }
 
+/// Migration utilities for upgrading the Identity pallet between its different versions.
+pub mod migration {
+	use super::*;
+	use frame_support::storage::IterableStorageMap;
+
+	/// Migration code for named reserves.
+	///
+	/// Before this migration, the deposits for identities, sub-accounts and judgement fees were
+	/// reserved anonymously. This function moves the deposits recorded for every account into the
+	/// reserve named [`IDENTITY_ID`]. Does nothing if the storage is already at `Releases::V2`.
+	pub fn migrate_to_named_reserves<T: Config>() -> Weight {
+		if StorageVersion::get() >= Some(Releases::V2) {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut accounts: Weight = 0;
+		for (who, registration) in IdentityOf::<T>::iter() {
+			let _ = T::Currency::name_reserved(&IDENTITY_ID, &who, registration.total_deposit());
+			accounts += 1;
+		}
+		for (who, (deposit, _)) in SubsOf::<T>::iter() {
+			let _ = T::Currency::name_reserved(&IDENTITY_ID, &who, deposit);
+			accounts += 1;
+		}
+		StorageVersion::put(Releases::V2);
+		T::DbWeight::get().reads_writes(1 + 3 * accounts, 1 + 2 * accounts)
+	}
+}
@@ -22,7 +22,7 @@ use super::*;
 use sp_runtime::traits::BadOrigin;
 use frame_support::{
 	assert_ok, assert_noop, impl_outer_origin, parameter_types,
-	ord_parameter_types,
+	ord_parameter_types, traits::{MigrateAccount, ReservableCurrency},
 };
 use sp_core::H256;
 use frame_system::{EnsureSignedBy, EnsureOneOf, EnsureRoot};
@@ -66,6 +66,7 @@ impl frame_system::Config for Test {
 }
 parameter_types! {
 	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxReserves: u32 = 2;
 }
 impl pallet_balances::Config for Test {
 	type Balance = u64;
@@ -74,6 +75,9 @@ impl pallet_balances::Config for Test {
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
 	type MaxLocks = ();
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type WeightInfo = ();
 }
 parameter_types! {
@@ -83,6 +87,9 @@ parameter_types! {
 	pub const MaxSubAccounts: u32 = 2;
 	pub const MaxAdditionalFields: u32 = 2;
 	pub const MaxRegistrars: u32 = 20;
+	pub const PendingUsernameExpiration: u64 = 10;
+	pub const MaxSuffixLength: u32 = 7;
+	pub const MaxUsernameLength: u32 = 32;
 }
 ord_parameter_types! {
 	pub const One: u64 = 1;
@@ -110,6 +117,10 @@ impl Config for Test {
 	type MaxRegistrars = MaxRegistrars;
 	type RegistrarOrigin = EnsureOneOrRoot;
 	type ForceOrigin = EnsureTwoOrRoot;
+	type UsernameAuthorityOrigin = EnsureOneOrRoot;
+	type PendingUsernameExpiration = PendingUsernameExpiration;
+	type MaxSuffixLength = MaxSuffixLength;
+	type MaxUsernameLength = MaxUsernameLength;
 	type WeightInfo = ();
 }
 type System = frame_system::Module<Test>;
@@ -380,6 +391,44 @@ fn clearing_account_should_remove_subaccounts_and_refund() {
 	});
 }
 
+#[test]
+fn migrating_balances_leaves_the_identity_deposit_in_place() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+		assert_eq!(Balances::reserved_balance(10), 10);
+
+		<Balances as MigrateAccount<_>>::migrate_account(&10, &20);
+		assert_eq!(Balances::reserved_balance(10), 10);
+		assert_eq!(Balances::reserved_balance(20), 0);
+
+		// the deposit is still returned along with the identity it backs.
+		assert_ok!(Identity::clear_identity(Origin::signed(10)));
+		assert_eq!(Balances::free_balance(10), 100);
+		assert_eq!(Balances::reserved_balance(10), 0);
+	});
+}
+
+#[test]
+fn migrate_to_named_reserves_works() {
+	use frame_support::storage::StorageValue;
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+		assert_ok!(Identity::set_subs(Origin::signed(10), vec![(20, Data::Raw(vec![40; 1]))]));
+		assert_eq!(Balances::reserved_balance_named(&IDENTITY_ID, &10), 20);
+
+		// the deposits as they were held before named reserves were used.
+		let _ = Balances::unreserve_named(&IDENTITY_ID, &10, 20);
+		assert_ok!(Balances::reserve(&10, 20));
+		StorageVersion::kill();
+
+		migration::migrate_to_named_reserves::<Test>();
+		assert_eq!(Balances::reserved_balance_named(&IDENTITY_ID, &10), 20);
+		assert_eq!(Balances::reserved_balance(10), 20);
+		assert_eq!(StorageVersion::get(), Some(Releases::V2));
+	});
+}
+
 #[test]
 fn killing_account_should_remove_subaccounts_and_not_refund() {
 	new_test_ext().execute_with(|| {
@@ -465,3 +514,143 @@ fn setting_account_id_should_work() {
 		assert_ok!(Identity::set_account_id(Origin::signed(4), 0, 3));
 	});
 }
+
+#[test]
+fn username_authorities_can_be_managed() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Identity::add_username_authority(Origin::signed(3), 3, b"chain".to_vec(), 2),
+			BadOrigin,
+		);
+		assert_noop!(
+			Identity::add_username_authority(Origin::signed(1), 3, b"Chain".to_vec(), 2),
+			Error::<Test>::InvalidSuffix,
+		);
+		assert_noop!(
+			Identity::add_username_authority(Origin::signed(1), 3, vec![], 2),
+			Error::<Test>::InvalidSuffix,
+		);
+		assert_noop!(
+			Identity::add_username_authority(Origin::signed(1), 3, b"toolongx".to_vec(), 2),
+			Error::<Test>::InvalidSuffix,
+		);
+
+		assert_ok!(Identity::add_username_authority(Origin::signed(1), 3, b"chain".to_vec(), 2));
+		assert_eq!(
+			Identity::username_authority(3),
+			Some(AuthorityProperties { suffix: b"chain".to_vec(), allocation: 2 }),
+		);
+		assert_ok!(Identity::remove_username_authority(Origin::signed(1), 3));
+		assert_eq!(Identity::username_authority(3), None);
+		assert_noop!(
+			Identity::remove_username_authority(Origin::signed(1), 3),
+			Error::<Test>::NotUsernameAuthority,
+		);
+	});
+}
+
+#[test]
+fn usernames_can_be_granted_and_accepted() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Identity::add_username_authority(Origin::signed(1), 3, b"chain".to_vec(), 2));
+		assert_noop!(
+			Identity::set_username_for(Origin::signed(10), 10, b"alice".to_vec()),
+			Error::<Test>::NotUsernameAuthority,
+		);
+		for name in vec![b"Alice".to_vec(), b"al.ice".to_vec(), vec![], vec![b'a'; 27]] {
+			assert_noop!(
+				Identity::set_username_for(Origin::signed(3), 10, name),
+				Error::<Test>::InvalidUsername,
+			);
+		}
+
+		assert_ok!(Identity::set_username_for(Origin::signed(3), 10, b"alice".to_vec()));
+		let alice = b"alice.chain".to_vec();
+		assert_eq!(Identity::pending_username(&alice), Some((10, 10)));
+		assert_noop!(
+			Identity::set_username_for(Origin::signed(3), 20, b"alice".to_vec()),
+			Error::<Test>::UsernameTaken,
+		);
+		assert_noop!(
+			Identity::accept_username(Origin::signed(20), alice.clone()),
+			Error::<Test>::NoUsername,
+		);
+		assert_ok!(Identity::accept_username(Origin::signed(10), alice.clone()));
+		assert_eq!(Identity::pending_username(&alice), None);
+		assert_eq!(Identity::account_of_username(&alice), Some(10));
+		assert_eq!(Identity::primary_username_of(10), Some(alice.clone()));
+		assert_noop!(
+			Identity::set_username_for(Origin::signed(3), 20, b"alice".to_vec()),
+			Error::<Test>::UsernameTaken,
+		);
+
+		// The first username stays the primary one until another is chosen.
+		assert_ok!(Identity::set_username_for(Origin::signed(3), 10, b"bob".to_vec()));
+		let bob = b"bob.chain".to_vec();
+		assert_ok!(Identity::accept_username(Origin::signed(10), bob.clone()));
+		assert_eq!(Identity::primary_username_of(10), Some(alice.clone()));
+		assert_noop!(
+			Identity::set_primary_username(Origin::signed(20), bob.clone()),
+			Error::<Test>::NoUsername,
+		);
+		assert_ok!(Identity::set_primary_username(Origin::signed(10), bob.clone()));
+		assert_eq!(Identity::primary_username_of(10), Some(bob));
+
+		// The allocation of the authority is used up.
+		assert_noop!(
+			Identity::set_username_for(Origin::signed(3), 20, b"carol".to_vec()),
+			Error::<Test>::NoAllocation,
+		);
+	});
+}
+
+#[test]
+fn pending_usernames_can_be_rejected_or_expire() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Identity::add_username_authority(Origin::signed(1), 3, b"chain".to_vec(), 2));
+		let alice = b"alice.chain".to_vec();
+
+		assert_ok!(Identity::set_username_for(Origin::signed(3), 10, b"alice".to_vec()));
+		assert_noop!(
+			Identity::reject_username(Origin::signed(20), alice.clone()),
+			Error::<Test>::NoUsername,
+		);
+		assert_ok!(Identity::reject_username(Origin::signed(10), alice.clone()));
+		assert_eq!(Identity::pending_username(&alice), None);
+
+		assert_ok!(Identity::set_username_for(Origin::signed(3), 10, b"alice".to_vec()));
+		System::set_block_number(11);
+		assert_noop!(
+			Identity::remove_expired_approval(Origin::signed(20), alice.clone()),
+			Error::<Test>::NotExpired,
+		);
+		System::set_block_number(12);
+		assert_ok!(Identity::remove_expired_approval(Origin::signed(20), alice.clone()));
+		assert_noop!(
+			Identity::accept_username(Origin::signed(10), alice),
+			Error::<Test>::NoUsername,
+		);
+	});
+}
+
+#[test]
+fn usernames_can_be_removed() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Identity::add_username_authority(Origin::signed(1), 3, b"chain".to_vec(), 2));
+		let alice = b"alice.chain".to_vec();
+		assert_ok!(Identity::set_username_for(Origin::signed(3), 10, b"alice".to_vec()));
+		assert_ok!(Identity::accept_username(Origin::signed(10), alice.clone()));
+
+		assert_noop!(
+			Identity::remove_username(Origin::signed(20), alice.clone()),
+			Error::<Test>::NoUsername,
+		);
+		assert_ok!(Identity::remove_username(Origin::signed(10), alice.clone()));
+		assert_eq!(Identity::account_of_username(&alice), None);
+		assert_eq!(Identity::primary_username_of(10), None);
+
+		// The username is free to be granted again.
+		assert_ok!(Identity::set_username_for(Origin::signed(3), 20, b"alice".to_vec()));
+	});
+}
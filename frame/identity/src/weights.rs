@@ -59,6 +59,14 @@ pub trait WeightInfo {
 	fn rename_sub(_s: u32, ) -> Weight;
 	fn remove_sub(_s: u32, ) -> Weight;
 	fn quit_sub(_s: u32, ) -> Weight;
+	fn add_username_authority() -> Weight;
+	fn remove_username_authority() -> Weight;
+	fn set_username_for() -> Weight;
+	fn accept_username() -> Weight;
+	fn reject_username() -> Weight;
+	fn remove_expired_approval() -> Weight;
+	fn set_primary_username() -> Weight;
+	fn remove_username() -> Weight;
 	
 }
 
@@ -184,6 +192,53 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 			
 	}
+	fn add_username_authority() -> Weight {
+		(13_207_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			
+	}
+	fn remove_username_authority() -> Weight {
+		(15_482_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			
+	}
+	fn set_username_for() -> Weight {
+		(42_815_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			
+	}
+	fn accept_username() -> Weight {
+		(37_964_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			
+	}
+	fn reject_username() -> Weight {
+		(28_309_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			
+	}
+	fn remove_expired_approval() -> Weight {
+		(29_671_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			
+	}
+	fn set_primary_username() -> Weight {
+		(31_036_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			
+	}
+	fn remove_username() -> Weight {
+		(34_550_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			
+	}
 	
 }
 
@@ -308,5 +363,52 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 			
 	}
+	fn add_username_authority() -> Weight {
+		(13_207_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			
+	}
+	fn remove_username_authority() -> Weight {
+		(15_482_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			
+	}
+	fn set_username_for() -> Weight {
+		(42_815_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			
+	}
+	fn accept_username() -> Weight {
+		(37_964_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			
+	}
+	fn reject_username() -> Weight {
+		(28_309_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			
+	}
+	fn remove_expired_approval() -> Weight {
+		(29_671_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			
+	}
+	fn set_primary_username() -> Weight {
+		(31_036_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			
+	}
+	fn remove_username() -> Weight {
+		(34_550_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			
+	}
 	
 }
@@ -0,0 +1,52 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for querying the heartbeat stats of the im-online pallet.
+//!
+//! This API should be imported and implemented by the runtime of a node whose operators want to
+//! build uptime dashboards from the stats carried by the validators' heartbeats.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Encode, Decode};
+use sp_runtime::RuntimeDebug;
+use sp_staking::SessionIndex;
+use sp_std::vec::Vec;
+
+/// Stats about an authority's node, carried by its heartbeat.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct HeartbeatStats<BlockNumber> {
+	/// The last block authored by the authority, as recorded on-chain.
+	pub last_authored_block: Option<BlockNumber>,
+	/// The number of the best finalized block known to the authority's node.
+	pub finalized_block: BlockNumber,
+	/// The number of peers the authority's node is connected to.
+	#[codec(compact)]
+	pub peers: u32,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to query heartbeat stats.
+	pub trait ImOnlineApi<BlockNumber> where
+		BlockNumber: Codec,
+	{
+		/// Returns the stats carried by the heartbeats received in `session`, by authority index.
+		///
+		/// Stats are only kept for a limited number of past sessions.
+		fn heartbeat_stats(session: SessionIndex) -> Vec<(u32, HeartbeatStats<BlockNumber>)>;
+	}
+}
@@ -53,6 +53,7 @@ pub fn create_heartbeat<T: Config>(k: u32, e: u32) ->
 		session_index: 0,
 		authority_index: k-1,
 		validators_len: keys.len() as u32,
+		stats: Default::default(),
 	};
 
 	let encoded_heartbeat = input_heartbeat.encode();
@@ -30,6 +30,11 @@
 //! as the [NetworkState](../../client/offchain/struct.NetworkState.html).
 //! It is submitted as an Unsigned Transaction via off-chain workers.
 //!
+//! Heartbeats also carry [`HeartbeatStats`](./struct.HeartbeatStats.html) about the validator's
+//! node: the last block it authored, which is checked against the on-chain record, as well as the
+//! finalized height and peer count reported by the node. The stats are kept for
+//! `StatsHistoryDepth` sessions and can be queried through the `ImOnlineApi` runtime API.
+//!
 //! - [`im_online::Config`](./trait.Config.html)
 //! - [`Call`](./enum.Call.html)
 //! - [`Module`](./struct.Module.html)
@@ -39,6 +44,7 @@
 //! ### Public Functions
 //!
 //! - `is_online` - True if the validator sent a heartbeat in the current session.
+//! - `session_heartbeat_stats` - The stats carried by the heartbeats received in a session.
 //!
 //! ## Usage
 //!
@@ -77,6 +83,7 @@ pub mod weights;
 use sp_application_crypto::RuntimeAppPublic;
 use codec::{Encode, Decode};
 use sp_core::offchain::OpaqueNetworkState;
+use sp_offchain::NodeHealth;
 use sp_std::prelude::*;
 use sp_std::convert::TryInto;
 use pallet_session::historical::IdentificationTuple;
@@ -95,7 +102,7 @@ use sp_staking::{
 };
 use frame_support::{
 	decl_module, decl_event, decl_storage, Parameter, debug, decl_error,
-	traits::Get,
+	traits::Get, weights::Weight, IterableStorageDoubleMap, IterableStorageMap,
 };
 use frame_system::ensure_none;
 use frame_system::offchain::{
@@ -103,6 +110,7 @@ use frame_system::offchain::{
 	SubmitTransaction,
 };
 pub use weights::WeightInfo;
+pub use pallet_im_online_rpc_runtime_api::HeartbeatStats;
 
 pub mod sr25519 {
 	mod app_sr25519 {
@@ -225,6 +233,8 @@ pub struct Heartbeat<BlockNumber>
 	pub authority_index: AuthIndex,
 	/// The length of session validator set
 	pub validators_len: u32,
+	/// Stats about the authority's node.
+	pub stats: HeartbeatStats<BlockNumber>,
 }
 
 pub trait Config: SendTransactionTypes<Call<Self>> + pallet_session::historical::Config {
@@ -256,6 +266,9 @@ pub trait Config: SendTransactionTypes<Call<Self>> + pallet_session::historical:
 	/// multiple pallets send unsigned transactions.
 	type UnsignedPriority: Get<TransactionPriority>;
 
+	/// The number of ended sessions for which the stats carried by heartbeats are kept.
+	type StatsHistoryDepth: Get<SessionIndex>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
@@ -298,6 +311,16 @@ decl_storage! {
 		AuthoredBlocks get(fn authored_blocks):
 			double_map hasher(twox_64_concat) SessionIndex, hasher(twox_64_concat) T::ValidatorId
 			=> u32;
+
+		/// The block number of the last block authored by each validator.
+		LastAuthoredBlock get(fn last_authored_block):
+			map hasher(twox_64_concat) T::ValidatorId => Option<T::BlockNumber>;
+
+		/// For each session index, we keep a mapping of `AuthIndex` to the stats carried by
+		/// its heartbeat. These are kept for `StatsHistoryDepth` sessions after the session ends.
+		ReceivedStats get(fn received_stats):
+			double_map hasher(twox_64_concat) SessionIndex, hasher(twox_64_concat) AuthIndex
+			=> Option<HeartbeatStats<T::BlockNumber>>;
 	}
 	add_extra_genesis {
 		config(keys): Vec<T::AuthorityId>;
@@ -321,14 +344,20 @@ decl_module! {
 
 		fn deposit_event() = default;
 
+		/// The author of every block is noted in `LastAuthoredBlock` when the block is
+		/// initialized.
+		fn on_initialize(_n: T::BlockNumber) -> Weight {
+			T::DbWeight::get().writes(1)
+		}
+
 		/// # <weight>
 		/// - Complexity: `O(K + E)` where K is length of `Keys` (heartbeat.validators_len)
 		///   and E is length of `heartbeat.network_state.external_address`
 		///   - `O(K)`: decoding of length `K`
 		///   - `O(E)`: decoding/encoding of length `E`
 		/// - DbReads: pallet_session `Validators`, pallet_session `CurrentIndex`, `Keys`,
-		///   `ReceivedHeartbeats`
-		/// - DbWrites: `ReceivedHeartbeats`
+		///   `ReceivedHeartbeats`, `LastAuthoredBlock`
+		/// - DbWrites: `ReceivedHeartbeats`, `ReceivedStats`
 		/// # </weight>
 		// NOTE: the weight includes the cost of validate_unsigned as it is part of the cost to
 		// import block with such an extrinsic.
@@ -361,6 +390,11 @@ decl_module! {
 					&heartbeat.authority_index,
 					&network_state
 				);
+				<ReceivedStats<T>>::insert(
+					&current_session,
+					&heartbeat.authority_index,
+					&heartbeat.stats
+				);
 			} else if exists {
 				Err(Error::<T>::DuplicatedHeartbeat)?
 			} else {
@@ -399,6 +433,7 @@ type OffchainResult<T, A> = Result<A, OffchainErr<<T as frame_system::Config>::B
 /// well since they're a valid proof of being online.
 impl<T: Config + pallet_authorship::Config> pallet_authorship::EventHandler<T::ValidatorId, T::BlockNumber> for Module<T> {
 	fn note_author(author: T::ValidatorId) {
+		<LastAuthoredBlock<T>>::insert(&author, <frame_system::Module<T>>::block_number());
 		Self::note_authorship(author);
 	}
 
@@ -441,6 +476,39 @@ impl<T: Config> Module<T> {
 		<ReceivedHeartbeats>::contains_key(&current_session, &authority_index)
 	}
 
+	/// Returns the stats carried by the heartbeats received in `session`, by authority index.
+	pub fn session_heartbeat_stats(
+		session: SessionIndex,
+	) -> Vec<(AuthIndex, HeartbeatStats<T::BlockNumber>)> {
+		<ReceivedStats<T>>::iter_prefix(session).collect()
+	}
+
+	/// Returns the stats of the local node to be carried by the heartbeat of the validator at
+	/// `authority_index`.
+	///
+	/// The finalized height and peer count are the ones last stored by the node for the
+	/// offchain workers, or zero if the node doesn't provide them.
+	fn local_heartbeat_stats(authority_index: AuthIndex) -> HeartbeatStats<T::BlockNumber> {
+		let health = StorageValueRef::persistent(sp_offchain::NODE_HEALTH_KEY)
+			.get::<NodeHealth<T::BlockNumber>>()
+			.flatten()
+			.unwrap_or_default();
+
+		HeartbeatStats {
+			last_authored_block: Self::recorded_last_authored_block(authority_index),
+			finalized_block: health.finalized_number,
+			peers: health.peers,
+		}
+	}
+
+	/// Returns the on-chain record of the last block authored by the validator at
+	/// `authority_index`.
+	fn recorded_last_authored_block(authority_index: AuthIndex) -> Option<T::BlockNumber> {
+		<pallet_session::Module<T>>::validators()
+			.get(authority_index as usize)
+			.and_then(|validator| <LastAuthoredBlock<T>>::get(validator))
+	}
+
 	/// Note that the given authority has authored a block in the current session.
 	fn note_authorship(author: T::ValidatorId) {
 		let current_session = <pallet_session::Module<T>>::current_index();
@@ -493,6 +561,7 @@ impl<T: Config> Module<T> {
 				session_index,
 				authority_index,
 				validators_len,
+				stats: Self::local_heartbeat_stats(authority_index),
 			};
 
 			let signature = key.sign(&heartbeat_data.encode()).ok_or(OffchainErr::FailedSigning)?;
@@ -636,6 +705,16 @@ impl<T: Config> pallet_session::OneSessionHandler<T::AccountId> for Module<T> {
 
 		// Remember who the authorities are for the new session.
 		Keys::<T>::put(validators.map(|x| x.1).collect::<Vec<_>>());
+
+		// Forget the last authored block of the validators which left the set.
+		let current_validators = <pallet_session::Module<T>>::validators();
+		let departed = <LastAuthoredBlock<T>>::iter()
+			.map(|(validator, _)| validator)
+			.filter(|validator| !current_validators.contains(validator))
+			.collect::<Vec<_>>();
+		for validator in departed {
+			<LastAuthoredBlock<T>>::remove(validator);
+		}
 	}
 
 	fn on_before_session_ending() {
@@ -656,6 +735,11 @@ impl<T: Config> pallet_session::OneSessionHandler<T::AccountId> for Module<T> {
 		<ReceivedHeartbeats>::remove_prefix(&<pallet_session::Module<T>>::current_index());
 		<AuthoredBlocks<T>>::remove_prefix(&<pallet_session::Module<T>>::current_index());
 
+		// Heartbeat stats are kept around for a while, only remove the ones that are now too old.
+		if let Some(expired) = session_index.checked_sub(T::StatsHistoryDepth::get()) {
+			<ReceivedStats<T>>::remove_prefix(&expired);
+		}
+
 		if offenders.is_empty() {
 			Self::deposit_event(RawEvent::AllGood);
 		} else {
@@ -676,6 +760,9 @@ impl<T: Config> pallet_session::OneSessionHandler<T::AccountId> for Module<T> {
 
 /// Invalid transaction custom error. Returned when validators_len field in heartbeat is incorrect.
 const INVALID_VALIDATORS_LEN: u8 = 10;
+/// Invalid transaction custom error. Returned when the stats in heartbeat are inconsistent with
+/// the chain.
+const INVALID_STATS: u8 = 11;
 
 impl<T: Config> frame_support::unsigned::ValidateUnsigned for Module<T> {
 	type Call = Call<T>;
@@ -706,6 +793,16 @@ impl<T: Config> frame_support::unsigned::ValidateUnsigned for Module<T> {
 				None => return InvalidTransaction::BadProof.into(),
 			};
 
+			// the last authored block must match our records and the node can't have finalized
+			// a block it hasn't seen yet.
+			let stats = &heartbeat.stats;
+			let last_authored_block = Self::recorded_last_authored_block(heartbeat.authority_index);
+			if stats.last_authored_block != last_authored_block ||
+				stats.finalized_block > heartbeat.block_number
+			{
+				return InvalidTransaction::Custom(INVALID_STATS).into();
+			}
+
 			// check signature (this is expensive so we do it last).
 			let signature_valid = heartbeat.using_encoded(|encoded_heartbeat| {
 				authority_id.verify(&encoded_heartbeat, &signature)
@@ -157,6 +157,7 @@ impl pallet_session::Config for Runtime {
 impl pallet_session::historical::Config for Runtime {
 	type FullIdentification = u64;
 	type FullIdentificationOf = ConvertInto;
+	type PruningDepth = ();
 }
 
 parameter_types! {
@@ -172,6 +173,7 @@ impl pallet_authorship::Config for Runtime {
 
 parameter_types! {
 	pub const UnsignedPriority: u64 = 1 << 20;
+	pub const StatsHistoryDepth: u32 = 2;
 }
 
 impl Config for Runtime {
@@ -180,6 +182,7 @@ impl Config for Runtime {
 	type ReportUnresponsiveness = OffenceHandler;
 	type SessionDuration = Period;
 	type UnsignedPriority = UnsignedPriority;
+	type StatsHistoryDepth = StatsHistoryDepth;
 	type WeightInfo = ();
 }
 
@@ -24,10 +24,11 @@ use crate::mock::*;
 use sp_core::OpaquePeerId;
 use sp_core::offchain::{
 	OffchainExt,
+	StorageKind,
 	TransactionPoolExt,
 	testing::{TestOffchainExt, TestTransactionPoolExt},
 };
-use frame_support::{dispatch, assert_noop};
+use frame_support::{dispatch, assert_noop, assert_ok};
 use sp_runtime::{testing::UintAuthorityId, transaction_validity::TransactionValidityError};
 
 #[test]
@@ -113,6 +114,24 @@ fn heartbeat(
 	authority_index: u32,
 	id: UintAuthorityId,
 	validators: Vec<u64>,
+) -> dispatch::DispatchResult {
+	heartbeat_with_stats(
+		block_number,
+		session_index,
+		authority_index,
+		id,
+		validators,
+		Default::default(),
+	)
+}
+
+fn heartbeat_with_stats(
+	block_number: u64,
+	session_index: u32,
+	authority_index: u32,
+	id: UintAuthorityId,
+	validators: Vec<u64>,
+	stats: HeartbeatStats<u64>,
 ) -> dispatch::DispatchResult {
 	use frame_support::unsigned::ValidateUnsigned;
 
@@ -125,6 +144,7 @@ fn heartbeat(
 		session_index,
 		authority_index,
 		validators_len: validators.len() as u32,
+		stats,
 	};
 	let signature = id.sign(&heartbeat.encode()).unwrap();
 
@@ -132,6 +152,8 @@ fn heartbeat(
 		.map_err(|e| match e {
 			TransactionValidityError::Invalid(InvalidTransaction::Custom(INVALID_VALIDATORS_LEN)) =>
 				"invalid validators len",
+			TransactionValidityError::Invalid(InvalidTransaction::Custom(INVALID_STATS)) =>
+				"invalid stats",
 			e @ _ => <&'static str>::from(e),
 		})?;
 	ImOnline::heartbeat(
@@ -240,6 +262,7 @@ fn should_generate_heartbeats() {
 			session_index: 2,
 			authority_index: 2,
 			validators_len: 3,
+			stats: Default::default(),
 		});
 	});
 }
@@ -351,6 +374,145 @@ fn should_not_send_a_report_if_already_online() {
 			session_index: 2,
 			authority_index: 0,
 			validators_len: 3,
+			stats: Default::default(),
+		});
+	});
+}
+
+#[test]
+fn should_generate_heartbeats_with_node_stats() {
+	use frame_support::traits::OffchainWorker;
+	use pallet_authorship::EventHandler;
+
+	let mut ext = new_test_ext();
+	let (offchain, _state) = TestOffchainExt::new();
+	let (pool, state) = TestTransactionPoolExt::new();
+	ext.register_extension(OffchainExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	ext.execute_with(|| {
+		// given
+		let block = 1;
+		System::set_block_number(block);
+		UintAuthorityId::set_all_keys(vec![0, 1, 2]);
+		// buffer new validators
+		Session::rotate_session();
+		// validator 3 authors a block in the previous session
+		ImOnline::note_author(3);
+		// enact the change and buffer another one
+		VALIDATORS.with(|l| *l.borrow_mut() = Some(vec![1, 2, 3, 4, 5, 6]));
+		Session::rotate_session();
+
+		// the node provides its health to the offchain workers
+		let health = NodeHealth { finalized_number: 0u64, peers: 5 };
+		sp_io::offchain::local_storage_set(
+			StorageKind::PERSISTENT,
+			sp_offchain::NODE_HEALTH_KEY,
+			&health.encode(),
+		);
+
+		// when
+		ImOnline::offchain_worker(block);
+
+		// then
+		let transaction = state.write().transactions.pop().unwrap();
+		let ex: Extrinsic = Decode::decode(&mut &*transaction).unwrap();
+		let heartbeat = match ex.call {
+			crate::mock::Call::ImOnline(crate::Call::heartbeat(h, ..)) => h,
+			e => panic!("Unexpected call: {:?}", e),
+		};
+
+		assert_eq!(heartbeat.authority_index, 2);
+		assert_eq!(heartbeat.stats, HeartbeatStats {
+			last_authored_block: Some(block),
+			finalized_block: 0,
+			peers: 5,
 		});
 	});
 }
+
+#[test]
+fn heartbeat_with_inconsistent_stats_should_fail() {
+	new_test_ext().execute_with(|| {
+		advance_session();
+		// given
+		VALIDATORS.with(|l| *l.borrow_mut() = Some(vec![1, 2, 3, 4, 5, 6]));
+		// enact the change and buffer another one
+		advance_session();
+
+		assert_eq!(Session::current_index(), 2);
+		assert_eq!(Session::validators(), vec![1, 2, 3]);
+
+		// the validator hasn't authored any block.
+		let stats = HeartbeatStats { last_authored_block: Some(1), finalized_block: 0, peers: 3 };
+		assert_noop!(
+			heartbeat_with_stats(1, 2, 0, 1.into(), Session::validators(), stats),
+			"invalid stats",
+		);
+
+		// the node can't have finalized a block past the heartbeat.
+		let stats = HeartbeatStats { last_authored_block: None, finalized_block: 2, peers: 3 };
+		assert_noop!(
+			heartbeat_with_stats(1, 2, 0, 1.into(), Session::validators(), stats),
+			"invalid stats",
+		);
+
+		// when
+		let stats = HeartbeatStats { last_authored_block: None, finalized_block: 1, peers: 3 };
+		assert_ok!(heartbeat_with_stats(1, 2, 0, 1.into(), Session::validators(), stats.clone()));
+
+		// then
+		assert_eq!(ImOnline::received_stats(&2, &0), Some(stats));
+	});
+}
+
+#[test]
+fn should_keep_heartbeat_stats_for_history_depth() {
+	new_test_ext().execute_with(|| {
+		advance_session();
+
+		VALIDATORS.with(|l| *l.borrow_mut() = Some(vec![1, 2, 3]));
+		// enact the change and buffer another one
+		advance_session();
+
+		assert_eq!(Session::current_index(), 2);
+
+		let stats = HeartbeatStats { last_authored_block: None, finalized_block: 1, peers: 8 };
+		assert_ok!(heartbeat_with_stats(1, 2, 0, 1.into(), Session::validators(), stats.clone()));
+
+		// the stats outlive the heartbeat itself, for `StatsHistoryDepth` sessions.
+		advance_session();
+		assert!(ImOnline::received_heartbeats(&2, &0).is_none());
+		assert_eq!(ImOnline::session_heartbeat_stats(2), vec![(0, stats.clone())]);
+
+		advance_session();
+		assert_eq!(ImOnline::session_heartbeat_stats(2), vec![(0, stats)]);
+
+		advance_session();
+		assert!(ImOnline::session_heartbeat_stats(2).is_empty());
+		assert!(ImOnline::received_stats(&2, &0).is_none());
+	});
+}
+
+#[test]
+fn last_authored_block_of_departed_validators_is_removed() {
+	use pallet_authorship::EventHandler;
+
+	new_test_ext().execute_with(|| {
+		advance_session();
+		VALIDATORS.with(|l| *l.borrow_mut() = Some(vec![1, 2]));
+		// enact the change and buffer another one
+		advance_session();
+		assert_eq!(Session::validators(), vec![1, 2, 3]);
+
+		let block = System::block_number();
+		ImOnline::note_author(2);
+		ImOnline::note_author(3);
+
+		// validator 3 leaves the set.
+		advance_session();
+		assert_eq!(Session::validators(), vec![1, 2]);
+		assert_eq!(ImOnline::last_authored_block(2), Some(block));
+		assert_eq!(ImOnline::last_authored_block(3), None);
+	});
+}
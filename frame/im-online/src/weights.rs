@@ -54,8 +54,8 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		(114_379_000 as Weight)
 			.saturating_add((219_000 as Weight).saturating_mul(k as Weight))
 			.saturating_add((481_000 as Weight).saturating_mul(e as Weight))
-			.saturating_add(T::DbWeight::get().reads(4 as Weight))
-			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 			
 	}
 	
@@ -67,8 +67,8 @@ impl WeightInfo for () {
 		(114_379_000 as Weight)
 			.saturating_add((219_000 as Weight).saturating_mul(k as Weight))
 			.saturating_add((481_000 as Weight).saturating_mul(e as Weight))
-			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 			
 	}
 	
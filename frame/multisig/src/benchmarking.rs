@@ -47,6 +47,18 @@ fn setup_multi<T: Config>(s: u32, z: u32)
 	return Ok((signatories, call_data))
 }
 
+fn setup_managed<T: Config>(s: u32, threshold: u16)
+	-> Result<(T::AccountId, Vec<T::AccountId>), &'static str>
+{
+	let (signatories, _) = setup_multi::<T>(s, 0)?;
+	let creator: T::AccountId = account("creator", 0, SEED);
+	T::Currency::make_free_balance_be(&creator, BalanceOf::<T>::max_value());
+	let origin = RawOrigin::Signed(creator.clone()).into();
+	Multisig::<T>::create_managed(origin, signatories.clone(), threshold, 0)?;
+	let managed = Multisig::<T>::managed_account_id(&creator, 0, None);
+	Ok((managed, signatories))
+}
+
 benchmarks! {
 	_ { }
 
@@ -298,6 +310,117 @@ benchmarks! {
 		assert!(!Multisigs::<T>::contains_key(multi_account_id, call_hash));
 		assert!(!Calls::<T>::contains_key(call_hash));
 	}
+
+	create_managed {
+		// Signatories, need at least 2 people
+		let s in 2 .. T::MaxSignatories::get() as u32;
+		let (signatories, _) = setup_multi::<T>(s, 0)?;
+		let caller: T::AccountId = account("caller", 0, SEED);
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let managed = Multisig::<T>::managed_account_id(&caller, 0, None);
+	}: _(RawOrigin::Signed(caller), signatories, s as u16, 0)
+	verify {
+		assert!(Managed::<T>::contains_key(managed));
+	}
+
+	propose_managed {
+		// Transaction Length
+		let z in 0 .. 10_000;
+		let s = T::MaxSignatories::get() as u32;
+		let (managed, signatories) = setup_managed::<T>(s, s as u16)?;
+		let call: <T as Config>::Call = frame_system::Call::<T>::remark(vec![0; z as usize]).into();
+		let call_hash = call.using_encoded(blake2_256);
+		let caller = signatories[0].clone();
+		// Whitelist caller account from further DB operations.
+		let caller_key = frame_system::Account::<T>::hashed_key_for(&caller);
+		frame_benchmarking::benchmarking::add_to_whitelist(caller_key.into());
+	}: _(RawOrigin::Signed(caller), managed.clone(), Box::new(call))
+	verify {
+		assert!(Multisigs::<T>::contains_key(&managed, call_hash));
+		assert!(ManagedCalls::<T>::contains_key(&managed, call_hash));
+	}
+
+	approve_managed_approve {
+		// Signatories, need at least 3 people (so we don't complete the multisig)
+		let s in 3 .. T::MaxSignatories::get() as u32;
+		let (managed, signatories) = setup_managed::<T>(s, s as u16)?;
+		let call: <T as Config>::Call = frame_system::Call::<T>::remark(vec![0; 10_000]).into();
+		let call_hash = call.using_encoded(blake2_256);
+		let timepoint = Multisig::<T>::timepoint();
+		let o = RawOrigin::Signed(signatories[0].clone()).into();
+		Multisig::<T>::propose_managed(o, managed.clone(), Box::new(call))?;
+		let caller = signatories[1].clone();
+		// Whitelist caller account from further DB operations.
+		let caller_key = frame_system::Account::<T>::hashed_key_for(&caller);
+		frame_benchmarking::benchmarking::add_to_whitelist(caller_key.into());
+	}: approve_managed(RawOrigin::Signed(caller), managed.clone(), timepoint, call_hash, 0)
+	verify {
+		let multisig = Multisigs::<T>::get(&managed, call_hash).ok_or("multisig not created")?;
+		assert_eq!(multisig.approvals.len(), 2);
+	}
+
+	approve_managed_complete {
+		// Signatories, need at least 2 people
+		let s in 2 .. T::MaxSignatories::get() as u32;
+		let (managed, signatories) = setup_managed::<T>(s, s as u16)?;
+		let call: <T as Config>::Call = frame_system::Call::<T>::remark(vec![0; 10_000]).into();
+		let call_hash = call.using_encoded(blake2_256);
+		let timepoint = Multisig::<T>::timepoint();
+		let o = RawOrigin::Signed(signatories[0].clone()).into();
+		Multisig::<T>::propose_managed(o, managed.clone(), Box::new(call))?;
+		for i in 1 .. s - 1 {
+			let o = RawOrigin::Signed(signatories[i as usize].clone()).into();
+			Multisig::<T>::approve_managed(o, managed.clone(), timepoint, call_hash, 0)?;
+		}
+		let caller = signatories[s as usize - 1].clone();
+		// Whitelist caller account from further DB operations.
+		let caller_key = frame_system::Account::<T>::hashed_key_for(&caller);
+		frame_benchmarking::benchmarking::add_to_whitelist(caller_key.into());
+	}: approve_managed(
+		RawOrigin::Signed(caller),
+		managed.clone(),
+		timepoint,
+		call_hash,
+		Weight::max_value()
+	)
+	verify {
+		assert!(!Multisigs::<T>::contains_key(&managed, call_hash));
+		assert!(!ManagedCalls::<T>::contains_key(&managed, call_hash));
+	}
+
+	cancel_managed {
+		let s = T::MaxSignatories::get() as u32;
+		let (managed, signatories) = setup_managed::<T>(s, s as u16)?;
+		let call: <T as Config>::Call = frame_system::Call::<T>::remark(vec![0; 10_000]).into();
+		let call_hash = call.using_encoded(blake2_256);
+		let timepoint = Multisig::<T>::timepoint();
+		let caller = signatories[0].clone();
+		let o = RawOrigin::Signed(caller.clone()).into();
+		Multisig::<T>::propose_managed(o, managed.clone(), Box::new(call))?;
+		// Whitelist caller account from further DB operations.
+		let caller_key = frame_system::Account::<T>::hashed_key_for(&caller);
+		frame_benchmarking::benchmarking::add_to_whitelist(caller_key.into());
+	}: _(RawOrigin::Signed(caller), managed.clone(), timepoint, call_hash)
+	verify {
+		assert!(!Multisigs::<T>::contains_key(&managed, call_hash));
+		assert!(!ManagedCalls::<T>::contains_key(&managed, call_hash));
+	}
+
+	set_signatories {
+		// Signatories, need at least 2 people
+		let s in 2 .. T::MaxSignatories::get() as u32;
+		let (managed, _) = setup_managed::<T>(2, 2)?;
+		T::Currency::make_free_balance_be(&managed, BalanceOf::<T>::max_value());
+		let mut signatories: Vec<T::AccountId> = (0 .. s)
+			.map(|i| account("new_signatory", i, SEED))
+			.collect();
+		signatories.sort();
+	}: _(RawOrigin::Signed(managed.clone()), signatories.clone(), 2)
+	verify {
+		let m = Managed::<T>::get(&managed).ok_or("managed multisig not found")?;
+		assert_eq!(m.signatories, signatories);
+		assert_eq!(m.depositor, managed);
+	}
 }
 
 #[cfg(test)]
@@ -319,6 +442,12 @@ mod tests {
 			assert_ok!(test_benchmark_approve_as_multi_approve::<Test>());
 			assert_ok!(test_benchmark_approve_as_multi_complete::<Test>());
 			assert_ok!(test_benchmark_cancel_as_multi::<Test>());
+			assert_ok!(test_benchmark_create_managed::<Test>());
+			assert_ok!(test_benchmark_propose_managed::<Test>());
+			assert_ok!(test_benchmark_approve_managed_approve::<Test>());
+			assert_ok!(test_benchmark_approve_managed_complete::<Test>());
+			assert_ok!(test_benchmark_cancel_managed::<Test>());
+			assert_ok!(test_benchmark_set_signatories::<Test>());
 		});
 	}
 }
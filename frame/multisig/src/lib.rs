@@ -31,6 +31,11 @@
 //! operation. This is useful for multisig wallets where cryptographic threshold signatures are
 //! not available or desired.
 //!
+//! Managed multisigs keep their signatories and threshold in storage instead. Their account id is
+//! fixed when they are created, so the signatories and threshold can be changed by the managed
+//! multisig itself without moving any funds. Calls to be dispatched from a managed multisig are
+//! stored on chain by the first approver, and later approvals only refer to the stored call.
+//!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
@@ -39,6 +44,13 @@
 //!   number of signed origins.
 //! * `approve_as_multi` - Approve a call from a composite origin.
 //! * `cancel_as_multi` - Cancel a call from a composite origin.
+//! * `create_managed` - Create a managed multisig account whose signatories and threshold are
+//!   kept in storage.
+//! * `propose_managed` - Store a call on chain and approve it on behalf of a managed multisig.
+//! * `approve_managed` - Approve, and if possible dispatch, a call stored for a managed multisig.
+//! * `cancel_managed` - Cancel a call stored for a managed multisig.
+//! * `set_signatories` - Change the signatories and threshold of a managed multisig. Must be
+//!   dispatched by the managed multisig itself.
 //!
 //! [`Call`]: ./enum.Call.html
 //! [`Config`]: ./trait.Config.html
@@ -54,7 +66,8 @@ use sp_std::prelude::*;
 use codec::{Encode, Decode};
 use sp_io::hashing::blake2_256;
 use frame_support::{decl_module, decl_event, decl_error, decl_storage, Parameter, ensure, RuntimeDebug};
-use frame_support::{traits::{Get, ReservableCurrency, Currency},
+use frame_support::{traits::{Get, ReservableCurrency, Currency, BalanceStatus, MigrateAccount},
+	storage::IterableStorageDoubleMap,
 	weights::{Weight, GetDispatchInfo},
 	dispatch::{DispatchResultWithPostInfo, DispatchErrorWithPostInfo, PostDispatchInfo},
 };
@@ -122,6 +135,20 @@ pub struct Multisig<BlockNumber, Balance, AccountId> {
 	approvals: Vec<AccountId>,
 }
 
+/// A multisig account whose signatories and threshold are kept in storage, so that they can be
+/// changed without changing the account id.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug)]
+pub struct ManagedMultisig<Balance, AccountId> {
+	/// The accounts who may approve operations of the managed multisig. Always sorted.
+	pub signatories: Vec<AccountId>,
+	/// The number of approvals from `signatories` needed before an operation is dispatched.
+	pub threshold: u16,
+	/// The account holding the deposit for this record.
+	pub depositor: AccountId,
+	/// The amount held in reserve of the `depositor`.
+	pub deposit: Balance,
+}
+
 decl_storage! {
 	trait Store for Module<T: Config> as Multisig {
 		/// The set of open multisig operations.
@@ -130,6 +157,23 @@ decl_storage! {
 			=> Option<Multisig<T::BlockNumber, BalanceOf<T>, T::AccountId>>;
 
 		pub Calls: map hasher(identity) [u8; 32] => Option<(OpaqueCall, T::AccountId, BalanceOf<T>)>;
+
+		/// The signatories and threshold of each managed multisig account.
+		pub Managed get(fn managed): map hasher(twox_64_concat) T::AccountId
+			=> Option<ManagedMultisig<BalanceOf<T>, T::AccountId>>;
+
+		/// The managed multisig accounts each account is a signatory of.
+		pub ManagedMemberships: double_map
+			hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) T::AccountId => ();
+
+		/// The number of managed multisig accounts each account is a signatory of.
+		pub ManagedMembershipCount get(fn managed_membership_count):
+			map hasher(twox_64_concat) T::AccountId => u32;
+
+		/// The call data of the open operations of managed multisig accounts.
+		pub ManagedCalls: double_map
+			hasher(twox_64_concat) T::AccountId, hasher(identity) [u8; 32]
+			=> Option<OpaqueCall>;
 	}
 }
 
@@ -163,6 +207,14 @@ decl_error! {
 		WeightTooLow,
 		/// The data to be stored is already stored.
 		AlreadyStored,
+		/// The account is not a managed multisig.
+		NotManaged,
+		/// A managed multisig already exists for this account.
+		AlreadyManaged,
+		/// The sender is not a signatory of the managed multisig.
+		NotSignatory,
+		/// The stored call data could not be decoded.
+		InvalidCall,
 	}
 }
 
@@ -182,6 +234,10 @@ decl_event! {
 		MultisigExecuted(AccountId, Timepoint<BlockNumber>, AccountId, CallHash, DispatchResult),
 		/// A multisig operation has been cancelled. \[cancelling, timepoint, multisig, call_hash\]
 		MultisigCancelled(AccountId, Timepoint<BlockNumber>, AccountId, CallHash),
+		/// A managed multisig has been created. \[creator, managed, threshold\]
+		ManagedMultisigCreated(AccountId, AccountId, u16),
+		/// The signatories of a managed multisig have been changed. \[managed, threshold\]
+		SignatoriesChanged(AccountId, u16),
 	}
 }
 
@@ -440,6 +496,284 @@ decl_module! {
 			Self::deposit_event(RawEvent::MultisigCancelled(who, timepoint, id, call_hash));
 			Ok(())
 		}
+
+		/// Create a managed multisig account, whose signatories and threshold are kept in storage
+		/// and may later be changed through `set_signatories` without changing its account id.
+		///
+		/// Payment: `DepositBase` plus `DepositFactor` for each signatory is reserved from the
+		/// sender for as long as the managed multisig exists.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `signatories`: The accounts who can approve operations of the managed multisig.
+		/// Must be sorted, and the sender need not be amongst them.
+		/// - `threshold`: The number of approvals needed before an operation is dispatched.
+		/// - `index`: A disambiguation index, in case this is called multiple times in the same
+		/// transaction by the same sender.
+		///
+		/// The account id of the managed multisig is given by `managed_account_id`, and is
+		/// reported in the `ManagedMultisigCreated` event.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` is the number of signatories.
+		/// - One balance-reserve operation.
+		/// - DB Weight:
+		///     - Reads: Managed, [Caller Account]
+		///     - Writes: Managed, [Caller Account], S * ManagedMemberships,
+		///       S * ManagedMembershipCount
+		/// # </weight>
+		#[weight = T::WeightInfo::create_managed(signatories.len() as u32)]
+		fn create_managed(origin,
+			signatories: Vec<T::AccountId>,
+			threshold: u16,
+			index: u16,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_managed_signatories(&signatories, threshold)?;
+
+			let managed = Self::managed_account_id(&who, index, None);
+			ensure!(!Managed::<T>::contains_key(&managed), Error::<T>::AlreadyManaged);
+
+			let deposit = Self::managed_deposit(signatories.len());
+			T::Currency::reserve(&who, deposit)?;
+
+			for signatory in signatories.iter() {
+				Self::add_membership(signatory, &managed);
+			}
+			Managed::<T>::insert(&managed, ManagedMultisig {
+				signatories,
+				threshold,
+				depositor: who.clone(),
+				deposit,
+			});
+			Self::deposit_event(RawEvent::ManagedMultisigCreated(who, managed, threshold));
+			Ok(())
+		}
+
+		/// Store a call on chain to be dispatched from a managed multisig account, registering the
+		/// sender's approval of it.
+		///
+		/// Further approvals are given with `approve_managed`, using the timepoint of this
+		/// transaction, and only ever refer to the call stored here.
+		///
+		/// Payment: `DepositBase` plus `threshold` times `DepositFactor`, plus `DepositFactor` for
+		/// each 32 bytes of encoded call, is reserved from the sender. It is returned once the
+		/// call is dispatched or cancelled.
+		///
+		/// The dispatch origin for this call must be _Signed_ by a signatory of `managed`.
+		///
+		/// - `managed`: The managed multisig account.
+		/// - `call`: The call to be dispatched from `managed`.
+		///
+		/// # <weight>
+		/// - `O(Z)` where `Z` is the length of the encoded call.
+		/// - One balance-reserve operation.
+		/// - DB Weight:
+		///     - Reads: Managed, Multisig Storage, [Caller Account]
+		///     - Writes: Multisig Storage, ManagedCalls, [Caller Account]
+		/// # </weight>
+		#[weight = T::WeightInfo::propose_managed(call.using_encoded(|c| c.len() as u32))]
+		fn propose_managed(origin,
+			managed: T::AccountId,
+			call: Box<<T as Config>::Call>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let m = Managed::<T>::get(&managed).ok_or(Error::<T>::NotManaged)?;
+			ensure!(m.signatories.binary_search(&who).is_ok(), Error::<T>::NotSignatory);
+
+			let data = call.encode();
+			let call_hash = blake2_256(&data);
+			ensure!(!<Multisigs<T>>::contains_key(&managed, call_hash), Error::<T>::AlreadyStored);
+
+			let words = BalanceOf::<T>::from(((data.len() + 31) / 32) as u32);
+			let deposit = T::DepositBase::get()
+				+ T::DepositFactor::get() * (BalanceOf::<T>::from(m.threshold) + words);
+			T::Currency::reserve(&who, deposit)?;
+
+			<Multisigs<T>>::insert(&managed, call_hash, Multisig {
+				when: Self::timepoint(),
+				deposit,
+				depositor: who.clone(),
+				approvals: vec![who.clone()],
+			});
+			ManagedCalls::<T>::insert(&managed, call_hash, data);
+			Self::deposit_event(RawEvent::NewMultisig(who, managed, call_hash));
+			Ok(())
+		}
+
+		/// Approve a call stored for a managed multisig account, dispatching it if this brings
+		/// the approvals up to the current threshold.
+		///
+		/// Only approvals of accounts which are signatories at the time of dispatch are counted,
+		/// so approvals of signatories removed in the meantime no longer count.
+		///
+		/// The dispatch origin for this call must be _Signed_ by a signatory of `managed`.
+		///
+		/// - `managed`: The managed multisig account.
+		/// - `timepoint`: The timepoint (block number and transaction index) of the
+		/// `propose_managed` transaction.
+		/// - `call_hash`: The hash of the stored call.
+		/// - `max_weight`: The maximum weight of the stored call, should it be dispatched.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` is the number of signatories, bounded by `MaxSignatories`.
+		/// - Up to one balance-unreserve operation.
+		/// - One event.
+		/// - The weight of the stored call, bounded by `max_weight`.
+		/// - DB Weight:
+		///     - Reads: Managed, Multisig Storage, ManagedCalls
+		///     - Writes: Multisig Storage, ManagedCalls, Depositor Account
+		/// - Plus Call Weight
+		/// # </weight>
+		#[weight = {
+			let s = T::MaxSignatories::get() as u32;
+
+			T::WeightInfo::approve_managed_approve(s)
+			.max(T::WeightInfo::approve_managed_complete(s))
+			.saturating_add(*max_weight)
+		}]
+		fn approve_managed(origin,
+			managed: T::AccountId,
+			timepoint: Timepoint<T::BlockNumber>,
+			call_hash: [u8; 32],
+			max_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let m = Managed::<T>::get(&managed).ok_or(Error::<T>::NotManaged)?;
+			ensure!(m.signatories.binary_search(&who).is_ok(), Error::<T>::NotSignatory);
+			let s = m.signatories.len() as u32;
+
+			let mut op = <Multisigs<T>>::get(&managed, call_hash).ok_or(Error::<T>::NotFound)?;
+			ensure!(op.when == timepoint, Error::<T>::WrongTimepoint);
+
+			let maybe_pos = op.approvals.binary_search(&who).err();
+			let approvals = op.approvals.iter()
+				.filter(|a| m.signatories.binary_search(*a).is_ok())
+				.count() + maybe_pos.iter().count();
+
+			if approvals < m.threshold as usize {
+				let pos = maybe_pos.ok_or(Error::<T>::AlreadyApproved)?;
+				op.approvals.insert(pos, who.clone());
+				<Multisigs<T>>::insert(&managed, call_hash, op);
+				Self::deposit_event(RawEvent::MultisigApproval(who, timepoint, managed, call_hash));
+				return Ok(Some(T::WeightInfo::approve_managed_approve(s)).into())
+			}
+
+			let data = ManagedCalls::<T>::get(&managed, call_hash).ok_or(Error::<T>::NotFound)?;
+			let call = <T as Config>::Call::decode(&mut &data[..])
+				.map_err(|_| Error::<T>::InvalidCall)?;
+			ensure!(call.get_dispatch_info().weight <= max_weight, Error::<T>::WeightTooLow);
+
+			// Clean up storage before executing call to avoid an possibility of reentrancy attack.
+			<Multisigs<T>>::remove(&managed, call_hash);
+			ManagedCalls::<T>::remove(&managed, call_hash);
+			T::Currency::unreserve(&op.depositor, op.deposit);
+
+			let result = call.dispatch(RawOrigin::Signed(managed.clone()).into());
+			Self::deposit_event(RawEvent::MultisigExecuted(
+				who, timepoint, managed, call_hash, result.map(|_| ()).map_err(|e| e.error)
+			));
+			Ok(get_result_weight(result).map(|actual_weight|
+				T::WeightInfo::approve_managed_complete(s).saturating_add(actual_weight)
+			).into())
+		}
+
+		/// Cancel a call stored for a managed multisig account. The deposit reserved for it is
+		/// returned.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the account which proposed it.
+		///
+		/// - `managed`: The managed multisig account.
+		/// - `timepoint`: The timepoint (block number and transaction index) of the
+		/// `propose_managed` transaction.
+		/// - `call_hash`: The hash of the stored call.
+		///
+		/// # <weight>
+		/// - One balance-unreserve operation.
+		/// - One event.
+		/// - DB Weight:
+		///     - Reads: Multisig Storage, ManagedCalls, [Caller Account]
+		///     - Writes: Multisig Storage, ManagedCalls, [Caller Account]
+		/// # </weight>
+		#[weight = T::WeightInfo::cancel_managed()]
+		fn cancel_managed(origin,
+			managed: T::AccountId,
+			timepoint: Timepoint<T::BlockNumber>,
+			call_hash: [u8; 32],
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(ManagedCalls::<T>::contains_key(&managed, call_hash), Error::<T>::NotFound);
+			let op = <Multisigs<T>>::get(&managed, call_hash).ok_or(Error::<T>::NotFound)?;
+			ensure!(op.when == timepoint, Error::<T>::WrongTimepoint);
+			ensure!(op.depositor == who, Error::<T>::NotOwner);
+
+			T::Currency::unreserve(&op.depositor, op.deposit);
+			<Multisigs<T>>::remove(&managed, call_hash);
+			ManagedCalls::<T>::remove(&managed, call_hash);
+
+			Self::deposit_event(RawEvent::MultisigCancelled(who, timepoint, managed, call_hash));
+			Ok(())
+		}
+
+		/// Change the signatories and threshold of a managed multisig account.
+		///
+		/// Operations already underway are kept; only approvals of the new signatories count
+		/// towards the new threshold when they are next approved.
+		///
+		/// Payment: the deposit of the managed multisig becomes `DepositBase` plus
+		/// `DepositFactor` for each new signatory, held from the managed multisig account itself.
+		/// Any deposit previously held from its creator is returned.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the managed multisig account,
+		/// i.e. it must be dispatched through `approve_managed`.
+		///
+		/// - `signatories`: The new signatories. Must be sorted.
+		/// - `threshold`: The new number of approvals needed before an operation is dispatched.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` is the number of new signatories.
+		/// - Up to one balance-reserve and one balance-unreserve operation.
+		/// - DB Weight:
+		///     - Reads: Managed, [Caller Account], Depositor Account
+		///     - Writes: Managed, [Caller Account], Depositor Account, S * ManagedMemberships,
+		///       S * ManagedMembershipCount
+		/// # </weight>
+		#[weight = T::WeightInfo::set_signatories(signatories.len() as u32)]
+		fn set_signatories(origin,
+			signatories: Vec<T::AccountId>,
+			threshold: u16,
+		) -> DispatchResult {
+			let managed = ensure_signed(origin)?;
+			let m = Managed::<T>::get(&managed).ok_or(Error::<T>::NotManaged)?;
+			Self::ensure_managed_signatories(&signatories, threshold)?;
+
+			let deposit = Self::managed_deposit(signatories.len());
+			if m.depositor == managed {
+				if deposit > m.deposit {
+					T::Currency::reserve(&managed, deposit - m.deposit)?;
+				} else {
+					T::Currency::unreserve(&managed, m.deposit - deposit);
+				}
+			} else {
+				T::Currency::reserve(&managed, deposit)?;
+				T::Currency::unreserve(&m.depositor, m.deposit);
+			}
+
+			for signatory in m.signatories.iter() {
+				Self::remove_membership(signatory, &managed);
+			}
+			for signatory in signatories.iter() {
+				Self::add_membership(signatory, &managed);
+			}
+			Managed::<T>::insert(&managed, ManagedMultisig {
+				signatories,
+				threshold,
+				depositor: managed.clone(),
+				deposit,
+			});
+			Self::deposit_event(RawEvent::SignatoriesChanged(managed, threshold));
+			Ok(())
+		}
 	}
 }
 
@@ -453,6 +787,51 @@ impl<T: Config> Module<T> {
 		T::AccountId::decode(&mut &entropy[..]).unwrap_or_default()
 	}
 
+	/// Derive the account id of a managed multisig created by `who`.
+	///
+	/// - `who`: The account which called `create_managed`.
+	/// - `index`: The index given to `create_managed`.
+	/// - `maybe_when`: The timepoint of the `create_managed` transaction; the current one if
+	/// `None`.
+	pub fn managed_account_id(
+		who: &T::AccountId,
+		index: u16,
+		maybe_when: Option<Timepoint<T::BlockNumber>>,
+	) -> T::AccountId {
+		let when = maybe_when.unwrap_or_else(Self::timepoint);
+		let entropy = (b"modlpy/multimngd", who, when, index).using_encoded(blake2_256);
+		T::AccountId::decode(&mut &entropy[..]).unwrap_or_default()
+	}
+
+	/// Record `who` as a signatory of the managed multisig `managed`.
+	fn add_membership(who: &T::AccountId, managed: &T::AccountId) {
+		ManagedMemberships::<T>::insert(who, managed, ());
+		ManagedMembershipCount::<T>::mutate(who, |count| *count = count.saturating_add(1));
+	}
+
+	/// Forget that `who` is a signatory of the managed multisig `managed`.
+	fn remove_membership(who: &T::AccountId, managed: &T::AccountId) {
+		ManagedMemberships::<T>::remove(who, managed);
+		ManagedMembershipCount::<T>::mutate_exists(who, |count| {
+			*count = count.and_then(|c| c.checked_sub(1)).filter(|c| *c > 0);
+		});
+	}
+
+	/// The deposit held for a managed multisig with `signatories` signatories.
+	fn managed_deposit(signatories: usize) -> BalanceOf<T> {
+		T::DepositBase::get() + T::DepositFactor::get() * BalanceOf::<T>::from(signatories as u32)
+	}
+
+	/// Check that `signatories` is sorted, free of duplicates and can reach `threshold`.
+	fn ensure_managed_signatories(signatories: &[T::AccountId], threshold: u16) -> DispatchResult {
+		ensure!(threshold >= 2, Error::<T>::MinimumThreshold);
+		ensure!(signatories.len() >= threshold as usize, Error::<T>::TooFewSignatories);
+		let max_sigs = T::MaxSignatories::get() as usize;
+		ensure!(signatories.len() <= max_sigs, Error::<T>::TooManySignatories);
+		ensure!(signatories.windows(2).all(|w| w[0] < w[1]), Error::<T>::SignatoriesOutOfOrder);
+		Ok(())
+	}
+
 	fn operate(
 		who: T::AccountId,
 		threshold: u16,
@@ -662,6 +1041,49 @@ impl<T: Config> Module<T> {
 	}
 }
 
+/// Swaps an account for a new one amongst the signatories of the managed multisigs it belongs to,
+/// unless the new account is already a signatory of them. Deposits held from the old account for
+/// those managed multisigs are moved along. Approvals already given by the old account are not.
+impl<T: Config> MigrateAccount<T::AccountId> for Module<T> {
+	fn migration_weight(from: &T::AccountId) -> Weight {
+		let n = ManagedMembershipCount::<T>::get(from) as Weight;
+		T::DbWeight::get().reads(1)
+			.saturating_add(T::DbWeight::get().reads_writes(3, 4).saturating_mul(n))
+	}
+
+	fn migrate_account(from: &T::AccountId, to: &T::AccountId) -> Weight {
+		let weight = Self::migration_weight(from);
+		let memberships: Vec<_> = ManagedMemberships::<T>::iter_prefix(from)
+			.map(|(managed, _)| managed)
+			.collect();
+		for managed in memberships {
+			let mut m = match Managed::<T>::get(&managed) {
+				Some(m) => m,
+				None => continue,
+			};
+			m.signatories.retain(|s| s != from);
+			let pos = match m.signatories.binary_search(to) {
+				Ok(_) => continue,
+				Err(pos) => pos,
+			};
+			m.signatories.insert(pos, to.clone());
+			if &m.depositor == from {
+				let moved = T::Currency::repatriate_reserved(
+					from, to, m.deposit, BalanceStatus::Reserved,
+				);
+				if let Ok(remaining) = moved {
+					m.deposit -= remaining;
+					m.depositor = to.clone();
+				}
+			}
+			Managed::<T>::insert(&managed, m);
+			Self::remove_membership(from, &managed);
+			Self::add_membership(to, &managed);
+		}
+		weight
+	}
+}
+
 /// Return the weight of a dispatch call result as an `Option`.
 ///
 /// Will return the weight regardless of what the state of the result is.
@@ -86,6 +86,9 @@ parameter_types! {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type Balance = u64;
 	type Event = TestEvent;
 	type DustRemoval = ();
@@ -103,6 +106,8 @@ impl Filter<Call> for TestBaseCallFilter {
 	fn filter(c: &Call) -> bool {
 		match *c {
 			Call::Balances(_) => true,
+			// Needed for managed multisigs to change their own signatories
+			Call::Multisig(multisig::Call::set_signatories(..)) => true,
 			// Needed for benchmarking
 			Call::System(frame_system::Call::remark(_)) => true,
 			_ => false,
@@ -571,3 +576,213 @@ fn multisig_handles_no_preimage_after_all_approve() {
 		assert_eq!(Balances::free_balance(6), 15);
 	});
 }
+
+#[test]
+fn managed_multisig_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Multisig::create_managed(Origin::signed(1), vec![1, 2, 3], 2, 0));
+		let managed = Multisig::managed_account_id(&1, 0, None);
+		expect_event(RawEvent::ManagedMultisigCreated(1, managed, 2));
+		assert_eq!(Balances::reserved_balance(1), 4);
+		assert_ok!(Balances::transfer(Origin::signed(4), managed, 10));
+
+		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 5)));
+		let call_weight = call.get_dispatch_info().weight;
+		let hash = blake2_256(&call.encode());
+		assert_ok!(Multisig::propose_managed(Origin::signed(2), managed, call.clone()));
+		assert_eq!(Balances::reserved_balance(2), 4);
+		assert_noop!(
+			Multisig::propose_managed(Origin::signed(3), managed, call),
+			Error::<Test>::AlreadyStored,
+		);
+		assert_noop!(
+			Multisig::approve_managed(Origin::signed(2), managed, now(), hash, call_weight),
+			Error::<Test>::AlreadyApproved,
+		);
+		assert_noop!(
+			Multisig::approve_managed(Origin::signed(3), managed, now(), hash, 0),
+			Error::<Test>::WeightTooLow,
+		);
+
+		assert_ok!(Multisig::approve_managed(Origin::signed(3), managed, now(), hash, call_weight));
+		assert_eq!(Balances::free_balance(6), 5);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert!(!Multisigs::<Test>::contains_key(managed, hash));
+		assert!(!ManagedCalls::<Test>::contains_key(managed, hash));
+	});
+}
+
+#[test]
+fn managed_multisig_checks_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Multisig::create_managed(Origin::signed(1), vec![1, 2], 1, 0),
+			Error::<Test>::MinimumThreshold,
+		);
+		assert_noop!(
+			Multisig::create_managed(Origin::signed(1), vec![1], 2, 0),
+			Error::<Test>::TooFewSignatories,
+		);
+		assert_noop!(
+			Multisig::create_managed(Origin::signed(1), vec![1, 2, 3, 4], 2, 0),
+			Error::<Test>::TooManySignatories,
+		);
+		assert_noop!(
+			Multisig::create_managed(Origin::signed(1), vec![2, 1], 2, 0),
+			Error::<Test>::SignatoriesOutOfOrder,
+		);
+		assert_noop!(
+			Multisig::create_managed(Origin::signed(1), vec![1, 1], 2, 0),
+			Error::<Test>::SignatoriesOutOfOrder,
+		);
+
+		assert_ok!(Multisig::create_managed(Origin::signed(1), vec![1, 2, 3], 2, 0));
+		let managed = Multisig::managed_account_id(&1, 0, None);
+		assert_noop!(
+			Multisig::create_managed(Origin::signed(1), vec![1, 2, 3], 2, 0),
+			Error::<Test>::AlreadyManaged,
+		);
+
+		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 5)));
+		let hash = blake2_256(&call.encode());
+		assert_noop!(
+			Multisig::propose_managed(Origin::signed(1), 42, call.clone()),
+			Error::<Test>::NotManaged,
+		);
+		assert_noop!(
+			Multisig::propose_managed(Origin::signed(4), managed, call.clone()),
+			Error::<Test>::NotSignatory,
+		);
+		assert_noop!(
+			Multisig::approve_managed(Origin::signed(2), managed, now(), hash, 0),
+			Error::<Test>::NotFound,
+		);
+		assert_noop!(
+			Multisig::set_signatories(Origin::signed(1), vec![1, 2], 2),
+			Error::<Test>::NotManaged,
+		);
+	});
+}
+
+#[test]
+fn managed_signatories_can_be_changed() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Multisig::create_managed(Origin::signed(1), vec![1, 2, 3], 2, 0));
+		let managed = Multisig::managed_account_id(&1, 0, None);
+		assert_ok!(Balances::transfer(Origin::signed(4), managed, 10));
+
+		let call = Box::new(Call::Multisig(multisig::Call::set_signatories(vec![2, 3, 5], 3)));
+		let call_weight = call.get_dispatch_info().weight;
+		let hash = blake2_256(&call.encode());
+		assert_ok!(Multisig::propose_managed(Origin::signed(1), managed, call));
+		assert_ok!(Multisig::approve_managed(Origin::signed(2), managed, now(), hash, call_weight));
+
+		let m = Multisig::managed(managed).unwrap();
+		assert_eq!(m.signatories, vec![2, 3, 5]);
+		assert_eq!(m.threshold, 3);
+		assert_eq!(m.depositor, managed);
+		// The deposit has moved from the creator onto the managed account itself.
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(managed), 4);
+		assert!(System::events().iter().any(|r|
+			r.event == RawEvent::SignatoriesChanged(managed, 3).into()
+		));
+
+		// The account id is unchanged, but it is now governed by the new signatories.
+		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 5)));
+		let call_weight = call.get_dispatch_info().weight;
+		let hash = blake2_256(&call.encode());
+		assert_noop!(
+			Multisig::propose_managed(Origin::signed(1), managed, call.clone()),
+			Error::<Test>::NotSignatory,
+		);
+		assert_ok!(Multisig::propose_managed(Origin::signed(2), managed, call));
+		assert_ok!(Multisig::approve_managed(Origin::signed(3), managed, now(), hash, call_weight));
+		assert_eq!(Balances::free_balance(6), 0);
+		assert_ok!(Multisig::approve_managed(Origin::signed(5), managed, now(), hash, call_weight));
+		assert_eq!(Balances::free_balance(6), 5);
+	});
+}
+
+#[test]
+fn approvals_of_removed_signatories_do_not_count() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Multisig::create_managed(Origin::signed(1), vec![1, 2, 3], 2, 0));
+		let managed = Multisig::managed_account_id(&1, 0, None);
+		assert_ok!(Balances::transfer(Origin::signed(4), managed, 10));
+
+		let transfer = Box::new(Call::Balances(BalancesCall::transfer(6, 5)));
+		let transfer_weight = transfer.get_dispatch_info().weight;
+		let transfer_hash = blake2_256(&transfer.encode());
+		assert_ok!(Multisig::propose_managed(Origin::signed(1), managed, transfer));
+
+		let call = Box::new(Call::Multisig(multisig::Call::set_signatories(vec![2, 3, 4], 2)));
+		let call_weight = call.get_dispatch_info().weight;
+		let hash = blake2_256(&call.encode());
+		assert_ok!(Multisig::propose_managed(Origin::signed(2), managed, call));
+		assert_ok!(Multisig::approve_managed(Origin::signed(3), managed, now(), hash, call_weight));
+
+		// Only the approval of 2 counts; the one of 1 was made before it was removed.
+		assert_ok!(Multisig::approve_managed(
+			Origin::signed(2), managed, now(), transfer_hash, transfer_weight,
+		));
+		assert_eq!(Balances::free_balance(6), 0);
+		assert_ok!(Multisig::approve_managed(
+			Origin::signed(4), managed, now(), transfer_hash, transfer_weight,
+		));
+		assert_eq!(Balances::free_balance(6), 5);
+		// The deposit is still returned to the proposer.
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
+
+#[test]
+fn cancel_managed_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Multisig::create_managed(Origin::signed(1), vec![1, 2, 3], 2, 0));
+		let managed = Multisig::managed_account_id(&1, 0, None);
+
+		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 5)));
+		let hash = blake2_256(&call.encode());
+		assert_ok!(Multisig::propose_managed(Origin::signed(2), managed, call));
+		assert_eq!(Balances::reserved_balance(2), 4);
+		assert_noop!(
+			Multisig::cancel_managed(Origin::signed(3), managed, now(), hash),
+			Error::<Test>::NotOwner,
+		);
+		assert_ok!(Multisig::cancel_managed(Origin::signed(2), managed, now(), hash));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert!(!ManagedCalls::<Test>::contains_key(managed, hash));
+
+		// Operations of plain multisigs cannot be cancelled through here.
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 2, vec![2, 3], None, hash, 0));
+		assert_noop!(
+			Multisig::cancel_managed(Origin::signed(1), multi, now(), hash),
+			Error::<Test>::NotFound,
+		);
+	});
+}
+
+#[test]
+fn migrate_account_swaps_managed_signatories() {
+	use frame_support::traits::MigrateAccount;
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(Multisig::create_managed(Origin::signed(1), vec![1, 2, 3], 2, 0));
+		assert_ok!(Multisig::create_managed(Origin::signed(2), vec![1, 2, 4], 2, 0));
+		let first = Multisig::managed_account_id(&1, 0, None);
+		let second = Multisig::managed_account_id(&2, 0, None);
+		assert_eq!(Multisig::managed_membership_count(1), 2);
+
+		// 4 is a signatory of the second multisig already, so 1 stays a signatory of it.
+		<Multisig as MigrateAccount<_>>::migrate_account(&1, &4);
+		assert_eq!(Multisig::managed(first).unwrap().signatories, vec![2, 3, 4]);
+		assert_eq!(Multisig::managed(second).unwrap().signatories, vec![1, 2, 4]);
+		assert_eq!(Multisig::managed_membership_count(1), 1);
+		assert_eq!(Multisig::managed_membership_count(4), 2);
+		// The deposit for the first multisig has moved along.
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(4), 4);
+	});
+}
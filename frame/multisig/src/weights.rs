@@ -53,6 +53,12 @@ pub trait WeightInfo {
 	fn approve_as_multi_approve(s: u32, ) -> Weight;
 	fn approve_as_multi_complete(s: u32, ) -> Weight;
 	fn cancel_as_multi(s: u32, ) -> Weight;
+	fn create_managed(s: u32, ) -> Weight;
+	fn propose_managed(z: u32, ) -> Weight;
+	fn approve_managed_approve(s: u32, ) -> Weight;
+	fn approve_managed_complete(s: u32, ) -> Weight;
+	fn cancel_managed() -> Weight;
+	fn set_signatories(s: u32, ) -> Weight;
 	
 }
 
@@ -132,6 +138,49 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 			
 	}
+	fn create_managed(s: u32, ) -> Weight {
+		(48_212_000 as Weight)
+			.saturating_add((152_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+			
+	}
+	fn propose_managed(z: u32, ) -> Weight {
+		(79_406_000 as Weight)
+			.saturating_add((3_000 as Weight).saturating_mul(z as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			
+	}
+	fn approve_managed_approve(s: u32, ) -> Weight {
+		(47_830_000 as Weight)
+			.saturating_add((186_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			
+	}
+	fn approve_managed_complete(s: u32, ) -> Weight {
+		(121_574_000 as Weight)
+			.saturating_add((291_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			
+	}
+	fn cancel_managed() -> Weight {
+		(68_945_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			
+	}
+	fn set_signatories(s: u32, ) -> Weight {
+		(62_117_000 as Weight)
+			.saturating_add((158_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+			
+	}
 	
 }
 
@@ -210,5 +259,48 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 			
 	}
+	fn create_managed(s: u32, ) -> Weight {
+		(48_212_000 as Weight)
+			.saturating_add((152_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+			
+	}
+	fn propose_managed(z: u32, ) -> Weight {
+		(79_406_000 as Weight)
+			.saturating_add((3_000 as Weight).saturating_mul(z as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			
+	}
+	fn approve_managed_approve(s: u32, ) -> Weight {
+		(47_830_000 as Weight)
+			.saturating_add((186_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			
+	}
+	fn approve_managed_complete(s: u32, ) -> Weight {
+		(121_574_000 as Weight)
+			.saturating_add((291_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			
+	}
+	fn cancel_managed() -> Weight {
+		(68_945_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			
+	}
+	fn set_signatories(s: u32, ) -> Weight {
+		(62_117_000 as Weight)
+			.saturating_add((158_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+			
+	}
 	
 }
@@ -289,6 +289,9 @@ mod tests {
 	}
 	impl pallet_balances::Config for Test {
 		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 		type Balance = u64;
 		type Event = ();
 		type DustRemoval = ();
@@ -0,0 +1,208 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Nomination pools pallet benchmarking.
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, account, whitelisted_caller};
+use frame_system::RawOrigin;
+
+use crate::Module as Pools;
+
+const SEED: u32 = 0;
+const MAX_NOMINATIONS: u32 = 16;
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&who, Pools::<T>::min_create_bond() * 100u32.into());
+	who
+}
+
+/// Create a pool of a new depositor, which is also its root and nominator.
+fn create_pool<T: Config>() -> Result<(T::AccountId, PoolId), &'static str> {
+	let depositor = funded_account::<T>("depositor", 0);
+	Pools::<T>::create(
+		RawOrigin::Signed(depositor.clone()).into(),
+		Pools::<T>::min_create_bond(),
+		depositor.clone(),
+		depositor.clone(),
+	)?;
+	Ok((depositor, Pools::<T>::last_pool_id()))
+}
+
+/// Join the pool with a new member bonding twice `MinJoinBond`.
+fn join_pool<T: Config>(pool_id: PoolId) -> Result<T::AccountId, &'static str> {
+	let member = funded_account::<T>("member", 0);
+	Pools::<T>::join(
+		RawOrigin::Signed(member.clone()).into(),
+		T::MinJoinBond::get() * 2u32.into(),
+		pool_id,
+	)?;
+	Ok(member)
+}
+
+/// Pay rewards into the reward account of the pool.
+fn pay_rewards<T: Config>(pool_id: PoolId) {
+	let reward_account = Pools::<T>::reward_account(pool_id);
+	let balance = T::Currency::free_balance(&reward_account);
+	T::Currency::make_free_balance_be(&reward_account, balance + T::MinJoinBond::get());
+}
+
+benchmarks! {
+	_ { }
+
+	create {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, Pools::<T>::min_create_bond() * 10u32.into());
+		let amount = Pools::<T>::min_create_bond();
+	}: _(RawOrigin::Signed(caller.clone()), amount, caller.clone(), caller.clone())
+	verify {
+		let pool_id = Pools::<T>::last_pool_id();
+		assert_eq!(Pools::<T>::pool_member(&caller).map(|m| m.pool_id), Some(pool_id));
+		assert_eq!(T::Staking::active_stake(&Pools::<T>::bonded_account(pool_id)), Some(amount));
+	}
+
+	join {
+		let (_, pool_id) = create_pool::<T>()?;
+		pay_rewards::<T>(pool_id);
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, T::MinJoinBond::get() * 10u32.into());
+	}: _(RawOrigin::Signed(caller.clone()), T::MinJoinBond::get(), pool_id)
+	verify {
+		assert_eq!(Pools::<T>::pool_member(&caller).map(|m| m.pool_id), Some(pool_id));
+	}
+
+	bond_extra {
+		let (_, pool_id) = create_pool::<T>()?;
+		let member = join_pool::<T>(pool_id)?;
+		pay_rewards::<T>(pool_id);
+		let extra = BondExtra::FreeBalance(T::MinJoinBond::get());
+	}: _(RawOrigin::Signed(member.clone()), extra)
+	verify {
+		let expected = T::MinJoinBond::get() * 3u32.into();
+		assert_eq!(Pools::<T>::bonded_balance_of(&member), Some(expected));
+	}
+
+	claim_payout {
+		let (_, pool_id) = create_pool::<T>()?;
+		let member = join_pool::<T>(pool_id)?;
+		pay_rewards::<T>(pool_id);
+	}: _(RawOrigin::Signed(member.clone()))
+	verify {
+		assert_eq!(Pools::<T>::pending_rewards_of(&member), Some(Zero::zero()));
+	}
+
+	unbond {
+		let (_, pool_id) = create_pool::<T>()?;
+		let member = join_pool::<T>(pool_id)?;
+		pay_rewards::<T>(pool_id);
+		let points = Pools::<T>::pool_member(&member).ok_or("member not found")?.points;
+	}: _(RawOrigin::Signed(member.clone()), member.clone(), points)
+	verify {
+		assert_eq!(Pools::<T>::pool_member(&member).map(|m| m.points), Some(Zero::zero()));
+	}
+
+	// The worst case is the depositor leaving a pool being destroyed, which destroys the pool.
+	withdraw_unbonded {
+		let (depositor, pool_id) = create_pool::<T>()?;
+		Pools::<T>::set_state(
+			RawOrigin::Signed(depositor.clone()).into(),
+			pool_id,
+			PoolState::Destroying,
+		)?;
+		let points = Pools::<T>::pool_member(&depositor).ok_or("member not found")?.points;
+		Pools::<T>::unbond(
+			RawOrigin::Signed(depositor.clone()).into(),
+			depositor.clone(),
+			points,
+		)?;
+
+		// Move the unbonded funds to the current era, as if the bonding duration had passed.
+		let current_era = T::Staking::current_era();
+		let unbond_era = current_era.saturating_add(T::Staking::bonding_duration());
+		PoolMembers::<T>::mutate(&depositor, |member| if let Some(member) = member {
+			member.unbonding_eras = member.unbonding_eras.values()
+				.map(|points| (current_era, *points))
+				.collect();
+		});
+		SubPoolsStorage::<T>::mutate(pool_id, |sub_pools| if let Some(sub_pools) = sub_pools {
+			if let Some(unbond_pool) = sub_pools.with_era.remove(&unbond_era) {
+				sub_pools.with_era.insert(current_era, unbond_pool);
+			}
+		});
+		let bonded_account = Pools::<T>::bonded_account(pool_id);
+		let balance = T::Currency::free_balance(&bonded_account);
+		T::Currency::make_free_balance_be(&bonded_account, balance * 2u32.into());
+	}: _(RawOrigin::Signed(depositor.clone()), depositor.clone())
+	verify {
+		assert!(Pools::<T>::bonded_pool(pool_id).is_none());
+		assert!(Pools::<T>::pool_member(&depositor).is_none());
+	}
+
+	nominate {
+		let n in 1 .. MAX_NOMINATIONS;
+		let (depositor, pool_id) = create_pool::<T>()?;
+		let validators: Vec<T::AccountId> =
+			(0..n).map(|i| account("validator", i, SEED)).collect();
+	}: _(RawOrigin::Signed(depositor), pool_id, validators)
+
+	chill {
+		let (depositor, pool_id) = create_pool::<T>()?;
+		let validators: Vec<T::AccountId> = vec![account("validator", 0, SEED)];
+		Pools::<T>::nominate(RawOrigin::Signed(depositor.clone()).into(), pool_id, validators)?;
+	}: _(RawOrigin::Signed(depositor), pool_id)
+
+	set_state {
+		let (depositor, pool_id) = create_pool::<T>()?;
+	}: _(RawOrigin::Signed(depositor), pool_id, PoolState::Blocked)
+	verify {
+		assert_eq!(Pools::<T>::bonded_pool(pool_id).map(|p| p.state), Some(PoolState::Blocked));
+	}
+
+	update_roles {
+		let (depositor, pool_id) = create_pool::<T>()?;
+		let root: T::AccountId = account("root", 0, SEED);
+		let nominator: T::AccountId = account("nominator", 0, SEED);
+	}: _(RawOrigin::Signed(depositor), pool_id, root.clone(), nominator)
+	verify {
+		assert_eq!(Pools::<T>::bonded_pool(pool_id).map(|p| p.roles.root), Some(root));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_create::<Test>());
+			assert_ok!(test_benchmark_join::<Test>());
+			assert_ok!(test_benchmark_bond_extra::<Test>());
+			assert_ok!(test_benchmark_claim_payout::<Test>());
+			assert_ok!(test_benchmark_unbond::<Test>());
+			assert_ok!(test_benchmark_withdraw_unbonded::<Test>());
+			assert_ok!(test_benchmark_nominate::<Test>());
+			assert_ok!(test_benchmark_chill::<Test>());
+			assert_ok!(test_benchmark_set_state::<Test>());
+			assert_ok!(test_benchmark_update_roles::<Test>());
+		});
+	}
+}
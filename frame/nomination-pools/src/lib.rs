@@ -0,0 +1,1080 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Nomination Pools Pallet
+//!
+//! - [`nomination_pools::Config`](./trait.Config.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! A pallet which allows members to delegate their stake to nominating pools. A nominating pool
+//! acts like a single nominator in the staking system, so members can take part in staking with
+//! any amount above `MinJoinBond`, regardless of the minimum bond of the staking system and of
+//! the number of nominators rewarded per validator.
+//!
+//! ### Terminology
+//!
+//! * Pool: A bonded account which stakes and nominates on behalf of its members, together with
+//!   a reward account which receives the staking rewards of the pool.
+//! * Member: An account which has bonded funds into a pool. An account can be a member of only
+//!   one pool at a time.
+//! * Points: The share of a member in a pool. Points are issued when funds are bonded into a
+//!   pool, and their value follows the bonded balance of the pool, so that slashes are shared
+//!   pro-rata by all members.
+//! * Reward counter: The amount of rewards which has been received by the pool per point since
+//!   its creation. The pending rewards of a member are the points of the member times the
+//!   increase of the reward counter since the member last claimed.
+//! * Unbonding pool: The funds unbonded by members in the same era are tracked together until
+//!   they can be withdrawn, so that slashes of unlocking funds are shared pro-rata too.
+//!
+//! ### Roles
+//!
+//! * Depositor: Creates the pool with the initial bond. It can only fully unbond once the pool
+//!   is being destroyed and it is the last member.
+//! * Root: Can change the roles and the state of the pool, and kick members out of a blocked
+//!   pool.
+//! * Nominator: Can nominate and chill on behalf of the pool.
+//!
+//! The depositor can change the state of the pool too.
+//!
+//! ### Pool Life Cycle
+//!
+//! 1. A depositor calls `create` to bond at least `MinCreateBond` into a new pool, which is
+//!    `Open`.
+//! 2. The nominator of the pool calls `nominate` to choose the validators backed by the pool.
+//! 3. Accounts call `join` to become members of an `Open` pool, and `bond_extra` to bond more.
+//! 4. The rewards of the pool are paid into its reward account. Members call `claim_payout`
+//!    to receive their share of them.
+//! 5. Members call `unbond` to schedule their funds to be unlocked, and once the bonding
+//!    duration of the staking system is over, `withdraw_unbonded` to receive them. A member
+//!    which has neither points nor unbonding funds left is removed from the pool.
+//! 6. The root or the depositor can set the pool to `Blocked`, so that no one can join it and
+//!    the root can kick members out, or to `Destroying`, so that anyone can unbond and withdraw
+//!    the funds of any member. Once the depositor, as the last member, has withdrawn its funds,
+//!    the pool is destroyed.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! #### For General Users
+//!
+//! * `create` - Create a new pool.
+//! * `join` - Join a pool by bonding funds into it.
+//!
+//! #### For Members
+//!
+//! * `bond_extra` - Bond more funds, either from the free balance or from pending rewards.
+//! * `claim_payout` - Claim the pending rewards.
+//! * `unbond` - Unbond points, or those of another member if the state of the pool allows it.
+//! * `withdraw_unbonded` - Withdraw unlocked funds, or those of another member if the pool is
+//!   being destroyed.
+//!
+//! #### For Pool Roles
+//!
+//! * `nominate` - Nominate validators on behalf of a pool.
+//! * `chill` - Stop nominating on behalf of a pool.
+//! * `set_state` - Set the state of a pool.
+//! * `update_roles` - Update the root and nominator of a pool.
+//!
+//! ### Staking Integration
+//!
+//! The pallet stakes through [`sp_staking::StakingInterface`], and should be registered as the
+//! [`sp_staking::OnStakerSlash`] handler of the staking system, so that slashes of unlocking
+//! funds are applied to the unbonding pools.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::{prelude::*, collections::btree_map::BTreeMap};
+use codec::{Encode, Decode};
+use sp_runtime::{
+	ModuleId, RuntimeDebug, DispatchError, FixedU128, FixedPointNumber,
+	helpers_128bit::multiply_by_rational,
+	traits::{
+		AccountIdConversion, Bounded, Zero, Saturating, SaturatedConversion, StaticLookup,
+	},
+};
+use frame_support::{
+	decl_module, decl_event, decl_storage, decl_error, ensure, transactional,
+	traits::{Currency, ExistenceRequirement, Get},
+};
+use frame_system::ensure_signed;
+use sp_staking::{EraIndex, OnStakerSlash, StakingInterface};
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Identifier of a pool.
+pub type PoolId = u32;
+
+/// Configuration trait.
+pub trait Config: frame_system::Config {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+	/// The currency in which members bond and are rewarded.
+	type Currency: Currency<Self::AccountId>;
+
+	/// The staking system through which the pools stake.
+	type Staking: StakingInterface<Balance = BalanceOf<Self>, AccountId = Self::AccountId>;
+
+	/// The pallet's id, used for deriving the bonded and reward accounts of the pools.
+	type ModuleId: Get<ModuleId>;
+
+	/// The minimum amount which has to be bonded to join a pool.
+	type MinJoinBond: Get<BalanceOf<Self>>;
+
+	/// The minimum amount which has to be bonded to create a pool.
+	///
+	/// The depositor must keep at least this much bonded until the pool is being destroyed.
+	type MinCreateBond: Get<BalanceOf<Self>>;
+
+	/// The maximum number of eras in which a member can have funds unbonding at the same time.
+	type MaxUnbonding: Get<u32>;
+
+	/// The number of eras after the bonding duration of the staking system after which the
+	/// unbonding pool of an era is merged into the unbonding pool without era.
+	type PostUnbondingPoolsWindow: Get<u32>;
+
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+/// The kind of the accounts of a pool, used to derive them.
+///
+/// Encoded as a single byte, so that the accounts of pools with different ids differ even for
+/// short account ids.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+enum AccountType {
+	Bonded,
+	Reward,
+}
+
+/// The state of a pool.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum PoolState {
+	/// The pool is open to be joined.
+	Open,
+	/// The pool cannot be joined, and the root can kick its members out.
+	Blocked,
+	/// The pool is being destroyed: anyone can unbond and withdraw the funds of its members.
+	Destroying,
+}
+
+/// The accounts which manage a pool.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct PoolRoles<AccountId> {
+	/// The account which created the pool and placed the initial bond.
+	pub depositor: AccountId,
+	/// The account which can change the roles and the state of the pool.
+	pub root: AccountId,
+	/// The account which can nominate on behalf of the pool.
+	pub nominator: AccountId,
+}
+
+/// A bonded pool.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct BondedPool<AccountId, Balance> {
+	/// The total points of the members of the pool.
+	pub points: Balance,
+	/// The state of the pool.
+	pub state: PoolState,
+	/// The number of members of the pool, including those which are only unbonding.
+	pub member_counter: u32,
+	/// The accounts which manage the pool.
+	pub roles: PoolRoles<AccountId>,
+}
+
+/// The rewards of a pool.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct RewardPool<Balance> {
+	/// The reward counter of the pool when it was last recorded.
+	pub last_recorded_reward_counter: FixedU128,
+	/// The total rewards the pool had received when the reward counter was last recorded.
+	pub last_recorded_total_payouts: Balance,
+	/// The total rewards claimed by the members of the pool.
+	pub total_rewards_claimed: Balance,
+}
+
+/// The funds of a pool which are unbonding, or unbonded but not withdrawn yet.
+#[derive(Clone, Default, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct UnbondPool<Balance> {
+	/// The total points issued for the funds.
+	pub points: Balance,
+	/// The funds.
+	pub balance: Balance,
+}
+
+/// The unbonding pools of a pool.
+#[derive(Clone, Default, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct SubPools<Balance> {
+	/// The pool into which the pools of eras older than the bonding duration plus
+	/// `PostUnbondingPoolsWindow` are merged.
+	pub no_era: UnbondPool<Balance>,
+	/// The pools of each era in which funds unlock.
+	pub with_era: BTreeMap<EraIndex, UnbondPool<Balance>>,
+}
+
+/// A member of a pool.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct PoolMember<Balance> {
+	/// The pool of the member.
+	pub pool_id: PoolId,
+	/// The points of the member in the bonded pool.
+	pub points: Balance,
+	/// The reward counter of the pool when the member last claimed its rewards.
+	pub last_recorded_reward_counter: FixedU128,
+	/// The points of the member in the unbonding pools, keyed by the era the funds unlock in.
+	pub unbonding_eras: BTreeMap<EraIndex, Balance>,
+}
+
+/// The source of the funds bonded by `bond_extra`.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum BondExtra<Balance> {
+	/// Bond the given amount of the free balance of the member.
+	FreeBalance(Balance),
+	/// Bond the pending rewards of the member.
+	Rewards,
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as NominationPools {
+		/// The id of the most recently created pool.
+		pub LastPoolId get(fn last_pool_id): PoolId;
+
+		/// The bonded pools.
+		pub BondedPools get(fn bonded_pool):
+			map hasher(twox_64_concat) PoolId => Option<BondedPool<T::AccountId, BalanceOf<T>>>;
+
+		/// The reward pools.
+		pub RewardPools get(fn reward_pool):
+			map hasher(twox_64_concat) PoolId => Option<RewardPool<BalanceOf<T>>>;
+
+		/// The unbonding pools of each pool.
+		pub SubPoolsStorage get(fn sub_pools):
+			map hasher(twox_64_concat) PoolId => Option<SubPools<BalanceOf<T>>>;
+
+		/// The members of the pools.
+		pub PoolMembers get(fn pool_member):
+			map hasher(twox_64_concat) T::AccountId => Option<PoolMember<BalanceOf<T>>>;
+
+		/// The pool of each bonded account, used to find the pool of a slashed stash.
+		pub ReversePoolIdLookup get(fn reverse_pool_id):
+			map hasher(twox_64_concat) T::AccountId => Option<PoolId>;
+	}
+}
+
+decl_event! {
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Config>::AccountId,
+		Balance = BalanceOf<T>,
+	{
+		/// A pool has been created. \[depositor, pool_id\]
+		Created(AccountId, PoolId),
+		/// A member has bonded funds into a pool. \[member, pool_id, bonded\]
+		Bonded(AccountId, PoolId, Balance),
+		/// A member has been paid its pending rewards. \[member, pool_id, payout\]
+		PaidOut(AccountId, PoolId, Balance),
+		/// A member has unbonded funds from a pool, unlocking in the given era.
+		/// \[member, pool_id, balance, era\]
+		Unbonded(AccountId, PoolId, Balance, EraIndex),
+		/// A member has withdrawn unlocked funds from a pool. \[member, pool_id, balance\]
+		Withdrawn(AccountId, PoolId, Balance),
+		/// A member has been removed from a pool. \[pool_id, member\]
+		MemberRemoved(PoolId, AccountId),
+		/// A pool has been destroyed. \[pool_id\]
+		Destroyed(PoolId),
+		/// The state of a pool has changed. \[pool_id, state\]
+		StateChanged(PoolId, PoolState),
+		/// The roles of a pool have been updated. \[pool_id, root, nominator\]
+		RolesUpdated(PoolId, AccountId, AccountId),
+		/// The bonded balance of a pool has been slashed. \[pool_id, balance\]
+		PoolSlashed(PoolId, Balance),
+		/// An unbonding pool has been slashed. \[pool_id, era, balance\]
+		UnbondingPoolSlashed(PoolId, EraIndex, Balance),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// The pool does not exist.
+		PoolNotFound,
+		/// The account is not a member of any pool.
+		PoolMemberNotFound,
+		/// The reward pool of the pool does not exist.
+		RewardPoolNotFound,
+		/// The unbonding pools of the pool do not exist.
+		SubPoolsNotFound,
+		/// The account is already a member of a pool.
+		AccountBelongsToOtherPool,
+		/// The amount is below the minimum bond.
+		MinimumBondNotMet,
+		/// The pool is not open to be joined.
+		NotOpen,
+		/// No points would be issued for the bonded funds.
+		ZeroPoints,
+		/// The member does not have enough points.
+		NotEnoughPoints,
+		/// The member has funds unbonding in too many eras.
+		MaxUnbondingLimit,
+		/// There are no unlocked funds to withdraw.
+		CannotWithdrawAny,
+		/// The caller does not have the permission for this operation.
+		DoesNotHavePermission,
+		/// Only the root of a blocked pool, or anyone in a pool being destroyed, can unbond
+		/// another member.
+		NotKickerOrDestroying,
+		/// Other members can only be unbonded fully.
+		PartialUnbondNotAllowedPermissionlessly,
+		/// The depositor can only unbond fully as the last member of a pool being destroyed.
+		NotOnlyPoolMember,
+		/// The state of a pool being destroyed cannot be changed.
+		CanNotChangeState,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		/// The minimum amount which has to be bonded to join a pool.
+		const MinJoinBond: BalanceOf<T> = T::MinJoinBond::get();
+
+		/// The minimum amount which has to be bonded to create a pool.
+		const MinCreateBond: BalanceOf<T> = T::MinCreateBond::get();
+
+		/// The maximum number of eras in which a member can have funds unbonding at the same time.
+		const MaxUnbonding: u32 = T::MaxUnbonding::get();
+
+		/// The pallet's id, used for deriving the accounts of the pools.
+		const ModuleId: ModuleId = T::ModuleId::get();
+
+		fn deposit_event() = default;
+
+		/// Create a new pool, bonding `amount` from the free balance of the origin.
+		///
+		/// The origin becomes the depositor and the first member of the pool. Additionally,
+		/// the existential deposit is transferred into the reward account of the pool.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Parameters:
+		/// - `amount`: The amount to bond. Must be at least `MinCreateBond`, `MinJoinBond` and
+		///   the minimum bond of the staking system.
+		/// - `root`: The root of the pool.
+		/// - `nominator`: The nominator of the pool.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Two balance transfers and one bond in the staking system.
+		/// - Five storage writes.
+		/// # </weight>
+		#[weight = T::WeightInfo::create()]
+		#[transactional]
+		fn create(origin,
+			#[compact] amount: BalanceOf<T>,
+			root: T::AccountId,
+			nominator: T::AccountId,
+		) {
+			let who = ensure_signed(origin)?;
+			ensure!(amount >= Self::min_create_bond(), Error::<T>::MinimumBondNotMet);
+			ensure!(!PoolMembers::<T>::contains_key(&who), Error::<T>::AccountBelongsToOtherPool);
+
+			let pool_id = Self::last_pool_id().saturating_add(1);
+			let bonded_account = Self::bonded_account(pool_id);
+			let reward_account = Self::reward_account(pool_id);
+			T::Currency::transfer(&who, &bonded_account, amount, ExistenceRequirement::KeepAlive)?;
+			T::Currency::transfer(
+				&who,
+				&reward_account,
+				T::Currency::minimum_balance(),
+				ExistenceRequirement::KeepAlive,
+			)?;
+			T::Staking::bond(
+				bonded_account.clone(),
+				bonded_account.clone(),
+				amount,
+				reward_account,
+			)?;
+
+			LastPoolId::put(pool_id);
+			BondedPools::<T>::insert(pool_id, BondedPool {
+				points: amount,
+				state: PoolState::Open,
+				member_counter: 1,
+				roles: PoolRoles { depositor: who.clone(), root, nominator },
+			});
+			RewardPools::<T>::insert(pool_id, RewardPool {
+				last_recorded_reward_counter: FixedU128::zero(),
+				last_recorded_total_payouts: Zero::zero(),
+				total_rewards_claimed: Zero::zero(),
+			});
+			ReversePoolIdLookup::<T>::insert(&bonded_account, pool_id);
+			PoolMembers::<T>::insert(&who, PoolMember {
+				pool_id,
+				points: amount,
+				last_recorded_reward_counter: FixedU128::zero(),
+				unbonding_eras: BTreeMap::new(),
+			});
+
+			Self::deposit_event(RawEvent::Created(who.clone(), pool_id));
+			Self::deposit_event(RawEvent::Bonded(who, pool_id, amount));
+		}
+
+		/// Join an open pool, bonding `amount` from the free balance of the origin.
+		///
+		/// The dispatch origin for this call must be _Signed_, and must not be a member of a
+		/// pool yet.
+		///
+		/// Parameters:
+		/// - `amount`: The amount to bond. Must be at least `MinJoinBond`.
+		/// - `pool_id`: The pool to join.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - One balance transfer and one bond in the staking system.
+		/// - Three storage writes.
+		/// # </weight>
+		#[weight = T::WeightInfo::join()]
+		#[transactional]
+		fn join(origin, #[compact] amount: BalanceOf<T>, pool_id: PoolId) {
+			let who = ensure_signed(origin)?;
+			ensure!(amount >= T::MinJoinBond::get(), Error::<T>::MinimumBondNotMet);
+			ensure!(!PoolMembers::<T>::contains_key(&who), Error::<T>::AccountBelongsToOtherPool);
+			let mut bonded_pool = Self::bonded_pool(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(bonded_pool.state == PoolState::Open, Error::<T>::NotOpen);
+			let mut reward_pool = Self::reward_pool(pool_id).ok_or(Error::<T>::RewardPoolNotFound)?;
+
+			// Record the rewards received so far, the new member is not entitled to them.
+			let reward_counter =
+				Self::update_reward_counter(pool_id, &mut reward_pool, bonded_pool.points);
+			let points = Self::bond_funds(pool_id, &who, &mut bonded_pool, amount)?;
+			bonded_pool.member_counter = bonded_pool.member_counter.saturating_add(1);
+
+			PoolMembers::<T>::insert(&who, PoolMember {
+				pool_id,
+				points,
+				last_recorded_reward_counter: reward_counter,
+				unbonding_eras: BTreeMap::new(),
+			});
+			BondedPools::<T>::insert(pool_id, bonded_pool);
+			RewardPools::<T>::insert(pool_id, reward_pool);
+		}
+
+		/// Bond more funds into the pool of the origin, after claiming its pending rewards.
+		///
+		/// The dispatch origin for this call must be _Signed_ by a member of an open pool.
+		///
+		/// Parameters:
+		/// - `extra`: Whether to bond an amount of the free balance or the pending rewards.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Up to two balance transfers and one bond in the staking system.
+		/// - Three storage writes.
+		/// # </weight>
+		#[weight = T::WeightInfo::bond_extra()]
+		#[transactional]
+		fn bond_extra(origin, extra: BondExtra<BalanceOf<T>>) {
+			let who = ensure_signed(origin)?;
+			let mut member = Self::pool_member(&who).ok_or(Error::<T>::PoolMemberNotFound)?;
+			let pool_id = member.pool_id;
+			let mut bonded_pool = Self::bonded_pool(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(bonded_pool.state == PoolState::Open, Error::<T>::NotOpen);
+			let mut reward_pool = Self::reward_pool(pool_id).ok_or(Error::<T>::RewardPoolNotFound)?;
+
+			let rewards =
+				Self::do_reward_payout(&who, &mut member, &bonded_pool, &mut reward_pool)?;
+			let amount = match extra {
+				BondExtra::FreeBalance(amount) => amount,
+				BondExtra::Rewards => rewards,
+			};
+			let points = Self::bond_funds(pool_id, &who, &mut bonded_pool, amount)?;
+			member.points = member.points.saturating_add(points);
+
+			PoolMembers::<T>::insert(&who, member);
+			BondedPools::<T>::insert(pool_id, bonded_pool);
+			RewardPools::<T>::insert(pool_id, reward_pool);
+		}
+
+		/// Claim the pending rewards of the origin.
+		///
+		/// The dispatch origin for this call must be _Signed_ by a member of a pool.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - One balance transfer.
+		/// - Two storage writes.
+		/// # </weight>
+		#[weight = T::WeightInfo::claim_payout()]
+		fn claim_payout(origin) {
+			let who = ensure_signed(origin)?;
+			let mut member = Self::pool_member(&who).ok_or(Error::<T>::PoolMemberNotFound)?;
+			let pool_id = member.pool_id;
+			let bonded_pool = Self::bonded_pool(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			let mut reward_pool = Self::reward_pool(pool_id).ok_or(Error::<T>::RewardPoolNotFound)?;
+
+			Self::do_reward_payout(&who, &mut member, &bonded_pool, &mut reward_pool)?;
+
+			PoolMembers::<T>::insert(&who, member);
+			RewardPools::<T>::insert(pool_id, reward_pool);
+		}
+
+		/// Unbond `unbonding_points` of `member_account`, after claiming its pending rewards.
+		///
+		/// The funds unlock after the bonding duration of the staking system, and can then be
+		/// withdrawn with `withdraw_unbonded`.
+		///
+		/// The dispatch origin for this call must be _Signed_, and either:
+		/// - be `member_account`. If it is the depositor, it must either keep `MinCreateBond`
+		///   bonded, or be the last member of a pool being destroyed. Otherwise, it must either
+		///   keep `MinJoinBond` bonded or unbond fully.
+		/// - be the root of a blocked pool, or any account if the pool is being destroyed, in
+		///   which case `member_account` must not be the depositor and must unbond fully.
+		///
+		/// Parameters:
+		/// - `member_account`: The member to unbond.
+		/// - `unbonding_points`: The points to unbond.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Up to one balance transfer, one withdrawal and one unbond in the staking system.
+		/// - Four storage writes.
+		/// # </weight>
+		#[weight = T::WeightInfo::unbond()]
+		#[transactional]
+		fn unbond(origin,
+			member_account: <T::Lookup as StaticLookup>::Source,
+			#[compact] unbonding_points: BalanceOf<T>,
+		) {
+			let who = ensure_signed(origin)?;
+			let member_account = T::Lookup::lookup(member_account)?;
+			let mut member = Self::pool_member(&member_account)
+				.ok_or(Error::<T>::PoolMemberNotFound)?;
+			let pool_id = member.pool_id;
+			let mut bonded_pool = Self::bonded_pool(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(
+				!unbonding_points.is_zero() && unbonding_points <= member.points,
+				Error::<T>::NotEnoughPoints
+			);
+			let bonded_account = Self::bonded_account(pool_id);
+			let bonded_balance =
+				T::Staking::active_stake(&bonded_account).unwrap_or_else(Zero::zero);
+			Self::ensure_can_unbond(
+				&who,
+				&member_account,
+				&member,
+				&bonded_pool,
+				bonded_balance,
+				unbonding_points,
+			)?;
+
+			let current_era = T::Staking::current_era();
+			let unbond_era = current_era.saturating_add(T::Staking::bonding_duration());
+			ensure!(
+				member.unbonding_eras.contains_key(&unbond_era) ||
+					member.unbonding_eras.len() < T::MaxUnbonding::get() as usize,
+				Error::<T>::MaxUnbondingLimit
+			);
+			let mut reward_pool = Self::reward_pool(pool_id).ok_or(Error::<T>::RewardPoolNotFound)?;
+			Self::do_reward_payout(&member_account, &mut member, &bonded_pool, &mut reward_pool)?;
+
+			let balance =
+				Self::balance_of_points(bonded_balance, bonded_pool.points, unbonding_points);
+			// Free the unlocking chunks of the staking system which are already unlocked.
+			T::Staking::withdraw_unbonded(bonded_account.clone())?;
+			T::Staking::unbond(bonded_account, balance)?;
+
+			let mut sub_pools = Self::sub_pools(pool_id).unwrap_or_default();
+			Self::merge_unbonding_pools(&mut sub_pools, current_era);
+			let unbond_pool = sub_pools.with_era.entry(unbond_era).or_default();
+			let points = Self::points_to_issue(unbond_pool.balance, unbond_pool.points, balance);
+			unbond_pool.points = unbond_pool.points.saturating_add(points);
+			unbond_pool.balance = unbond_pool.balance.saturating_add(balance);
+			let member_unbonding =
+				member.unbonding_eras.entry(unbond_era).or_insert_with(Zero::zero);
+			*member_unbonding = member_unbonding.saturating_add(points);
+			member.points -= unbonding_points;
+			bonded_pool.points = bonded_pool.points.saturating_sub(unbonding_points);
+
+			PoolMembers::<T>::insert(&member_account, member);
+			BondedPools::<T>::insert(pool_id, bonded_pool);
+			RewardPools::<T>::insert(pool_id, reward_pool);
+			SubPoolsStorage::<T>::insert(pool_id, sub_pools);
+			Self::deposit_event(RawEvent::Unbonded(member_account, pool_id, balance, unbond_era));
+		}
+
+		/// Withdraw the unlocked funds of `member_account`.
+		///
+		/// A member which has neither points nor unbonding funds left is removed from its pool.
+		/// Once the last member is removed, the pool is destroyed and any funds left in its
+		/// accounts are transferred to that member.
+		///
+		/// The dispatch origin for this call must be _Signed_, and either be `member_account`,
+		/// or any account if the pool is being destroyed.
+		///
+		/// Parameters:
+		/// - `member_account`: The member whose unlocked funds to withdraw.
+		///
+		/// # <weight>
+		/// - O(U) where U is `MaxUnbonding`.
+		/// - Up to two balance transfers and one withdrawal in the staking system.
+		/// - Up to six storage writes.
+		/// # </weight>
+		#[weight = T::WeightInfo::withdraw_unbonded()]
+		#[transactional]
+		fn withdraw_unbonded(origin, member_account: <T::Lookup as StaticLookup>::Source) {
+			let who = ensure_signed(origin)?;
+			let member_account = T::Lookup::lookup(member_account)?;
+			let mut member = Self::pool_member(&member_account)
+				.ok_or(Error::<T>::PoolMemberNotFound)?;
+			let pool_id = member.pool_id;
+			let mut bonded_pool = Self::bonded_pool(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(
+				who == member_account || bonded_pool.state == PoolState::Destroying,
+				Error::<T>::DoesNotHavePermission
+			);
+			let current_era = T::Staking::current_era();
+			let withdrawable = member.unbonding_eras.iter()
+				.filter(|(era, _)| **era <= current_era)
+				.map(|(era, points)| (*era, *points))
+				.collect::<Vec<_>>();
+			ensure!(!withdrawable.is_empty(), Error::<T>::CannotWithdrawAny);
+			let mut sub_pools = Self::sub_pools(pool_id).ok_or(Error::<T>::SubPoolsNotFound)?;
+
+			let bonded_account = Self::bonded_account(pool_id);
+			if T::Staking::total_stake(&bonded_account).is_some() {
+				T::Staking::withdraw_unbonded(bonded_account.clone())?;
+			}
+
+			let mut balance: BalanceOf<T> = Zero::zero();
+			for (era, points) in withdrawable {
+				member.unbonding_eras.remove(&era);
+				let unbond_pool = match sub_pools.with_era.get_mut(&era) {
+					Some(unbond_pool) => unbond_pool,
+					// The pool of this era has been merged.
+					None => &mut sub_pools.no_era,
+				};
+				let value =
+					Self::balance_of_points(unbond_pool.balance, unbond_pool.points, points);
+				unbond_pool.points = unbond_pool.points.saturating_sub(points);
+				unbond_pool.balance = unbond_pool.balance.saturating_sub(value);
+				balance = balance.saturating_add(value);
+				if sub_pools.with_era.get(&era).map_or(false, |p| p.points.is_zero()) {
+					sub_pools.with_era.remove(&era);
+				}
+			}
+
+			let leaving = member.points.is_zero() && member.unbonding_eras.is_empty();
+			let destroying = leaving && bonded_pool.member_counter <= 1;
+			let transferable = T::Currency::free_balance(&bonded_account)
+				.saturating_sub(
+					T::Staking::total_stake(&bonded_account).unwrap_or_else(Zero::zero)
+				);
+			// The last member receives whatever is left in the pool.
+			let balance = if destroying { transferable } else { balance.min(transferable) };
+			T::Currency::transfer(
+				&bonded_account,
+				&member_account,
+				balance,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			Self::deposit_event(RawEvent::Withdrawn(member_account.clone(), pool_id, balance));
+
+			if !leaving {
+				PoolMembers::<T>::insert(&member_account, member);
+				SubPoolsStorage::<T>::insert(pool_id, sub_pools);
+				return Ok(())
+			}
+			PoolMembers::<T>::remove(&member_account);
+			Self::deposit_event(RawEvent::MemberRemoved(pool_id, member_account.clone()));
+			if !destroying {
+				bonded_pool.member_counter -= 1;
+				BondedPools::<T>::insert(pool_id, bonded_pool);
+				SubPoolsStorage::<T>::insert(pool_id, sub_pools);
+				return Ok(())
+			}
+
+			let reward_account = Self::reward_account(pool_id);
+			T::Currency::transfer(
+				&reward_account,
+				&member_account,
+				T::Currency::free_balance(&reward_account),
+				ExistenceRequirement::AllowDeath,
+			)?;
+			BondedPools::<T>::remove(pool_id);
+			RewardPools::<T>::remove(pool_id);
+			SubPoolsStorage::<T>::remove(pool_id);
+			ReversePoolIdLookup::<T>::remove(&bonded_account);
+			Self::deposit_event(RawEvent::Destroyed(pool_id));
+		}
+
+		/// Nominate `validators` on behalf of a pool.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the nominator or the root of
+		/// the pool.
+		///
+		/// # <weight>
+		/// - O(N) where N is the number of validators.
+		/// - One nomination in the staking system.
+		/// # </weight>
+		#[weight = T::WeightInfo::nominate(validators.len() as u32)]
+		fn nominate(origin, pool_id: PoolId, validators: Vec<T::AccountId>) {
+			let who = ensure_signed(origin)?;
+			let bonded_pool = Self::bonded_pool(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(
+				who == bonded_pool.roles.nominator || who == bonded_pool.roles.root,
+				Error::<T>::DoesNotHavePermission
+			);
+			T::Staking::nominate(Self::bonded_account(pool_id), validators)?;
+		}
+
+		/// Stop nominating on behalf of a pool.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the nominator or the root of
+		/// the pool.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - One chill in the staking system.
+		/// # </weight>
+		#[weight = T::WeightInfo::chill()]
+		fn chill(origin, pool_id: PoolId) {
+			let who = ensure_signed(origin)?;
+			let bonded_pool = Self::bonded_pool(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(
+				who == bonded_pool.roles.nominator || who == bonded_pool.roles.root,
+				Error::<T>::DoesNotHavePermission
+			);
+			T::Staking::chill(Self::bonded_account(pool_id))?;
+		}
+
+		/// Set the state of a pool. The state of a pool being destroyed cannot be changed.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the root or the depositor of
+		/// the pool.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - One storage write.
+		/// # </weight>
+		#[weight = T::WeightInfo::set_state()]
+		fn set_state(origin, pool_id: PoolId, state: PoolState) {
+			let who = ensure_signed(origin)?;
+			let mut bonded_pool = Self::bonded_pool(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(
+				who == bonded_pool.roles.root || who == bonded_pool.roles.depositor,
+				Error::<T>::DoesNotHavePermission
+			);
+			ensure!(bonded_pool.state != PoolState::Destroying, Error::<T>::CanNotChangeState);
+			bonded_pool.state = state;
+			BondedPools::<T>::insert(pool_id, bonded_pool);
+			Self::deposit_event(RawEvent::StateChanged(pool_id, state));
+		}
+
+		/// Update the root and the nominator of a pool.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the root of the pool.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - One storage write.
+		/// # </weight>
+		#[weight = T::WeightInfo::update_roles()]
+		fn update_roles(origin, pool_id: PoolId, root: T::AccountId, nominator: T::AccountId) {
+			let who = ensure_signed(origin)?;
+			let mut bonded_pool = Self::bonded_pool(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(who == bonded_pool.roles.root, Error::<T>::DoesNotHavePermission);
+			bonded_pool.roles.root = root.clone();
+			bonded_pool.roles.nominator = nominator.clone();
+			BondedPools::<T>::insert(pool_id, bonded_pool);
+			Self::deposit_event(RawEvent::RolesUpdated(pool_id, root, nominator));
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// The account which bonds the funds of a pool.
+	pub fn bonded_account(pool_id: PoolId) -> T::AccountId {
+		T::ModuleId::get().into_sub_account((AccountType::Bonded, pool_id))
+	}
+
+	/// The account which receives the rewards of a pool.
+	pub fn reward_account(pool_id: PoolId) -> T::AccountId {
+		T::ModuleId::get().into_sub_account((AccountType::Reward, pool_id))
+	}
+
+	/// The minimum amount which has to be bonded to create a pool.
+	pub fn min_create_bond() -> BalanceOf<T> {
+		T::MinCreateBond::get()
+			.max(T::MinJoinBond::get())
+			.max(T::Staking::minimum_bond())
+	}
+
+	/// The current balance of the bonded points of `who`, if it is a member of a pool.
+	pub fn bonded_balance_of(who: &T::AccountId) -> Option<BalanceOf<T>> {
+		let member = Self::pool_member(who)?;
+		let bonded_pool = Self::bonded_pool(member.pool_id)?;
+		let bonded_balance = T::Staking::active_stake(&Self::bonded_account(member.pool_id))?;
+		Some(Self::balance_of_points(bonded_balance, bonded_pool.points, member.points))
+	}
+
+	/// The pending rewards of `who`, if it is a member of a pool.
+	pub fn pending_rewards_of(who: &T::AccountId) -> Option<BalanceOf<T>> {
+		let member = Self::pool_member(who)?;
+		let bonded_pool = Self::bonded_pool(member.pool_id)?;
+		let mut reward_pool = Self::reward_pool(member.pool_id)?;
+		let reward_counter =
+			Self::update_reward_counter(member.pool_id, &mut reward_pool, bonded_pool.points);
+		Some(Self::pending_rewards(reward_counter, &member))
+	}
+
+	/// Calculate `a * b / c` without overflowing, saturating the result.
+	fn mul_div(a: BalanceOf<T>, b: BalanceOf<T>, c: BalanceOf<T>) -> BalanceOf<T> {
+		multiply_by_rational(a.saturated_into(), b.saturated_into(), c.saturated_into())
+			.map(|value| value.saturated_into())
+			.unwrap_or_else(|_| Bounded::max_value())
+	}
+
+	/// The points to issue for `new_funds` in a pool with the given balance and points.
+	fn points_to_issue(
+		balance: BalanceOf<T>,
+		points: BalanceOf<T>,
+		new_funds: BalanceOf<T>,
+	) -> BalanceOf<T> {
+		match (balance.is_zero(), points.is_zero()) {
+			// A new pool issues one point per unit of funds.
+			(_, true) => new_funds,
+			// A pool which has been slashed entirely cannot issue points fairly.
+			(true, false) => Zero::zero(),
+			(false, false) => Self::mul_div(new_funds, points, balance),
+		}
+	}
+
+	/// The balance of `member_points` in a pool with the given balance and points.
+	fn balance_of_points(
+		balance: BalanceOf<T>,
+		points: BalanceOf<T>,
+		member_points: BalanceOf<T>,
+	) -> BalanceOf<T> {
+		if points.is_zero() {
+			return Zero::zero()
+		}
+		Self::mul_div(balance, member_points, points)
+	}
+
+	/// Transfer `amount` of `who` into the bonded account of a pool, and bond it.
+	///
+	/// Returns the points issued for it.
+	fn bond_funds(
+		pool_id: PoolId,
+		who: &T::AccountId,
+		bonded_pool: &mut BondedPool<T::AccountId, BalanceOf<T>>,
+		amount: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let bonded_account = Self::bonded_account(pool_id);
+		let bonded_balance = T::Staking::active_stake(&bonded_account).unwrap_or_else(Zero::zero);
+		let points = Self::points_to_issue(bonded_balance, bonded_pool.points, amount);
+		ensure!(!points.is_zero(), Error::<T>::ZeroPoints);
+
+		T::Currency::transfer(who, &bonded_account, amount, ExistenceRequirement::KeepAlive)?;
+		T::Staking::bond_extra(bonded_account, amount)?;
+		bonded_pool.points = bonded_pool.points.saturating_add(points);
+
+		Self::deposit_event(RawEvent::Bonded(who.clone(), pool_id, amount));
+		Ok(points)
+	}
+
+	/// Record the rewards received by a pool since they were last recorded into its reward
+	/// counter, and return the updated reward counter.
+	///
+	/// Must be called before the points of the pool change.
+	fn update_reward_counter(
+		pool_id: PoolId,
+		reward_pool: &mut RewardPool<BalanceOf<T>>,
+		bonded_points: BalanceOf<T>,
+	) -> FixedU128 {
+		// Without points, the rewards are left for the points issued next.
+		if bonded_points.is_zero() {
+			return reward_pool.last_recorded_reward_counter
+		}
+		let balance = T::Currency::free_balance(&Self::reward_account(pool_id))
+			.saturating_sub(T::Currency::minimum_balance());
+		let total_payouts = balance.saturating_add(reward_pool.total_rewards_claimed);
+		let new_payouts = total_payouts.saturating_sub(reward_pool.last_recorded_total_payouts);
+		let increase = FixedU128::saturating_from_rational(
+			new_payouts.saturated_into::<u128>(),
+			bonded_points.saturated_into::<u128>(),
+		);
+		reward_pool.last_recorded_reward_counter =
+			reward_pool.last_recorded_reward_counter.saturating_add(increase);
+		reward_pool.last_recorded_total_payouts = total_payouts;
+		reward_pool.last_recorded_reward_counter
+	}
+
+	/// The rewards of `member` which are pending at the given reward counter.
+	fn pending_rewards(
+		reward_counter: FixedU128,
+		member: &PoolMember<BalanceOf<T>>,
+	) -> BalanceOf<T> {
+		reward_counter
+			.saturating_sub(member.last_recorded_reward_counter)
+			.saturating_mul_int(member.points.saturated_into::<u128>())
+			.saturated_into()
+	}
+
+	/// Pay the pending rewards of `who` out of the reward account of its pool.
+	///
+	/// Returns the rewards paid.
+	fn do_reward_payout(
+		who: &T::AccountId,
+		member: &mut PoolMember<BalanceOf<T>>,
+		bonded_pool: &BondedPool<T::AccountId, BalanceOf<T>>,
+		reward_pool: &mut RewardPool<BalanceOf<T>>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let reward_counter =
+			Self::update_reward_counter(member.pool_id, reward_pool, bonded_pool.points);
+		let pending = Self::pending_rewards(reward_counter, member);
+		member.last_recorded_reward_counter = reward_counter;
+		if pending.is_zero() {
+			return Ok(pending)
+		}
+
+		T::Currency::transfer(
+			&Self::reward_account(member.pool_id),
+			who,
+			pending,
+			ExistenceRequirement::KeepAlive,
+		)?;
+		reward_pool.total_rewards_claimed =
+			reward_pool.total_rewards_claimed.saturating_add(pending);
+		Self::deposit_event(RawEvent::PaidOut(who.clone(), member.pool_id, pending));
+		Ok(pending)
+	}
+
+	/// Check that `who` can unbond `unbonding_points` of `member_account`.
+	fn ensure_can_unbond(
+		who: &T::AccountId,
+		member_account: &T::AccountId,
+		member: &PoolMember<BalanceOf<T>>,
+		bonded_pool: &BondedPool<T::AccountId, BalanceOf<T>>,
+		bonded_balance: BalanceOf<T>,
+		unbonding_points: BalanceOf<T>,
+	) -> Result<(), DispatchError> {
+		let is_depositor = *member_account == bonded_pool.roles.depositor;
+		let is_full_unbond = unbonding_points == member.points;
+
+		if who != member_account {
+			ensure!(is_full_unbond, Error::<T>::PartialUnbondNotAllowedPermissionlessly);
+			ensure!(!is_depositor, Error::<T>::DoesNotHavePermission);
+			let can_kick = bonded_pool.state == PoolState::Destroying ||
+				(bonded_pool.state == PoolState::Blocked && *who == bonded_pool.roles.root);
+			ensure!(can_kick, Error::<T>::NotKickerOrDestroying);
+			return Ok(())
+		}
+
+		let remaining = Self::balance_of_points(
+			bonded_balance,
+			bonded_pool.points,
+			member.points - unbonding_points,
+		);
+		if is_depositor {
+			if is_full_unbond {
+				ensure!(
+					bonded_pool.state == PoolState::Destroying && bonded_pool.member_counter == 1,
+					Error::<T>::NotOnlyPoolMember
+				);
+			} else {
+				ensure!(remaining >= T::MinCreateBond::get(), Error::<T>::MinimumBondNotMet);
+			}
+		} else if !is_full_unbond {
+			ensure!(remaining >= T::MinJoinBond::get(), Error::<T>::MinimumBondNotMet);
+		}
+		Ok(())
+	}
+
+	/// Merge the unbonding pools of the eras which are older than the bonding duration plus
+	/// `PostUnbondingPoolsWindow` into the unbonding pool without era.
+	fn merge_unbonding_pools(sub_pools: &mut SubPools<BalanceOf<T>>, current_era: EraIndex) {
+		let window = T::Staking::bonding_duration()
+			.saturating_add(T::PostUnbondingPoolsWindow::get());
+		let newest_to_merge = match current_era.checked_sub(window) {
+			Some(era) => era,
+			None => return,
+		};
+		let eras = sub_pools.with_era.keys()
+			.filter(|era| **era <= newest_to_merge)
+			.cloned()
+			.collect::<Vec<_>>();
+		for era in eras {
+			if let Some(unbond_pool) = sub_pools.with_era.remove(&era) {
+				sub_pools.no_era.points =
+					sub_pools.no_era.points.saturating_add(unbond_pool.points);
+				sub_pools.no_era.balance =
+					sub_pools.no_era.balance.saturating_add(unbond_pool.balance);
+			}
+		}
+	}
+}
+
+/// Apply the slashes of the bonded accounts of the pools to their unbonding pools.
+///
+/// Slashes of the active stake need no bookkeeping, as the balance of the points of a bonded
+/// pool always follows its active stake.
+impl<T: Config> OnStakerSlash<T::AccountId, BalanceOf<T>> for Module<T> {
+	fn on_slash(
+		stash: &T::AccountId,
+		active: BalanceOf<T>,
+		unlocking: &BTreeMap<EraIndex, BalanceOf<T>>,
+	) {
+		let pool_id = match Self::reverse_pool_id(stash) {
+			Some(pool_id) => pool_id,
+			None => return,
+		};
+		let current_era = T::Staking::current_era();
+		if let Some(mut sub_pools) = Self::sub_pools(pool_id) {
+			for (era, unbond_pool) in sub_pools.with_era.iter_mut() {
+				let slashed = match unlocking.get(era) {
+					Some(value) => unbond_pool.balance.min(*value),
+					// A chunk which has not unlocked yet has been slashed entirely. Unlocked
+					// ones may have been withdrawn already, so they are left as they are.
+					None if *era > current_era => Zero::zero(),
+					None => continue,
+				};
+				if slashed < unbond_pool.balance {
+					unbond_pool.balance = slashed;
+					Self::deposit_event(RawEvent::UnbondingPoolSlashed(pool_id, *era, slashed));
+				}
+			}
+			SubPoolsStorage::<T>::insert(pool_id, sub_pools);
+		}
+		Self::deposit_event(RawEvent::PoolSlashed(pool_id, active));
+	}
+}
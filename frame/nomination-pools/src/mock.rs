@@ -0,0 +1,296 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test utilities
+
+use super::*;
+
+use frame_support::{impl_outer_origin, impl_outer_event, parameter_types};
+use std::{cell::RefCell, collections::BTreeMap as StdBTreeMap};
+use sp_core::H256;
+use sp_runtime::{
+	DispatchResult, traits::{BlakeTwo256, IdentityLookup}, testing::Header,
+};
+use crate as pools;
+
+pub type AccountId = u128;
+pub type Balance = u64;
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		system<T>,
+		pallet_balances<T>,
+		pools<T>,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 5;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
+	type Balance = Balance;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+pub const BONDING_DURATION: EraIndex = 3;
+
+/// The ledger of a stash of the mock staking system.
+#[derive(Clone, Default)]
+pub struct Ledger {
+	pub active: Balance,
+	pub unlocking: Vec<(EraIndex, Balance)>,
+}
+
+thread_local! {
+	static CURRENT_ERA: RefCell<EraIndex> = RefCell::new(0);
+	pub static LEDGERS: RefCell<StdBTreeMap<AccountId, Ledger>> = RefCell::new(Default::default());
+	pub static NOMINATIONS: RefCell<StdBTreeMap<AccountId, Vec<AccountId>>> =
+		RefCell::new(Default::default());
+}
+
+/// A staking system which keeps its ledgers in memory, the stash being its own controller.
+pub struct StakingMock;
+impl StakingMock {
+	pub fn set_current_era(era: EraIndex) {
+		CURRENT_ERA.with(|e| *e.borrow_mut() = era);
+	}
+
+	pub fn ledger(stash: &AccountId) -> Option<Ledger> {
+		LEDGERS.with(|l| l.borrow().get(stash).cloned())
+	}
+
+	/// Slash the active stake and the unlocking chunks of `stash` to the given values, and
+	/// notify the pools like the staking pallet would.
+	pub fn slash_to(stash: &AccountId, active: Balance, unlocking: Vec<(EraIndex, Balance)>) {
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			let ledger = ledgers.get_mut(stash).unwrap();
+			ledger.active = active;
+			ledger.unlocking = unlocking.clone();
+		});
+		// The slashed funds leave the stash.
+		let total = Self::total_stake(stash).unwrap();
+		let free = Balances::free_balance(stash);
+		let _ = Balances::slash(stash, free - total);
+		Pools::on_slash(stash, active, &unlocking.into_iter().collect());
+	}
+}
+
+impl StakingInterface for StakingMock {
+	type Balance = Balance;
+	type AccountId = AccountId;
+
+	fn minimum_bond() -> Balance {
+		10
+	}
+
+	fn bonding_duration() -> EraIndex {
+		BONDING_DURATION
+	}
+
+	fn current_era() -> EraIndex {
+		CURRENT_ERA.with(|e| *e.borrow())
+	}
+
+	fn validator_count() -> u32 {
+		0
+	}
+
+	fn active_stake(stash: &AccountId) -> Option<Balance> {
+		Self::ledger(stash).map(|l| l.active)
+	}
+
+	fn total_stake(stash: &AccountId) -> Option<Balance> {
+		Self::ledger(stash).map(|l| l.active + l.unlocking.iter().map(|(_, v)| v).sum::<Balance>())
+	}
+
+	fn bond(
+		stash: AccountId,
+		_controller: AccountId,
+		value: Balance,
+		_payee: AccountId,
+	) -> DispatchResult {
+		LEDGERS.with(|l| l.borrow_mut().insert(stash, Ledger { active: value, unlocking: vec![] }));
+		Ok(())
+	}
+
+	fn bond_extra(stash: AccountId, extra: Balance) -> DispatchResult {
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			let ledger = ledgers.get_mut(&stash).ok_or(DispatchError::Other("not bonded"))?;
+			ledger.active += extra;
+			Ok(())
+		})
+	}
+
+	fn unbond(controller: AccountId, value: Balance) -> DispatchResult {
+		let era = Self::current_era() + BONDING_DURATION;
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			let ledger = ledgers.get_mut(&controller).ok_or(DispatchError::Other("not bonded"))?;
+			ledger.active -= value;
+			ledger.unlocking.push((era, value));
+			Ok(())
+		})
+	}
+
+	fn withdraw_unbonded(controller: AccountId) -> Result<bool, DispatchError> {
+		let current_era = Self::current_era();
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			let ledger = ledgers.get_mut(&controller).ok_or(DispatchError::Other("not bonded"))?;
+			ledger.unlocking.retain(|(era, _)| *era > current_era);
+			if ledger.active == 0 && ledger.unlocking.is_empty() {
+				ledgers.remove(&controller);
+				return Ok(true)
+			}
+			Ok(false)
+		})
+	}
+
+	fn nominate(controller: AccountId, targets: Vec<AccountId>) -> DispatchResult {
+		NOMINATIONS.with(|n| n.borrow_mut().insert(controller, targets));
+		Ok(())
+	}
+
+	fn chill(controller: AccountId) -> DispatchResult {
+		NOMINATIONS.with(|n| n.borrow_mut().remove(&controller));
+		Ok(())
+	}
+
+	fn is_chilled(stash: &AccountId) -> bool {
+		NOMINATIONS.with(|n| !n.borrow().contains_key(stash))
+	}
+
+	fn stash_by_ctrl(controller: &AccountId) -> Result<AccountId, DispatchError> {
+		Self::ledger(controller).map(|_| *controller).ok_or(DispatchError::Other("not bonded"))
+	}
+
+	fn is_exposed_in_era(_: &AccountId, _: &EraIndex) -> bool {
+		false
+	}
+
+	fn force_unstake(stash: AccountId) -> DispatchResult {
+		LEDGERS.with(|l| l.borrow_mut().remove(&stash));
+		NOMINATIONS.with(|n| n.borrow_mut().remove(&stash));
+		Ok(())
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn max_exposure_size() -> u32 {
+		0
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn add_era_stakers(_: &EraIndex, _: &AccountId, _: Vec<AccountId>) {}
+}
+
+parameter_types! {
+	pub const NominationPoolsModuleId: ModuleId = ModuleId(*b"py/nopls");
+	pub const MinJoinBond: Balance = 10;
+	pub const MinCreateBond: Balance = 20;
+	pub const MaxUnbonding: u32 = 3;
+	pub const PostUnbondingPoolsWindow: u32 = 2;
+}
+
+impl Config for Test {
+	type Event = TestEvent;
+	type Currency = Balances;
+	type Staking = StakingMock;
+	type ModuleId = NominationPoolsModuleId;
+	type MinJoinBond = MinJoinBond;
+	type MinCreateBond = MinCreateBond;
+	type MaxUnbonding = MaxUnbonding;
+	type PostUnbondingPoolsWindow = PostUnbondingPoolsWindow;
+	type WeightInfo = ();
+}
+
+pub type System = frame_system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type Pools = Module<Test>;
+
+/// The depositor of the pool created at genesis.
+pub const DEPOSITOR: AccountId = 10;
+
+/// Build the externalities with a pool of `DEPOSITOR` bonding 100, which is open.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	StakingMock::set_current_era(0);
+	LEDGERS.with(|l| l.borrow_mut().clear());
+	NOMINATIONS.with(|n| n.borrow_mut().clear());
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(DEPOSITOR, 1000), (20, 1000), (30, 1000), (40, 1000), (50, 1000)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		assert!(Pools::create(Origin::signed(DEPOSITOR), 100, 900, 901).is_ok());
+	});
+	ext
+}
+
+/// The events of this pallet deposited so far.
+pub fn pool_events() -> Vec<RawEvent<AccountId, Balance>> {
+	System::events().into_iter().filter_map(|r| match r.event {
+		TestEvent::pools(e) => Some(e),
+		_ => None,
+	}).collect()
+}
@@ -0,0 +1,379 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the module.
+
+use super::*;
+use mock::{
+	Balances, Pools, StakingMock, Origin, Test, DEPOSITOR, NOMINATIONS,
+	new_test_ext, pool_events,
+};
+use frame_support::{assert_noop, assert_ok};
+
+fn bonded() -> u128 {
+	Pools::bonded_account(1)
+}
+
+fn reward() -> u128 {
+	Pools::reward_account(1)
+}
+
+/// Pay `amount` of rewards into the reward account of the pool.
+fn pay_rewards(amount: u64) {
+	let _ = Balances::deposit_creating(&reward(), amount);
+}
+
+#[test]
+fn create_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Pools::bonded_pool(1), Some(BondedPool {
+			points: 100,
+			state: PoolState::Open,
+			member_counter: 1,
+			roles: PoolRoles { depositor: DEPOSITOR, root: 900, nominator: 901 },
+		}));
+		assert_eq!(Pools::pool_member(DEPOSITOR).unwrap().points, 100);
+		assert_eq!(Pools::reverse_pool_id(bonded()), Some(1));
+		assert_eq!(StakingMock::active_stake(&bonded()), Some(100));
+		// The depositor pays the bond and the existential deposit of the reward account.
+		assert_eq!(Balances::free_balance(DEPOSITOR), 895);
+		assert_eq!(Balances::free_balance(bonded()), 100);
+		assert_eq!(Balances::free_balance(reward()), 5);
+		assert_eq!(pool_events(), vec![
+			RawEvent::Created(DEPOSITOR, 1),
+			RawEvent::Bonded(DEPOSITOR, 1, 100),
+		]);
+
+		assert_noop!(
+			Pools::create(Origin::signed(DEPOSITOR), 100, 900, 901),
+			Error::<Test>::AccountBelongsToOtherPool
+		);
+		assert_noop!(
+			Pools::create(Origin::signed(20), 19, 20, 20),
+			Error::<Test>::MinimumBondNotMet
+		);
+		assert_ok!(Pools::create(Origin::signed(20), 20, 20, 20));
+		assert_eq!(Pools::last_pool_id(), 2);
+		assert_ne!(Pools::bonded_account(2), bonded());
+		assert_ne!(Pools::reward_account(2), reward());
+		assert_eq!(StakingMock::active_stake(&Pools::bonded_account(2)), Some(20));
+	});
+}
+
+#[test]
+fn join_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 50, 1));
+		assert_eq!(Pools::pool_member(20), Some(PoolMember {
+			pool_id: 1,
+			points: 50,
+			last_recorded_reward_counter: FixedU128::zero(),
+			unbonding_eras: BTreeMap::new(),
+		}));
+		let bonded_pool = Pools::bonded_pool(1).unwrap();
+		assert_eq!(bonded_pool.points, 150);
+		assert_eq!(bonded_pool.member_counter, 2);
+		assert_eq!(Balances::free_balance(20), 950);
+		assert_eq!(StakingMock::active_stake(&bonded()), Some(150));
+		assert_eq!(pool_events().last(), Some(&RawEvent::Bonded(20, 1, 50)));
+
+		assert_noop!(Pools::join(Origin::signed(30), 9, 1), Error::<Test>::MinimumBondNotMet);
+		assert_noop!(Pools::join(Origin::signed(30), 10, 2), Error::<Test>::PoolNotFound);
+		assert_noop!(
+			Pools::join(Origin::signed(20), 10, 1),
+			Error::<Test>::AccountBelongsToOtherPool
+		);
+		assert_ok!(Pools::set_state(Origin::signed(900), 1, PoolState::Blocked));
+		assert_noop!(Pools::join(Origin::signed(30), 10, 1), Error::<Test>::NotOpen);
+	});
+}
+
+#[test]
+fn rewards_are_shared_pro_rata() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 100, 1));
+		pay_rewards(60);
+		assert_eq!(Pools::pending_rewards_of(&DEPOSITOR), Some(30));
+
+		assert_ok!(Pools::claim_payout(Origin::signed(DEPOSITOR)));
+		assert_eq!(Balances::free_balance(DEPOSITOR), 925);
+		assert_eq!(pool_events().last(), Some(&RawEvent::PaidOut(DEPOSITOR, 1, 30)));
+		// Claiming again pays nothing.
+		assert_ok!(Pools::claim_payout(Origin::signed(DEPOSITOR)));
+		assert_eq!(Balances::free_balance(DEPOSITOR), 925);
+
+		// A new member is not entitled to the rewards received before it joined.
+		assert_ok!(Pools::join(Origin::signed(30), 200, 1));
+		assert_eq!(Pools::pending_rewards_of(&30), Some(0));
+		pay_rewards(40);
+
+		assert_ok!(Pools::claim_payout(Origin::signed(20)));
+		assert_eq!(Balances::free_balance(20), 940);
+		assert_ok!(Pools::claim_payout(Origin::signed(30)));
+		assert_eq!(Balances::free_balance(30), 820);
+		assert_ok!(Pools::claim_payout(Origin::signed(DEPOSITOR)));
+		assert_eq!(Balances::free_balance(DEPOSITOR), 935);
+		assert_eq!(Balances::free_balance(reward()), 5);
+		assert_eq!(Pools::reward_pool(1).unwrap().total_rewards_claimed, 100);
+
+		assert_noop!(Pools::claim_payout(Origin::signed(40)), Error::<Test>::PoolMemberNotFound);
+	});
+}
+
+#[test]
+fn bond_extra_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 100, 1));
+		pay_rewards(100);
+
+		// The rewards are paid out and bonded again.
+		assert_ok!(Pools::bond_extra(Origin::signed(DEPOSITOR), BondExtra::Rewards));
+		assert_eq!(Balances::free_balance(DEPOSITOR), 895);
+		assert_eq!(Pools::pool_member(DEPOSITOR).unwrap().points, 150);
+		assert_eq!(StakingMock::active_stake(&bonded()), Some(250));
+
+		// The pending rewards are paid out before bonding more.
+		assert_ok!(Pools::bond_extra(Origin::signed(20), BondExtra::FreeBalance(20)));
+		assert_eq!(Balances::free_balance(20), 930);
+		assert_eq!(Pools::pool_member(20).unwrap().points, 120);
+		assert_eq!(Pools::bonded_pool(1).unwrap().points, 270);
+		assert_eq!(StakingMock::active_stake(&bonded()), Some(270));
+		assert_eq!(pool_events()[3..], [
+			RawEvent::PaidOut(DEPOSITOR, 1, 50),
+			RawEvent::Bonded(DEPOSITOR, 1, 50),
+			RawEvent::PaidOut(20, 1, 50),
+			RawEvent::Bonded(20, 1, 20),
+		]);
+	});
+}
+
+#[test]
+fn unbond_and_withdraw_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 100, 1));
+		assert_noop!(Pools::unbond(Origin::signed(20), 20, 101), Error::<Test>::NotEnoughPoints);
+		assert_noop!(Pools::unbond(Origin::signed(20), 20, 95), Error::<Test>::MinimumBondNotMet);
+
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 40));
+		let member = Pools::pool_member(20).unwrap();
+		assert_eq!(member.points, 60);
+		assert_eq!(member.unbonding_eras, vec![(3, 40)].into_iter().collect::<BTreeMap<_, _>>());
+		let unbond_pool = UnbondPool { points: 40, balance: 40 };
+		assert_eq!(Pools::sub_pools(1).unwrap().with_era.get(&3), Some(&unbond_pool));
+		assert_eq!(StakingMock::active_stake(&bonded()), Some(160));
+		assert_eq!(pool_events().last(), Some(&RawEvent::Unbonded(20, 1, 40, 3)));
+
+		assert_noop!(
+			Pools::withdraw_unbonded(Origin::signed(20), 20),
+			Error::<Test>::CannotWithdrawAny
+		);
+		StakingMock::set_current_era(3);
+		// Only the member can withdraw from a pool which is not being destroyed.
+		assert_noop!(
+			Pools::withdraw_unbonded(Origin::signed(30), 20),
+			Error::<Test>::DoesNotHavePermission
+		);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(20), 20));
+		assert_eq!(Balances::free_balance(20), 940);
+		assert!(Pools::sub_pools(1).unwrap().with_era.is_empty());
+		assert_eq!(pool_events().last(), Some(&RawEvent::Withdrawn(20, 1, 40)));
+
+		// Once the member has nothing left, it is removed.
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 60));
+		StakingMock::set_current_era(6);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(20), 20));
+		assert_eq!(Balances::free_balance(20), 1000);
+		assert_eq!(Pools::pool_member(20), None);
+		assert_eq!(Pools::bonded_pool(1).unwrap().member_counter, 1);
+		assert_eq!(pool_events().last(), Some(&RawEvent::MemberRemoved(1, 20)));
+	});
+}
+
+#[test]
+fn unbond_permissions_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 100, 1));
+
+		// The depositor must keep `MinCreateBond` while the pool has other members.
+		assert_noop!(
+			Pools::unbond(Origin::signed(DEPOSITOR), DEPOSITOR, 100),
+			Error::<Test>::NotOnlyPoolMember
+		);
+		assert_noop!(
+			Pools::unbond(Origin::signed(DEPOSITOR), DEPOSITOR, 90),
+			Error::<Test>::MinimumBondNotMet
+		);
+		assert_ok!(Pools::unbond(Origin::signed(DEPOSITOR), DEPOSITOR, 80));
+
+		// Other members can only be kicked fully, and not out of an open pool.
+		assert_noop!(
+			Pools::unbond(Origin::signed(30), 20, 50),
+			Error::<Test>::PartialUnbondNotAllowedPermissionlessly
+		);
+		assert_noop!(
+			Pools::unbond(Origin::signed(30), 20, 100),
+			Error::<Test>::NotKickerOrDestroying
+		);
+		assert_noop!(
+			Pools::unbond(Origin::signed(900), DEPOSITOR, 20),
+			Error::<Test>::DoesNotHavePermission
+		);
+
+		// Only the root can kick members out of a blocked pool.
+		assert_ok!(Pools::set_state(Origin::signed(900), 1, PoolState::Blocked));
+		assert_noop!(
+			Pools::unbond(Origin::signed(30), 20, 100),
+			Error::<Test>::NotKickerOrDestroying
+		);
+		assert_ok!(Pools::unbond(Origin::signed(900), 20, 100));
+		assert_eq!(Pools::pool_member(20).unwrap().points, 0);
+	});
+}
+
+#[test]
+fn destroying_pool_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 100, 1));
+		pay_rewards(10);
+		assert_ok!(Pools::set_state(Origin::signed(DEPOSITOR), 1, PoolState::Destroying));
+		assert_noop!(
+			Pools::set_state(Origin::signed(900), 1, PoolState::Open),
+			Error::<Test>::CanNotChangeState
+		);
+
+		// Anyone can unbond and withdraw the funds of the members.
+		assert_ok!(Pools::unbond(Origin::signed(30), 20, 100));
+		assert_noop!(
+			Pools::unbond(Origin::signed(DEPOSITOR), DEPOSITOR, 100),
+			Error::<Test>::NotOnlyPoolMember
+		);
+		StakingMock::set_current_era(3);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(30), 20));
+		assert_eq!(Balances::free_balance(20), 1005);
+		assert_eq!(Pools::bonded_pool(1).unwrap().member_counter, 1);
+
+		// The depositor leaves last, and receives whatever is left in the pool.
+		assert_ok!(Pools::unbond(Origin::signed(DEPOSITOR), DEPOSITOR, 100));
+		StakingMock::set_current_era(6);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(30), DEPOSITOR));
+		assert_eq!(Balances::free_balance(DEPOSITOR), 1005);
+		assert_eq!(Balances::free_balance(bonded()), 0);
+		assert_eq!(Balances::free_balance(reward()), 0);
+		assert!(StakingMock::ledger(&bonded()).is_none());
+		assert_eq!(Pools::bonded_pool(1), None);
+		assert_eq!(Pools::reward_pool(1), None);
+		assert_eq!(Pools::sub_pools(1), None);
+		assert_eq!(Pools::pool_member(DEPOSITOR), None);
+		assert_eq!(Pools::reverse_pool_id(bonded()), None);
+		assert_eq!(pool_events().last(), Some(&RawEvent::Destroyed(1)));
+	});
+}
+
+#[test]
+fn slashes_are_shared_pro_rata() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 100, 1));
+		assert_ok!(Pools::join(Origin::signed(30), 200, 1));
+		assert_ok!(Pools::unbond(Origin::signed(30), 30, 100));
+
+		// Half of both the active stake and the unlocking funds is slashed.
+		StakingMock::slash_to(&bonded(), 150, vec![(3, 50)]);
+		assert_eq!(Pools::bonded_balance_of(&DEPOSITOR), Some(50));
+		assert_eq!(Pools::bonded_balance_of(&20), Some(50));
+		assert_eq!(Pools::bonded_balance_of(&30), Some(50));
+		assert_eq!(Pools::sub_pools(1).unwrap().with_era[&3].balance, 50);
+		assert_eq!(pool_events()[pool_events().len() - 2..], [
+			RawEvent::UnbondingPoolSlashed(1, 3, 50),
+			RawEvent::PoolSlashed(1, 150),
+		]);
+
+		StakingMock::set_current_era(3);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(30), 30));
+		assert_eq!(Balances::free_balance(30), 850);
+
+		// New members receive points at the slashed rate.
+		assert_ok!(Pools::join(Origin::signed(40), 30, 1));
+		assert_eq!(Pools::pool_member(40).unwrap().points, 60);
+		assert_eq!(Pools::bonded_balance_of(&40), Some(30));
+	});
+}
+
+#[test]
+fn unbonding_limits_and_merging_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 100, 1));
+		for era in 0..3 {
+			StakingMock::set_current_era(era);
+			assert_ok!(Pools::unbond(Origin::signed(20), 20, 10));
+		}
+		// Unbonding again in the same era does not use another slot.
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 10));
+		StakingMock::set_current_era(3);
+		assert_noop!(Pools::unbond(Origin::signed(20), 20, 10), Error::<Test>::MaxUnbondingLimit);
+
+		// The pools older than the bonding duration plus the window are merged.
+		StakingMock::set_current_era(8);
+		assert_ok!(Pools::unbond(Origin::signed(DEPOSITOR), DEPOSITOR, 10));
+		let sub_pools = Pools::sub_pools(1).unwrap();
+		assert_eq!(sub_pools.no_era, UnbondPool { points: 10, balance: 10 });
+		assert_eq!(sub_pools.with_era.keys().cloned().collect::<Vec<_>>(), vec![4, 5, 11]);
+
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(20), 20));
+		assert_eq!(Balances::free_balance(20), 940);
+		assert!(Pools::pool_member(20).unwrap().unbonding_eras.is_empty());
+		assert_eq!(Pools::sub_pools(1).unwrap().no_era, UnbondPool { points: 0, balance: 0 });
+	});
+}
+
+#[test]
+fn pool_roles_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Pools::nominate(Origin::signed(20), 1, vec![1, 2]),
+			Error::<Test>::DoesNotHavePermission
+		);
+		assert_ok!(Pools::nominate(Origin::signed(901), 1, vec![1, 2]));
+		assert_eq!(NOMINATIONS.with(|n| n.borrow().get(&bonded()).cloned()), Some(vec![1, 2]));
+		assert_noop!(
+			Pools::chill(Origin::signed(DEPOSITOR), 1),
+			Error::<Test>::DoesNotHavePermission
+		);
+		assert_ok!(Pools::chill(Origin::signed(900), 1));
+		assert_eq!(NOMINATIONS.with(|n| n.borrow().get(&bonded()).cloned()), None);
+
+		assert_noop!(
+			Pools::set_state(Origin::signed(901), 1, PoolState::Blocked),
+			Error::<Test>::DoesNotHavePermission
+		);
+		assert_ok!(Pools::set_state(Origin::signed(DEPOSITOR), 1, PoolState::Blocked));
+		assert_eq!(pool_events().last(), Some(&RawEvent::StateChanged(1, PoolState::Blocked)));
+
+		assert_noop!(
+			Pools::update_roles(Origin::signed(DEPOSITOR), 1, 902, 903),
+			Error::<Test>::DoesNotHavePermission
+		);
+		assert_ok!(Pools::update_roles(Origin::signed(900), 1, 902, 903));
+		assert_eq!(
+			Pools::bonded_pool(1).unwrap().roles,
+			PoolRoles { depositor: DEPOSITOR, root: 902, nominator: 903 }
+		);
+		assert_noop!(
+			Pools::set_state(Origin::signed(900), 1, PoolState::Open),
+			Error::<Test>::DoesNotHavePermission
+		);
+	});
+}
@@ -69,6 +69,9 @@ parameter_types! {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type Balance = Balance;
 	type Event = Event;
 	type DustRemoval = ();
@@ -89,6 +92,7 @@ impl pallet_timestamp::Config for Test {
 impl pallet_session::historical::Config for Test {
 	type FullIdentification = pallet_staking::Exposure<AccountId, Balance>;
 	type FullIdentificationOf = pallet_staking::ExposureOf<Test>;
+	type PruningDepth = ();
 }
 
 sp_runtime::impl_opaque_keys! {
@@ -142,6 +146,7 @@ pallet_staking_reward_curve::build! {
 parameter_types! {
 	pub const RewardCurve: &'static sp_runtime::curve::PiecewiseLinear<'static> = &I_NPOS;
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const VoterSnapshotWeightLimit: u64 = u64::max_value();
 }
 
 pub type Extrinsic = sp_runtime::testing::TestXt<Call, ()>;
@@ -168,6 +173,10 @@ impl pallet_staking::Config for Test {
 	type MaxIterations = ();
 	type MinSolutionScoreBump = ();
 	type OffchainSolutionWeightLimit = ();
+	type OnStakerSlash = ();
+	type VoterList = pallet_staking::UseNominatorsMap<Self>;
+	type VoterSnapshotWeightLimit = VoterSnapshotWeightLimit;
+	type CommissionChangeDelay = ();
 	type WeightInfo = ();
 }
 
@@ -177,6 +186,7 @@ impl pallet_im_online::Config for Test {
 	type SessionDuration = Period;
 	type ReportUnresponsiveness = Offences;
 	type UnsignedPriority = ();
+	type StatsHistoryDepth = ();
 	type WeightInfo = ();
 }
 
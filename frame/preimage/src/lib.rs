@@ -0,0 +1,384 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Preimage Module
+//! A module for storing the preimages of hashes on chain.
+//!
+//! - [`preimage::Config`](./trait.Config.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! The Preimage module allows for the users and the runtime to store the preimage
+//! of a hash on chain. This can be used by other pallets for storing and managing
+//! large byte-blobs, such as the encoded calls of proposals and scheduled tasks.
+//!
+//! Preimages noted by a signed origin are backed by a deposit of `BaseDeposit` plus
+//! `ByteDeposit` per byte of the preimage. The deposit is returned once the preimage
+//! is removed through `unnote_preimage`.
+//!
+//! Other pallets interact with this module through the [`PreimageProvider`] and
+//! [`PreimageRecipient`] traits. A preimage that is requested is kept on chain until
+//! all requests for it were dropped again. Requested preimages can be provided by
+//! anyone without paying a deposit or a fee, and requesting a preimage that was already
+//! noted refunds the deposit of the account that noted it.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! * `note_preimage` - Store a preimage on chain.
+//! * `unnote_preimage` - Remove an unrequested preimage and refund its deposit.
+//! * `request_preimage` - Request that a preimage be kept on chain.
+//! * `unrequest_preimage` - Drop a previously made request for a preimage.
+//!
+//! [`Call`]: ./enum.Call.html
+//! [`Config`]: ./trait.Config.html
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod tests;
+mod benchmarking;
+pub mod weights;
+
+use sp_std::prelude::*;
+use codec::{Encode, Decode};
+use sp_runtime::{RuntimeDebug, DispatchResult, traits::{BadOrigin, Hash, Saturating}};
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, ensure,
+	dispatch::{DispatchError, DispatchResultWithPostInfo},
+	traits::{
+		Currency, ReservableCurrency, Get, EnsureOrigin, PreimageProvider, PreimageRecipient,
+	},
+	weights::Pays,
+};
+use frame_system::ensure_signed;
+pub use weights::WeightInfo;
+
+type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// A type to note whether a preimage is owned by a user or the system.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum RequestStatus<AccountId, Balance> {
+	/// The associated preimage has not yet been requested by the system. The given deposit (if
+	/// some) is being held until either it becomes requested or the user retracts the preimage.
+	Unrequested(Option<(AccountId, Balance)>),
+	/// There are a non-zero number of outstanding requests for this hash by this chain. If there
+	/// is a preimage registered, then it may be removed iff this counter becomes zero.
+	Requested(u32),
+}
+
+/// Configuration trait.
+pub trait Config: frame_system::Config {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+	/// Currency type for this pallet.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// An origin that can request a preimage be placed on-chain without a deposit or fee, or
+	/// manage existing preimages.
+	type ManagerOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Max size allowed for a preimage.
+	type MaxSize: Get<u32>;
+
+	/// The base deposit for placing a preimage on chain.
+	type BaseDeposit: Get<BalanceOf<Self>>;
+
+	/// The per-byte deposit for placing a preimage on chain.
+	type ByteDeposit: Get<BalanceOf<Self>>;
+
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as Preimage {
+		/// The request status of a given hash.
+		pub StatusFor get(fn status_for):
+			map hasher(identity) T::Hash => Option<RequestStatus<T::AccountId, BalanceOf<T>>>;
+
+		/// The preimages stored by this pallet.
+		pub PreimageFor get(fn preimage_for): map hasher(identity) T::Hash => Option<Vec<u8>>;
+	}
+}
+
+decl_event! {
+	pub enum Event<T> where <T as frame_system::Config>::Hash {
+		/// A preimage has been noted. \[hash\]
+		Noted(Hash),
+		/// A preimage has been requested. \[hash\]
+		Requested(Hash),
+		/// A preimage has been cleared. \[hash\]
+		Cleared(Hash),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// Preimage is too large to store on-chain.
+		TooLarge,
+		/// Preimage has already been noted on-chain.
+		AlreadyNoted,
+		/// The user is not authorized to perform this action.
+		NotAuthorized,
+		/// The preimage cannot be removed since it has not yet been noted.
+		NotNoted,
+		/// A preimage may not be removed when there are outstanding requests.
+		Requested,
+		/// The preimage request cannot be removed since no outstanding requests exist.
+		NotRequested,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		/// Max size allowed for a preimage.
+		const MaxSize: u32 = T::MaxSize::get();
+
+		/// The base deposit for placing a preimage on chain.
+		const BaseDeposit: BalanceOf<T> = T::BaseDeposit::get();
+
+		/// The per-byte deposit for placing a preimage on chain.
+		const ByteDeposit: BalanceOf<T> = T::ByteDeposit::get();
+
+		fn deposit_event() = default;
+
+		/// Register a preimage on-chain.
+		///
+		/// If the preimage was previously requested, no fees or deposits are taken for providing
+		/// the preimage. Otherwise, a deposit is taken proportional to the size of the preimage.
+		/// No deposit is taken if the origin is the `ManagerOrigin`.
+		///
+		/// Emits `Noted`.
+		#[weight = T::WeightInfo::note_preimage(bytes.len() as u32)]
+		fn note_preimage(origin, bytes: Vec<u8>) -> DispatchResultWithPostInfo {
+			// We accept a signed origin which will pay a deposit, or a manager origin where a
+			// deposit is not taken.
+			let maybe_sender = Self::ensure_signed_or_manager(origin)?;
+			let was_requested = Self::note_bytes(bytes, maybe_sender.as_ref())?;
+			if was_requested {
+				Ok(Pays::No.into())
+			} else {
+				Ok(().into())
+			}
+		}
+
+		/// Clear an unrequested preimage from the runtime storage.
+		///
+		/// A signed origin can only clear the preimages it noted itself and gets its deposit
+		/// refunded. The `ManagerOrigin` can clear any unrequested preimage.
+		///
+		/// Emits `Cleared`.
+		#[weight = T::WeightInfo::unnote_preimage()]
+		fn unnote_preimage(origin, hash: T::Hash) {
+			let maybe_sender = Self::ensure_signed_or_manager(origin)?;
+			Self::do_unnote_preimage(&hash, maybe_sender)?;
+		}
+
+		/// Request a preimage be uploaded to the chain without paying any fees or deposits.
+		///
+		/// If the preimage requests has already been provided on-chain, we unreserve any deposit
+		/// a user may have paid, and take the control of the preimage out of their hands.
+		///
+		/// The dispatch origin of this call must be `ManagerOrigin`.
+		#[weight = T::WeightInfo::request_preimage()]
+		fn request_preimage(origin, hash: T::Hash) {
+			T::ManagerOrigin::ensure_origin(origin)?;
+			Self::do_request_preimage(&hash);
+		}
+
+		/// Clear a previously made request for a preimage.
+		///
+		/// The preimage is removed once no requests for it are left.
+		///
+		/// The dispatch origin of this call must be `ManagerOrigin`.
+		#[weight = T::WeightInfo::unrequest_preimage()]
+		fn unrequest_preimage(origin, hash: T::Hash) {
+			T::ManagerOrigin::ensure_origin(origin)?;
+			Self::do_unrequest_preimage(&hash)?;
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// Ensure that the origin is either the `ManagerOrigin` or a signed origin.
+	///
+	/// Returns `None` for the `ManagerOrigin` and the signer otherwise.
+	fn ensure_signed_or_manager(origin: T::Origin) -> Result<Option<T::AccountId>, BadOrigin> {
+		if T::ManagerOrigin::try_origin(origin.clone()).is_ok() {
+			return Ok(None)
+		}
+		let who = ensure_signed(origin)?;
+		Ok(Some(who))
+	}
+
+	/// Store some preimage on chain.
+	///
+	/// We verify that the preimage is within the bounds of what the pallet supports.
+	///
+	/// If the preimage was requested to be uploaded, then the user pays no deposits or tx fees.
+	fn note_bytes(
+		preimage: Vec<u8>,
+		maybe_depositor: Option<&T::AccountId>,
+	) -> Result<bool, DispatchError> {
+		ensure!(preimage.len() <= T::MaxSize::get() as usize, Error::<T>::TooLarge);
+		let hash = T::Hashing::hash(&preimage);
+		ensure!(!PreimageFor::<T>::contains_key(hash), Error::<T>::AlreadyNoted);
+
+		// We take a deposit only if there is a provided depositor, and the preimage was not
+		// previously requested. This also allows the tx to pay no fee.
+		let was_requested = match (StatusFor::<T>::get(hash), maybe_depositor) {
+			(Some(RequestStatus::Requested(..)), _) => true,
+			(Some(RequestStatus::Unrequested(..)), _) => Err(Error::<T>::AlreadyNoted)?,
+			(None, None) => {
+				StatusFor::<T>::insert(hash, RequestStatus::Unrequested(None));
+				false
+			},
+			(None, Some(depositor)) => {
+				let length = preimage.len() as u32;
+				let deposit = T::BaseDeposit::get()
+					.saturating_add(T::ByteDeposit::get().saturating_mul(length.into()));
+				T::Currency::reserve(depositor, deposit)?;
+				let status = RequestStatus::Unrequested(Some((depositor.clone(), deposit)));
+				StatusFor::<T>::insert(hash, status);
+				false
+			},
+		};
+
+		PreimageFor::<T>::insert(hash, preimage);
+		Self::deposit_event(RawEvent::Noted(hash));
+
+		Ok(was_requested)
+	}
+
+	/// Clear a preimage from the storage of the chain, returning any deposit that may be reserved.
+	///
+	/// If `maybe_check_owner` is provided, we verify that it is the correct owner before clearing
+	/// the data.
+	fn do_unnote_preimage(
+		hash: &T::Hash,
+		maybe_check_owner: Option<T::AccountId>,
+	) -> DispatchResult {
+		match StatusFor::<T>::get(hash).ok_or(Error::<T>::NotNoted)? {
+			RequestStatus::Unrequested(Some((owner, deposit))) => {
+				ensure!(maybe_check_owner.map_or(true, |c| c == owner), Error::<T>::NotAuthorized);
+				T::Currency::unreserve(&owner, deposit);
+			},
+			RequestStatus::Unrequested(None) => {
+				ensure!(maybe_check_owner.is_none(), Error::<T>::NotAuthorized);
+			},
+			RequestStatus::Requested(_) => Err(Error::<T>::Requested)?,
+		}
+		StatusFor::<T>::remove(hash);
+		PreimageFor::<T>::remove(hash);
+		Self::deposit_event(RawEvent::Cleared(*hash));
+		Ok(())
+	}
+
+	/// Request that someone report a preimage. Providers use this to optimise the economics for
+	/// preimage reporting.
+	fn do_request_preimage(hash: &T::Hash) {
+		let count = StatusFor::<T>::mutate(hash, |maybe_status| {
+			match maybe_status {
+				Some(RequestStatus::Requested(n)) => {
+					*n = n.saturating_add(1);
+					*n
+				},
+				Some(RequestStatus::Unrequested(maybe_deposit)) => {
+					if let Some((owner, deposit)) = maybe_deposit.take() {
+						T::Currency::unreserve(&owner, deposit);
+					}
+					*maybe_status = Some(RequestStatus::Requested(1));
+					1
+				},
+				None => {
+					*maybe_status = Some(RequestStatus::Requested(1));
+					1
+				},
+			}
+		});
+		if count == 1 {
+			Self::deposit_event(RawEvent::Requested(*hash));
+		}
+	}
+
+	/// Clear a preimage request.
+	///
+	/// The preimage itself is removed once the last request for it is cleared.
+	fn do_unrequest_preimage(hash: &T::Hash) -> DispatchResult {
+		let count = StatusFor::<T>::try_mutate_exists(hash, |maybe_status| {
+			match maybe_status {
+				Some(RequestStatus::Requested(n)) if *n > 1 => {
+					*n -= 1;
+					Ok(*n)
+				},
+				Some(RequestStatus::Requested(_)) => {
+					*maybe_status = None;
+					Ok(0)
+				},
+				_ => Err(Error::<T>::NotRequested),
+			}
+		})?;
+		if count == 0 {
+			PreimageFor::<T>::remove(hash);
+			Self::deposit_event(RawEvent::Cleared(*hash));
+		}
+		Ok(())
+	}
+}
+
+impl<T: Config> PreimageProvider<T::Hash> for Module<T> {
+	fn have_preimage(hash: &T::Hash) -> bool {
+		PreimageFor::<T>::contains_key(hash)
+	}
+
+	fn get_preimage(hash: &T::Hash) -> Option<Vec<u8>> {
+		PreimageFor::<T>::get(hash)
+	}
+
+	fn preimage_requested(hash: &T::Hash) -> bool {
+		matches!(StatusFor::<T>::get(hash), Some(RequestStatus::Requested(..)))
+	}
+
+	fn request_preimage(hash: &T::Hash) {
+		Self::do_request_preimage(hash)
+	}
+
+	fn unrequest_preimage(hash: &T::Hash) {
+		let res = Self::do_unrequest_preimage(hash);
+		debug_assert!(res.is_ok(), "do_unrequest_preimage failed - counter underflow?");
+	}
+}
+
+impl<T: Config> PreimageRecipient<T::Hash> for Module<T> {
+	type MaxSize = T::MaxSize;
+
+	fn note_preimage(bytes: Vec<u8>) {
+		// Note that this will fail silently if the preimage is too large or already noted.
+		let _ = Self::note_bytes(bytes, None);
+	}
+
+	fn unnote_preimage(hash: &T::Hash) {
+		// Requested preimages and preimages that were never noted are left untouched.
+		let _ = Self::do_unnote_preimage(hash, None);
+	}
+}
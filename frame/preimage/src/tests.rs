@@ -0,0 +1,352 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Tests for Preimage Pallet
+
+#![cfg(test)]
+
+use super::*;
+
+use frame_support::{
+	assert_ok, assert_noop, impl_outer_origin, impl_outer_event, parameter_types,
+	weights::Pays,
+};
+use frame_system::EnsureSignedBy;
+use sp_core::H256;
+use sp_runtime::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+use crate as preimage;
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		frame_system<T>,
+		pallet_balances<T>,
+		preimage<T>,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Call = ();
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const One: u64 = 1;
+	pub const MaxSize: u32 = 1024;
+	pub const BaseDeposit: u64 = 2;
+	pub const ByteDeposit: u64 = 1;
+}
+impl Config for Test {
+	type Event = TestEvent;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureSignedBy<One, u64>;
+	type MaxSize = MaxSize;
+	type BaseDeposit = BaseDeposit;
+	type ByteDeposit = ByteDeposit;
+	type WeightInfo = ();
+}
+
+type System = frame_system::Module<Test>;
+type Balances = pallet_balances::Module<Test>;
+type Preimage = Module<Test>;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 100), (2, 100), (3, 100)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+fn hashed(data: impl AsRef<[u8]>) -> H256 {
+	BlakeTwo256::hash(data.as_ref())
+}
+
+#[test]
+fn user_note_preimage_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		assert_eq!(Balances::reserved_balance(2), 3);
+		assert_eq!(Balances::free_balance(2), 97);
+
+		let h = hashed([1]);
+		assert!(Preimage::have_preimage(&h));
+		assert_eq!(Preimage::get_preimage(&h), Some(vec![1]));
+
+		assert_noop!(
+			Preimage::note_preimage(Origin::signed(2), vec![1]),
+			Error::<Test>::AlreadyNoted
+		);
+		assert_noop!(
+			Preimage::note_preimage(Origin::signed(0), vec![2]),
+			pallet_balances::Error::<Test, _>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn manager_note_preimage_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(1), vec![1]));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+
+		let h = hashed([1]);
+		assert!(Preimage::have_preimage(&h));
+		assert_eq!(Preimage::get_preimage(&h), Some(vec![1]));
+
+		assert_noop!(
+			Preimage::note_preimage(Origin::signed(1), vec![1]),
+			Error::<Test>::AlreadyNoted
+		);
+	});
+}
+
+#[test]
+fn preimages_larger_than_max_size_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Preimage::note_preimage(Origin::signed(1), vec![0; MaxSize::get() as usize + 1]),
+			Error::<Test>::TooLarge
+		);
+	});
+}
+
+#[test]
+fn user_unnote_preimage_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		assert_noop!(
+			Preimage::unnote_preimage(Origin::signed(3), hashed([1])),
+			Error::<Test>::NotAuthorized
+		);
+		assert_noop!(
+			Preimage::unnote_preimage(Origin::signed(2), hashed([2])),
+			Error::<Test>::NotNoted
+		);
+
+		assert_ok!(Preimage::unnote_preimage(Origin::signed(2), hashed([1])));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 100);
+
+		let h = hashed([1]);
+		assert!(!Preimage::have_preimage(&h));
+		assert_eq!(Preimage::get_preimage(&h), None);
+	});
+}
+
+#[test]
+fn manager_unnote_preimage_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(1), vec![1]));
+		assert_ok!(Preimage::unnote_preimage(Origin::signed(1), hashed([1])));
+
+		let h = hashed([1]);
+		assert!(!Preimage::have_preimage(&h));
+		assert_eq!(Preimage::get_preimage(&h), None);
+	});
+}
+
+#[test]
+fn manager_unnote_user_preimage_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		assert_ok!(Preimage::unnote_preimage(Origin::signed(1), hashed([1])));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 100);
+
+		let h = hashed([1]);
+		assert!(!Preimage::have_preimage(&h));
+	});
+}
+
+#[test]
+fn requested_then_noted_preimage_cannot_be_unnoted() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(1), vec![1]));
+		assert_ok!(Preimage::request_preimage(Origin::signed(1), hashed([1])));
+		assert_noop!(
+			Preimage::unnote_preimage(Origin::signed(1), hashed([1])),
+			Error::<Test>::Requested
+		);
+
+		let h = hashed([1]);
+		assert!(Preimage::have_preimage(&h));
+		assert!(Preimage::preimage_requested(&h));
+	});
+}
+
+#[test]
+fn request_note_order_makes_no_difference() {
+	let one_way = new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::request_preimage(Origin::signed(1), hashed([1])));
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		(StatusFor::<Test>::iter().collect::<Vec<_>>(), PreimageFor::<Test>::iter().collect::<Vec<_>>())
+	});
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		assert_ok!(Preimage::request_preimage(Origin::signed(1), hashed([1])));
+		let other_way = (
+			StatusFor::<Test>::iter().collect::<Vec<_>>(),
+			PreimageFor::<Test>::iter().collect::<Vec<_>>(),
+		);
+		assert_eq!(one_way, other_way);
+	});
+}
+
+#[test]
+fn requested_then_user_noted_preimage_is_free() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::request_preimage(Origin::signed(1), hashed([1])));
+		let post_info = Preimage::note_preimage(Origin::signed(2), vec![1]).unwrap();
+		assert_eq!(post_info.pays_fee, Pays::No);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 100);
+
+		let h = hashed([1]);
+		assert!(Preimage::have_preimage(&h));
+		assert_eq!(Preimage::get_preimage(&h), Some(vec![1]));
+	});
+}
+
+#[test]
+fn request_user_note_order_makes_no_difference() {
+	let one_way = new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::request_preimage(Origin::signed(1), hashed([1])));
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		(StatusFor::<Test>::iter().collect::<Vec<_>>(), PreimageFor::<Test>::iter().collect::<Vec<_>>())
+	});
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		assert_ok!(Preimage::request_preimage(Origin::signed(1), hashed([1])));
+		let other_way = (
+			StatusFor::<Test>::iter().collect::<Vec<_>>(),
+			PreimageFor::<Test>::iter().collect::<Vec<_>>(),
+		);
+		assert_eq!(one_way, other_way);
+		// Requesting the preimage refunded the deposit of the user.
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 100);
+	});
+}
+
+#[test]
+fn unrequest_preimage_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::request_preimage(Origin::signed(1), hashed([1])));
+		assert_ok!(Preimage::request_preimage(Origin::signed(1), hashed([1])));
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		assert_noop!(
+			Preimage::unrequest_preimage(Origin::signed(1), hashed([2])),
+			Error::<Test>::NotRequested
+		);
+
+		assert_ok!(Preimage::unrequest_preimage(Origin::signed(1), hashed([1])));
+		assert!(Preimage::have_preimage(&hashed([1])));
+
+		assert_ok!(Preimage::unrequest_preimage(Origin::signed(1), hashed([1])));
+		assert!(!Preimage::have_preimage(&hashed([1])));
+		assert_eq!(StatusFor::<Test>::get(hashed([1])), None);
+	});
+}
+
+#[test]
+fn user_noted_then_requested_preimage_is_refunded_once_only() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1; 3]));
+		assert_ok!(Preimage::note_preimage(Origin::signed(2), vec![1]));
+		assert_ok!(Preimage::request_preimage(Origin::signed(1), hashed([1])));
+		assert_ok!(Preimage::unrequest_preimage(Origin::signed(1), hashed([1])));
+		// Only the deposit for the preimage of size 3 is left reserved.
+		assert_eq!(Balances::reserved_balance(2), 5);
+		assert_eq!(Balances::free_balance(2), 95);
+	});
+}
+
+#[test]
+fn only_manager_can_request_preimages() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Preimage::request_preimage(Origin::signed(2), hashed([1])),
+			sp_runtime::traits::BadOrigin
+		);
+		assert_noop!(
+			Preimage::unrequest_preimage(Origin::signed(2), hashed([1])),
+			sp_runtime::traits::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn preimage_recipient_works() {
+	new_test_ext().execute_with(|| {
+		let h = hashed([1]);
+		<Preimage as PreimageRecipient<_>>::note_preimage(vec![1]);
+		assert!(Preimage::have_preimage(&h));
+		assert!(!Preimage::preimage_requested(&h));
+
+		// Requested preimages can't be removed through the trait.
+		<Preimage as PreimageProvider<_>>::request_preimage(&h);
+		<Preimage as PreimageRecipient<_>>::unnote_preimage(&h);
+		assert!(Preimage::have_preimage(&h));
+
+		<Preimage as PreimageProvider<_>>::unrequest_preimage(&h);
+		assert!(!Preimage::have_preimage(&h));
+	});
+}
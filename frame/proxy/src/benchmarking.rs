@@ -97,6 +97,26 @@ benchmarks! {
 		assert_last_event::<T>(RawEvent::ProxyExecuted(Ok(())).into())
 	}
 
+	proxy_limited {
+		let p in ...;
+		let caller: T::AccountId = account("limited", 0, SEED);
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let real: T::AccountId = whitelisted_caller();
+		let limit = SpendLimit { amount: BalanceOf::<T>::max_value(), period: T::BlockNumber::zero() };
+		Proxy::<T>::add_limited_proxy(
+			RawOrigin::Signed(real.clone()).into(),
+			caller.clone(),
+			T::ProxyType::default(),
+			T::BlockNumber::zero(),
+			Some(T::BlockNumber::max_value()),
+			Some(limit),
+		)?;
+		let call: <T as Config>::Call = frame_system::Call::<T>::remark(vec![]).into();
+	}: proxy(RawOrigin::Signed(caller), real, Some(T::ProxyType::default()), Box::new(call))
+	verify {
+		assert_last_event::<T>(RawEvent::ProxyExecuted(Ok(())).into())
+	}
+
 	proxy_announced {
 		let a in 0 .. T::MaxPending::get() - 1;
 		let p in ...;
@@ -190,6 +210,23 @@ benchmarks! {
 		assert_eq!(proxies.len() as u32, p + 1);
 	}
 
+	add_limited_proxy {
+		let p in ...;
+		let caller: T::AccountId = whitelisted_caller();
+		let limit = SpendLimit { amount: BalanceOf::<T>::max_value(), period: T::BlockNumber::max_value() };
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		account("target", T::MaxProxies::get().into(), SEED),
+		T::ProxyType::default(),
+		T::BlockNumber::zero(),
+		Some(T::BlockNumber::max_value()),
+		Some(limit)
+	)
+	verify {
+		let (proxies, _) = Proxies::<T>::get(caller);
+		assert_eq!(proxies.len() as u32, p + 1);
+	}
+
 	remove_proxy {
 		let p in ...;
 		let caller: T::AccountId = whitelisted_caller();
@@ -265,11 +302,13 @@ mod tests {
 	fn test_benchmarks() {
 		new_test_ext().execute_with(|| {
 			assert_ok!(test_benchmark_proxy::<Test>());
+			assert_ok!(test_benchmark_proxy_limited::<Test>());
 			assert_ok!(test_benchmark_proxy_announced::<Test>());
 			assert_ok!(test_benchmark_remove_announcement::<Test>());
 			assert_ok!(test_benchmark_reject_announcement::<Test>());
 			assert_ok!(test_benchmark_announce::<Test>());
 			assert_ok!(test_benchmark_add_proxy::<Test>());
+			assert_ok!(test_benchmark_add_limited_proxy::<Test>());
 			assert_ok!(test_benchmark_remove_proxy::<Test>());
 			assert_ok!(test_benchmark_remove_proxies::<Test>());
 			assert_ok!(test_benchmark_anonymous::<Test>());
@@ -23,6 +23,11 @@
 //! wish to execute some duration prior to execution happens. In this case, the target account may
 //! reject the announcement and in doing so, veto the execution.
 //!
+//! A proxy may also be limited with `add_limited_proxy`: it may expire at a given block, and it may
+//! have a spending allowance which caps how much the calls it makes may reduce the balance of the
+//! proxied account in each period. Expired proxies are removed, and their deposit returned, the
+//! next time the proxies of the account are used or changed.
+//!
 //! - [`proxy::Config`](./trait.Config.html)
 //! - [`Call`](./enum.Call.html)
 //!
@@ -42,14 +47,19 @@ mod tests;
 mod benchmarking;
 pub mod weights;
 
-use sp_std::prelude::*;
+use sp_std::{prelude::*, cmp::Ordering};
 use codec::{Encode, Decode};
 use sp_io::hashing::blake2_256;
-use sp_runtime::{DispatchResult, traits::{Dispatchable, Zero, Hash, Member, Saturating}};
+use sp_runtime::{
+	DispatchResult, TransactionOutcome,
+	traits::{Dispatchable, Zero, Hash, Member, Saturating, AtLeast32BitUnsigned},
+};
 use frame_support::{
 	decl_module, decl_event, decl_error, decl_storage, Parameter, ensure, RuntimeDebug, traits::{
-		Get, ReservableCurrency, Currency, InstanceFilter, OriginTrait, IsType, IsSubType,
-	}, weights::{Weight, GetDispatchInfo}, dispatch::PostDispatchInfo, storage::IterableStorageMap,
+		Get, NamedReservableCurrency, ReserveIdentifier, Currency, InstanceFilter, OriginTrait,
+		IsType, IsSubType, BalanceStatus, MigrateAccount,
+	}, weights::{Weight, GetDispatchInfo}, dispatch::PostDispatchInfo,
+	storage::{IterableStorageMap, with_transaction},
 };
 use frame_system::{self as system, ensure_signed};
 use frame_support::dispatch::DispatchError;
@@ -68,7 +78,7 @@ pub trait Config: frame_system::Config {
 		+ IsType<<Self as frame_system::Config>::Call>;
 
 	/// The currency mechanism.
-	type Currency: ReservableCurrency<Self::AccountId>;
+	type Currency: NamedReservableCurrency<Self::AccountId, ReserveIdentifier = ReserveIdentifier>;
 
 	/// A kind of proxy; specified with the proxy and passed in to the `IsProxyable` fitler.
 	/// The instance filter determines whether a given call may be proxied under this type.
@@ -89,6 +99,13 @@ pub trait Config: frame_system::Config {
 	/// storage value.
 	type ProxyDepositFactor: Get<BalanceOf<Self>>;
 
+	/// The additional amount of currency needed per proxy which has an expiry or a spending
+	/// allowance.
+	///
+	/// This is held for the expiry `BlockNumber` and the allowance, which is two `Balance`s and
+	/// two `BlockNumber`s, that such a proxy adds to the storage value.
+	type ProxyLimitDepositFactor: Get<BalanceOf<Self>>;
+
 	/// The maximum amount of proxies allowed for a single account.
 	type MaxProxies: Get<u16>;
 
@@ -113,10 +130,64 @@ pub trait Config: frame_system::Config {
 	type AnnouncementDepositFactor: Get<BalanceOf<Self>>;
 }
 
+/// The most that a proxy may spend from the account it acts for.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, RuntimeDebug)]
+pub struct SpendLimit<Balance, BlockNumber> {
+	/// The amount by which the calls of the proxy may reduce the balance of the account in one
+	/// period.
+	pub amount: Balance,
+	/// The length of a period in blocks. Periods start at multiples of this length; if it is
+	/// zero, the allowance never resets.
+	pub period: BlockNumber,
+}
+
+/// A spending allowance of a proxy, together with how much of it is used up.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, RuntimeDebug)]
+pub struct Allowance<Balance, BlockNumber> {
+	/// The limit of the allowance.
+	limit: SpendLimit<Balance, BlockNumber>,
+	/// The first block of the period in which `spent` was spent.
+	period_start: BlockNumber,
+	/// The amount spent in the period which starts at `period_start`.
+	spent: Balance,
+}
+
+impl<Balance, BlockNumber> Allowance<Balance, BlockNumber> where
+	Balance: AtLeast32BitUnsigned + Copy,
+	BlockNumber: AtLeast32BitUnsigned + Copy,
+{
+	fn new(limit: SpendLimit<Balance, BlockNumber>) -> Self {
+		Allowance { limit, period_start: Zero::zero(), spent: Zero::zero() }
+	}
+
+	/// Record `amount` as spent at block `now`.
+	///
+	/// Returns `false`, leaving the allowance unchanged, if `amount` is more than what remains of
+	/// the allowance in the current period.
+	fn spend(&mut self, amount: Balance, now: BlockNumber) -> bool {
+		let period_start = if self.limit.period.is_zero() {
+			Zero::zero()
+		} else {
+			now - now % self.limit.period
+		};
+		let spent = if period_start == self.period_start { self.spent } else { Zero::zero() };
+		let spent = spent.saturating_add(amount);
+		if spent > self.limit.amount {
+			return false;
+		}
+		self.period_start = period_start;
+		self.spent = spent;
+		true
+	}
+}
+
 /// The parameters under which a particular account has a proxy relationship with some other
 /// account.
+///
+/// An account has at most one proxy definition for each combination of delegate, proxy type and
+/// delay.
 #[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, RuntimeDebug)]
-pub struct ProxyDefinition<AccountId, ProxyType, BlockNumber> {
+pub struct ProxyDefinition<AccountId, ProxyType, BlockNumber, Balance> {
 	/// The account which may act on behalf of another.
 	delegate: AccountId,
 	/// A value defining the subset of calls that it is allowed to make.
@@ -124,8 +195,42 @@ pub struct ProxyDefinition<AccountId, ProxyType, BlockNumber> {
 	/// The number of blocks that an announcement must be in place for before the corresponding call
 	/// may be dispatched. If zero, then no announcement is needed.
 	delay: BlockNumber,
+	/// The block at which the proxy expires, if any.
+	expiry: Option<BlockNumber>,
+	/// The spending allowance of the proxy, if any.
+	allowance: Option<Allowance<Balance, BlockNumber>>,
 }
 
+impl<AccountId, ProxyType, BlockNumber, Balance>
+	ProxyDefinition<AccountId, ProxyType, BlockNumber, Balance>
+where
+	AccountId: Ord,
+	ProxyType: Ord,
+	BlockNumber: Ord,
+{
+	/// Compare the delegate, proxy type and delay of the definition with the given ones.
+	fn cmp_key(&self, delegate: &AccountId, proxy_type: &ProxyType, delay: &BlockNumber) -> Ordering {
+		(&self.delegate, &self.proxy_type, &self.delay).cmp(&(delegate, proxy_type, delay))
+	}
+
+	/// Whether the proxy has expired at block `now`.
+	fn is_expired(&self, now: &BlockNumber) -> bool {
+		self.expiry.as_ref().map_or(false, |expiry| now >= expiry)
+	}
+
+	/// Whether the proxy has an expiry or a spending allowance.
+	fn is_limited(&self) -> bool {
+		self.expiry.is_some() || self.allowance.is_some()
+	}
+}
+
+type ProxyDefinitionOf<T> = ProxyDefinition<
+	<T as frame_system::Config>::AccountId,
+	<T as Config>::ProxyType,
+	<T as frame_system::Config>::BlockNumber,
+	BalanceOf<T>,
+>;
+
 /// Details surrounding a specific instance of an announcement to make a call.
 #[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
 pub struct Announcement<AccountId, Hash, BlockNumber> {
@@ -139,16 +244,34 @@ pub struct Announcement<AccountId, Hash, BlockNumber> {
 
 type CallHashOf<T> = <<T as Config>::CallHasher as Hash>::Output;
 
+/// The identifier of the reserve holding the deposits for proxies and announcements.
+pub const PROXY_ID: ReserveIdentifier = *b"proxy   ";
+
+// A value placed in storage that represents the current version of the Proxy storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+enum Releases {
+	V1,
+	V2,
+	V3,
+}
+
 decl_storage! {
 	trait Store for Module<T: Config> as Proxy {
 		/// The set of account proxies. Maps the account which has delegated to the accounts
 		/// which are being delegated to, together with the amount held on deposit.
 		pub Proxies get(fn proxies): map hasher(twox_64_concat) T::AccountId
-			=> (Vec<ProxyDefinition<T::AccountId, T::ProxyType, T::BlockNumber>>, BalanceOf<T>);
+			=> (Vec<ProxyDefinitionOf<T>>, BalanceOf<T>);
 
 		/// The announcements made by the proxy (key).
 		pub Announcements get(fn announcements): map hasher(twox_64_concat) T::AccountId
 			=> (Vec<Announcement<T::AccountId, CallHashOf<T>, T::BlockNumber>>, BalanceOf<T>);
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with last version.
+		StorageVersion build(|_| Some(Releases::V3)): Option<Releases>;
 	}
 }
 
@@ -168,6 +291,10 @@ decl_error! {
 		NoPermission,
 		/// Announcement, if made at all, was made too recently.
 		Unannounced,
+		/// The proxy has expired, or the given expiry is not in the future.
+		Expired,
+		/// The call spent more than what remains of the spending allowance of the proxy.
+		OverAllowance,
 	}
 }
 
@@ -185,6 +312,9 @@ decl_event! {
 		AnonymousCreated(AccountId, AccountId, ProxyType, u16),
 		/// An announcement was placed to make a call in the future. \[real, proxy, call_hash\]
 		Announced(AccountId, AccountId, Hash),
+		/// The proxies of an account were merged into those of another account, some of them
+		/// being dropped. \[from, to, moved, dropped\]
+		ProxiesMigrated(AccountId, AccountId, u32, u32),
 	}
 }
 
@@ -201,6 +331,9 @@ decl_module! {
 		/// The amount of currency needed per proxy added.
 		const ProxyDepositFactor: BalanceOf<T> = T::ProxyDepositFactor::get();
 
+		/// The additional amount of currency needed per proxy with an expiry or an allowance.
+		const ProxyLimitDepositFactor: BalanceOf<T> = T::ProxyLimitDepositFactor::get();
+
 		/// The maximum amount of proxies allowed for a single account.
 		const MaxProxies: u16 = T::MaxProxies::get();
 
@@ -213,11 +346,20 @@ decl_module! {
 		/// `AnnouncementDepositFactor` metadata shadow.
 		const AnnouncementDepositFactor: BalanceOf<T> = T::AnnouncementDepositFactor::get();
 
+		fn on_runtime_upgrade() -> Weight {
+			migration::migrate_to_limited_proxies::<T>()
+				.saturating_add(migration::migrate_to_named_reserves::<T>())
+		}
+
 		/// Dispatch the given `call` from an account that the sender is authorised for through
-		/// `add_proxy`.
+		/// `add_proxy` or `add_limited_proxy`.
 		///
 		/// Removes any corresponding announcement(s).
 		///
+		/// If the proxy has a spending allowance and the call reduces the balance of `real` by more
+		/// than what remains of it, the call is reverted and `ProxyExecuted` reports
+		/// `OverAllowance`.
+		///
 		/// The dispatch origin for this call must be _Signed_.
 		///
 		/// Parameters:
@@ -230,7 +372,8 @@ decl_module! {
 		/// # </weight>
 		#[weight = {
 			let di = call.get_dispatch_info();
-			(T::WeightInfo::proxy(T::MaxProxies::get().into())
+			let p = T::MaxProxies::get().into();
+			(T::WeightInfo::proxy(p).max(T::WeightInfo::proxy_limited(p))
 				.saturating_add(di.weight)
 				 // AccountData for inner call origin accountdata.
 				.saturating_add(T::DbWeight::get().reads_writes(1, 1)),
@@ -245,6 +388,7 @@ decl_module! {
 			let def = Self::find_proxy(&real, &who, force_proxy_type)?;
 			ensure!(def.delay.is_zero(), Error::<T>::Unannounced);
 
+			Self::remove_expired(&real);
 			Self::do_proxy(def, real, *call);
 		}
 
@@ -306,7 +450,7 @@ decl_module! {
 		fn remove_proxies(origin) {
 			let who = ensure_signed(origin)?;
 			let (_, old_deposit) = Proxies::<T>::take(&who);
-			T::Currency::unreserve(&who, old_deposit);
+			T::Currency::unreserve_named(&PROXY_ID, &who, old_deposit);
 		}
 
 		/// Spawn a fresh new account that is guaranteed to be otherwise inaccessible, and
@@ -338,12 +482,14 @@ decl_module! {
 
 			let anonymous = Self::anonymous_account(&who, &proxy_type, index, None);
 			ensure!(!Proxies::<T>::contains_key(&anonymous), Error::<T>::Duplicate);
-			let deposit = T::ProxyDepositBase::get() + T::ProxyDepositFactor::get();
-			T::Currency::reserve(&who, deposit)?;
+			let deposit = Self::deposit(1, 0);
+			T::Currency::reserve_named(&PROXY_ID, &who, deposit)?;
 			let proxy_def = ProxyDefinition {
 				delegate: who.clone(),
 				proxy_type: proxy_type.clone(),
 				delay,
+				expiry: None,
+				allowance: None,
 			};
 			Proxies::<T>::insert(&anonymous, (vec![proxy_def], deposit));
 			Self::deposit_event(RawEvent::AnonymousCreated(anonymous, who, proxy_type, index));
@@ -384,7 +530,7 @@ decl_module! {
 			ensure!(proxy == who, Error::<T>::NoPermission);
 
 			let (_, deposit) = Proxies::<T>::take(&who);
-			T::Currency::unreserve(&spawner, deposit);
+			T::Currency::unreserve_named(&PROXY_ID, &spawner, deposit);
 		}
 
 		/// Publish the hash of a proxy-call that will be made in the future.
@@ -411,9 +557,7 @@ decl_module! {
 		#[weight = T::WeightInfo::announce(T::MaxPending::get(), T::MaxProxies::get().into())]
 		fn announce(origin, real: T::AccountId, call_hash: CallHashOf<T>) {
 			let who = ensure_signed(origin)?;
-			Proxies::<T>::get(&real).0.into_iter()
-				.find(|x| &x.delegate == &who)
-				.ok_or(Error::<T>::NotProxy)?;
+			Self::find_proxy(&real, &who, None)?;
 
 			let announcement = Announcement {
 				real: real.clone(),
@@ -433,6 +577,7 @@ decl_module! {
 				).map(|d| d.expect("Just pushed; pending.len() > 0; rejig_deposit returns Some; qed"))
 				.map(|d| *deposit = d)
 			})?;
+			Self::remove_expired(&real);
 			Self::deposit_event(RawEvent::Announced(real, who, call_hash));
 		}
 
@@ -481,10 +626,12 @@ decl_module! {
 		}
 
 		/// Dispatch the given `call` from an account that the sender is authorised for through
-		/// `add_proxy`.
+		/// `add_proxy` or `add_limited_proxy`.
 		///
 		/// Removes any corresponding announcement(s).
 		///
+		/// The spending allowance of the proxy, if any, applies as for `proxy`.
+		///
 		/// The dispatch origin for this call must be _Signed_.
 		///
 		/// Parameters:
@@ -499,7 +646,10 @@ decl_module! {
 		/// # </weight>
 		#[weight = {
 			let di = call.get_dispatch_info();
-			(T::WeightInfo::proxy_announced(T::MaxPending::get(), T::MaxProxies::get().into())
+			let p = T::MaxProxies::get().into();
+			(T::WeightInfo::proxy_announced(T::MaxPending::get(), p)
+				// The additional cost of a proxy with an allowance.
+				.saturating_add(T::WeightInfo::proxy_limited(p).saturating_sub(T::WeightInfo::proxy(p)))
 				.saturating_add(di.weight)
 				 // AccountData for inner call origin accountdata.
 				.saturating_add(T::DbWeight::get().reads_writes(1, 1)),
@@ -520,8 +670,43 @@ decl_module! {
 				ann.real != real || ann.call_hash != call_hash || now.saturating_sub(ann.height) < def.delay
 			).map_err(|_| Error::<T>::Unannounced)?;
 
+			Self::remove_expired(&real);
 			Self::do_proxy(def, real, *call);
 		}
+
+		/// Register a proxy account for the sender that is able to make calls on its behalf until
+		/// it expires, and spend at most a given amount per period.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// A limited proxy may not add or remove proxies of the sender. Besides the deposit of
+		/// `add_proxy`, this reserves `ProxyLimitDepositFactor`, which is returned when the proxy
+		/// is removed, or once it has expired and the proxies of the sender are next used or
+		/// changed.
+		///
+		/// Parameters:
+		/// - `proxy`: The account that the `caller` would like to make a proxy.
+		/// - `proxy_type`: The permissions allowed for this proxy account.
+		/// - `delay`: The announcement period required of the initial proxy. Will generally be
+		/// zero.
+		/// - `expiry`: The block from which the proxy may no longer be used, if any.
+		/// - `limit`: The most that the calls of the proxy may reduce the balance of the sender
+		/// per period, if anything.
+		///
+		/// # <weight>
+		/// Weight is a function of the number of proxies the user has (P).
+		/// # </weight>
+		#[weight = T::WeightInfo::add_limited_proxy(T::MaxProxies::get().into())]
+		fn add_limited_proxy(origin,
+			delegate: T::AccountId,
+			proxy_type: T::ProxyType,
+			delay: T::BlockNumber,
+			expiry: Option<T::BlockNumber>,
+			limit: Option<SpendLimit<BalanceOf<T>, T::BlockNumber>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::add_limited_proxy_delegate(&who, delegate, proxy_type, delay, expiry, limit)
+		}
 	}
 }
 
@@ -567,18 +752,41 @@ impl<T: Config> Module<T> {
 		proxy_type: T::ProxyType,
 		delay: T::BlockNumber,
 	) -> DispatchResult {
+		Self::add_limited_proxy_delegate(delegator, delegatee, proxy_type, delay, None, None)
+	}
+
+	/// Register a proxy account for the delegator that is able to make calls on its behalf until
+	/// it expires, and spend at most a given amount per period.
+	///
+	/// Parameters:
+	/// - `delegator`: The delegator account.
+	/// - `delegatee`: The account that the `delegator` would like to make a proxy.
+	/// - `proxy_type`: The permissions allowed for this proxy account.
+	/// - `delay`: The announcement period required of the initial proxy. Will generally be
+	/// zero.
+	/// - `expiry`: The block from which the proxy may no longer be used, if any.
+	/// - `limit`: The most that the calls of the proxy may reduce the balance of the delegator
+	/// per period, if anything.
+	pub fn add_limited_proxy_delegate(
+		delegator: &T::AccountId,
+		delegatee: T::AccountId,
+		proxy_type: T::ProxyType,
+		delay: T::BlockNumber,
+		expiry: Option<T::BlockNumber>,
+		limit: Option<SpendLimit<BalanceOf<T>, T::BlockNumber>>,
+	) -> DispatchResult {
+		let now = system::Module::<T>::block_number();
+		ensure!(expiry.map_or(true, |expiry| expiry > now), Error::<T>::Expired);
 		Proxies::<T>::try_mutate(delegator, |(ref mut proxies, ref mut deposit)| {
+			proxies.retain(|x| !x.is_expired(&now));
 			ensure!(proxies.len() < T::MaxProxies::get() as usize, Error::<T>::TooMany);
-			let proxy_def = ProxyDefinition { delegate: delegatee, proxy_type, delay };
-			let i = proxies.binary_search(&proxy_def).err().ok_or(Error::<T>::Duplicate)?;
+			let i = proxies.binary_search_by(|x| x.cmp_key(&delegatee, &proxy_type, &delay))
+				.err()
+				.ok_or(Error::<T>::Duplicate)?;
+			let allowance = limit.map(Allowance::new);
+			let proxy_def = ProxyDefinition { delegate: delegatee, proxy_type, delay, expiry, allowance };
 			proxies.insert(i, proxy_def);
-			let new_deposit = Self::deposit(proxies.len() as u32);
-			if new_deposit > *deposit {
-				T::Currency::reserve(delegator, new_deposit - *deposit)?;
-			} else if new_deposit < *deposit {
-				T::Currency::unreserve(delegator, *deposit - new_deposit);
-			}
-			*deposit = new_deposit;
+			*deposit = Self::rejig_proxy_deposit(delegator, *deposit, proxies)?;
 			Ok(())
 		})
 	}
@@ -597,17 +805,15 @@ impl<T: Config> Module<T> {
 		proxy_type: T::ProxyType,
 		delay: T::BlockNumber,
 	) -> DispatchResult {
+		let now = system::Module::<T>::block_number();
 		Proxies::<T>::try_mutate_exists(delegator, |x| {
 			let (mut proxies, old_deposit) = x.take().ok_or(Error::<T>::NotFound)?;
-			let proxy_def = ProxyDefinition { delegate: delegatee, proxy_type, delay };
-			let i = proxies.binary_search(&proxy_def).ok().ok_or(Error::<T>::NotFound)?;
+			let i = proxies.binary_search_by(|x| x.cmp_key(&delegatee, &proxy_type, &delay))
+				.ok()
+				.ok_or(Error::<T>::NotFound)?;
 			proxies.remove(i);
-			let new_deposit = Self::deposit(proxies.len() as u32);
-			if new_deposit > old_deposit {
-				T::Currency::reserve(delegator, new_deposit - old_deposit)?;
-			} else if new_deposit < old_deposit {
-				T::Currency::unreserve(delegator, old_deposit - new_deposit);
-			}
+			proxies.retain(|x| !x.is_expired(&now));
+			let new_deposit = Self::rejig_proxy_deposit(delegator, old_deposit, &proxies)?;
 			if !proxies.is_empty() {
 				*x = Some((proxies, new_deposit))
 			}
@@ -615,11 +821,51 @@ impl<T: Config> Module<T> {
 		})
 	}
 
-	pub fn deposit(num_proxies: u32) -> BalanceOf<T> {
+	/// The deposit held for `num_proxies` proxies of which `num_limited` have an expiry or a
+	/// spending allowance.
+	pub fn deposit(num_proxies: u32, num_limited: u32) -> BalanceOf<T> {
 		if num_proxies == 0 {
 			Zero::zero()
 		} else {
-			T::ProxyDepositBase::get() + T::ProxyDepositFactor::get() * num_proxies.into()
+			T::ProxyDepositBase::get()
+				+ T::ProxyDepositFactor::get() * num_proxies.into()
+				+ T::ProxyLimitDepositFactor::get() * num_limited.into()
+		}
+	}
+
+	/// Reserve or unreserve the difference between `old_deposit` and the deposit for `proxies`,
+	/// returning the new deposit.
+	fn rejig_proxy_deposit(
+		delegator: &T::AccountId,
+		old_deposit: BalanceOf<T>,
+		proxies: &[ProxyDefinitionOf<T>],
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let num_limited = proxies.iter().filter(|x| x.is_limited()).count();
+		let new_deposit = Self::deposit(proxies.len() as u32, num_limited as u32);
+		if new_deposit > old_deposit {
+			T::Currency::reserve_named(&PROXY_ID, delegator, new_deposit - old_deposit)?;
+		} else if new_deposit < old_deposit {
+			T::Currency::unreserve_named(&PROXY_ID, delegator, old_deposit - new_deposit);
+		}
+		Ok(new_deposit)
+	}
+
+	/// Remove the expired proxies of `delegator` and return their deposit.
+	fn remove_expired(delegator: &T::AccountId) {
+		let now = system::Module::<T>::block_number();
+		let (mut proxies, old_deposit) = Proxies::<T>::get(delegator);
+		let len = proxies.len();
+		proxies.retain(|x| !x.is_expired(&now));
+		if proxies.len() == len {
+			return;
+		}
+		// The deposit only shrinks, so this cannot fail.
+		let new_deposit = Self::rejig_proxy_deposit(delegator, old_deposit, &proxies)
+			.unwrap_or(old_deposit);
+		if proxies.is_empty() {
+			Proxies::<T>::remove(delegator);
+		} else {
+			Proxies::<T>::insert(delegator, (proxies, new_deposit));
 		}
 	}
 
@@ -636,9 +882,9 @@ impl<T: Config> Module<T> {
 			base + factor * (len as u32).into()
 		};
 		if new_deposit > old_deposit {
-			T::Currency::reserve(&who, new_deposit - old_deposit)?;
+			T::Currency::reserve_named(&PROXY_ID, &who, new_deposit - old_deposit)?;
 		} else if new_deposit < old_deposit {
-			T::Currency::unreserve(&who, old_deposit - new_deposit);
+			T::Currency::unreserve_named(&PROXY_ID, &who, old_deposit - new_deposit);
 		}
 		Ok(if len == 0 {
 			None
@@ -666,39 +912,153 @@ impl<T: Config> Module<T> {
 		})
 	}
 
+	/// Find the proxy definition by which `delegate` may act for `real`, ignoring the expired ones.
 	fn find_proxy(
 		real: &T::AccountId,
 		delegate: &T::AccountId,
 		force_proxy_type: Option<T::ProxyType>,
-	) -> Result<ProxyDefinition<T::AccountId, T::ProxyType, T::BlockNumber>, DispatchError> {
-		let f = |x: &ProxyDefinition<T::AccountId, T::ProxyType, T::BlockNumber>| -> bool {
+	) -> Result<ProxyDefinitionOf<T>, DispatchError> {
+		let now = system::Module::<T>::block_number();
+		let f = |x: &ProxyDefinitionOf<T>| -> bool {
 			&x.delegate == delegate && force_proxy_type.as_ref().map_or(true, |y| &x.proxy_type == y)
 		};
-		Ok(Proxies::<T>::get(real).0.into_iter().find(f).ok_or(Error::<T>::NotProxy)?)
+		let (found, expired): (Vec<_>, Vec<_>) = Proxies::<T>::get(real).0.into_iter()
+			.filter(f)
+			.partition(|x| !x.is_expired(&now));
+		match found.into_iter().next() {
+			Some(def) => Ok(def),
+			None if !expired.is_empty() => Err(Error::<T>::Expired.into()),
+			None => Err(Error::<T>::NotProxy.into()),
+		}
 	}
 
 	fn do_proxy(
-		def: ProxyDefinition<T::AccountId, T::ProxyType, T::BlockNumber>,
+		def: ProxyDefinitionOf<T>,
 		real: T::AccountId,
 		call: <T as Config>::Call,
 	) {
 		// This is a freshly authenticated new account, the origin restrictions doesn't apply.
-		let mut origin: T::Origin = frame_system::RawOrigin::Signed(real).into();
+		let mut origin: T::Origin = frame_system::RawOrigin::Signed(real.clone()).into();
+		let limited = def.is_limited();
+		let proxy_type = def.proxy_type.clone();
 		origin.add_filter(move |c: &<T as frame_system::Config>::Call| {
 			let c = <T as Config>::Call::from_ref(c);
 			// We make sure the proxy call does access this pallet to change modify proxies.
 			match c.is_sub_type() {
+				// Limited proxy call cannot change the proxies at all, since it could lift its limits.
+				Some(Call::add_proxy(..)) | Some(Call::add_limited_proxy(..)) |
+				Some(Call::remove_proxy(..)) | Some(Call::remove_proxies(..)) |
+				Some(Call::kill_anonymous(..)) if limited => false,
 				// Proxy call cannot add or remove a proxy with more permissions than it already has.
-				Some(Call::add_proxy(_, ref pt, _)) | Some(Call::remove_proxy(_, ref pt, _))
-					if !def.proxy_type.is_superset(&pt) => false,
+				Some(Call::add_proxy(_, ref pt, _)) | Some(Call::add_limited_proxy(_, ref pt, ..)) |
+				Some(Call::remove_proxy(_, ref pt, _)) if !proxy_type.is_superset(&pt) => false,
 				// Proxy call cannot remove all proxies or kill anonymous proxies unless it has full permissions.
 				Some(Call::remove_proxies(..)) | Some(Call::kill_anonymous(..))
-					if def.proxy_type != T::ProxyType::default() => false,
-				_ => def.proxy_type.filter(c)
+					if proxy_type != T::ProxyType::default() => false,
+				_ => proxy_type.filter(c)
 			}
 		});
-		let e = call.dispatch(origin);
-		Self::deposit_event(RawEvent::ProxyExecuted(e.map(|_| ()).map_err(|e| e.error)));
+		let e = if def.allowance.is_some() {
+			Self::dispatch_within_allowance(&def, &real, call, origin)
+		} else {
+			call.dispatch(origin).map(|_| ()).map_err(|e| e.error)
+		};
+		Self::deposit_event(RawEvent::ProxyExecuted(e));
+	}
+
+	/// Dispatch `call` and charge the amount by which it reduced the balance of `real` to the
+	/// allowance of `def`. The call is reverted if it spent more than what remains of the
+	/// allowance.
+	fn dispatch_within_allowance(
+		def: &ProxyDefinitionOf<T>,
+		real: &T::AccountId,
+		call: <T as Config>::Call,
+		origin: T::Origin,
+	) -> DispatchResult {
+		let now = system::Module::<T>::block_number();
+		let before = T::Currency::total_balance(real);
+		with_transaction(|| {
+			let result = call.dispatch(origin).map(|_| ()).map_err(|e| e.error);
+			let spent = before.saturating_sub(T::Currency::total_balance(real));
+			let charged = Proxies::<T>::try_mutate(real, |(ref mut proxies, _)| {
+				let allowance = match proxies.binary_search_by(|x|
+					x.cmp_key(&def.delegate, &def.proxy_type, &def.delay)
+				) {
+					Ok(i) => proxies[i].allowance.as_mut(),
+					Err(_) => None,
+				};
+				match allowance {
+					Some(allowance) if !allowance.spend(spent, now) => Err(()),
+					_ => Ok(()),
+				}
+			});
+			match charged {
+				Ok(()) => TransactionOutcome::Commit(result),
+				Err(()) => TransactionOutcome::Rollback(Err(Error::<T>::OverAllowance.into())),
+			}
+		})
+	}
+}
+
+/// Merges the proxies delegated by an account into those of the new account, together with the
+/// deposit they need. Expired proxies, proxies which the new account already has, proxies to the
+/// new account itself and any proxies beyond `MaxProxies` are dropped, and the rest of the old
+/// deposit is returned. Announcements made by the old account are dropped and their deposit is
+/// returned.
+///
+/// Proxies for which the old account is the delegate are left unchanged: there is no index of
+/// them, so finding them would mean iterating over all of `Proxies`.
+impl<T: Config> MigrateAccount<T::AccountId> for Module<T> {
+	fn migration_weight(_from: &T::AccountId) -> Weight {
+		T::DbWeight::get().reads_writes(5, 5)
+	}
+
+	fn migrate_account(from: &T::AccountId, to: &T::AccountId) -> Weight {
+		let weight = Self::migration_weight(from);
+
+		let (_, announcement_deposit) = Announcements::<T>::take(from);
+		T::Currency::unreserve_named(&PROXY_ID, from, announcement_deposit);
+
+		let (old_proxies, old_deposit) = Proxies::<T>::take(from);
+		if old_proxies.is_empty() {
+			return weight
+		}
+		let now = system::Module::<T>::block_number();
+		let (mut proxies, deposit) = Proxies::<T>::get(to);
+		let mut moved = 0u32;
+		for proxy in old_proxies.iter() {
+			if proxy.is_expired(&now) || &proxy.delegate == to
+				|| proxies.len() >= T::MaxProxies::get() as usize
+			{
+				continue
+			}
+			if let Err(i) = proxies.binary_search_by(|x|
+				x.cmp_key(&proxy.delegate, &proxy.proxy_type, &proxy.delay)
+			) {
+				proxies.insert(i, proxy.clone());
+				moved += 1;
+			}
+		}
+		let dropped = old_proxies.len() as u32 - moved;
+
+		let num_limited = proxies.iter().filter(|x| x.is_limited()).count();
+		let new_deposit = Self::deposit(proxies.len() as u32, num_limited as u32);
+		// The moved proxies are a subset of the old ones, so their deposit is covered by the old
+		// deposit.
+		let needed = new_deposit.saturating_sub(deposit).min(old_deposit);
+		let status = BalanceStatus::Reserved;
+		match T::Currency::repatriate_reserved_named(&PROXY_ID, from, to, needed, status) {
+			Ok(remaining) => {
+				T::Currency::unreserve_named(&PROXY_ID, from, old_deposit - needed);
+				if !proxies.is_empty() {
+					Proxies::<T>::insert(to, (proxies, deposit + needed - remaining));
+				}
+				let event = RawEvent::ProxiesMigrated(from.clone(), to.clone(), moved, dropped);
+				Self::deposit_event(event);
+			}
+			Err(_) => Proxies::<T>::insert(from, (old_proxies, old_deposit)),
+		}
+		weight
 	}
 }
 
@@ -714,6 +1074,9 @@ pub mod migration {
 	/// `ProxyDefinition` which additionally included a `BlockNumber` delay value. This function,
 	/// simply takes any existing proxies using the old tuple format, and migrates it to the new
 	/// struct by setting the delay to zero.
+	///
+	/// The proxies are migrated directly to the struct of `migrate_to_limited_proxies`, which must
+	/// not be run afterwards.
 	pub fn migrate_to_time_delayed_proxies<T: Config>() -> Weight {
 		Proxies::<T>::translate::<(Vec<(T::AccountId, T::ProxyType)>, BalanceOf<T>), _>(
 			|_, (targets, deposit)| Some((
@@ -722,6 +1085,8 @@ pub mod migration {
 						delegate: a,
 						proxy_type: t,
 						delay: Zero::zero(),
+						expiry: None,
+						allowance: None,
 					})
 					.collect::<Vec<_>>(),
 				deposit,
@@ -729,4 +1094,79 @@ pub mod migration {
 		);
 		T::BlockWeights::get().max_block
 	}
+
+	/// A proxy definition before proxies could have an expiry or a spending allowance.
+	#[derive(Decode)]
+	struct ProxyDefinitionV1<AccountId, ProxyType, BlockNumber> {
+		delegate: AccountId,
+		proxy_type: ProxyType,
+		delay: BlockNumber,
+	}
+
+	/// Migration code for limited proxies.
+	///
+	/// Before this migration, a `ProxyDefinition` held a delegate, a proxy type and a delay. It now
+	/// also holds an optional expiry and an optional spending allowance. This function takes any
+	/// existing proxies and migrates them to the new struct without either, so that their deposits
+	/// stay the same. Does nothing if the storage is already at `Releases::V2`.
+	pub fn migrate_to_limited_proxies<T: Config>() -> Weight {
+		if StorageVersion::get() >= Some(Releases::V2) {
+			return T::DbWeight::get().reads(1);
+		}
+
+		type OldProxies<T> = (
+			Vec<ProxyDefinitionV1<
+				<T as frame_system::Config>::AccountId,
+				<T as Config>::ProxyType,
+				<T as frame_system::Config>::BlockNumber,
+			>>,
+			BalanceOf<T>,
+		);
+		Proxies::<T>::translate::<OldProxies<T>, _>(
+			|_, (proxies, deposit)| Some((
+				proxies.into_iter()
+					.map(|x| ProxyDefinition {
+						delegate: x.delegate,
+						proxy_type: x.proxy_type,
+						delay: x.delay,
+						expiry: None,
+						allowance: None,
+					})
+					.collect::<Vec<_>>(),
+				deposit,
+			))
+		);
+		StorageVersion::put(Releases::V2);
+		T::BlockWeights::get().max_block
+	}
+
+	/// Migration code for named reserves.
+	///
+	/// Before this migration, the deposits of proxies and announcements were held in the anonymous
+	/// reserve of the depositor. This function moves them into the reserve named `PROXY_ID`. The
+	/// deposit of an anonymous proxy is held by its spawner, which is the only delegate of the
+	/// anonymous account, so any part of a deposit not found on the delegator is looked up there.
+	/// Does nothing if the storage is already at `Releases::V3`.
+	pub fn migrate_to_named_reserves<T: Config>() -> Weight {
+		if StorageVersion::get() >= Some(Releases::V3) {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut accounts: Weight = 0;
+		for (who, (proxies, deposit)) in Proxies::<T>::iter() {
+			let remaining = T::Currency::name_reserved(&PROXY_ID, &who, deposit)
+				.unwrap_or(deposit);
+			accounts += 1;
+			if !remaining.is_zero() && proxies.len() == 1 {
+				let _ = T::Currency::name_reserved(&PROXY_ID, &proxies[0].delegate, remaining);
+				accounts += 1;
+			}
+		}
+		for (who, (_, deposit)) in Announcements::<T>::iter() {
+			let _ = T::Currency::name_reserved(&PROXY_ID, &who, deposit);
+			accounts += 1;
+		}
+		StorageVersion::put(Releases::V3);
+		T::DbWeight::get().reads_writes(1 + 3 * accounts, 1 + 2 * accounts)
+	}
 }
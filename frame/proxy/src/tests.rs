@@ -23,7 +23,8 @@ use super::*;
 
 use frame_support::{
 	assert_ok, assert_noop, impl_outer_origin, parameter_types, impl_outer_dispatch,
-	impl_outer_event, RuntimeDebug, dispatch::DispatchError, traits::Filter,
+	impl_outer_event, RuntimeDebug, StorageMap, StorageValue, dispatch::DispatchError,
+	traits::{Filter, OnRuntimeUpgrade},
 };
 use codec::{Encode, Decode};
 use sp_core::H256;
@@ -85,9 +86,13 @@ impl frame_system::Config for Test {
 }
 parameter_types! {
 	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxReserves: u32 = 2;
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type Balance = u64;
 	type Event = TestEvent;
 	type DustRemoval = ();
@@ -98,11 +103,13 @@ impl pallet_balances::Config for Test {
 impl pallet_utility::Config for Test {
 	type Event = TestEvent;
 	type Call = Call;
+	type PalletsOrigin = OriginCaller;
 	type WeightInfo = ();
 }
 parameter_types! {
 	pub const ProxyDepositBase: u64 = 1;
 	pub const ProxyDepositFactor: u64 = 1;
+	pub const ProxyLimitDepositFactor: u64 = 2;
 	pub const MaxProxies: u16 = 4;
 	pub const MaxPending: u32 = 2;
 	pub const AnnouncementDepositBase: u64 = 1;
@@ -145,6 +152,7 @@ impl Config for Test {
 	type ProxyType = ProxyType;
 	type ProxyDepositBase = ProxyDepositBase;
 	type ProxyDepositFactor = ProxyDepositFactor;
+	type ProxyLimitDepositFactor = ProxyLimitDepositFactor;
 	type MaxProxies = MaxProxies;
 	type WeightInfo = ();
 	type CallHasher = BlakeTwo256;
@@ -316,7 +324,7 @@ fn proxy_announced_removes_announcement_and_returns_deposit() {
 #[test]
 fn filtering_works() {
 	new_test_ext().execute_with(|| {
-		Balances::mutate_account(&1, |a| a.free = 1000);
+		assert_ok!(Balances::mutate_account(&1, |a| a.free = 1000));
 		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any, 0));
 		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::JustTransfer, 0));
 		assert_ok!(Proxy::add_proxy(Origin::signed(1), 4, ProxyType::JustUtility, 0));
@@ -330,7 +338,7 @@ fn filtering_works() {
 		expect_event(RawEvent::ProxyExecuted(Err(DispatchError::BadOrigin)));
 
 		let derivative_id = Utility::derivative_account_id(1, 0);
-		Balances::mutate_account(&derivative_id, |a| a.free = 1000);
+		assert_ok!(Balances::mutate_account(&derivative_id, |a| a.free = 1000));
 		let inner = Box::new(Call::Balances(BalancesCall::transfer(6, 1)));
 
 		let call = Box::new(Call::Utility(UtilityCall::as_derivative(0, inner.clone())));
@@ -399,6 +407,23 @@ fn add_remove_proxies_works() {
 	});
 }
 
+#[test]
+fn removing_proxies_of_frozen_account_returns_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any, 0));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any, 0));
+		assert_eq!(Balances::reserved_balance(1), 3);
+		let free = Balances::free_balance(1);
+
+		assert_ok!(Balances::freeze_account(Origin::root(), 1));
+		assert_ok!(Proxy::remove_proxy(Origin::signed(1), 3, ProxyType::Any, 0));
+		assert_eq!(Balances::reserved_balance(1), 2);
+		assert_ok!(Proxy::remove_proxies(Origin::signed(1)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), free + 3);
+	});
+}
+
 #[test]
 fn cannot_add_proxy_without_balance() {
 	new_test_ext().execute_with(|| {
@@ -479,3 +504,214 @@ fn anonymous_works() {
 		assert_noop!(Proxy::proxy(Origin::signed(1), anon, None, call.clone()), Error::<Test>::NotProxy);
 	});
 }
+
+#[test]
+fn limited_proxy_expires() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Proxy::add_limited_proxy(Origin::signed(1), 2, ProxyType::Any, 0, Some(1), None),
+			Error::<Test>::Expired
+		);
+		assert_ok!(Proxy::add_limited_proxy(Origin::signed(1), 2, ProxyType::Any, 0, Some(3), None));
+		assert_eq!(Balances::reserved_balance(1), 4);
+
+		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 1)));
+		System::set_block_number(2);
+		assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, call.clone()));
+		expect_event(RawEvent::ProxyExecuted(Ok(())));
+		assert_eq!(Balances::free_balance(6), 1);
+
+		System::set_block_number(3);
+		assert_noop!(Proxy::proxy(Origin::signed(2), 1, None, call.clone()), Error::<Test>::Expired);
+		assert_noop!(Proxy::announce(Origin::signed(2), 1, H256::zero()), Error::<Test>::Expired);
+
+		// The expired proxy is cleaned up when the proxies are next changed.
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any, 0));
+		assert_eq!(Proxy::proxies(1).0.len(), 1);
+		assert_eq!(Balances::reserved_balance(1), 2);
+	});
+}
+
+#[test]
+fn expired_proxies_are_removed_on_use() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_limited_proxy(Origin::signed(1), 2, ProxyType::Any, 0, Some(3), None));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any, 0));
+		assert_eq!(Balances::reserved_balance(1), 5);
+
+		System::set_block_number(3);
+		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 1)));
+		assert_ok!(Proxy::proxy(Origin::signed(3), 1, None, call));
+		assert_eq!(Proxy::proxies(1), (vec![ProxyDefinition {
+			delegate: 3,
+			proxy_type: ProxyType::Any,
+			delay: 0,
+			expiry: None,
+			allowance: None,
+		}], 2));
+		assert_eq!(Balances::reserved_balance(1), 2);
+
+		// Removing the last proxy removes the storage item.
+		System::set_block_number(4);
+		assert_ok!(Proxy::add_limited_proxy(Origin::signed(1), 2, ProxyType::Any, 0, Some(5), None));
+		System::set_block_number(5);
+		assert_ok!(Proxy::remove_proxy(Origin::signed(1), 3, ProxyType::Any, 0));
+		assert!(!Proxies::<Test>::contains_key(1));
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
+
+#[test]
+fn spending_allowance_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Balances::mutate_account(&1, |a| a.free = 1000));
+		let limit = SpendLimit { amount: 5, period: 10 };
+		assert_ok!(Proxy::add_limited_proxy(Origin::signed(1), 2, ProxyType::Any, 0, None, Some(limit)));
+
+		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 3)));
+		assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, call.clone()));
+		expect_event(RawEvent::ProxyExecuted(Ok(())));
+		assert_eq!(Balances::free_balance(6), 3);
+
+		// A second transfer of 3 would spend 6 of the allowance of 5 in this period.
+		assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, call.clone()));
+		expect_event(RawEvent::ProxyExecuted(Err(Error::<Test>::OverAllowance.into())));
+		assert_eq!(Balances::free_balance(6), 3);
+
+		// Calls which do not spend are not limited, and neither are other proxies.
+		let remark = Box::new(Call::System(SystemCall::remark(vec![])));
+		assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, remark));
+		expect_event(RawEvent::ProxyExecuted(Ok(())));
+		let small = Box::new(Call::Balances(BalancesCall::transfer(6, 2)));
+		assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, small));
+		expect_event(RawEvent::ProxyExecuted(Ok(())));
+		assert_eq!(Balances::free_balance(6), 5);
+
+		// The allowance resets in the next period.
+		System::set_block_number(10);
+		assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, call.clone()));
+		expect_event(RawEvent::ProxyExecuted(Ok(())));
+		assert_eq!(Balances::free_balance(6), 8);
+
+		// Spending through a batch counts as well.
+		let batch = Box::new(Call::Utility(UtilityCall::batch(vec![*call.clone(), *call.clone()])));
+		System::set_block_number(20);
+		assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, batch));
+		expect_event(RawEvent::ProxyExecuted(Err(Error::<Test>::OverAllowance.into())));
+		assert_eq!(Balances::free_balance(6), 8);
+	});
+}
+
+#[test]
+fn limited_proxy_cannot_change_proxies() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Balances::mutate_account(&1, |a| a.free = 1000));
+		assert_ok!(Proxy::add_limited_proxy(Origin::signed(1), 2, ProxyType::Any, 0, Some(10), None));
+
+		let call = Box::new(Call::Proxy(ProxyCall::add_proxy(2, ProxyType::Any, 1)));
+		assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, call));
+		expect_event(RawEvent::ProxyExecuted(Err(DispatchError::BadOrigin)));
+		let call = Box::new(Call::Proxy(ProxyCall::add_limited_proxy(5, ProxyType::Any, 0, None, None)));
+		assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, call));
+		expect_event(RawEvent::ProxyExecuted(Err(DispatchError::BadOrigin)));
+		let call = Box::new(Call::Proxy(ProxyCall::remove_proxies()));
+		assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, call));
+		expect_event(RawEvent::ProxyExecuted(Err(DispatchError::BadOrigin)));
+		assert_eq!(Proxy::proxies(1).0.len(), 1);
+	});
+}
+
+#[test]
+fn migration_to_limited_proxies_works() {
+	new_test_ext().execute_with(|| {
+		let old = (vec![(2u64, ProxyType::Any, 0u64), (3u64, ProxyType::JustTransfer, 1u64)], 3u64);
+		sp_io::storage::set(&Proxies::<Test>::hashed_key_for(1), &old.encode());
+		StorageVersion::put(Releases::V1);
+
+		Proxy::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get(), Some(Releases::V3));
+
+		let def = |delegate, proxy_type, delay| ProxyDefinition {
+			delegate,
+			proxy_type,
+			delay,
+			expiry: None,
+			allowance: None,
+		};
+		let migrated = (vec![def(2, ProxyType::Any, 0), def(3, ProxyType::JustTransfer, 1)], 3);
+		assert_eq!(Proxy::proxies(1), migrated);
+
+		// the migrated proxies are not migrated again.
+		Proxy::on_runtime_upgrade();
+		assert_eq!(Proxy::proxies(1), migrated);
+	});
+}
+
+#[test]
+fn migration_to_named_reserves_works() {
+	use frame_support::traits::ReservableCurrency;
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any, 0));
+		assert_ok!(Proxy::anonymous(Origin::signed(2), ProxyType::Any, 0, 0));
+		assert_ok!(Proxy::announce(Origin::signed(3), 1, [1; 32].into()));
+		for who in 1..=3 {
+			assert_eq!(Balances::reserved_balance_named(&PROXY_ID, &who), 2);
+		}
+
+		// the deposits as they were held before named reserves were used.
+		for who in 1..=3 {
+			let _ = Balances::unreserve_named(&PROXY_ID, &who, 2);
+			assert_ok!(Balances::reserve(&who, 2));
+		}
+		StorageVersion::put(Releases::V2);
+
+		Proxy::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get(), Some(Releases::V3));
+		for who in 1..=3 {
+			assert_eq!(Balances::reserved_balance_named(&PROXY_ID, &who), 2);
+			assert_eq!(Balances::reserved_balance(who), 2);
+		}
+	});
+}
+
+#[test]
+fn migrate_account_merges_proxies_up_to_max_proxies() {
+	use frame_support::traits::{MigrateAccount, ReservableCurrency};
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any, 0));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any, 0));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 4, ProxyType::JustTransfer, 0));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 5, ProxyType::JustTransfer, 0));
+		assert_ok!(Proxy::add_proxy(Origin::signed(2), 1, ProxyType::Any, 0));
+		assert_ok!(Proxy::announce(Origin::signed(1), 2, [1; 32].into()));
+		assert_ok!(Proxy::add_proxy(Origin::signed(3), 2, ProxyType::Any, 0));
+		assert_ok!(Proxy::add_proxy(Origin::signed(3), 4, ProxyType::Any, 0));
+		assert_ok!(Proxy::add_proxy(Origin::signed(3), 5, ProxyType::Any, 0));
+		assert_eq!(Balances::reserved_balance(1), 7);
+		assert_eq!(Balances::reserved_balance(3), 4);
+
+		<Proxy as MigrateAccount<_>>::migrate_account(&1, &3);
+
+		// the duplicate, the proxy to 3 itself and the one beyond `MaxProxies` are dropped.
+		expect_event(RawEvent::ProxiesMigrated(1, 3, 1, 3));
+		assert_eq!(Proxy::proxies(1), (vec![], 0));
+		assert_eq!(Proxy::announcements(1), (vec![], 0));
+		let proxies = Proxy::proxies(3).0.into_iter()
+			.map(|x| (x.delegate, x.proxy_type))
+			.collect::<Vec<_>>();
+		assert_eq!(proxies, vec![
+			(2, ProxyType::Any),
+			(4, ProxyType::Any),
+			(4, ProxyType::JustTransfer),
+			(5, ProxyType::Any),
+		]);
+		assert_eq!(Proxy::proxies(3).1, 5);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(3), 5);
+
+		// proxies for which 1 is the delegate are not found, so they are left unchanged.
+		assert_eq!(Proxy::proxies(2).0[0].delegate, 1);
+	});
+}
@@ -44,11 +44,13 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for pallet_proxy.
 pub trait WeightInfo {
 	fn proxy(p: u32, ) -> Weight;
+	fn proxy_limited(p: u32, ) -> Weight;
 	fn proxy_announced(a: u32, p: u32, ) -> Weight;
 	fn remove_announcement(a: u32, p: u32, ) -> Weight;
 	fn reject_announcement(a: u32, p: u32, ) -> Weight;
 	fn announce(a: u32, p: u32, ) -> Weight;
 	fn add_proxy(p: u32, ) -> Weight;
+	fn add_limited_proxy(p: u32, ) -> Weight;
 	fn remove_proxy(p: u32, ) -> Weight;
 	fn remove_proxies(p: u32, ) -> Weight;
 	fn anonymous(p: u32, ) -> Weight;
@@ -64,6 +66,13 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add((215_000 as Weight).saturating_mul(p as Weight))
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
 			
+	}
+	fn proxy_limited(p: u32, ) -> Weight {
+		(48_316_000 as Weight)
+			.saturating_add((331_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			
 	}
 	fn proxy_announced(a: u32, p: u32, ) -> Weight {
 		(67_490_000 as Weight)
@@ -103,6 +112,13 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 			
+	}
+	fn add_limited_proxy(p: u32, ) -> Weight {
+		(47_802_000 as Weight)
+			.saturating_add((262_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			
 	}
 	fn remove_proxy(p: u32, ) -> Weight {
 		(40_742_000 as Weight)
@@ -142,6 +158,13 @@ impl WeightInfo for () {
 			.saturating_add((215_000 as Weight).saturating_mul(p as Weight))
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
 			
+	}
+	fn proxy_limited(p: u32, ) -> Weight {
+		(48_316_000 as Weight)
+			.saturating_add((331_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			
 	}
 	fn proxy_announced(a: u32, p: u32, ) -> Weight {
 		(67_490_000 as Weight)
@@ -181,6 +204,13 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 			
+	}
+	fn add_limited_proxy(p: u32, ) -> Weight {
+		(47_802_000 as Weight)
+			.saturating_add((262_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			
 	}
 	fn remove_proxy(p: u32, ) -> Weight {
 		(40_742_000 as Weight)
@@ -64,16 +64,18 @@
 //!    when they initiated the recovery process.
 //! 6. Now the account owner is able to call `claim_recovery`, which subsequently
 //!    allows them to call `as_recovered` and directly make calls on-behalf-of the lost
-//!    account.
+//!    account once the challenge period has passed.
 //! 7. Using the now recovered account, the account owner can call `close_recovery`
 //!    on the recovery process they opened, reclaiming the recovery deposit they
 //!    placed.
 //! 8. Then the account owner should then call `remove_recovery` to remove the recovery
 //!    configuration on the recovered account and reclaim the recovery configuration
 //!    deposit they placed.
-//! 9. Using `as_recovered`, the account owner is able to call any other pallets
-//!    to clean up their state and reclaim any reserved or locked funds. They
-//!    can then transfer all funds from the recovered account to the new account.
+//! 9. Using `finish_recovery`, the account owner moves the reserved and locked funds
+//!    of the recovered account, together with the state they back, such as proxy
+//!    relationships and multisig memberships, onto the new account. Anything which
+//!    is not migrated can be cleaned up by calling other pallets with `as_recovered`.
+//!    They can then transfer all funds from the recovered account to the new account.
 //! 10. When the recovered account becomes reaped (i.e. its free and reserved
 //!     balance drops to zero), the final recovery link is removed.
 //!
@@ -95,6 +97,13 @@
 //! this pallet makes it near-zero cost to re-configure the recovery settings and
 //! remove/replace friends who are acting inappropriately.
 //!
+//! ### Challenge Period
+//!
+//! After a recovery has been claimed, the rescuer has to wait for a configurable
+//! `ChallengePeriod` before it can act as the lost account. During this period,
+//! an account owner who still has access to their account can call `veto_recovery`
+//! to remove the recovery link and claim the recovery deposit of the rescuer.
+//!
 //! ### Safety Considerations
 //!
 //! It is important to note that this is a powerful pallet that can compromise the
@@ -137,10 +146,12 @@
 //!
 //! * `claim_recovery` - Claim access to the account that you have successfully completed the recovery process for.
 //! * `as_recovered` - Send a transaction as an account that you have recovered. See other functions below.
+//! * `finish_recovery` - Migrate the funds and state of an account that you have recovered to your account.
 //!
 //! #### For the Recoverable Account
 //!
 //! * `close_recovery` - Close an active recovery process for your account and reclaim the recovery deposit.
+//! * `veto_recovery` - Veto a claimed recovery of your account during its challenge period.
 //! * `remove_recovery` - Remove the recovery configuration from the account, making it un-recoverable.
 //!
 //! #### For Super Users
@@ -153,16 +164,16 @@
 
 use sp_std::prelude::*;
 use sp_runtime::{
-	traits::{Dispatchable, SaturatedConversion, CheckedAdd, CheckedMul},
+	traits::{Dispatchable, SaturatedConversion, CheckedAdd, CheckedMul, Zero},
 	DispatchResult
 };
 use codec::{Encode, Decode};
 
 use frame_support::{
 	decl_module, decl_event, decl_storage, decl_error, ensure,
-	Parameter, RuntimeDebug, weights::GetDispatchInfo,
-	traits::{Currency, ReservableCurrency, Get, BalanceStatus},
-	dispatch::PostDispatchInfo,
+	Parameter, RuntimeDebug, weights::{GetDispatchInfo, Weight},
+	traits::{Currency, ReservableCurrency, Get, BalanceStatus, MigrateAccount},
+	dispatch::{DispatchResultWithPostInfo, PostDispatchInfo},
 };
 use frame_system::{self as system, ensure_signed, ensure_root};
 
@@ -207,6 +218,13 @@ pub trait Config: frame_system::Config {
 	/// `sizeof(BlockNumber, Balance + T * AccountId)` bytes. Where T is a configurable
 	/// threshold.
 	type RecoveryDeposit: Get<BalanceOf<Self>>;
+
+	/// The number of blocks after a successful claim during which the lost account can still
+	/// veto the recovery. The rescuer cannot act as the lost account until it has passed.
+	type ChallengePeriod: Get<Self::BlockNumber>;
+
+	/// Handlers which move the state of the lost account onto the rescuer in `finish_recovery`.
+	type MigrateAccount: MigrateAccount<Self::AccountId>;
 }
 
 /// An active recovery process.
@@ -256,6 +274,12 @@ decl_storage! {
 		/// Map from the user who can access it to the recovered account.
 		pub Proxy get(fn proxy):
 			map hasher(blake2_128_concat) T::AccountId => Option<T::AccountId>;
+
+		/// The block at which the challenge period of a claimed recovery ends.
+		///
+		/// Map from the rescuer to the block number. Absent once the period is over.
+		pub ChallengeEnds get(fn challenge_ends):
+			map hasher(twox_64_concat) T::AccountId => Option<T::BlockNumber>;
 	}
 }
 
@@ -280,6 +304,10 @@ decl_event! {
 		AccountRecovered(AccountId, AccountId),
 		/// A recovery process has been removed for an \[account\].
 		RecoveryRemoved(AccountId),
+		/// Lost account has vetoed its recovery by rescuer account. \[lost, rescuer\]
+		RecoveryVetoed(AccountId, AccountId),
+		/// The state of lost account has been migrated to rescuer account. \[lost, rescuer\]
+		RecoveryFinished(AccountId, AccountId),
 	}
 }
 
@@ -317,6 +345,12 @@ decl_error! {
 		Overflow,
 		/// This account is already set up for recovery
 		AlreadyProxy,
+		/// The recovery is still within its challenge period
+		ChallengePeriod,
+		/// The recovery can no longer be vetoed
+		NotChallengeable,
+		/// The weight given is not enough to migrate the lost account
+		WeightTooLow,
 	}
 }
 
@@ -336,6 +370,9 @@ decl_module! {
 		/// The base amount of currency needed to reserve for starting a recovery.
 		const RecoveryDeposit: BalanceOf<T> = T::RecoveryDeposit::get();
 
+		/// The number of blocks after a claim during which the lost account can veto it.
+		const ChallengePeriod: T::BlockNumber = T::ChallengePeriod::get();
+
 		/// Deposit one of this module's events by using the default implementation.
 		fn deposit_event() = default;
 
@@ -348,15 +385,18 @@ decl_module! {
 		/// - `account`: The recovered account you want to make a call on-behalf-of.
 		/// - `call`: The call you want to make with the recovered account.
 		///
+		/// Fails while the recovery is still within its challenge period.
+		///
 		/// # <weight>
 		/// - The weight of the `call` + 10,000.
 		/// - One storage lookup to check account is recovered by `who`. O(1)
+		/// - One storage lookup to check the challenge period is over. O(1)
 		/// # </weight>
 		#[weight = (
 			call.get_dispatch_info().weight
 				.saturating_add(10_000)
 				 // AccountData for inner call origin accountdata.
-				.saturating_add(T::DbWeight::get().reads_writes(1, 1)),
+				.saturating_add(T::DbWeight::get().reads_writes(2, 1)),
 			call.get_dispatch_info().class
 		)]
 		fn as_recovered(origin,
@@ -367,6 +407,7 @@ decl_module! {
 			// Check `who` is allowed to make a call on behalf of `account`
 			let target = Self::proxy(&who).ok_or(Error::<T>::NotAllowed)?;
 			ensure!(&target == &account, Error::<T>::NotAllowed);
+			Self::ensure_unchallengeable(&who)?;
 			call.dispatch(frame_system::RawOrigin::Signed(account).into())
 				.map(|_| ()).map_err(|e| e.error)
 		}
@@ -559,7 +600,7 @@ decl_module! {
 		/// - One storage read to get the recovery configuration. O(1), Codec O(F)
 		/// - One storage read to get the active recovery process. O(1), Codec O(V)
 		/// - One storage read to get the current block number. O(1)
-		/// - One storage write. O(1), Codec O(V).
+		/// - Up to two storage writes. O(1), Codec O(V).
 		/// - One event.
 		///
 		/// Total Complexity: O(F + V)
@@ -584,8 +625,13 @@ decl_module! {
 				Error::<T>::Threshold
 			);
 			// Create the recovery storage item
+			system::Module::<T>::inc_consumers(&who)?;
 			Proxy::<T>::insert(&who, &account);
-			system::Module::<T>::inc_ref(&who);
+			// Give the lost account a chance to veto before the rescuer can act
+			let challenge_period = T::ChallengePeriod::get();
+			if !challenge_period.is_zero() {
+				ChallengeEnds::<T>::insert(&who, current_block_number + challenge_period);
+			}
 			Self::deposit_event(RawEvent::AccountRecovered(account, who));
 		}
 
@@ -665,6 +711,7 @@ decl_module! {
 		///
 		/// # <weight>
 		/// - One storage mutation to check account is recovered by `who`. O(1)
+		/// - One storage removal of the challenge period. O(1)
 		/// # </weight>
 		#[weight = 0]
 		fn cancel_recovered(origin, account: T::AccountId) {
@@ -672,7 +719,93 @@ decl_module! {
 			// Check `who` is allowed to make a call on behalf of `account`
 			ensure!(Self::proxy(&who) == Some(account), Error::<T>::NotAllowed);
 			Proxy::<T>::remove(&who);
-			system::Module::<T>::dec_ref(&who);
+			ChallengeEnds::<T>::remove(&who);
+			system::Module::<T>::dec_consumers(&who);
+		}
+
+		/// As a lost account which still controls its key, veto a successful recovery
+		/// during its challenge period.
+		///
+		/// Payment: The recovery deposit of the rescuer, if the recovery process has not
+		/// been closed yet, is repatriated to the lost account.
+		///
+		/// The dispatch origin for this call must be _Signed_ and must be the lost account
+		/// claimed by `rescuer`.
+		///
+		/// Parameters:
+		/// - `rescuer`: The account which claimed the recovery of this account.
+		///
+		/// # <weight>
+		/// Key: V (len of vouching friends)
+		/// - One storage read/remove of the recovery link. O(1)
+		/// - One storage read/remove of the challenge period. O(1)
+		/// - One storage read/remove to get the active recovery process. O(1), Codec O(V)
+		/// - One balance call to repatriate reserved. O(X)
+		/// - One event.
+		///
+		/// Total Complexity: O(V + X)
+		/// # </weight>
+		#[weight = 50_000_000]
+		fn veto_recovery(origin, rescuer: T::AccountId) {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::proxy(&rescuer) == Some(who.clone()), Error::<T>::NotAllowed);
+			let end = Self::challenge_ends(&rescuer).ok_or(Error::<T>::NotChallengeable)?;
+			ensure!(<system::Module<T>>::block_number() < end, Error::<T>::NotChallengeable);
+
+			Proxy::<T>::remove(&rescuer);
+			ChallengeEnds::<T>::remove(&rescuer);
+			system::Module::<T>::dec_consumers(&rescuer);
+			// Punish the rescuer just like `close_recovery` would.
+			if let Some(active_recovery) = <ActiveRecoveries<T>>::take(&who, &rescuer) {
+				let _ = T::Currency::repatriate_reserved(
+					&rescuer,
+					&who,
+					active_recovery.deposit,
+					BalanceStatus::Free,
+				);
+			}
+			Self::deposit_event(RawEvent::RecoveryVetoed(who, rescuer));
+		}
+
+		/// Move the state of a recovered account, together with the funds reserved or locked for
+		/// it, onto the rescuer account.
+		///
+		/// Every pallet configured in `MigrateAccount` moves its own records, such as proxy
+		/// relationships, multisig memberships, vesting locks and staking bonds. Deposits of
+		/// records which are not moved stay reserved on the recovered account. The link to the
+		/// recovered account is kept, so `as_recovered` can still be used to clean up anything
+		/// left.
+		///
+		/// The dispatch origin for this call must be _Signed_ and registered to
+		/// be able to make calls on behalf of the recovered account, and the challenge
+		/// period must be over.
+		///
+		/// Parameters:
+		/// - `account`: The recovered account to migrate.
+		/// - `max_weight`: The maximum weight the migration is allowed to use.
+		///
+		/// # <weight>
+		/// - One storage lookup to check account is recovered by `who`. O(1)
+		/// - One storage lookup to check the challenge period is over. O(1)
+		/// - The weight of the migration, at most `max_weight`.
+		/// - One event.
+		/// # </weight>
+		#[weight = 50_000_000u64.saturating_add(*max_weight)]
+		fn finish_recovery(origin,
+			account: T::AccountId,
+			max_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::proxy(&who) == Some(account.clone()), Error::<T>::NotAllowed);
+			Self::ensure_unchallengeable(&who)?;
+			ensure!(
+				T::MigrateAccount::migration_weight(&account) <= max_weight,
+				Error::<T>::WeightTooLow
+			);
+
+			let used = T::MigrateAccount::migrate_account(&account, &who);
+			Self::deposit_event(RawEvent::RecoveryFinished(account, who));
+			Ok(Some(50_000_000u64.saturating_add(used)).into())
 		}
 	}
 }
@@ -687,4 +820,13 @@ impl<T: Config> Module<T> {
 	fn is_friend(friends: &Vec<T::AccountId>, friend: &T::AccountId) -> bool {
 		friends.binary_search(&friend).is_ok()
 	}
+
+	/// Check that the challenge period of the recovery claimed by `rescuer` is over.
+	fn ensure_unchallengeable(rescuer: &T::AccountId) -> DispatchResult {
+		if let Some(end) = Self::challenge_ends(rescuer) {
+			ensure!(<system::Module<T>>::block_number() >= end, Error::<T>::ChallengePeriod);
+			ChallengeEnds::<T>::remove(rescuer);
+		}
+		Ok(())
+	}
 }
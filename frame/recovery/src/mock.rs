@@ -21,8 +21,10 @@ use super::*;
 
 use frame_support::{
 	impl_outer_origin, impl_outer_dispatch, impl_outer_event, parameter_types,
-	traits::{OnInitialize, OnFinalize},
+	traits::{OnInitialize, OnFinalize, Get, MigrateAccount},
+	weights::Weight,
 };
+use std::cell::RefCell;
 use sp_core::H256;
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup}, testing::Header,
@@ -86,6 +88,9 @@ parameter_types! {
 
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type Balance = u128;
 	type DustRemoval = ();
 	type Event = TestEvent;
@@ -101,6 +106,36 @@ parameter_types! {
 	pub const RecoveryDeposit: u64 = 10;
 }
 
+thread_local! {
+	static CHALLENGE_PERIOD: RefCell<u64> = RefCell::new(0);
+	pub static MIGRATED: RefCell<Vec<(u64, u64)>> = RefCell::new(vec![]);
+}
+
+pub struct ChallengePeriod;
+impl ChallengePeriod {
+	pub fn set(period: u64) {
+		CHALLENGE_PERIOD.with(|v| *v.borrow_mut() = period);
+	}
+}
+impl Get<u64> for ChallengePeriod {
+	fn get() -> u64 {
+		CHALLENGE_PERIOD.with(|v| *v.borrow())
+	}
+}
+
+/// Records every migration so tests can check what was moved.
+pub struct TestMigrateAccount;
+impl MigrateAccount<u64> for TestMigrateAccount {
+	fn migration_weight(_from: &u64) -> Weight {
+		1_000
+	}
+
+	fn migrate_account(from: &u64, to: &u64) -> Weight {
+		MIGRATED.with(|m| m.borrow_mut().push((*from, *to)));
+		1_000
+	}
+}
+
 impl Config for Test {
 	type Event = TestEvent;
 	type Call = Call;
@@ -109,6 +144,8 @@ impl Config for Test {
 	type FriendDepositFactor = FriendDepositFactor;
 	type MaxFriends = MaxFriends;
 	type RecoveryDeposit = RecoveryDeposit;
+	type ChallengePeriod = ChallengePeriod;
+	type MigrateAccount = TestMigrateAccount;
 }
 
 pub type Recovery = Module<Test>;
@@ -119,6 +156,8 @@ pub type BalancesCall = pallet_balances::Call<Test>;
 pub type RecoveryCall = super::Call<Test>;
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
+	ChallengePeriod::set(0);
+	MIGRATED.with(|m| m.borrow_mut().clear());
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	pallet_balances::GenesisConfig::<Test> {
 		balances: vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100)],
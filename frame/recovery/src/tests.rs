@@ -20,7 +20,7 @@
 use super::*;
 use mock::{
 	Recovery, Balances, Test, Origin, Call, BalancesCall, RecoveryCall,
-	new_test_ext, run_to_block
+	ChallengePeriod, MIGRATED, new_test_ext, run_to_block
 };
 use sp_runtime::traits::{BadOrigin};
 use frame_support::{
@@ -385,3 +385,82 @@ fn remove_recovery_works() {
 		assert_ok!(Recovery::remove_recovery(Origin::signed(5)));
 	});
 }
+
+/// Create, initiate, vouch and claim a recovery of account 5 by account 1 at block 11.
+fn claim_recovery_of_5_by_1() {
+	let friends = vec![2, 3, 4];
+	assert_ok!(Recovery::create_recovery(Origin::signed(5), friends, 3, 10));
+	assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+	assert_ok!(Recovery::vouch_recovery(Origin::signed(2), 5, 1));
+	assert_ok!(Recovery::vouch_recovery(Origin::signed(3), 5, 1));
+	assert_ok!(Recovery::vouch_recovery(Origin::signed(4), 5, 1));
+	run_to_block(11);
+	assert_ok!(Recovery::claim_recovery(Origin::signed(1), 5));
+}
+
+#[test]
+fn challenge_period_blocks_rescuer() {
+	new_test_ext().execute_with(|| {
+		ChallengePeriod::set(5);
+		claim_recovery_of_5_by_1();
+		assert_eq!(Recovery::challenge_ends(&1), Some(16));
+		// The rescuer cannot act as the lost account during the challenge period
+		let call = Box::new(Call::Balances(BalancesCall::transfer(1, 10)));
+		assert_noop!(
+			Recovery::as_recovered(Origin::signed(1), 5, call.clone()),
+			Error::<Test>::ChallengePeriod
+		);
+		assert_noop!(
+			Recovery::finish_recovery(Origin::signed(1), 5, 1_000),
+			Error::<Test>::ChallengePeriod
+		);
+		run_to_block(16);
+		// The lost account can no longer veto
+		assert_noop!(Recovery::veto_recovery(Origin::signed(5), 1), Error::<Test>::NotChallengeable);
+		assert_ok!(Recovery::as_recovered(Origin::signed(1), 5, call));
+		assert_eq!(Recovery::challenge_ends(&1), None);
+	});
+}
+
+#[test]
+fn veto_recovery_works() {
+	new_test_ext().execute_with(|| {
+		ChallengePeriod::set(5);
+		claim_recovery_of_5_by_1();
+		// Only the lost account can veto
+		assert_noop!(Recovery::veto_recovery(Origin::signed(2), 1), Error::<Test>::NotAllowed);
+		assert_noop!(Recovery::veto_recovery(Origin::signed(5), 2), Error::<Test>::NotAllowed);
+		assert_ok!(Recovery::veto_recovery(Origin::signed(5), 1));
+		// The link is gone and the rescuer lost its recovery deposit
+		assert_eq!(Recovery::proxy(&1), None);
+		assert_eq!(Recovery::challenge_ends(&1), None);
+		assert_eq!(Recovery::active_recovery(&5, &1), None);
+		assert_eq!(Balances::total_balance(&1), 90);
+		assert_eq!(Balances::free_balance(5), 97);
+		let call = Box::new(Call::Balances(BalancesCall::transfer(1, 10)));
+		assert_noop!(Recovery::as_recovered(Origin::signed(1), 5, call), Error::<Test>::NotAllowed);
+	});
+}
+
+#[test]
+fn finish_recovery_works() {
+	new_test_ext().execute_with(|| {
+		claim_recovery_of_5_by_1();
+		// No challenge period is configured
+		assert_eq!(Recovery::challenge_ends(&1), None);
+		assert_noop!(
+			Recovery::finish_recovery(Origin::signed(2), 5, 1_000),
+			Error::<Test>::NotAllowed
+		);
+		// Not enough weight for the migration
+		assert_noop!(
+			Recovery::finish_recovery(Origin::signed(1), 5, 999),
+			Error::<Test>::WeightTooLow
+		);
+		let post_info = Recovery::finish_recovery(Origin::signed(1), 5, 1_000).unwrap();
+		assert_eq!(post_info.actual_weight, Some(50_001_000));
+		assert_eq!(MIGRATED.with(|m| m.borrow().clone()), vec![(5, 1)]);
+		// The link stays so leftovers can be handled with `as_recovered`
+		assert_eq!(Recovery::proxy(&1), Some(5));
+	});
+}
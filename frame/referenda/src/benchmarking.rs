@@ -0,0 +1,221 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Referenda pallet benchmarking.
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, account, whitelist_account};
+use frame_support::traits::{Currency, Get, EnsureOrigin, OnInitialize, UnfilteredDispatchable, schedule::DispatchTime};
+use frame_system::{RawOrigin, Module as System};
+use sp_runtime::traits::{Bounded, Hash};
+
+use crate::Module as Referenda;
+
+const SEED: u32 = 0;
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let caller: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	caller
+}
+
+/// Submit a referendum on the track of the root origin, which the runtime must provide.
+fn create_referendum<T: Config>(n: u32) -> ReferendumIndex {
+	let caller = funded_account::<T>("submitter", n);
+	let proposal_hash: T::Hash = T::Hashing::hash_of(&n);
+	Referenda::<T>::submit(
+		RawOrigin::Signed(caller).into(),
+		Box::new(RawOrigin::Root.into()),
+		proposal_hash,
+		DispatchTime::After(0u32.into()),
+	).expect("the root origin must have a track");
+	ReferendumCount::get() - 1
+}
+
+fn place_deposit<T: Config>(index: ReferendumIndex) {
+	let caller = funded_account::<T>("depositor", index);
+	Referenda::<T>::place_decision_deposit(RawOrigin::Signed(caller).into(), index)
+		.expect("referendum is ongoing");
+}
+
+fn account_vote<T: Config>(b: BalanceOf<T>) -> AccountVote<BalanceOf<T>> {
+	let v = Vote {
+		aye: true,
+		conviction: Conviction::Locked1x,
+	};
+
+	AccountVote::Standard {
+		vote: v,
+		balance: b,
+	}
+}
+
+fn alarm_of<T: Config>(index: ReferendumIndex) -> Option<T::BlockNumber> {
+	match Referenda::<T>::referendum_info(index) {
+		Some(ReferendumInfo::Ongoing(status)) => status.alarm,
+		_ => None,
+	}
+}
+
+benchmarks! {
+	_ { }
+
+	submit {
+		let caller = funded_account::<T>("caller", 0);
+		whitelist_account!(caller);
+		let proposal_hash: T::Hash = T::Hashing::hash_of(&0);
+	}: _(
+		RawOrigin::Signed(caller),
+		Box::new(RawOrigin::Root.into()),
+		proposal_hash,
+		DispatchTime::After(0u32.into())
+	)
+	verify {
+		assert_eq!(Referenda::<T>::referendum_count(), 1, "Referendum not submitted");
+	}
+
+	place_decision_deposit {
+		let index = create_referendum::<T>(0);
+		let caller = funded_account::<T>("caller", 0);
+		whitelist_account!(caller);
+	}: _(RawOrigin::Signed(caller), index)
+	verify {
+		match Referenda::<T>::referendum_info(index) {
+			Some(ReferendumInfo::Ongoing(status)) =>
+				assert!(status.decision_deposit.is_some(), "Deposit not placed"),
+			_ => panic!("referendum not ongoing"),
+		}
+	}
+
+	vote {
+		let r in 1 .. T::MaxVotes::get();
+
+		let caller = funded_account::<T>("caller", 0);
+		let account_vote = account_vote::<T>(100u32.into());
+
+		// Vote on all but one of the referenda.
+		for i in 0 .. r {
+			let index = create_referendum::<T>(i);
+			if i + 1 < r {
+				Referenda::<T>::vote(RawOrigin::Signed(caller.clone()).into(), index, account_vote)?;
+			}
+		}
+		let index = r - 1;
+		whitelist_account!(caller);
+	}: _(RawOrigin::Signed(caller.clone()), index, account_vote)
+	verify {
+		assert_eq!(Referenda::<T>::voting_for(&caller).votes.len(), r as usize, "Vote was not recorded.");
+	}
+
+	remove_vote {
+		let r in 1 .. T::MaxVotes::get();
+
+		let caller = funded_account::<T>("caller", 0);
+		let account_vote = account_vote::<T>(100u32.into());
+
+		for i in 0 .. r {
+			let index = create_referendum::<T>(i);
+			Referenda::<T>::vote(RawOrigin::Signed(caller.clone()).into(), index, account_vote)?;
+		}
+		let index = r - 1;
+		whitelist_account!(caller);
+	}: _(RawOrigin::Signed(caller.clone()), index)
+	verify {
+		assert_eq!(Referenda::<T>::voting_for(&caller).votes.len(), (r - 1) as usize, "Vote was not removed");
+	}
+
+	unlock {
+		let r in 1 .. T::MaxVotes::get();
+
+		let locker = funded_account::<T>("locker", 0);
+		let account_vote = account_vote::<T>(100u32.into());
+
+		for i in 0 .. r {
+			let index = create_referendum::<T>(i);
+			Referenda::<T>::vote(RawOrigin::Signed(locker.clone()).into(), index, account_vote)?;
+		}
+
+		let caller = funded_account::<T>("caller", 0);
+		whitelist_account!(caller);
+	}: _(RawOrigin::Signed(caller), locker.clone())
+	verify {
+		assert_eq!(Referenda::<T>::voting_for(&locker).votes.len(), r as usize, "Votes were removed");
+	}
+
+	cancel {
+		let index = create_referendum::<T>(0);
+		place_deposit::<T>(index);
+		let origin = T::CancelOrigin::successful_origin();
+		let call = Call::<T>::cancel(index);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		match Referenda::<T>::referendum_info(index) {
+			Some(ReferendumInfo::Cancelled(_)) => (),
+			_ => panic!("referendum not cancelled"),
+		}
+	}
+
+	kill {
+		let index = create_referendum::<T>(0);
+		place_deposit::<T>(index);
+		let origin = T::KillOrigin::successful_origin();
+		let call = Call::<T>::kill(index);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		match Referenda::<T>::referendum_info(index) {
+			Some(ReferendumInfo::Killed(_)) => (),
+			_ => panic!("referendum not killed"),
+		}
+	}
+
+	// Worst case for servicing a referendum: it starts deciding at the end of its prepare period.
+	service_referendum {
+		let index = create_referendum::<T>(0);
+		place_deposit::<T>(index);
+		let when = alarm_of::<T>(index).ok_or("referendum has no alarm")?;
+		System::<T>::set_block_number(when);
+	}: { Referenda::<T>::on_initialize(when) }
+	verify {
+		match Referenda::<T>::referendum_info(index) {
+			Some(ReferendumInfo::Ongoing(status)) =>
+				assert!(status.deciding.is_some() || status.in_queue, "Referendum not serviced"),
+			_ => (),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_submit::<Test>());
+			assert_ok!(test_benchmark_place_decision_deposit::<Test>());
+			assert_ok!(test_benchmark_vote::<Test>());
+			assert_ok!(test_benchmark_remove_vote::<Test>());
+			assert_ok!(test_benchmark_unlock::<Test>());
+			assert_ok!(test_benchmark_cancel::<Test>());
+			assert_ok!(test_benchmark_kill::<Test>());
+			assert_ok!(test_benchmark_service_referendum::<Test>());
+		});
+	}
+}
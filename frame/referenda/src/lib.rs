@@ -0,0 +1,779 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Referenda Module
+//!
+//! - [`referenda::Config`](./trait.Config.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! The Referenda pallet handles a collection of referenda which are decided on a number of
+//! tracks. Every proposal is submitted together with the origin it should be dispatched from if
+//! approved, and this origin determines the track of the referendum through
+//! `Config::Tracks`. Each track has its own decision deposit, limit on the number of referenda
+//! deciding at once, preparation, decision and confirmation periods, and approval and support
+//! curves.
+//!
+//! ### Lifecycle
+//!
+//! - A referendum is *submitted* with a submission deposit. It can only start deciding once a
+//!   decision deposit has been placed for it and the prepare period of its track has passed. If no
+//!   decision deposit is placed within `UndecidingTimeout`, the referendum times out.
+//! - A referendum which is ready to decide starts *deciding* as soon as its track has a free
+//!   deciding slot; until then it waits in the queue of its track.
+//! - While deciding, a referendum is *passing* if both its approval (the share of
+//!   conviction-weighted aye votes in all votes) and its support (the share of the total issuance
+//!   voting aye, without conviction) are above the curves of its track. The curves are evaluated at
+//!   the share of the decision period which has elapsed.
+//! - A referendum which keeps passing for the confirm period of its track is *approved*, and its
+//!   proposal is scheduled for dispatch with the given origin through `Config::Scheduler`, no
+//!   earlier than the minimum enactment period of the track. A referendum which is not confirming
+//!   at the end of its decision period is *rejected*.
+//!
+//! Deposits are returned once a referendum is concluded, unless it was killed, in which case they
+//! are slashed.
+//!
+//! Voting uses the conviction voting of the democracy pallet: the voted balance is locked, and
+//! accounts on the winning side of a referendum with conviction stay locked for the corresponding
+//! number of `VoteLockingPeriod`s.
+//!
+//! Proposals are identified by their hash. The preimage of a proposal is requested from
+//! `Config::Preimages` for as long as its referendum is ongoing, so that it can be noted for free.
+//! A referendum which has finished confirming while the preimage of its proposal is missing
+//! stays ongoing, and is approved once the preimage is noted and it is still passing. If the
+//! preimage is still missing `UndecidingTimeout` after the end of its confirm period, the
+//! referendum times out and frees its deciding slot.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `submit` - Submit a proposal on the track of its origin. Requires the submission deposit.
+//! - `place_decision_deposit` - Place the decision deposit of a referendum.
+//! - `vote` - Vote in an ongoing referendum.
+//! - `remove_vote` - Remove a vote, locking the balance if needed.
+//! - `unlock` - Redetermine the account's balance lock, potentially making tokens available.
+//! - `cancel` - Cancel a referendum, returning its deposits. Requires `CancelOrigin`.
+//! - `kill` - Cancel a referendum, slashing its deposits. Requires `KillOrigin`.
+
+#![recursion_limit="128"]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use sp_runtime::{
+	DispatchResult, Perbill,
+	traits::{Zero, Saturating, Dispatchable},
+};
+use codec::{Encode, Decode};
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, ensure, Parameter,
+	weights::Weight,
+	traits::{
+		Currency, ReservableCurrency, LockableCurrency, WithdrawReasons, LockIdentifier, Get,
+		OnUnbalanced, EnsureOrigin, PreimageProvider,
+		schedule::{Named as ScheduleNamed, DispatchTime, HARD_DEADLINE},
+	},
+};
+use frame_system::{self as system, ensure_signed};
+
+mod types;
+pub mod weights;
+pub use weights::WeightInfo;
+pub use types::{
+	ReferendumIndex, TrackId, TrackInfo, TracksInfo, Curve, Tally, Deposit, DecidingStatus,
+	ReferendumStatus, ReferendumInfo, Voting,
+};
+pub use pallet_democracy::{AccountVote, Conviction, Vote};
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+const REFERENDA_ID: LockIdentifier = *b"referend";
+
+type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
+type ReferendumStatusOf<T> = ReferendumStatus<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	<T as frame_system::Config>::BlockNumber,
+	<T as frame_system::Config>::Hash,
+	<T as Config>::PalletsOrigin,
+>;
+type ReferendumInfoOf<T> = ReferendumInfo<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	<T as frame_system::Config>::BlockNumber,
+	<T as frame_system::Config>::Hash,
+	<T as Config>::PalletsOrigin,
+>;
+type TrackInfoOf<T> = TrackInfo<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+pub trait Config: frame_system::Config + Sized {
+	type Proposal: Parameter + Dispatchable<Origin=Self::Origin>;
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+	/// Currency type for this module.
+	type Currency: ReservableCurrency<Self::AccountId>
+		+ LockableCurrency<Self::AccountId, Moment=Self::BlockNumber>;
+
+	/// The Scheduler used to dispatch the proposals of approved referenda.
+	type Scheduler: ScheduleNamed<Self::BlockNumber, Self::Proposal, Self::PalletsOrigin>;
+
+	/// Overarching type of all pallets origins. Proposals are dispatched from one of these.
+	type PalletsOrigin: Parameter + From<system::RawOrigin<Self::AccountId>>;
+
+	/// The preimage provider used to look up the preimages of proposals.
+	type Preimages: PreimageProvider<Self::Hash>;
+
+	/// The tracks on which referenda are decided, and the track of each origin.
+	type Tracks: TracksInfo<BalanceOf<Self>, Self::BlockNumber, Origin=Self::PalletsOrigin>;
+
+	/// Origin from which any ongoing referendum may be cancelled, returning its deposits.
+	type CancelOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Origin from which any ongoing referendum may be killed, slashing its deposits.
+	type KillOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Handler for the unbalanced reduction when slashing the deposits of a killed referendum.
+	type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// The amount which must be placed on deposit to submit a referendum.
+	type SubmissionDeposit: Get<BalanceOf<Self>>;
+
+	/// The time after its submission after which a referendum without a decision deposit times
+	/// out. Also the time after the end of its confirm period after which a referendum whose
+	/// proposal preimage is still missing times out.
+	type UndecidingTimeout: Get<Self::BlockNumber>;
+
+	/// The period for which the balance of a winning voter is locked per conviction lock period.
+	type VoteLockingPeriod: Get<Self::BlockNumber>;
+
+	/// The maximum number of ongoing votes of a single account.
+	///
+	/// Also used to compute weight, an overly big value can
+	/// lead to extrinsic with very big weight: see `vote` for example.
+	type MaxVotes: Get<u32>;
+
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as Referenda {
+		/// The next free referendum index, aka the number of referenda started so far.
+		pub ReferendumCount get(fn referendum_count): ReferendumIndex;
+
+		/// Information concerning any given referendum.
+		///
+		/// TWOX-NOTE: SAFE as indexes are not under an attacker’s control.
+		pub ReferendumInfoFor get(fn referendum_info):
+			map hasher(twox_64_concat) ReferendumIndex => Option<ReferendumInfoOf<T>>;
+
+		/// The referenda of each track which are ready to decide, but wait for a free deciding
+		/// slot, in order of arrival.
+		pub TrackQueue get(fn track_queue): map hasher(twox_64_concat) TrackId => Vec<ReferendumIndex>;
+
+		/// The number of referenda deciding on each track.
+		pub DecidingCount get(fn deciding_count): map hasher(twox_64_concat) TrackId => u32;
+
+		/// The referenda to service at a given block.
+		///
+		/// Superseded alarms are removed when a referendum is serviced or concluded.
+		pub Alarms: map hasher(twox_64_concat) T::BlockNumber => Vec<ReferendumIndex>;
+
+		/// All votes for a particular voter. We store the balance for the number of votes that we
+		/// have recorded.
+		///
+		/// TWOX-NOTE: SAFE as `AccountId`s are crypto hashes anyway.
+		pub VotingFor get(fn voting_for):
+			map hasher(twox_64_concat) T::AccountId => Voting<BalanceOf<T>, T::BlockNumber>;
+	}
+}
+
+decl_event! {
+	pub enum Event<T> where
+		Balance = BalanceOf<T>,
+		<T as frame_system::Config>::AccountId,
+		<T as frame_system::Config>::Hash,
+	{
+		/// A referendum has been submitted. \[ref_index, track, proposal_hash\]
+		Submitted(ReferendumIndex, TrackId, Hash),
+		/// The decision deposit of a referendum has been placed. \[ref_index, who, amount\]
+		DecisionDepositPlaced(ReferendumIndex, AccountId, Balance),
+		/// A referendum has started deciding. \[ref_index, track, proposal_hash, tally\]
+		DecisionStarted(ReferendumIndex, TrackId, Hash, Tally<Balance>),
+		/// A referendum has started passing and is confirming. \[ref_index\]
+		ConfirmStarted(ReferendumIndex),
+		/// A referendum has stopped passing while confirming. \[ref_index\]
+		ConfirmAborted(ReferendumIndex),
+		/// A referendum has been approved and its proposal scheduled. \[ref_index\]
+		Approved(ReferendumIndex),
+		/// A referendum has been rejected. \[ref_index, tally\]
+		Rejected(ReferendumIndex, Tally<Balance>),
+		/// A referendum has timed out without a decision deposit or without the preimage of its
+		/// proposal. \[ref_index\]
+		TimedOut(ReferendumIndex),
+		/// A referendum has been cancelled. \[ref_index\]
+		Cancelled(ReferendumIndex),
+		/// A referendum has been killed. \[ref_index\]
+		Killed(ReferendumIndex),
+		/// A referendum has finished confirming but the preimage of its proposal is missing or
+		/// invalid. It stays ongoing until the preimage is noted or it times out.
+		/// \[ref_index, proposal_hash\]
+		PreimageUnavailable(ReferendumIndex, Hash),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// There is no track for the origin of the proposal.
+		NoTrack,
+		/// The track of the referendum does not exist.
+		BadTrack,
+		/// The referendum does not exist or is not ongoing.
+		NotOngoing,
+		/// The decision deposit of the referendum has already been placed.
+		HasDeposit,
+		/// Too high a balance was provided that the account cannot afford.
+		InsufficientFunds,
+		/// Maximum number of votes reached.
+		MaxVotesReached,
+		/// The account is not voting in the referendum.
+		NotVoter,
+		/// The tally of the referendum overflowed.
+		Overflow,
+		/// The tally of the referendum underflowed.
+		Underflow,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		/// The amount which must be placed on deposit to submit a referendum.
+		const SubmissionDeposit: BalanceOf<T> = T::SubmissionDeposit::get();
+
+		/// The time after its submission after which a referendum without a decision deposit
+		/// times out. Also the time after the end of its confirm period after which a referendum
+		/// whose proposal preimage is still missing times out.
+		const UndecidingTimeout: T::BlockNumber = T::UndecidingTimeout::get();
+
+		/// The period for which the balance of a winning voter is locked per conviction lock
+		/// period.
+		const VoteLockingPeriod: T::BlockNumber = T::VoteLockingPeriod::get();
+
+		/// The maximum number of ongoing votes of a single account.
+		const MaxVotes: u32 = T::MaxVotes::get();
+
+		fn deposit_event() = default;
+
+		/// Submit a proposal to be decided on the track of `proposal_origin`.
+		///
+		/// The dispatch origin of this call must be _Signed_ and the sender must
+		/// have funds to cover the submission deposit.
+		///
+		/// - `proposal_origin`: The origin from which the proposal should be dispatched.
+		/// - `proposal_hash`: The hash of the proposal preimage.
+		/// - `enactment_moment`: When the proposal should be dispatched if approved. It is never
+		///   dispatched earlier than the minimum enactment period of the track after approval.
+		///
+		/// Emits `Submitted`.
+		///
+		/// Weight: `O(1)`.
+		#[weight = T::WeightInfo::submit()]
+		fn submit(origin,
+			proposal_origin: Box<T::PalletsOrigin>,
+			proposal_hash: T::Hash,
+			enactment_moment: DispatchTime<T::BlockNumber>,
+		) {
+			let who = ensure_signed(origin)?;
+			let track = T::Tracks::track_for(&proposal_origin).map_err(|_| Error::<T>::NoTrack)?;
+			let amount = T::SubmissionDeposit::get();
+			T::Currency::reserve(&who, amount)?;
+
+			let index = Self::referendum_count();
+			ReferendumCount::put(index + 1);
+			T::Preimages::request_preimage(&proposal_hash);
+
+			let now = system::Module::<T>::block_number();
+			let mut status = ReferendumStatus {
+				track,
+				origin: *proposal_origin,
+				proposal_hash,
+				enactment: enactment_moment,
+				submitted: now,
+				submission_deposit: Deposit { who, amount },
+				decision_deposit: None,
+				deciding: None,
+				tally: Default::default(),
+				in_queue: false,
+				alarm: None,
+			};
+			Self::set_alarm(index, &mut status, Some(now.saturating_add(T::UndecidingTimeout::get())));
+			ReferendumInfoFor::<T>::insert(index, ReferendumInfo::Ongoing(status));
+
+			Self::deposit_event(RawEvent::Submitted(index, track, proposal_hash));
+		}
+
+		/// Place the decision deposit of an ongoing referendum.
+		///
+		/// The dispatch origin of this call must be _Signed_ and the sender must
+		/// have funds to cover the decision deposit of the track of the referendum.
+		///
+		/// - `index`: The index of the referendum.
+		///
+		/// Emits `DecisionDepositPlaced`, and `DecisionStarted` if the referendum starts deciding
+		/// right away.
+		///
+		/// Weight: `O(1)`.
+		#[weight = T::WeightInfo::place_decision_deposit()]
+		fn place_decision_deposit(origin, #[compact] index: ReferendumIndex) {
+			let who = ensure_signed(origin)?;
+			let mut status = Self::ensure_ongoing(index)?;
+			ensure!(status.decision_deposit.is_none(), Error::<T>::HasDeposit);
+			let track = T::Tracks::info(status.track).ok_or(Error::<T>::BadTrack)?;
+			let amount = track.decision_deposit;
+			T::Currency::reserve(&who, amount)?;
+			status.decision_deposit = Some(Deposit { who: who.clone(), amount });
+
+			Self::deposit_event(RawEvent::DecisionDepositPlaced(index, who, amount));
+			let now = system::Module::<T>::block_number();
+			Self::service_and_store(now, index, status);
+		}
+
+		/// Vote in an ongoing referendum. If `vote.is_aye()`, the vote is to enact the proposal;
+		/// otherwise it is a vote to keep the status-quo.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `index`: The index of the referendum to vote for.
+		/// - `vote`: The vote configuration.
+		///
+		/// Weight: `O(R)` where R is the number of referenda the voter has voted on.
+		#[weight = T::WeightInfo::vote(T::MaxVotes::get())]
+		fn vote(origin, #[compact] index: ReferendumIndex, vote: AccountVote<BalanceOf<T>>) {
+			let who = ensure_signed(origin)?;
+			ensure!(vote.balance() <= T::Currency::free_balance(&who), Error::<T>::InsufficientFunds);
+			let mut status = Self::ensure_ongoing(index)?;
+
+			VotingFor::<T>::try_mutate(&who, |voting| -> DispatchResult {
+				match voting.votes.binary_search_by_key(&index, |i| i.0) {
+					Ok(i) => {
+						// Shouldn't be possible to fail, but we handle it gracefully.
+						status.tally.remove(voting.votes[i].1).ok_or(Error::<T>::Underflow)?;
+						voting.votes[i].1 = vote;
+					}
+					Err(i) => {
+						ensure!(
+							(voting.votes.len() as u32) < T::MaxVotes::get(),
+							Error::<T>::MaxVotesReached
+						);
+						voting.votes.insert(i, (index, vote));
+					}
+				}
+				// Shouldn't be possible to fail, but we handle it gracefully.
+				status.tally.add(vote).ok_or(Error::<T>::Overflow)?;
+				Ok(())
+			})?;
+			// Extend the lock to `balance` (rather than setting it) since we don't know what other
+			// votes are in place.
+			T::Currency::extend_lock(REFERENDA_ID, &who, vote.balance(), WithdrawReasons::TRANSFER);
+
+			let now = system::Module::<T>::block_number();
+			Self::service_and_store(now, index, status);
+		}
+
+		/// Remove a vote for a referendum.
+		///
+		/// If the referendum is ongoing, the vote is removed from its tally. If the referendum was
+		/// approved or rejected and the vote was on the winning side with conviction, the voted
+		/// balance stays locked for the corresponding number of `VoteLockingPeriod`s.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `index`: The index of the referendum.
+		///
+		/// Weight: `O(R)` where R is the number of referenda the voter has voted on.
+		#[weight = T::WeightInfo::remove_vote(T::MaxVotes::get())]
+		fn remove_vote(origin, #[compact] index: ReferendumIndex) {
+			let who = ensure_signed(origin)?;
+			let info = Self::referendum_info(index);
+			VotingFor::<T>::try_mutate(&who, |voting| -> DispatchResult {
+				let i = voting.votes.binary_search_by_key(&index, |i| i.0)
+					.map_err(|_| Error::<T>::NotVoter)?;
+				let (_, vote) = voting.votes.remove(i);
+				let now = system::Module::<T>::block_number();
+				let (end, approved) = match info {
+					Some(ReferendumInfo::Ongoing(mut status)) => {
+						// Shouldn't be possible to fail, but we handle it gracefully.
+						status.tally.remove(vote).ok_or(Error::<T>::Underflow)?;
+						Self::service_and_store(now, index, status);
+						return Ok(());
+					}
+					Some(ReferendumInfo::Approved(end)) => (end, true),
+					Some(ReferendumInfo::Rejected(end)) => (end, false),
+					// Referendum was cancelled, timed out or killed.
+					_ => return Ok(()),
+				};
+				if let Some((lock_periods, balance)) = vote.locked_if(approved) {
+					let unlock_at = end.saturating_add(
+						T::VoteLockingPeriod::get().saturating_mul(lock_periods.into())
+					);
+					if now < unlock_at {
+						voting.prior.accumulate(unlock_at, balance);
+					}
+				}
+				Ok(())
+			})?;
+		}
+
+		/// Unlock tokens that have an expired lock.
+		///
+		/// The dispatch origin of this call must be _Signed_.
+		///
+		/// - `target`: The account to remove the lock on.
+		///
+		/// Weight: `O(R)` with R number of vote of target.
+		#[weight = T::WeightInfo::unlock(T::MaxVotes::get())]
+		fn unlock(origin, target: T::AccountId) {
+			ensure_signed(origin)?;
+			Self::update_lock(&target);
+		}
+
+		/// Cancel an ongoing referendum, returning its deposits.
+		///
+		/// The dispatch origin of this call must be `CancelOrigin`.
+		///
+		/// - `index`: The index of the referendum to cancel.
+		///
+		/// Weight: `O(1)`.
+		#[weight = T::WeightInfo::cancel()]
+		fn cancel(origin, #[compact] index: ReferendumIndex) {
+			T::CancelOrigin::ensure_origin(origin)?;
+			let status = Self::ensure_ongoing(index)?;
+			let now = system::Module::<T>::block_number();
+			Self::conclude(now, index, status, ReferendumInfo::Cancelled(now), false);
+			Self::deposit_event(RawEvent::Cancelled(index));
+		}
+
+		/// Cancel an ongoing referendum, slashing its deposits.
+		///
+		/// The dispatch origin of this call must be `KillOrigin`.
+		///
+		/// - `index`: The index of the referendum to kill.
+		///
+		/// Weight: `O(1)`.
+		#[weight = T::WeightInfo::kill()]
+		fn kill(origin, #[compact] index: ReferendumIndex) {
+			T::KillOrigin::ensure_origin(origin)?;
+			let status = Self::ensure_ongoing(index)?;
+			let now = system::Module::<T>::block_number();
+			Self::conclude(now, index, status, ReferendumInfo::Killed(now), true);
+			Self::deposit_event(RawEvent::Killed(index));
+		}
+
+		/// Service the referenda which have an alarm at this block.
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let alarms = Alarms::<T>::take(n);
+			let weight = T::DbWeight::get().reads_writes(1, 1)
+				.saturating_add(T::WeightInfo::service_referendum().saturating_mul(alarms.len() as Weight));
+			for index in alarms {
+				if let Some(ReferendumInfo::Ongoing(status)) = Self::referendum_info(index) {
+					if status.alarm == Some(n) {
+						Self::service_and_store(n, index, status);
+					}
+				}
+			}
+			weight
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	// exposed immutables.
+
+	/// The track of each origin, and the parameters of each track.
+	pub fn tracks() -> &'static [(TrackId, TrackInfoOf<T>)] {
+		T::Tracks::tracks()
+	}
+
+	// private.
+
+	/// Ok if the given referendum is ongoing, with its status.
+	fn ensure_ongoing(index: ReferendumIndex) -> Result<ReferendumStatusOf<T>, Error<T>> {
+		match Self::referendum_info(index) {
+			Some(ReferendumInfo::Ongoing(status)) => Ok(status),
+			_ => Err(Error::<T>::NotOngoing),
+		}
+	}
+
+	/// Rejig the lock on an account. It will never get more stringent (since that would indicate
+	/// a security hole) but may be reduced from what they are currently.
+	fn update_lock(who: &T::AccountId) {
+		let now = system::Module::<T>::block_number();
+		let lock_needed = VotingFor::<T>::mutate(who, |voting| {
+			voting.prior.rejig(now);
+			voting.locked_balance()
+		});
+		if lock_needed.is_zero() {
+			T::Currency::remove_lock(REFERENDA_ID, who);
+		} else {
+			T::Currency::set_lock(REFERENDA_ID, who, lock_needed, WithdrawReasons::TRANSFER);
+		}
+	}
+
+	/// Set the block at which `status` needs to be serviced next, if any, removing its previous
+	/// alarm.
+	fn set_alarm(index: ReferendumIndex, status: &mut ReferendumStatusOf<T>, alarm: Option<T::BlockNumber>) {
+		if status.alarm != alarm {
+			if let Some(old) = status.alarm {
+				Self::remove_alarm(index, old);
+			}
+			if let Some(when) = alarm {
+				Alarms::<T>::append(when, index);
+			}
+			status.alarm = alarm;
+		}
+	}
+
+	/// Remove the alarm of referendum `index` at block `when`.
+	fn remove_alarm(index: ReferendumIndex, when: T::BlockNumber) {
+		Alarms::<T>::mutate_exists(when, |maybe_alarms| {
+			if let Some(alarms) = maybe_alarms {
+				alarms.retain(|i| *i != index);
+				if alarms.is_empty() {
+					*maybe_alarms = None;
+				}
+			}
+		});
+	}
+
+	/// Service a referendum and store the result, starting to decide on the next queued
+	/// referendum of its track if this frees a deciding slot.
+	fn service_and_store(now: T::BlockNumber, index: ReferendumIndex, status: ReferendumStatusOf<T>) {
+		let track = status.track;
+		let (info, freed_slot) = Self::service_referendum(now, index, status);
+		ReferendumInfoFor::<T>::insert(index, info);
+		if freed_slot {
+			Self::note_one_fewer_deciding(now, track);
+		}
+	}
+
+	/// Release a deciding slot of `track`, and use it for the next queued referendum, if any.
+	fn note_one_fewer_deciding(now: T::BlockNumber, track: TrackId) {
+		DecidingCount::mutate(track, |c| *c = c.saturating_sub(1));
+		let next = TrackQueue::mutate(track, |queue| {
+			if queue.is_empty() { None } else { Some(queue.remove(0)) }
+		});
+		if let Some(index) = next {
+			if let Some(ReferendumInfo::Ongoing(mut status)) = Self::referendum_info(index) {
+				status.in_queue = false;
+				Self::service_and_store(now, index, status);
+			}
+		}
+	}
+
+	/// Move an ongoing referendum along its lifecycle at block `now`.
+	///
+	/// Returns the new info of the referendum, and whether a deciding slot of its track was freed.
+	fn service_referendum(
+		now: T::BlockNumber,
+		index: ReferendumIndex,
+		mut status: ReferendumStatusOf<T>,
+	) -> (ReferendumInfoOf<T>, bool) {
+		let track = match T::Tracks::info(status.track) {
+			Some(track) => track,
+			None => {
+				frame_support::print("LOGIC ERROR: service_referendum/unknown track");
+				return (ReferendumInfo::Ongoing(status), false);
+			}
+		};
+
+		if status.deciding.is_none() {
+			if status.decision_deposit.is_none() {
+				let timeout = status.submitted.saturating_add(T::UndecidingTimeout::get());
+				if now >= timeout {
+					Self::conclude_deposits(index, &status, false);
+					Self::deposit_event(RawEvent::TimedOut(index));
+					return (ReferendumInfo::TimedOut(now), false);
+				}
+				Self::set_alarm(index, &mut status, Some(timeout));
+				return (ReferendumInfo::Ongoing(status), false);
+			}
+			if status.in_queue {
+				Self::set_alarm(index, &mut status, None);
+				return (ReferendumInfo::Ongoing(status), false);
+			}
+			let prepare_end = status.submitted.saturating_add(track.prepare_period);
+			if now < prepare_end {
+				Self::set_alarm(index, &mut status, Some(prepare_end));
+				return (ReferendumInfo::Ongoing(status), false);
+			}
+			if Self::deciding_count(status.track) >= track.max_deciding {
+				TrackQueue::append(status.track, index);
+				status.in_queue = true;
+				Self::set_alarm(index, &mut status, None);
+				return (ReferendumInfo::Ongoing(status), false);
+			}
+			DecidingCount::mutate(status.track, |c| *c += 1);
+			status.deciding = Some(DecidingStatus { since: now, confirming: None });
+			Self::deposit_event(RawEvent::DecisionStarted(
+				index,
+				status.track,
+				status.proposal_hash,
+				status.tally,
+			));
+		}
+
+		let (since, confirming) = match status.deciding {
+			Some(ref deciding) => (deciding.since, deciding.confirming),
+			None => return (ReferendumInfo::Ongoing(status), false),
+		};
+		let elapsed = now.saturating_sub(since);
+		let decision_end = since.saturating_add(track.decision_period);
+		let x = if track.decision_period.is_zero() {
+			Perbill::one()
+		} else {
+			Perbill::from_rational_approximation(elapsed.min(track.decision_period), track.decision_period)
+		};
+		let approval = status.tally.approval();
+		let support = status.tally.support(T::Currency::total_issuance());
+		let passing = track.min_approval.passing(x, approval) && track.min_support.passing(x, support);
+
+		if passing {
+			let confirm_end = match confirming {
+				Some(end) => end,
+				None => {
+					let end = now.saturating_add(track.confirm_period);
+					status.deciding = Some(DecidingStatus { since, confirming: Some(end) });
+					Self::deposit_event(RawEvent::ConfirmStarted(index));
+					end
+				}
+			};
+			if now >= confirm_end {
+				let proposal = T::Preimages::get_preimage(&status.proposal_hash)
+					.and_then(|data| T::Proposal::decode(&mut &data[..]).ok());
+				if let Some(proposal) = proposal {
+					Self::schedule_enactment(now, index, &status, track, proposal);
+					Self::conclude_deposits(index, &status, false);
+					Self::deposit_event(RawEvent::Approved(index));
+					return (ReferendumInfo::Approved(now), true);
+				}
+				// The preimage is still requested, so it can be noted for free. Check again on the
+				// next block, until the referendum times out and frees its deciding slot.
+				let timeout = confirm_end.saturating_add(T::UndecidingTimeout::get());
+				if now >= timeout {
+					Self::conclude_deposits(index, &status, false);
+					Self::deposit_event(RawEvent::TimedOut(index));
+					return (ReferendumInfo::TimedOut(now), true);
+				}
+				if now == confirm_end {
+					Self::deposit_event(RawEvent::PreimageUnavailable(index, status.proposal_hash));
+				}
+				Self::set_alarm(index, &mut status, Some(now.saturating_add(1u32.into())));
+				return (ReferendumInfo::Ongoing(status), false);
+			}
+			Self::set_alarm(index, &mut status, Some(confirm_end));
+		} else {
+			if confirming.is_some() {
+				status.deciding = Some(DecidingStatus { since, confirming: None });
+				Self::deposit_event(RawEvent::ConfirmAborted(index));
+			}
+			if now >= decision_end {
+				Self::conclude_deposits(index, &status, false);
+				Self::deposit_event(RawEvent::Rejected(index, status.tally));
+				return (ReferendumInfo::Rejected(now), true);
+			}
+			// With no change to the tally, the referendum starts passing once both curves have
+			// dropped far enough.
+			let delay = track.min_approval.delay(approval).max(track.min_support.delay(support));
+			let when = since.saturating_add(delay * track.decision_period)
+				.max(now.saturating_add(1u32.into()))
+				.min(decision_end);
+			Self::set_alarm(index, &mut status, Some(when));
+		}
+		(ReferendumInfo::Ongoing(status), false)
+	}
+
+	/// Schedule the proposal of an approved referendum for dispatch with its origin.
+	fn schedule_enactment(
+		now: T::BlockNumber,
+		index: ReferendumIndex,
+		status: &ReferendumStatusOf<T>,
+		track: &TrackInfoOf<T>,
+		proposal: T::Proposal,
+	) {
+		let desired = match status.enactment {
+			DispatchTime::At(when) => when,
+			DispatchTime::After(delay) => now.saturating_add(delay),
+		};
+		let earliest = now.saturating_add(track.min_enactment_period.max(1u32.into()));
+		if T::Scheduler::schedule_named(
+			(REFERENDA_ID, index).encode(),
+			DispatchTime::At(desired.max(earliest)),
+			None,
+			HARD_DEADLINE,
+			status.origin.clone(),
+			proposal,
+		).is_err() {
+			frame_support::print("LOGIC ERROR: schedule_enactment/schedule_named failed");
+		}
+	}
+
+	/// Conclude an ongoing referendum with `info`, releasing its deciding slot or its place in
+	/// the queue of its track, and returning or slashing its deposits.
+	fn conclude(
+		now: T::BlockNumber,
+		index: ReferendumIndex,
+		status: ReferendumStatusOf<T>,
+		info: ReferendumInfoOf<T>,
+		slash: bool,
+	) {
+		Self::conclude_deposits(index, &status, slash);
+		ReferendumInfoFor::<T>::insert(index, info);
+		if status.deciding.is_some() {
+			Self::note_one_fewer_deciding(now, status.track);
+		} else if status.in_queue {
+			TrackQueue::mutate(status.track, |queue| queue.retain(|i| *i != index));
+		}
+	}
+
+	/// Return or slash the deposits of a concluded referendum, and drop its preimage request and
+	/// its alarm.
+	fn conclude_deposits(index: ReferendumIndex, status: &ReferendumStatusOf<T>, slash: bool) {
+		let deposits = sp_std::iter::once(&status.submission_deposit)
+			.chain(status.decision_deposit.as_ref());
+		for deposit in deposits {
+			if slash {
+				let (imbalance, _) = T::Currency::slash_reserved(&deposit.who, deposit.amount);
+				T::Slash::on_unbalanced(imbalance);
+			} else {
+				T::Currency::unreserve(&deposit.who, deposit.amount);
+			}
+		}
+		T::Preimages::unrequest_preimage(&status.proposal_hash);
+		if let Some(when) = status.alarm {
+			Self::remove_alarm(index, when);
+		}
+	}
+}
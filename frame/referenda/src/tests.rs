@@ -0,0 +1,687 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The crate's tests.
+
+use super::*;
+use codec::Encode;
+use frame_support::{
+	impl_outer_origin, impl_outer_dispatch, assert_noop, assert_ok, parameter_types,
+	impl_outer_event, ord_parameter_types, traits::OnInitialize, weights::Weight,
+};
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup, BadOrigin, Hash},
+	testing::Header, Perbill,
+};
+use frame_system::{EnsureSignedBy, EnsureRoot, RawOrigin};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		frame_system::System,
+		pallet_balances::Balances,
+		referenda::Referenda,
+	}
+}
+
+mod referenda {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum Event for Test {
+		system<T>,
+		pallet_balances<T>,
+		pallet_scheduler<T>,
+		pallet_preimage<T>,
+		referenda<T>,
+	}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub BlockWeights: frame_system::limits::BlockWeights =
+		frame_system::limits::BlockWeights::simple_max(1_000_000);
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+}
+impl pallet_scheduler::Config for Test {
+	type Event = Event;
+	type Origin = Origin;
+	type PalletsOrigin = OriginCaller;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<u64>;
+	type MaxScheduledPerBlock = ();
+	type WeightInfo = ();
+	type PreimageProvider = Preimage;
+	type NoPreimagePostponement = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const PreimageMaxSize: u32 = 4096;
+	pub const PreimageBaseDeposit: u64 = 0;
+	pub const PreimageByteDeposit: u64 = 0;
+}
+impl pallet_preimage::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<u64>;
+	type MaxSize = PreimageMaxSize;
+	type BaseDeposit = PreimageBaseDeposit;
+	type ByteDeposit = PreimageByteDeposit;
+	type WeightInfo = ();
+}
+
+const ROOT_TRACK: TrackId = 0;
+const NONE_TRACK: TrackId = 1;
+
+pub struct TestTracks;
+impl TracksInfo<u64, u64> for TestTracks {
+	type Origin = OriginCaller;
+	fn tracks() -> &'static [(TrackId, TrackInfo<u64, u64>)] {
+		static DATA: [(TrackId, TrackInfo<u64, u64>); 2] = [
+			(ROOT_TRACK, TrackInfo {
+				name: "root",
+				max_deciding: 1,
+				decision_deposit: 10,
+				prepare_period: 4,
+				decision_period: 4,
+				confirm_period: 2,
+				min_enactment_period: 4,
+				min_approval: Curve::LinearDecreasing {
+					begin: Perbill::from_percent(100),
+					end: Perbill::from_percent(50),
+				},
+				min_support: Curve::LinearDecreasing {
+					begin: Perbill::from_percent(50),
+					end: Perbill::from_percent(0),
+				},
+			}),
+			(NONE_TRACK, TrackInfo {
+				name: "none",
+				max_deciding: 3,
+				decision_deposit: 1,
+				prepare_period: 2,
+				decision_period: 2,
+				confirm_period: 1,
+				min_enactment_period: 2,
+				min_approval: Curve::SteppedDecreasing {
+					begin: Perbill::from_percent(100),
+					end: Perbill::from_percent(50),
+					step: Perbill::from_percent(10),
+					period: Perbill::from_percent(20),
+				},
+				min_support: Curve::LinearDecreasing {
+					begin: Perbill::from_percent(10),
+					end: Perbill::from_percent(0),
+				},
+			}),
+		];
+		&DATA[..]
+	}
+	fn track_for(origin: &OriginCaller) -> Result<TrackId, ()> {
+		match origin {
+			OriginCaller::system(RawOrigin::Root) => Ok(ROOT_TRACK),
+			OriginCaller::system(RawOrigin::None) => Ok(NONE_TRACK),
+			_ => Err(()),
+		}
+	}
+}
+
+parameter_types! {
+	pub const SubmissionDeposit: u64 = 2;
+	pub const UndecidingTimeout: u64 = 20;
+	pub const VoteLockingPeriod: u64 = 3;
+	pub const MaxVotes: u32 = 3;
+}
+ord_parameter_types! {
+	pub const Four: u64 = 4;
+}
+impl super::Config for Test {
+	type Proposal = Call;
+	type Event = Event;
+	type Currency = pallet_balances::Module<Self>;
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
+	type Preimages = Preimage;
+	type Tracks = TestTracks;
+	type CancelOrigin = EnsureSignedBy<Four, u64>;
+	type KillOrigin = EnsureRoot<u64>;
+	type Slash = ();
+	type SubmissionDeposit = SubmissionDeposit;
+	type UndecidingTimeout = UndecidingTimeout;
+	type VoteLockingPeriod = VoteLockingPeriod;
+	type MaxVotes = MaxVotes;
+	type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test>{
+		balances: vec![(1, 10), (2, 20), (3, 30), (4, 40), (5, 50), (6, 60)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+type System = frame_system::Module<Test>;
+type Balances = pallet_balances::Module<Test>;
+type Scheduler = pallet_scheduler::Module<Test>;
+type Preimage = pallet_preimage::Module<Test>;
+type Referenda = Module<Test>;
+
+fn run_to(n: u64) {
+	while System::block_number() < n {
+		let next = System::block_number() + 1;
+		System::set_block_number(next);
+		Referenda::on_initialize(next);
+		Scheduler::on_initialize(next);
+	}
+}
+
+fn set_balance_proposal(value: u64) -> Vec<u8> {
+	Call::Balances(pallet_balances::Call::set_balance(42, value, 0)).encode()
+}
+
+fn set_balance_proposal_hash_and_note(value: u64) -> H256 {
+	let p = set_balance_proposal(value);
+	let h = BlakeTwo256::hash(&p[..]);
+	assert_ok!(Preimage::note_preimage(Origin::signed(6), p));
+	h
+}
+
+fn submit(who: u64, origin: RawOrigin<u64>, proposal_hash: H256) -> ReferendumIndex {
+	let index = Referenda::referendum_count();
+	assert_ok!(Referenda::submit(
+		Origin::signed(who),
+		Box::new(origin.into()),
+		proposal_hash,
+		DispatchTime::After(0),
+	));
+	index
+}
+
+fn aye(conviction: Conviction, balance: u64) -> AccountVote<u64> {
+	AccountVote::Standard { vote: Vote { aye: true, conviction }, balance }
+}
+
+fn nay(conviction: Conviction, balance: u64) -> AccountVote<u64> {
+	AccountVote::Standard { vote: Vote { aye: false, conviction }, balance }
+}
+
+fn deciding_status(index: ReferendumIndex) -> Option<DecidingStatus<u64>> {
+	match Referenda::referendum_info(index) {
+		Some(ReferendumInfo::Ongoing(status)) => status.deciding,
+		_ => None,
+	}
+}
+
+fn referenda_events() -> Vec<RawEvent<u64, u64, H256>> {
+	System::events().into_iter().filter_map(|r| match r.event {
+		Event::referenda(e) => Some(e),
+		_ => None,
+	}).collect()
+}
+
+#[test]
+fn params_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Referenda::referendum_count(), 0);
+		assert_eq!(Balances::total_issuance(), 210);
+		assert_eq!(Referenda::tracks().len(), 2);
+	});
+}
+
+#[test]
+fn curves_work() {
+	let linear = Curve::LinearDecreasing {
+		begin: Perbill::from_percent(100),
+		end: Perbill::from_percent(50),
+	};
+	assert_eq!(linear.threshold(Perbill::zero()), Perbill::from_percent(100));
+	assert_eq!(linear.threshold(Perbill::from_percent(50)), Perbill::from_percent(75));
+	assert_eq!(linear.threshold(Perbill::one()), Perbill::from_percent(50));
+	assert_eq!(linear.delay(Perbill::from_percent(100)), Perbill::zero());
+	assert_eq!(linear.delay(Perbill::from_percent(75)), Perbill::from_percent(50));
+	assert_eq!(linear.delay(Perbill::from_percent(40)), Perbill::one());
+	assert!(linear.passing(Perbill::from_percent(50), Perbill::from_percent(75)));
+	assert!(!linear.passing(Perbill::from_percent(49), Perbill::from_percent(75)));
+
+	let stepped = Curve::SteppedDecreasing {
+		begin: Perbill::from_percent(100),
+		end: Perbill::from_percent(50),
+		step: Perbill::from_percent(10),
+		period: Perbill::from_percent(20),
+	};
+	assert_eq!(stepped.threshold(Perbill::zero()), Perbill::from_percent(100));
+	assert_eq!(stepped.threshold(Perbill::from_percent(19)), Perbill::from_percent(100));
+	assert_eq!(stepped.threshold(Perbill::from_percent(20)), Perbill::from_percent(90));
+	assert_eq!(stepped.threshold(Perbill::one()), Perbill::from_percent(50));
+	assert_eq!(stepped.delay(Perbill::from_percent(85)), Perbill::from_percent(40));
+	assert_eq!(stepped.delay(Perbill::from_percent(30)), Perbill::one());
+}
+
+#[test]
+fn submit_without_track_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Referenda::submit(
+				Origin::signed(1),
+				Box::new(RawOrigin::Signed(1).into()),
+				H256::zero(),
+				DispatchTime::After(0),
+			),
+			Error::<Test>::NoTrack
+		);
+	});
+}
+
+#[test]
+fn happy_path_works() {
+	new_test_ext().execute_with(|| {
+		let h = set_balance_proposal_hash_and_note(2);
+		let r = submit(1, RawOrigin::Root, h);
+		assert_eq!(Balances::reserved_balance(1), 2);
+		assert!(Preimage::preimage_requested(&h));
+
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r));
+		assert_eq!(Balances::reserved_balance(2), 10);
+		assert_noop!(Referenda::place_decision_deposit(Origin::signed(3), r), Error::<Test>::HasDeposit);
+		assert_ok!(Referenda::vote(Origin::signed(4), r, aye(Conviction::None, 40)));
+		assert_ok!(Referenda::vote(Origin::signed(5), r, aye(Conviction::None, 50)));
+		assert_ok!(Referenda::vote(Origin::signed(6), r, aye(Conviction::None, 60)));
+
+		// Preparing.
+		run_to(4);
+		assert_eq!(deciding_status(r), None);
+
+		// Deciding and passing right away.
+		run_to(5);
+		assert_eq!(deciding_status(r), Some(DecidingStatus { since: 5, confirming: Some(7) }));
+		assert_eq!(Referenda::deciding_count(ROOT_TRACK), 1);
+
+		// Approved once confirmed.
+		run_to(7);
+		assert_eq!(Referenda::referendum_info(r), Some(ReferendumInfo::Approved(7)));
+		assert_eq!(Referenda::deciding_count(ROOT_TRACK), 0);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(referenda_events(), vec![
+			RawEvent::Submitted(r, ROOT_TRACK, h),
+			RawEvent::DecisionDepositPlaced(r, 2, 10),
+			RawEvent::DecisionStarted(r, ROOT_TRACK, h, Tally { ayes: 15, nays: 0, support: 150 }),
+			RawEvent::ConfirmStarted(r),
+			RawEvent::Approved(r),
+		]);
+
+		// Dispatched after the minimum enactment period of the track.
+		run_to(10);
+		assert_eq!(Balances::free_balance(42), 0);
+		run_to(11);
+		assert_eq!(Balances::free_balance(42), 2);
+		assert!(!Preimage::have_preimage(&h));
+	});
+}
+
+#[test]
+fn approved_referendum_waits_for_missing_preimage() {
+	new_test_ext().execute_with(|| {
+		let p = set_balance_proposal(2);
+		let h = BlakeTwo256::hash(&p[..]);
+		let r = submit(1, RawOrigin::Root, h);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r));
+		assert_ok!(Referenda::vote(Origin::signed(4), r, aye(Conviction::None, 40)));
+		assert_ok!(Referenda::vote(Origin::signed(5), r, aye(Conviction::None, 50)));
+		assert_ok!(Referenda::vote(Origin::signed(6), r, aye(Conviction::None, 60)));
+
+		// Confirmed, but the preimage is missing: the referendum stays ongoing.
+		run_to(8);
+		assert_eq!(deciding_status(r), Some(DecidingStatus { since: 5, confirming: Some(7) }));
+		assert_eq!(Balances::reserved_balance(2), 10);
+		let unavailable = RawEvent::PreimageUnavailable(r, h);
+		assert_eq!(referenda_events().into_iter().filter(|e| *e == unavailable).count(), 1);
+
+		// Approved once the preimage is noted.
+		assert_ok!(Preimage::note_preimage(Origin::signed(6), p));
+		run_to(9);
+		assert_eq!(Referenda::referendum_info(r), Some(ReferendumInfo::Approved(9)));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		run_to(13);
+		assert_eq!(Balances::free_balance(42), 2);
+	});
+}
+
+#[test]
+fn approved_referendum_times_out_without_preimage() {
+	new_test_ext().execute_with(|| {
+		let h = BlakeTwo256::hash(&set_balance_proposal(2)[..]);
+		let r = submit(1, RawOrigin::Root, h);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r));
+		assert_ok!(Referenda::vote(Origin::signed(4), r, aye(Conviction::None, 40)));
+		assert_ok!(Referenda::vote(Origin::signed(5), r, aye(Conviction::None, 50)));
+		assert_ok!(Referenda::vote(Origin::signed(6), r, aye(Conviction::None, 60)));
+
+		// Confirmed at block 7, then waits for the preimage for `UndecidingTimeout`.
+		run_to(26);
+		assert_eq!(deciding_status(r), Some(DecidingStatus { since: 5, confirming: Some(7) }));
+		assert_eq!(Referenda::deciding_count(ROOT_TRACK), 1);
+		run_to(27);
+		assert_eq!(Referenda::referendum_info(r), Some(ReferendumInfo::TimedOut(27)));
+		assert_eq!(Referenda::deciding_count(ROOT_TRACK), 0);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Alarms::<Test>::iter().count(), 0);
+		assert_eq!(referenda_events().last(), Some(&RawEvent::TimedOut(r)));
+	});
+}
+
+#[test]
+fn superseded_alarms_are_removed() {
+	new_test_ext().execute_with(|| {
+		let h = set_balance_proposal_hash_and_note(2);
+		let r = submit(1, RawOrigin::Root, h);
+		assert_eq!(Alarms::<Test>::get(21), vec![r]);
+
+		// Placing the decision deposit moves the alarm to the end of the prepare period.
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r));
+		assert!(!Alarms::<Test>::contains_key(21));
+		assert_eq!(Alarms::<Test>::get(5), vec![r]);
+
+		// Concluding the referendum removes its alarm.
+		assert_ok!(Referenda::cancel(Origin::signed(4), r));
+		assert_eq!(Alarms::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn referendum_without_approval_is_rejected() {
+	new_test_ext().execute_with(|| {
+		let h = set_balance_proposal_hash_and_note(2);
+		let r = submit(1, RawOrigin::Root, h);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r));
+		assert_ok!(Referenda::vote(Origin::signed(1), r, aye(Conviction::None, 10)));
+		assert_ok!(Referenda::vote(Origin::signed(3), r, nay(Conviction::Locked1x, 30)));
+
+		run_to(5);
+		assert_eq!(deciding_status(r), Some(DecidingStatus { since: 5, confirming: None }));
+
+		run_to(9);
+		let tally = Tally { ayes: 1, nays: 30, support: 10 };
+		assert_eq!(Referenda::referendum_info(r), Some(ReferendumInfo::Rejected(9)));
+		assert_eq!(referenda_events().last(), Some(&RawEvent::Rejected(r, tally)));
+		assert_eq!(Balances::reserved_balance(2), 0);
+
+		run_to(20);
+		assert_eq!(Balances::free_balance(42), 0);
+	});
+}
+
+#[test]
+fn support_curve_decreasing_over_time_approves() {
+	new_test_ext().execute_with(|| {
+		let h = set_balance_proposal_hash_and_note(2);
+		let r = submit(1, RawOrigin::Root, h);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r));
+		// 30 / 210 is about 14% support, which is passing after 3 / 4 of the decision period.
+		assert_ok!(Referenda::vote(Origin::signed(3), r, aye(Conviction::None, 30)));
+
+		run_to(5);
+		assert_eq!(deciding_status(r), Some(DecidingStatus { since: 5, confirming: None }));
+		run_to(7);
+		assert_eq!(deciding_status(r), Some(DecidingStatus { since: 5, confirming: None }));
+		run_to(8);
+		assert_eq!(deciding_status(r), Some(DecidingStatus { since: 5, confirming: Some(10) }));
+		run_to(10);
+		assert_eq!(Referenda::referendum_info(r), Some(ReferendumInfo::Approved(10)));
+	});
+}
+
+#[test]
+fn confirmation_is_aborted_when_votes_are_removed() {
+	new_test_ext().execute_with(|| {
+		let h = set_balance_proposal_hash_and_note(2);
+		let r = submit(1, RawOrigin::Root, h);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r));
+		assert_ok!(Referenda::vote(Origin::signed(6), r, aye(Conviction::None, 60)));
+		assert_ok!(Referenda::vote(Origin::signed(5), r, aye(Conviction::None, 50)));
+
+		run_to(5);
+		assert_eq!(deciding_status(r), Some(DecidingStatus { since: 5, confirming: Some(7) }));
+
+		run_to(6);
+		assert_ok!(Referenda::remove_vote(Origin::signed(6), r));
+		assert_eq!(deciding_status(r), Some(DecidingStatus { since: 5, confirming: None }));
+		assert_eq!(referenda_events().last(), Some(&RawEvent::ConfirmAborted(r)));
+		assert_ok!(Referenda::remove_vote(Origin::signed(5), r));
+
+		run_to(9);
+		assert_eq!(Referenda::referendum_info(r), Some(ReferendumInfo::Rejected(9)));
+	});
+}
+
+#[test]
+fn referenda_are_queued_for_deciding_slots() {
+	new_test_ext().execute_with(|| {
+		let first = submit(1, RawOrigin::Root, set_balance_proposal_hash_and_note(1));
+		let second = submit(1, RawOrigin::Root, set_balance_proposal_hash_and_note(2));
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(3), first));
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(3), second));
+
+		run_to(5);
+		assert_eq!(deciding_status(first), Some(DecidingStatus { since: 5, confirming: None }));
+		assert_eq!(deciding_status(second), None);
+		assert_eq!(Referenda::track_queue(ROOT_TRACK), vec![second]);
+		assert_eq!(Referenda::deciding_count(ROOT_TRACK), 1);
+
+		// The first referendum is rejected, which lets the second one decide.
+		run_to(9);
+		assert_eq!(Referenda::referendum_info(first), Some(ReferendumInfo::Rejected(9)));
+		assert_eq!(deciding_status(second), Some(DecidingStatus { since: 9, confirming: None }));
+		assert!(Referenda::track_queue(ROOT_TRACK).is_empty());
+		assert_eq!(Referenda::deciding_count(ROOT_TRACK), 1);
+
+		run_to(13);
+		assert_eq!(Referenda::referendum_info(second), Some(ReferendumInfo::Rejected(13)));
+		assert_eq!(Referenda::deciding_count(ROOT_TRACK), 0);
+	});
+}
+
+#[test]
+fn tracks_are_independent() {
+	new_test_ext().execute_with(|| {
+		let root = submit(1, RawOrigin::Root, set_balance_proposal_hash_and_note(1));
+		let none = submit(1, RawOrigin::None, set_balance_proposal_hash_and_note(2));
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(3), root));
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(3), none));
+		assert_eq!(Balances::reserved_balance(3), 11);
+
+		// The `none` track has a shorter prepare period.
+		run_to(3);
+		assert_eq!(deciding_status(root), None);
+		assert_eq!(deciding_status(none), Some(DecidingStatus { since: 3, confirming: None }));
+		assert_eq!(Referenda::deciding_count(NONE_TRACK), 1);
+		assert_eq!(Referenda::deciding_count(ROOT_TRACK), 0);
+
+		run_to(5);
+		assert_eq!(Referenda::referendum_info(none), Some(ReferendumInfo::Rejected(5)));
+		assert_eq!(deciding_status(root), Some(DecidingStatus { since: 5, confirming: None }));
+	});
+}
+
+#[test]
+fn referendum_without_decision_deposit_times_out() {
+	new_test_ext().execute_with(|| {
+		let h = set_balance_proposal_hash_and_note(2);
+		let r = submit(1, RawOrigin::Root, h);
+
+		run_to(20);
+		assert!(deciding_status(r).is_none());
+		assert!(matches!(Referenda::referendum_info(r), Some(ReferendumInfo::Ongoing(_))));
+
+		run_to(21);
+		assert_eq!(Referenda::referendum_info(r), Some(ReferendumInfo::TimedOut(21)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(!Preimage::preimage_requested(&h));
+	});
+}
+
+#[test]
+fn cancel_works() {
+	new_test_ext().execute_with(|| {
+		let first = submit(1, RawOrigin::Root, set_balance_proposal_hash_and_note(1));
+		let second = submit(1, RawOrigin::Root, set_balance_proposal_hash_and_note(2));
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(3), first));
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(3), second));
+		run_to(5);
+		assert_eq!(Referenda::track_queue(ROOT_TRACK), vec![second]);
+
+		assert_noop!(Referenda::cancel(Origin::signed(1), first), BadOrigin);
+		assert_ok!(Referenda::cancel(Origin::signed(4), first));
+		assert_eq!(Referenda::referendum_info(first), Some(ReferendumInfo::Cancelled(5)));
+		assert_eq!(Balances::reserved_balance(1), 2);
+		assert_eq!(Balances::reserved_balance(3), 10);
+		// The freed deciding slot goes to the queued referendum.
+		assert_eq!(deciding_status(second), Some(DecidingStatus { since: 5, confirming: None }));
+
+		assert_ok!(Referenda::cancel(Origin::signed(4), second));
+		assert_eq!(Referenda::deciding_count(ROOT_TRACK), 0);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(3), 0);
+		assert_noop!(Referenda::cancel(Origin::signed(4), second), Error::<Test>::NotOngoing);
+	});
+}
+
+#[test]
+fn kill_slashes_deposits() {
+	new_test_ext().execute_with(|| {
+		let r = submit(1, RawOrigin::Root, set_balance_proposal_hash_and_note(2));
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(3), r));
+
+		assert_noop!(Referenda::kill(Origin::signed(4), r), BadOrigin);
+		assert_ok!(Referenda::kill(Origin::root(), r));
+		assert_eq!(Referenda::referendum_info(r), Some(ReferendumInfo::Killed(1)));
+		assert_eq!(Balances::total_balance(&1), 8);
+		assert_eq!(Balances::total_balance(&3), 20);
+		assert_eq!(referenda_events().last(), Some(&RawEvent::Killed(r)));
+	});
+}
+
+#[test]
+fn voting_limits_work() {
+	new_test_ext().execute_with(|| {
+		let h = set_balance_proposal_hash_and_note(2);
+		for _ in 0..4 {
+			submit(6, RawOrigin::None, h);
+		}
+		assert_noop!(
+			Referenda::vote(Origin::signed(1), 0, aye(Conviction::None, 11)),
+			Error::<Test>::InsufficientFunds
+		);
+		for r in 0..3 {
+			assert_ok!(Referenda::vote(Origin::signed(1), r, aye(Conviction::None, 10)));
+		}
+		assert_noop!(
+			Referenda::vote(Origin::signed(1), 3, aye(Conviction::None, 10)),
+			Error::<Test>::MaxVotesReached
+		);
+		assert_noop!(
+			Referenda::vote(Origin::signed(1), 4, aye(Conviction::None, 10)),
+			Error::<Test>::NotOngoing
+		);
+		assert_noop!(Referenda::remove_vote(Origin::signed(2), 0), Error::<Test>::NotVoter);
+		// Changing a vote is fine.
+		assert_ok!(Referenda::vote(Origin::signed(1), 0, aye(Conviction::Locked1x, 5)));
+	});
+}
+
+#[test]
+fn winning_conviction_vote_stays_locked() {
+	new_test_ext().execute_with(|| {
+		let h = set_balance_proposal_hash_and_note(2);
+		let r = submit(1, RawOrigin::Root, h);
+		assert_ok!(Referenda::place_decision_deposit(Origin::signed(2), r));
+		assert_ok!(Referenda::vote(Origin::signed(6), r, aye(Conviction::Locked1x, 60)));
+		assert_ok!(Referenda::vote(Origin::signed(5), r, aye(Conviction::None, 50)));
+		assert_eq!(Balances::locks(6)[0].amount, 60);
+
+		run_to(7);
+		assert_eq!(Referenda::referendum_info(r), Some(ReferendumInfo::Approved(7)));
+
+		// Locked for one `VoteLockingPeriod` after the approval.
+		assert_ok!(Referenda::remove_vote(Origin::signed(6), r));
+		assert_ok!(Referenda::unlock(Origin::signed(6), 6));
+		assert_eq!(Balances::locks(6)[0].amount, 60);
+		run_to(9);
+		assert_ok!(Referenda::unlock(Origin::signed(6), 6));
+		assert_eq!(Balances::locks(6)[0].amount, 60);
+		run_to(10);
+		assert_ok!(Referenda::unlock(Origin::signed(6), 6));
+		assert!(Balances::locks(6).is_empty());
+
+		// Without conviction there is no lock once the vote is removed.
+		assert_ok!(Referenda::remove_vote(Origin::signed(5), r));
+		assert_ok!(Referenda::unlock(Origin::signed(5), 5));
+		assert!(Balances::locks(5).is_empty());
+	});
+}
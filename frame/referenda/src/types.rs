@@ -0,0 +1,302 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Miscellaneous additional datatypes.
+
+use codec::{Encode, Decode};
+use sp_std::prelude::*;
+use sp_runtime::{RuntimeDebug, Perbill};
+use sp_runtime::traits::{AtLeast32BitUnsigned, Saturating, Zero};
+use frame_support::traits::schedule::DispatchTime;
+use pallet_democracy::{AccountVote, Conviction, PriorLock};
+
+/// A referendum index.
+pub type ReferendumIndex = u32;
+
+/// The identifier of a track.
+pub type TrackId = u16;
+
+/// A deposit and the account which placed it.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Deposit<AccountId, Balance> {
+	/// The account which placed the deposit.
+	pub who: AccountId,
+	/// The amount placed on deposit.
+	pub amount: Balance,
+}
+
+/// Parameters of the referenda on a single track.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct TrackInfo<Balance, BlockNumber> {
+	/// Name of this track.
+	pub name: &'static str,
+	/// The maximum number of referenda which may be deciding on this track at once.
+	pub max_deciding: u32,
+	/// Amount that must be placed on deposit before a referendum may start deciding.
+	pub decision_deposit: Balance,
+	/// Minimum time a referendum must spend after its submission before it may start deciding.
+	pub prepare_period: BlockNumber,
+	/// Maximum time a referendum may spend deciding before it is rejected.
+	pub decision_period: BlockNumber,
+	/// Time for which a referendum must continuously pass before it is approved.
+	pub confirm_period: BlockNumber,
+	/// Minimum time between the approval of a referendum and the dispatch of its proposal.
+	pub min_enactment_period: BlockNumber,
+	/// Minimum share of conviction-weighted aye votes in all votes, as a function of the share of
+	/// the decision period which has elapsed.
+	pub min_approval: Curve,
+	/// Minimum share of the total issuance which must have voted aye, without conviction, as a
+	/// function of the share of the decision period which has elapsed.
+	pub min_support: Curve,
+}
+
+/// Information on the tracks of a referenda pallet.
+pub trait TracksInfo<Balance, BlockNumber> {
+	/// The origin type from which a track is implied.
+	type Origin;
+
+	/// All tracks with their identifiers.
+	fn tracks() -> &'static [(TrackId, TrackInfo<Balance, BlockNumber>)];
+
+	/// The track on which referenda for proposals dispatched from `origin` are decided.
+	fn track_for(origin: &Self::Origin) -> Result<TrackId, ()>;
+
+	/// The parameters of the track `id`, if it exists.
+	fn info(id: TrackId) -> Option<&'static TrackInfo<Balance, BlockNumber>> {
+		Self::tracks().iter().find(|(i, _)| *i == id).map(|(_, info)| info)
+	}
+}
+
+/// A threshold which decreases over the decision period of a referendum.
+///
+/// The threshold is evaluated at `x`, the share of the decision period which has elapsed.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Curve {
+	/// Decreasing linearly from `begin` when deciding starts to `end` at the end of the decision
+	/// period.
+	LinearDecreasing { begin: Perbill, end: Perbill },
+	/// Starting at `begin` and decreasing by `step` after each `period` of the decision period,
+	/// but never below `end`.
+	SteppedDecreasing { begin: Perbill, end: Perbill, step: Perbill, period: Perbill },
+}
+
+impl Curve {
+	/// The threshold once a share `x` of the decision period has elapsed.
+	pub fn threshold(&self, x: Perbill) -> Perbill {
+		let x = x.deconstruct() as u64;
+		let one = Perbill::one().deconstruct() as u64;
+		match *self {
+			Curve::LinearDecreasing { begin, end } => {
+				let (begin, end) = (begin.deconstruct() as u64, end.deconstruct() as u64);
+				let drop = begin.saturating_sub(end) * x / one;
+				Perbill::from_parts(begin.saturating_sub(drop) as u32)
+			}
+			Curve::SteppedDecreasing { begin, end, step, period } => {
+				let steps = x / (period.deconstruct() as u64).max(1);
+				let drop = steps.saturating_mul(step.deconstruct() as u64);
+				let threshold = (begin.deconstruct() as u64).saturating_sub(drop);
+				Perbill::from_parts(threshold.max(end.deconstruct() as u64) as u32)
+			}
+		}
+	}
+
+	/// The smallest share of the decision period after which the threshold is at most `y`.
+	///
+	/// Returns `Perbill::one()` if the threshold stays above `y` for the whole decision period.
+	pub fn delay(&self, y: Perbill) -> Perbill {
+		let y = y.deconstruct() as u64;
+		let one = Perbill::one().deconstruct() as u64;
+		let (begin, end) = match *self {
+			Curve::LinearDecreasing { begin, end } | Curve::SteppedDecreasing { begin, end, .. } =>
+				(begin.deconstruct() as u64, end.deconstruct() as u64),
+		};
+		if y >= begin {
+			return Perbill::zero();
+		}
+		if y < end {
+			return Perbill::one();
+		}
+		let x = match *self {
+			// Here `end <= y < begin`, so the division is well defined.
+			Curve::LinearDecreasing { .. } => ((begin - y) * one + (begin - end) - 1) / (begin - end),
+			Curve::SteppedDecreasing { step, period, .. } => {
+				let step = step.deconstruct() as u64;
+				if step == 0 {
+					return Perbill::one();
+				}
+				let steps = (begin - y + step - 1) / step;
+				steps.saturating_mul(period.deconstruct() as u64)
+			}
+		};
+		Perbill::from_parts(x.min(one) as u32)
+	}
+
+	/// Whether `y` meets the threshold once a share `x` of the decision period has elapsed.
+	pub fn passing(&self, x: Perbill, y: Perbill) -> bool {
+		y >= self.threshold(x)
+	}
+}
+
+/// The votes on a referendum.
+#[derive(Encode, Decode, Default, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct Tally<Balance> {
+	/// The number of aye votes, expressed in terms of post-conviction lock-vote.
+	pub ayes: Balance,
+	/// The number of nay votes, expressed in terms of post-conviction lock-vote.
+	pub nays: Balance,
+	/// The amount of funds which voted aye. Pre-conviction.
+	pub support: Balance,
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> Tally<Balance> {
+	/// Add an account's vote into the tally.
+	pub fn add(&mut self, vote: AccountVote<Balance>) -> Option<()> {
+		match vote {
+			AccountVote::Standard { vote, balance } => {
+				let votes = vote.conviction.votes(balance);
+				match vote.aye {
+					true => {
+						self.support = self.support.checked_add(&votes.capital)?;
+						self.ayes = self.ayes.checked_add(&votes.votes)?;
+					}
+					false => self.nays = self.nays.checked_add(&votes.votes)?,
+				}
+			}
+			AccountVote::Split { aye, nay } => {
+				let aye = Conviction::None.votes(aye);
+				let nay = Conviction::None.votes(nay);
+				self.support = self.support.checked_add(&aye.capital)?;
+				self.ayes = self.ayes.checked_add(&aye.votes)?;
+				self.nays = self.nays.checked_add(&nay.votes)?;
+			}
+		}
+		Some(())
+	}
+
+	/// Remove an account's vote from the tally.
+	pub fn remove(&mut self, vote: AccountVote<Balance>) -> Option<()> {
+		match vote {
+			AccountVote::Standard { vote, balance } => {
+				let votes = vote.conviction.votes(balance);
+				match vote.aye {
+					true => {
+						self.support = self.support.checked_sub(&votes.capital)?;
+						self.ayes = self.ayes.checked_sub(&votes.votes)?;
+					}
+					false => self.nays = self.nays.checked_sub(&votes.votes)?,
+				}
+			}
+			AccountVote::Split { aye, nay } => {
+				let aye = Conviction::None.votes(aye);
+				let nay = Conviction::None.votes(nay);
+				self.support = self.support.checked_sub(&aye.capital)?;
+				self.ayes = self.ayes.checked_sub(&aye.votes)?;
+				self.nays = self.nays.checked_sub(&nay.votes)?;
+			}
+		}
+		Some(())
+	}
+
+	/// The share of aye votes in all votes.
+	pub fn approval(&self) -> Perbill {
+		let total = self.ayes.saturating_add(self.nays);
+		if total.is_zero() {
+			return Perbill::zero();
+		}
+		Perbill::from_rational_approximation(self.ayes, total)
+	}
+
+	/// The share of `total_issuance` which voted aye.
+	pub fn support(&self, total_issuance: Balance) -> Perbill {
+		if total_issuance.is_zero() {
+			return Perbill::zero();
+		}
+		Perbill::from_rational_approximation(self.support.min(total_issuance), total_issuance)
+	}
+}
+
+/// The state of a referendum which is deciding.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct DecidingStatus<BlockNumber> {
+	/// When the referendum started deciding.
+	pub since: BlockNumber,
+	/// When the referendum will be approved if it keeps passing until then, or `None` if it is
+	/// not currently passing.
+	pub confirming: Option<BlockNumber>,
+}
+
+/// Info regarding an ongoing referendum.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ReferendumStatus<AccountId, Balance, BlockNumber, Hash, Origin> {
+	/// The track on which the referendum is decided.
+	pub track: TrackId,
+	/// The origin with which the proposal is dispatched if approved.
+	pub origin: Origin,
+	/// The hash of the proposal.
+	pub proposal_hash: Hash,
+	/// When the proposal should be dispatched if approved.
+	pub enactment: DispatchTime<BlockNumber>,
+	/// When the referendum was submitted.
+	pub submitted: BlockNumber,
+	/// The deposit placed on submission.
+	pub submission_deposit: Deposit<AccountId, Balance>,
+	/// The deposit placed to allow the referendum to decide, if any.
+	pub decision_deposit: Option<Deposit<AccountId, Balance>>,
+	/// The decision state, if the referendum is deciding.
+	pub deciding: Option<DecidingStatus<BlockNumber>>,
+	/// The current tally of votes.
+	pub tally: Tally<Balance>,
+	/// Whether the referendum is queued for a free deciding slot of its track.
+	pub in_queue: bool,
+	/// The next block at which the referendum needs to be serviced, if any.
+	pub alarm: Option<BlockNumber>,
+}
+
+/// Info regarding a referendum, present or past.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ReferendumInfo<AccountId, Balance, BlockNumber, Hash, Origin> {
+	/// Referendum is happening.
+	Ongoing(ReferendumStatus<AccountId, Balance, BlockNumber, Hash, Origin>),
+	/// Referendum was approved at the given block.
+	Approved(BlockNumber),
+	/// Referendum was rejected at the given block.
+	Rejected(BlockNumber),
+	/// Referendum was cancelled at the given block.
+	Cancelled(BlockNumber),
+	/// Referendum timed out waiting for its decision deposit or its preimage at the given block.
+	TimedOut(BlockNumber),
+	/// Referendum was killed at the given block.
+	Killed(BlockNumber),
+}
+
+/// The votes of an account.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Voting<Balance, BlockNumber> {
+	/// The current votes of the account, sorted by referendum index.
+	pub votes: Vec<(ReferendumIndex, AccountVote<Balance>)>,
+	/// Any pre-existing locks from past voting activity.
+	pub prior: PriorLock<BlockNumber, Balance>,
+}
+
+impl<Balance: Saturating + Ord + Zero + Copy, BlockNumber: Ord + Copy + Zero> Voting<Balance, BlockNumber> {
+	/// The amount of funds which needs to be locked for the votes of the account.
+	pub fn locked_balance(&self) -> Balance {
+		self.votes.iter()
+			.map(|(_, vote)| vote.balance())
+			.fold(self.prior.locked(), |a, i| a.max(i))
+	}
+}
@@ -47,6 +47,10 @@
 //! * `schedule_named` - augments the `schedule` interface with an additional
 //!   `Vec<u8>` parameter that can be used for identification.
 //! * `cancel_named` - the named complement to the cancel function.
+//!
+//! Calls may be scheduled either inline or by the hash of their encoding. In the latter case
+//! the preimage is requested from `Config::PreimageProvider` when the task is scheduled and
+//! looked up lazily when the task is due.
 
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -60,7 +64,10 @@ use sp_runtime::{RuntimeDebug, traits::{Zero, One, BadOrigin, Saturating}};
 use frame_support::{
 	decl_module, decl_storage, decl_event, decl_error, IterableStorageMap,
 	dispatch::{Dispatchable, DispatchError, DispatchResult, Parameter},
-	traits::{Get, schedule::{self, DispatchTime}, OriginTrait, EnsureOrigin, IsType},
+	traits::{
+		Get, schedule::{self, DispatchTime, MaybeHashed, LookupError}, OriginTrait, EnsureOrigin,
+		IsType, PreimageProvider,
+	},
 	weights::{GetDispatchInfo, Weight},
 };
 use frame_system::{self as system, ensure_signed};
@@ -98,6 +105,14 @@ pub trait Config: system::Config {
 
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
+
+	/// The preimage provider with which we look up call hashes to get the call.
+	type PreimageProvider: PreimageProvider<Self::Hash>;
+
+	/// If `Some` then the number of blocks to postpone execution for when the item is delayed
+	/// because its preimage is not available. The item is dropped instead if the agenda it would
+	/// be postponed to already holds `MaxScheduledPerBlock` items.
+	type NoPreimagePostponement: Get<Option<Self::BlockNumber>>;
 }
 
 /// Just a simple index for naming period tasks.
@@ -105,6 +120,9 @@ pub type PeriodicIndex = u32;
 /// The location of a scheduled task that can be used to remove it.
 pub type TaskAddress<BlockNumber> = (BlockNumber, u32);
 
+/// A call that is either stored inline or referred to by the hash of its encoding.
+pub type CallOrHashOf<T> = MaybeHashed<<T as Config>::Call, <T as system::Config>::Hash>;
+
 #[cfg_attr(any(feature = "std", test), derive(PartialEq, Eq))]
 #[derive(Clone, RuntimeDebug, Encode, Decode)]
 struct ScheduledV1<Call, BlockNumber> {
@@ -141,6 +159,7 @@ pub type Scheduled<Call, BlockNumber, PalletsOrigin, AccountId> = ScheduledV2<Ca
 enum Releases {
 	V1,
 	V2,
+	V3,
 }
 
 impl Default for Releases {
@@ -153,7 +172,7 @@ decl_storage! {
 	trait Store for Module<T: Config> as Scheduler {
 		/// Items to be executed, indexed by the block number that they should be executed on.
 		pub Agenda: map hasher(twox_64_concat) T::BlockNumber
-			=> Vec<Option<Scheduled<<T as Config>::Call, T::BlockNumber, T::PalletsOrigin, T::AccountId>>>;
+			=> Vec<Option<Scheduled<CallOrHashOf<T>, T::BlockNumber, T::PalletsOrigin, T::AccountId>>>;
 
 		/// Lookup from identity to the block number and index of the task.
 		Lookup: map hasher(twox_64_concat) Vec<u8> => Option<TaskAddress<T::BlockNumber>>;
@@ -161,7 +180,7 @@ decl_storage! {
 		/// Storage version of the pallet.
 		///
 		/// New networks start with last version.
-		StorageVersion build(|_| Releases::V2): Releases;
+		StorageVersion build(|_| Releases::V3): Releases;
 	}
 }
 
@@ -173,6 +192,12 @@ decl_event!(
 		Canceled(BlockNumber, u32),
 		/// Dispatched some task. \[task, id, result\]
 		Dispatched(TaskAddress<BlockNumber>, Option<Vec<u8>>, DispatchResult),
+		/// The call for the provided hash was not found so the task has been aborted or
+		/// postponed. \[task, id, error\]
+		CallLookupFailed(TaskAddress<BlockNumber>, Option<Vec<u8>>, LookupError),
+		/// A task whose call was not found could not be postponed because the agenda of the
+		/// target block is full, so it has been aborted. \[task, id\]
+		PostponementFailed(TaskAddress<BlockNumber>, Option<Vec<u8>>),
 	}
 );
 
@@ -210,7 +235,7 @@ decl_module! {
 			when: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -250,7 +275,7 @@ decl_module! {
 			when: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -286,7 +311,7 @@ decl_module! {
 			after: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -306,7 +331,7 @@ decl_module! {
 			after: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -315,6 +340,14 @@ decl_module! {
 			)?;
 		}
 
+		fn on_runtime_upgrade() -> Weight {
+			if Self::migrate_v1_to_v3() || Self::migrate_v2_to_v3() {
+				T::BlockWeights::get().max_block
+			} else {
+				T::DbWeight::get().reads(1)
+			}
+		}
+
 		/// Execute the scheduled calls
 		///
 		/// # <weight>
@@ -322,13 +355,16 @@ decl_module! {
 		/// - N = Named scheduled calls
 		/// - P = Periodic Calls
 		/// - Base Weight: 9.243 + 23.45 * S µs
+		/// - H = Calls scheduled by hash
+		/// - D = Calls scheduled by hash whose preimage is missing
 		/// - DB Weight:
-		///     - Read: Agenda + Lookup * N + Agenda(Future) * P
-		///     - Write: Agenda + Lookup * N  + Agenda(future) * P
+		///     - Read: Agenda + Lookup * N + Agenda(Future) * P + Preimage * H + Agenda(Future) * D
+		///     - Write: Agenda + Lookup * N  + Agenda(future) * P + Preimage * H
+		///       + (Agenda(Future) + Lookup) * D
 		/// # </weight>
 		fn on_initialize(now: T::BlockNumber) -> Weight {
 			let limit = T::MaximumWeight::get();
-			let mut queued = Agenda::<T>::take(now).into_iter()
+			let queued = Agenda::<T>::take(now).into_iter()
 				.enumerate()
 				.filter_map(|(index, s)| s.map(|inner| (index as u32, inner)))
 				.collect::<Vec<_>>();
@@ -338,14 +374,25 @@ decl_module! {
 					expected from the runtime configuration. An update might be needed."
 				);
 			}
-			queued.sort_by_key(|(_, s)| s.priority);
-			let base_weight: Weight = T::DbWeight::get().reads_writes(1, 2); // Agenda + Agenda(next)
-			let mut total_weight: Weight = 0;
+			let mut lookup_weight: Weight = 0;
+			let mut queued = queued.into_iter()
+				.filter_map(|(index, s)| {
+					if s.call.as_hash().is_some() {
+						// Preimage lookup and unrequest
+						lookup_weight = lookup_weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+					}
+					Self::resolve_call(now, index, s, &mut lookup_weight)
+				})
+				.collect::<Vec<_>>();
+			queued.sort_by_key(|(_, _, s)| s.priority);
+			// Agenda + Agenda(next)
+			let base_weight: Weight = T::DbWeight::get().reads_writes(1, 2).saturating_add(lookup_weight);
+			let mut total_weight: Weight = lookup_weight;
 			queued.into_iter()
 				.enumerate()
-				.scan(base_weight, |cumulative_weight, (order, (index, s))| {
+				.scan(base_weight, |cumulative_weight, (order, (index, call, s))| {
 					*cumulative_weight = cumulative_weight
-						.saturating_add(s.call.get_dispatch_info().weight);
+						.saturating_add(call.get_dispatch_info().weight);
 
 					let origin = <<T as Config>::Origin as From<T::PalletsOrigin>>::from(
 						s.origin.clone()
@@ -366,15 +413,15 @@ decl_module! {
 						*cumulative_weight = cumulative_weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
 					}
 
-					Some((order, index, *cumulative_weight, s))
+					Some((order, index, *cumulative_weight, call, s))
 				})
-				.filter_map(|(order, index, cumulative_weight, mut s)| {
+				.filter_map(|(order, index, cumulative_weight, call, mut s)| {
 					// We allow a scheduled call if any is true:
 					// - It's priority is `HARD_DEADLINE`
 					// - It does not push the weight past the limit.
 					// - It is the first item in the schedule
 					if s.priority <= schedule::HARD_DEADLINE || cumulative_weight <= limit || order == 0 {
-						let r = s.call.clone().dispatch(s.origin.clone().into());
+						let r = call.dispatch(s.origin.clone().into());
 						let maybe_id = s.maybe_id.clone();
 						if let &Some((period, count)) = &s.maybe_periodic {
 							if count > 1 {
@@ -416,11 +463,11 @@ decl_module! {
 }
 
 impl<T: Config> Module<T> {
-	/// Migrate storage format from V1 to V2.
+	/// Migrate storage format from V1 to V3.
 	/// Return true if migration is performed.
-	pub fn migrate_v1_to_t2() -> bool {
+	pub fn migrate_v1_to_v3() -> bool {
 		if StorageVersion::get() == Releases::V1 {
-			StorageVersion::put(Releases::V2);
+			StorageVersion::put(Releases::V3);
 
 			Agenda::<T>::translate::<
 				Vec<Option<ScheduledV1<<T as Config>::Call, T::BlockNumber>>>, _
@@ -430,7 +477,7 @@ impl<T: Config> Module<T> {
 					.map(|schedule| schedule.map(|schedule| ScheduledV2 {
 						maybe_id: schedule.maybe_id,
 						priority: schedule.priority,
-						call: schedule.call,
+						call: schedule.call.into(),
 						maybe_periodic: schedule.maybe_periodic,
 						origin: system::RawOrigin::Root.into(),
 						_phantom: Default::default(),
@@ -444,10 +491,38 @@ impl<T: Config> Module<T> {
 		}
 	}
 
+	/// Migrate storage format from V2 to V3, wrapping every stored call as an inline value.
+	/// Return true if migration is performed.
+	pub fn migrate_v2_to_v3() -> bool {
+		if StorageVersion::get() == Releases::V2 {
+			StorageVersion::put(Releases::V3);
+
+			Agenda::<T>::translate::<
+				Vec<Option<ScheduledV2<<T as Config>::Call, T::BlockNumber, T::PalletsOrigin, T::AccountId>>>, _
+			>(|_, agenda| Some(
+				agenda
+					.into_iter()
+					.map(|schedule| schedule.map(|schedule| ScheduledV2 {
+						maybe_id: schedule.maybe_id,
+						priority: schedule.priority,
+						call: schedule.call.into(),
+						maybe_periodic: schedule.maybe_periodic,
+						origin: schedule.origin,
+						_phantom: Default::default(),
+					}))
+					.collect::<Vec<_>>()
+			));
+
+			true
+		} else {
+			false
+		}
+	}
+
 	/// Helper to migrate scheduler when the pallet origin type has changed.
 	pub fn migrate_origin<OldOrigin: Into<T::PalletsOrigin> + codec::Decode>() {
 		Agenda::<T>::translate::<
-			Vec<Option<Scheduled<<T as Config>::Call, T::BlockNumber, OldOrigin, T::AccountId>>>, _
+			Vec<Option<Scheduled<CallOrHashOf<T>, T::BlockNumber, OldOrigin, T::AccountId>>>, _
 		>(|_, agenda| Some(
 			agenda
 				.into_iter()
@@ -463,6 +538,64 @@ impl<T: Config> Module<T> {
 		));
 	}
 
+	/// Look up the preimage of a task's call if it was scheduled by hash.
+	///
+	/// Returns the task together with its resolved call. If the preimage is not available the
+	/// task is either postponed by `NoPreimagePostponement` or dropped and `None` is returned.
+	/// A task is also dropped if the agenda it would be postponed to is already full. The weight
+	/// of postponing is added to `weight`.
+	fn resolve_call(
+		now: T::BlockNumber,
+		index: u32,
+		mut s: Scheduled<CallOrHashOf<T>, T::BlockNumber, T::PalletsOrigin, T::AccountId>,
+		weight: &mut Weight,
+	) -> Option<(u32, <T as Config>::Call, Scheduled<CallOrHashOf<T>, T::BlockNumber, T::PalletsOrigin, T::AccountId>)> {
+		match s.call.clone().resolved::<T::PreimageProvider>() {
+			Ok((MaybeHashed::Value(call), maybe_hash)) => {
+				if let Some(hash) = maybe_hash {
+					T::PreimageProvider::unrequest_preimage(&hash);
+				}
+				s.call = MaybeHashed::Value(call.clone());
+				Some((index, call, s))
+			},
+			// `resolved` never returns a hash on success.
+			Ok((MaybeHashed::Hash(_), _)) => None,
+			Err(error) => {
+				Self::deposit_event(RawEvent::CallLookupFailed((now, index), s.maybe_id.clone(), error));
+				let maybe_until = T::NoPreimagePostponement::get().and_then(|delay| {
+					let until = now.saturating_add(delay.max(One::one()));
+					// Agenda(Future) length
+					*weight = weight.saturating_add(T::DbWeight::get().reads(1));
+					let next_index = Agenda::<T>::decode_len(until).unwrap_or(0) as u32;
+					if next_index < T::MaxScheduledPerBlock::get() {
+						Some((until, next_index))
+					} else {
+						Self::deposit_event(
+							RawEvent::PostponementFailed((now, index), s.maybe_id.clone())
+						);
+						None
+					}
+				});
+				if let Some((until, next_index)) = maybe_until {
+					if let Some(ref id) = s.maybe_id {
+						Lookup::<T>::insert(id, (until, next_index));
+					}
+					Agenda::<T>::append(until, Some(s));
+					// Agenda(Future) + Lookup
+					*weight = weight.saturating_add(T::DbWeight::get().writes(2));
+				} else {
+					if let Some(ref id) = s.maybe_id {
+						Lookup::<T>::remove(id);
+					}
+					s.call.ensure_unrequested::<T::PreimageProvider>();
+					// Lookup + Preimage request
+					*weight = weight.saturating_add(T::DbWeight::get().writes(2));
+				}
+				None
+			},
+		}
+	}
+
 	fn resolve_time(when: DispatchTime<T::BlockNumber>) -> Result<T::BlockNumber, DispatchError> {
 		let now = frame_system::Module::<T>::block_number();
 
@@ -485,9 +618,10 @@ impl<T: Config> Module<T> {
 		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 		priority: schedule::Priority,
 		origin: T::PalletsOrigin,
-		call: <T as Config>::Call
+		call: CallOrHashOf<T>,
 	) -> Result<TaskAddress<T::BlockNumber>, DispatchError> {
 		let when = Self::resolve_time(when)?;
+		call.ensure_requested::<T::PreimageProvider>();
 
 		// sanitize maybe_periodic
 		let maybe_periodic = maybe_periodic
@@ -529,6 +663,7 @@ impl<T: Config> Module<T> {
 			},
 		)?;
 		if let Some(s) = scheduled {
+			s.call.ensure_unrequested::<T::PreimageProvider>();
 			if let Some(id) = s.maybe_id {
 				Lookup::<T>::remove(id);
 			}
@@ -569,7 +704,7 @@ impl<T: Config> Module<T> {
 		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 		priority: schedule::Priority,
 		origin: T::PalletsOrigin,
-		call: <T as Config>::Call,
+		call: CallOrHashOf<T>,
 	) -> Result<TaskAddress<T::BlockNumber>, DispatchError> {
 		// ensure id it is unique
 		if Lookup::<T>::contains_key(&id) {
@@ -577,6 +712,7 @@ impl<T: Config> Module<T> {
 		}
 
 		let when = Self::resolve_time(when)?;
+		call.ensure_requested::<T::PreimageProvider>();
 
 		// sanitize maybe_periodic
 		let maybe_periodic = maybe_periodic
@@ -613,7 +749,9 @@ impl<T: Config> Module<T> {
 								return Err(BadOrigin.into());
 							}
 						}
-						*s = None;
+						if let Some(s) = s.take() {
+							s.call.ensure_unrequested::<T::PreimageProvider>();
+						}
 					}
 					Ok(())
 				})?;
@@ -667,7 +805,7 @@ impl<T: Config> schedule::Anon<T::BlockNumber, <T as Config>::Call, T::PalletsOr
 		origin: T::PalletsOrigin,
 		call: <T as Config>::Call
 	) -> Result<Self::Address, DispatchError> {
-		Self::do_schedule(when, maybe_periodic, priority, origin, call)
+		Self::do_schedule(when, maybe_periodic, priority, origin, call.into())
 	}
 
 	fn cancel((when, index): Self::Address) -> Result<(), ()> {
@@ -697,7 +835,7 @@ impl<T: Config> schedule::Named<T::BlockNumber, <T as Config>::Call, T::PalletsO
 		origin: T::PalletsOrigin,
 		call: <T as Config>::Call,
 	) -> Result<Self::Address, ()> {
-		Self::do_schedule_named(id, when, maybe_periodic, priority, origin, call).map_err(|_| ())
+		Self::do_schedule_named(id, when, maybe_periodic, priority, origin, call.into()).map_err(|_| ())
 	}
 
 	fn cancel_named(id: Vec<u8>) -> Result<(), ()> {
@@ -730,7 +868,7 @@ mod tests {
 	use sp_runtime::{
 		Perbill,
 		testing::Header,
-		traits::{BlakeTwo256, IdentityLookup},
+		traits::{BlakeTwo256, IdentityLookup, Hash},
 	};
 	use frame_system::{EnsureOneOf, EnsureRoot, EnsureSignedBy};
 	use substrate_test_utils::assert_eq_uvec;
@@ -833,7 +971,7 @@ mod tests {
 		type BlockHashCount = BlockHashCount;
 		type Version = ();
 		type PalletInfo = ();
-		type AccountData = ();
+		type AccountData = pallet_balances::AccountData<u64>;
 		type OnNewAccount = ();
 		type OnKilledAccount = ();
 		type SystemWeightInfo = ();
@@ -841,9 +979,39 @@ mod tests {
 	impl logger::Config for Test {
 		type Event = ();
 	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+	impl pallet_balances::Config for Test {
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
+		type Balance = u64;
+		type Event = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type WeightInfo = ();
+	}
+	parameter_types! {
+		pub const MaxSize: u32 = 1024;
+		pub const PreimageBaseDeposit: u64 = 2;
+		pub const PreimageByteDeposit: u64 = 1;
+	}
+	impl pallet_preimage::Config for Test {
+		type Event = ();
+		type Currency = Balances;
+		type ManagerOrigin = EnsureRoot<u64>;
+		type MaxSize = MaxSize;
+		type BaseDeposit = PreimageBaseDeposit;
+		type ByteDeposit = PreimageByteDeposit;
+		type WeightInfo = ();
+	}
 	parameter_types! {
 		pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
 		pub const MaxScheduledPerBlock: u32 = 10;
+		pub const NoPreimagePostponement: Option<u64> = Some(2);
 	}
 	ord_parameter_types! {
 		pub const One: u64 = 1;
@@ -858,9 +1026,13 @@ mod tests {
 		type ScheduleOrigin = EnsureOneOf<u64, EnsureRoot<u64>, EnsureSignedBy<One, u64>>;
 		type MaxScheduledPerBlock = MaxScheduledPerBlock;
 		type WeightInfo = ();
+		type PreimageProvider = Preimage;
+		type NoPreimagePostponement = NoPreimagePostponement;
 	}
 	type System = system::Module<Test>;
 	type Logger = logger::Module<Test>;
+	type Balances = pallet_balances::Module<Test>;
+	type Preimage = pallet_preimage::Module<Test>;
 	type Scheduler = Module<Test>;
 
 	pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -885,16 +1057,111 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
-			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call));
+			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call.into()));
+			run_to_block(3);
+			assert!(logger::log().is_empty());
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+		});
+	}
+
+	#[test]
+	fn scheduling_with_preimages_works() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			let hashed = MaybeHashed::Hash(hash);
+			assert_ok!(Preimage::note_preimage(Origin::root(), call.encode()));
+			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), hashed));
+			assert!(Preimage::preimage_requested(&hash));
 			run_to_block(3);
 			assert!(logger::log().is_empty());
 			run_to_block(4);
+			assert!(!Preimage::have_preimage(&hash));
+			assert!(!Preimage::preimage_requested(&hash));
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+		});
+	}
+
+	#[test]
+	fn scheduling_with_preimage_postpones_correctly() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			let hashed = MaybeHashed::Hash(hash);
+
+			assert_ok!(Scheduler::do_schedule_named(
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), hashed
+			));
+			assert!(Preimage::preimage_requested(&hash));
+
+			run_to_block(4);
+			// #4 empty due to no preimage
+			assert!(logger::log().is_empty());
+			assert_eq!(Lookup::<Test>::get(1u32.encode()), Some((6, 0)));
+
+			// Register preimage.
+			assert_ok!(Preimage::note_preimage(Origin::root(), call.encode()));
+
+			run_to_block(5);
+			// #5 empty since postponement is 2 blocks.
+			assert!(logger::log().is_empty());
+
+			run_to_block(6);
+			// #6 is good.
 			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			assert!(!Preimage::have_preimage(&hash));
+			assert!(!Preimage::preimage_requested(&hash));
+			assert_eq!(Lookup::<Test>::get(1u32.encode()), None);
+
 			run_to_block(100);
 			assert_eq!(logger::log(), vec![(root(), 42u32)]);
 		});
 	}
 
+	#[test]
+	fn scheduling_with_preimage_drops_when_postponement_agenda_is_full() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			let hashed = MaybeHashed::Hash(hash);
+
+			assert_ok!(Scheduler::do_schedule_named(
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), hashed
+			));
+			// Fill the agenda of #6, where the task would be postponed to.
+			for i in 0..MaxScheduledPerBlock::get() {
+				let filler = Call::Logger(logger::Call::log(i, 1000));
+				assert_ok!(
+					Scheduler::do_schedule(DispatchTime::At(6), None, 127, root(), filler.into())
+				);
+			}
+
+			run_to_block(4);
+			// #4 empty due to no preimage and the task is not postponed.
+			assert!(logger::log().is_empty());
+			assert_eq!(Lookup::<Test>::get(1u32.encode()), None);
+			assert_eq!(Agenda::<Test>::get(6).len() as u32, MaxScheduledPerBlock::get());
+			assert!(!Preimage::preimage_requested(&hash));
+		});
+	}
+
+	#[test]
+	fn cancelling_hashed_call_drops_preimage_request() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)));
+			assert!(Preimage::preimage_requested(&hash));
+			assert_ok!(Scheduler::do_cancel(None, (4, 0)));
+			assert!(!Preimage::preimage_requested(&hash));
+		});
+	}
+
 	#[test]
 	fn schedule_after_works() {
 		new_test_ext().execute_with(|| {
@@ -902,7 +1169,7 @@ mod tests {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
 			// This will schedule the call 3 blocks after the next block... so block 3 + 3 = 6
-			assert_ok!(Scheduler::do_schedule(DispatchTime::After(3), None, 127, root(), call));
+			assert_ok!(Scheduler::do_schedule(DispatchTime::After(3), None, 127, root(), call.into()));
 			run_to_block(5);
 			assert!(logger::log().is_empty());
 			run_to_block(6);
@@ -918,7 +1185,7 @@ mod tests {
 			run_to_block(2);
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
-			assert_ok!(Scheduler::do_schedule(DispatchTime::After(0), None, 127, root(), call));
+			assert_ok!(Scheduler::do_schedule(DispatchTime::After(0), None, 127, root(), call.into()));
 			// Will trigger on the next block.
 			run_to_block(3);
 			assert_eq!(logger::log(), vec![(root(), 42u32)]);
@@ -932,7 +1199,7 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			// at #4, every 3 blocks, 3 times.
 			assert_ok!(Scheduler::do_schedule(
-				DispatchTime::At(4), Some((3, 3)), 127, root(), Call::Logger(logger::Call::log(42, 1000))
+				DispatchTime::At(4), Some((3, 3)), 127, root(), Call::Logger(logger::Call::log(42, 1000)).into()
 			));
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -956,7 +1223,7 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
-			assert_eq!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call).unwrap(), (4, 0));
+			assert_eq!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call.into()).unwrap(), (4, 0));
 
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -982,7 +1249,7 @@ mod tests {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
 			assert_eq!(Scheduler::do_schedule_named(
-				1u32.encode(), DispatchTime::At(4), None, 127, root(), call
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), call.into()
 			).unwrap(), (4, 0));
 
 			run_to_block(3);
@@ -1009,7 +1276,7 @@ mod tests {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
 			assert_eq!(Scheduler::do_schedule_named(
-				1u32.encode(), DispatchTime::At(4), Some((3, 3)), 127, root(), call
+				1u32.encode(), DispatchTime::At(4), Some((3, 3)), 127, root(), call.into()
 			).unwrap(), (4, 0));
 
 			run_to_block(3);
@@ -1045,10 +1312,10 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			// at #4.
 			Scheduler::do_schedule_named(
-				1u32.encode(), DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(69, 1000))
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(69, 1000)).into()
 			).unwrap();
 			let i = Scheduler::do_schedule(
-				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(42, 1000))
+				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(42, 1000)).into()
 			).unwrap();
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -1069,7 +1336,7 @@ mod tests {
 				Some((3, 3)),
 				127,
 				root(),
-				Call::Logger(logger::Call::log(42, 1000))
+				Call::Logger(logger::Call::log(42, 1000)).into()
 			).unwrap();
 			// same id results in error.
 			assert!(Scheduler::do_schedule_named(
@@ -1078,11 +1345,11 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(69, 1000))
+				Call::Logger(logger::Call::log(69, 1000)).into()
 			).is_err());
 			// different id is ok.
 			Scheduler::do_schedule_named(
-				2u32.encode(), DispatchTime::At(8), None, 127, root(), Call::Logger(logger::Call::log(69, 1000))
+				2u32.encode(), DispatchTime::At(8), None, 127, root(), Call::Logger(logger::Call::log(69, 1000)).into()
 			).unwrap();
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -1103,13 +1370,13 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				127,
-				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			// 69 and 42 do not fit together
 			run_to_block(4);
@@ -1127,14 +1394,14 @@ mod tests {
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			// With base weights, 69 and 42 should not fit together, but do because of hard deadlines
 			run_to_block(4);
@@ -1150,14 +1417,14 @@ mod tests {
 				None,
 				1,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			run_to_block(4);
 			assert_eq!(logger::log(), vec![(root(), 69u32), (root(), 42u32)]);
@@ -1171,19 +1438,19 @@ mod tests {
 				DispatchTime::At(4),
 				None,
 				255,
-				root(), Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3))
+				root(), Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				127,
-				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				126,
-				root(), Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)).into()
 			));
 
 			// 2600 does not fit with 69 or 42, but has higher priority, so will go through
@@ -1207,7 +1474,7 @@ mod tests {
 			assert_ok!(
 				Scheduler::do_schedule_named(
 					1u32.encode(), DispatchTime::At(1), None, 255, root(),
-					Call::Logger(logger::Call::log(3, MaximumSchedulerWeight::get() / 3))
+					Call::Logger(logger::Call::log(3, MaximumSchedulerWeight::get() / 3)).into()
 				)
 			);
 			// Anon Periodic
@@ -1216,7 +1483,7 @@ mod tests {
 				Some((1000, 3)),
 				128,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3)).into()
 			));
 			// Anon
 			assert_ok!(Scheduler::do_schedule(
@@ -1224,12 +1491,12 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			// Named Periodic
 			assert_ok!(Scheduler::do_schedule_named(
 				2u32.encode(), DispatchTime::At(1), Some((1000, 3)), 126, root(),
-				Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)))
+				Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)).into())
 			);
 
 			// Will include the named periodic only
@@ -1261,8 +1528,8 @@ mod tests {
 	#[test]
 	fn root_calls_works() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 			assert_ok!(Scheduler::schedule_named(Origin::root(), 1u32.encode(), 4, None, 127, call));
 			assert_ok!(Scheduler::schedule(Origin::root(), 4, None, 127, call2));
 			run_to_block(3);
@@ -1282,8 +1549,8 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			run_to_block(3);
 
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 
 			assert_err!(
 				Scheduler::schedule_named(Origin::root(), 1u32.encode(), 2, None, 127, call),
@@ -1305,8 +1572,8 @@ mod tests {
 	#[test]
 	fn should_use_orign() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 			assert_ok!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(1).into(), 1u32.encode(), 4, None, 127, call)
 			);
@@ -1326,8 +1593,8 @@ mod tests {
 	#[test]
 	fn should_check_orign() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 			assert_noop!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(2).into(), 1u32.encode(), 4, None, 127, call),
 				BadOrigin
@@ -1339,8 +1606,8 @@ mod tests {
 	#[test]
 	fn should_check_orign_for_cancel() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log_without_filter(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log_without_filter(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log_without_filter(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log_without_filter(42, 1000)).into());
 			assert_ok!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(1).into(), 1u32.encode(), 4, None, 127, call)
 			);
@@ -1362,7 +1629,7 @@ mod tests {
 	}
 
 	#[test]
-	fn migration_to_v2_works() {
+	fn migration_to_v3_works() {
 		new_test_ext().execute_with(|| {
 			for i in 0..3u64 {
 				let k = i.twox_64_concat();
@@ -1391,7 +1658,7 @@ mod tests {
 
 			assert_eq!(StorageVersion::get(), Releases::V1);
 
-			assert!(Scheduler::migrate_v1_to_t2());
+			assert!(Scheduler::migrate_v1_to_v3());
 
 			assert_eq_uvec!(Agenda::<Test>::iter().collect::<Vec<_>>(), vec![
 				(
@@ -1400,7 +1667,7 @@ mod tests {
 					Some(ScheduledV2 {
 						maybe_id: None,
 						priority: 10,
-						call: Call::Logger(logger::Call::log(96, 100)),
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
 						maybe_periodic: None,
 						origin: root(),
 						_phantom: PhantomData::<u64>::default(),
@@ -1409,7 +1676,7 @@ mod tests {
 					Some(ScheduledV2 {
 						maybe_id: Some(b"test".to_vec()),
 						priority: 123,
-						call: Call::Logger(logger::Call::log(69, 1000)),
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
 						maybe_periodic: Some((456u64, 10)),
 						origin: root(),
 						_phantom: PhantomData::<u64>::default(),
@@ -1421,7 +1688,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: None,
 							priority: 11,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1430,7 +1697,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1443,7 +1710,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: None,
 							priority: 12,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1452,7 +1719,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1461,7 +1728,43 @@ mod tests {
 				)
 			]);
 
-			assert_eq!(StorageVersion::get(), Releases::V2);
+			assert_eq!(StorageVersion::get(), Releases::V3);
+		});
+	}
+
+	#[test]
+	fn migration_v2_to_v3_works() {
+		new_test_ext().execute_with(|| {
+			StorageVersion::put(Releases::V2);
+			let k = 0u64.twox_64_concat();
+			let old: Vec<Option<ScheduledV2<Call, u64, OriginCaller, u64>>> = vec![
+				Some(ScheduledV2 {
+					maybe_id: None,
+					priority: 10,
+					call: Call::Logger(logger::Call::log(96, 100)),
+					maybe_periodic: None,
+					origin: root(),
+					_phantom: PhantomData::<u64>::default(),
+				}),
+				None,
+			];
+			frame_support::migration::put_storage_value(b"Scheduler", b"Agenda", &k, old);
+
+			assert!(Scheduler::migrate_v2_to_v3());
+			assert!(!Scheduler::migrate_v2_to_v3());
+
+			assert_eq!(Agenda::<Test>::get(0), vec![
+				Some(ScheduledV2 {
+					maybe_id: None,
+					priority: 10,
+					call: Call::Logger(logger::Call::log(96, 100)).into(),
+					maybe_periodic: None,
+					origin: root(),
+					_phantom: PhantomData::<u64>::default(),
+				}),
+				None,
+			]);
+			assert_eq!(StorageVersion::get(), Releases::V3);
 		});
 	}
 
@@ -1470,11 +1773,11 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			for i in 0..3u64 {
 				let k = i.twox_64_concat();
-				let old: Vec<Option<Scheduled<_, _, u32, u64>>> = vec![
+				let old: Vec<Option<Scheduled<CallOrHashOf<Test>, _, u32, u64>>> = vec![
 					Some(Scheduled {
 						maybe_id: None,
 						priority: i as u8 + 10,
-						call: Call::Logger(logger::Call::log(96, 100)),
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
 						origin: 3u32,
 						maybe_periodic: None,
 						_phantom: Default::default(),
@@ -1484,7 +1787,7 @@ mod tests {
 						maybe_id: Some(b"test".to_vec()),
 						priority: 123,
 						origin: 2u32,
-						call: Call::Logger(logger::Call::log(69, 1000)),
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
 						maybe_periodic: Some((456u64, 10)),
 						_phantom: Default::default(),
 					}),
@@ -1516,7 +1819,7 @@ mod tests {
 					Some(ScheduledV2::<_, _, OriginCaller, u64> {
 						maybe_id: None,
 						priority: 10,
-						call: Call::Logger(logger::Call::log(96, 100)),
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
 						maybe_periodic: None,
 						origin: system::RawOrigin::Root.into(),
 						_phantom: PhantomData::<u64>::default(),
@@ -1525,7 +1828,7 @@ mod tests {
 					Some(ScheduledV2 {
 						maybe_id: Some(b"test".to_vec()),
 						priority: 123,
-						call: Call::Logger(logger::Call::log(69, 1000)),
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
 						maybe_periodic: Some((456u64, 10)),
 						origin: system::RawOrigin::None.into(),
 						_phantom: PhantomData::<u64>::default(),
@@ -1537,7 +1840,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: None,
 							priority: 11,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: system::RawOrigin::Root.into(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1546,7 +1849,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: system::RawOrigin::None.into(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1559,7 +1862,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: None,
 							priority: 12,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: system::RawOrigin::Root.into(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1568,7 +1871,7 @@ mod tests {
 						Some(ScheduledV2 {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: system::RawOrigin::None.into(),
 							_phantom: PhantomData::<u64>::default(),
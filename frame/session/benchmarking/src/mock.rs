@@ -73,6 +73,9 @@ parameter_types! {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type Balance = Balance;
 	type Event = ();
 	type DustRemoval = ();
@@ -93,6 +96,7 @@ impl pallet_timestamp::Config for Test {
 impl pallet_session::historical::Config for Test {
 	type FullIdentification = pallet_staking::Exposure<AccountId, Balance>;
 	type FullIdentificationOf = pallet_staking::ExposureOf<Test>;
+	type PruningDepth = ();
 }
 
 sp_runtime::impl_opaque_keys! {
@@ -141,6 +145,7 @@ pallet_staking_reward_curve::build! {
 parameter_types! {
 	pub const RewardCurve: &'static sp_runtime::curve::PiecewiseLinear<'static> = &I_NPOS;
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const VoterSnapshotWeightLimit: u64 = u64::max_value();
 	pub const UnsignedPriority: u64 = 1 << 20;
 }
 
@@ -175,6 +180,10 @@ impl pallet_staking::Config for Test {
 	type MaxIterations = ();
 	type MinSolutionScoreBump = ();
 	type OffchainSolutionWeightLimit = ();
+	type OnStakerSlash = ();
+	type VoterList = pallet_staking::UseNominatorsMap<Self>;
+	type VoterSnapshotWeightLimit = VoterSnapshotWeightLimit;
+	type CommissionChangeDelay = ();
 	type WeightInfo = ();
 }
 
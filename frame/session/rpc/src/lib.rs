@@ -0,0 +1,240 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RPC interface for the historical session module.
+//!
+//! Generates membership proofs for past sessions from the validator sets that the historical
+//! session module indexes into the off-chain database. The node must therefore run with
+//! off-chain indexing enabled for proofs to be available.
+
+use std::{convert::TryInto, sync::Arc};
+use codec::Encode;
+use sp_blockchain::HeaderBackend;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_api::ProvideRuntimeApi;
+use sp_core::{
+	Bytes, ExecutionContext, OpaquePeerId,
+	offchain::{
+		self, Capabilities, Capability, HttpError, HttpRequestId, HttpRequestStatus,
+		OffchainStorage, OpaqueNetworkState, StorageKind, Timestamp, STORAGE_PREFIX,
+	},
+};
+use sp_staking::SessionIndex;
+pub use sp_session::SessionMembershipApi as SessionMembershipRuntimeApi;
+pub use self::gen_client::Client as SessionClient;
+
+#[rpc]
+pub trait SessionApi<BlockHash> {
+	/// Generate a proof that the raw public `key` of type `key_type` was part of the validator
+	/// set of the past `session`.
+	///
+	/// Returns the SCALE encoded membership proof, or `None` if the validator set of the session
+	/// is not available in the off-chain database or the key was not part of it.
+	#[rpc(name = "session_proveMembership")]
+	fn prove_membership(
+		&self,
+		key_type: String,
+		key: Bytes,
+		session: SessionIndex,
+		at: Option<BlockHash>
+	) -> Result<Option<Bytes>>;
+}
+
+/// A struct that implements the [`SessionApi`].
+pub struct Session<C, S, B> {
+	client: Arc<C>,
+	offchain_storage: S,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, S, B> Session<C, S, B> {
+	/// Create new `Session` with the given reference to the client and the node's off-chain
+	/// storage.
+	pub fn new(client: Arc<C>, offchain_storage: S) -> Self {
+		Session { client, offchain_storage, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The key type was not valid.
+	BadKeyType,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::BadKeyType => 2,
+		}
+	}
+}
+
+impl<C, S, Block> SessionApi<<Block as BlockT>::Hash> for Session<C, S, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: SessionMembershipRuntimeApi<Block>,
+	S: OffchainStorage + 'static,
+{
+	fn prove_membership(
+		&self,
+		key_type: String,
+		key: Bytes,
+		session: SessionIndex,
+		at: Option<<Block as BlockT>::Hash>
+	) -> Result<Option<Bytes>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let key_type = key_type.as_str().try_into().map_err(|_| RpcError {
+			code: ErrorCode::ServerError(Error::BadKeyType.into()),
+			message: "Unable to generate membership proof.".into(),
+			data: Some(format!("Invalid key type: {:?}", key_type).into()),
+		})?;
+
+		// the runtime only gets to read the persistent off-chain storage.
+		let context = ExecutionContext::OffchainCall(Some((
+			Box::new(OffchainDbReader(self.offchain_storage.clone())),
+			Capabilities::from(&[Capability::OffchainWorkerDbRead][..]),
+		)));
+
+		api.prove_session_membership_with_context(&at, context, session, key_type, key.to_vec())
+			.map(|proof| proof.map(|proof| proof.encode().into()))
+			.map_err(|e| RpcError {
+				code: ErrorCode::ServerError(Error::RuntimeError.into()),
+				message: "Unable to generate membership proof.".into(),
+				data: Some(format!("{:?}", e).into()),
+			})
+	}
+}
+
+/// Off-chain externalities that only provide read access to the persistent off-chain storage.
+///
+/// Any other capability is denied before reaching these externalities, so the remaining
+/// functions are never called.
+struct OffchainDbReader<S>(S);
+
+impl<S: OffchainStorage> offchain::Externalities for OffchainDbReader<S> {
+	fn is_validator(&self) -> bool {
+		unavailable("is_validator")
+	}
+
+	fn network_state(&self) -> std::result::Result<OpaqueNetworkState, ()> {
+		unavailable("network_state")
+	}
+
+	fn timestamp(&mut self) -> Timestamp {
+		unavailable("timestamp")
+	}
+
+	fn sleep_until(&mut self, _deadline: Timestamp) {
+		unavailable("sleep_until")
+	}
+
+	fn random_seed(&mut self) -> [u8; 32] {
+		unavailable("random_seed")
+	}
+
+	fn local_storage_set(&mut self, _kind: StorageKind, _key: &[u8], _value: &[u8]) {
+		unavailable("local_storage_set")
+	}
+
+	fn local_storage_clear(&mut self, _kind: StorageKind, _key: &[u8]) {
+		unavailable("local_storage_clear")
+	}
+
+	fn local_storage_compare_and_set(
+		&mut self,
+		_kind: StorageKind,
+		_key: &[u8],
+		_old_value: Option<&[u8]>,
+		_new_value: &[u8],
+	) -> bool {
+		unavailable("local_storage_compare_and_set")
+	}
+
+	fn local_storage_get(&mut self, kind: StorageKind, key: &[u8]) -> Option<Vec<u8>> {
+		match kind {
+			StorageKind::PERSISTENT => self.0.get(STORAGE_PREFIX, key),
+			StorageKind::LOCAL => None,
+		}
+	}
+
+	fn http_request_start(
+		&mut self,
+		_method: &str,
+		_uri: &str,
+		_meta: &[u8]
+	) -> std::result::Result<HttpRequestId, ()> {
+		unavailable("http_request_start")
+	}
+
+	fn http_request_add_header(
+		&mut self,
+		_request_id: HttpRequestId,
+		_name: &str,
+		_value: &str
+	) -> std::result::Result<(), ()> {
+		unavailable("http_request_add_header")
+	}
+
+	fn http_request_write_body(
+		&mut self,
+		_request_id: HttpRequestId,
+		_chunk: &[u8],
+		_deadline: Option<Timestamp>
+	) -> std::result::Result<(), HttpError> {
+		unavailable("http_request_write_body")
+	}
+
+	fn http_response_wait(
+		&mut self,
+		_ids: &[HttpRequestId],
+		_deadline: Option<Timestamp>
+	) -> Vec<HttpRequestStatus> {
+		unavailable("http_response_wait")
+	}
+
+	fn http_response_headers(&mut self, _request_id: HttpRequestId) -> Vec<(Vec<u8>, Vec<u8>)> {
+		unavailable("http_response_headers")
+	}
+
+	fn http_response_read_body(
+		&mut self,
+		_request_id: HttpRequestId,
+		_buffer: &mut [u8],
+		_deadline: Option<Timestamp>
+	) -> std::result::Result<usize, HttpError> {
+		unavailable("http_response_read_body")
+	}
+
+	fn set_authorized_nodes(&mut self, _nodes: Vec<OpaquePeerId>, _authorized_only: bool) {
+		unavailable("set_authorized_nodes")
+	}
+}
+
+fn unavailable(name: &str) -> ! {
+	panic!("Off-chain function `{}` is not available when generating membership proofs.", name)
+}
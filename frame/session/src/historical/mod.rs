@@ -32,7 +32,7 @@ use sp_runtime::KeyTypeId;
 use sp_runtime::traits::{Convert, OpaqueKeys};
 use sp_session::{MembershipProof, ValidatorCount};
 use frame_support::{decl_module, decl_storage};
-use frame_support::{Parameter, print};
+use frame_support::{Parameter, print, traits::Get};
 use sp_trie::{MemoryDB, Trie, TrieMut, Recorder, EMPTY_PREFIX};
 use sp_trie::trie_types::{TrieDBMut, TrieDB};
 use super::{SessionIndex, Module as SessionModule};
@@ -54,6 +54,14 @@ pub trait Config: super::Config {
 	///
 	/// It must return the identification for the current session index.
 	type FullIdentificationOf: Convert<Self::ValidatorId, Option<Self::FullIdentification>>;
+
+	/// The number of most recent sessions whose historical roots and off-chain indexed validator
+	/// sets are kept.
+	///
+	/// When set, older sessions are pruned as new sessions are planned and calls to `prune_up_to`
+	/// (e.g. from staking at the end of its bonding duration) never prune these sessions. When
+	/// `None`, pruning is left entirely to the callers of `prune_up_to`.
+	type PruningDepth: Get<Option<SessionIndex>>;
 }
 
 decl_storage! {
@@ -77,7 +85,20 @@ decl_module! {
 impl<T: Config> Module<T> {
 	/// Prune historical stored session roots up to (but not including)
 	/// `up_to`.
+	///
+	/// Sessions within the configured `PruningDepth` are never pruned.
 	pub fn prune_up_to(up_to: SessionIndex) {
+		let up_to = match (T::PruningDepth::get(), StoredRange::get()) {
+			(Some(depth), Some((_, end))) => sp_std::cmp::min(up_to, end.saturating_sub(depth)),
+			_ => up_to,
+		};
+
+		Self::do_prune_up_to(up_to)
+	}
+
+	/// Prune historical stored session roots, along with the validator sets indexed into the
+	/// off-chain database for those sessions, up to (but not including) `up_to`.
+	fn do_prune_up_to(up_to: SessionIndex) {
 		<Self as Store>::StoredRange::mutate(|range| {
 			let (start, end) = match *range {
 				Some(range) => range,
@@ -90,7 +111,10 @@ impl<T: Config> Module<T> {
 				return // out of bounds. harmless.
 			}
 
-			(start..up_to).for_each(<Self as Store>::HistoricalSessions::remove);
+			for session_index in start..up_to {
+				<Self as Store>::HistoricalSessions::remove(session_index);
+				onchain::clear_session_validator_set_from_offchain(session_index);
+			}
 
 			let new_start = up_to;
 			*range = if new_start == end {
@@ -146,6 +170,10 @@ impl<T: Config, I> crate::SessionManager<T::ValidatorId> for NoteHistoricalRoot<
 			}
 		}
 
+		if let Some(depth) = T::PruningDepth::get() {
+			<Module<T>>::do_prune_up_to((new_index + 1).saturating_sub(depth));
+		}
+
 		new_validators
 	}
 
@@ -324,7 +352,7 @@ pub(crate) mod tests {
 	use sp_runtime::testing::UintAuthorityId;
 	use crate::mock::{
 		NEXT_VALIDATORS, force_new_session,
-		set_next_validators, Test, System, Session,
+		set_next_validators, set_pruning_depth, Test, System, Session,
 	};
 	use frame_support::traits::{KeyOwnerProofSystem, OnInitialize};
 
@@ -432,4 +460,35 @@ pub(crate) mod tests {
 			}
 		});
 	}
+
+	#[test]
+	fn pruning_depth_keeps_recent_sessions() {
+		new_test_ext().execute_with(|| {
+			set_pruning_depth(Some(5));
+
+			for i in 1..20u64 {
+				set_next_validators(vec![i]);
+				force_new_session();
+
+				System::set_block_number(i);
+				Session::on_initialize(i);
+			}
+
+			// only the five most recent sessions are kept.
+			assert_eq!(StoredRange::get(), Some((16, 21)));
+			for i in 0..16 {
+				assert!(Historical::historical_root(i).is_none())
+			}
+			for i in 16..21 {
+				assert!(Historical::historical_root(i).is_some())
+			}
+
+			// explicit pruning cannot go past the pruning depth.
+			Historical::prune_up_to(18);
+			assert_eq!(StoredRange::get(), Some((16, 21)));
+
+			Historical::prune_up_to(9999);
+			assert_eq!(StoredRange::get(), Some((16, 21)));
+		});
+	}
 }
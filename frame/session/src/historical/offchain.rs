@@ -260,4 +260,34 @@ mod tests {
 			assert!(Historical::check_proof((DUMMY, &encoded_key_1[..]), proof.clone()).is_some());
 		});
 	}
+
+	#[test]
+	fn pruning_clears_offchain_validator_set() {
+		let mut ext = new_test_ext();
+		let encoded_key_1 = UintAuthorityId(1).encode();
+
+		ext.execute_with(|| {
+			set_next_validators(vec![1, 2]);
+			force_new_session();
+
+			System::set_block_number(1);
+			Session::on_initialize(1);
+
+			onchain::store_current_session_validator_set_to_offchain::<Test>();
+		});
+
+		ext.persist_offchain_overlay();
+
+		ext.execute_with(|| {
+			assert!(prove_session_membership::<Test, _>(1, (DUMMY, &encoded_key_1)).is_some());
+
+			Historical::prune_up_to(2);
+		});
+
+		ext.persist_offchain_overlay();
+
+		ext.execute_with(|| {
+			assert!(prove_session_membership::<Test, _>(1, (DUMMY, &encoded_key_1)).is_none());
+		});
+	}
 }
@@ -60,3 +60,10 @@ pub fn store_session_validator_set_to_offchain<T: HistoricalConfig + SessionConf
 pub fn store_current_session_validator_set_to_offchain<T: HistoricalConfig + SessionConfig>() {
 	store_session_validator_set_to_offchain::<T>(<SessionModule<T>>::current_index());
 }
+
+/// Remove the validator set of the given session from the off-chain database, e.g. when the
+/// session is pruned from the historical roots.
+pub fn clear_session_validator_set_from_offchain(session_index: SessionIndex) {
+	let derived_key = shared::derive_key(shared::PREFIX, session_index);
+	sp_io::offchain_index::clear(derived_key.as_slice());
+}
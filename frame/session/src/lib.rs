@@ -438,7 +438,15 @@ decl_storage! {
 			for (account, val, keys) in config.keys.iter().cloned() {
 				<Module<T>>::inner_set_keys(&val, keys)
 					.expect("genesis config must not contain duplicates; qed");
-				frame_system::Module::<T>::inc_ref(&account);
+				if frame_system::Module::<T>::inc_consumers(&account).is_err() {
+					// This will leak a provider reference, however it only happens once (at
+					// genesis) so it's really not a big deal and we assume that the user wants to
+					// do this since it's the only way a non-endowed account can contain a session
+					// key.
+					frame_system::Module::<T>::inc_providers(&account);
+					frame_system::Module::<T>::inc_consumers(&account)
+						.expect("account has a provider reference now; qed");
+				}
 			}
 
 			let initial_validators_0 = T::SessionManager::new_session(0)
@@ -492,6 +500,8 @@ decl_error! {
 		DuplicatedKey,
 		/// No keys are associated with this account.
 		NoKeys,
+		/// Key setting account is not live, so it's impossible to associate keys.
+		NoAccount,
 	}
 }
 
@@ -734,17 +744,25 @@ impl<T: Config> Module<T> {
 
 	/// Perform the set_key operation, checking for duplicates. Does not set `Changed`.
 	///
-	/// This ensures that the reference counter in system is incremented appropriately and as such
-	/// must accept an account ID, rather than a validator ID.
+	/// This ensures that the consumer reference counter in system is incremented appropriately and
+	/// as such must accept an account ID, rather than a validator ID.
 	fn do_set_keys(account: &T::AccountId, keys: T::Keys) -> dispatch::DispatchResult {
 		let who = T::ValidatorIdOf::convert(account.clone())
 			.ok_or(Error::<T>::NoAssociatedValidatorId)?;
 
-		let old_keys = Self::inner_set_keys(&who, keys)?;
-		if old_keys.is_none() {
-			frame_system::Module::<T>::inc_ref(&account);
+		let had_keys = Self::load_keys(&who).is_some();
+		if !had_keys {
+			frame_system::Module::<T>::inc_consumers(&account)
+				.map_err(|_| Error::<T>::NoAccount)?;
 		}
 
+		Self::inner_set_keys(&who, keys).map_err(|e| {
+			if !had_keys {
+				frame_system::Module::<T>::dec_consumers(&account);
+			}
+			e
+		})?;
+
 		Ok(())
 	}
 
@@ -752,8 +770,8 @@ impl<T: Config> Module<T> {
 	///
 	/// The old keys for this validator are returned, or `None` if there were none.
 	///
-	/// This does not ensure that the reference counter in system is incremented appropriately, it
-	/// must be done by the caller or the keys will be leaked in storage.
+	/// This does not ensure that the consumer reference counter in system is incremented
+	/// appropriately, it must be done by the caller or the keys will be leaked in storage.
 	fn inner_set_keys(who: &T::ValidatorId, keys: T::Keys) -> Result<Option<T::Keys>, DispatchError> {
 		let old_keys = Self::load_keys(who);
 
@@ -790,7 +808,7 @@ impl<T: Config> Module<T> {
 			let key_data = old_keys.get_raw(*id);
 			Self::clear_key_owner(*id, key_data);
 		}
-		frame_system::Module::<T>::dec_ref(&account);
+		frame_system::Module::<T>::dec_consumers(&account);
 
 		Ok(())
 	}
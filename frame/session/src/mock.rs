@@ -81,6 +81,7 @@ thread_local! {
 	pub static DISABLED: RefCell<bool> = RefCell::new(false);
 	// Stores if `on_before_session_end` was called
 	pub static BEFORE_SESSION_END_CALLED: RefCell<bool> = RefCell::new(false);
+	pub static PRUNING_DEPTH: RefCell<Option<SessionIndex>> = RefCell::new(None);
 }
 
 pub struct TestShouldEndSession;
@@ -91,6 +92,15 @@ impl ShouldEndSession<u64> for TestShouldEndSession {
 	}
 }
 
+#[cfg(feature = "historical")]
+pub struct PruningDepth;
+#[cfg(feature = "historical")]
+impl Get<Option<SessionIndex>> for PruningDepth {
+	fn get() -> Option<SessionIndex> {
+		PRUNING_DEPTH.with(|d| *d.borrow())
+	}
+}
+
 pub struct TestSessionHandler;
 impl SessionHandler<u64> for TestSessionHandler {
 	const KEY_TYPE_IDS: &'static [sp_runtime::KeyTypeId] = &[UintAuthorityId::ID];
@@ -164,6 +174,11 @@ pub fn session_changed() -> bool {
 	SESSION_CHANGED.with(|l| *l.borrow())
 }
 
+#[cfg(feature = "historical")]
+pub fn set_pruning_depth(depth: Option<SessionIndex>) {
+	PRUNING_DEPTH.with(|d| *d.borrow_mut() = depth);
+}
+
 pub fn set_next_validators(next: Vec<u64>) {
 	NEXT_VALIDATORS.with(|v| *v.borrow_mut() = next);
 }
@@ -183,7 +198,13 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 			l.borrow().iter().cloned().map(|i| (i, i, UintAuthorityId(i).into())).collect()
 		),
 	}.assimilate_storage(&mut t).unwrap();
-	sp_io::TestExternalities::new(t)
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		// Additional identities that set keys in tests; they need to be kept alive by something.
+		frame_system::Module::<Test>::inc_providers(&4);
+		frame_system::Module::<Test>::inc_providers(&69);
+	});
+	ext
 }
 
 #[derive(Clone, Eq, PartialEq)]
@@ -251,6 +272,7 @@ impl Config for Test {
 impl crate::historical::Config for Test {
 	type FullIdentification = u64;
 	type FullIdentificationOf = sp_runtime::traits::ConvertInto;
+	type PruningDepth = PruningDepth;
 }
 
 pub type System = frame_system::Module<Test>;
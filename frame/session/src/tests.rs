@@ -18,14 +18,14 @@
 // Tests for the Session Pallet
 
 use super::*;
-use frame_support::{traits::OnInitialize, assert_ok};
+use frame_support::{traits::OnInitialize, assert_ok, assert_noop};
 use sp_core::crypto::key_types::DUMMY;
 use sp_runtime::testing::UintAuthorityId;
 use mock::{
 	SESSION_CHANGED, TEST_SESSION_CHANGED, authorities, force_new_session,
 	set_next_validators, set_session_length, session_changed, Origin, System, Session,
 	reset_before_session_end_called, before_session_end_called, new_test_ext,
-	PreUpgradeMockSessionKeys,
+	PreUpgradeMockSessionKeys, Test,
 };
 
 fn initialize_block(block: u64) {
@@ -60,15 +60,41 @@ fn keys_cleared_on_kill() {
 		let id = DUMMY;
 		assert_eq!(Session::key_owner(id, UintAuthorityId(1).get_raw(id)), Some(1));
 
-		assert!(!System::allow_death(&1));
+		assert!(System::is_provider_required(&1));
 		assert_ok!(Session::purge_keys(Origin::signed(1)));
-		assert!(System::allow_death(&1));
+		assert!(!System::is_provider_required(&1));
 
 		assert_eq!(Session::load_keys(&1), None);
 		assert_eq!(Session::key_owner(id, UintAuthorityId(1).get_raw(id)), None);
 	})
 }
 
+#[test]
+fn setting_keys_requires_a_live_account() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Session::set_keys(Origin::signed(5), UintAuthorityId(5).into(), vec![]),
+			Error::<Test>::NoAccount,
+		);
+
+		System::inc_providers(&5);
+		assert_ok!(Session::set_keys(Origin::signed(5), UintAuthorityId(5).into(), vec![]));
+		assert_eq!(System::consumers(&5), 1);
+
+		// Replacing keys doesn't take another reference.
+		assert_ok!(Session::set_keys(Origin::signed(5), UintAuthorityId(6).into(), vec![]));
+		assert_eq!(System::consumers(&5), 1);
+
+		// A failed attempt to set keys leaves the reference count untouched.
+		System::inc_providers(&7);
+		assert_noop!(
+			Session::set_keys(Origin::signed(7), UintAuthorityId(6).into(), vec![]),
+			Error::<Test>::DuplicatedKey,
+		);
+		assert_eq!(System::consumers(&7), 0);
+	});
+}
+
 #[test]
 fn authorities_should_track_validators() {
 	reset_before_session_end_called();
@@ -190,13 +190,16 @@ benchmarks! {
 		assert!(!Ledger::<T>::contains_key(controller));
 	}
 
+	// Worst case scenario, a commission change of an existing validator
 	validate {
 		let (stash, controller) = create_stash_controller::<T>(USER_SEED, 100, Default::default())?;
-		let prefs = ValidatorPrefs::default();
+		Staking::<T>::validate(RawOrigin::Signed(controller.clone()).into(), Default::default())?;
+		let prefs = ValidatorPrefs { commission: Perbill::from_percent(10) };
 		whitelist_account!(controller);
 	}: _(RawOrigin::Signed(controller), prefs)
 	verify {
-		assert!(Validators::<T>::contains_key(stash));
+		assert!(Validators::<T>::contains_key(&stash));
+		assert!(PendingCommission::<T>::contains_key(&stash));
 	}
 
 	// Worst case scenario, MAX_NOMINATIONS
@@ -241,6 +244,28 @@ benchmarks! {
 		assert_eq!(ValidatorCount::get(), validator_count);
 	}
 
+	set_min_commission {
+		let min_commission = Perbill::from_percent(10);
+	}: _(RawOrigin::Root, min_commission)
+	verify {
+		assert_eq!(MinCommission::get(), min_commission);
+	}
+
+	set_commission_limits {
+		let (stash, controller) = create_stash_controller::<T>(USER_SEED, 100, Default::default())?;
+		Staking::<T>::validate(RawOrigin::Signed(controller.clone()).into(), ValidatorPrefs {
+			commission: Perbill::from_percent(10),
+		})?;
+		let limits = CommissionLimits {
+			max_commission: Perbill::from_percent(50),
+			max_change_rate: Perbill::from_percent(5),
+		};
+		whitelist_account!(controller);
+	}: _(RawOrigin::Signed(controller), limits)
+	verify {
+		assert_eq!(ValidatorCommissionLimits::<T>::get(&stash), limits);
+	}
+
 	force_no_eras {}: _(RawOrigin::Root)
 	verify { assert_eq!(ForceEra::get(), Forcing::ForceNone); }
 
@@ -820,6 +845,8 @@ mod tests {
 			assert_ok!(test_benchmark_set_payee::<Test>());
 			assert_ok!(test_benchmark_set_controller::<Test>());
 			assert_ok!(test_benchmark_set_validator_count::<Test>());
+			assert_ok!(test_benchmark_set_min_commission::<Test>());
+			assert_ok!(test_benchmark_set_commission_limits::<Test>());
 			assert_ok!(test_benchmark_force_no_eras::<Test>());
 			assert_ok!(test_benchmark_force_new_era::<Test>());
 			assert_ok!(test_benchmark_force_new_era_always::<Test>());
@@ -218,6 +218,18 @@
 //! [`others`](./struct.Exposure.html#structfield.others) by
 //! [`total`](./struct.Exposure.html#structfield.total) in [`Exposure`](./struct.Exposure.html)).
 //!
+//! The commission can not be lower than [`MinCommission`](./struct.MinCommission.html), set by
+//! Root. Each validator can also bound its own commission through
+//! [`set_commission_limits`](./enum.Call.html#variant.set_commission_limits), with a maximum
+//! commission and a maximum change of commission, which can only be tightened afterwards. A change
+//! of the commission of an existing validator is not applied right away: it is kept in
+//! [`PendingCommission`](./struct.PendingCommission.html) for
+//! [`CommissionChangeDelay`](./trait.Config.html#associatedtype.CommissionChangeDelay) eras, so
+//! that its nominators have time to react. This also holds for a validator which chilled and
+//! validates again, whose last commission is kept in
+//! [`LastCommission`](./struct.LastCommission.html). Payouts always use the commission recorded
+//! for the paid era in [`ErasValidatorPrefs`](./struct.ErasValidatorPrefs.html).
+//!
 //! All entities who receive a reward have the option to choose their reward destination through the
 //! [`Payee`](./struct.Payee.html) storage item (see
 //! [`set_payee`](enum.Call.html#variant.set_payee)), to be one of the following:
@@ -253,6 +265,10 @@
 //! values until the total difference among votes of a particular nominator are less than a
 //! threshold.
 //!
+//! All validators take part in the election, but only the nominators with the most stake do: they
+//! are taken from [`Config::VoterList`], from the highest vote weight to the lowest, until the
+//! weight of reading them reaches [`Config::VoterSnapshotWeightLimit`].
+//!
 //! ## GenesisConfig
 //!
 //! The Staking module depends on the [`GenesisConfig`](./struct.GenesisConfig.html). The
@@ -279,6 +295,7 @@ pub mod benchmarking;
 pub mod slashing;
 pub mod offchain_election;
 pub mod inflation;
+pub mod migrations;
 pub mod weights;
 
 use sp_std::{
@@ -292,7 +309,7 @@ use codec::{HasCompact, Encode, Decode};
 use frame_support::{
 	decl_module, decl_event, decl_storage, ensure, decl_error,
 	weights::{Weight, constants::{WEIGHT_PER_MICROS, WEIGHT_PER_NANOS}},
-	storage::IterableStorageMap,
+	storage::{IterableStorageMap, IterableStorageDoubleMap},
 	dispatch::{
 		DispatchResult, DispatchResultWithPostInfo, DispatchErrorWithPostInfo,
 		WithPostDispatchInfo,
@@ -300,6 +317,7 @@ use frame_support::{
 	traits::{
 		Currency, LockIdentifier, LockableCurrency, WithdrawReasons, OnUnbalanced, Imbalance, Get,
 		UnixTime, EstimateNextNewSession, EnsureOrigin, CurrencyToVote, IsSubType,
+		MigrateAccount, ExistenceRequirement,
 	}
 };
 use pallet_session::historical;
@@ -316,7 +334,7 @@ use sp_runtime::{
 	},
 };
 use sp_staking::{
-	SessionIndex,
+	SessionIndex, OnStakerSlash, StakingInterface, ScoreProvider, SortedListProvider,
 	offence::{OnOffenceHandler, OffenceDetails, Offence, ReportOffence, OffenceError},
 };
 #[cfg(feature = "std")]
@@ -365,8 +383,7 @@ static_assertions::const_assert!(size_of::<NominatorIndex>() <= size_of::<u32>()
 pub(crate) const MAX_VALIDATORS: usize = ValidatorIndex::max_value() as usize;
 pub(crate) const MAX_NOMINATORS: usize = NominatorIndex::max_value() as usize;
 
-/// Counter for the number of eras that have passed.
-pub type EraIndex = u32;
+pub use sp_staking::EraIndex;
 
 /// Counter for the number of "reward" points earned by a given validator.
 pub type RewardPoint = u32;
@@ -463,6 +480,29 @@ impl Default for ValidatorPrefs {
 	}
 }
 
+/// The bounds a validator puts on its own commission.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug)]
+pub struct CommissionLimits {
+	/// The maximum commission the validator can ever set.
+	#[codec(compact)]
+	pub max_commission: Perbill,
+	/// The maximum change of commission, up or down, in a single commission change.
+	///
+	/// Since only one change can be pending at a time, this also bounds the change per
+	/// [`Config::CommissionChangeDelay`] eras.
+	#[codec(compact)]
+	pub max_change_rate: Perbill,
+}
+
+impl Default for CommissionLimits {
+	fn default() -> Self {
+		CommissionLimits {
+			max_commission: Perbill::one(),
+			max_change_rate: Perbill::one(),
+		}
+	}
+}
+
 /// Just a Balance/BlockNumber tuple to encode when a chunk of funds will be unlocked.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
 pub struct UnlockChunk<Balance: HasCompact> {
@@ -864,6 +904,29 @@ pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
 	/// enough to fit in the block.
 	type OffchainSolutionWeightLimit: Get<Weight>;
 
+	/// Something that listens to the slashes applied to stakers, e.g. a pallet which manages
+	/// the stake of several accounts through a single stash.
+	type OnStakerSlash: OnStakerSlash<Self::AccountId, BalanceOf<Self>>;
+
+	/// The list of nominators, sorted by their vote weight, from which the nominators of the
+	/// election snapshot are taken.
+	///
+	/// The staking pallet keeps it up to date with [`Nominators`] and with the active stake of
+	/// each nominator. Use [`UseNominatorsMap`] to iterate the nominators in storage order.
+	type VoterList: SortedListProvider<Self::AccountId, Score = VoteWeight>;
+
+	/// Maximum weight of reading the voters of the election snapshot.
+	///
+	/// All validators are read, and the nominators with the highest vote weight are taken from
+	/// [`Config::VoterList`] until this weight is consumed.
+	type VoterSnapshotWeightLimit: Get<Weight>;
+
+	/// Number of eras after which a change of the commission of an existing validator takes
+	/// effect.
+	///
+	/// Set to 0 if the changes should be used from the next election, like the other preferences.
+	type CommissionChangeDelay: Get<EraIndex>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
@@ -895,11 +958,12 @@ enum Releases {
 	V2_0_0,
 	V3_0_0,
 	V4_0_0,
+	V5_0_0, // populate `VoterList` from `Nominators`.
 }
 
 impl Default for Releases {
 	fn default() -> Self {
-		Releases::V4_0_0
+		Releases::V5_0_0
 	}
 }
 
@@ -920,6 +984,11 @@ decl_storage! {
 		/// Minimum number of staking participants before emergency conditions are imposed.
 		pub MinimumValidatorCount get(fn minimum_validator_count) config(): u32;
 
+		/// The minimum commission of all validators.
+		///
+		/// Validators with a lower commission are elected with this commission instead.
+		pub MinCommission get(fn min_commission) config(): Perbill;
+
 		/// Any validators that may never be slashed or forcibly kicked. It's a Vec since they're
 		/// easy to initialize and the performance hit is minimal (we expect no more than four
 		/// invulnerables) and restricted to testnets.
@@ -928,6 +997,16 @@ decl_storage! {
 		/// Map from all locked "stash" accounts to the controller account.
 		pub Bonded get(fn bonded): map hasher(twox_64_concat) T::AccountId => Option<T::AccountId>;
 
+		/// Map from stash accounts which were migrated onto another account to the stash they
+		/// were moved to and the era they were moved in. Slashes for the eras in which the old
+		/// stash was exposed follow it.
+		pub MigratedStashes get(fn migrated_stash):
+			map hasher(twox_64_concat) T::AccountId => Option<(T::AccountId, EraIndex)>;
+
+		/// The stashes migrated in an era, pruned along with the slashing metadata of the era.
+		StashMigrationsInEra:
+			map hasher(twox_64_concat) EraIndex => Vec<T::AccountId>;
+
 		/// Map from all (unlocked) "controller" accounts to the info regarding the staking.
 		pub Ledger get(fn ledger):
 			map hasher(blake2_128_concat) T::AccountId
@@ -940,6 +1019,20 @@ decl_storage! {
 		pub Validators get(fn validators):
 			map hasher(twox_64_concat) T::AccountId => ValidatorPrefs;
 
+		/// The limits set by validator stashes on their own commission.
+		pub ValidatorCommissionLimits get(fn commission_limits):
+			map hasher(twox_64_concat) T::AccountId => CommissionLimits;
+
+		/// The commission changes of validator stashes which do not apply yet, with the first era
+		/// in which they apply.
+		pub PendingCommission get(fn pending_commission):
+			map hasher(twox_64_concat) T::AccountId => Option<(EraIndex, Perbill)>;
+
+		/// The commission of validator stashes which stopped validating, against which their
+		/// commission is checked when they validate again.
+		pub LastCommission get(fn last_commission):
+			map hasher(twox_64_concat) T::AccountId => Option<Perbill>;
+
 		/// The map from nominator stash key to the set of stash keys of all validators to nominate.
 		pub Nominators get(fn nominators):
 			map hasher(twox_64_concat) T::AccountId => Option<Nominations<T::AccountId>>;
@@ -1084,7 +1177,7 @@ decl_storage! {
 		/// Storage version of the pallet.
 		///
 		/// This is set to v3.0.0 for new networks.
-		StorageVersion build(|_: &GenesisConfig<T>| Releases::V4_0_0): Releases;
+		StorageVersion build(|_: &GenesisConfig<T>| Releases::V5_0_0): Releases;
 	}
 	add_extra_genesis {
 		config(stakers):
@@ -1148,6 +1241,11 @@ decl_event!(
 		/// An account has called `withdraw_unbonded` and removed unbonding chunks worth `Balance`
 		/// from the unlocking queue. \[stash, amount\]
 		Withdrawn(AccountId, Balance),
+		/// A validator changed its commission, which applies from the given era.
+		/// \[stash, commission, era_index\]
+		CommissionChangeScheduled(AccountId, Perbill, EraIndex),
+		/// A validator set new limits on its commission. \[stash, limits\]
+		CommissionLimitsSet(AccountId, CommissionLimits),
 	}
 );
 
@@ -1219,6 +1317,16 @@ decl_error! {
 		IncorrectHistoryDepth,
 		/// Incorrect number of slashing spans provided.
 		IncorrectSlashingSpans,
+		/// The commission is lower than the minimum commission.
+		CommissionTooLow,
+		/// The commission is higher than the maximum commission of the validator.
+		CommissionTooHigh,
+		/// The commission changes more than the maximum change rate of the validator.
+		CommissionChangeTooLarge,
+		/// Commission limits can only be tightened.
+		CommissionLimitsLoosened,
+		/// The stash could not be inserted into the voter list.
+		VoterListInsertFailed,
 	}
 }
 
@@ -1237,6 +1345,10 @@ decl_module! {
 		/// intervention.
 		const SlashDeferDuration: EraIndex = T::SlashDeferDuration::get();
 
+		/// Number of eras after which a change of the commission of an existing validator takes
+		/// effect.
+		const CommissionChangeDelay: EraIndex = T::CommissionChangeDelay::get();
+
 		/// The number of blocks before the end of the era from which election submissions are allowed.
 		///
 		/// Setting this to zero will disable the offchain compute and only on-chain seq-phragmen will
@@ -1264,6 +1376,10 @@ decl_module! {
 
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			migrations::migrate_to_v5::<T>()
+		}
+
 		/// sets `ElectionStatus` to `Open(now)` where `now` is the block number at which the
 		/// election window has opened, if we are at the last session and less blocks than
 		/// `T::ElectionLookahead` is remaining until the next new session schedule. The offchain
@@ -1420,13 +1536,14 @@ decl_module! {
 				Err(Error::<T>::InsufficientValue)?
 			}
 
+			// The stash must be kept alive by something (its balance) for as long as it is bonded.
+			system::Module::<T>::inc_consumers(&stash)?;
+
 			// You're auto-bonded forever, here. We might improve this by only bonding when
 			// you actually validate/nominate and remove once you unbond __everything__.
 			<Bonded<T>>::insert(&stash, &controller);
 			<Payee<T>>::insert(&stash, payee);
 
-			system::Module::<T>::inc_ref(&stash);
-
 			let current_era = CurrentEra::get().unwrap_or(0);
 			let history_depth = Self::history_depth();
 			let last_reward_era = current_era.saturating_sub(history_depth);
@@ -1618,6 +1735,11 @@ decl_module! {
 		///
 		/// Effects will be felt at the beginning of the next era.
 		///
+		/// The commission must be at least [`MinCommission`] and at most the maximum commission of
+		/// the stash. If the stash is already validating, or validated before, a change of
+		/// commission must be within its maximum change rate, and only takes effect after
+		/// `CommissionChangeDelay` eras. It replaces any pending change.
+		///
 		/// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
 		/// And, it can be only called when [`EraElectionStatus`] is `Closed`.
 		///
@@ -1628,8 +1750,9 @@ decl_module! {
 		/// -----------
 		/// Weight: O(1)
 		/// DB Weight:
-		/// - Read: Era Election Status, Ledger
-		/// - Write: Nominators, Validators
+		/// - Read: Era Election Status, Ledger, Min Commission, Validator Commission Limits,
+		///   Validators, Last Commission, Current Era, Eras Validator Prefs
+		/// - Write: Nominators, Validators, Pending Commission, Last Commission
 		/// # </weight>
 		#[weight = T::WeightInfo::validate()]
 		pub fn validate(origin, prefs: ValidatorPrefs) {
@@ -1637,7 +1760,31 @@ decl_module! {
 			let controller = ensure_signed(origin)?;
 			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
 			let stash = &ledger.stash;
-			<Nominators<T>>::remove(stash);
+			ensure!(prefs.commission >= Self::min_commission(), Error::<T>::CommissionTooLow);
+			let limits = Self::commission_limits(stash);
+			ensure!(prefs.commission <= limits.max_commission, Error::<T>::CommissionTooHigh);
+
+			let mut prefs = prefs;
+			match Self::current_commission(stash) {
+				Some(current) if current != prefs.commission => {
+					let change = prefs.commission.saturating_sub(current)
+						.max(current.saturating_sub(prefs.commission));
+					ensure!(change <= limits.max_change_rate, Error::<T>::CommissionChangeTooLarge);
+
+					let era = Self::current_era().unwrap_or(0)
+						.saturating_add(T::CommissionChangeDelay::get());
+					<PendingCommission<T>>::insert(stash, (era, prefs.commission));
+					Self::deposit_event(
+						RawEvent::CommissionChangeScheduled(stash.clone(), prefs.commission, era)
+					);
+					// The current commission is used until the change applies.
+					prefs.commission = current;
+				},
+				_ => <PendingCommission<T>>::remove(stash),
+			}
+
+			Self::do_remove_nominator(stash);
+			<LastCommission<T>>::remove(stash);
 			<Validators<T>>::insert(stash, prefs);
 		}
 
@@ -1679,8 +1826,8 @@ decl_module! {
 				suppressed: false,
 			};
 
-			<Validators<T>>::remove(stash);
-			<Nominators<T>>::insert(stash, &nominations);
+			Self::do_add_nominator(stash, nominations)?;
+			Self::do_remove_validator(stash);
 		}
 
 		/// Declare no desire to either validate or nominate.
@@ -1697,8 +1844,8 @@ decl_module! {
 		/// --------
 		/// Weight: O(1)
 		/// DB Weight:
-		/// - Read: EraElectionStatus, Ledger
-		/// - Write: Validators, Nominators
+		/// - Read: EraElectionStatus, Ledger, Validators
+		/// - Write: Validators, Nominators, PendingCommission, LastCommission
 		/// # </weight>
 		#[weight = T::WeightInfo::chill()]
 		fn chill(origin) {
@@ -2156,6 +2303,61 @@ decl_module! {
 
 			Ok(adjustments)
 		}
+
+		/// Set limits on the commission of the stash of the origin controller.
+		///
+		/// Once set, the limits can only be tightened: neither the maximum commission nor the
+		/// maximum change rate can be raised again. The current commission of the stash, and any
+		/// pending change, must be within the new maximum commission.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// DB Weight:
+		/// - Read: Ledger, Validator Commission Limits, Validators, Pending Commission
+		/// - Write: Validator Commission Limits
+		/// # </weight>
+		#[weight = T::WeightInfo::set_commission_limits()]
+		fn set_commission_limits(origin, limits: CommissionLimits) {
+			let controller = ensure_signed(origin)?;
+			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+			let stash = ledger.stash;
+			let old = Self::commission_limits(&stash);
+			ensure!(
+				limits.max_commission <= old.max_commission &&
+					limits.max_change_rate <= old.max_change_rate,
+				Error::<T>::CommissionLimitsLoosened,
+			);
+			if <Validators<T>>::contains_key(&stash) {
+				ensure!(
+					Self::validators(&stash).commission <= limits.max_commission,
+					Error::<T>::CommissionTooHigh,
+				);
+			}
+			if let Some((_, pending)) = Self::pending_commission(&stash) {
+				ensure!(pending <= limits.max_commission, Error::<T>::CommissionTooHigh);
+			}
+
+			<ValidatorCommissionLimits<T>>::insert(&stash, limits);
+			Self::deposit_event(RawEvent::CommissionLimitsSet(stash, limits));
+		}
+
+		/// Sets the minimum commission of all validators.
+		///
+		/// Validators with a lower commission are elected with the minimum commission.
+		///
+		/// The dispatch origin must be Root.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// Write: Min Commission
+		/// # </weight>
+		#[weight = T::WeightInfo::set_min_commission()]
+		fn set_min_commission(origin, new: Perbill) {
+			ensure_root(origin)?;
+			MinCommission::put(new);
+		}
 	}
 }
 
@@ -2184,7 +2386,22 @@ impl<T: Config> Module<T> {
 		})
 	}
 
-	/// Dump the list of validators and nominators into vectors and keep them on-chain.
+	/// The nominators to take into the election alongside `num_validators` validators.
+	///
+	/// The nominators are taken from [`Config::VoterList`], from the highest vote weight to the
+	/// lowest, until reading them would exceed [`Config::VoterSnapshotWeightLimit`] or the
+	/// snapshot would exceed `MAX_NOMINATORS` voters.
+	pub fn electable_nominators(num_validators: usize) -> Vec<T::AccountId> {
+		let read = T::DbWeight::get().reads(1).max(1);
+		let budget = T::VoterSnapshotWeightLimit::get()
+			.saturating_sub(read.saturating_mul(num_validators as Weight));
+		let max_nominators = (budget / read)
+			.min(MAX_NOMINATORS.saturating_sub(num_validators) as Weight) as usize;
+		T::VoterList::iter().take(max_nominators).collect()
+	}
+
+	/// Dump the list of validators and the electable nominators into vectors and keep them
+	/// on-chain.
 	///
 	/// This data is used to efficiently evaluate election results. returns `true` if the operation
 	/// is successful.
@@ -2194,26 +2411,21 @@ impl<T: Config> Module<T> {
 			consumed_weight += T::DbWeight::get().reads_writes(reads, writes);
 		};
 		let validators = <Validators<T>>::iter().map(|(v, _)| v).collect::<Vec<_>>();
-		let mut nominators = <Nominators<T>>::iter().map(|(n, _)| n).collect::<Vec<_>>();
-
 		let num_validators = validators.len();
-		let num_nominators = nominators.len();
-		add_db_reads_writes((num_validators + num_nominators) as Weight, 0);
+		add_db_reads_writes(num_validators as Weight, 0);
 
-		if
-			num_validators > MAX_VALIDATORS ||
-			num_nominators.saturating_add(num_validators) > MAX_NOMINATORS
-		{
+		if num_validators > MAX_VALIDATORS {
 			log!(
 				warn,
-				"💸 Snapshot size too big [{} <> {}][{} <> {}].",
+				"💸 Snapshot size too big [{} <> {}].",
 				num_validators,
 				MAX_VALIDATORS,
-				num_nominators,
-				MAX_NOMINATORS,
 			);
 			(false, consumed_weight)
 		} else {
+			let mut nominators = Self::electable_nominators(num_validators);
+			add_db_reads_writes(nominators.len() as Weight, 0);
+
 			// all validators nominate themselves;
 			nominators.extend(validators.clone());
 
@@ -2339,12 +2551,86 @@ impl<T: Config> Module<T> {
 			WithdrawReasons::all(),
 		);
 		<Ledger<T>>::insert(controller, ledger);
+		if T::VoterList::contains(&ledger.stash) {
+			let weight = T::CurrencyToVote::to_vote(ledger.active, T::Currency::total_issuance());
+			T::VoterList::on_update(&ledger.stash, weight);
+		}
 	}
 
 	/// Chill a stash account.
 	fn chill_stash(stash: &T::AccountId) {
-		<Validators<T>>::remove(stash);
-		<Nominators<T>>::remove(stash);
+		Self::do_remove_validator(stash);
+		Self::do_remove_nominator(stash);
+	}
+
+	/// Stop `stash` from validating, if it does.
+	///
+	/// Its commission is kept in [`LastCommission`], and any pending commission change of the
+	/// stash is dropped.
+	fn do_remove_validator(stash: &T::AccountId) {
+		if <Validators<T>>::contains_key(stash) {
+			<LastCommission<T>>::insert(stash, <Validators<T>>::take(stash).commission);
+		}
+		<PendingCommission<T>>::remove(stash);
+	}
+
+	/// The commission of `stash` against which a commission change is checked, if it is
+	/// validating, validated before or was elected for the current era.
+	fn current_commission(stash: &T::AccountId) -> Option<Perbill> {
+		if <Validators<T>>::contains_key(stash) {
+			return Some(Self::validators(stash).commission)
+		}
+		if let Some(commission) = Self::last_commission(stash) {
+			return Some(commission)
+		}
+		Self::current_era()
+			.filter(|era| <ErasValidatorPrefs<T>>::contains_key(era, stash))
+			.map(|era| Self::eras_validator_prefs(era, stash).commission)
+	}
+
+	/// The preferences of `stash`, elected for `era`.
+	///
+	/// A pending commission change which applies from `era` is applied to [`Validators`], and the
+	/// commission is raised to [`MinCommission`] if needed.
+	fn elected_validator_prefs(stash: &T::AccountId, era: EraIndex) -> ValidatorPrefs {
+		let mut prefs = Self::validators(stash);
+		if let Some((from, commission)) = Self::pending_commission(stash) {
+			if from <= era {
+				prefs.commission = commission;
+				<Validators<T>>::insert(stash, prefs.clone());
+				<PendingCommission<T>>::remove(stash);
+			}
+		}
+		prefs.commission = prefs.commission.max(Self::min_commission());
+		prefs
+	}
+
+	/// Store the nominations of `stash`, inserting it into [`Config::VoterList`] if it was not
+	/// nominating already.
+	///
+	/// Nothing is stored if `stash` cannot be inserted into the voter list.
+	fn do_add_nominator(
+		stash: &T::AccountId,
+		nominations: Nominations<T::AccountId>,
+	) -> DispatchResult {
+		if !<Nominators<T>>::contains_key(stash) {
+			let issuance = T::Currency::total_issuance();
+			let weight = Self::slashable_balance_of_vote_weight(stash, issuance);
+			T::VoterList::on_insert(stash.clone(), weight).map_err(|err| {
+				log!(warn, "💸 Nominator {:?} not added to the voter list: {:?}", stash, err);
+				Error::<T>::VoterListInsertFailed
+			})?;
+		}
+		<Nominators<T>>::insert(stash, nominations);
+		Ok(())
+	}
+
+	/// Remove the nominations of `stash`, if any, and remove it from [`Config::VoterList`].
+	fn do_remove_nominator(stash: &T::AccountId) {
+		if <Nominators<T>>::contains_key(stash) {
+			<Nominators<T>>::remove(stash);
+			T::VoterList::on_remove(stash);
+		}
 	}
 
 	/// Actually make a payment to a staker. This uses the currency's reward function
@@ -2791,7 +3077,7 @@ impl<T: Config> Module<T> {
 
 			// collect the pref of all winners
 			for stash in &elected_stashes {
-				let pref = Self::validators(stash);
+				let pref = Self::elected_validator_prefs(stash, current_era);
 				<ErasValidatorPrefs<T>>::insert(&current_era, stash, pref);
 			}
 
@@ -2902,8 +3188,10 @@ impl<T: Config> Module<T> {
 			all_validators.push(validator);
 		}
 
-		let nominator_votes = <Nominators<T>>::iter().map(|(nominator, nominations)| {
-			let Nominations { submitted_in, mut targets, suppressed: _ } = nominations;
+		let nominators = Self::electable_nominators(all_validators.len());
+		let nominator_votes = nominators.into_iter().filter_map(|nominator| {
+			let Nominations { submitted_in, mut targets, suppressed: _ } =
+				<Nominators<T>>::get(&nominator)?;
 
 			// Filter out nomination targets which were nominated before the most recent
 			// slashing span.
@@ -2914,7 +3202,7 @@ impl<T: Config> Module<T> {
 				)
 			});
 
-			(nominator, targets)
+			Some((nominator, targets))
 		});
 		all_nominators.extend(nominator_votes.map(|(n, ns)| {
 			let s = weight_of(&n);
@@ -2988,9 +3276,12 @@ impl<T: Config> Module<T> {
 
 		<Payee<T>>::remove(stash);
 		<Validators<T>>::remove(stash);
-		<Nominators<T>>::remove(stash);
+		<PendingCommission<T>>::remove(stash);
+		<LastCommission<T>>::remove(stash);
+		<ValidatorCommissionLimits<T>>::remove(stash);
+		Self::do_remove_nominator(stash);
 
-		system::Module::<T>::dec_ref(stash);
+		system::Module::<T>::dec_consumers(stash);
 
 		Ok(())
 	}
@@ -3137,6 +3428,261 @@ impl<T> pallet_authorship::EventHandler<T::AccountId, T::BlockNumber> for Module
 	}
 }
 
+/// Stake on behalf of accounts through the dispatchables of this pallet, as if `stash` or
+/// `controller` had called them.
+impl<T: Config> StakingInterface for Module<T> {
+	type Balance = BalanceOf<T>;
+	type AccountId = T::AccountId;
+
+	fn minimum_bond() -> Self::Balance {
+		T::Currency::minimum_balance()
+	}
+
+	fn bonding_duration() -> EraIndex {
+		T::BondingDuration::get()
+	}
+
+	fn current_era() -> EraIndex {
+		Self::current_era().unwrap_or(0)
+	}
+
+	fn validator_count() -> u32 {
+		Self::validator_count()
+	}
+
+	fn active_stake(stash: &Self::AccountId) -> Option<Self::Balance> {
+		Self::bonded(stash).and_then(Self::ledger).map(|l| l.active)
+	}
+
+	fn total_stake(stash: &Self::AccountId) -> Option<Self::Balance> {
+		Self::bonded(stash).and_then(Self::ledger).map(|l| l.total)
+	}
+
+	fn bond(
+		stash: Self::AccountId,
+		controller: Self::AccountId,
+		value: Self::Balance,
+		payee: Self::AccountId,
+	) -> DispatchResult {
+		Self::bond(
+			system::RawOrigin::Signed(stash).into(),
+			T::Lookup::unlookup(controller),
+			value,
+			RewardDestination::Account(payee),
+		)
+	}
+
+	fn bond_extra(stash: Self::AccountId, extra: Self::Balance) -> DispatchResult {
+		Self::bond_extra(system::RawOrigin::Signed(stash).into(), extra)
+	}
+
+	fn unbond(controller: Self::AccountId, value: Self::Balance) -> DispatchResult {
+		Self::unbond(system::RawOrigin::Signed(controller).into(), value)
+	}
+
+	fn withdraw_unbonded(controller: Self::AccountId) -> Result<bool, DispatchError> {
+		let stash = Self::ledger(&controller).ok_or(Error::<T>::NotController)?.stash;
+		let num_slashing_spans = Self::slashing_spans(&stash).map_or(0, |s| s.iter().count());
+		Self::withdraw_unbonded(
+			system::RawOrigin::Signed(controller.clone()).into(),
+			num_slashing_spans as u32,
+		).map_err(|e| e.error)?;
+		Ok(!<Ledger<T>>::contains_key(&controller))
+	}
+
+	fn nominate(controller: Self::AccountId, targets: Vec<Self::AccountId>) -> DispatchResult {
+		let targets = targets.into_iter().map(T::Lookup::unlookup).collect();
+		Self::nominate(system::RawOrigin::Signed(controller).into(), targets)
+	}
+
+	fn chill(controller: Self::AccountId) -> DispatchResult {
+		Self::chill(system::RawOrigin::Signed(controller).into())
+	}
+
+	fn is_chilled(stash: &Self::AccountId) -> bool {
+		!<Nominators<T>>::contains_key(stash) && !<Validators<T>>::contains_key(stash)
+	}
+
+	fn stash_by_ctrl(controller: &Self::AccountId) -> Result<Self::AccountId, DispatchError> {
+		Self::ledger(controller).map(|l| l.stash).ok_or_else(|| Error::<T>::NotController.into())
+	}
+
+	fn is_exposed_in_era(who: &Self::AccountId, era: &EraIndex) -> bool {
+		<ErasStakers<T>>::contains_key(era, who) ||
+			<ErasStakers<T>>::iter_prefix_values(era)
+				.any(|exposure| exposure.others.iter().any(|i| i.who == *who))
+	}
+
+	fn force_unstake(stash: Self::AccountId) -> DispatchResult {
+		let num_slashing_spans = Self::slashing_spans(&stash).map_or(0, |s| s.iter().count());
+		Self::kill_stash(&stash, num_slashing_spans as u32)?;
+		T::Currency::remove_lock(STAKING_ID, &stash);
+		Ok(())
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn max_exposure_size() -> u32 {
+		T::MaxNominatorRewardedPerValidator::get()
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn add_era_stakers(
+		era: &EraIndex,
+		validator: &Self::AccountId,
+		nominators: Vec<Self::AccountId>,
+	) {
+		let others = nominators.into_iter()
+			.map(|who| IndividualExposure { who, value: Zero::zero() })
+			.collect();
+		let exposure = Exposure { total: Zero::zero(), own: Zero::zero(), others };
+		<ErasStakers<T>>::insert(era, validator, exposure);
+	}
+}
+
+/// Hands the bonds of an account over to another one.
+///
+/// If the account is a controller, the destination becomes the controller of the stash instead.
+/// If it is a stash, the bonded funds are moved together with the ledger, the reward destination
+/// and the nominations, unless the destination is bonded already. A validator is chilled, since
+/// its session keys are not moved. Slashes for eras in which the old stash was exposed are
+/// applied to the new stash, but rewards of those eras are not paid out to it.
+impl<T: Config> MigrateAccount<T::AccountId> for Module<T> {
+	fn migration_weight(_from: &T::AccountId) -> Weight {
+		T::DbWeight::get().reads_writes(14, 15)
+	}
+
+	fn migrate_account(from: &T::AccountId, to: &T::AccountId) -> Weight {
+		let weight = Self::migration_weight(from);
+		if from == to { return weight }
+
+		if let Some(ledger) = Self::ledger(from) {
+			if &ledger.stash != from && !<Ledger<T>>::contains_key(to) {
+				<Bonded<T>>::insert(&ledger.stash, to);
+				<Ledger<T>>::remove(from);
+				<Ledger<T>>::insert(to, ledger);
+			}
+		}
+
+		let controller = match Self::bonded(from) {
+			Some(c) => c,
+			None => return weight,
+		};
+		let new_controller = if &controller == from { to.clone() } else { controller.clone() };
+		if <Bonded<T>>::contains_key(to) || (&controller == from && <Ledger<T>>::contains_key(to)) {
+			return weight
+		}
+		let mut ledger = match Self::ledger(&controller) {
+			Some(l) => l,
+			None => return weight,
+		};
+		if system::Module::<T>::inc_consumers(to).is_err() { return weight }
+		// the consumer reference of the bond goes first, so that a stash which bonded all of its
+		// funds can be reaped by the transfer.
+		T::Currency::remove_lock(STAKING_ID, from);
+		system::Module::<T>::dec_consumers(from);
+		let transfer = T::Currency::transfer(
+			from,
+			to,
+			ledger.total,
+			ExistenceRequirement::AllowDeath,
+		);
+		if transfer.is_err() {
+			// the account still exists, since the transfer did not go through.
+			let _ = system::Module::<T>::inc_consumers(from);
+			T::Currency::set_lock(STAKING_ID, from, ledger.total, WithdrawReasons::all());
+			system::Module::<T>::dec_consumers(to);
+			return weight
+		}
+
+		Self::do_remove_validator(from);
+		if let Some(commission) = <LastCommission<T>>::take(from) {
+			<LastCommission<T>>::insert(to, commission);
+		}
+		if <ValidatorCommissionLimits<T>>::contains_key(from) {
+			<ValidatorCommissionLimits<T>>::insert(to, <ValidatorCommissionLimits<T>>::take(from));
+		}
+		let nominations = Self::nominators(from);
+		Self::do_remove_nominator(from);
+
+		<Bonded<T>>::remove(from);
+		<Bonded<T>>::insert(to, &new_controller);
+		<Payee<T>>::insert(to, <Payee<T>>::take(from));
+		<Ledger<T>>::remove(&controller);
+		ledger.stash = to.clone();
+		Self::update_ledger(&new_controller, &ledger);
+		let era = Self::current_era().unwrap_or(0);
+		<MigratedStashes<T>>::insert(from, (to, era));
+		<StashMigrationsInEra<T>>::append(era, from);
+
+		if let Some(nominations) = nominations {
+			// the nominations are dropped if the new stash cannot be added to the voter list.
+			let _ = Self::do_add_nominator(to, nominations);
+		}
+		weight
+	}
+}
+
+impl<T: Config> ScoreProvider<T::AccountId> for Module<T> {
+	type Score = VoteWeight;
+
+	fn score(who: &T::AccountId) -> VoteWeight {
+		Self::slashable_balance_of_vote_weight(who, T::Currency::total_issuance())
+	}
+}
+
+/// A [`SortedListProvider`] over the [`Nominators`] map, which iterates the nominators in storage
+/// order.
+///
+/// It keeps no state of its own, and is meant for runtimes which do not need the nominators of
+/// the snapshot to be sorted, e.g. tests.
+pub struct UseNominatorsMap<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: Config> SortedListProvider<T::AccountId> for UseNominatorsMap<T> {
+	type Score = VoteWeight;
+	type Error = ();
+
+	fn iter() -> Box<dyn Iterator<Item = T::AccountId>> {
+		Box::new(<Nominators<T>>::iter().map(|(n, _)| n))
+	}
+
+	fn count() -> u32 {
+		<Nominators<T>>::iter().count() as u32
+	}
+
+	fn contains(id: &T::AccountId) -> bool {
+		<Nominators<T>>::contains_key(id)
+	}
+
+	fn on_insert(_: T::AccountId, _: VoteWeight) -> Result<(), ()> {
+		// nothing to do on insert.
+		Ok(())
+	}
+
+	fn on_update(_: &T::AccountId, _: VoteWeight) {
+		// nothing to do on update.
+	}
+
+	fn on_remove(_: &T::AccountId) {
+		// nothing to do on remove.
+	}
+
+	fn regenerate(
+		_: impl IntoIterator<Item = T::AccountId>,
+		_: Box<dyn Fn(&T::AccountId) -> VoteWeight>,
+	) -> u32 {
+		// nothing to do upon regenerate.
+		0
+	}
+
+	fn clear() {
+		// nothing to do upon clear.
+	}
+
+	fn sanity_check() -> Result<(), &'static str> {
+		Ok(())
+	}
+}
+
 /// A `Convert` implementation that finds the stash of the given controller account,
 /// if any.
 pub struct StashOf<T>(sp_std::marker::PhantomData<T>);
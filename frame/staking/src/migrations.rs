@@ -0,0 +1,41 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations for the staking module.
+
+use super::*;
+use frame_support::{StorageValue, IterableStorageMap};
+
+/// Populate [`Config::VoterList`] from the existing [`Nominators`].
+///
+/// This is a no-op unless the storage version is `V4_0_0`, in which case the version is bumped to
+/// `V5_0_0`.
+pub fn migrate_to_v5<T: Config>() -> Weight {
+	if StorageVersion::get() != Releases::V4_0_0 {
+		return T::DbWeight::get().reads(1)
+	}
+
+	let nominators = <Nominators<T>>::iter().map(|(n, _)| n);
+	let inserted = T::VoterList::regenerate(nominators, <Module<T>>::slashable_balance_of_fn());
+	StorageVersion::put(Releases::V5_0_0);
+	crate::log!(info, "💸 Migrated {} nominators to the voter list.", inserted);
+
+	// For each nominator: the nominator, its bonded controller and ledger are read, and its node
+	// and bag written.
+	let inserted = inserted as Weight;
+	T::DbWeight::get().reads_writes(inserted.saturating_mul(3).saturating_add(2), inserted * 2 + 1)
+}
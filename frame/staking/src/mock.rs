@@ -34,8 +34,8 @@ use sp_runtime::{
 	testing::{Header, TestXt, UintAuthorityId},
 	traits::{IdentityLookup, Zero},
 };
-use sp_staking::offence::{OffenceDetails, OnOffenceHandler};
-use std::{cell::RefCell, collections::HashSet};
+use sp_staking::{offence::{OffenceDetails, OnOffenceHandler}, OnStakerSlash};
+use std::{cell::RefCell, collections::{BTreeMap, HashSet}};
 
 pub const INIT_TIMESTAMP: u64 = 30_000;
 
@@ -103,6 +103,7 @@ mod staking {
 use frame_system as system;
 use pallet_balances as balances;
 use pallet_session as session;
+use pallet_bags_list as bags_list;
 
 impl_outer_event! {
 	pub enum MetaEvent for Test {
@@ -110,6 +111,7 @@ impl_outer_event! {
 		balances<T>,
 		session,
 		staking<T>,
+		bags_list<T>,
 	}
 }
 
@@ -167,6 +169,9 @@ impl frame_system::Config for Test {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = MaxLocks;
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type Balance = Balance;
 	type Event = MetaEvent;
 	type DustRemoval = ();
@@ -200,6 +205,7 @@ impl pallet_session::Config for Test {
 impl pallet_session::historical::Config for Test {
 	type FullIdentification = crate::Exposure<AccountId, Balance>;
 	type FullIdentificationOf = crate::ExposureOf<Test>;
+	type PruningDepth = ();
 }
 impl pallet_authorship::Config for Test {
 	type FindAuthor = Author11;
@@ -233,6 +239,21 @@ parameter_types! {
 	pub const UnsignedPriority: u64 = 1 << 20;
 	pub const MinSolutionScoreBump: Perbill = Perbill::zero();
 	pub OffchainSolutionWeightLimit: Weight = BlockWeights::get().max_block;
+	pub static VoterSnapshotWeightLimit: Weight = Weight::max_value();
+	pub static CommissionChangeDelay: EraIndex = 0;
+}
+
+const THRESHOLDS: [VoteWeight; 9] = [10, 20, 30, 40, 50, 60, 1_000, 2_000, 10_000];
+
+parameter_types! {
+	pub const BagThresholds: &'static [VoteWeight] = &THRESHOLDS;
+}
+
+impl pallet_bags_list::Config for Test {
+	type Event = MetaEvent;
+	type ScoreProvider = Staking;
+	type BagThresholds = BagThresholds;
+	type WeightInfo = ();
 }
 
 thread_local! {
@@ -250,6 +271,19 @@ impl OnUnbalanced<NegativeImbalanceOf<Test>> for RewardRemainderMock {
 	}
 }
 
+thread_local! {
+	pub static SLASHED_STAKERS: RefCell<Vec<(AccountId, Balance, BTreeMap<EraIndex, Balance>)>> =
+		RefCell::new(vec![]);
+}
+
+pub struct OnStakerSlashMock;
+
+impl OnStakerSlash<AccountId, Balance> for OnStakerSlashMock {
+	fn on_slash(stash: &AccountId, active: Balance, unlocking: &BTreeMap<EraIndex, Balance>) {
+		SLASHED_STAKERS.with(|v| v.borrow_mut().push((*stash, active, unlocking.clone())));
+	}
+}
+
 impl Config for Test {
 	type Currency = Balances;
 	type UnixTime = Timestamp;
@@ -272,6 +306,10 @@ impl Config for Test {
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type UnsignedPriority = UnsignedPriority;
 	type OffchainSolutionWeightLimit = OffchainSolutionWeightLimit;
+	type OnStakerSlash = OnStakerSlashMock;
+	type VoterList = BagsList;
+	type VoterSnapshotWeightLimit = VoterSnapshotWeightLimit;
+	type CommissionChangeDelay = CommissionChangeDelay;
 	type WeightInfo = ();
 }
 
@@ -392,6 +430,8 @@ impl ExtBuilder {
 		ELECTION_LOOKAHEAD.with(|v| *v.borrow_mut() = self.election_lookahead);
 		PERIOD.with(|v| *v.borrow_mut() = self.session_length);
 		MAX_ITERATIONS.with(|v| *v.borrow_mut() = self.max_offchain_iterations);
+		VOTER_SNAPSHOT_WEIGHT_LIMIT.with(|v| *v.borrow_mut() = Weight::max_value());
+		COMMISSION_CHANGE_DELAY.with(|v| *v.borrow_mut() = 0);
 	}
 	pub fn build(self) -> sp_io::TestExternalities {
 		sp_tracing::try_init_simple();
@@ -497,6 +537,7 @@ pub type Balances = pallet_balances::Module<Test>;
 pub type Session = pallet_session::Module<Test>;
 pub type Timestamp = pallet_timestamp::Module<Test>;
 pub type Staking = Module<Test>;
+pub type BagsList = pallet_bags_list::Module<Test>;
 
 pub(crate) fn current_era() -> EraIndex {
 	Staking::current_era().unwrap()
@@ -506,6 +547,7 @@ fn post_conditions() {
 	check_nominators();
 	check_exposures();
 	check_ledgers();
+	check_voter_list();
 }
 
 pub(crate) fn active_era() -> EraIndex {
@@ -517,6 +559,13 @@ fn check_ledgers() {
 	Bonded::<Test>::iter().for_each(|(_, ctrl)| assert_ledger_consistent(ctrl))
 }
 
+fn check_voter_list() {
+	// the voter list must be consistent and contain exactly the nominators.
+	assert_ok!(BagsList::sanity_check());
+	assert_eq!(BagsList::count() as usize, <Nominators<Test>>::iter().count());
+	<Nominators<Test>>::iter().for_each(|(nominator, _)| assert!(BagsList::contains(&nominator)));
+}
+
 fn check_exposures() {
 	// a check per validator to ensure the exposure struct is always sane.
 	let era = active_era();
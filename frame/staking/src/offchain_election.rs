@@ -22,7 +22,7 @@ use crate::{
 	Config, ValidatorIndex, WeightInfo,
 };
 use codec::Decode;
-use frame_support::{traits::Get, weights::Weight, IterableStorageMap};
+use frame_support::{traits::Get, weights::Weight, IterableStorageMap, StorageMap};
 use frame_system::offchain::SubmitTransaction;
 use sp_npos_elections::{
 	build_support_map, evaluate_support, reduce, Assignment, ElectionResult, ElectionScore,
@@ -267,10 +267,16 @@ where
 {
 	match compact.len().checked_sub(maximum_allowed_voters as usize) {
 		Some(to_remove) if to_remove > 0 => {
-			// grab all voters and sort them by least stake.
+			// grab all the nominators of the snapshot and sort them by least stake.
 			let balance_of = <Module<T>>::slashable_balance_of_fn();
-			let mut voters_sorted = <Nominators<T>>::iter()
-				.map(|(who, _)| (who.clone(), balance_of(&who)))
+			let mut voters_sorted = <Module<T>>::snapshot_nominators()
+				.unwrap_or_default()
+				.into_iter()
+				.filter(|who| <Nominators<T>>::contains_key(who))
+				.map(|who| {
+					let stake = balance_of(&who);
+					(who, stake)
+				})
 				.collect::<Vec<_>>();
 			voters_sorted.sort_by_key(|(_, y)| *y);
 
@@ -58,7 +58,8 @@ use frame_support::{
 	StorageMap, StorageDoubleMap, ensure,
 	traits::{Currency, OnUnbalanced, Imbalance},
 };
-use sp_std::vec::Vec;
+use sp_std::{vec::Vec, collections::btree_map::BTreeMap};
+use sp_staking::OnStakerSlash;
 use codec::{Encode, Decode};
 
 /// The proportion of the slashing reward to be paid out on the first slashing detection.
@@ -545,6 +546,15 @@ impl<'a, T: 'a + Config> Drop for InspectingSpans<'a, T> {
 pub(crate) fn clear_era_metadata<T: Config>(obsolete_era: EraIndex) {
 	<Module<T> as Store>::ValidatorSlashInEra::remove_prefix(&obsolete_era);
 	<Module<T> as Store>::NominatorSlashInEra::remove_prefix(&obsolete_era);
+
+	// no slash can be reported anymore for the eras in which these stashes were exposed.
+	for stash in <Module<T> as Store>::StashMigrationsInEra::take(&obsolete_era) {
+		<Module<T> as Store>::MigratedStashes::mutate_exists(&stash, |migrated| {
+			if migrated.as_ref().map_or(false, |(_, era)| *era == obsolete_era) {
+				*migrated = None;
+			}
+		});
+	}
 }
 
 /// Clear slashing metadata for a dead account.
@@ -582,6 +592,12 @@ pub fn do_slash<T: Config>(
 	reward_payout: &mut BalanceOf<T>,
 	slashed_imbalance: &mut NegativeImbalanceOf<T>,
 ) {
+	let mut stash = stash.clone();
+	while let Some((migrated, _)) = <Module<T>>::migrated_stash(&stash) {
+		stash = migrated;
+	}
+	let stash = &stash;
+
 	let controller = match <Module<T>>::bonded(stash) {
 		None => return, // defensive: should always exist.
 		Some(c) => c,
@@ -605,6 +621,13 @@ pub fn do_slash<T: Config>(
 
 		<Module<T>>::update_ledger(&controller, &ledger);
 
+		let mut unlocking = BTreeMap::<EraIndex, BalanceOf<T>>::new();
+		for chunk in ledger.unlocking.iter() {
+			let value = unlocking.entry(chunk.era).or_insert_with(Zero::zero);
+			*value = value.saturating_add(chunk.value);
+		}
+		T::OnStakerSlash::on_slash(stash, ledger.active, &unlocking);
+
 		// trigger the event
 		<Module<T>>::deposit_event(
 			super::RawEvent::Slash(stash.clone(), value)
@@ -24,8 +24,8 @@ use sp_runtime::{
 };
 use sp_staking::offence::OffenceDetails;
 use frame_support::{
-	assert_ok, assert_noop, StorageMap,
-	traits::{Currency, ReservableCurrency, OnInitialize, OnFinalize},
+	assert_ok, assert_noop, StorageMap, StorageValue,
+	traits::{Currency, ReservableCurrency, OnInitialize, OnFinalize, MigrateAccount},
 };
 use pallet_balances::Error as BalancesError;
 use substrate_test_utils::assert_eq_uvec;
@@ -2095,6 +2095,30 @@ fn slashing_performed_according_exposure() {
 	});
 }
 
+#[test]
+fn slashing_notifies_on_staker_slash() {
+	ExtBuilder::default().build_and_execute(|| {
+		// Unbond some of the stake of 11 so that it has an unlocking chunk.
+		assert_ok!(Staking::unbond(Origin::signed(10), 500));
+		let unlock_era = Staking::current_era().unwrap() + 3;
+
+		on_offence_now(
+			&[OffenceDetails {
+				offender: (11, Staking::eras_stakers(Staking::active_era().unwrap().index, 11)),
+				reporters: vec![],
+			}],
+			&[Perbill::from_percent(10)],
+		);
+
+		// The slash is taken from the active stake, the unlocking chunk is left untouched.
+		let slashed = SLASHED_STAKERS.with(|v| v.borrow().clone());
+		assert_eq!(
+			slashed.into_iter().filter(|(stash, ..)| *stash == 11).collect::<Vec<_>>(),
+			vec![(11, 400, vec![(unlock_era, 500)].into_iter().collect())],
+		);
+	});
+}
+
 #[test]
 fn slash_in_old_span_does_not_deselect() {
 	ExtBuilder::default().build_and_execute(|| {
@@ -4696,3 +4720,366 @@ fn payout_to_any_account_works() {
 		assert!(Balances::free_balance(42) > 0);
 	})
 }
+
+#[test]
+fn voter_list_follows_nominators() {
+	ExtBuilder::default().build_and_execute(|| {
+		// the genesis nominator is in the bag of its stake.
+		assert!(BagsList::contains(&101));
+		assert_eq!(BagsList::list_node(&101).unwrap().bag_upper, 1_000);
+		assert_eq!(BagsList::count(), 1);
+
+		// bonding extra moves it up.
+		assert_ok!(Staking::bond_extra(Origin::signed(101), 600));
+		assert_eq!(BagsList::list_node(&101).unwrap().bag_upper, 2_000);
+
+		// unbonding moves it down.
+		assert_ok!(Staking::unbond(Origin::signed(100), 1_050));
+		assert_eq!(BagsList::list_node(&101).unwrap().bag_upper, 50);
+
+		// validating removes it.
+		assert_ok!(Staking::validate(Origin::signed(100), ValidatorPrefs::default()));
+		assert!(!BagsList::contains(&101));
+
+		// nominating again inserts it with its current stake.
+		assert_ok!(Staking::nominate(Origin::signed(100), vec![11]));
+		assert_eq!(BagsList::list_node(&101).unwrap().bag_upper, 50);
+		// nominating while already nominating keeps it in place.
+		assert_ok!(Staking::nominate(Origin::signed(100), vec![21]));
+		assert_eq!(BagsList::count(), 1);
+
+		// chilling removes it.
+		assert_ok!(Staking::chill(Origin::signed(100)));
+		assert!(!BagsList::contains(&101));
+		assert_eq!(BagsList::count(), 0);
+	});
+}
+
+#[test]
+fn nominate_fails_atomically_if_voter_list_insertion_fails() {
+	ExtBuilder::default().build_and_execute(|| {
+		// the validator 11 is put into the voter list without nominating.
+		assert_ok!(BagsList::on_insert(11, 1_000));
+
+		assert_noop!(
+			Staking::nominate(Origin::signed(10), vec![21]),
+			Error::<Test>::VoterListInsertFailed,
+		);
+		assert!(<Validators<Test>>::contains_key(11));
+		assert!(!<Nominators<Test>>::contains_key(11));
+
+		BagsList::on_remove(&11);
+	});
+}
+
+#[test]
+fn voter_list_follows_slashes() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_eq!(BagsList::list_node(&101).unwrap().bag_upper, 1_000);
+		on_offence_now(
+			&[OffenceDetails {
+				offender: (11, Staking::eras_stakers(active_era(), 11)),
+				reporters: vec![],
+			}],
+			&[Perbill::from_percent(100)],
+		);
+
+		// the nominator was slashed, and moved to the bag of its remaining stake.
+		let active = Staking::ledger(100).unwrap().active;
+		assert!(active < 500);
+		assert_eq!(
+			BagsList::list_node(&101).unwrap().bag_upper,
+			pallet_bags_list::notional_bag_for::<Test>(active as VoteWeight),
+		);
+	});
+}
+
+#[test]
+fn migrate_account_moves_bonds_and_their_slashes() {
+	ExtBuilder::default().build_and_execute(|| {
+		let _ = Balances::make_free_balance_be(&1001, 10);
+		let _ = Balances::make_free_balance_be(&1010, 10);
+		let ledger = Staking::ledger(100).unwrap();
+		let nominations = Staking::nominators(101).unwrap();
+
+		// the stash is moved together with its bonded funds and nominations.
+		<Staking as MigrateAccount<_>>::migrate_account(&101, &1001);
+		assert_eq!(Staking::bonded(101), None);
+		assert_eq!(Staking::bonded(1001), Some(100));
+		assert_eq!(Staking::ledger(100), Some(StakingLedger { stash: 1001, ..ledger.clone() }));
+		assert_eq!(Staking::nominators(1001), Some(nominations));
+		assert!(Staking::nominators(101).is_none());
+		assert!(BagsList::list_node(&1001).is_some());
+		assert_eq!(Balances::locks(&1001)[0].amount, ledger.total);
+		assert!(Balances::locks(&101).is_empty());
+
+		// a controller hands the bond over.
+		<Staking as MigrateAccount<_>>::migrate_account(&10, &1010);
+		assert_eq!(Staking::bonded(11), Some(1010));
+		assert!(Staking::ledger(10).is_none());
+		assert_eq!(Staking::ledger(1010).unwrap().stash, 11);
+
+		// the old stash was exposed in this era, the new stash is slashed for it.
+		on_offence_now(
+			&[OffenceDetails {
+				offender: (11, Staking::eras_stakers(active_era(), 11)),
+				reporters: vec![],
+			}],
+			&[Perbill::from_percent(100)],
+		);
+		assert!(Staking::ledger(100).unwrap().active < ledger.active);
+		assert!(Balances::free_balance(1001) < ledger.total + 10);
+
+		// a stash which bonded all of its funds is reaped once the bond is moved.
+		let _ = Balances::make_free_balance_be(&3, 1000);
+		let _ = Balances::make_free_balance_be(&1003, 10);
+		assert_ok!(Staking::bond(Origin::signed(3), 4, 1000, RewardDestination::Controller));
+		<Staking as MigrateAccount<_>>::migrate_account(&3, &1003);
+		assert_eq!(Staking::bonded(3), None);
+		assert_eq!(Staking::bonded(1003), Some(4));
+		assert_eq!(Balances::free_balance(1003), 1010);
+		assert_eq!(Balances::locks(&1003)[0].amount, 1000);
+		assert!(!System::account_exists(&3));
+
+		// the old stashes are forgotten once no slash can be reported for their eras anymore.
+		let era = current_era();
+		assert_eq!(Staking::migrated_stash(101), Some((1001, era)));
+		assert_eq!(Staking::migrated_stash(3), Some((1003, era)));
+		mock::start_era(era + BondingDuration::get() + 1);
+		assert!(Staking::migrated_stash(101).is_none());
+		assert!(Staking::migrated_stash(3).is_none());
+	});
+}
+
+#[test]
+fn snapshot_takes_the_highest_nominators_within_weight_limit() {
+	ExtBuilder::default().build_and_execute(|| {
+		bond_nominator(1001, 1000, 5_000, vec![11]);
+		bond_nominator(2001, 2000, 20, vec![21]);
+		let num_validators = <Validators<Test>>::iter().count();
+
+		// all the nominators fit, from the highest stake to the lowest.
+		assert_eq!(Staking::electable_nominators(num_validators), vec![1001, 101, 2001]);
+
+		// only two nominators fit.
+		VoterSnapshotWeightLimit::set(
+			<Test as frame_system::Config>::DbWeight::get().reads(num_validators as u64 + 2)
+		);
+		assert_eq!(Staking::electable_nominators(num_validators), vec![1001, 101]);
+
+		let (did_snapshot, _) = Staking::create_stakers_snapshot();
+		assert!(did_snapshot);
+		let mut snapshot = Staking::snapshot_nominators().unwrap();
+		snapshot.sort();
+		let mut expected = <Validators<Test>>::iter().map(|(v, _)| v).collect::<Vec<_>>();
+		expected.extend(vec![101, 1001]);
+		expected.sort();
+		assert_eq!(snapshot, expected);
+	});
+}
+
+#[test]
+fn migrate_to_v5_populates_voter_list() {
+	ExtBuilder::default().build_and_execute(|| {
+		bond_nominator(1001, 1000, 5_000, vec![11]);
+		BagsList::clear();
+		assert_eq!(BagsList::count(), 0);
+
+		// nothing to do on the current version.
+		crate::migrations::migrate_to_v5::<Test>();
+		assert_eq!(BagsList::count(), 0);
+
+		StorageVersion::put(Releases::V4_0_0);
+		crate::migrations::migrate_to_v5::<Test>();
+		assert_eq!(StorageVersion::get(), Releases::V5_0_0);
+		assert_eq!(BagsList::count(), 2);
+		assert_eq!(BagsList::iter().collect::<Vec<_>>(), vec![1001, 101]);
+	});
+}
+
+#[test]
+fn staking_interface_exposure_and_force_unstake() {
+	ExtBuilder::default().build_and_execute(|| {
+		let era = active_era();
+		assert!(<Staking as StakingInterface>::is_exposed_in_era(&11, &era));
+		assert!(<Staking as StakingInterface>::is_exposed_in_era(&101, &era));
+		assert!(!<Staking as StakingInterface>::is_exposed_in_era(&41, &era));
+		assert_eq!(<Staking as StakingInterface>::stash_by_ctrl(&100), Ok(101));
+		assert!(<Staking as StakingInterface>::stash_by_ctrl(&101).is_err());
+
+		assert_ok!(<Staking as StakingInterface>::force_unstake(101));
+		assert!(Staking::bonded(&101).is_none());
+		assert!(Staking::ledger(&100).is_none());
+		assert!(!<Nominators<Test>>::contains_key(&101));
+		assert!(!BagsList::contains(&101));
+		// the funds are unlocked right away.
+		assert_ok!(Balances::transfer(Origin::signed(101), 1, 1_000));
+	});
+}
+
+#[test]
+fn min_commission_works() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_noop!(
+			Staking::set_min_commission(Origin::signed(10), Perbill::from_percent(10)),
+			BadOrigin,
+		);
+		assert_ok!(Staking::set_min_commission(Origin::root(), Perbill::from_percent(10)));
+		assert_eq!(Staking::min_commission(), Perbill::from_percent(10));
+
+		assert_noop!(
+			Staking::validate(Origin::signed(10), ValidatorPrefs {
+				commission: Perbill::from_percent(5),
+			}),
+			Error::<Test>::CommissionTooLow,
+		);
+
+		// existing validators with a lower commission are elected with the minimum commission.
+		assert_eq!(Staking::validators(&11).commission, Perbill::zero());
+		mock::start_era(1);
+		assert_eq!(Staking::eras_validator_prefs(1, &11).commission, Perbill::from_percent(10));
+		assert_eq!(Staking::validators(&11).commission, Perbill::zero());
+	});
+}
+
+#[test]
+fn commission_limits_work() {
+	ExtBuilder::default().build_and_execute(|| {
+		let limits = CommissionLimits {
+			max_commission: Perbill::from_percent(50),
+			max_change_rate: Perbill::from_percent(10),
+		};
+		assert_noop!(
+			Staking::set_commission_limits(Origin::signed(11), limits),
+			Error::<Test>::NotController,
+		);
+		assert_ok!(Staking::set_commission_limits(Origin::signed(10), limits));
+		assert_eq!(Staking::commission_limits(&11), limits);
+		assert_eq!(
+			*staking_events().last().unwrap(),
+			RawEvent::CommissionLimitsSet(11, limits),
+		);
+
+		let prefs = |percent| ValidatorPrefs { commission: Perbill::from_percent(percent) };
+		assert_noop!(
+			Staking::validate(Origin::signed(10), prefs(60)),
+			Error::<Test>::CommissionTooHigh,
+		);
+		assert_noop!(
+			Staking::validate(Origin::signed(10), prefs(20)),
+			Error::<Test>::CommissionChangeTooLarge,
+		);
+		assert_ok!(Staking::validate(Origin::signed(10), prefs(10)));
+		assert_eq!(Staking::pending_commission(&11), Some((0, Perbill::from_percent(10))));
+
+		// the limits can only be tightened, and must hold for the pending commission.
+		assert_noop!(
+			Staking::set_commission_limits(Origin::signed(10), CommissionLimits {
+				max_commission: Perbill::from_percent(60),
+				..limits
+			}),
+			Error::<Test>::CommissionLimitsLoosened,
+		);
+		assert_noop!(
+			Staking::set_commission_limits(Origin::signed(10), CommissionLimits {
+				max_commission: Perbill::from_percent(5),
+				..limits
+			}),
+			Error::<Test>::CommissionTooHigh,
+		);
+
+		// a new validator sets its commission right away.
+		let _ = Balances::make_free_balance_be(&81, 1000);
+		assert_ok!(Staking::bond(Origin::signed(81), 80, 1000, RewardDestination::Controller));
+		assert_ok!(Staking::validate(Origin::signed(80), prefs(40)));
+		assert_eq!(Staking::validators(&81), prefs(40));
+		assert!(Staking::pending_commission(&81).is_none());
+
+		// the limits are removed with the stash.
+		assert_ok!(Staking::set_commission_limits(Origin::signed(80), limits));
+		assert_ok!(Staking::chill(Origin::signed(80)));
+		assert_ok!(Staking::unbond(Origin::signed(80), 1000));
+		mock::start_era(BondingDuration::get() + 1);
+		assert_ok!(Staking::withdraw_unbonded(Origin::signed(80), 0));
+		assert!(!<ValidatorCommissionLimits<Test>>::contains_key(&81));
+	});
+}
+
+#[test]
+fn commission_change_is_delayed() {
+	ExtBuilder::default().build_and_execute(|| {
+		CommissionChangeDelay::set(2);
+		<Payee<Test>>::insert(&11, RewardDestination::Controller);
+		<Payee<Test>>::insert(&101, RewardDestination::Controller);
+
+		let commission = Perbill::from_percent(40);
+		assert_ok!(Staking::validate(Origin::signed(10), ValidatorPrefs { commission }));
+		assert_eq!(Staking::pending_commission(&11), Some((2, commission)));
+		assert_eq!(Staking::validators(&11).commission, Perbill::zero());
+		assert_eq!(
+			*staking_events().last().unwrap(),
+			RawEvent::CommissionChangeScheduled(11, commission, 2),
+		);
+
+		// the current commission is used until the change applies.
+		mock::start_era(1);
+		assert_eq!(Staking::eras_validator_prefs(1, &11).commission, Perbill::zero());
+		assert!(Staking::pending_commission(&11).is_some());
+		let total_payout_1 = current_total_payout_for_duration(3000);
+		let exposure_1 = Staking::eras_stakers(1, 11);
+		<Module<Test>>::reward_by_ids(vec![(11, 1)]);
+
+		mock::start_era(2);
+		assert_eq!(Staking::eras_validator_prefs(2, &11).commission, commission);
+		assert_eq!(Staking::validators(&11).commission, commission);
+		assert!(Staking::pending_commission(&11).is_none());
+
+		// the payout of era 1 is not affected by the change.
+		let balance_10 = Balances::total_balance(&10);
+		let balance_100 = Balances::total_balance(&100);
+		mock::make_all_reward_payment(1);
+		let reward_of_100 = total_payout_1 * exposure_1.others[0].value / exposure_1.total;
+		assert_eq_error_rate!(Balances::total_balance(&100), balance_100 + reward_of_100, 2);
+		let reward_of_10 = total_payout_1 * exposure_1.own / exposure_1.total;
+		assert_eq_error_rate!(Balances::total_balance(&10), balance_10 + reward_of_10, 2);
+
+		// but the payout of era 2 is.
+		let total_payout_2 = current_total_payout_for_duration(3000);
+		let exposure_2 = Staking::eras_stakers(2, 11);
+		<Module<Test>>::reward_by_ids(vec![(11, 1)]);
+		mock::start_era(3);
+		let balance_100 = Balances::total_balance(&100);
+		mock::make_all_reward_payment(2);
+		let shared_cut = total_payout_2 - commission * total_payout_2;
+		let reward_of_100 = shared_cut * exposure_2.others[0].value / exposure_2.total;
+		assert_eq_error_rate!(Balances::total_balance(&100), balance_100 + reward_of_100, 2);
+
+		// chilling drops a pending change.
+		assert_ok!(Staking::validate(Origin::signed(10), ValidatorPrefs::default()));
+		assert!(Staking::pending_commission(&11).is_some());
+		assert_ok!(Staking::chill(Origin::signed(10)));
+		assert!(Staking::pending_commission(&11).is_none());
+	});
+}
+
+#[test]
+fn commission_change_after_chill_is_delayed() {
+	ExtBuilder::default().build_and_execute(|| {
+		CommissionChangeDelay::set(2);
+		assert_ok!(Staking::chill(Origin::signed(10)));
+		assert_eq!(Staking::last_commission(&11), Some(Perbill::zero()));
+
+		// the stash validates again long after it chilled, the change is still delayed.
+		mock::start_era(2);
+		let commission = Perbill::from_percent(40);
+		assert_ok!(Staking::validate(Origin::signed(10), ValidatorPrefs { commission }));
+		assert_eq!(Staking::validators(&11).commission, Perbill::zero());
+		assert_eq!(Staking::pending_commission(&11), Some((4, commission)));
+		assert!(Staking::last_commission(&11).is_none());
+
+		// switching to nominating keeps the commission as well.
+		assert_ok!(Staking::nominate(Origin::signed(10), vec![21]));
+		assert_eq!(Staking::last_commission(&11), Some(Perbill::zero()));
+		assert!(Staking::pending_commission(&11).is_none());
+	});
+}
@@ -67,6 +67,8 @@ pub trait WeightInfo {
 	fn reap_stash(_s: u32, ) -> Weight;
 	fn new_era(_v: u32, _n: u32, ) -> Weight;
 	fn submit_solution_better(_v: u32, _n: u32, _a: u32, _w: u32, ) -> Weight;
+	fn set_min_commission() -> Weight;
+	fn set_commission_limits() -> Weight;
 
 }
 
@@ -106,9 +108,9 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
 	}
 	fn validate() -> Weight {
-		(25_691_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(2 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+		(34_816_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 
 	}
 	fn nominate(n: u32, ) -> Weight {
@@ -235,6 +237,17 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 
 	}
+	fn set_min_commission() -> Weight {
+		(3_412_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+
+	}
+	fn set_commission_limits() -> Weight {
+		(28_902_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+
+	}
 
 }
 
@@ -273,9 +286,9 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
 	}
 	fn validate() -> Weight {
-		(25_691_000 as Weight)
-			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+		(34_816_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 
 	}
 	fn nominate(n: u32, ) -> Weight {
@@ -402,5 +415,16 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 
 	}
+	fn set_min_commission() -> Weight {
+		(3_412_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+
+	}
+	fn set_commission_limits() -> Weight {
+		(28_902_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+
+	}
 
 }
@@ -300,6 +300,13 @@ impl<T> Parameter for T where T: Codec + EncodeLike + Clone + Eq + fmt::Debug {}
 ///   * `fn on_finalize(n: BlockNumber) -> frame_support::weights::Weight` or
 ///   * `fn on_finalize() -> frame_support::weights::Weight`
 ///
+/// * `on_idle`: Executes at the end of a block, after all extrinsics were applied and before
+/// `on_finalize`, with the weight left unused in the block. Using this function will
+/// implement the [`OnIdle`](./traits/trait.OnIdle.html) trait.
+/// Function signature must be
+/// `fn on_idle(n: BlockNumber, remaining_weight: Weight) -> frame_support::weights::Weight`
+/// and it must return the weight it consumed, which should not exceed `remaining_weight`.
+///
 /// * `offchain_worker`: Executes at the beginning of a block and produces extrinsics for a future block
 ///   upon completion. Using this function will implement the
 ///   [`OffchainWorker`](./traits/trait.OffchainWorker.html) trait.
@@ -334,6 +341,7 @@ macro_rules! decl_module {
 			{}
 			{}
 			{}
+			{}
 			[]
 			$($t)*
 		);
@@ -369,6 +377,7 @@ macro_rules! decl_module {
 			{}
 			{}
 			{}
+			{}
 			[]
 			$($t)*
 		);
@@ -384,6 +393,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -402,6 +412,7 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_runtime_upgrade )* }
 			{ $( $on_finalize )* }
+			{ $( $on_idle )* }
 			{ $( $offchain )* }
 			{ $( $constants )* }
 			{ $( $error_type )* }
@@ -419,6 +430,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -444,6 +456,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -465,6 +478,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{}
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -485,6 +499,7 @@ macro_rules! decl_module {
 			{
 				fn on_finalize( $( $param_name : $param ),* ) { $( $impl )* }
 			}
+			{ $( $on_idle )* }
 			{ $( $offchain )* }
 			{ $( $constants )* }
 			{ $( $error_type )* }
@@ -503,6 +518,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{}
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -530,6 +546,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )+ }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -554,6 +571,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{}
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -579,6 +597,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{}
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -606,6 +625,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{}
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -626,6 +646,7 @@ macro_rules! decl_module {
 				fn on_runtime_upgrade( $( $param_name : $param ),* ) -> $return { $( $impl )* }
 			}
 			{ $( $on_finalize )* }
+			{ $( $on_idle )* }
 			{ $( $offchain )* }
 			{ $( $constants )* }
 			{ $( $error_type )* }
@@ -646,6 +667,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )+ }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -669,6 +691,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -687,6 +710,7 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_runtime_upgrade )* }
 			{ $( $on_finalize )* }
+			{ $( $on_idle )* }
 			{ $( $offchain )* }
 			{ $( $constants )* }
 			{ $( $error_type )* }
@@ -710,6 +734,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -733,6 +758,7 @@ macro_rules! decl_module {
 		{}
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -758,6 +784,7 @@ macro_rules! decl_module {
 		{}
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -773,6 +800,96 @@ macro_rules! decl_module {
 			by the function directly."
 		);
 	};
+	// Add on_idle
+	(@normalize
+		$(#[$attr:meta])*
+		pub struct $mod_type:ident<
+			$trait_instance:ident: $trait_name:ident$(<I>, I: $instantiable:path $(= $module_default_instance:path)?)?
+		>
+		for enum $call_type:ident where origin: $origin_type:ty, system = $system:ident
+		{ $( $other_where_bounds:tt )* }
+		{ $( $deposit_event:tt )* }
+		{ $( $on_initialize:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $on_finalize:tt )* }
+		{}
+		{ $( $offchain:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
+		{ $( $integrity_test:tt )* }
+		[ $( $dispatchables:tt )* ]
+		$(#[doc = $doc_attr:tt])*
+		fn on_idle( $( $param_name:ident : $param:ty ),* $(,)? ) -> $return:ty { $( $impl:tt )* }
+		$($rest:tt)*
+	) => {
+		$crate::decl_module!(@normalize
+			$(#[$attr])*
+			pub struct $mod_type<$trait_instance: $trait_name$(<I>, I: $instantiable $(= $module_default_instance)?)?>
+			for enum $call_type where origin: $origin_type, system = $system
+			{ $( $other_where_bounds )* }
+			{ $( $deposit_event )* }
+			{ $( $on_initialize )* }
+			{ $( $on_runtime_upgrade )* }
+			{ $( $on_finalize )* }
+			{
+				fn on_idle( $( $param_name : $param ),* ) -> $return { $( $impl )* }
+			}
+			{ $( $offchain )* }
+			{ $( $constants )* }
+			{ $( $error_type )* }
+			{ $( $integrity_test)* }
+			[ $( $dispatchables )* ]
+			$($rest)*
+		);
+	};
+	// Compile error on trying to add a second `on_idle`.
+	(@normalize
+		$(#[$attr:meta])*
+		pub struct $mod_type:ident<
+			$trait_instance:ident: $trait_name:ident$(<I>, I: $instantiable:path $(= $module_default_instance:path)?)?
+		>
+		for enum $call_type:ident where origin: $origin_type:ty, system = $system:ident
+		{ $( $other_where_bounds:tt )* }
+		{ $( $deposit_event:tt )* }
+		{ $( $on_initialize:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )+ }
+		{ $( $offchain:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
+		{ $( $integrity_test:tt )* }
+		[ $( $dispatchables:tt )* ]
+		$(#[doc = $doc_attr:tt])*
+		fn on_idle( $( $param_name:ident : $param:ty ),* $(,)? ) -> $return:ty { $( $impl:tt )* }
+		$($rest:tt)*
+	) => {
+		compile_error!("`on_idle` can only be passed once as input.");
+	};
+	// compile_error on_idle, without a given weight removed syntax.
+	(@normalize
+		$(#[$attr:meta])*
+		pub struct $mod_type:ident<
+			$trait_instance:ident: $trait_name:ident$(<I>, I: $instantiable:path $(= $module_default_instance:path)?)?
+		>
+		for enum $call_type:ident where origin: $origin_type:ty, system = $system:ident
+		{ $( $other_where_bounds:tt )* }
+		{ $( $deposit_event:tt )* }
+		{ $( $on_initialize:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
+		{ $( $offchain:tt )* }
+		{ $( $constants:tt )* }
+		{ $( $error_type:tt )* }
+		{ $( $integrity_test:tt )* }
+		[ $( $dispatchables:tt )* ]
+		$(#[doc = $doc_attr:tt])*
+		fn on_idle( $( $param_name:ident : $param:ty ),* $(,)? ) { $( $impl:tt )* }
+		$($rest:tt)*
+	) => {
+		compile_error!("`on_idle` must return the consumed Weight.");
+	};
 	// Add on_initialize
 	(@normalize
 		$(#[$attr:meta])*
@@ -785,6 +902,7 @@ macro_rules! decl_module {
 		{}
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -805,6 +923,7 @@ macro_rules! decl_module {
 			}
 			{ $( $on_runtime_upgrade )* }
 			{ $( $on_finalize )* }
+			{ $( $on_idle )* }
 			{ $( $offchain )* }
 			{ $( $constants )* }
 			{ $( $error_type )* }
@@ -825,6 +944,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )+ }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -848,6 +968,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -868,6 +989,7 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_runtime_upgrade )* }
 			{ $( $on_finalize )* }
+			{ $( $on_idle )* }
 			{ fn offchain_worker( $( $param_name : $param ),* ) { $( $impl )* } }
 			{ $( $constants )* }
 			{ $( $error_type )* }
@@ -888,6 +1010,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )+ }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -912,6 +1035,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -933,6 +1057,7 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_runtime_upgrade )* }
 			{ $( $on_finalize )* }
+			{ $( $on_idle )* }
 			{ $( $offchain )* }
 			{
 				$( $constants )*
@@ -959,6 +1084,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ }
@@ -979,6 +1105,7 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_runtime_upgrade )* }
 			{ $( $on_finalize )* }
+			{ $( $on_idle )* }
 			{ $( $offchain )* }
 			{ $( $constants )* }
 			{ $error_type }
@@ -1000,6 +1127,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ }
@@ -1018,6 +1146,7 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_runtime_upgrade )* }
 			{ $( $on_finalize )* }
+			{ $( $on_idle )* }
 			{ $( $offchain )* }
 			{ $( $constants )* }
 			{ &'static str }
@@ -1040,6 +1169,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $error_type:ty }
@@ -1064,6 +1194,7 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_runtime_upgrade )* }
 			{ $( $on_finalize )* }
+			{ $( $on_idle )* }
 			{ $( $offchain )* }
 			{ $( $constants )* }
 			{ $error_type }
@@ -1094,6 +1225,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -1122,6 +1254,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -1150,6 +1283,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -1178,6 +1312,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -1207,6 +1342,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $( $error_type:tt )* }
@@ -1224,6 +1360,7 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_runtime_upgrade )* }
 			{ $( $on_finalize )* }
+			{ $( $on_idle )* }
 			{ $( $offchain )* }
 			{ $( $constants )* }
 			{ $( $error_type )* }
@@ -1434,6 +1571,36 @@ macro_rules! decl_module {
 		}
 	};
 
+	(@impl_on_idle
+		{ $system:ident }
+		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
+		{ $( $other_where_bounds:tt )* }
+		fn on_idle(
+			$param:ident : $param_ty:ty, $weight:ident : $weight_ty:ty
+		) -> $return:ty { $( $impl:tt )* }
+	) => {
+		impl<$trait_instance: $system::Config + $trait_name$(<I>, $instance: $instantiable)?>
+			$crate::traits::OnIdle<<$trait_instance as $system::Config>::BlockNumber>
+			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
+		{
+			fn on_idle($param: $param_ty, $weight: $weight_ty) -> $return {
+				$crate::sp_tracing::enter_span!($crate::sp_tracing::trace_span!("on_idle"));
+				{ $( $impl )* }
+			}
+		}
+	};
+
+	(@impl_on_idle
+		{ $system:ident }
+		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
+		{ $( $other_where_bounds:tt )* }
+	) => {
+		impl<$trait_instance: $system::Config + $trait_name$(<I>, $instance: $instantiable)?>
+			$crate::traits::OnIdle<<$trait_instance as $system::Config>::BlockNumber>
+			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
+		{}
+	};
+
 	(@impl_offchain
 		{ $system:ident }
 		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
@@ -1650,6 +1817,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_runtime_upgrade:tt )* }
 		{ $( $on_finalize:tt )* }
+		{ $( $on_idle:tt )* }
 		{ $( $offchain:tt )* }
 		{ $( $constants:tt )* }
 		{ $error_type:ty }
@@ -1690,6 +1858,14 @@ macro_rules! decl_module {
 			$( $on_finalize )*
 		}
 
+		$crate::decl_module! {
+			@impl_on_idle
+			{ $system }
+			$mod_type<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?>;
+			{ $( $other_where_bounds )* }
+			$( $on_idle )*
+		}
+
 		$crate::decl_module! {
 			@impl_offchain
 			{ $system }
@@ -2367,6 +2543,9 @@ macro_rules! __check_reserved_fn_name {
 	(on_finalize $( $rest:ident )*) => {
 		$crate::__check_reserved_fn_name!(@compile_error on_finalize);
 	};
+	(on_idle $( $rest:ident )*) => {
+		$crate::__check_reserved_fn_name!(@compile_error on_idle);
+	};
 	(offchain_worker $( $rest:ident )*) => {
 		$crate::__check_reserved_fn_name!(@compile_error offchain_worker);
 	};
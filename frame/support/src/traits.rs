@@ -314,27 +314,36 @@ pub trait StoredMap<K, T> {
 	/// deletion of them all.
 	fn is_explicit(k: &K) -> bool;
 	/// Mutate the item.
-	fn mutate<R>(k: &K, f: impl FnOnce(&mut T) -> R) -> R;
+	///
+	/// Fails, without mutating anything, if the item cannot be created.
+	fn mutate<R>(k: &K, f: impl FnOnce(&mut T) -> R) -> Result<R, DispatchError>;
 	/// Mutate the item, removing or resetting to default value if it has been mutated to `None`.
-	fn mutate_exists<R>(k: &K, f: impl FnOnce(&mut Option<T>) -> R) -> R;
+	///
+	/// Fails, without mutating anything, if the item cannot be created or removed.
+	fn mutate_exists<R>(k: &K, f: impl FnOnce(&mut Option<T>) -> R) -> Result<R, DispatchError>;
 	/// Maybe mutate the item only if an `Ok` value is returned from `f`. Do nothing if an `Err` is
-	/// returned. It is removed or reset to default value if it has been mutated to `None`
-	fn try_mutate_exists<R, E>(k: &K, f: impl FnOnce(&mut Option<T>) -> Result<R, E>) -> Result<R, E>;
+	/// returned. It is removed or reset to default value if it has been mutated to `None`.
+	///
+	/// Also does nothing, and returns the error, if the item cannot be created or removed.
+	fn try_mutate_exists<R, E: From<DispatchError>>(
+		k: &K,
+		f: impl FnOnce(&mut Option<T>) -> Result<R, E>,
+	) -> Result<R, E>;
 	/// Set the item to something new.
-	fn insert(k: &K, t: T) { Self::mutate(k, |i| *i = t); }
+	fn insert(k: &K, t: T) -> Result<(), DispatchError> { Self::mutate(k, |i| *i = t) }
 	/// Remove the item or otherwise replace it with its default value; we don't care which.
-	fn remove(k: &K);
+	fn remove(k: &K) -> Result<(), DispatchError>;
 }
 
-/// A simple, generic one-parameter event notifier/handler.
-pub trait Happened<T> {
-	/// The thing happened.
-	fn happened(t: &T);
+/// A handler for the creation and the removal of an item, which may refuse either of them.
+pub trait HandleLifetime<T> {
+	/// The item is about to be created.
+	fn created(_t: &T) -> Result<(), DispatchError> { Ok(()) }
+	/// The item is about to be removed.
+	fn killed(_t: &T) -> Result<(), DispatchError> { Ok(()) }
 }
 
-impl<T> Happened<T> for () {
-	fn happened(_: &T) {}
-}
+impl<T> HandleLifetime<T> for () {}
 
 /// A shim for placing around a storage item in order to use it as a `StoredValue`. Ideally this
 /// wouldn't be needed as `StorageValue`s should blanket implement `StoredValue`s, however this
@@ -342,11 +351,12 @@ impl<T> Happened<T> for () {
 /// implement it directly in the macro.
 ///
 /// This form has the advantage that two additional types are provides, `Created` and `Removed`,
-/// which are both generic events that can be tied to handlers to do something in the case of being
-/// about to create an account where one didn't previously exist (at all; not just where it used to
-/// be the default value), or where the account is being removed or reset back to the default value
-/// where previously it did exist (though may have been in a default state). This works well with
-/// system module's `CallOnCreatedAccount` and `CallKillAccount`.
+/// which are both generic handlers that can do something in the case of being about to create an
+/// account where one didn't previously exist (at all; not just where it used to be the default
+/// value), or where the account is being removed or reset back to the default value where
+/// previously it did exist (though may have been in a default state). If the handler fails, the
+/// item is left unchanged. This works well with system module's `CallOnCreatedAccount` and
+/// `CallKillAccount`.
 pub struct StorageMapShim<
 	S,
 	Created,
@@ -356,59 +366,50 @@ pub struct StorageMapShim<
 >(sp_std::marker::PhantomData<(S, Created, Removed, K, T)>);
 impl<
 	S: StorageMap<K, T, Query=T>,
-	Created: Happened<K>,
-	Removed: Happened<K>,
+	Created: HandleLifetime<K>,
+	Removed: HandleLifetime<K>,
 	K: FullCodec,
 	T: FullCodec,
 > StoredMap<K, T> for StorageMapShim<S, Created, Removed, K, T> {
 	fn get(k: &K) -> T { S::get(k) }
 	fn is_explicit(k: &K) -> bool { S::contains_key(k) }
-	fn insert(k: &K, t: T) {
-		let existed = S::contains_key(&k);
-		S::insert(k, t);
-		if !existed {
-			Created::happened(k);
+	fn insert(k: &K, t: T) -> Result<(), DispatchError> {
+		if !S::contains_key(&k) {
+			Created::created(k)?;
 		}
+		S::insert(k, t);
+		Ok(())
 	}
-	fn remove(k: &K) {
-		let existed = S::contains_key(&k);
-		S::remove(k);
-		if existed {
-			Removed::happened(&k);
+	fn remove(k: &K) -> Result<(), DispatchError> {
+		if S::contains_key(&k) {
+			Removed::killed(k)?;
+			S::remove(k);
 		}
+		Ok(())
 	}
-	fn mutate<R>(k: &K, f: impl FnOnce(&mut T) -> R) -> R {
-		let existed = S::contains_key(&k);
-		let r = S::mutate(k, f);
-		if !existed {
-			Created::happened(k);
+	fn mutate<R>(k: &K, f: impl FnOnce(&mut T) -> R) -> Result<R, DispatchError> {
+		if !S::contains_key(&k) {
+			Created::created(k)?;
 		}
-		r
+		Ok(S::mutate(k, f))
 	}
-	fn mutate_exists<R>(k: &K, f: impl FnOnce(&mut Option<T>) -> R) -> R {
-		let (existed, exists, r) = S::mutate_exists(k, |maybe_value| {
-			let existed = maybe_value.is_some();
-			let r = f(maybe_value);
-			(existed, maybe_value.is_some(), r)
-		});
-		if !existed && exists {
-			Created::happened(k);
-		} else if existed && !exists {
-			Removed::happened(k);
-		}
-		r
+	fn mutate_exists<R>(k: &K, f: impl FnOnce(&mut Option<T>) -> R) -> Result<R, DispatchError> {
+		Self::try_mutate_exists(k, |maybe_value| -> Result<R, DispatchError> { Ok(f(maybe_value)) })
 	}
-	fn try_mutate_exists<R, E>(k: &K, f: impl FnOnce(&mut Option<T>) -> Result<R, E>) -> Result<R, E> {
+	fn try_mutate_exists<R, E: From<DispatchError>>(
+		k: &K,
+		f: impl FnOnce(&mut Option<T>) -> Result<R, E>,
+	) -> Result<R, E> {
 		S::try_mutate_exists(k, |maybe_value| {
 			let existed = maybe_value.is_some();
-			f(maybe_value).map(|v| (existed, maybe_value.is_some(), v))
-		}).map(|(existed, exists, v)| {
+			let r = f(maybe_value)?;
+			let exists = maybe_value.is_some();
 			if !existed && exists {
-				Created::happened(k);
+				Created::created(k)?;
 			} else if existed && !exists {
-				Removed::happened(k);
+				Removed::killed(k)?;
 			}
-			v
+			Ok(r)
 		})
 	}
 }
@@ -533,6 +534,34 @@ pub trait OnKilledAccount<AccountId> {
 	fn on_killed_account(who: &AccountId);
 }
 
+/// Something which holds state on behalf of accounts and can move it over to another account, as
+/// is done when a lost account has been recovered by a new one.
+///
+/// Implementations should move any funds they hold reserved or locked for the account together
+/// with the state they belong to, so that the state stays consistent with the balances.
+pub trait MigrateAccount<AccountId> {
+	/// The weight that `migrate_account` would consume to move the state of `from`.
+	fn migration_weight(from: &AccountId) -> crate::weights::Weight;
+
+	/// Move the state held on behalf of `from` over to `to`, returning the weight consumed.
+	fn migrate_account(from: &AccountId, to: &AccountId) -> crate::weights::Weight;
+}
+
+#[impl_for_tuples(30)]
+impl<AccountId> MigrateAccount<AccountId> for Tuple {
+	fn migration_weight(from: &AccountId) -> crate::weights::Weight {
+		let mut weight: crate::weights::Weight = 0;
+		for_tuples!( #( weight = weight.saturating_add(Tuple::migration_weight(from)); )* );
+		weight
+	}
+
+	fn migrate_account(from: &AccountId, to: &AccountId) -> crate::weights::Weight {
+		let mut weight: crate::weights::Weight = 0;
+		for_tuples!( #( weight = weight.saturating_add(Tuple::migrate_account(from, to)); )* );
+		weight
+	}
+}
+
 /// A trait for finding the author of a block header based on the `PreRuntime` digests contained
 /// within it.
 pub trait FindAuthor<Author> {
@@ -1104,10 +1133,136 @@ pub trait ReservableCurrency<AccountId>: Currency<AccountId> {
 	) -> result::Result<Self::Balance, DispatchError>;
 }
 
+/// A currency whose reserved balance can be split by identifier, so that the funds reserved by one
+/// subsystem can not be unreserved or slashed by another.
+///
+/// The named reserves are part of the reserved balance of [`ReservableCurrency`], but the anonymous
+/// functions which unreserve, slash or move reserved funds only act on the reserved balance held
+/// outside of any named reserve.
+pub trait NamedReservableCurrency<AccountId>: ReservableCurrency<AccountId> {
+	/// An identifier for a reserve. Used for disambiguating different reserves so that
+	/// they can be individually unreserved or slashed.
+	type ReserveIdentifier;
+
+	/// Deducts up to `value` from the reserved balance of `who` named `id`. This function cannot
+	/// fail.
+	///
+	/// As much funds up to `value` will be deducted as possible. If the named reserve of `who`
+	/// is less than `value`, then a non-zero second item will be returned.
+	fn slash_reserved_named(
+		id: &Self::ReserveIdentifier,
+		who: &AccountId,
+		value: Self::Balance
+	) -> (Self::NegativeImbalance, Self::Balance);
+
+	/// The amount of the balance of a given account that is reserved under `id`.
+	fn reserved_balance_named(id: &Self::ReserveIdentifier, who: &AccountId) -> Self::Balance;
+
+	/// Moves `value` from balance to the reserved balance named `id`.
+	///
+	/// If the free balance is lower than `value`, or the account has too many named reserves,
+	/// then no funds will be moved and an `Err` will be returned.
+	fn reserve_named(
+		id: &Self::ReserveIdentifier,
+		who: &AccountId,
+		value: Self::Balance
+	) -> DispatchResult;
+
+	/// Moves up to `value` from the reserved balance named `id` to free balance. This function
+	/// cannot fail.
+	///
+	/// As much funds up to `value` will be moved as possible. If the named reserve of `who`
+	/// is less than `value`, then the remaining amount will be returned.
+	fn unreserve_named(
+		id: &Self::ReserveIdentifier,
+		who: &AccountId,
+		value: Self::Balance
+	) -> Self::Balance;
+
+	/// Moves up to `value` from the reserved balance named `id` of account `slashed` to balance of
+	/// account `beneficiary`. `beneficiary` must exist for this to succeed. If it does not, `Err`
+	/// will be returned. Funds will be placed in either the `free` balance or the reserved balance
+	/// named `id` of `beneficiary`, depending on the `status`.
+	///
+	/// As much funds up to `value` will be deducted as possible. If this is less than `value`,
+	/// then `Ok(non_zero)` will be returned.
+	fn repatriate_reserved_named(
+		id: &Self::ReserveIdentifier,
+		slashed: &AccountId,
+		beneficiary: &AccountId,
+		value: Self::Balance,
+		status: BalanceStatus,
+	) -> result::Result<Self::Balance, DispatchError>;
+
+	/// Moves up to `value` of the reserved balance of `who` which is not held in any named reserve
+	/// into the reserve named `id`, without touching the free balance. This is meant for migrating
+	/// funds which were reserved before named reserves were used.
+	///
+	/// As much funds up to `value` will be moved as possible. If this is less than `value`, then
+	/// `Ok(non_zero)` will be returned.
+	fn name_reserved(
+		id: &Self::ReserveIdentifier,
+		who: &AccountId,
+		value: Self::Balance,
+	) -> result::Result<Self::Balance, DispatchError>;
+
+	/// Ensure the reserved balance of `who` named `id` is equal to `value`, reserving or
+	/// unreserving the difference.
+	fn ensure_reserved_named(
+		id: &Self::ReserveIdentifier,
+		who: &AccountId,
+		value: Self::Balance,
+	) -> DispatchResult {
+		let current = Self::reserved_balance_named(id, who);
+		if current > value {
+			// we always have enough balance to unreserve here
+			Self::unreserve_named(id, who, current - value);
+			Ok(())
+		} else if value > current {
+			// we checked value > current
+			Self::reserve_named(id, who, value - current)
+		} else { // current == value
+			Ok(())
+		}
+	}
+
+	/// Unreserve all the reserved balance of `who` named `id`, returning the unreserved amount.
+	fn unreserve_all_named(id: &Self::ReserveIdentifier, who: &AccountId) -> Self::Balance {
+		let value = Self::reserved_balance_named(id, who);
+		let remaining = Self::unreserve_named(id, who, value);
+		value.saturating_sub(remaining)
+	}
+
+	/// Slash all the reserved balance of `who` named `id`.
+	fn slash_all_reserved_named(
+		id: &Self::ReserveIdentifier,
+		who: &AccountId,
+	) -> Self::NegativeImbalance {
+		let value = Self::reserved_balance_named(id, who);
+		Self::slash_reserved_named(id, who, value).0
+	}
+
+	/// Move all the reserved balance of `slashed` named `id` to balance of account `beneficiary`,
+	/// according to `status`. `beneficiary` must exist for this to succeed.
+	fn repatriate_all_reserved_named(
+		id: &Self::ReserveIdentifier,
+		slashed: &AccountId,
+		beneficiary: &AccountId,
+		status: BalanceStatus,
+	) -> DispatchResult {
+		let value = Self::reserved_balance_named(id, slashed);
+		Self::repatriate_reserved_named(id, slashed, beneficiary, value, status).map(|_| ())
+	}
+}
+
 /// An identifier for a lock. Used for disambiguating different locks so that
 /// they can be individually replaced or removed.
 pub type LockIdentifier = [u8; 8];
 
+/// An identifier for a named reserve, as used by the pallets which reserve funds through
+/// [`NamedReservableCurrency`].
+pub type ReserveIdentifier = [u8; 8];
+
 /// A currency whose accounts can have liquidity restrictions.
 pub trait LockableCurrency<AccountId>: Currency<AccountId> {
 	/// The quantity used to denote time; usually just a `BlockNumber`.
@@ -1457,6 +1612,48 @@ impl<BlockNumber: Clone> OnInitialize<BlockNumber> for Tuple {
 	}
 }
 
+/// The block idle trait.
+///
+/// Implementing this lets you express what should happen for your pallet when the block has
+/// spare weight left after all extrinsics were applied. Useful for lazy, deferrable work such
+/// as cleaning up stale storage.
+pub trait OnIdle<BlockNumber> {
+	/// The block is being finalized and there is `remaining_weight` left in it.
+	///
+	/// Return the weight consumed, which should not exceed `remaining_weight`.
+	///
+	/// NOTE: This function is called AFTER ALL extrinsics in a block are applied and BEFORE
+	/// `on_finalize`.
+	fn on_idle(
+		_n: BlockNumber,
+		_remaining_weight: crate::weights::Weight,
+	) -> crate::weights::Weight {
+		0
+	}
+}
+
+/// The tuple implementation gives every element the weight the previous ones left over. The
+/// element that goes first rotates with the block number, so that a single greedy pallet can
+/// not starve the others of idle weight forever.
+#[impl_for_tuples(30)]
+impl<BlockNumber: Copy + AtLeast32BitUnsigned> OnIdle<BlockNumber> for Tuple {
+	fn on_idle(n: BlockNumber, remaining_weight: Weight) -> Weight {
+		let on_idle_functions: &[fn(BlockNumber, Weight) -> Weight] =
+			&[for_tuples!( #( Tuple::on_idle ),* )];
+		let len = on_idle_functions.len();
+		if len == 0 {
+			return 0
+		}
+		let start_index = (n % (len as u32).into()).saturated_into::<u32>() as usize;
+		let mut weight: Weight = 0;
+		for on_idle in on_idle_functions.iter().cycle().skip(start_index).take(len) {
+			let adjusted_remaining_weight = remaining_weight.saturating_sub(weight);
+			weight = weight.saturating_add(on_idle(n, adjusted_remaining_weight));
+		}
+		weight
+	}
+}
+
 /// A trait that will be called at genesis.
 ///
 /// Implementing this trait for a pallet let's you express operations that should
@@ -1628,6 +1825,132 @@ pub mod schedule {
 		/// Will return an error if the `id` is invalid.
 		fn next_dispatch_time(id: Vec<u8>) -> Result<BlockNumber, ()>;
 	}
+
+	/// A call that is either stored inline or referred to by the hash of its preimage.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub enum MaybeHashed<T, Hash> {
+		/// The value itself.
+		Value(T),
+		/// The hash of the encoded value which this value represents.
+		Hash(Hash),
+	}
+
+	impl<T, H> From<T> for MaybeHashed<T, H> {
+		fn from(t: T) -> Self {
+			MaybeHashed::Value(t)
+		}
+	}
+
+	/// Error type for `MaybeHashed::lookup`.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+	pub enum LookupError {
+		/// A call of this hash was not known.
+		Unknown,
+		/// The preimage for this hash was known but could not be decoded into a `Call`.
+		BadFormat,
+	}
+
+	impl<T: Decode, H> MaybeHashed<T, H> {
+		/// Returns the value if it is stored inline.
+		pub fn as_value(&self) -> Option<&T> {
+			match &self {
+				Self::Value(c) => Some(c),
+				Self::Hash(_) => None,
+			}
+		}
+
+		/// Returns the hash if the value is referred to by its preimage.
+		pub fn as_hash(&self) -> Option<&H> {
+			match &self {
+				Self::Value(_) => None,
+				Self::Hash(h) => Some(h),
+			}
+		}
+
+		/// Request the preimage of the value from `P` if it is referred to by hash.
+		pub fn ensure_requested<P: PreimageProvider<H>>(&self) {
+			if let Self::Hash(hash) = self {
+				P::request_preimage(hash)
+			}
+		}
+
+		/// Drop the request for the preimage of the value from `P` if it is referred to by hash.
+		pub fn ensure_unrequested<P: PreimageProvider<H>>(&self) {
+			if let Self::Hash(hash) = self {
+				P::unrequest_preimage(hash)
+			}
+		}
+
+		/// Look up the preimage of the value in `P` and decode it.
+		///
+		/// Returns the decoded value together with the hash that was resolved, if any. Values
+		/// that are stored inline are returned unchanged.
+		pub fn resolved<P: PreimageProvider<H>>(self) -> Result<(Self, Option<H>), LookupError> {
+			match self {
+				Self::Value(c) => Ok((Self::Value(c), None)),
+				Self::Hash(h) => {
+					let data = P::get_preimage(&h).ok_or(LookupError::Unknown)?;
+					let value = T::decode(&mut &data[..]).map_err(|_| LookupError::BadFormat)?;
+					Ok((Self::Value(value), Some(h)))
+				}
+			}
+		}
+	}
+}
+
+/// A interface for looking up preimages from their hash on chain.
+pub trait PreimageProvider<Hash> {
+	/// Returns whether a preimage exists for a given hash.
+	///
+	/// A value of `true` implies that `get_preimage` is `Some`.
+	fn have_preimage(hash: &Hash) -> bool;
+
+	/// Returns the preimage for a given hash.
+	fn get_preimage(hash: &Hash) -> Option<Vec<u8>>;
+
+	/// Returns whether a preimage request exists for a given hash.
+	fn preimage_requested(hash: &Hash) -> bool;
+
+	/// Request that someone report a preimage. Providers use this to optimise the economics for
+	/// preimage reporting.
+	fn request_preimage(hash: &Hash);
+
+	/// Cancel a previous preimage request.
+	fn unrequest_preimage(hash: &Hash);
+}
+
+impl<Hash> PreimageProvider<Hash> for () {
+	fn have_preimage(_: &Hash) -> bool { false }
+	fn get_preimage(_: &Hash) -> Option<Vec<u8>> { None }
+	fn preimage_requested(_: &Hash) -> bool { false }
+	fn request_preimage(_: &Hash) {}
+	fn unrequest_preimage(_: &Hash) {}
+}
+
+/// A interface for managing preimages to hashes on chain.
+///
+/// Note that this API does not assume any underlying user is calling, and thus
+/// does not handle any preimage ownership or fees. Other system level logic that
+/// uses this API should implement that on their own side.
+pub trait PreimageRecipient<Hash>: PreimageProvider<Hash> {
+	/// Maximum size of a preimage.
+	type MaxSize: Get<u32>;
+
+	/// Store the bytes of a preimage on chain.
+	///
+	/// Preimages larger than `MaxSize` or ones that are already stored are ignored.
+	fn note_preimage(bytes: Vec<u8>);
+
+	/// Clear a previously noted preimage. This is infallible and should be treated more like a
+	/// hint - if it was not previously noted or if it is now requested, then this will not do
+	/// anything.
+	fn unnote_preimage(hash: &Hash);
+}
+
+impl<Hash> PreimageRecipient<Hash> for () {
+	type MaxSize = ();
+	fn note_preimage(_: Vec<u8>) {}
+	fn unnote_preimage(_: &Hash) {}
 }
 
 /// Some sort of check on the origin is performed by this object.
@@ -2000,6 +2323,56 @@ mod tests {
 		assert_eq!(<(Test, Test)>::on_runtime_upgrade(), 40);
 	}
 
+	#[test]
+	fn on_idle_round_robin_works() {
+		thread_local! {
+			static ON_IDLE_INVOCATION_ORDER: RefCell<Vec<&'static str>> = RefCell::new(Vec::new());
+		}
+
+		struct Test1;
+		struct Test2;
+		struct Test3;
+		impl OnIdle<u32> for Test1 {
+			fn on_idle(_n: u32, _remaining_weight: Weight) -> Weight {
+				ON_IDLE_INVOCATION_ORDER.with(|o| o.borrow_mut().push("Test1"));
+				0
+			}
+		}
+		impl OnIdle<u32> for Test2 {
+			fn on_idle(_n: u32, _remaining_weight: Weight) -> Weight {
+				ON_IDLE_INVOCATION_ORDER.with(|o| o.borrow_mut().push("Test2"));
+				0
+			}
+		}
+		impl OnIdle<u32> for Test3 {
+			fn on_idle(_n: u32, remaining_weight: Weight) -> Weight {
+				ON_IDLE_INVOCATION_ORDER.with(|o| o.borrow_mut().push("Test3"));
+				remaining_weight.min(5)
+			}
+		}
+
+		type TestTuple = (Test1, Test2, Test3);
+		assert_eq!(<TestTuple as OnIdle<u32>>::on_idle(0, 10), 5);
+		assert_eq!(
+			ON_IDLE_INVOCATION_ORDER.with(|o| o.borrow().clone()),
+			vec!["Test1", "Test2", "Test3"],
+		);
+		ON_IDLE_INVOCATION_ORDER.with(|o| o.borrow_mut().clear());
+
+		assert_eq!(<TestTuple as OnIdle<u32>>::on_idle(1, 10), 5);
+		assert_eq!(
+			ON_IDLE_INVOCATION_ORDER.with(|o| o.borrow().clone()),
+			vec!["Test2", "Test3", "Test1"],
+		);
+		ON_IDLE_INVOCATION_ORDER.with(|o| o.borrow_mut().clear());
+
+		assert_eq!(<TestTuple as OnIdle<u32>>::on_idle(5, 3), 3);
+		assert_eq!(
+			ON_IDLE_INVOCATION_ORDER.with(|o| o.borrow().clone()),
+			vec!["Test3", "Test1", "Test2"],
+		);
+	}
+
 	#[test]
 	fn check_pallet_version_ordering() {
 		let version = PalletVersion::new(1, 0, 0);
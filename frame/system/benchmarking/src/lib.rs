@@ -142,7 +142,9 @@ benchmarks! {
 		let caller: T::AccountId = whitelisted_caller();
 		let account_info = AccountInfo::<T::Index, T::AccountData> {
 			nonce: 1337u32.into(),
-			refcount: 0,
+			consumers: 0,
+			providers: 0,
+			sufficients: 0,
 			data: T::AccountData::default()
 		};
 		frame_system::Account::<T>::insert(&caller, account_info);
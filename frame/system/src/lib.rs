@@ -33,6 +33,12 @@
 //! It also handles low-level tasks like depositing logs, basic set up and take down of
 //! temporary storage entries, and access to previous block hashes.
 //!
+//! Accounts are reference counted. *Providers* (e.g. a native balance) allow an account to exist,
+//! *sufficients* allow it to exist for the purposes of a single pallet only (e.g. an asset balance)
+//! and *consumers* (e.g. session keys or balance locks) depend on its existence. An account is
+//! reaped once it has neither providers nor sufficients, and its last provider may not be removed
+//! while it still has consumers.
+//!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
@@ -97,7 +103,6 @@ use serde::Serialize;
 use sp_std::prelude::*;
 #[cfg(any(feature = "std", test))]
 use sp_std::map;
-use sp_std::convert::Infallible;
 use sp_std::marker::PhantomData;
 use sp_std::fmt::Debug;
 use sp_version::RuntimeVersion;
@@ -117,7 +122,7 @@ use frame_support::{
 	decl_module, decl_event, decl_storage, decl_error, Parameter, ensure, debug,
 	storage,
 	traits::{
-		Contains, Get, PalletInfo, OnNewAccount, OnKilledAccount, IsDeadAccount, Happened,
+		Contains, Get, PalletInfo, OnNewAccount, OnKilledAccount, IsDeadAccount, HandleLifetime,
 		StoredMap, EnsureOrigin, OriginTrait, Filter,
 	},
 	weights::{
@@ -344,7 +349,13 @@ pub struct AccountInfo<Index, AccountData> {
 	pub nonce: Index,
 	/// The number of other modules that currently depend on this account's existence. The account
 	/// cannot be reaped until this is zero.
-	pub refcount: RefCount,
+	pub consumers: RefCount,
+	/// The number of other modules that allow this account to exist. The account may not be reaped
+	/// until this and `sufficients` are both zero.
+	pub providers: RefCount,
+	/// The number of modules that allow this account to exist for their own purposes only. The
+	/// account may not be reaped until this and `providers` are both zero.
+	pub sufficients: RefCount,
 	/// The additional data that belongs to this account. Used to store the balance(s) in a lot of
 	/// chains.
 	pub data: AccountData,
@@ -440,6 +451,10 @@ decl_storage! {
 		/// True if we have upgraded so that `type RefCount` is `u32`. False (default) if not.
 		UpgradedToU32RefCount build(|_| true): bool;
 
+		/// True if we have upgraded so that `AccountInfo` contains three types of `RefCount`. False
+		/// (default) if not.
+		UpgradedToTripleRefCount build(|_| true): bool;
+
 		/// The execution phase of the block.
 		ExecutionPhase: Option<Phase>;
 	}
@@ -497,6 +512,11 @@ decl_error! {
 		NonDefaultComposite,
 		/// There is a non-zero reference count preventing the account from being purged.
 		NonZeroRefCount,
+		/// The account has no provider references, so nothing else may depend on it.
+		NoProviders,
+		/// The last provider reference of the account cannot be removed while there are still
+		/// consumer references.
+		ConsumerRemaining,
 	}
 }
 
@@ -516,9 +536,31 @@ decl_module! {
 		fn on_runtime_upgrade() -> frame_support::weights::Weight {
 			if !UpgradedToU32RefCount::get() {
 				Account::<T>::translate::<(T::Index, u8, T::AccountData), _>(|_key, (nonce, rc, data)|
-					Some(AccountInfo { nonce, refcount: rc as RefCount, data })
+					Some(AccountInfo {
+						nonce,
+						consumers: rc as RefCount,
+						providers: 1,
+						sufficients: 0,
+						data,
+					})
 				);
 				UpgradedToU32RefCount::put(true);
+				UpgradedToTripleRefCount::put(true);
+				T::BlockWeights::get().max_block
+			} else if !UpgradedToTripleRefCount::get() {
+				// Every account that exists at this point is kept alive by its data (usually the
+				// native balance), so it gets a single provider. The old refcount was only ever
+				// incremented by modules depending on the account, i.e. consumers.
+				Account::<T>::translate::<(T::Index, RefCount, T::AccountData), _>(
+					|_key, (nonce, rc, data)| Some(AccountInfo {
+						nonce,
+						consumers: rc,
+						providers: 1,
+						sufficients: 0,
+						data,
+					})
+				);
+				UpgradedToTripleRefCount::put(true);
 				T::BlockWeights::get().max_block
 			} else {
 				0
@@ -698,7 +740,10 @@ decl_module! {
 		pub fn suicide(origin) {
 			let who = ensure_signed(origin)?;
 			let account = Account::<T>::get(&who);
-			ensure!(account.refcount == 0, Error::<T>::NonZeroRefCount);
+			ensure!(
+				account.consumers == 0 && account.sufficients == 0,
+				Error::<T>::NonZeroRefCount,
+			);
 			ensure!(account.data == T::AccountData::default(), Error::<T>::NonDefaultComposite);
 			Self::kill_account(&who);
 		}
@@ -882,6 +927,24 @@ pub enum RefStatus {
 	Unreferenced,
 }
 
+/// Some resultant status relevant to incrementing a provider/self-sufficient reference.
+#[derive(Eq, PartialEq, RuntimeDebug)]
+pub enum IncRefStatus {
+	/// Account was created.
+	Created,
+	/// Account already existed.
+	Existed,
+}
+
+/// Some resultant status relevant to decrementing a provider/self-sufficient reference.
+#[derive(Eq, PartialEq, RuntimeDebug)]
+pub enum DecRefStatus {
+	/// Account was destroyed.
+	Reaped,
+	/// Account still exists.
+	Exists,
+}
+
 impl<T: Config> Module<T> {
 	/// Deposits an event into this block's event record.
 	pub fn deposit_event(event: impl Into<T::Event>) {
@@ -893,24 +956,182 @@ impl<T: Config> Module<T> {
 	}
 
 	/// Increment the reference counter on an account.
+	#[deprecated = "Use `inc_consumers` instead"]
 	pub fn inc_ref(who: &T::AccountId) {
-		Account::<T>::mutate(who, |a| a.refcount = a.refcount.saturating_add(1));
+		let _ = Self::inc_consumers(who);
 	}
 
 	/// Decrement the reference counter on an account. This *MUST* only be done once for every time
-	/// you called `inc_ref` on `who`.
+	/// you called `inc_consumers` on `who`.
+	#[deprecated = "Use `dec_consumers` instead"]
 	pub fn dec_ref(who: &T::AccountId) {
-		Account::<T>::mutate(who, |a| a.refcount = a.refcount.saturating_sub(1));
+		Self::dec_consumers(who);
 	}
 
 	/// The number of outstanding references for the account `who`.
+	#[deprecated = "Use `consumers` instead"]
 	pub fn refs(who: &T::AccountId) -> RefCount {
-		Account::<T>::get(who).refcount
+		Self::consumers(who)
 	}
 
 	/// True if the account has no outstanding references.
+	#[deprecated = "Use `!is_provider_required` instead"]
 	pub fn allow_death(who: &T::AccountId) -> bool {
-		Account::<T>::get(who).refcount == 0
+		!Self::is_provider_required(who)
+	}
+
+	/// Increment the provider reference counter on an account.
+	pub fn inc_providers(who: &T::AccountId) -> IncRefStatus {
+		Account::<T>::mutate(who, |a| if a.providers == 0 && a.sufficients == 0 {
+			// Account is being created.
+			a.providers = 1;
+			Self::on_created_account(who.clone());
+			IncRefStatus::Created
+		} else {
+			a.providers = a.providers.saturating_add(1);
+			IncRefStatus::Existed
+		})
+	}
+
+	/// Decrement the provider reference counter on an account.
+	///
+	/// This *MUST* only be done once for every time you called `inc_providers` on `who`. It fails
+	/// with `ConsumerRemaining` if this is the last provider and there are still consumers.
+	pub fn dec_providers(who: &T::AccountId) -> Result<DecRefStatus, DispatchError> {
+		Account::<T>::try_mutate_exists(who, |maybe_account| {
+			if let Some(mut account) = maybe_account.take() {
+				if account.providers == 0 {
+					// Logic error - cannot decrement beyond zero.
+					debug::error!(
+						target: "system",
+						"Logic error: Unexpected underflow in reducing provider"
+					);
+					account.providers = 1;
+				}
+				match (account.providers, account.consumers, account.sufficients) {
+					(1, 0, 0) => {
+						// No providers left (and no consumers) and no sufficients. Account dead.
+						Module::<T>::on_killed_account(who.clone());
+						Ok(DecRefStatus::Reaped)
+					}
+					(1, c, _) if c > 0 => {
+						// Cannot remove last provider if there are consumers.
+						Err(Error::<T>::ConsumerRemaining.into())
+					}
+					(x, _, _) => {
+						// Account will continue to exist as there is either > 1 provider or
+						// > 0 sufficients.
+						account.providers = x - 1;
+						*maybe_account = Some(account);
+						Ok(DecRefStatus::Exists)
+					}
+				}
+			} else {
+				debug::error!(
+					target: "system",
+					"Logic error: Account already dead when reducing provider"
+				);
+				Ok(DecRefStatus::Reaped)
+			}
+		})
+	}
+
+	/// Increment the self-sufficient reference counter on an account.
+	pub fn inc_sufficients(who: &T::AccountId) -> IncRefStatus {
+		Account::<T>::mutate(who, |a| if a.providers + a.sufficients == 0 {
+			// Account is being created.
+			a.sufficients = 1;
+			Self::on_created_account(who.clone());
+			IncRefStatus::Created
+		} else {
+			a.sufficients = a.sufficients.saturating_add(1);
+			IncRefStatus::Existed
+		})
+	}
+
+	/// Decrement the sufficients reference counter on an account.
+	///
+	/// This *MUST* only be done once for every time you called `inc_sufficients` on `who`.
+	pub fn dec_sufficients(who: &T::AccountId) -> DecRefStatus {
+		Account::<T>::mutate_exists(who, |maybe_account| {
+			if let Some(mut account) = maybe_account.take() {
+				if account.sufficients == 0 {
+					// Logic error - cannot decrement beyond zero.
+					debug::error!(
+						target: "system",
+						"Logic error: Unexpected underflow in reducing sufficients"
+					);
+				}
+				match (account.sufficients, account.providers) {
+					(0, 0) | (1, 0) => {
+						Module::<T>::on_killed_account(who.clone());
+						DecRefStatus::Reaped
+					}
+					(x, _) => {
+						account.sufficients = x.saturating_sub(1);
+						*maybe_account = Some(account);
+						DecRefStatus::Exists
+					}
+				}
+			} else {
+				debug::error!(
+					target: "system",
+					"Logic error: Account already dead when reducing sufficients"
+				);
+				DecRefStatus::Reaped
+			}
+		})
+	}
+
+	/// The number of outstanding provider references for the account `who`.
+	pub fn providers(who: &T::AccountId) -> RefCount {
+		Account::<T>::get(who).providers
+	}
+
+	/// The number of outstanding sufficient references for the account `who`.
+	pub fn sufficients(who: &T::AccountId) -> RefCount {
+		Account::<T>::get(who).sufficients
+	}
+
+	/// Increment the reference counter on an account.
+	///
+	/// The account `who`'s `providers` must be non-zero or this will return an error.
+	pub fn inc_consumers(who: &T::AccountId) -> Result<(), DispatchError> {
+		Account::<T>::try_mutate(who, |a| if a.providers > 0 {
+			a.consumers = a.consumers.saturating_add(1);
+			Ok(())
+		} else {
+			Err(Error::<T>::NoProviders.into())
+		})
+	}
+
+	/// Decrement the reference counter on an account. This *MUST* only be done once for every time
+	/// you called `inc_consumers` on `who`.
+	pub fn dec_consumers(who: &T::AccountId) {
+		Account::<T>::mutate(who, |a| if a.consumers > 0 {
+			a.consumers -= 1;
+		} else {
+			debug::error!(
+				target: "system",
+				"Logic error: Unexpected underflow in reducing consumer"
+			);
+		})
+	}
+
+	/// The number of outstanding consumer references for the account `who`.
+	pub fn consumers(who: &T::AccountId) -> RefCount {
+		Account::<T>::get(who).consumers
+	}
+
+	/// True if the account has some outstanding consumer references.
+	pub fn is_provider_required(who: &T::AccountId) -> bool {
+		Account::<T>::get(who).consumers != 0
+	}
+
+	/// True if the account has no outstanding consumer references or more than one provider.
+	pub fn can_dec_provider(who: &T::AccountId) -> bool {
+		let a = Account::<T>::get(who);
+		a.consumers == 0 || a.providers > 1
 	}
 
 	/// Deposits an event into this block's event record adding this event
@@ -1206,7 +1427,7 @@ impl<T: Config> Module<T> {
 	fn kill_account(who: &T::AccountId) {
 		if Account::<T>::contains_key(who) {
 			let account = Account::<T>::take(who);
-			if account.refcount > 0 {
+			if account.consumers > 0 {
 				debug::debug!(
 					target: "system",
 					"WARNING: Referenced account deleted. This is probably a bug."
@@ -1239,19 +1460,22 @@ impl<T: Config> Module<T> {
 	}
 }
 
-/// Event handler which calls on_created_account when it happens.
+/// Lifetime handler which registers a provider reference for the account when it is created.
 pub struct CallOnCreatedAccount<T>(PhantomData<T>);
-impl<T: Config> Happened<T::AccountId> for CallOnCreatedAccount<T> {
-	fn happened(who: &T::AccountId) {
-		Module::<T>::on_created_account(who.clone());
+impl<T: Config> HandleLifetime<T::AccountId> for CallOnCreatedAccount<T> {
+	fn created(who: &T::AccountId) -> Result<(), DispatchError> {
+		Module::<T>::inc_providers(who);
+		Ok(())
 	}
 }
 
-/// Event handler which calls kill_account when it happens.
+/// Lifetime handler which drops the provider reference of the account when it is removed, killing
+/// it if this was the last one. Fails with `ConsumerRemaining` if this was the last provider while
+/// consumers still depend on the account.
 pub struct CallKillAccount<T>(PhantomData<T>);
-impl<T: Config> Happened<T::AccountId> for CallKillAccount<T> {
-	fn happened(who: &T::AccountId) {
-		Module::<T>::kill_account(who)
+impl<T: Config> HandleLifetime<T::AccountId> for CallKillAccount<T> {
+	fn killed(who: &T::AccountId) -> Result<(), DispatchError> {
+		Module::<T>::dec_providers(who).map(|_| ())
 	}
 }
 
@@ -1264,8 +1488,9 @@ impl<T: Config> BlockNumberProvider for Module<T>
 	}
 }
 
-// Implement StoredMap for a simple single-item, kill-account-on-remove system. This works fine for
-// storing a single item which is required to not be empty/default for the account to exist.
+// Implement StoredMap for a simple single-item, provider-on-existence system. This works fine for
+// storing a single item which is required to not be empty/default for the account to exist: while
+// it is non-default, it holds a provider reference on the account.
 // Anything more complex will need more sophisticated logic.
 impl<T: Config> StoredMap<T::AccountId, T::AccountData> for Module<T> {
 	fn get(k: &T::AccountId) -> T::AccountData {
@@ -1274,49 +1499,51 @@ impl<T: Config> StoredMap<T::AccountId, T::AccountData> for Module<T> {
 	fn is_explicit(k: &T::AccountId) -> bool {
 		Account::<T>::contains_key(k)
 	}
-	fn insert(k: &T::AccountId, data: T::AccountData) {
-		let existed = Account::<T>::contains_key(k);
-		Account::<T>::mutate(k, |a| a.data = data);
-		if !existed {
-			Self::on_created_account(k.clone());
-		}
-	}
-	fn remove(k: &T::AccountId) {
-		Self::kill_account(k)
+	fn insert(k: &T::AccountId, data: T::AccountData) -> Result<(), DispatchError> {
+		Self::mutate(k, |a| *a = data)
+	}
+	fn remove(k: &T::AccountId) -> Result<(), DispatchError> {
+		Self::mutate_exists(k, |a| *a = None)
+	}
+	fn mutate<R>(
+		k: &T::AccountId,
+		f: impl FnOnce(&mut T::AccountData) -> R,
+	) -> Result<R, DispatchError> {
+		Self::mutate_exists(k, |maybe_data| {
+			let mut data = maybe_data.take().unwrap_or_default();
+			let r = f(&mut data);
+			*maybe_data = Some(data);
+			r
+		})
 	}
-	fn mutate<R>(k: &T::AccountId, f: impl FnOnce(&mut T::AccountData) -> R) -> R {
-		let existed = Account::<T>::contains_key(k);
-		let r = Account::<T>::mutate(k, |a| f(&mut a.data));
-		if !existed {
-			Self::on_created_account(k.clone());
+	fn mutate_exists<R>(
+		k: &T::AccountId,
+		f: impl FnOnce(&mut Option<T::AccountData>) -> R,
+	) -> Result<R, DispatchError> {
+		Self::try_mutate_exists(k, |x| -> Result<R, DispatchError> { Ok(f(x)) })
+	}
+	fn try_mutate_exists<R, E: From<DispatchError>>(
+		k: &T::AccountId,
+		f: impl FnOnce(&mut Option<T::AccountData>) -> Result<R, E>,
+	) -> Result<R, E> {
+		let account = Account::<T>::get(k);
+		let was_providing = account.data != T::AccountData::default();
+		let mut some_data = if was_providing { Some(account.data) } else { None };
+		let result = f(&mut some_data)?;
+		let data = some_data.unwrap_or_default();
+		let is_providing = data != T::AccountData::default();
+		match (was_providing, is_providing) {
+			(false, false) => return Ok(result),
+			(true, false) => if let DecRefStatus::Reaped = Self::dec_providers(k)? {
+				return Ok(result)
+			},
+			_ => {},
 		}
-		r
-	}
-	fn mutate_exists<R>(k: &T::AccountId, f: impl FnOnce(&mut Option<T::AccountData>) -> R) -> R {
-		Self::try_mutate_exists(k, |x| -> Result<R, Infallible> { Ok(f(x)) }).expect("Infallible; qed")
-	}
-	fn try_mutate_exists<R, E>(k: &T::AccountId, f: impl FnOnce(&mut Option<T::AccountData>) -> Result<R, E>) -> Result<R, E> {
-		Account::<T>::try_mutate_exists(k, |maybe_value| {
-			let existed = maybe_value.is_some();
-			let (maybe_prefix, mut maybe_data) = split_inner(
-				maybe_value.take(),
-				|account| ((account.nonce, account.refcount), account.data)
-			);
-			f(&mut maybe_data).map(|result| {
-				*maybe_value = maybe_data.map(|data| {
-					let (nonce, refcount) = maybe_prefix.unwrap_or_default();
-					AccountInfo { nonce, refcount, data }
-				});
-				(existed, maybe_value.is_some(), result)
-			})
-		}).map(|(existed, exists, v)| {
-			if !existed && exists {
-				Self::on_created_account(k.clone());
-			} else if existed && !exists {
-				Self::on_killed_account(k.clone());
-			}
-			v
-		})
+		Account::<T>::mutate(k, |a| a.data = data);
+		if !was_providing && is_providing {
+			Self::inc_providers(k);
+		}
+		Ok(result)
 	}
 }
 
@@ -19,7 +19,10 @@ use crate::*;
 use mock::{*, Origin};
 use sp_core::H256;
 use sp_runtime::DispatchError;
-use frame_support::weights::WithPostDispatchInfo;
+use frame_support::{
+	assert_noop, assert_ok, StorageMap, StorageValue,
+	weights::WithPostDispatchInfo, traits::OnRuntimeUpgrade,
+};
 
 #[test]
 fn origin_works() {
@@ -31,17 +34,18 @@ fn origin_works() {
 #[test]
 fn stored_map_works() {
 	new_test_ext().execute_with(|| {
-		System::insert(&0, 42);
-		assert!(System::allow_death(&0));
+		assert_ok!(System::insert(&0, 42));
+		assert!(!System::is_provider_required(&0));
+		assert_eq!(System::providers(&0), 1);
 
-		System::inc_ref(&0);
-		assert!(!System::allow_death(&0));
+		assert_ok!(System::inc_consumers(&0));
+		assert!(System::is_provider_required(&0));
 
-		System::insert(&0, 69);
-		assert!(!System::allow_death(&0));
+		assert_ok!(System::insert(&0, 69));
+		assert!(System::is_provider_required(&0));
 
-		System::dec_ref(&0);
-		assert!(System::allow_death(&0));
+		System::dec_consumers(&0);
+		assert!(!System::is_provider_required(&0));
 
 		assert!(KILLED.with(|r| r.borrow().is_empty()));
 		System::kill_account(&0);
@@ -49,6 +53,106 @@ fn stored_map_works() {
 	});
 }
 
+#[test]
+fn stored_map_removal_drops_provider() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(System::insert(&0, 42));
+		assert_eq!(System::inc_providers(&0), IncRefStatus::Existed);
+
+		assert_ok!(System::remove(&0));
+		assert!(System::account_exists(&0));
+		assert_eq!(System::providers(&0), 1);
+		assert!(KILLED.with(|r| r.borrow().is_empty()));
+
+		assert_eq!(System::dec_providers(&0), Ok(DecRefStatus::Reaped));
+		assert!(!System::account_exists(&0));
+		assert_eq!(KILLED.with(|r| r.borrow().clone()), vec![0u64]);
+	});
+}
+
+#[test]
+fn stored_map_removal_fails_with_consumers_remaining() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(System::insert(&0, 42));
+		assert_ok!(System::inc_consumers(&0));
+
+		assert_noop!(System::remove(&0), Error::<Test>::ConsumerRemaining);
+		assert_noop!(
+			System::try_mutate_exists(&0, |a| -> Result<(), DispatchError> { *a = None; Ok(()) }),
+			Error::<Test>::ConsumerRemaining,
+		);
+		assert_eq!(Account::<Test>::get(&0).data, 42);
+		assert_eq!(System::providers(&0), 1);
+		assert!(KILLED.with(|r| r.borrow().is_empty()));
+
+		System::dec_consumers(&0);
+		assert_ok!(System::remove(&0));
+		assert!(!System::account_exists(&0));
+		assert_eq!(KILLED.with(|r| r.borrow().clone()), vec![0u64]);
+	});
+}
+
+#[test]
+fn provider_required_to_support_consumer() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(System::inc_consumers(&0), Error::<Test>::NoProviders);
+
+		assert_eq!(System::inc_providers(&0), IncRefStatus::Created);
+		System::inc_account_nonce(&0);
+		assert_eq!(System::account_nonce(&0), 1);
+
+		assert_eq!(System::inc_providers(&0), IncRefStatus::Existed);
+		assert_eq!(System::dec_providers(&0), Ok(DecRefStatus::Exists));
+		assert_eq!(System::account_nonce(&0), 1);
+
+		assert_ok!(System::inc_consumers(&0));
+		assert_noop!(System::dec_providers(&0), Error::<Test>::ConsumerRemaining);
+
+		System::dec_consumers(&0);
+		assert_eq!(System::dec_providers(&0), Ok(DecRefStatus::Reaped));
+		assert_eq!(System::account_nonce(&0), 0);
+	});
+}
+
+#[test]
+fn sufficient_reference_keeps_account_alive() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(System::inc_sufficients(&0), IncRefStatus::Created);
+		assert_eq!(System::inc_providers(&0), IncRefStatus::Existed);
+		assert_noop!(System::inc_consumers(&1), Error::<Test>::NoProviders);
+
+		assert_eq!(System::dec_providers(&0), Ok(DecRefStatus::Exists));
+		assert!(System::account_exists(&0));
+		assert_eq!(System::sufficients(&0), 1);
+
+		assert_eq!(System::dec_sufficients(&0), DecRefStatus::Reaped);
+		assert!(!System::account_exists(&0));
+		assert_eq!(KILLED.with(|r| r.borrow().clone()), vec![0u64]);
+	});
+}
+
+#[test]
+fn refcount_is_upgraded_to_triple_ref_count() {
+	new_test_ext().execute_with(|| {
+		UpgradedToTripleRefCount::put(false);
+		sp_io::storage::set(
+			&Account::<Test>::hashed_key_for(&1),
+			&(3u64, 2 as RefCount, 42u32).encode(),
+		);
+
+		System::on_runtime_upgrade();
+
+		assert!(UpgradedToTripleRefCount::get());
+		assert_eq!(Account::<Test>::get(&1), AccountInfo {
+			nonce: 3,
+			consumers: 2,
+			providers: 1,
+			sufficients: 0,
+			data: 42,
+		});
+	});
+}
+
 #[test]
 fn deposit_event_should_work() {
 	new_test_ext().execute_with(|| {
@@ -69,6 +69,32 @@ benchmarks! {
 	verify {
 		assert_last_event::<T>(Event::BatchCompleted.into())
 	}
+
+	force_batch {
+		let c in 0 .. 1000;
+		let mut calls: Vec<<T as Config>::Call> = Vec::new();
+		for i in 0 .. c {
+			let call = frame_system::Call::remark(vec![]).into();
+			calls.push(call);
+		}
+		let caller = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), calls)
+	verify {
+		assert_last_event::<T>(Event::BatchCompleted.into())
+	}
+
+	dispatch_as {
+		let caller = account("caller", SEED, SEED);
+		let call = Box::new(frame_system::Call::remark(vec![]).into());
+		let origin: T::PalletsOrigin = RawOrigin::Signed(caller).into();
+	}: _(RawOrigin::Root, Box::new(origin), call)
+	verify {
+		assert_last_event::<T>(Event::DispatchedAs(Ok(())).into())
+	}
+
+	with_weight {
+		let call = Box::new(frame_system::Call::remark(vec![]).into());
+	}: _(RawOrigin::Root, call, 0)
 }
 
 #[cfg(test)]
@@ -83,6 +109,9 @@ mod tests {
 			assert_ok!(test_benchmark_batch::<Test>());
 			assert_ok!(test_benchmark_as_derivative::<Test>());
 			assert_ok!(test_benchmark_batch_all::<Test>());
+			assert_ok!(test_benchmark_force_batch::<Test>());
+			assert_ok!(test_benchmark_dispatch_as::<Test>());
+			assert_ok!(test_benchmark_with_weight::<Test>());
 		});
 	}
 }
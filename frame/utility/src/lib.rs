@@ -23,7 +23,7 @@
 //!
 //! ## Overview
 //!
-//! This module contains two basic pieces of functionality:
+//! This module contains three basic pieces of functionality:
 //! - Batch dispatch: A stateless operation, allowing any origin to execute multiple calls in a
 //!   single dispatch. This can be useful to amalgamate proposals, combining `set_code` with
 //!   corresponding `set_storage`s, for efficient multiple payouts with just a single signature
@@ -35,6 +35,8 @@
 //!   it's perfectly fine to have each of them controlled by the same underlying keypair.
 //!   Derivative accounts are, for the purposes of proxy filtering considered exactly the same as
 //!   the oigin and are thus hampered with the origin's filters.
+//! - Privileged dispatch: Operations only available to the _Root_ origin, allowing governance to
+//!   dispatch a call from any origin, or with a declared weight overriding the weight of the call.
 //!
 //! Since proxy filters are respected in all dispatches of this module, it should never need to be
 //! filtered by any proxy.
@@ -45,10 +47,16 @@
 //!
 //! #### For batch dispatch
 //! * `batch` - Dispatch multiple calls from the sender's origin.
+//! * `force_batch` - Dispatch multiple calls from the sender's origin, continuing after any
+//!   failed call.
 //!
 //! #### For pseudonymal dispatch
 //! * `as_derivative` - Dispatch a call from a derivative signed origin.
 //!
+//! #### For privileged dispatch
+//! * `dispatch_as` - Dispatch a call from a given origin, as _Root_.
+//! * `with_weight` - Dispatch a call with a declared weight overriding its own, as _Root_.
+//!
 //! [`Call`]: ./enum.Call.html
 //! [`Config`]: ./trait.Config.html
 
@@ -65,12 +73,13 @@ use sp_core::TypeId;
 use sp_io::hashing::blake2_256;
 use frame_support::{decl_module, decl_event, decl_storage, Parameter, transactional};
 use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
 	traits::{OriginTrait, UnfilteredDispatchable, Get},
 	weights::{Weight, GetDispatchInfo, DispatchClass, extract_actual_weight},
 	dispatch::{PostDispatchInfo, DispatchResultWithPostInfo},
 };
 use frame_system::{ensure_signed, ensure_root};
-use sp_runtime::{DispatchError, traits::Dispatchable};
+use sp_runtime::{DispatchError, DispatchResult, traits::Dispatchable};
 pub use weights::WeightInfo;
 
 /// Configuration trait.
@@ -83,6 +92,10 @@ pub trait Config: frame_system::Config {
 		+ GetDispatchInfo + From<frame_system::Call<Self>>
 		+ UnfilteredDispatchable<Origin=Self::Origin>;
 
+	/// The caller origin, overarching type of all pallets origins.
+	type PalletsOrigin: Parameter + Into<<Self as frame_system::Config>::Origin>
+		+ From<frame_system::RawOrigin<Self::AccountId>>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
@@ -99,6 +112,16 @@ decl_event! {
 		BatchInterrupted(u32, DispatchError),
 		/// Batch of dispatches completed fully with no error.
 		BatchCompleted,
+		/// Batch of dispatches completed but has errors.
+		BatchCompletedWithErrors,
+		/// A single item within a batch of dispatches has completed with no error, using the
+		/// given weight. \[index, weight\]
+		ItemCompleted(u32, Weight),
+		/// A single item within a batch of dispatches has failed with the given error, using the
+		/// given weight. \[index, error, weight\]
+		ItemFailed(u32, DispatchError, Weight),
+		/// A call was dispatched from a given origin. \[result\]
+		DispatchedAs(DispatchResult),
 	}
 }
 
@@ -271,6 +294,147 @@ decl_module! {
 			let base_weight = T::WeightInfo::batch_all(calls_len as u32);
 			Ok(Some(base_weight + weight).into())
 		}
+
+		/// Send a batch of dispatch calls, attempting every one of them regardless of whether
+		/// the previous ones failed.
+		///
+		/// Each call is dispatched in its own storage transaction, so a failing call leaves no
+		/// changes behind while the changes of the successful calls are kept.
+		///
+		/// May be called from any origin.
+		///
+		/// - `calls`: The calls to be dispatched from the same origin.
+		///
+		/// If origin is root then call are dispatch without checking origin filter. (This includes
+		/// bypassing `frame_system::Config::BaseCallFilter`).
+		///
+		/// # <weight>
+		/// - Complexity: O(C) where C is the number of calls to be batched.
+		/// # </weight>
+		///
+		/// This will return `Ok` in all circumstances. For every call, either an `ItemCompleted`
+		/// or an `ItemFailed` event is deposited with the index of the call and its actual weight.
+		/// Finally `BatchCompleted` is deposited if all calls were successful, and
+		/// `BatchCompletedWithErrors` otherwise.
+		#[weight = (
+			calls.iter()
+				.map(|call| call.get_dispatch_info().weight)
+				.fold(0, |total: Weight, weight: Weight| total.saturating_add(weight))
+				.saturating_add(T::WeightInfo::force_batch(calls.len() as u32)),
+			{
+				let all_operational = calls.iter()
+					.map(|call| call.get_dispatch_info().class)
+					.all(|class| class == DispatchClass::Operational);
+				if all_operational {
+					DispatchClass::Operational
+				} else {
+					DispatchClass::Normal
+				}
+			},
+		)]
+		fn force_batch(origin, calls: Vec<<T as Config>::Call>) -> DispatchResultWithPostInfo {
+			let is_root = ensure_root(origin.clone()).is_ok();
+			let calls_len = calls.len();
+			// Track the actual weight of each of the batch calls.
+			let mut weight: Weight = 0;
+			let mut has_error = false;
+			for (index, call) in calls.into_iter().enumerate() {
+				let info = call.get_dispatch_info();
+				// Dispatch each call in its own layer, so a failure only reverts its own changes.
+				let result = with_transaction(|| {
+					// If origin is root, bypass any dispatch filter; root can call anything.
+					let result = if is_root {
+						call.dispatch_bypass_filter(origin.clone())
+					} else {
+						call.dispatch(origin.clone())
+					};
+					if result.is_ok() {
+						TransactionOutcome::Commit(result)
+					} else {
+						TransactionOutcome::Rollback(result)
+					}
+				});
+				// Add the weight of this call.
+				let call_weight = extract_actual_weight(&result, &info);
+				weight = weight.saturating_add(call_weight);
+				match result {
+					Ok(_) => Self::deposit_event(Event::ItemCompleted(index as u32, call_weight)),
+					Err(e) => {
+						has_error = true;
+						Self::deposit_event(Event::ItemFailed(index as u32, e.error, call_weight));
+					},
+				}
+			}
+			if has_error {
+				Self::deposit_event(Event::BatchCompletedWithErrors);
+			} else {
+				Self::deposit_event(Event::BatchCompleted);
+			}
+			let base_weight = T::WeightInfo::force_batch(calls_len as u32);
+			Ok(Some(base_weight + weight).into())
+		}
+
+		/// Dispatches a function call with a provided origin.
+		///
+		/// The call is dispatched with the filters of the provided origin, so e.g. a _Signed_
+		/// origin is still subject to `frame_system::Config::BaseCallFilter`.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		///
+		/// - `as_origin`: The origin to dispatch the call from.
+		/// - `call`: The call to be dispatched.
+		///
+		/// The result of the call is deposited in a `DispatchedAs` event.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - The weight of the `call` + a constant overhead.
+		/// # </weight>
+		#[weight = {
+			let dispatch_info = call.get_dispatch_info();
+			(
+				T::WeightInfo::dispatch_as()
+					.saturating_add(dispatch_info.weight),
+				dispatch_info.class,
+			)
+		}]
+		fn dispatch_as(origin,
+			as_origin: Box<T::PalletsOrigin>,
+			call: Box<<T as Config>::Call>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let info = call.get_dispatch_info();
+			let result = call.dispatch((*as_origin).into());
+			// Always take into account the base weight of this call.
+			let weight = T::WeightInfo::dispatch_as()
+				.saturating_add(extract_actual_weight(&result, &info));
+			Self::deposit_event(Event::DispatchedAs(result.map(|_| ()).map_err(|e| e.error)));
+			Ok(Some(weight).into())
+		}
+
+		/// Dispatch a function call as _Root_, declaring the given weight instead of the weight
+		/// of the call.
+		///
+		/// This is meant for emergencies, when the declared weight of a call is wrong and would
+		/// prevent it from being dispatched, e.g. because it does not fit into a block.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		///
+		/// - `call`: The call to be dispatched.
+		/// - `weight`: The weight to declare for `call`.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - The given `weight` + a constant overhead.
+		/// # </weight>
+		#[weight = (
+			T::WeightInfo::with_weight().saturating_add(*weight),
+			call.get_dispatch_info().class,
+		)]
+		fn with_weight(origin, call: Box<<T as Config>::Call>, weight: Weight) {
+			ensure_root(origin.clone())?;
+			call.dispatch(origin).map(|_| ()).map_err(|e| e.error)?;
+		}
 	}
 }
 
@@ -63,6 +63,12 @@ pub mod example {
 					Ok(end_weight.into())
 				}
 			}
+
+			#[weight = 0]
+			fn write_and_fail(_origin) {
+				storage::unhashed::put(b"write_and_fail", &true);
+				Err("The cake is a lie.")?
+			}
 		}
 	}
 }
@@ -124,6 +130,9 @@ parameter_types! {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type Balance = u64;
 	type DustRemoval = ();
 	type Event = TestEvent;
@@ -156,6 +165,7 @@ impl Filter<Call> for TestBaseCallFilter {
 impl Config for Test {
 	type Event = TestEvent;
 	type Call = Call;
+	type PalletsOrigin = OriginCaller;
 	type WeightInfo = ();
 }
 type System = frame_system::Module<Test>;
@@ -536,3 +546,163 @@ fn batch_all_handles_weight_refund() {
 		);
 	});
 }
+
+fn utility_events() -> Vec<Event> {
+	frame_system::Module::<Test>::events().into_iter().filter_map(|r| match r.event {
+		TestEvent::utility(e) => Some(e),
+		_ => None,
+	}).collect()
+}
+
+#[test]
+fn force_batch_works() {
+	new_test_ext().execute_with(|| {
+		let call = Call::Balances(BalancesCall::transfer(2, 5));
+		let weight = call.get_dispatch_info().weight;
+		assert_eq!(Balances::free_balance(1), 10);
+		assert_eq!(Balances::free_balance(2), 10);
+		assert_ok!(
+			Utility::force_batch(Origin::signed(1), vec![
+				call.clone(),
+				Call::Balances(BalancesCall::transfer(2, 10)),
+				call,
+			]),
+		);
+		// Every call is attempted
+		assert_eq!(Balances::free_balance(1), 0);
+		assert_eq!(Balances::free_balance(2), 20);
+		assert_eq!(utility_events(), vec![
+			Event::ItemCompleted(0, weight),
+			Event::ItemFailed(1, BalancesError::<Test, _>::InsufficientBalance.into(), weight),
+			Event::ItemCompleted(2, weight),
+			Event::BatchCompletedWithErrors,
+		]);
+	});
+}
+
+#[test]
+fn force_batch_reverts_failed_items() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(
+			Utility::force_batch(Origin::signed(1), vec![
+				Call::Example(ExampleCall::write_and_fail()),
+				Call::Balances(BalancesCall::transfer(2, 5)),
+			]),
+		);
+		// The changes of the failed call are reverted, the others are kept
+		assert_eq!(storage::unhashed::get::<bool>(b"write_and_fail"), None);
+		assert_eq!(Balances::free_balance(2), 15);
+		expect_event(Event::BatchCompletedWithErrors);
+
+		assert_ok!(Utility::force_batch(Origin::signed(1), vec![
+			Call::Balances(BalancesCall::transfer(2, 5)),
+		]));
+		expect_event(Event::BatchCompleted);
+	});
+}
+
+#[test]
+fn force_batch_handles_weight_refund() {
+	new_test_ext().execute_with(|| {
+		let start_weight = 100;
+		let end_weight = 75;
+		let diff = start_weight - end_weight;
+
+		// Refund weight when ok
+		let inner_call = Call::Example(ExampleCall::foobar(false, start_weight, Some(end_weight)));
+		let batch_calls = vec![inner_call; 4];
+		let call = Call::Utility(UtilityCall::force_batch(batch_calls));
+		let info = call.get_dispatch_info();
+		let result = call.dispatch(Origin::signed(1));
+		assert_ok!(result);
+		assert_eq!(extract_actual_weight(&result, &info), info.weight - diff * 4);
+
+		// Refund weight of both the good and the bad calls
+		let good_call = Call::Example(ExampleCall::foobar(false, start_weight, Some(end_weight)));
+		let bad_call = Call::Example(ExampleCall::foobar(true, start_weight, Some(end_weight)));
+		let batch_calls = vec![good_call, bad_call.clone(), bad_call];
+		let call = Call::Utility(UtilityCall::force_batch(batch_calls));
+		let info = call.get_dispatch_info();
+		let result = call.dispatch(Origin::signed(1));
+		assert_ok!(result);
+		expect_event(Event::BatchCompletedWithErrors);
+		assert_eq!(
+			extract_actual_weight(&result, &info),
+			// Real weight is 3 calls at end_weight
+			<Test as Config>::WeightInfo::force_batch(3) + end_weight * 3,
+		);
+	});
+}
+
+#[test]
+fn dispatch_as_works() {
+	new_test_ext().execute_with(|| {
+		let as_origin = Box::new(OriginCaller::system(frame_system::RawOrigin::Signed(1)));
+		let call = Box::new(Call::Balances(BalancesCall::transfer(2, 5)));
+		// Only root can dispatch as another origin
+		assert_noop!(
+			Utility::dispatch_as(Origin::signed(1), as_origin.clone(), call.clone()),
+			DispatchError::BadOrigin,
+		);
+		assert_ok!(Utility::dispatch_as(Origin::root(), as_origin.clone(), call));
+		expect_event(Event::DispatchedAs(Ok(())));
+		assert_eq!(Balances::free_balance(1), 5);
+		assert_eq!(Balances::free_balance(2), 15);
+		// The failure of the call is reported in the event
+		let call = Box::new(Call::Balances(BalancesCall::transfer(2, 10)));
+		assert_ok!(Utility::dispatch_as(Origin::root(), as_origin, call));
+		expect_event(Event::DispatchedAs(
+			Err(BalancesError::<Test, _>::InsufficientBalance.into())
+		));
+	});
+}
+
+#[test]
+fn dispatch_as_filters() {
+	new_test_ext().execute_with(|| {
+		let call = Box::new(Call::System(frame_system::Call::remark(vec![])));
+		assert!(!TestBaseCallFilter::filter(&Call::System(frame_system::Call::suicide())));
+		// A signed origin is filtered
+		let as_origin = Box::new(OriginCaller::system(frame_system::RawOrigin::Signed(1)));
+		assert_ok!(Utility::dispatch_as(
+			Origin::root(),
+			as_origin.clone(),
+			Box::new(Call::System(frame_system::Call::suicide())),
+		));
+		expect_event(Event::DispatchedAs(Err(DispatchError::BadOrigin)));
+		assert_ok!(Utility::dispatch_as(Origin::root(), as_origin, call));
+		expect_event(Event::DispatchedAs(Ok(())));
+		// While root is not
+		let as_origin = Box::new(OriginCaller::system(frame_system::RawOrigin::Root));
+		let call = Box::new(Call::System(frame_system::Call::set_code_without_checks(vec![])));
+		assert!(!TestBaseCallFilter::filter(&call));
+		assert_ok!(Utility::dispatch_as(Origin::root(), as_origin, call));
+		expect_event(Event::DispatchedAs(Ok(())));
+	});
+}
+
+#[test]
+fn with_weight_works() {
+	new_test_ext().execute_with(|| {
+		let upgrade_code_call =
+			Box::new(Call::System(frame_system::Call::set_code_without_checks(vec![])));
+		// Only root can call with a different weight
+		assert_noop!(
+			Utility::with_weight(Origin::signed(1), upgrade_code_call.clone(), 10),
+			DispatchError::BadOrigin,
+		);
+
+		let call = Call::Utility(UtilityCall::with_weight(upgrade_code_call.clone(), 321));
+		let info = call.get_dispatch_info();
+		assert_eq!(info.weight, <Test as Config>::WeightInfo::with_weight() + 321);
+		assert_eq!(info.class, upgrade_code_call.get_dispatch_info().class);
+		assert_ok!(call.dispatch(Origin::root()));
+
+		// Errors of the call are returned
+		let call = Box::new(Call::Balances(BalancesCall::force_transfer(1, 2, 20)));
+		assert_noop!(
+			Utility::with_weight(Origin::root(), call, 10),
+			BalancesError::<Test, _>::InsufficientBalance,
+		);
+	});
+}
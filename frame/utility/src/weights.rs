@@ -46,6 +46,9 @@ pub trait WeightInfo {
 	fn batch(c: u32, ) -> Weight;
 	fn as_derivative() -> Weight;
 	fn batch_all(c: u32, ) -> Weight;
+	fn force_batch(c: u32, ) -> Weight;
+	fn dispatch_as() -> Weight;
+	fn with_weight() -> Weight;
 	
 }
 
@@ -66,6 +69,19 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add((2_738_000 as Weight).saturating_mul(c as Weight))
 			
 	}
+	fn force_batch(c: u32, ) -> Weight {
+		(20_385_000 as Weight)
+			.saturating_add((2_915_000 as Weight).saturating_mul(c as Weight))
+			
+	}
+	fn dispatch_as() -> Weight {
+		(8_463_000 as Weight)
+			
+	}
+	fn with_weight() -> Weight {
+		(4_307_000 as Weight)
+			
+	}
 	
 }
 
@@ -85,5 +101,18 @@ impl WeightInfo for () {
 			.saturating_add((2_738_000 as Weight).saturating_mul(c as Weight))
 			
 	}
+	fn force_batch(c: u32, ) -> Weight {
+		(20_385_000 as Weight)
+			.saturating_add((2_915_000 as Weight).saturating_mul(c as Weight))
+			
+	}
+	fn dispatch_as() -> Weight {
+		(8_463_000 as Weight)
+			
+	}
+	fn with_weight() -> Weight {
+		(4_307_000 as Weight)
+			
+	}
 	
 }
@@ -59,8 +59,9 @@ use sp_runtime::{DispatchResult, RuntimeDebug, traits::{
 use frame_support::{decl_module, decl_event, decl_storage, decl_error, ensure};
 use frame_support::traits::{
 	Currency, LockableCurrency, VestingSchedule, WithdrawReasons, LockIdentifier,
-	ExistenceRequirement, Get,
+	ExistenceRequirement, Get, MigrateAccount,
 };
+use frame_support::weights::Weight;
 use frame_system::{ensure_signed, ensure_root};
 pub use weights::WeightInfo;
 
@@ -386,6 +387,42 @@ impl<T: Config> VestingSchedule<T::AccountId> for Module<T> where
 	}
 }
 
+/// Moves a vesting schedule, together with the funds it still locks, onto another account.
+///
+/// Nothing is moved if the destination already has a schedule of its own.
+impl<T: Config> MigrateAccount<T::AccountId> for Module<T> {
+	fn migration_weight(_from: &T::AccountId) -> Weight {
+		T::DbWeight::get().reads_writes(5, 5)
+	}
+
+	fn migrate_account(from: &T::AccountId, to: &T::AccountId) -> Weight {
+		let weight = T::DbWeight::get().reads_writes(2, 0);
+		let vesting = match Self::vesting(from) {
+			Some(v) if !Vesting::<T>::contains_key(to) => v,
+			_ => return weight,
+		};
+		let now = <frame_system::Module<T>>::block_number();
+		let locked_now = vesting.locked_at::<T::BlockNumberToBalance>(now);
+		T::Currency::remove_lock(VESTING_ID, from);
+		let transfer = T::Currency::transfer(
+			from,
+			to,
+			locked_now,
+			ExistenceRequirement::AllowDeath,
+		);
+		if transfer.is_err() {
+			let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
+			T::Currency::set_lock(VESTING_ID, from, locked_now, reasons);
+			return weight.saturating_add(T::DbWeight::get().reads_writes(2, 2));
+		}
+		Vesting::<T>::remove(from);
+		Vesting::<T>::insert(to, vesting);
+		// it can't fail, but even if somehow it did, we don't really care.
+		let _ = Self::update_lock(to.clone());
+		Self::migration_weight(from)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -444,6 +481,9 @@ mod tests {
 		type ExistentialDeposit = ExistentialDeposit;
 		type AccountStore = System;
 		type MaxLocks = MaxLocks;
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
 		type WeightInfo = ();
 	}
 	parameter_types! {
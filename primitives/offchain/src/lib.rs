@@ -23,6 +23,22 @@
 /// Re-export of parent module scope storage prefix.
 pub use sp_core::offchain::STORAGE_PREFIX as STORAGE_PREFIX;
 
+use codec::{Encode, Decode};
+use sp_runtime::RuntimeDebug;
+
+/// Key under which the node stores its [`NodeHealth`] in the persistent off-chain storage before
+/// starting the off-chain workers for a new best block.
+pub const NODE_HEALTH_KEY: &[u8] = b"node/health";
+
+/// A snapshot of the local node's health, made available to the off-chain workers.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct NodeHealth<BlockNumber> {
+	/// The number of the best finalized block known to the node.
+	pub finalized_number: BlockNumber,
+	/// The number of peers the node is connected to.
+	pub peers: u32,
+}
+
 sp_api::decl_runtime_apis! {
 	/// The offchain worker api.
 	#[api_version(2)]
@@ -48,6 +48,23 @@ sp_api::decl_runtime_apis! {
 		/// Returns the list of public raw public keys + key type.
 		fn decode_session_keys(encoded: Vec<u8>) -> Option<Vec<(Vec<u8>, KeyTypeId)>>;
 	}
+
+	/// Historical session membership runtime api.
+	///
+	/// This api reads the validator sets that were indexed into the off-chain database at the end
+	/// of each session and should therefore be called with off-chain database read access.
+	pub trait SessionMembershipApi {
+		/// Generate a proof that the raw public `key` of type `key_type` was part of the validator
+		/// set of the given past `session`.
+		///
+		/// Returns `None` if the validator set of the session is not available in the off-chain
+		/// database (e.g. because it was pruned) or if the key was not part of it.
+		fn prove_session_membership(
+			session: SessionIndex,
+			key_type: KeyTypeId,
+			key: Vec<u8>,
+		) -> Option<MembershipProof>;
+	}
 }
 
 /// Number of validators in a given session.
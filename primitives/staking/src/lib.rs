@@ -17,7 +17,167 @@
 //! A crate which contains primitives that are useful for implementation that uses staking
 //! approaches in general. Definitions related to sessions, slashing, etc go here.
 
+use sp_runtime::{DispatchError, DispatchResult};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
 pub mod offence;
 
 /// Simple index type with which we can count sessions.
 pub type SessionIndex = u32;
+
+/// Counter for the number of eras that have passed.
+pub type EraIndex = u32;
+
+/// Trait describing something that implements a hook for any operations to perform when a staker is
+/// slashed.
+pub trait OnStakerSlash<AccountId, Balance> {
+	/// A hook for any operations to perform when a staker is slashed.
+	///
+	/// # Arguments
+	///
+	/// * `stash` - The stash of the staker whom the slash was applied to.
+	/// * `active` - The active bonded balance of the staker after the slash was applied.
+	/// * `unlocking` - The value of each of the unlocking chunks of the staker after the slash
+	///   was applied, keyed by the era in which they unlock. Chunks which have been fully slashed
+	///   are absent.
+	fn on_slash(stash: &AccountId, active: Balance, unlocking: &BTreeMap<EraIndex, Balance>);
+}
+
+impl<AccountId, Balance> OnStakerSlash<AccountId, Balance> for () {
+	fn on_slash(_: &AccountId, _: Balance, _: &BTreeMap<EraIndex, Balance>) {}
+}
+
+/// A generic representation of a staking implementation, which other pallets can use to stake on
+/// behalf of accounts they manage.
+pub trait StakingInterface {
+	/// Balance type used by the staking system.
+	type Balance;
+
+	/// AccountId type used by the staking system.
+	type AccountId;
+
+	/// The minimum amount that can be bonded to become a staker.
+	fn minimum_bond() -> Self::Balance;
+
+	/// Number of eras that staked funds must remain bonded for.
+	fn bonding_duration() -> EraIndex;
+
+	/// The current era index.
+	fn current_era() -> EraIndex;
+
+	/// The number of validators elected per era.
+	fn validator_count() -> u32;
+
+	/// The amount of the `stash` which is actively bonded, if it is bonded at all.
+	fn active_stake(stash: &Self::AccountId) -> Option<Self::Balance>;
+
+	/// The total amount of the `stash` which is bonded, including unlocking funds.
+	fn total_stake(stash: &Self::AccountId) -> Option<Self::Balance>;
+
+	/// Bond `value` of `stash`, controlled by `controller`, with rewards paid to `payee`.
+	fn bond(
+		stash: Self::AccountId,
+		controller: Self::AccountId,
+		value: Self::Balance,
+		payee: Self::AccountId,
+	) -> DispatchResult;
+
+	/// Bond up to `extra` additional funds of `stash`.
+	fn bond_extra(stash: Self::AccountId, extra: Self::Balance) -> DispatchResult;
+
+	/// Schedule `value` of the funds bonded by `controller` to be unlocked.
+	fn unbond(controller: Self::AccountId, value: Self::Balance) -> DispatchResult;
+
+	/// Withdraw the unlocked funds of `controller`. Returns whether the stash was removed
+	/// because all of its funds were withdrawn.
+	fn withdraw_unbonded(controller: Self::AccountId) -> Result<bool, DispatchError>;
+
+	/// Nominate `targets` with the funds bonded by `controller`.
+	fn nominate(controller: Self::AccountId, targets: Vec<Self::AccountId>) -> DispatchResult;
+
+	/// Stop nominating or validating with the funds bonded by `controller`.
+	fn chill(controller: Self::AccountId) -> DispatchResult;
+
+	/// Whether `stash` is neither nominating nor validating.
+	fn is_chilled(stash: &Self::AccountId) -> bool;
+
+	/// The stash bonded by `controller`.
+	fn stash_by_ctrl(controller: &Self::AccountId) -> Result<Self::AccountId, DispatchError>;
+
+	/// Whether `who` was exposed in `era`, either as a validator or as a nominator.
+	///
+	/// This iterates over the exposures of all the validators of `era`, so it should be weighed
+	/// against `validator_count` exposures of `max_exposure_size` nominators each.
+	fn is_exposed_in_era(who: &Self::AccountId, era: &EraIndex) -> bool;
+
+	/// Remove all the staking information of `stash` and unlock all of its funds right away,
+	/// without waiting for the bonding duration.
+	fn force_unstake(stash: Self::AccountId) -> DispatchResult;
+
+	/// The number of nominators per validator exposure against which exposure checks are
+	/// benchmarked.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn max_exposure_size() -> u32;
+
+	/// Expose `validator` and `nominators` in `era`.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn add_era_stakers(
+		era: &EraIndex,
+		validator: &Self::AccountId,
+		nominators: Vec<Self::AccountId>,
+	);
+}
+
+/// Something which provides the score of an account, by which it is sorted in a
+/// [`SortedListProvider`].
+pub trait ScoreProvider<AccountId> {
+	/// The type of the score.
+	type Score;
+
+	/// The current score of `who`.
+	fn score(who: &AccountId) -> Self::Score;
+}
+
+/// A list of accounts which is sorted by their score, at least approximately.
+///
+/// The staking system uses it to take only the voters with the highest stake into the election,
+/// without iterating over all of them.
+pub trait SortedListProvider<AccountId> {
+	/// The type of the score by which the list is sorted.
+	type Score;
+
+	/// The error returned when an account cannot be inserted.
+	type Error: sp_std::fmt::Debug;
+
+	/// An iterator over the accounts of the list, from the highest to the lowest score.
+	fn iter() -> Box<dyn Iterator<Item = AccountId>>;
+
+	/// The number of accounts in the list.
+	fn count() -> u32;
+
+	/// Whether `id` is in the list.
+	fn contains(id: &AccountId) -> bool;
+
+	/// Insert `id` into the list with the given score.
+	fn on_insert(id: AccountId, score: Self::Score) -> Result<(), Self::Error>;
+
+	/// Update the position of `id` in the list after its score changed to `score`.
+	fn on_update(id: &AccountId, score: Self::Score);
+
+	/// Remove `id` from the list.
+	fn on_remove(id: &AccountId);
+
+	/// Rebuild the list from scratch out of `all`, using `score_of` to find their scores.
+	///
+	/// Returns the number of accounts inserted.
+	fn regenerate(
+		all: impl IntoIterator<Item = AccountId>,
+		score_of: Box<dyn Fn(&AccountId) -> Self::Score>,
+	) -> u32;
+
+	/// Remove all the accounts from the list.
+	fn clear();
+
+	/// Check the internal consistency of the list.
+	fn sanity_check() -> Result<(), &'static str>;
+}